@@ -2,7 +2,6 @@ from __future__ import annotations
 
 import asyncio
 from collections.abc import AsyncGenerator
-import os
 from pathlib import Path
 import sys
 from typing import Any, cast, override
@@ -45,11 +44,20 @@ from acp.schema import (
     TextResourceContents,
     ToolCallProgress,
     ToolCallUpdate,
+    UsageUpdate,
     UserMessageChunk,
 )
-from pydantic import BaseModel, ConfigDict
+from pydantic import BaseModel, ConfigDict, Field, ValidationError
 
 from rune import RUNE_ROOT, __version__
+from rune.acp import deprecations
+from rune.acp.deprecations import (
+    DEPRECATION_NOTIFICATION,
+    ProtocolDeprecation,
+    Stage,
+    deprecation_for,
+    describe_deprecation,
+)
 from rune.acp.tools.base import BaseAcpTool
 from rune.acp.tools.session_update import (
     tool_call_session_update,
@@ -65,8 +73,30 @@ from rune.acp.utils import (
 )
 from rune.core.agent_loop import AgentLoop
 from rune.core.agents.models import BuiltinAgentName
+from rune.core.auth.accounts import AccountError, AccountStore
 from rune.core.autocompletion.path_prompt_adapter import render_path_prompt
-from rune.core.config import MissingAPIKeyError, RuneConfig, load_dotenv_values
+from rune.core.config import (
+    MissingAPIKeyError,
+    RuneConfig,
+    SessionLoggingConfig,
+    load_dotenv_values,
+)
+from rune.core.context_snapshots import ContextSnapshotError
+from rune.core.lmstudio import LMStudioClient, LMStudioError, lmstudio_provider
+from rune.core.memory import MemoryStore, MemoryStoreError
+from rune.core.moderation import describe_verdict
+from rune.core.paths.config_paths import use_project_dir
+from rune.core.session.session_archive import (
+    SessionArchiveError,
+    archive_session,
+    archived_config,
+    refresh_index,
+    unarchive_session,
+)
+from rune.core.session.session_fork import SessionForkError, history_until_turn
+from rune.core.session.session_index import SessionIndex, SessionIndexError
+from rune.core.session.session_loader import SessionFilter, SessionLoader
+from rune.core.status import StatusSnapshot, UsageStatus
 from rune.core.tools.base import BaseToolConfig, ToolPermission
 from rune.core.types import (
     ApprovalResponse,
@@ -74,13 +104,29 @@ from rune.core.types import (
     AsyncApprovalCallback,
     CompactEndEvent,
     CompactStartEvent,
+    ConcurrentSessionEvent,
+    ConfigReloadedEvent,
+    ContextBudgetWarningEvent,
+    DeliverableEvent,
+    LocalModelDownloadEvent,
+    ModelListChangedEvent,
+    ModerationEvent,
+    ProjectDocsLoadedEvent,
     ReasoningEvent,
+    SecretsRedactedEvent,
+    TokenCountEvent,
     ToolCallEvent,
+    ToolOutputEvent,
     ToolResultEvent,
     ToolStreamEvent,
     UserMessageEvent,
 )
 from rune.core.utils import CancellationReason, get_user_cancellation_message
+from rune.core.workspace_lease import WorktreeError, describe_peers
+
+# Characters of a running command's output sent with each update; a tool call
+# update replaces the content shown before it.
+_TOOL_OUTPUT_TAIL_CHARS = 8_000
 
 
 class AcpSessionLoop(BaseModel):
@@ -88,14 +134,23 @@ class AcpSessionLoop(BaseModel):
     id: str
     agent_loop: AgentLoop
     task: asyncio.Task[None] | None = None
+    # What the session was created with, so forks start out the same way.
+    cwd: str = ""
+    mcp_servers: list[HttpMcpServer | SseMcpServer | McpServerStdio] = Field(
+        default_factory=list
+    )
 
 
 class RuneAcpAgentLoop(AcpAgent):
     client: Client
 
-    def __init__(self) -> None:
+    def __init__(self, project: Path | None = None) -> None:
         self.sessions: dict[str, AcpSessionLoop] = {}
+        self._announced_deprecations: set[str] = set()
         self.client_capabilities = None
+        # Set by `rune daemon`, whose process and working directory all of its
+        # connections share.
+        self.project = project
 
     @override
     async def initialize(
@@ -162,6 +217,12 @@ class RuneAcpAgentLoop(AcpAgent):
                 version=__version__,
             ),
             auth_methods=auth_methods,
+            field_meta={
+                "deprecations": [
+                    d.model_dump(mode="json", by_alias=True)
+                    for d in deprecations.DEPRECATIONS
+                ]
+            },
         )
         return response
 
@@ -178,11 +239,24 @@ class RuneAcpAgentLoop(AcpAgent):
         mcp_servers: list[HttpMcpServer | SseMcpServer | McpServerStdio] | None = None,
         **kwargs: Any,
     ) -> NewSessionResponse:
+        if self.project is not None and Path(cwd).resolve() != self.project.resolve():
+            raise RequestError.invalid_params({
+                "cwd": f"This daemon only serves {self.project}"
+            })
         load_dotenv_values()
-        os.chdir(cwd)
 
+        overrides: dict[str, Any] = {}
+        meta = kwargs.get("field_meta") or {}
+        if response_language := meta.get("responseLanguage"):
+            overrides["response_language"] = response_language
+
+        # Sessions share the process, so each one's directory is passed along
+        # rather than made the process's working directory.
         try:
-            config = RuneConfig.load(disabled_tools=["ask_user_question"])
+            with use_project_dir(Path(cwd)):
+                config = RuneConfig.load(
+                    disabled_tools=["ask_user_question"], **overrides
+                )
             config.tool_paths.extend(self._get_acp_tool_overrides())
         except MissingAPIKeyError as e:
             raise RequestError.auth_required({
@@ -190,13 +264,21 @@ class RuneAcpAgentLoop(AcpAgent):
             }) from e
 
         agent_loop = AgentLoop(
-            config=config, agent_name=BuiltinAgentName.DEFAULT, enable_streaming=True
+            config=config,
+            agent_name=BuiltinAgentName.DEFAULT,
+            enable_streaming=True,
+            cwd=Path(cwd),
         )
         # NOTE: For now, we pin session.id to agent_loop.session_id right after init time.
         # We should just use agent_loop.session_id everywhere, but it can still change during
         # session lifetime (e.g. agent_loop.compact is called).
         # We should refactor agent_loop.session_id to make it immutable in ACP context.
-        session = AcpSessionLoop(id=agent_loop.session_id, agent_loop=agent_loop)
+        session = AcpSessionLoop(
+            id=agent_loop.session_id,
+            agent_loop=agent_loop,
+            cwd=cwd,
+            mcp_servers=mcp_servers or [],
+        )
         self.sessions[session.id] = session
 
         if not agent_loop.auto_approve:
@@ -267,6 +349,24 @@ class RuneAcpAgentLoop(AcpAgent):
         ) -> tuple[ApprovalResponse, str | None]:
             # Create the tool call update
             tool_call = ToolCallUpdate(tool_call_id=tool_call_id)
+            notes: list[str] = []
+            if directory := session.agent_loop.write_grant_requests.get(tool_call_id):
+                notes.append(
+                    "Writes outside the workspace. Approving grants write access to "
+                    f"{directory} for this session."
+                )
+            if review := session.agent_loop.critic.reviews.get(tool_call_id):
+                notes.append(
+                    f"Review by {review.model}: "
+                    f"{review.verdict.upper()}\n{review.summary}"
+                )
+            if notes:
+                tool_call.content = [
+                    ContentToolCallContent(
+                        type="content", content=TextContentBlock(type="text", text=note)
+                    )
+                    for note in notes
+                ]
 
             response = await self.client.request_permission(
                 session_id=session_id, tool_call=tool_call, options=TOOL_OPTIONS
@@ -333,12 +433,12 @@ class RuneAcpAgentLoop(AcpAgent):
         if model_id not in model_aliases:
             return None
 
-        VibeConfig.save_updates({"active_model": model_id})
-
-        new_config = RuneConfig.load(
-            tool_paths=session.agent_loop.config.tool_paths,
-            disabled_tools=["ask_user_question"],
-        )
+        with use_project_dir(Path(session.cwd)):
+            VibeConfig.save_updates({"active_model": model_id})
+            new_config = RuneConfig.load(
+                tool_paths=session.agent_loop.config.tool_paths,
+                disabled_tools=["ask_user_question"],
+            )
 
         await session.agent_loop.reload_with_initial_messages(base_config=new_config)
 
@@ -392,7 +492,17 @@ class RuneAcpAgentLoop(AcpAgent):
         finally:
             session.task = None
 
-        return PromptResponse(stop_reason="end_turn")
+        stats = session.agent_loop.stats
+        return PromptResponse(
+            stop_reason="end_turn",
+            field_meta={
+                "timing": {
+                    "wallTime": stats.last_turn_wall_time,
+                    "modelTime": stats.last_turn_model_time,
+                    "toolTime": stats.last_turn_tool_time,
+                }
+            },
+        )
 
     def _build_text_prompt(self, acp_prompt: list[ContentBlock]) -> str:
         text_prompt = ""
@@ -442,7 +552,10 @@ class RuneAcpAgentLoop(AcpAgent):
     async def _run_agent_loop(
         self, session: AcpSessionLoop, prompt: str, user_message_id: str | None = None
     ) -> AsyncGenerator[SessionUpdate]:
-        rendered_prompt = render_path_prompt(prompt, base_dir=Path.cwd())
+        rendered_prompt = render_path_prompt(
+            prompt, base_dir=session.agent_loop.workspace.active.path
+        )
+        tool_outputs: dict[str, str] = {}
 
         async for event in session.agent_loop.act(rendered_prompt):
             if isinstance(event, UserMessageEvent):
@@ -487,10 +600,26 @@ class RuneAcpAgentLoop(AcpAgent):
                     yield session_update
 
             elif isinstance(event, ToolResultEvent):
+                tool_outputs.pop(event.tool_call_id, None)
                 session_update = tool_result_session_update(event)
                 if session_update:
                     yield session_update
 
+            elif isinstance(event, ToolOutputEvent):
+                output = tool_outputs.get(event.tool_call_id, "") + event.message
+                output = output[-_TOOL_OUTPUT_TAIL_CHARS:]
+                tool_outputs[event.tool_call_id] = output
+                yield ToolCallProgress(
+                    session_update="tool_call_update",
+                    tool_call_id=event.tool_call_id,
+                    content=[
+                        ContentToolCallContent(
+                            type="content",
+                            content=TextContentBlock(type="text", text=output),
+                        )
+                    ],
+                )
+
             elif isinstance(event, ToolStreamEvent):
                 yield ToolCallProgress(
                     session_update="tool_call_update",
@@ -503,6 +632,125 @@ class RuneAcpAgentLoop(AcpAgent):
                     ],
                 )
 
+            elif isinstance(event, ModerationEvent):
+                text = describe_verdict(event.action, event.message)
+                yield AgentMessageChunk(
+                    session_update="agent_message_chunk",
+                    content=TextContentBlock(type="text", text=f"\n\n> {text}\n"),
+                    field_meta={"moderation": event.action.value},
+                )
+
+            elif isinstance(event, TokenCountEvent):
+                yield UsageUpdate(
+                    session_update="usage_update",
+                    used=event.context_tokens,
+                    size=event.context_window
+                    or session.agent_loop.config.auto_compact_threshold,
+                    field_meta={
+                        "promptTokens": event.prompt_tokens,
+                        "completionTokens": event.completion_tokens,
+                        "cachedTokens": event.cached_tokens,
+                        "estimated": event.estimated,
+                        "rateLimitedAt": event.rate_limited_at,
+                        "cost": event.cost,
+                        "sessionCost": event.session_cost,
+                    },
+                )
+
+            elif isinstance(event, ConcurrentSessionEvent):
+                text = describe_peers(event.workspace, event.peers)
+                yield AgentMessageChunk(
+                    session_update="agent_message_chunk",
+                    content=TextContentBlock(type="text", text=f"> {text}\n\n"),
+                    field_meta={
+                        "concurrentSessions": [
+                            peer.model_dump(mode="json") for peer in event.peers
+                        ]
+                    },
+                )
+
+            elif isinstance(event, LocalModelDownloadEvent):
+                await self._ext_notify(
+                    "rune/localModelDownload",
+                    {
+                        "sessionId": session.id,
+                        "model": event.model,
+                        "fraction": event.fraction,
+                        "done": event.done,
+                    },
+                )
+
+            elif isinstance(event, ContextBudgetWarningEvent):
+                await self._ext_notify(
+                    "rune/contextWarning",
+                    {
+                        "sessionId": session.id,
+                        "contextTokens": event.context_tokens,
+                        "contextWindow": event.context_window,
+                        "threshold": event.threshold,
+                    },
+                )
+
+            elif isinstance(event, DeliverableEvent):
+                await self._ext_notify(
+                    "rune/deliverable",
+                    {
+                        "sessionId": session.id,
+                        "messageId": event.message_id,
+                        "kind": event.kind.value,
+                        "content": event.content,
+                        "language": event.language,
+                    },
+                )
+
+            elif isinstance(event, SecretsRedactedEvent):
+                await self._ext_notify(
+                    "rune/secretsRedacted",
+                    {
+                        "sessionId": session.id,
+                        "toolCallId": event.tool_call_id,
+                        "toolName": event.tool_name,
+                        "redactions": event.redactions,
+                    },
+                )
+
+            elif isinstance(event, ProjectDocsLoadedEvent):
+                await self._ext_notify(
+                    "rune/projectDocsLoaded",
+                    {
+                        "sessionId": session.id,
+                        "paths": event.paths,
+                        "truncated": event.truncated,
+                    },
+                )
+
+            elif isinstance(event, ModelListChangedEvent):
+                await self._ext_notify(
+                    "rune/modelListChanged",
+                    {
+                        "sessionId": session.id,
+                        "currentModelId": event.active_model,
+                        "availableModels": [
+                            model.alias for model in session.agent_loop.config.models
+                        ],
+                        "added": event.added,
+                        "removed": event.removed,
+                        "changed": event.changed,
+                        "upgradedFrom": event.upgraded_from,
+                    },
+                )
+
+            elif isinstance(event, ConfigReloadedEvent):
+                await self._ext_notify(
+                    "rune/configReloaded",
+                    {
+                        "sessionId": session.id,
+                        "currentModelId": event.active_model,
+                        "applied": event.applied,
+                        "needsRestart": event.needs_restart,
+                    },
+                )
+
             elif isinstance(event, CompactStartEvent):
                 yield create_compact_start_session_update(event)
 
@@ -538,7 +786,289 @@ class RuneAcpAgentLoop(AcpAgent):
 
     @override
     async def ext_method(self, method: str, params: dict) -> dict:
-        raise NotImplementedError()
+        deprecation = await self._announce_deprecation(method)
+        if deprecation is not None and deprecation.stage == Stage.REMOVED:
+            raise RequestError.method_not_found(method)
+        match method:
+            case "rune/setReadOnly":
+                session = self._get_session(params.get("sessionId", ""))
+                session.agent_loop.read_only = bool(params.get("readOnly"))
+                return {"readOnly": session.agent_loop.read_only}
+            case "rune/setDryRun":
+                session = self._get_session(params.get("sessionId", ""))
+                session.agent_loop.dry_run = bool(params.get("dryRun"))
+                return {"dryRun": session.agent_loop.dry_run}
+            case "rune/stopCommand":
+                session = self._get_session(params.get("sessionId", ""))
+                tool_call_id = params.get("toolCallId")
+                stopped = session.agent_loop.tool_stops.stop(
+                    str(tool_call_id) if tool_call_id else None
+                )
+                return {"stopped": stopped}
+            case "rune/listBackgroundProcesses":
+                session = self._get_session(params.get("sessionId", ""))
+                processes = session.agent_loop.tool_manager.list_background_processes()
+                return {
+                    "processes": [
+                        p.model_dump(mode="json", by_alias=True) for p in processes
+                    ]
+                }
+            case "rune/killBackgroundProcess":
+                session = self._get_session(params.get("sessionId", ""))
+                process_id = str(params.get("id") or "")
+                if not process_id:
+                    raise RequestError.invalid_params({"id": "must not be empty"})
+                killed = await session.agent_loop.tool_manager.kill_background_process(
+                    process_id
+                )
+                return {"killed": killed}
+            case "rune/setSessionName":
+                session = self._get_session(params.get("sessionId", ""))
+                name = str(params.get("name") or "").strip()
+                if not name:
+                    raise RequestError.invalid_params({"name": "must not be empty"})
+                await session.agent_loop.session_logger.set_name(name)
+                return {"name": name}
+            case "rune/updateSession":
+                session = self._get_session(params.get("sessionId", ""))
+                tags = params.get("tags")
+                if tags is not None and not (
+                    isinstance(tags, list) and all(isinstance(t, str) for t in tags)
+                ):
+                    raise RequestError.invalid_params({"tags": "must be strings"})
+                if name := str(params.get("name") or "").strip():
+                    await session.agent_loop.session_logger.set_name(name)
+                if tags is not None:
+                    await session.agent_loop.session_logger.set_tags(tags)
+                return {
+                    "name": name or None,
+                    "tags": session.agent_loop.session_logger.tags,
+                }
+            case "rune/forkSession":
+                source = self._get_session(params.get("sessionId", ""))
+                at_turn = params.get("atTurn")
+                try:
+                    if at_turn is not None and not isinstance(at_turn, int):
+                        raise SessionForkError("must be a turn number")
+                    history_until_turn(source.agent_loop.messages, at_turn)
+                except SessionForkError as e:
+                    raise RequestError.invalid_params({"atTurn": str(e)}) from e
+                response = await self.new_session(
+                    cwd=source.cwd, mcp_servers=source.mcp_servers
+                )
+                fork = self._get_session(response.session_id)
+                await fork.agent_loop.rewind.fork_at_turn(
+                    at_turn, source=source.agent_loop
+                )
+                return {"sessionId": fork.id, "forkedFrom": source.id}
+            case "rune/forkWorktree":
+                session = self._get_session(params.get("sessionId", ""))
+                try:
+                    root = session.agent_loop.workspace.fork_worktree(
+                        session.agent_loop.session_id
+                    )
+                except WorktreeError as e:
+                    raise RequestError.internal_error({"message": str(e)}) from e
+                return {"cwd": str(root.path)}
+            case "rune/status":
+                session = self._get_session(params.get("sessionId", ""))
+                return StatusSnapshot.from_agent_loop(session.agent_loop).model_dump(
+                    mode="json", by_alias=True
+                )
+            case "rune/readUsage":
+                session = self._get_session(params.get("sessionId", ""))
+                return UsageStatus.from_stats(
+                    session.agent_loop.stats, session.agent_loop.config
+                ).model_dump(mode="json", by_alias=True)
+            case "rune/contextUsage":
+                session = self._get_session(params.get("sessionId", ""))
+                return session.agent_loop.context_usage().model_dump(
+                    mode="json", by_alias=True
+                )
+            case "rune/snapshotContext":
+                session = self._get_session(params.get("sessionId", ""))
+                snapshot = session.agent_loop.rewind.snapshot_context()
+                return {
+                    "snapshotId": snapshot.id,
+                    "messageCount": snapshot.message_count,
+                }
+            case "rune/restoreContext" | "rune/undoTurns":
+                session = self._get_session(params.get("sessionId", ""))
+                try:
+                    if method == "rune/undoTurns":
+                        snapshot = await session.agent_loop.rewind.undo_turns(
+                            int(params.get("count", 1))
+                        )
+                    else:
+                        snapshot = await session.agent_loop.rewind.restore_context(
+                            str(params.get("snapshotId", ""))
+                        )
+                except ContextSnapshotError as e:
+                    raise RequestError.invalid_params({"message": str(e)}) from e
+                # After an undo, the snapshot taken before it; restoring it redoes.
+                return {
+                    "snapshotId": snapshot.id,
+                    "messageCount": len(session.agent_loop.messages) - 1,
+                }
+            case "rune/memories" | "rune/forgetMemory":
+                session = self._get_session(params.get("sessionId", ""))
+                store = MemoryStore(project=session.agent_loop.workspace.active.path)
+                try:
+                    if method == "rune/forgetMemory":
+                        store.delete(int(params.get("id", 0)))
+                    memories = store.recent()
+                except MemoryStoreError as e:
+                    raise RequestError.invalid_params({"message": str(e)}) from e
+                return {"memories": [m.model_dump(mode="json") for m in memories]}
+            case "rune/searchSessions":
+                session = self._get_session(params.get("sessionId", ""))
+                index = SessionIndex(session.agent_loop.config.session_logging)
+                try:
+                    hits = index.search(
+                        str(params.get("query", "")), int(params.get("limit", 20))
+                    )
+                except SessionIndexError as e:
+                    raise RequestError.internal_error({"message": str(e)}) from e
+                return {
+                    "results": [
+                        {
+                            "sessionId": hit.session_id,
+                            "title": hit.title,
+                            "kind": hit.kind,
+                            "snippet": hit.snippet,
+                        }
+                        for hit in hits
+                    ]
+                }
+            case "rune/listSessions":
+                session = self._get_session(params.get("sessionId", ""))
+                logging_config = session.agent_loop.config.session_logging
+                if params.get("archived"):
+                    logging_config = archived_config(logging_config)
+                try:
+                    session_filter = SessionFilter(
+                        tags=params.get("tags") or [],
+                        cwd=params.get("cwd") or None,
+                        model=params.get("model") or None,
+                    )
+                except ValidationError as e:
+                    raise RequestError.invalid_params({"message": str(e)}) from e
+                sessions = SessionLoader.list_recent_sessions(
+                    logging_config,
+                    int(params.get("limit", 50)),
+                    session_filter,
+                )
+                return {
+                    "sessions": [
+                        {
+                            "sessionId": metadata.get("session_id"),
+                            "title": metadata.get("title"),
+                            "tags": metadata.get("tags") or [],
+                            "cwd": (metadata.get("environment") or {}).get(
+                                "working_directory"
+                            ),
+                            "model": (metadata.get("config") or {}).get("active_model"),
+                            "endTime": metadata.get("end_time"),
+                        }
+                        for _, metadata in sessions
+                    ]
+                }
+            case "rune/archiveSession" | "rune/unarchiveSession":
+                session = self._get_session(params.get("sessionId", ""))
+                archived = method == "rune/archiveSession"
+                return await self._archive_session(
+                    session.agent_loop.config.session_logging,
+                    str(params.get("targetSessionId") or ""),
+                    archived=archived,
+                )
+            case "rune/listAccounts" | "rune/switchAccount":
+                store = AccountStore()
+                try:
+                    if method == "rune/switchAccount":
+                        store.switch(str(params.get("label", "")))
+                    accounts = store.accounts()
+                    active = store.active_labels()
+                except AccountError as e:
+                    raise RequestError.invalid_params({"message": str(e)}) from e
+                return {
+                    "accounts": [
+                        {
+                            "label": account.label,
+                            "provider": account.provider,
+                            "active": active.get(account.provider) == account.label,
+                        }
+                        for account in accounts
+                    ]
+                }
+            case "rune/localModels":
+                session = self._get_session(params.get("sessionId", ""))
+                try:
+                    provider = lmstudio_provider(session.agent_loop.config)
+                    client = LMStudioClient(provider)
+                    models = await client.list_models()
+                except LMStudioError as e:
+                    raise RequestError.internal_error({"message": str(e)}) from e
+                return {
+                    "provider": client.provider.name,
+                    "models": [model.model_dump(mode="json") for model in models],
+                }
+            case _:
+                raise RequestError.method_not_found(method)
+
+    async def _archive_session(
+        self, config: SessionLoggingConfig, session_id: str, *, archived: bool
+    ) -> dict[str, Any]:
+        if not session_id:
+            raise RequestError.invalid_params({"targetSessionId": "is required"})
+        source_config = config if archived else archived_config(config)
+        session_dir = SessionLoader.find_session_by_id(session_id, source_config)
+        if session_dir is None:
+            where = "logged" if archived else "archived"
+            raise RequestError.invalid_params(
+                {"targetSessionId": f"no {where} session {session_id}"}
+            )
+        if any(
+            s.agent_loop.session_logger.session_dir == session_dir
+            for s in self.sessions.values()
+        ):
+            raise RequestError.invalid_params(
+                {"targetSessionId": "is open in this agent"}
+            )
+        try:
+            if archived:
+                archive_session(session_dir, config)
+            else:
+                unarchive_session(session_dir, config)
+        except (OSError, SessionArchiveError) as e:
+            raise RequestError.internal_error({"message": str(e)}) from e
+        refresh_index(config)
+
+        params = {"sessionId": session_id, "archived": archived}
+        await self._ext_notify(
+            "rune/sessionArchived" if archived else "rune/sessionUnarchived", params
+        )
+        return params
+
+    async def _ext_notify(self, method: str, params: dict) -> None:
+        deprecation = await self._announce_deprecation(method)
+        if deprecation is not None and deprecation.stage == Stage.REMOVED:
+            return
+        await self.client.ext_notification(method, params)
+
+    async def _announce_deprecation(self, name: str) -> ProtocolDeprecation | None:
+        """Tell the client, once per connection, that ``name`` is on its way out."""
+        if (deprecation := deprecation_for(name)) is None:
+            return None
+        if name not in self._announced_deprecations:
+            self._announced_deprecations.add(name)
+            await self.client.ext_notification(
+                DEPRECATION_NOTIFICATION,
+                {
+                    **deprecation.model_dump(mode="json", by_alias=True),
+                    "description": describe_deprecation(deprecation),
+                },
+            )
+        return deprecation
 
     @override
     async def ext_notification(self, method: str, params: dict) -> None:
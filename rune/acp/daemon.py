@@ -0,0 +1,283 @@
+from __future__ import annotations
+
+import asyncio
+from functools import partial
+import hashlib
+import itertools
+import json
+import os
+from pathlib import Path
+import signal
+import subprocess
+import sys
+import time
+from typing import Any, NamedTuple
+
+from acp import PROTOCOL_VERSION, run_agent
+
+from rune.core.config import RuneConfig, load_dotenv_values
+from rune.core.paths.global_paths import DAEMON_DIR
+from rune.core.tools.manager import ToolManager, enable_mcp_discovery_cache
+from rune.core.utils import is_windows, logger
+
+# Set to skip a running daemon and start Rune in-process.
+NO_DAEMON_ENV = "RUNE_NO_DAEMON"
+START_TIMEOUT_SECONDS = 30.0
+_CHUNK_SIZE = 64 * 1024
+# Tool results can make a single ACP message far longer than a StreamReader line.
+_MESSAGE_LIMIT = 16 * 1024 * 1024
+
+
+class DaemonError(Exception):
+    pass
+
+
+class DaemonPaths(NamedTuple):
+    socket: Path
+    pid_file: Path
+    log_file: Path
+
+
+def daemon_paths(project: Path, directory: Path | None = None) -> DaemonPaths:
+    """Where the daemon of ``project`` listens. Paths are keyed by a digest of
+    the project path, which keeps sockets under the length limit of their OS.
+    """
+    digest = hashlib.sha256(str(project.resolve()).encode()).hexdigest()[:16]
+    base = (directory or DAEMON_DIR.path) / digest
+    return DaemonPaths(
+        base.with_suffix(".sock"), base.with_suffix(".pid"), base.with_suffix(".log")
+    )
+
+
+def daemon_pid(project: Path) -> int | None:
+    """The pid of ``project``'s daemon, if one is running."""
+    paths = daemon_paths(project)
+    try:
+        pid = int(paths.pid_file.read_text(encoding="utf-8").strip())
+    except (OSError, ValueError):
+        return None
+    try:
+        os.kill(pid, 0)
+    except PermissionError:
+        return pid
+    except OSError:
+        return None
+    return pid if paths.socket.exists() else None
+
+
+def running_daemon_socket(project: Path) -> Path | None:
+    """The socket to connect to instead of starting Rune, if any."""
+    if is_windows() or os.environ.get(NO_DAEMON_ENV):
+        return None
+    if daemon_pid(project) is None:
+        return None
+    return daemon_paths(project).socket
+
+
+async def serve(project: Path) -> None:
+    """Serve ACP connections for ``project`` until SIGTERM or SIGINT.
+
+    Each connection is its own ACP client with its own sessions. What is
+    expensive to set up (config, tool modules, MCP tool discovery) is loaded
+    once here and shared by all of them.
+    """
+    if is_windows():
+        raise DaemonError("rune daemon needs Unix sockets, which Windows lacks")
+    paths = daemon_paths(project)
+    _make_private_dir(paths.socket.parent)
+    _warm_up(project)
+
+    paths.socket.unlink(missing_ok=True)
+    # Other users must never be able to connect, not even between bind and chmod.
+    umask = os.umask(0o177)
+    try:
+        server = await asyncio.start_unix_server(
+            partial(_handle_connection, project), path=paths.socket
+        )
+    finally:
+        os.umask(umask)
+    paths.socket.chmod(0o600)
+    paths.pid_file.write_text(str(os.getpid()), encoding="utf-8")
+    logger.info("Rune daemon for %s listening on %s", project, paths.socket)
+
+    stop = asyncio.Event()
+    loop = asyncio.get_running_loop()
+    for sig in (signal.SIGTERM, signal.SIGINT):
+        loop.add_signal_handler(sig, stop.set)
+    try:
+        async with server:
+            await stop.wait()
+    finally:
+        paths.socket.unlink(missing_ok=True)
+        paths.pid_file.unlink(missing_ok=True)
+
+
+def _make_private_dir(directory: Path) -> None:
+    directory.mkdir(mode=0o700, parents=True, exist_ok=True)
+    directory.chmod(0o700)
+
+
+def _warm_up(project: Path) -> None:
+    os.chdir(project)
+    load_dotenv_values()
+    enable_mcp_discovery_cache()
+    try:
+        config = RuneConfig.load()
+        ToolManager(lambda: config)
+    except Exception as e:
+        # Sessions report config errors to their client when they start.
+        logger.warning("Rune daemon warm-up failed: %s", e)
+
+
+async def _handle_connection(
+    project: Path, reader: asyncio.StreamReader, writer: asyncio.StreamWriter
+) -> None:
+    from rune.acp.acp_agent_loop import RuneAcpAgentLoop
+
+    try:
+        await run_agent(
+            agent=RuneAcpAgentLoop(project),
+            input_stream=writer,
+            output_stream=reader,
+            use_unstable_protocol=True,
+        )
+    except Exception as e:
+        logger.warning("Rune daemon connection failed: %s", e)
+    finally:
+        writer.close()
+
+
+def _daemon_command() -> list[str]:
+    # Bundled binaries are their own interpreter, see RuneAcpAgentLoop.initialize.
+    if "python" in Path(sys.executable).name:
+        return [sys.executable, "-m", "rune.cli.entrypoint", "daemon", "run"]
+    return [sys.executable, "daemon", "run"]
+
+
+def start_daemon(project: Path) -> int:
+    if is_windows():
+        raise DaemonError("rune daemon needs Unix sockets, which Windows lacks")
+    if (pid := daemon_pid(project)) is not None:
+        raise DaemonError(f"A daemon already runs for {project} (pid {pid})")
+    paths = daemon_paths(project)
+    _make_private_dir(paths.log_file.parent)
+    with paths.log_file.open("ab") as log:
+        process = subprocess.Popen(
+            _daemon_command(),
+            cwd=project,
+            stdin=subprocess.DEVNULL,
+            stdout=log,
+            stderr=log,
+            start_new_session=True,
+        )
+
+    deadline = time.monotonic() + START_TIMEOUT_SECONDS
+    while time.monotonic() < deadline:
+        if daemon_pid(project) == process.pid:
+            return process.pid
+        if process.poll() is not None:
+            raise DaemonError(f"The daemon exited at startup; see {paths.log_file}")
+        time.sleep(0.1)
+    process.terminate()
+    raise DaemonError(f"The daemon did not start in time; see {paths.log_file}")
+
+
+def stop_daemon(project: Path) -> int | None:
+    """Stop ``project``'s daemon; returns its pid, or None if none was running."""
+    if (pid := daemon_pid(project)) is None:
+        return None
+    os.kill(pid, signal.SIGTERM)
+    return pid
+
+
+async def pipe_stdio(socket_path: Path) -> None:
+    """Relay this process's stdio to the daemon at ``socket_path``, so an editor
+    that launched `rune-acp` talks to the warm daemon instead.
+    """
+    reader, writer = await asyncio.open_unix_connection(socket_path)
+    loop = asyncio.get_running_loop()
+    stdin = asyncio.StreamReader()
+    await loop.connect_read_pipe(
+        lambda: asyncio.StreamReaderProtocol(stdin), sys.stdin.buffer
+    )
+
+    async def upstream() -> None:
+        while data := await stdin.read(_CHUNK_SIZE):
+            writer.write(data)
+            await writer.drain()
+        writer.write_eof()
+
+    async def downstream() -> None:
+        while data := await reader.read(_CHUNK_SIZE):
+            sys.stdout.buffer.write(data)
+            sys.stdout.buffer.flush()
+
+    try:
+        await asyncio.gather(upstream(), downstream())
+    finally:
+        writer.close()
+
+
+async def run_prompt(
+    socket_path: Path, project: Path, prompt: str, agent_name: str
+) -> str | None:
+    """Run ``prompt`` in a new session of the daemon at ``socket_path`` and
+    return its last reply, the way programmatic mode does in-process.
+    """
+    reader, writer = await asyncio.open_unix_connection(socket_path, limit=_MESSAGE_LIMIT)
+    request_ids = itertools.count(1)
+    replies: dict[str, str] = {}
+
+    async def send(message: dict[str, Any]) -> None:
+        writer.write(json.dumps({"jsonrpc": "2.0", **message}).encode() + b"\n")
+        await writer.drain()
+
+    async def call(method: str, params: dict[str, Any]) -> Any:
+        request_id = next(request_ids)
+        await send({"id": request_id, "method": method, "params": params})
+        while line := await reader.readline():
+            message = json.loads(line)
+            if "method" not in message and message.get("id") == request_id:
+                if error := message.get("error"):
+                    raise DaemonError(str(error.get("data") or error.get("message")))
+                return message.get("result")
+            if "id" in message:
+                # Nothing is advertised that the daemon could ask for, and
+                # programmatic agents approve their own tools.
+                await send({
+                    "id": message["id"],
+                    "error": {"code": -32601, "message": "Method not found"},
+                })
+            elif message.get("method") == "session/update":
+                _collect_reply(message["params"]["update"], replies)
+        raise DaemonError("The daemon closed the connection")
+
+    try:
+        await call(
+            "initialize",
+            {"protocolVersion": PROTOCOL_VERSION, "clientCapabilities": {}},
+        )
+        session = await call("session/new", {"cwd": str(project), "mcpServers": []})
+        session_id = session["sessionId"]
+        mode = await call(
+            "session/set_mode", {"sessionId": session_id, "modeId": agent_name}
+        )
+        if mode is None:
+            raise DaemonError(f"The daemon has no agent named {agent_name}")
+        result = await call(
+            "session/prompt",
+            {"sessionId": session_id, "prompt": [{"type": "text", "text": prompt}]},
+        )
+    finally:
+        writer.close()
+    reply = next(reversed(replies.values()), None)
+    if result.get("stopReason") != "end_turn":
+        raise DaemonError(reply or f"The prompt stopped: {result.get('stopReason')}")
+    return reply
+
+
+def _collect_reply(update: dict[str, Any], replies: dict[str, str]) -> None:
+    if update.get("sessionUpdate") != "agent_message_chunk":
+        return
+    message_id = (update.get("_meta") or {}).get("messageId", "")
+    replies[message_id] = replies.get(message_id, "") + update["content"]["text"]
@@ -0,0 +1,52 @@
+from __future__ import annotations
+
+from enum import StrEnum, auto
+from typing import Literal
+
+from pydantic import BaseModel, ConfigDict
+from pydantic.alias_generators import to_camel
+
+DEPRECATION_NOTIFICATION = "rune/deprecation"
+
+
+class Stage(StrEnum):
+    # Still served, with a notice, until the removal version.
+    DEPRECATED = auto()
+    # No longer served; calls fail after the notice.
+    REMOVED = auto()
+
+
+class ProtocolDeprecation(BaseModel):
+    """A Rune extension method or notification on its way out of the protocol."""
+
+    model_config = ConfigDict(alias_generator=to_camel, populate_by_name=True)
+
+    name: str
+    kind: Literal["method", "notification"]
+    stage: Stage
+    replacement: str | None = None
+    # The first Rune version without it.
+    removal_version: str | None = None
+    message: str = ""
+
+
+# Add an entry here, with its replacement and removal version, at least one
+# release before renaming or removing an extension method or notification.
+DEPRECATIONS: list[ProtocolDeprecation] = []
+
+
+def deprecation_for(name: str) -> ProtocolDeprecation | None:
+    return next((d for d in DEPRECATIONS if d.name == name), None)
+
+
+def describe_deprecation(deprecation: ProtocolDeprecation) -> str:
+    verb = "was removed" if deprecation.stage == Stage.REMOVED else "is deprecated"
+    text = f"The {deprecation.kind} `{deprecation.name}` {verb}"
+    if deprecation.stage == Stage.DEPRECATED and deprecation.removal_version:
+        text += f" and will be removed in Rune {deprecation.removal_version}"
+    if deprecation.replacement:
+        text += f"; use `{deprecation.replacement}` instead"
+    text += "."
+    if deprecation.message:
+        text += f" {deprecation.message}"
+    return text
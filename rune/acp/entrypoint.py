@@ -1,8 +1,10 @@
 from __future__ import annotations
 
 import argparse
+import asyncio
 from dataclasses import dataclass
 import os
+from pathlib import Path
 import sys
 
 from rune import __version__
@@ -62,6 +64,20 @@ def handle_debug_mode() -> None:
     # debugpy.wait_for_client()
 
 
+def run_through_daemon() -> bool:
+    """Hand this connection to `rune daemon` when one runs for the project."""
+    from rune.acp.daemon import pipe_stdio, running_daemon_socket
+
+    if (socket_path := running_daemon_socket(Path.cwd())) is None:
+        return False
+    try:
+        asyncio.run(pipe_stdio(socket_path))
+    except (ConnectionRefusedError, FileNotFoundError):
+        # The daemon went away before the connection; nothing was read yet.
+        return False
+    return True
+
+
 def main() -> None:
     handle_debug_mode()
     unlock_config_paths()
@@ -74,6 +90,8 @@ def main() -> None:
     if args.setup:
         run_onboarding()
         sys.exit(0)
+    if run_through_daemon():
+        return
     run_acp_server()
 
 
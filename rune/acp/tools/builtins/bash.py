@@ -2,7 +2,6 @@ from __future__ import annotations
 
 import asyncio
 from collections.abc import AsyncGenerator
-from pathlib import Path
 
 from acp.schema import (
     TerminalToolCallContent,
@@ -13,7 +12,7 @@ from acp.schema import (
 
 from rune import RUNE_ROOT
 from rune.acp.tools.base import AcpToolState, BaseAcpTool
-from rune.core.tools.base import BaseToolState, InvokeContext, ToolError
+from rune.core.tools.base import BaseToolState, InvokeContext, ToolError, working_dir
 from rune.core.tools.builtins.bash import Bash as CoreBashTool, BashArgs, BashResult
 from rune.core.types import ToolCallEvent, ToolResultEvent, ToolStreamEvent
 from rune.core.utils import logger
@@ -43,7 +42,7 @@ class Bash(CoreBashTool, BaseAcpTool[AcpBashState]):
             terminal = await client.create_terminal(
                 session_id=session_id,
                 command=args.command,
-                cwd=str(Path.cwd()),
+                cwd=str(working_dir(ctx)),
                 output_byte_limit=max_bytes,
             )
         except Exception as e:
@@ -57,13 +56,27 @@ class Bash(CoreBashTool, BaseAcpTool[AcpBashState]):
 
         try:
             exit_response = await self._wait_for_terminal_exit(
-                terminal_id=terminal_id, timeout=timeout, command=args.command
+                terminal_id=terminal_id,
+                timeout=timeout,
+                command=args.command,
+                stop=ctx.stop_requested if ctx else None,
             )
 
             output_response = await client.terminal_output(
                 session_id=session_id, terminal_id=terminal_id
             )
 
+            if exit_response is None:
+                exit_status = output_response.exit_status
+                yield self._build_result(
+                    command=args.command,
+                    stdout=output_response.output,
+                    stderr="",
+                    returncode=(exit_status.exit_code if exit_status else None) or 0,
+                    stopped=True,
+                )
+                return
+
             yield self._build_result(
                 command=args.command,
                 stdout=output_response.output,
@@ -88,26 +101,42 @@ class Bash(CoreBashTool, BaseAcpTool[AcpBashState]):
         return summary
 
     async def _wait_for_terminal_exit(
-        self, terminal_id: str, timeout: int, command: str
-    ) -> WaitForTerminalExitResponse:
+        self,
+        terminal_id: str,
+        timeout: int,
+        command: str,
+        stop: asyncio.Event | None = None,
+    ) -> WaitForTerminalExitResponse | None:
+        """Wait for the command to exit. Returns None if `stop` is set first,
+        after killing it.
+        """
         client, session_id, _ = self._load_state()
 
+        exit_wait = asyncio.ensure_future(
+            client.wait_for_terminal_exit(
+                session_id=session_id, terminal_id=terminal_id
+            )
+        )
+        stop_wait = asyncio.ensure_future(stop.wait()) if stop else None
+        waiters = [task for task in (exit_wait, stop_wait) if task is not None]
         try:
-            return await asyncio.wait_for(
-                client.wait_for_terminal_exit(
-                    session_id=session_id, terminal_id=terminal_id
-                ),
-                timeout=timeout,
+            done, _ = await asyncio.wait(
+                waiters, timeout=timeout, return_when=asyncio.FIRST_COMPLETED
             )
-        except TimeoutError:
-            try:
-                await client.kill_terminal(
-                    session_id=session_id, terminal_id=terminal_id
-                )
-            except Exception as e:
-                logger.error(f"Failed to kill terminal: {e!r}")
+        finally:
+            for task in waiters:
+                task.cancel()
+        if exit_wait in done:
+            return exit_wait.result()
+
+        try:
+            await client.kill_terminal(session_id=session_id, terminal_id=terminal_id)
+        except Exception as e:
+            logger.error(f"Failed to kill terminal: {e!r}")
 
-            raise self._build_timeout_error(command, timeout)
+        if stop_wait is not None and stop_wait in done:
+            return None
+        raise self._build_timeout_error(command, timeout)
 
     @classmethod
     def tool_call_session_update(cls, event: ToolCallEvent) -> ToolCallStart:
@@ -18,6 +18,7 @@ from rune.core.types import ToolCallEvent, ToolResultEvent
 from rune.core.utils import TaggedText, is_user_cancellation_event
 
 TOOL_KIND: dict[str, ToolKind] = {
+    "fetch_url": "fetch",
     "grep": "search",
     "read_file": "read",
     # Right now, jetbrains implementation of "edit" tool kind is broken
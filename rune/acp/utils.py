@@ -69,19 +69,24 @@ def create_compact_start_session_update(event: CompactStartEvent) -> ToolCallSta
     # This should be revisited when the ACP protocol defines how compact events
     # should be represented.
     # [RFD](https://agentclientprotocol.com/rfds/session-usage)
+    if event.background:
+        title = "Compacting conversation history in the background..."
+        text = (
+            "Automatic context management, no approval required. The summary "
+            "replaces older messages at the start of a later turn."
+        )
+    else:
+        title = "Compacting conversation history..."
+        text = "Automatic context management, no approval required. This may take some time..."
     return ToolCallStart(
         session_update="tool_call",
         tool_call_id=event.tool_call_id,
-        title="Compacting conversation history...",
+        title=title,
         kind="other",
         status="in_progress",
         content=[
             ContentToolCallContent(
-                type="content",
-                content=TextContentBlock(
-                    type="text",
-                    text="Automatic context management, no approval required. This may take some time...",
-                ),
+                type="content", content=TextContentBlock(type="text", text=text)
             )
         ],
     )
@@ -0,0 +1,261 @@
+from __future__ import annotations
+
+import argparse
+from datetime import datetime
+import getpass
+import json
+import sys
+from typing import NamedTuple
+
+from rich import print as rprint
+
+from rune.core.auth.accounts import AccountError, AccountStore, CredentialsStoreMode
+from rune.core.auth.device_flow import DeviceCodeFlow, DeviceFlowError, OAuthToken
+from rune.core.auth.key_status import KeyState, KeyStatus, key_status
+from rune.core.config import (
+    DEFAULT_PROVIDERS,
+    MissingAPIKeyError,
+    ProviderConfig,
+    RuneConfig,
+    TomlFileSettingsSource,
+)
+from rune.core.llm.backend.transport import oauth_client_options
+from rune.core.utils import run_sync
+
+
+def parse_login_arguments(argv: list[str]) -> argparse.Namespace:
+    parser = argparse.ArgumentParser(
+        prog="rune login",
+        description="Store an API key or OAuth token as an account, and switch "
+        "to it",
+    )
+    parser.add_argument(
+        "--provider",
+        metavar="NAME",
+        help="Provider the key is for (default: the active model's provider)",
+    )
+    parser.add_argument(
+        "--label",
+        metavar="LABEL",
+        help="Name of the account, e.g. 'work' (default: the provider's name)",
+    )
+    parser.add_argument(
+        "--device",
+        action="store_true",
+        help="Log in with a code entered in a browser on another device, for "
+        "machines without one (needs the provider's OAuth settings)",
+    )
+    return parser.parse_args(argv[1:])
+
+
+def parse_accounts_arguments(argv: list[str]) -> argparse.Namespace:
+    parser = argparse.ArgumentParser(
+        prog="rune accounts", description="List and switch stored accounts"
+    )
+    subparsers = parser.add_subparsers(dest="action")
+    subparsers.add_parser("list", help="List accounts (default)")
+    switch_parser = subparsers.add_parser(
+        "switch", help="Use another account for its provider"
+    )
+    switch_parser.add_argument("label")
+    remove_parser = subparsers.add_parser("remove", help="Forget an account")
+    remove_parser.add_argument("label")
+    return parser.parse_args(argv[1:])
+
+
+def parse_auth_arguments(argv: list[str]) -> argparse.Namespace:
+    parser = argparse.ArgumentParser(
+        prog="rune auth", description="Inspect the credentials of providers"
+    )
+    subparsers = parser.add_subparsers(dest="action", required=True)
+    status_parser = subparsers.add_parser(
+        "status",
+        help="Show where each provider's key comes from and whether it needs "
+        "attention",
+    )
+    status_parser.add_argument(
+        "--json", action="store_true", help="Print the statuses as JSON"
+    )
+    return parser.parse_args(argv[1:])
+
+
+class _AuthSettings(NamedTuple):
+    providers: list[ProviderConfig]
+    active_provider: str
+    store: AccountStore
+    rotation_days: int
+
+
+def _auth_settings() -> _AuthSettings:
+    try:
+        config = RuneConfig.load()
+    except MissingAPIKeyError as e:
+        # Logging in is how this gets fixed, so it mustn't stand in the way:
+        # read the config file alone.
+        data = TomlFileSettingsSource(RuneConfig).toml_data
+        providers = [
+            ProviderConfig.model_validate(entry) for entry in data.get("providers", [])
+        ] or DEFAULT_PROVIDERS
+        mode = CredentialsStoreMode(
+            data.get("cli_auth_credentials_store_mode", CredentialsStoreMode.FILE)
+        )
+        return _AuthSettings(
+            providers,
+            e.provider_name,
+            AccountStore(
+                mode=mode, command=str(data.get("cli_auth_credentials_command", ""))
+            ),
+            int(data.get("api_key_rotation_days", 0)),
+        )
+    return _AuthSettings(
+        config.providers,
+        config.get_provider_for_model(config.get_active_model()).name,
+        AccountStore(
+            mode=config.cli_auth_credentials_store_mode,
+            command=config.cli_auth_credentials_command,
+        ),
+        config.api_key_rotation_days,
+    )
+
+
+def _login_settings(name: str | None) -> tuple[ProviderConfig, AccountStore]:
+    """The provider to log in to, and the store for its credentials."""
+    settings = _auth_settings()
+    name = name or settings.active_provider
+    for provider in settings.providers:
+        if provider.name == name:
+            return provider, settings.store
+    raise AccountError(f"Unknown provider '{name}'")
+
+
+async def _device_login(provider: ProviderConfig) -> OAuthToken:
+    if provider.oauth is None:
+        raise AccountError(
+            f"Provider '{provider.name}' has no OAuth settings for a device login; "
+            "add an oauth table to the provider"
+        )
+    async with DeviceCodeFlow(
+        provider.oauth,
+        client_options=oauth_client_options(provider, provider.oauth.token_url),
+    ) as flow:
+        authorization = await flow.start()
+        rprint(
+            f"To log in to {provider.name}, open {authorization.verification_uri} "
+            f"on any device and enter the code [bold]{authorization.user_code}[/]"
+        )
+        if authorization.verification_uri_complete:
+            rprint(f"or open {authorization.verification_uri_complete}")
+        rprint("Waiting for the login to complete...")
+        return await flow.wait_for_token(authorization)
+
+
+def _read_key(provider: str) -> str:
+    if sys.stdin.isatty():
+        return getpass.getpass(f"API key for {provider}: ")
+    return sys.stdin.read().strip()
+
+
+def run_login_command(argv: list[str]) -> None:
+    args = parse_login_arguments(argv)
+    try:
+        provider, store = _login_settings(args.provider)
+        label = args.label or provider.name
+        if args.device:
+            token = run_sync(_device_login(provider))
+            account = store.add(
+                label,
+                provider.name,
+                token.access_token,
+                refresh_token=token.refresh_token,
+                expires_at=token.expires_at,
+            )
+        else:
+            if not (api_key := _read_key(provider.name)):
+                raise AccountError("Refusing to store an empty key")
+            account = store.add(label, provider.name, api_key)
+    except (OSError, ValueError, AccountError, DeviceFlowError) as e:
+        rprint(f"[red]Error: {e}[/]")
+        sys.exit(1)
+    rprint(f"Logged in to {provider.name} as '{account.label}'")
+
+
+def run_accounts_command(argv: list[str]) -> None:
+    args = parse_accounts_arguments(argv)
+    store = AccountStore()
+    try:
+        match args.action:
+            case None | "list":
+                if not (accounts := store.accounts()):
+                    rprint("No accounts; add one with `rune login --label NAME`")
+                active = store.active_labels()
+                for account in accounts:
+                    in_use = active.get(account.provider) == account.label
+                    marker = "*" if in_use else " "
+                    rprint(f"{marker} {account.label} ({account.provider})")
+            case "switch":
+                account = store.switch(args.label)
+                rprint(f"Using '{account.label}' for {account.provider}")
+            case "remove":
+                account = store.remove(args.label)
+                rprint(f"Removed '{account.label}'")
+                if label := store.active_labels().get(account.provider):
+                    rprint(f"Using '{label}' for {account.provider}")
+    except (OSError, AccountError) as e:
+        rprint(f"[red]Error: {e}[/]")
+        sys.exit(1)
+
+
+def _describe_status(status: KeyStatus) -> str:
+    parts = [status.source.value]
+    if status.account:
+        parts = [f"account '{status.account}'"]
+    if status.created_at is not None:
+        parts.append(f"stored {_date(status.created_at)}")
+    if status.validated_at is not None:
+        parts.append(f"last accepted {_date(status.validated_at)}")
+    if status.message:
+        parts.append(status.message)
+    return ", ".join(parts)
+
+
+def _date(timestamp: float) -> str:
+    return datetime.fromtimestamp(timestamp).strftime("%Y-%m-%d")
+
+
+def run_auth_command(argv: list[str]) -> None:
+    args = parse_auth_arguments(argv)
+    try:
+        settings = _auth_settings()
+        statuses = [
+            key_status(p, rotation_days=settings.rotation_days, store=settings.store)
+            for p in settings.providers
+        ]
+    except (OSError, ValueError, AccountError) as e:
+        rprint(f"[red]Error: {e}[/]")
+        sys.exit(1)
+
+    if args.json:
+        print(
+            json.dumps(
+                {
+                    "active_provider": settings.active_provider,
+                    "providers": [s.model_dump(mode="json") for s in statuses],
+                },
+                indent=2,
+            )
+        )
+    else:
+        for status in statuses:
+            marker = "*" if status.provider == settings.active_provider else " "
+            color = "yellow" if status.needs_attention else "green"
+            rprint(
+                f"{marker} {status.provider}: [{color}]{status.state}[/] "
+                f"({_describe_status(status)})"
+            )
+    # Lets scripts check that a session could start, without parsing.
+    unusable = {KeyState.MISSING, KeyState.INVALID, KeyState.EXPIRED}
+    if any(
+        s.provider == settings.active_provider and s.state in unusable
+        for s in statuses
+    ):
+        sys.exit(1)
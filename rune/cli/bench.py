@@ -0,0 +1,107 @@
+from __future__ import annotations
+
+import argparse
+import asyncio
+from pathlib import Path
+import sys
+
+from rich import print as rprint
+from rich.markdown import Markdown
+
+from rune.core.bench import (
+    BenchError,
+    RunResult,
+    Variant,
+    load_tasks,
+    report_to_markdown,
+    run_bench,
+)
+from rune.core.config import MissingAPIKeyError, load_dotenv_values
+
+
+def parse_bench_arguments(argv: list[str]) -> argparse.Namespace:
+    parser = argparse.ArgumentParser(
+        prog="rune bench",
+        description=(
+            "Run a set of task files against two model or config variants and "
+            "compare success, tokens, cost and duration"
+        ),
+    )
+    parser.add_argument(
+        "tasks", type=Path, metavar="DIR", help="Directory of .md or .toml task files"
+    )
+    parser.add_argument(
+        "-a",
+        "--variant-a",
+        required=True,
+        metavar="VARIANT",
+        help="Model alias, or .toml file of config overrides",
+    )
+    parser.add_argument(
+        "-b",
+        "--variant-b",
+        required=True,
+        metavar="VARIANT",
+        help="Model alias, or .toml file of config overrides",
+    )
+    parser.add_argument(
+        "--repeat", type=int, default=1, metavar="N", help="Runs per task and variant"
+    )
+    parser.add_argument(
+        "--in-place",
+        action="store_true",
+        help="Run in the current directory instead of a throwaway copy of it",
+    )
+    parser.add_argument("--json", action="store_true", help="Print the report as JSON")
+    parser.add_argument(
+        "-o", "--output", type=Path, metavar="FILE", help="Output file (default: stdout)"
+    )
+    return parser.parse_args(argv[1:])
+
+
+def _print_progress(result: RunResult) -> None:
+    outcome = "[green]pass[/]" if result.success else f"[red]fail[/] ({result.error})"
+    rprint(
+        f"{result.task} [{result.variant}]: {outcome}, "
+        f"{result.total_tokens:,} tokens, {result.duration:.1f}s",
+        file=sys.stderr,
+    )
+
+
+def _bench(args: argparse.Namespace) -> None:
+    if args.repeat < 1:
+        raise BenchError("--repeat must be at least 1")
+    tasks = load_tasks(args.tasks)
+    variants = [Variant.parse(args.variant_a), Variant.parse(args.variant_b)]
+    if variants[0].label == variants[1].label:
+        raise BenchError(f"Both variants are named '{variants[0].label}'")
+
+    load_dotenv_values()
+    report = asyncio.run(
+        run_bench(
+            tasks,
+            variants,
+            source=Path.cwd(),
+            repeat=args.repeat,
+            isolate=not args.in_place,
+            on_result=_print_progress,
+        )
+    )
+
+    text = report.model_dump_json(indent=2) + "\n" if args.json else None
+    if args.output is not None:
+        args.output.write_text(text or report_to_markdown(report), encoding="utf-8")
+        rprint(f"Wrote the comparison of {len(tasks)} task(s) to {args.output}")
+    elif text is not None:
+        sys.stdout.write(text)
+    else:
+        rprint(Markdown(report_to_markdown(report)))
+
+
+def run_bench_command(argv: list[str]) -> None:
+    args = parse_bench_arguments(argv)
+    try:
+        _bench(args)
+    except (OSError, ValueError, BenchError, MissingAPIKeyError) as e:
+        rprint(f"[red]Error: {e}[/]")
+        sys.exit(1)
@@ -1,10 +1,15 @@
 from __future__ import annotations
 
 import argparse
+import asyncio
+import json
+from pathlib import Path
 import sys
+from typing import Any
 
 from rich import print as rprint
 
+from rune.acp.daemon import DaemonError, run_prompt, running_daemon_socket
 from rune.cli.textual_ui.app import run_textual_ui
 from rune.core.agent_loop import AgentLoop
 from rune.core.agents.models import BuiltinAgentName
@@ -14,6 +19,7 @@ from rune.core.config import (
     RuneConfig,
     load_dotenv_values,
 )
+from rune.core.config_migrations import migrate_config_file
 from rune.core.paths.config_paths import CONFIG_FILE, HISTORY_FILE
 from rune.core.programmatic import run_programmatic
 from rune.core.session.session_loader import SessionLoader
@@ -44,6 +50,8 @@ def get_prompt_from_stdin() -> str | None:
 
 
 def load_config_or_exit() -> RuneConfig:
+    if migration := migrate_config_file(CONFIG_FILE.path):
+        rprint(f"[yellow]{migration.describe()}[/]")
     try:
         return RuneConfig.load()
     except MissingAPIKeyError:
@@ -57,6 +65,20 @@ def load_config_or_exit() -> RuneConfig:
         sys.exit(1)
 
 
+def load_output_schema(path: Path | None) -> dict[str, Any] | None:
+    if path is None:
+        return None
+    try:
+        schema = json.loads(path.read_text(encoding="utf-8"))
+    except (OSError, ValueError) as e:
+        print(f"Error: cannot read output schema {path}: {e}", file=sys.stderr)
+        sys.exit(1)
+    if not isinstance(schema, dict):
+        print(f"Error: output schema {path} is not a JSON object", file=sys.stderr)
+        sys.exit(1)
+    return schema
+
+
 def bootstrap_config_files() -> None:
     if not CONFIG_FILE.path.exists():
         try:
@@ -97,7 +119,7 @@ def load_session(
     else:
         session_to_load = SessionLoader.find_session_by_id(
             args.resume, config.session_logging
-        )
+        ) or SessionLoader.find_session_by_name(args.resume, config.session_logging)
         if not session_to_load:
             rprint(
                 f"[red]Session '{args.resume}' not found in "
@@ -121,6 +143,39 @@ def _load_messages_from_previous_session(
     logger.info("Loaded %d messages from previous session", len(non_system_messages))
 
 
+def run_prompt_through_daemon(
+    args: argparse.Namespace, prompt: str | None, agent_name: str
+) -> bool:
+    """Run a programmatic prompt on the project's `rune daemon`, if one runs.
+    Options that a daemon session can't take keep the prompt in-process.
+    """
+    if (
+        not prompt
+        or args.continue_session
+        or args.resume
+        or args.enabled_tools
+        or args.add_roots
+        or args.output_schema
+        or args.max_turns is not None
+        or args.max_price is not None
+        or args.output != OutputFormat.TEXT
+    ):
+        return False
+    if (socket_path := running_daemon_socket(Path.cwd())) is None:
+        return False
+    try:
+        response = asyncio.run(run_prompt(socket_path, Path.cwd(), prompt, agent_name))
+    except (ConnectionRefusedError, FileNotFoundError):
+        # The daemon went away before the connection; nothing was sent yet.
+        return False
+    except DaemonError as e:
+        print(f"Error: {e}", file=sys.stderr)
+        sys.exit(1)
+    if response:
+        print(response)
+    return True
+
+
 def run_cli(args: argparse.Namespace) -> None:
     load_dotenv_values()
     bootstrap_config_files()
@@ -131,6 +186,12 @@ def run_cli(args: argparse.Namespace) -> None:
 
     try:
         initial_agent_name = get_initial_agent_name(args)
+        stdin_prompt = get_prompt_from_stdin()
+        if args.prompt is not None and run_prompt_through_daemon(
+            args, args.prompt or stdin_prompt, initial_agent_name
+        ):
+            sys.exit(0)
+
         config = load_config_or_exit()
 
         if args.enabled_tools:
@@ -138,7 +199,6 @@ def run_cli(args: argparse.Namespace) -> None:
 
         loaded_messages = load_session(args, config)
 
-        stdin_prompt = get_prompt_from_stdin()
         if args.prompt is not None:
             programmatic_prompt = args.prompt or stdin_prompt
             if not programmatic_prompt:
@@ -149,6 +209,7 @@ def run_cli(args: argparse.Namespace) -> None:
             output_format = OutputFormat(
                 args.output if hasattr(args, "output") else "text"
             )
+            output_schema = load_output_schema(getattr(args, "output_schema", None))
 
             try:
                 final_response = run_programmatic(
@@ -159,6 +220,8 @@ def run_cli(args: argparse.Namespace) -> None:
                     output_format=output_format,
                     previous_messages=loaded_messages,
                     agent_name=initial_agent_name,
+                    workspace_roots=args.add_roots,
+                    output_schema=output_schema,
                 )
                 if final_response:
                     print(final_response)
@@ -173,6 +236,8 @@ def run_cli(args: argparse.Namespace) -> None:
             agent_loop = AgentLoop(
                 config, agent_name=initial_agent_name, enable_streaming=True
             )
+            for root in args.add_roots or []:
+                agent_loop.workspace.add(root)
 
             if loaded_messages:
                 _load_messages_from_previous_session(agent_loop, loaded_messages)
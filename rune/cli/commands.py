@@ -9,6 +9,7 @@ class Command:
     description: str
     handler: str
     exits: bool = False
+    takes_args: bool = False
 
 
 class CommandRegistry:
@@ -62,11 +63,116 @@ class CommandRegistry:
                 description="Display agent statistics",
                 handler="_show_status",
             ),
+            "usage": Command(
+                aliases=frozenset(["/usage"]),
+                description="Show token, cost and time totals for the session",
+                handler="_show_usage",
+            ),
+            "context": Command(
+                aliases=frozenset(["/context"]),
+                description="Show what fills the context window",
+                handler="_show_context_usage",
+            ),
             "teleport": Command(
                 aliases=frozenset(["/teleport"]),
                 description="Teleport session to Rune Nuage",
                 handler="_teleport_command",
             ),
+            "lang": Command(
+                aliases=frozenset(["/lang"]),
+                description="Set the response language (`/lang off` to reset)",
+                handler="_set_response_language",
+                takes_args=True,
+            ),
+            "permissions": Command(
+                aliases=frozenset(["/permissions"]),
+                description="Show or edit directories writable outside the workspace",
+                handler="_manage_permissions",
+                takes_args=True,
+            ),
+            "workspace": Command(
+                aliases=frozenset(["/workspace"]),
+                description="List, add or switch between workspace roots",
+                handler="_manage_workspace",
+                takes_args=True,
+            ),
+            "dryrun": Command(
+                aliases=frozenset(["/dryrun"]),
+                description="Toggle dry-run mode: shell commands are assessed, not run",
+                handler="_toggle_dry_run",
+            ),
+            "processes": Command(
+                aliases=frozenset(["/processes"]),
+                description="List kernels and browsers tools left running, "
+                "or `/processes kill <id>`",
+                handler="_manage_background_processes",
+                takes_args=True,
+            ),
+            "local": Command(
+                aliases=frozenset(["/local"]),
+                description="List, download or load LM Studio models",
+                handler="_manage_local_models",
+                takes_args=True,
+            ),
+            "fork": Command(
+                aliases=frozenset(["/fork"]),
+                description="Continue in a new git worktree, on a branch of its own",
+                handler="_fork_worktree",
+            ),
+            "branch": Command(
+                aliases=frozenset(["/branch"]),
+                description="Continue in a new thread from a turn: `/branch [turn]`",
+                handler="_branch_thread",
+                takes_args=True,
+            ),
+            "checkpoint": Command(
+                aliases=frozenset(["/checkpoint"]),
+                description="Mark the current point as `/checkpoint <name>`",
+                handler="_create_checkpoint",
+                takes_args=True,
+            ),
+            "snapshot": Command(
+                aliases=frozenset(["/snapshot"]),
+                description="Save the conversation, or `/snapshot list|restore <id>`",
+                handler="_manage_context_snapshots",
+                takes_args=True,
+            ),
+            "undo": Command(
+                aliases=frozenset(["/undo"]),
+                description="Drop the last turn, or the last n with `/undo <n>`",
+                handler="_undo_turns",
+                takes_args=True,
+            ),
+            "account": Command(
+                aliases=frozenset(["/account"]),
+                description="List accounts, or switch with `/account <label>`",
+                handler="_manage_accounts",
+                takes_args=True,
+            ),
+            "memory": Command(
+                aliases=frozenset(["/memory"]),
+                description="List the project's memories, or `/memory forget <id>`",
+                handler="_manage_memories",
+                takes_args=True,
+            ),
+            "rename": Command(
+                aliases=frozenset(["/rename"]),
+                description="Name the session for the resume picker: `/rename <name>`",
+                handler="_rename_session",
+                takes_args=True,
+            ),
+            "tag": Command(
+                aliases=frozenset(["/tag"]),
+                description="Tag the session for the resume picker: `/tag <tag>...`",
+                handler="_tag_session",
+                takes_args=True,
+            ),
+            "restore": Command(
+                aliases=frozenset(["/restore"]),
+                description="Roll conversation and file edits back to a checkpoint",
+                handler="_restore_checkpoint",
+                takes_args=True,
+            ),
         }
 
         for command in excluded_commands:
@@ -78,8 +184,17 @@ class CommandRegistry:
                 self._alias_map[alias] = cmd_name
 
     def find_command(self, user_input: str) -> Command | None:
-        cmd_name = self._alias_map.get(user_input.lower().strip())
-        return self.commands.get(cmd_name) if cmd_name else None
+        name, _, args = user_input.strip().partition(" ")
+        if not (cmd_name := self._alias_map.get(name.lower())):
+            return None
+        command = self.commands[cmd_name]
+        if args.strip() and not command.takes_args:
+            return None
+        return command
+
+    @staticmethod
+    def get_args(user_input: str) -> str:
+        return user_input.strip().partition(" ")[2].strip()
 
     def get_help_text(self) -> str:
         lines: list[str] = [
@@ -92,6 +207,11 @@ class CommandRegistry:
             "- `Ctrl+G` Edit input in external editor",
             "- `Ctrl+O` Toggle tool output view",
             "- `Shift+Tab` Toggle auto-approve mode",
+            "- `Ctrl+R` Toggle read-only mode",
+            "- `Ctrl+N` Stop the running command and let the agent carry on",
+            "- `Ctrl+P` Open the command palette",
+            "- `Ctrl+B` Edit the command or commit message from the last reply",
+            "- `Ctrl+S` Save the command or commit message from the last reply",
             "",
             "### Special Features",
             "",
@@ -0,0 +1,73 @@
+from __future__ import annotations
+
+import argparse
+import json
+import sys
+from typing import Any
+
+from rich import print as rprint
+from rich.markup import escape
+
+from rune.core.config_edit import (
+    format_value,
+    parse_value,
+    read_config_key,
+    remove_config_key,
+    write_config_key,
+)
+
+
+def parse_config_arguments(argv: list[str]) -> argparse.Namespace:
+    parser = argparse.ArgumentParser(
+        prog="rune config",
+        description="Read and change individual keys of the config file",
+    )
+    subparsers = parser.add_subparsers(dest="action", required=True)
+
+    get_parser = subparsers.add_parser("get", help="Print the effective value of a key")
+    get_parser.add_argument(
+        "key", help="Dotted key, e.g. auto_title.enabled or providers.NAME.api_base"
+    )
+    get_parser.add_argument(
+        "--json", action="store_true", help="Print the value as JSON"
+    )
+
+    set_parser = subparsers.add_parser("set", help="Set a key in the config file")
+    set_parser.add_argument("key", help="Dotted key")
+    set_parser.add_argument(
+        "value",
+        help="A TOML value such as true, 3 or '[\"a\"]'; anything else is a string",
+    )
+
+    unset_parser = subparsers.add_parser(
+        "unset", help="Remove a key from the config file, back to its default"
+    )
+    unset_parser.add_argument("key", help="Dotted key")
+    return parser.parse_args(argv[1:])
+
+
+def _print_value(value: Any, as_json: bool) -> None:
+    if as_json or isinstance(value, dict | list):
+        sys.stdout.write(json.dumps(value, indent=2) + "\n")
+    elif isinstance(value, str):
+        sys.stdout.write(value + "\n")
+    elif value is not None:
+        sys.stdout.write(format_value(value) + "\n")
+
+
+def run_config_command(argv: list[str]) -> None:
+    args = parse_config_arguments(argv)
+    try:
+        match args.action:
+            case "get":
+                _print_value(read_config_key(args.key), args.json)
+            case "set":
+                value = parse_value(args.value)
+                path = write_config_key(args.key, value)
+                rprint(f"Set {args.key} in {path}")
+            case "unset":
+                path = remove_config_key(args.key)
+                rprint(f"Removed {args.key} from {path}")
+    except (OSError, ValueError, RuntimeError) as e:
+        rprint(f"[red]Error: {escape(str(e))}[/]")
+        sys.exit(1)
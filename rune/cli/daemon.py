@@ -0,0 +1,59 @@
+from __future__ import annotations
+
+import argparse
+import asyncio
+from pathlib import Path
+import sys
+
+from rich import print as rprint
+
+from rune.acp.daemon import (
+    DaemonError,
+    daemon_paths,
+    daemon_pid,
+    serve,
+    start_daemon,
+    stop_daemon,
+)
+
+
+def parse_daemon_arguments(argv: list[str]) -> argparse.Namespace:
+    parser = argparse.ArgumentParser(
+        prog="rune daemon",
+        description=(
+            "Keep Rune warm for this project behind a Unix socket that "
+            "rune-acp connects to"
+        ),
+    )
+    subparsers = parser.add_subparsers(dest="action", required=True)
+    subparsers.add_parser("start", help="Start the daemon in the background")
+    subparsers.add_parser("stop", help="Stop the daemon")
+    subparsers.add_parser("status", help="Show whether the daemon is running")
+    subparsers.add_parser("run", help="Run the daemon in the foreground")
+    return parser.parse_args(argv[1:])
+
+
+def run_daemon_command(argv: list[str]) -> None:
+    args = parse_daemon_arguments(argv)
+    project = Path.cwd()
+    try:
+        match args.action:
+            case "start":
+                pid = start_daemon(project)
+                rprint(f"Rune daemon started for {project} (pid {pid})")
+            case "stop":
+                if (pid := stop_daemon(project)) is None:
+                    rprint(f"No daemon running for {project}")
+                else:
+                    rprint(f"Stopped the Rune daemon (pid {pid})")
+            case "status":
+                if (pid := daemon_pid(project)) is None:
+                    rprint(f"No daemon running for {project}")
+                else:
+                    paths = daemon_paths(project)
+                    rprint(f"Rune daemon running (pid {pid}) on {paths.socket}")
+            case "run":
+                asyncio.run(serve(project))
+    except (OSError, DaemonError) as e:
+        rprint(f"[red]Error: {e}[/]")
+        sys.exit(1)
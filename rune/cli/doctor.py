@@ -0,0 +1,51 @@
+from __future__ import annotations
+
+import argparse
+import json
+import sys
+
+from rich import print as rprint
+from rich.markup import escape
+
+from rune.core.doctor import CheckStatus, DoctorCheck, run_doctor
+
+_LABELS = {
+    CheckStatus.OK: "[green]  ok[/]",
+    CheckStatus.WARN: "[yellow]warn[/]",
+    CheckStatus.FAIL: "[red]fail[/]",
+    CheckStatus.SKIP: "[dim]skip[/]",
+}
+
+
+def parse_doctor_arguments(argv: list[str]) -> argparse.Namespace:
+    parser = argparse.ArgumentParser(
+        prog="rune doctor",
+        description="Check this machine's Rune setup and suggest fixes",
+    )
+    parser.add_argument(
+        "--offline",
+        action="store_true",
+        help="Skip the checks that contact providers or start MCP servers",
+    )
+    parser.add_argument("--json", action="store_true", help="Print the checks as JSON")
+    return parser.parse_args(argv[1:])
+
+
+def _print_check(check: DoctorCheck) -> None:
+    rprint(f"{_LABELS[check.status]}  {escape(check.name)}: {escape(check.detail)}")
+    if check.fix and check.status in {CheckStatus.WARN, CheckStatus.FAIL}:
+        rprint(f"      [dim]Fix: {escape(check.fix)}[/]")
+
+
+def run_doctor_command(argv: list[str]) -> None:
+    args = parse_doctor_arguments(argv)
+    checks = run_doctor(online=not args.offline)
+    if args.json:
+        sys.stdout.write(
+            json.dumps([c.model_dump(mode="json") for c in checks], indent=2) + "\n"
+        )
+    else:
+        for check in checks:
+            _print_check(check)
+    if any(check.status == CheckStatus.FAIL for check in checks):
+        sys.exit(1)
@@ -1,6 +1,7 @@
 from __future__ import annotations
 
 import argparse
+import importlib
 import os
 from pathlib import Path
 import sys
@@ -17,6 +18,25 @@ from rune.setup.trusted_folders.trust_folder_dialog import (
 )
 
 
+# name -> (module, function); modules are imported only when their command runs.
+SUBCOMMANDS: dict[str, tuple[str, str]] = {
+    "approvals": ("rune.cli.policy", "run_policy_command"),
+    "policy": ("rune.cli.policy", "run_policy_command"),
+    "org-defaults": ("rune.cli.org_defaults", "run_org_defaults_command"),
+    "bench": ("rune.cli.bench", "run_bench_command"),
+    "config": ("rune.cli.config", "run_config_command"),
+    "login": ("rune.cli.accounts", "run_login_command"),
+    "accounts": ("rune.cli.accounts", "run_accounts_command"),
+    "auth": ("rune.cli.accounts", "run_auth_command"),
+    "doctor": ("rune.cli.doctor", "run_doctor_command"),
+    "privacy": ("rune.cli.privacy", "run_privacy_command"),
+    "daemon": ("rune.cli.daemon", "run_daemon_command"),
+    "secrets": ("rune.cli.secrets", "run_secrets_command"),
+    "sessions": ("rune.cli.sessions", "run_sessions_command"),
+    "export": ("rune.cli.sessions", "run_export_command"),
+}
+
+
 def parse_arguments() -> argparse.Namespace:
     parser = argparse.ArgumentParser(description="Run the Rune interactive CLI")
     parser.add_argument(
@@ -69,6 +89,14 @@ def parse_arguments() -> argparse.Namespace:
         "for human-readable (default), 'json' for all messages at end, "
         "'streaming' for newline-delimited JSON per message.",
     )
+    parser.add_argument(
+        "--output-schema",
+        type=Path,
+        metavar="FILE",
+        help="JSON schema the final response must follow (only applies in "
+        "programmatic mode with -p). Enforced with constrained decoding where "
+        "the provider supports it.",
+    )
     parser.add_argument(
         "--agent",
         metavar="NAME",
@@ -83,6 +111,15 @@ def parse_arguments() -> argparse.Namespace:
         metavar="DIR",
         help="Change to this directory before running",
     )
+    parser.add_argument(
+        "--add-root",
+        type=Path,
+        action="append",
+        metavar="DIR",
+        dest="add_roots",
+        help="Add another workspace root, addressable as 'name:path'. "
+        "Can be specified multiple times.",
+    )
 
     # Feature flag for teleport, not exposed to the user yet
     parser.add_argument("--teleport", action="store_true", help=argparse.SUPPRESS)
@@ -97,8 +134,8 @@ def parse_arguments() -> argparse.Namespace:
     )
     continuation_group.add_argument(
         "--resume",
-        metavar="SESSION_ID",
-        help="Resume a specific session by its ID (supports partial matching)",
+        metavar="SESSION",
+        help="Resume a specific session by its ID (supports partial matching) or its name set with /rename",
     )
     return parser.parse_args()
 
@@ -137,7 +174,19 @@ def check_and_resolve_trusted_folder() -> None:
         trusted_folders_manager.add_untrusted(cwd)
 
 
+def run_subcommand(argv: list[str]) -> bool:
+    if not argv or (target := SUBCOMMANDS.get(argv[0])) is None:
+        return False
+    unlock_config_paths()
+    module_name, function_name = target
+    getattr(importlib.import_module(module_name), function_name)(argv)
+    return True
+
+
 def main() -> None:
+    if run_subcommand(sys.argv[1:]):
+        return
+
     args = parse_arguments()
 
     if args.workdir:
@@ -149,6 +198,13 @@ def main() -> None:
             sys.exit(1)
         os.chdir(workdir)
 
+    for root in args.add_roots or []:
+        if not root.expanduser().is_dir():
+            rprint(
+                f"[red]Error: --add-root does not exist or is not a directory: {root}[/]"
+            )
+            sys.exit(1)
+
     is_interactive = args.prompt is None
     if is_interactive:
         check_and_resolve_trusted_folder()
@@ -160,6 +216,10 @@ def main() -> None:
             from rune.cli.onboarding import run_onboarding
             run_onboarding()
 
+        from rune.cli.privacy import ensure_privacy_consent
+
+        ensure_privacy_consent()
+
     from rune.cli.cli import run_cli
 
     run_cli(args)
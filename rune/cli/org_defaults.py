@@ -0,0 +1,102 @@
+from __future__ import annotations
+
+import argparse
+from pathlib import Path
+import sys
+import tomllib
+
+from rich import print as rprint
+
+from rune.core.config import RuneConfig, TomlFileSettingsSource
+from rune.core.org_defaults import (
+    OrgDefaults,
+    OrgDefaultsError,
+    load_org_defaults,
+    org_defaults_url,
+)
+from rune.core.policy_bundle import PolicyBundleError
+
+
+def parse_org_defaults_arguments(argv: list[str]) -> argparse.Namespace:
+    parser = argparse.ArgumentParser(
+        prog="rune org-defaults",
+        description="Publish and inspect organization-wide config defaults",
+    )
+    subparsers = parser.add_subparsers(dest="action", required=True)
+
+    sign_parser = subparsers.add_parser(
+        "sign", help="Turn a config.toml fragment into a signed defaults document"
+    )
+    sign_parser.add_argument("config", type=Path, metavar="FILE")
+    sign_parser.add_argument(
+        "--sign-key",
+        type=Path,
+        required=True,
+        metavar="PEM",
+        help="Ed25519 private key used to sign the document",
+    )
+    sign_parser.add_argument("--name", default="", help="Name of the defaults")
+    sign_parser.add_argument(
+        "--lock",
+        action="append",
+        default=[],
+        metavar="KEY",
+        help="Enforce the default of KEY (dotted for nested keys) on every "
+        "machine. Can be specified multiple times.",
+    )
+    sign_parser.add_argument(
+        "-o", "--output", type=Path, metavar="FILE", help="Output file (default: stdout)"
+    )
+
+    subparsers.add_parser("show", help="Show the defaults this machine receives")
+    return parser.parse_args(argv[1:])
+
+
+def _sign(args: argparse.Namespace) -> None:
+    with args.config.open("rb") as f:
+        defaults = tomllib.load(f)
+    document = OrgDefaults(name=args.name, defaults=defaults, locked=args.lock)
+    valid = document.validated_defaults(RuneConfig)
+    if rejected := sorted(set(defaults) - set(valid)):
+        raise OrgDefaultsError(f"Invalid config keys: {', '.join(rejected)}")
+    if unset := sorted(set(args.lock) - set(document.locked_values(valid))):
+        raise OrgDefaultsError(f"Locked keys without a default: {', '.join(unset)}")
+
+    document = document.sign(args.sign_key.read_bytes())
+    if args.output is None:
+        sys.stdout.write(document.to_toml())
+        return
+    args.output.write_text(document.to_toml(), encoding="utf-8")
+    rprint(f"Wrote defaults signed by key {document.signed_by} to {args.output}")
+
+
+def _show() -> None:
+    user_config = TomlFileSettingsSource(RuneConfig).toml_data
+    if not (url := org_defaults_url(user_config)):
+        rprint("No org defaults configured (set org_defaults_url)")
+        return
+    document = load_org_defaults(url, ttl=0)
+    if document is None:
+        raise OrgDefaultsError(f"No verified defaults available from {url}")
+
+    label = f" '{document.name}'" if document.name else ""
+    rprint(f"Org defaults{label} from {url}, signed by key {document.signed_by}")
+    defaults = document.validated_defaults(RuneConfig)
+    for key in sorted(defaults):
+        overridden = " (overridden by your config)" if key in user_config else ""
+        rprint(f"  {key}{overridden}")
+    if locked := document.locked_values(defaults):
+        rprint(f"Locked: {', '.join(sorted(locked))}")
+
+
+def run_org_defaults_command(argv: list[str]) -> None:
+    args = parse_org_defaults_arguments(argv)
+    try:
+        match args.action:
+            case "sign":
+                _sign(args)
+            case "show":
+                _show()
+    except (OSError, ValueError, OrgDefaultsError, PolicyBundleError) as e:
+        rprint(f"[red]Error: {e}[/]")
+        sys.exit(1)
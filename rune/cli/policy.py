@@ -0,0 +1,92 @@
+from __future__ import annotations
+
+import argparse
+from pathlib import Path
+import sys
+
+from rich import print as rprint
+
+from rune.core.config import RuneConfig, TomlFileSettingsSource
+from rune.core.paths.global_paths import TRUSTED_KEYS_DIR
+from rune.core.policy_bundle import (
+    BundleKind,
+    PolicyBundle,
+    PolicyBundleError,
+    load_trusted_keys,
+)
+
+POLICY_COMMANDS = {"approvals": BundleKind.APPROVALS, "policy": BundleKind.POLICY}
+
+
+def parse_policy_arguments(argv: list[str]) -> argparse.Namespace:
+    kind = argv[0]
+    parser = argparse.ArgumentParser(
+        prog=f"rune {kind}", description=f"Share {kind} rules between machines"
+    )
+    subparsers = parser.add_subparsers(dest="action", required=True)
+
+    export_parser = subparsers.add_parser(
+        "export", help=f"Write the {kind} rules from your config to a bundle"
+    )
+    export_parser.add_argument(
+        "-o", "--output", type=Path, metavar="FILE", help="Bundle file (default: stdout)"
+    )
+    export_parser.add_argument("--name", default="", help="Name of the bundle")
+    export_parser.add_argument(
+        "--sign-key",
+        type=Path,
+        metavar="PEM",
+        help="Ed25519 private key used to sign the bundle",
+    )
+
+    import_parser = subparsers.add_parser(
+        "import", help=f"Merge a {kind} bundle into your config"
+    )
+    import_parser.add_argument("bundle", type=Path, metavar="FILE")
+    import_parser.add_argument(
+        "--allow-unsigned",
+        action="store_true",
+        help=f"Accept bundles not signed by a key in {TRUSTED_KEYS_DIR.path}",
+    )
+    return parser.parse_args(argv[1:])
+
+
+def _export(kind: BundleKind, args: argparse.Namespace) -> None:
+    data = TomlFileSettingsSource(RuneConfig).toml_data
+    bundle = PolicyBundle.from_config_data(kind, data, name=args.name)
+    if args.sign_key:
+        bundle = bundle.sign(args.sign_key.read_bytes())
+
+    if args.output is None:
+        sys.stdout.write(bundle.to_toml())
+        return
+    args.output.write_text(bundle.to_toml(), encoding="utf-8")
+    rprint(f"Exported {len(bundle.tools)} tool rule(s) to {args.output}")
+
+
+def _import(kind: BundleKind, args: argparse.Namespace) -> None:
+    bundle = PolicyBundle.load(args.bundle)
+    if bundle.kind != kind:
+        raise PolicyBundleError(f"{args.bundle} is a {bundle.kind} bundle, not {kind}")
+
+    if bundle.signature or not args.allow_unsigned:
+        fingerprint = bundle.verify(load_trusted_keys())
+        rprint(f"Signature verified (key {fingerprint})")
+
+    RuneConfig.save_updates(bundle.to_config_updates())
+    label = f" '{bundle.name}'" if bundle.name else ""
+    rprint(f"Imported {kind} bundle{label} with {len(bundle.tools)} tool rule(s)")
+
+
+def run_policy_command(argv: list[str]) -> None:
+    kind = POLICY_COMMANDS[argv[0]]
+    args = parse_policy_arguments(argv)
+    try:
+        match args.action:
+            case "export":
+                _export(kind, args)
+            case "import":
+                _import(kind, args)
+    except (OSError, ValueError, PolicyBundleError) as e:
+        rprint(f"[red]Error: {e}[/]")
+        sys.exit(1)
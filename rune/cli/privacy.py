@@ -0,0 +1,125 @@
+from __future__ import annotations
+
+import argparse
+import json
+import sys
+
+from rich import print as rprint
+from rich.console import Console
+from rich.panel import Panel
+from rich.prompt import Confirm
+from rich.table import Table
+
+from rune.core.config import RuneConfig
+from rune.core.privacy import (
+    CATEGORIES,
+    PRIVACY_NOTICE_VERSION,
+    PrivacyError,
+    category_status,
+    needs_consent,
+    toggle_update,
+)
+
+
+def parse_privacy_arguments(argv: list[str]) -> argparse.Namespace:
+    parser = argparse.ArgumentParser(
+        prog="rune privacy",
+        description="Show and choose what data Rune collects and where it goes",
+    )
+    subparsers = parser.add_subparsers(dest="action")
+
+    status_parser = subparsers.add_parser(
+        "status", help="Show each data category and whether it is collected"
+    )
+    status_parser.add_argument(
+        "--json", action="store_true", help="Print the status as JSON"
+    )
+    for action, verb in (("enable", "Allow"), ("disable", "Stop")):
+        toggle_parser = subparsers.add_parser(
+            action, help=f"{verb} collecting a category of data"
+        )
+        toggle_parser.add_argument(
+            "category", choices=[c.name for c in CATEGORIES if c.key is not None]
+        )
+    subparsers.add_parser(
+        "consent", help="Review the privacy notice and choose what to allow"
+    )
+    return parser.parse_args(argv[1:])
+
+
+def _status(config: RuneConfig, as_json: bool) -> None:
+    statuses = category_status(config)
+    if as_json:
+        sys.stdout.write(
+            json.dumps([s.model_dump(mode="json") for s in statuses], indent=2) + "\n"
+        )
+        return
+
+    table = Table(title="Data collected by Rune")
+    table.add_column("Category")
+    table.add_column("Status")
+    table.add_column("What")
+    table.add_column("Where it goes")
+    for status in statuses:
+        table.add_row(
+            status.name,
+            "[green]on[/]" if status.enabled else "[dim]off[/]",
+            status.collects,
+            status.destination,
+        )
+    Console().print(table)
+    rprint("Change a category with `rune privacy enable|disable CATEGORY`.")
+
+
+def run_consent_flow(config: RuneConfig, console: Console | None = None) -> None:
+    console = console or Console()
+    console.print(
+        Panel.fit(
+            "Rune sends nothing to its authors. Besides your model provider, "
+            "these are the places data can go, each of which you can turn off "
+            "later with `rune privacy`.",
+            title="Privacy",
+        )
+    )
+    for status in category_status(config):
+        console.print(f"\n[bold]{status.title}[/]: {status.collects}")
+        console.print(f"Goes to {status.destination}.")
+        if status.key is None:
+            continue
+        allowed = Confirm.ask(f"Allow {status.title.lower()}?", default=status.enabled)
+        if allowed != status.enabled:
+            RuneConfig.save_updates(toggle_update(status.name, allowed))
+    RuneConfig.save_updates({"privacy_notice_version": PRIVACY_NOTICE_VERSION})
+    console.print("\nSaved. Run `rune privacy` to review these choices.\n")
+
+
+def ensure_privacy_consent() -> None:
+    """Shows the privacy notice once per notice version, on interactive runs."""
+    if not sys.stdin.isatty():
+        return
+    try:
+        config = RuneConfig.load()
+    except Exception:
+        # Config errors are reported, with more context, when the session starts.
+        return
+    if needs_consent(config):
+        run_consent_flow(config)
+
+
+def run_privacy_command(argv: list[str]) -> None:
+    args = parse_privacy_arguments(argv)
+    try:
+        config = RuneConfig.load()
+        match args.action:
+            case "enable" | "disable":
+                RuneConfig.save_updates(
+                    toggle_update(args.category, args.action == "enable")
+                )
+                rprint(f"{args.category}: {args.action}d")
+            case "consent":
+                run_consent_flow(config)
+            case _:
+                _status(config, getattr(args, "json", False))
+    except (OSError, ValueError, PrivacyError) as e:
+        rprint(f"[red]Error: {e}[/]")
+        sys.exit(1)
@@ -0,0 +1,78 @@
+from __future__ import annotations
+
+import argparse
+import getpass
+import sys
+
+from rich import print as rprint
+
+from rune.core.secrets import (
+    SecretsError,
+    SecretsStore,
+    delete_keychain,
+    write_keychain,
+)
+
+
+def parse_secrets_arguments(argv: list[str]) -> argparse.Namespace:
+    parser = argparse.ArgumentParser(
+        prog="rune secrets",
+        description=(
+            "Store credentials encrypted for MCP servers and tool commands, "
+            "referenced as ${secret:NAME}"
+        ),
+    )
+    subparsers = parser.add_subparsers(dest="action", required=True)
+
+    subparsers.add_parser("list", help="List the names of stored secrets")
+    set_parser = subparsers.add_parser(
+        "set", help="Store a secret, read from a prompt or from stdin"
+    )
+    set_parser.add_argument("name")
+    delete_parser = subparsers.add_parser("delete", help="Remove a secret")
+    delete_parser.add_argument("name")
+    for action_parser in (set_parser, delete_parser):
+        action_parser.add_argument(
+            "--keychain",
+            action="store_true",
+            help="Use the OS credential store, for `keychain:NAME` config values",
+        )
+    return parser.parse_args(argv[1:])
+
+
+def _read_value(name: str) -> str:
+    if sys.stdin.isatty():
+        return getpass.getpass(f"Value for {name}: ")
+    return sys.stdin.read().rstrip("\n")
+
+
+def run_secrets_command(argv: list[str]) -> None:
+    args = parse_secrets_arguments(argv)
+    store = SecretsStore()
+    try:
+        match args.action:
+            case "list":
+                if not (names := store.names()):
+                    rprint(f"No secrets stored in {store.path}")
+                for name in names:
+                    rprint(name)
+            case "set":
+                if not (value := _read_value(args.name)):
+                    raise SecretsError("Refusing to store an empty secret")
+                if args.keychain:
+                    write_keychain(args.name, value)
+                    rprint(f"Stored {args.name}; reference it as keychain:{args.name}")
+                else:
+                    store.set(args.name, value)
+                    rprint(
+                        f"Stored {args.name}; reference it as ${{secret:{args.name}}}"
+                    )
+            case "delete":
+                if args.keychain:
+                    delete_keychain(args.name)
+                else:
+                    store.delete(args.name)
+                rprint(f"Deleted {args.name}")
+    except (OSError, SecretsError) as e:
+        rprint(f"[red]Error: {e}[/]")
+        sys.exit(1)
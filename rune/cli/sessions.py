@@ -0,0 +1,379 @@
+from __future__ import annotations
+
+import argparse
+import json
+from pathlib import Path
+import sys
+
+from rich import print as rprint
+
+from rune.core.config import RuneConfig
+from rune.core.session.session_export import ExportFormat, export_session
+from rune.core.session.session_fork import SessionForkError, fork_session
+from rune.core.session.session_import import SessionImportError, import_session
+from rune.core.session.session_index import SessionIndex, SessionIndexError
+from rune.core.session.session_loader import SessionFilter, SessionLoader
+from rune.core.session.session_sync import SessionSync, SessionSyncError
+from rune.core.session.session_trace import TraceError, build_turn_trace
+from rune.core.session.session_verify import repair_sessions, verify_sessions
+from rune.core.tools.file_guards import format_size
+
+
+def parse_sessions_arguments(argv: list[str]) -> argparse.Namespace:
+    parser = argparse.ArgumentParser(
+        prog="rune sessions", description="Inspect logged sessions"
+    )
+    subparsers = parser.add_subparsers(dest="action", required=True)
+
+    trace_parser = subparsers.add_parser(
+        "trace", help="Show the prompt that was sent to the model for a turn"
+    )
+    trace_parser.add_argument("session_id", metavar="SESSION_ID")
+    trace_parser.add_argument(
+        "--turn", type=int, required=True, metavar="N", help="User turn, from 1"
+    )
+    trace_parser.add_argument(
+        "--request",
+        type=int,
+        default=1,
+        metavar="K",
+        help="Model request within the turn, from 1 (tool results add requests)",
+    )
+    trace_parser.add_argument(
+        "--no-redact",
+        dest="redact",
+        action="store_false",
+        help="Keep API keys, tokens and other secrets in the output",
+    )
+    trace_parser.add_argument(
+        "--omit-tool-output",
+        action="store_true",
+        help="Replace tool results with their length",
+    )
+    trace_parser.add_argument(
+        "--json", action="store_true", help="Print the trace as JSON"
+    )
+    trace_parser.add_argument(
+        "-o", "--output", type=Path, metavar="FILE", help="Output file (default: stdout)"
+    )
+
+    list_parser = subparsers.add_parser("list", help="List recent sessions")
+    list_parser.add_argument(
+        "--tag",
+        dest="tags",
+        action="append",
+        default=[],
+        metavar="TAG",
+        help="Only sessions with this tag (repeat to require several)",
+    )
+    list_parser.add_argument(
+        "--cwd", type=Path, metavar="DIR", help="Only sessions started in DIR or below"
+    )
+    list_parser.add_argument(
+        "--model", metavar="ALIAS", help="Only sessions that used this model"
+    )
+    list_parser.add_argument(
+        "--limit", type=int, default=20, metavar="N", help="Sessions to list"
+    )
+    list_parser.add_argument(
+        "--json", action="store_true", help="Print the sessions as JSON"
+    )
+
+    search_parser = subparsers.add_parser(
+        "search", help="Find sessions by what was said or run in them"
+    )
+    search_parser.add_argument("query", nargs="+", metavar="QUERY")
+    search_parser.add_argument(
+        "--limit", type=int, default=20, metavar="N", help="Sessions to list"
+    )
+    search_parser.add_argument(
+        "--json", action="store_true", help="Print the matches as JSON"
+    )
+
+    fork_parser = subparsers.add_parser(
+        "fork", help="Copy a session, up to a turn, into a new one to resume"
+    )
+    fork_parser.add_argument("session_id", metavar="SESSION_ID")
+    fork_parser.add_argument(
+        "--at-turn",
+        type=int,
+        metavar="N",
+        help="Last user turn to keep, from 1 (default: all of them)",
+    )
+
+    verify_parser = subparsers.add_parser(
+        "verify", help="Check the session logs and search index for damage"
+    )
+    verify_parser.add_argument(
+        "--repair",
+        action="store_true",
+        help="Cut truncated last lines (keeping a .bak) and rebuild the index",
+    )
+    verify_parser.add_argument(
+        "--json", action="store_true", help="Print the problems as JSON"
+    )
+
+    maintain_parser = subparsers.add_parser(
+        "maintain", help="Clean up, analyze and vacuum the session search index"
+    )
+    maintain_parser.add_argument(
+        "--json", action="store_true", help="Print the report as JSON"
+    )
+
+    sync_parser = subparsers.add_parser(
+        "sync", help="Push and pull sessions to and from the sync store"
+    )
+    sync_parser.add_argument(
+        "--json", action="store_true", help="Print the report as JSON"
+    )
+
+    import_parser = subparsers.add_parser(
+        "import", help="Make a session from another machine resumable here"
+    )
+    import_parser.add_argument(
+        "source",
+        type=Path,
+        metavar="FILE",
+        help="A session directory, its messages.jsonl, or a JSON export",
+    )
+    import_parser.add_argument(
+        "--workdir",
+        type=Path,
+        metavar="DIR",
+        help="Where the session continues (default: the current directory)",
+    )
+    return parser.parse_args(argv[1:])
+
+
+def parse_export_arguments(argv: list[str]) -> argparse.Namespace:
+    parser = argparse.ArgumentParser(
+        prog="rune export", description="Export a logged session as a transcript"
+    )
+    parser.add_argument("session_id", metavar="SESSION_ID")
+    parser.add_argument(
+        "--format",
+        type=ExportFormat,
+        choices=list(ExportFormat),
+        default=ExportFormat.MARKDOWN,
+        help="Transcript format (default: md)",
+    )
+    parser.add_argument(
+        "--no-redact",
+        dest="redact",
+        action="store_false",
+        help="Keep API keys, tokens and other secrets in the transcript",
+    )
+    parser.add_argument(
+        "-o", "--output", type=Path, metavar="FILE", help="Output file (default: stdout)"
+    )
+    return parser.parse_args(argv[1:])
+
+
+def _find_session(session_id: str, config: RuneConfig) -> Path:
+    session_dir = SessionLoader.find_session_by_id(
+        session_id, config.session_logging
+    ) or SessionLoader.find_session_by_name(session_id, config.session_logging)
+    if session_dir is None:
+        raise ValueError(f"No logged session matches '{session_id}'")
+    return session_dir
+
+
+def run_export_command(argv: list[str]) -> None:
+    args = parse_export_arguments(argv)
+    try:
+        session_dir = _find_session(args.session_id, RuneConfig.load())
+        text = export_session(session_dir, args.format, redact=args.redact)
+        if args.output is None:
+            sys.stdout.write(text)
+            return
+        args.output.write_text(text, encoding="utf-8")
+    except (OSError, ValueError) as e:
+        rprint(f"[red]Error: {e}[/]")
+        sys.exit(1)
+    rprint(f"Exported session {args.session_id} to {args.output}")
+
+
+def _trace(args: argparse.Namespace) -> None:
+    config = RuneConfig.load()
+    session_dir = SessionLoader.find_session_by_id(
+        args.session_id, config.session_logging
+    )
+    if session_dir is None:
+        raise TraceError(f"No logged session matches '{args.session_id}'")
+
+    trace = build_turn_trace(session_dir, args.turn, args.request).redacted(
+        secrets=args.redact, tool_outputs=args.omit_tool_output
+    )
+    text = trace.model_dump_json(indent=2) + "\n" if args.json else trace.to_text()
+
+    if args.output is None:
+        sys.stdout.write(text)
+        return
+    args.output.write_text(text, encoding="utf-8")
+    rprint(f"Wrote turn {args.turn} of session {trace.session_id} to {args.output}")
+
+
+def _list(args: argparse.Namespace) -> None:
+    config = RuneConfig.load()
+    session_filter = SessionFilter(tags=args.tags, cwd=args.cwd, model=args.model)
+    sessions = SessionLoader.list_recent_sessions(
+        config.session_logging, args.limit, session_filter
+    )
+    if args.json:
+        print(
+            json.dumps(
+                [
+                    {
+                        "session_id": metadata.get("session_id"),
+                        "title": metadata.get("title"),
+                        "tags": metadata.get("tags") or [],
+                        "cwd": (metadata.get("environment") or {}).get(
+                            "working_directory"
+                        ),
+                        "model": (metadata.get("config") or {}).get("active_model"),
+                        "end_time": metadata.get("end_time"),
+                        "path": str(path),
+                    }
+                    for path, metadata in sessions
+                ],
+                indent=2,
+            )
+        )
+        return
+    if not sessions:
+        rprint("No sessions match.")
+        return
+    for _, metadata in sessions:
+        session_id = str(metadata.get("session_id", ""))[:8]
+        tags = " ".join(f"#{tag}" for tag in metadata.get("tags") or [])
+        rprint(f"[bold]{session_id}[/]  {metadata.get('title') or 'Untitled session'}")
+        ended = str(metadata.get("end_time") or "")[:16].replace("T", " ")
+        print(f"  {ended}  {tags}".rstrip())
+
+
+def _search(args: argparse.Namespace) -> None:
+    config = RuneConfig.load()
+    hits = SessionIndex(config.session_logging).search(
+        " ".join(args.query), limit=args.limit
+    )
+    if args.json:
+        print(json.dumps([hit.model_dump(mode="json") for hit in hits], indent=2))
+        return
+    if not hits:
+        rprint("No sessions match.")
+        return
+    for hit in hits:
+        rprint(f"[bold]{hit.session_id[:8]}[/]  {hit.title}")
+        print(f"  {hit.kind}: {hit.snippet}")
+
+
+def _fork(args: argparse.Namespace) -> None:
+    config = RuneConfig.load()
+    session_dir = _find_session(args.session_id, config)
+    session_id, _ = fork_session(session_dir, config.session_logging, args.at_turn)
+    rprint(f"Forked as session {session_id[:8]}; resume it with:")
+    print(f"  rune --resume {session_id[:8]}")
+
+
+def _verify(args: argparse.Namespace) -> None:
+    config = RuneConfig.load()
+    problems = verify_sessions(config.session_logging)
+    repaired = repair_sessions(config.session_logging, problems) if args.repair else []
+    remaining = [p for p in problems if p not in repaired]
+    if args.json:
+        print(
+            json.dumps(
+                [
+                    {**p.model_dump(mode="json"), "repaired": p in repaired}
+                    for p in problems
+                ],
+                indent=2,
+            )
+        )
+    elif not problems:
+        rprint("All sessions are intact.")
+    else:
+        for problem in problems:
+            status = "[green]repaired[/]" if problem in repaired else "[red]problem[/]"
+            rprint(f"{status}  {problem.session_dir.name}: {problem.detail}")
+        if not args.repair and any(p.repairable for p in problems):
+            rprint("Run with --repair to fix what can be fixed.")
+    if remaining:
+        sys.exit(1)
+
+
+def _maintain(args: argparse.Namespace) -> None:
+    config = RuneConfig.load()
+    report = SessionIndex(config.session_logging).maintain()
+    if args.json:
+        print(report.model_dump_json(indent=2))
+        return
+    rprint(
+        f"Dropped {report.pruned_sessions} stale session(s) and "
+        f"{report.pruned_rows} orphaned row(s); "
+        f"{report.indexed_sessions} session(s) indexed."
+    )
+    print(
+        f"Index size: {format_size(report.size_before)} -> "
+        f"{format_size(report.size_after)}"
+    )
+
+
+def _sync(args: argparse.Namespace) -> None:
+    config = RuneConfig.load()
+    if not config.session_logging.enabled:
+        raise ValueError("Session logging is disabled; enable it to sync sessions")
+    report = SessionSync(
+        config.session_logging, config.session_sync, network=config.network
+    ).run()
+    if args.json:
+        print(report.model_dump_json(indent=2))
+        return
+    rprint(f"Pushed {len(report.pushed)} and pulled {len(report.pulled)} session(s).")
+    for name in report.conflicts:
+        rprint(f"[yellow]Changed on two machines; the other version is {name}[/]")
+    for error in report.errors:
+        rprint(f"[red]{error}[/]")
+    if report.errors:
+        sys.exit(1)
+
+
+def _import(args: argparse.Namespace) -> None:
+    config = RuneConfig.load()
+    if not config.session_logging.enabled:
+        raise ValueError("Session logging is disabled; enable it to import sessions")
+    session_id, _ = import_session(args.source, config.session_logging, args.workdir)
+    rprint(f"Imported as session {session_id[:8]}; resume it with:")
+    print(f"  rune --resume {session_id[:8]}")
+
+
+def run_sessions_command(argv: list[str]) -> None:
+    args = parse_sessions_arguments(argv)
+    try:
+        match args.action:
+            case "trace":
+                _trace(args)
+            case "list":
+                _list(args)
+            case "search":
+                _search(args)
+            case "fork":
+                _fork(args)
+            case "verify":
+                _verify(args)
+            case "maintain":
+                _maintain(args)
+            case "sync":
+                _sync(args)
+            case "import":
+                _import(args)
+    except (
+        OSError,
+        ValueError,
+        SessionForkError,
+        SessionIndexError,
+        SessionImportError,
+        SessionSyncError,
+    ) as e:
+        rprint(f"[red]Error: {e}[/]")
+        sys.exit(1)
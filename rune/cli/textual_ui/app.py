@@ -1,7 +1,9 @@
 from __future__ import annotations
 
 import asyncio
+from collections.abc import Awaitable, Callable
 from enum import StrEnum, auto
+from functools import partial
 from pathlib import Path
 import subprocess
 import time
@@ -11,6 +13,7 @@ from weakref import WeakKeyDictionary
 from pydantic import BaseModel
 from textual.app import App, ComposeResult
 from textual.binding import Binding, BindingType
+from textual.command import Provider
 from textual.containers import Horizontal, VerticalGroup, VerticalScroll
 from textual.events import AppBlur, AppFocus, MouseUp
 from textual.widget import Widget
@@ -28,6 +31,7 @@ from rune.cli.plan_offer.decide_plan_offer import (
 )
 from rune.cli.plan_offer.ports.whoami_gateway import WhoAmIGateway
 from rune.cli.terminal_setup import setup_terminal
+from rune.cli.textual_ui.command_palette import RuneCommandProvider
 from rune.cli.textual_ui.handlers.event_handler import EventHandler
 from rune.cli.textual_ui.widgets.approval_app import ApprovalApp
 from rune.cli.textual_ui.widgets.banner.banner import Banner
@@ -35,6 +39,7 @@ from rune.cli.textual_ui.widgets.chat_input import ChatInputContainer
 from rune.cli.textual_ui.widgets.compact import CompactMessage
 from rune.cli.textual_ui.widgets.config_app import ConfigApp
 from rune.cli.textual_ui.widgets.context_progress import ContextProgress, TokenState
+from rune.cli.textual_ui.widgets.editor_link import EditorLink, build_editor_uri
 from rune.cli.textual_ui.widgets.load_more import HistoryLoadMoreRequested
 from rune.cli.textual_ui.widgets.loading import LoadingWidget, paused_timer
 from rune.cli.textual_ui.widgets.messages import (
@@ -79,11 +84,25 @@ from rune.cli.update_notifier import (
 )
 from rune.cli.update_notifier.update import do_update
 from rune.core.agent_loop import AgentLoop, TeleportError
-from rune.core.agents import AgentProfile
+from rune.core.agents import AgentProfile, AgentSafety
+from rune.core.auth.accounts import AccountError, AccountStore
 from rune.core.autocompletion.path_prompt_adapter import render_path_prompt
 from rune.core.config import RuneConfig
+from rune.core.config_edit import read_config_key, write_config_key
+from rune.core.context_snapshots import ContextSnapshotError
+from rune.core.critic import CriticReview
+from rune.core.deliverables import composer_text, save_deliverable
+from rune.core.lmstudio import (
+    LMStudioClient,
+    LMStudioError,
+    describe_download,
+    lmstudio_provider,
+)
+from rune.core.memory import MemoryStore, MemoryStoreError
 from rune.core.paths.config_paths import HISTORY_FILE
+from rune.core.session.session_fork import SessionForkError, history_until_turn
 from rune.core.session.session_loader import SessionLoader
+from rune.core.status import StatusSnapshot, UsageStatus
 from rune.core.teleport.types import (
     TeleportAuthCompleteEvent,
     TeleportAuthRequiredEvent,
@@ -102,9 +121,11 @@ from rune.core.tools.builtins.ask_user_question import (
     Choice,
     Question,
 )
+from rune.core.tools.dry_run import EXEC_TOOLS
 from rune.core.types import (
     AgentStats,
     ApprovalResponse,
+    DeliverableEvent,
     LLMMessage,
     RateLimitError,
     Role,
@@ -114,7 +135,9 @@ from rune.core.utils import (
     get_user_cancellation_message,
     is_dangerous_directory,
     logger,
+    turn_timing_display,
 )
+from rune.core.workspace_lease import WorktreeError
 
 
 class BottomApp(StrEnum):
@@ -140,6 +163,8 @@ class ChatScroll(VerticalScroll):
 
 PRUNE_LOW_MARK = 1000
 PRUNE_HIGH_MARK = 1500
+# Quick turns are not worth a timing header.
+MIN_TURN_TIMING_SECONDS = 1.0
 
 
 async def prune_by_height(messages_area: Widget, low_mark: int, high_mark: int) -> bool:
@@ -173,7 +198,10 @@ async def prune_by_height(messages_area: Widget, low_mark: int, high_mark: int)
 
 
 class RuneApp(App):  # noqa: PLR0904
-    ENABLE_COMMAND_PALETTE = False
+    COMMANDS: ClassVar[set[type[Provider] | Callable[[], type[Provider]]]] = {
+        RuneCommandProvider
+    }
+    COMMAND_PALETTE_BINDING = "ctrl+p"
     CSS_PATH = "app.tcss"
 
     BINDINGS: ClassVar[list[BindingType]] = [
@@ -184,6 +212,22 @@ class RuneApp(App):  # noqa: PLR0904
         Binding("ctrl+y", "copy_selection", "Copy", show=False, priority=True),
         Binding("ctrl+shift+c", "copy_selection", "Copy", show=False, priority=True),
         Binding("shift+tab", "cycle_mode", "Cycle Mode", show=False, priority=True),
+        Binding("ctrl+r", "toggle_read_only", "Read-only", show=False, priority=True),
+        Binding("ctrl+n", "stop_command", "Stop Command", show=False, priority=True),
+        Binding(
+            "ctrl+b",
+            "use_deliverable",
+            "Edit Delivered Command",
+            show=False,
+            priority=True,
+        ),
+        Binding(
+            "ctrl+s",
+            "save_deliverable",
+            "Save Delivered Command",
+            show=False,
+            priority=True,
+        ),
         Binding("shift+up", "scroll_chat_up", "Scroll Up", show=False, priority=True),
         Binding(
             "shift+down", "scroll_chat_down", "Scroll Down", show=False, priority=True
@@ -212,6 +256,8 @@ class RuneApp(App):  # noqa: PLR0904
         self._pending_question: asyncio.Future | None = None
 
         self.event_handler: EventHandler | None = None
+        # Command or commit message delivered by the last reply.
+        self._deliverable: DeliverableEvent | None = None
 
         excluded_commands = []
         if not self.config.nuage_enabled:
@@ -281,20 +327,26 @@ class RuneApp(App):  # noqa: PLR0904
         self._cached_chat = self.query_one("#chat", ChatScroll)
         self._cached_loading_area = self.query_one("#loading-area-content")
 
+        context_progress = self.query_one(ContextProgress)
+
+        def show_context_tokens(tokens: int) -> None:
+            context_progress.tokens = TokenState(
+                max_tokens=self.config.auto_compact_threshold, current_tokens=tokens
+            )
+
+        def update_context_progress(stats: AgentStats) -> None:
+            show_context_tokens(stats.context_tokens)
+
         self.event_handler = EventHandler(
             mount_callback=self._mount_and_scroll,
             scroll_callback=self._scroll_to_bottom_deferred,
             get_tools_collapsed=lambda: self._tools_collapsed,
+            # Estimates while a reply streams; the stats listener settles them.
+            context_tokens_callback=show_context_tokens,
+            deliverable_callback=self._set_deliverable,
         )
 
         self._chat_input_container = self.query_one(ChatInputContainer)
-        context_progress = self.query_one(ContextProgress)
-
-        def update_context_progress(stats: AgentStats) -> None:
-            context_progress.tokens = TokenState(
-                max_tokens=self.config.auto_compact_threshold,
-                current_tokens=stats.context_tokens,
-            )
 
         self.agent_loop.stats.add_listener("context_tokens", update_context_progress)
         self.agent_loop.stats.trigger_listeners()
@@ -356,6 +408,60 @@ class RuneApp(App):  # noqa: PLR0904
 
         await self._handle_user_message(value)
 
+    def prefill_input(self, text: str) -> None:
+        input_widget = self.query_one(ChatInputContainer)
+        input_widget.value = text
+        input_widget.focus_input()
+
+    def submit_input(self, text: str) -> None:
+        self.post_message(ChatInputContainer.Submitted(text))
+
+    async def resume_thread(self, session_dir: Path) -> None:
+        async def resume() -> str:
+            await self.agent_loop.load_history(messages, metadata)
+            return f"Resumed thread from {session_dir.name}."
+
+        try:
+            messages, metadata = SessionLoader.load_session(session_dir)
+            await self._reset_history(resume())
+        except Exception as e:
+            await self._mount_and_scroll(
+                ErrorMessage(
+                    f"Failed to resume thread: {e}", collapsed=self._tools_collapsed
+                )
+            )
+
+    async def toggle_setting(self, key: str) -> None:
+        try:
+            write_config_key(key, not read_config_key(key))
+        except (OSError, ValueError) as e:
+            await self._mount_and_scroll(
+                ErrorMessage(
+                    f"Failed to toggle {key}: {e}", collapsed=self._tools_collapsed
+                )
+            )
+            return
+        await self._reload_config()
+
+    def on_editor_link_selected(self, message: EditorLink.Selected) -> None:
+        if not (template := self.config.editor_uri_template):
+            self.notify(
+                "Set editor_uri_template in config.toml, "
+                "e.g. 'vscode://file/{path}:{line}'.",
+                title="No editor configured",
+                severity="warning",
+                timeout=5,
+            )
+            return
+        try:
+            uri = build_editor_uri(template, message.path, message.line)
+        except (KeyError, IndexError, ValueError) as e:
+            self.notify(
+                f"Invalid editor_uri_template: {e}", severity="error", timeout=5
+            )
+            return
+        self.open_url(uri)
+
     async def on_approval_app_approval_granted(
         self, message: ApprovalApp.ApprovalGranted
     ) -> None:
@@ -367,7 +473,7 @@ class RuneApp(App):  # noqa: PLR0904
     async def on_approval_app_approval_granted_always_tool(
         self, message: ApprovalApp.ApprovalGrantedAlwaysTool
     ) -> None:
-        self._set_tool_permission_always(
+        await self._set_tool_permission_always(
             message.tool_name, save_permanently=message.save_permanently
         )
 
@@ -441,10 +547,10 @@ class RuneApp(App):  # noqa: PLR0904
             for widget in children[:compact_index]:
                 await widget.remove()
 
-    def _set_tool_permission_always(
+    async def _set_tool_permission_always(
         self, tool_name: str, save_permanently: bool = False
     ) -> None:
-        self.agent_loop.set_tool_permission(
+        await self.agent_loop.set_tool_permission(
             tool_name, ToolPermission.ALWAYS, save_permanently
         )
 
@@ -452,10 +558,11 @@ class RuneApp(App):  # noqa: PLR0904
         if command := self.commands.find_command(user_input):
             await self._mount_and_scroll(UserMessage(user_input))
             handler = getattr(self, command.handler)
+            args = (self.commands.get_args(user_input),) if command.takes_args else ()
             if asyncio.iscoroutinefunction(handler):
-                await handler()
+                await handler(*args)
             else:
-                handler()
+                handler(*args)
             return True
         return False
 
@@ -502,9 +609,15 @@ class RuneApp(App):  # noqa: PLR0904
             )
             return
 
+        cwd = self.agent_loop.workspace.active.path
         try:
             result = subprocess.run(
-                command, shell=True, capture_output=True, text=False, timeout=30
+                command,
+                shell=True,
+                capture_output=True,
+                text=False,
+                timeout=30,
+                cwd=cwd,
             )
             stdout = (
                 result.stdout.decode("utf-8", errors="replace") if result.stdout else ""
@@ -515,7 +628,7 @@ class RuneApp(App):  # noqa: PLR0904
             output = stdout or stderr or "(no output)"
             exit_code = result.returncode
             await self._mount_and_scroll(
-                BashOutputMessage(command, str(Path.cwd()), output, exit_code)
+                BashOutputMessage(command, str(cwd), output, exit_code)
             )
         except subprocess.TimeoutExpired:
             await self._mount_and_scroll(
@@ -604,13 +717,19 @@ class RuneApp(App):  # noqa: PLR0904
     ) -> tuple[ApprovalResponse, str | None]:
         # Auto-approve only if parent is in auto-approve mode AND tool is enabled
         # This ensures subagents respect the main agent's tool restrictions
-        if self.agent_loop and self.agent_loop.config.auto_approve:
+        write_grant = self.agent_loop.write_grant_requests.get(tool_call_id)
+        if self.agent_loop and self.agent_loop.config.auto_approve and not write_grant:
             if self._is_tool_enabled_in_main_agent(tool):
                 return (ApprovalResponse.YES, None)
 
         self._pending_approval = asyncio.Future()
         with paused_timer(self._loading_widget):
-            await self._switch_to_approval_app(tool, args)
+            await self._switch_to_approval_app(
+                tool,
+                args,
+                self.agent_loop.critic.reviews.get(tool_call_id),
+                write_grant,
+            )
             result = await self._pending_approval
 
         self._pending_approval = None
@@ -629,6 +748,7 @@ class RuneApp(App):  # noqa: PLR0904
 
     async def _handle_agent_loop_turn(self, prompt: str) -> None:
         self._agent_running = True
+        self._deliverable = None
 
         loading_area = self._cached_loading_area or self.query_one(
             "#loading-area-content"
@@ -639,7 +759,9 @@ class RuneApp(App):  # noqa: PLR0904
         await loading_area.mount(loading)
 
         try:
-            rendered_prompt = render_path_prompt(prompt, base_dir=Path.cwd())
+            rendered_prompt = render_path_prompt(
+                prompt, base_dir=self.agent_loop.workspace.active.path
+            )
             async for event in self.agent_loop.act(rendered_prompt):
                 if self.event_handler:
                     await self.event_handler.handle_event(
@@ -647,6 +769,7 @@ class RuneApp(App):  # noqa: PLR0904
                         loading_active=self._loading_widget is not None,
                         loading_widget=self._loading_widget,
                     )
+            await self._show_turn_timing()
 
         except asyncio.CancelledError:
             if self._loading_widget and self._loading_widget.parent:
@@ -680,6 +803,22 @@ class RuneApp(App):  # noqa: PLR0904
             await self._finalize_current_streaming_message()
             await self._refresh_windowing_from_history()
 
+    async def _show_turn_timing(self) -> None:
+        messages_area = self._cached_messages_area or self.query_one("#messages")
+        user_messages = messages_area.query(UserMessage)
+        if not user_messages:
+            return
+        stats = self.agent_loop.stats
+        if stats.last_turn_wall_time < MIN_TURN_TIMING_SECONDS:
+            return
+        user_messages.last().set_timing(
+            turn_timing_display(
+                stats.last_turn_wall_time,
+                stats.last_turn_model_time,
+                stats.last_turn_tool_time,
+            )
+        )
+
     async def _teleport_command(self) -> None:
         await self._handle_teleport_command(show_message=False)
 
@@ -806,17 +945,542 @@ class RuneApp(App):  # noqa: PLR0904
         await self._mount_and_scroll(UserCommandMessage(help_text))
 
     async def _show_status(self) -> None:
-        stats = self.agent_loop.stats
-        status_text = f"""## Agent Statistics
+        status = StatusSnapshot.from_agent_loop(self.agent_loop)
+        model = status.model
+        approvals = status.approvals
+        routed = f" (last routed to {model.routed_to})" if model.routed_to else ""
+        effort = f", {model.reasoning_effort} effort" if model.reasoning_effort else ""
+        write_roots = ", ".join(f"`{root}`" for root in approvals.write_roots)
+        mcp_lines = [
+            f"- **{name}**: {state}" for name, state in status.mcp_servers.items()
+        ] or ["- none configured"]
+        enabled = [name for name, on in status.features.items() if on]
+        roots = ", ".join(
+            f"{name} (`{path}`)" for name, path in status.workspace_roots.items()
+        )
+        lines = [
+            "## Status",
+            "",
+            f"- **Session**: {status.session_id}",
+            f"- **Working Directory**: {status.cwd}",
+            f"- **Workspace Roots**: {roots}",
+            f"- **Model**: {model.alias} ({model.provider}, "
+            f"temperature {model.temperature}{effort}){routed}",
+            f"- **Agent**: {approvals.agent} ({approvals.safety})",
+            f"- **Auto-approve**: {'on' if approvals.auto_approve else 'off'}",
+            f"- **Read-only**: {'on' if approvals.read_only else 'off'}",
+            f"- **Dry Run**: {'on' if approvals.dry_run else 'off'}",
+            f"- **Extra Write Roots**: {write_roots or 'none'}",
+            f"- **Response Language**: {status.response_language or 'not set'}",
+            f"- **Features**: {', '.join(enabled) or 'none'}",
+            f"- **Last Rate Limit**: {status.rate_limited_at or 'none'}",
+            "",
+            "### MCP Servers",
+            "",
+            *mcp_lines,
+            "",
+            *self._usage_lines(status.usage),
+        ]
+        await self._mount_and_scroll(UserCommandMessage("\n".join(lines)))
+
+    async def _show_usage(self) -> None:
+        usage = StatusSnapshot.from_agent_loop(self.agent_loop).usage
+        await self._mount_and_scroll(
+            UserCommandMessage("\n".join(self._usage_lines(usage)))
+        )
+
+    async def _show_context_usage(self) -> None:
+        usage = self.agent_loop.context_usage()
+        window = f"{usage.context_window:,}" if usage.context_window else "unknown"
+        utilization = (
+            f" ({usage.utilization:.0%} full)" if usage.utilization is not None else ""
+        )
+        lines = [
+            "### Context",
+            "",
+            f"- **Instructions**: {usage.instructions:,}",
+            f"- **History**: {usage.history:,}",
+            f"- **Tool Outputs**: {usage.tool_outputs:,}",
+            f"- **Total**: {usage.total:,} of {window} tokens{utilization}",
+            f"- **Last Reported**: {usage.reported_tokens:,}",
+            "",
+            "Counts are estimates; run /compact to free space.",
+        ]
+        await self._mount_and_scroll(UserCommandMessage("\n".join(lines)))
+
+    @staticmethod
+    def _usage_lines(usage: UsageStatus) -> list[str]:
+        return [
+            "### Usage",
+            "",
+            f"- **Steps**: {usage.steps:,}",
+            f"- **Session Prompt Tokens**: {usage.prompt_tokens:,}",
+            f"- **Session Completion Tokens**: {usage.completion_tokens:,}",
+            f"- **Session Total LLM Tokens**: {usage.total_tokens:,}",
+            f"- **Last Turn Tokens**: {usage.last_turn_tokens:,}",
+            f"- **Cached Prompt Tokens**: {usage.cached_tokens:,}",
+            f"- **Context Tokens**: {usage.context_tokens:,} / "
+            f"{usage.auto_compact_threshold:,}",
+            f"- **Cost**: ${usage.cost:.4f} (last request ${usage.last_turn_cost:.4f})",
+            "- **Session Time**: "
+            + turn_timing_display(usage.wall_time, usage.model_time, usage.tool_time),
+            "- **Last Turn**: "
+            + turn_timing_display(
+                usage.last_turn_wall_time,
+                usage.last_turn_model_time,
+                usage.last_turn_tool_time,
+            ),
+        ]
+
+    async def _set_response_language(self, language: str) -> None:
+        if not language:
+            current = self.config.response_language or "not set"
+            await self._mount_and_scroll(
+                UserCommandMessage(f"Response language: {current}")
+            )
+            return
+
+        if language.lower() in {"off", "none", "default"}:
+            language = ""
+        await self.agent_loop.set_response_language(language)
+        await self._mount_and_scroll(
+            UserCommandMessage(
+                f"Response language set to {language}."
+                if language
+                else "Response language reset."
+            )
+        )
+
+    async def _create_checkpoint(self, name: str) -> None:
+        if not name:
+            await self._mount_and_scroll(
+                ErrorMessage(
+                    "Usage: /checkpoint <name>", collapsed=self._tools_collapsed
+                )
+            )
+            return
+
+        self.agent_loop.rewind.create_checkpoint(name)
+        await self._mount_and_scroll(
+            UserCommandMessage(f"Checkpoint `{name}` created.")
+        )
+
+    async def _rename_session(self, name: str) -> None:
+        if not name:
+            await self._mount_and_scroll(
+                ErrorMessage("Usage: /rename <name>", collapsed=self._tools_collapsed)
+            )
+            return
+        if not self.agent_loop.session_logger.enabled:
+            await self._mount_and_scroll(
+                ErrorMessage(
+                    "Session logging is disabled, so sessions can't be named.",
+                    collapsed=self._tools_collapsed,
+                )
+            )
+            return
+
+        try:
+            await self.agent_loop.session_logger.set_name(name)
+        except RuntimeError as e:
+            await self._mount_and_scroll(
+                ErrorMessage(str(e), collapsed=self._tools_collapsed)
+            )
+            return
+        await self._mount_and_scroll(
+            UserCommandMessage(f"Session renamed to `{name}`.")
+        )
+
+    async def _tag_session(self, args: str) -> None:
+        if not args:
+            tags = self.agent_loop.session_logger.tags
+            await self._mount_and_scroll(
+                UserCommandMessage(
+                    "Tags: " + ", ".join(f"`{t}`" for t in tags)
+                    if tags
+                    else "No tags yet. Usage: /tag <tag>... (`/tag -` clears them)"
+                )
+            )
+            return
+        if not self.agent_loop.session_logger.enabled:
+            await self._mount_and_scroll(
+                ErrorMessage(
+                    "Session logging is disabled, so sessions can't be tagged.",
+                    collapsed=self._tools_collapsed,
+                )
+            )
+            return
+
+        try:
+            tags = await self.agent_loop.session_logger.set_tags(
+                [] if args.strip() == "-" else args.replace(",", " ").split()
+            )
+        except RuntimeError as e:
+            await self._mount_and_scroll(
+                ErrorMessage(str(e), collapsed=self._tools_collapsed)
+            )
+            return
+        listing = ", ".join(f"`{t}`" for t in tags)
+        message = f"Session tagged {listing}." if tags else "Tags cleared."
+        await self._mount_and_scroll(UserCommandMessage(message))
+
+    async def _restore_checkpoint(self, name: str) -> None:
+        if not name:
+            names = self.agent_loop.checkpoints.names
+            listing = (
+                "\n".join(f"- `{n}`" for n in names) if names else "No checkpoints yet."
+            )
+            await self._mount_and_scroll(UserCommandMessage(listing))
+            return
+
+        async def restore() -> str:
+            await self.agent_loop.rewind.restore_checkpoint(name)
+            return f"Restored checkpoint `{name}`."
 
-- **Steps**: {stats.steps:,}
-- **Session Prompt Tokens**: {stats.session_prompt_tokens:,}
-- **Session Completion Tokens**: {stats.session_completion_tokens:,}
-- **Session Total LLM Tokens**: {stats.session_total_llm_tokens:,}
-- **Last Turn Tokens**: {stats.last_turn_total_tokens:,}
-- **Cost**: ${stats.session_cost:.4f}
-"""
-        await self._mount_and_scroll(UserCommandMessage(status_text))
+        try:
+            await self._reset_history(restore())
+        except Exception as e:
+            await self._mount_and_scroll(
+                ErrorMessage(
+                    f"Failed to restore checkpoint: {e}",
+                    collapsed=self._tools_collapsed,
+                )
+            )
+
+    async def _manage_context_snapshots(self, args: str) -> None:
+        action, _, target = args.strip().partition(" ")
+        target = target.strip()
+        match action.lower():
+            case "":
+                snapshot = self.agent_loop.rewind.snapshot_context()
+                message = (
+                    f"Saved context snapshot `{snapshot.id}` "
+                    f"({snapshot.message_count} messages)."
+                )
+            case "list":
+                snapshots = self.agent_loop.context_snapshots.snapshots
+                message = (
+                    "\n".join(
+                        f"- `{s.id}`: {s.message_count} messages, "
+                        f"taken at {s.created_at.astimezone():%H:%M:%S}"
+                        for s in snapshots
+                    )
+                    if snapshots
+                    else "No context snapshots yet."
+                )
+            case "restore" if target:
+
+                async def restore() -> str:
+                    await self.agent_loop.rewind.restore_context(target)
+                    return f"Restored context snapshot `{target}`."
+
+                try:
+                    await self._reset_history(restore())
+                except ContextSnapshotError as e:
+                    await self._mount_and_scroll(
+                        ErrorMessage(str(e), collapsed=self._tools_collapsed)
+                    )
+                return
+            case _:
+                message = "Usage: /snapshot [list | restore <id>]"
+        await self._mount_and_scroll(UserCommandMessage(message))
+
+    async def _undo_turns(self, args: str) -> None:
+        count = args.strip() or "1"
+        if not count.isdigit() or int(count) < 1:
+            await self._mount_and_scroll(
+                ErrorMessage("Usage: /undo [n]", collapsed=self._tools_collapsed)
+            )
+            return
+
+        async def undo() -> str:
+            snapshot = await self.agent_loop.rewind.undo_turns(int(count))
+            return (
+                f"Undid the last {count} turn(s); "
+                f"`/snapshot restore {snapshot.id}` brings them back."
+            )
+
+        try:
+            await self._reset_history(undo())
+        except ContextSnapshotError as e:
+            await self._mount_and_scroll(
+                ErrorMessage(str(e), collapsed=self._tools_collapsed)
+            )
+
+    async def _branch_thread(self, args: str) -> None:
+        args = args.strip()
+        if args and not args.isdigit():
+            await self._mount_and_scroll(
+                ErrorMessage("Usage: /branch [turn]", collapsed=self._tools_collapsed)
+            )
+            return
+        turn = int(args) if args else None
+        try:
+            history_until_turn(self.agent_loop.messages, turn)
+        except SessionForkError as e:
+            await self._mount_and_scroll(
+                ErrorMessage(str(e), collapsed=self._tools_collapsed)
+            )
+            return
+
+        async def branch() -> str:
+            await self.agent_loop.rewind.fork_at_turn(turn)
+            where = f"after turn {turn}" if turn else "here"
+            return (
+                f"Branched off {where} into a new thread; "
+                "the original stays in the resume picker."
+            )
+
+        await self._reset_history(branch())
+
+    async def _reset_history(self, replace: Awaitable[str]) -> None:
+        """Swap the conversation for another history, redraw it and report
+        the message ``replace`` returns.
+        """
+        self._windowing.reset()
+        self._tool_call_map = None
+        self._history_widget_indices = WeakKeyDictionary()
+        await self._load_more.hide()
+        message = await replace
+        await self._finalize_current_streaming_message()
+        messages_area = self._cached_messages_area or self.query_one("#messages")
+        await messages_area.remove_children()
+        await self._resume_history_from_messages()
+        await self._mount_and_scroll(UserCommandMessage(message))
+
+    async def _manage_accounts(self, args: str) -> None:
+        store = AccountStore()
+        try:
+            if label := args.strip():
+                account = store.switch(label)
+                message = (
+                    f"Using account **{account.label}** for {account.provider} "
+                    "from the next request on."
+                )
+            elif accounts := store.accounts():
+                active = store.active_labels()
+                lines = ["### Accounts", ""]
+                lines += [
+                    f"- **{a.label}** ({a.provider})"
+                    + (" (active)" if active.get(a.provider) == a.label else "")
+                    for a in accounts
+                ]
+                lines += ["", "Switch with `/account <label>` or from Ctrl+P."]
+                message = "\n".join(lines)
+            else:
+                message = "No accounts yet; add one with `rune login --label <name>`."
+        except AccountError as e:
+            message = str(e)
+        await self._mount_and_scroll(UserCommandMessage(message))
+
+    async def _manage_memories(self, args: str) -> None:
+        action, _, target = args.strip().partition(" ")
+        store = MemoryStore(project=self.agent_loop.workspace.active.path)
+        try:
+            match action.lower():
+                case "" | "list":
+                    memories = store.recent()
+                    message = (
+                        "\n".join(m.render() for m in memories)
+                        if memories
+                        else "No memories in this project yet."
+                    )
+                case "forget" if target.strip().isdigit():
+                    store.delete(int(target))
+                    message = f"Forgot memory {target.strip()}."
+                case _:
+                    message = "Usage: /memory [list | forget <id>]"
+        except MemoryStoreError as e:
+            await self._mount_and_scroll(
+                ErrorMessage(str(e), collapsed=self._tools_collapsed)
+            )
+            return
+        await self._mount_and_scroll(UserCommandMessage(message))
+
+    async def _manage_permissions(self, args: str) -> None:
+        action, _, target = args.partition(" ")
+        grants = self.agent_loop.write_roots
+        match action.lower():
+            case "":
+                lines = [
+                    "### Write access",
+                    "",
+                    f"- `{self.agent_loop.workspace.active.path}` (workspace)",
+                    *(f"- `{root}`" for root in grants.roots),
+                    "",
+                    "Use `/permissions add <dir>`, `/permissions remove <dir>` "
+                    "or `/permissions clear` to edit the session grants.",
+                ]
+                message = "\n".join(lines)
+            case "add" if target.strip():
+                message = f"Granted write access to `{grants.add(Path(target.strip()))}`."
+            case "remove" if target.strip():
+                message = (
+                    f"Revoked write access to `{target.strip()}`."
+                    if grants.remove(Path(target.strip()))
+                    else f"`{target.strip()}` is not a granted directory."
+                )
+            case "clear":
+                grants.clear()
+                message = "Cleared all session write grants."
+            case _:
+                message = "Usage: /permissions [add <dir> | remove <dir> | clear]"
+        await self._mount_and_scroll(UserCommandMessage(message))
+
+    async def _manage_workspace(self, args: str) -> None:
+        action, _, target = args.strip().partition(" ")
+        target = target.strip()
+        workspace = self.agent_loop.workspace
+        try:
+            match action.lower():
+                case "" | "list":
+                    lines = [
+                        "### Workspace roots",
+                        "",
+                        *(
+                            f"- **{root.name}**: `{root.path}`"
+                            + (" (active)" if root == workspace.active else "")
+                            for root in workspace.roots
+                        ),
+                        "",
+                        "Use `/workspace add <dir> [name]`, `/workspace use <name>` "
+                        "or `/workspace remove <name>`. Other roots are addressed "
+                        "as `name:relative/path`.",
+                    ]
+                    message = "\n".join(lines)
+                case "add" if target:
+                    directory, _, name = target.rpartition(" ")
+                    if not directory or not Path(target).expanduser().is_dir():
+                        directory, name = target, ""
+                    root = workspace.add(Path(directory), name.strip() or None)
+                    message = f"Added workspace root **{root.name}** (`{root.path}`)."
+                case "use" if target:
+                    root = workspace.use(target)
+                    self.query_one(PathDisplay).set_path(root.path)
+                    message = f"Now working in **{root.name}** (`{root.path}`)."
+                case "remove" if target:
+                    message = (
+                        f"Removed workspace root **{target}**."
+                        if workspace.remove(target)
+                        else f"**{target}** is the active root or does not exist."
+                    )
+                case _:
+                    message = (
+                        "Usage: /workspace [add <dir> [name] | use <name> | "
+                        "remove <name>]"
+                    )
+        except ValueError as e:
+            message = str(e)
+        await self._mount_and_scroll(UserCommandMessage(message))
+
+    async def _manage_local_models(self, args: str) -> None:
+        action, _, model = args.strip().partition(" ")
+        model = model.strip()
+        try:
+            client = LMStudioClient(lmstudio_provider(self.config))
+            match action.lower():
+                case "" | "list":
+                    models = await client.list_models()
+                    lines = [f"### LM Studio models ({client.provider.name})", ""]
+                    lines += [f"- {m.describe()}" for m in models] or [
+                        "No models downloaded yet."
+                    ]
+                    lines += [
+                        "",
+                        "Use `/local pull <model>` to download one, "
+                        "`/local load <model>` to load it now.",
+                    ]
+                    message = "\n".join(lines)
+                case "pull" if model:
+                    self.run_worker(self._pull_local_model(client, model))
+                    return
+                case "load" if model:
+                    await client.load(model)
+                    message = f"Loaded `{model}` in LM Studio."
+                case _:
+                    message = "Usage: /local [list | pull <model> | load <model>]"
+        except LMStudioError as e:
+            await self._mount_and_scroll(
+                ErrorMessage(str(e), collapsed=self._tools_collapsed)
+            )
+            return
+        await self._mount_and_scroll(UserCommandMessage(message))
+
+    async def _pull_local_model(self, client: LMStudioClient, model: str) -> None:
+        status = NoMarkupStatic(
+            describe_download(model, None, False), classes="model-list-changed"
+        )
+        await self._mount_and_scroll(status)
+        try:
+            async for progress in client.download(model):
+                status.update(
+                    describe_download(model, progress.fraction, progress.done)
+                )
+        except LMStudioError as e:
+            await self._mount_and_scroll(
+                ErrorMessage(str(e), collapsed=self._tools_collapsed)
+            )
+
+    async def _fork_worktree(self) -> None:
+        try:
+            root = self.agent_loop.workspace.fork_worktree(self.agent_loop.session_id)
+        except WorktreeError as e:
+            await self._mount_and_scroll(
+                ErrorMessage(
+                    f"Could not fork into a worktree: {e}",
+                    collapsed=self._tools_collapsed,
+                )
+            )
+            return
+        self.query_one(PathDisplay).set_path(root.path)
+        await self._mount_and_scroll(
+            UserCommandMessage(
+                f"Now working in the worktree **{root.name}** (`{root.path}`), on a "
+                "branch of its own. Uncommitted changes of the original checkout "
+                "were not copied."
+            )
+        )
+
+    async def _toggle_dry_run(self) -> None:
+        self.agent_loop.dry_run = not self.agent_loop.dry_run
+        self._refresh_profile_widgets()
+        await self._mount_and_scroll(
+            UserCommandMessage(
+                "Dry-run mode on: shell commands will be classified and risk-scored "
+                "instead of executed."
+                if self.agent_loop.dry_run
+                else "Dry-run mode off: shell commands run normally again."
+            )
+        )
+
+    async def _manage_background_processes(self, args: str) -> None:
+        action, _, target = args.strip().partition(" ")
+        tools = self.agent_loop.tool_manager
+        match action.lower():
+            case "" | "list":
+                processes = tools.list_background_processes()
+                message = (
+                    "\n".join(
+                        f"- `{p.id}` {p.description}, pid {p.pid}"
+                        + (
+                            f", since {p.started_at.astimezone():%H:%M}"
+                            if p.started_at
+                            else ""
+                        )
+                        for p in processes
+                    )
+                    if processes
+                    else "No tool processes are running."
+                )
+            case "kill" if target.strip():
+                message = (
+                    f"Stopped `{target.strip()}`."
+                    if await tools.kill_background_process(target.strip())
+                    else f"No running process `{target.strip()}`."
+                )
+            case _:
+                message = "Usage: /processes [list | kill <id>]"
+        await self._mount_and_scroll(UserCommandMessage(message))
 
     async def _show_config(self) -> None:
         """Switch to the configuration app in the bottom panel."""
@@ -955,6 +1619,7 @@ class RuneApp(App):  # noqa: PLR0904
         return self.agent_loop.session_logger.session_id[:8]
 
     async def _exit_app(self) -> None:
+        self.agent_loop.cancel_background_tasks()
         self.exit(result=self._get_session_resume_info())
 
     async def _setup_terminal(self) -> None:
@@ -998,10 +1663,27 @@ class RuneApp(App):  # noqa: PLR0904
         await self._switch_from_input(ConfigApp(self.config))
 
     async def _switch_to_approval_app(
-        self, tool_name: str, tool_args: BaseModel
+        self,
+        tool_name: str,
+        tool_args: BaseModel,
+        critic_review: CriticReview | None = None,
+        write_grant: Path | None = None,
     ) -> None:
         approval_app = ApprovalApp(
-            tool_name=tool_name, tool_args=tool_args, config=self.config
+            tool_name=tool_name,
+            tool_args=tool_args,
+            config=self.config,
+            critic_review=critic_review,
+            write_grant=write_grant,
+            explain=(
+                partial(
+                    self.agent_loop.explainer.explain_call,
+                    self.agent_loop.tool_manager.get(tool_name),
+                    tool_args,
+                )
+                if tool_name in EXEC_TOOLS
+                else None
+            ),
         )
         await self._switch_from_input(approval_app, scroll=True)
 
@@ -1145,9 +1827,43 @@ class RuneApp(App):  # noqa: PLR0904
         self._update_profile_widgets(self.agent_loop.agent_profile)
 
     def _update_profile_widgets(self, profile: AgentProfile) -> None:
-        if self._chat_input_container:
-            self._chat_input_container.set_safety(profile.safety)
-            self._chat_input_container.set_agent_name(profile.display_name.lower())
+        if not self._chat_input_container:
+            return
+        modes = [
+            mode
+            for mode, enabled in (
+                ("read-only", self.agent_loop.read_only),
+                ("dry-run", self.agent_loop.dry_run),
+            )
+            if enabled
+        ]
+        self._chat_input_container.set_safety(
+            AgentSafety.SAFE if modes else profile.safety
+        )
+        self._chat_input_container.set_agent_name(
+            " · ".join([profile.display_name.lower(), *modes])
+        )
+
+    def action_toggle_read_only(self) -> None:
+        self.agent_loop.read_only = not self.agent_loop.read_only
+        self._refresh_profile_widgets()
+        self.notify(
+            "Edits and state-changing commands are blocked."
+            if self.agent_loop.read_only
+            else "Tools run with the active agent's permissions again.",
+            title=f"Read-only mode {'on' if self.agent_loop.read_only else 'off'}",
+            severity="information",
+            timeout=3,
+        )
+
+    def action_stop_command(self) -> None:
+        if self._agent_running and self.agent_loop.tool_stops.stop():
+            self.notify(
+                "The agent gets the output so far and carries on.",
+                title="Command stopped",
+                severity="information",
+                timeout=3,
+            )
 
     async def _cycle_agent(self) -> None:
         new_profile = self.agent_loop.agent_manager.next_agent(
@@ -1171,6 +1887,7 @@ class RuneApp(App):  # noqa: PLR0904
     def action_force_quit(self) -> None:
         if self._agent_task and not self._agent_task.done():
             self._agent_task.cancel()
+        self.agent_loop.cancel_background_tasks()
 
         self.exit(result=self._get_session_resume_info())
 
@@ -1430,6 +2147,24 @@ class RuneApp(App):  # noqa: PLR0904
             message, title="Update available", severity="information", timeout=10
         )
 
+    def _set_deliverable(self, deliverable: DeliverableEvent) -> None:
+        self._deliverable = deliverable
+
+    def action_use_deliverable(self) -> None:
+        if self._deliverable is None:
+            return
+        self.prefill_input(composer_text(self._deliverable))
+
+    def action_save_deliverable(self) -> None:
+        if self._deliverable is None:
+            return
+        try:
+            path = save_deliverable(self._deliverable)
+        except OSError as e:
+            self.notify(f"Could not save: {e}", severity="error")
+            return
+        self.notify(f"Saved to {path}", title="Saved", timeout=5)
+
     def action_copy_selection(self) -> None:
         copy_selection_to_clipboard(self, show_toast=False)
 
@@ -0,0 +1,175 @@
+from __future__ import annotations
+
+from collections.abc import Callable
+from dataclasses import dataclass
+from functools import partial
+from typing import TYPE_CHECKING, cast
+
+from textual.binding import Binding
+from textual.command import DiscoveryHit, Hit, Hits, Provider
+
+from rune.core.auth.accounts import AccountError, AccountStore
+from rune.core.config_edit import ConfigKeyError, get_value
+from rune.core.session.session_loader import SessionFilter, SessionLoader
+
+if TYPE_CHECKING:
+    from rune.cli.textual_ui.app import RuneApp
+
+RECENT_THREADS_LIMIT = 10
+
+# On/off settings the palette flips in config.toml, by dotted key.
+CONFIG_TOGGLES = {
+    "autocopy_to_clipboard": "Auto-copy",
+    "vim_keybindings": "Vim keybindings",
+    "context_warnings": "Context warnings",
+    "dedupe_tool_outputs": "Dedupe tool outputs",
+    "critic.enabled": "Patch critic",
+    "router.enabled": "Model router",
+    "auto_title.enabled": "Automatic titles",
+    "session_logging.enabled": "Session logging",
+}
+
+
+@dataclass(frozen=True)
+class PaletteEntry:
+    title: str
+    help: str
+    callback: Callable[[], object]
+
+
+def format_key(key: str) -> str:
+    return "+".join(part.capitalize() for part in key.split("+"))
+
+
+class RuneCommandProvider(Provider):
+    """Slash commands, config toggles, keybindings, accounts and recent threads
+    for the Ctrl+P palette.
+
+    `tag:`, `cwd:` and `model:` terms in the query narrow it to the threads
+    they match.
+    """
+
+    @property
+    def rune_app(self) -> RuneApp:
+        return cast("RuneApp", self.app)
+
+    def _command_entries(self) -> list[PaletteEntry]:
+        app = self.rune_app
+        entries = []
+        for command in app.commands.commands.values():
+            alias = min(command.aliases)
+            callback = (
+                partial(app.prefill_input, f"{alias} ")
+                if command.takes_args
+                else partial(app.submit_input, alias)
+            )
+            entries.append(PaletteEntry(alias, command.description, callback))
+        return entries
+
+    def _toggle_entries(self) -> list[PaletteEntry]:
+        app = self.rune_app
+        config = app.config.model_dump(mode="json")
+        entries = []
+        for key, label in CONFIG_TOGGLES.items():
+            try:
+                state = "on" if get_value(config, key) else "off"
+            except ConfigKeyError:
+                continue
+            entries.append(
+                PaletteEntry(
+                    f"Toggle {label} ({state})",
+                    f"Setting {key}",
+                    partial(app.toggle_setting, key),
+                )
+            )
+        return entries
+
+    def _binding_entries(self) -> list[PaletteEntry]:
+        app = self.rune_app
+        return [
+            PaletteEntry(
+                f"{binding.description} ({format_key(binding.key)})",
+                "Keybinding",
+                partial(app.run_action, binding.action),
+            )
+            for binding in app.BINDINGS
+            if isinstance(binding, Binding) and binding.description
+        ]
+
+    def _thread_entries(
+        self, session_filter: SessionFilter | None = None
+    ) -> list[PaletteEntry]:
+        app = self.rune_app
+        logging_config = app.config.session_logging
+        if not logging_config.enabled:
+            return []
+
+        entries = []
+        for path, metadata in SessionLoader.list_recent_sessions(
+            logging_config, RECENT_THREADS_LIMIT, session_filter
+        ):
+            title = metadata.get("title") or "Untitled session"
+            session_id = str(metadata.get("session_id", ""))[:8]
+            ended = str(metadata.get("end_time") or "")[:16].replace("T", " ")
+            details = [f"Thread {session_id}", ended]
+            if tags := metadata.get("tags"):
+                details.append(" ".join(f"#{tag}" for tag in tags))
+            entries.append(
+                PaletteEntry(
+                    f"Resume: {title}",
+                    " · ".join(details),
+                    partial(app.resume_thread, path),
+                )
+            )
+        return entries
+
+    def _account_entries(self) -> list[PaletteEntry]:
+        app = self.rune_app
+        store = AccountStore()
+        try:
+            accounts = store.accounts()
+            active = store.active_labels()
+        except AccountError:
+            return []
+        return [
+            PaletteEntry(
+                f"Switch account: {account.label}",
+                f"{account.provider} · active"
+                if active.get(account.provider) == account.label
+                else account.provider,
+                partial(app.submit_input, f"/account {account.label}"),
+            )
+            for account in accounts
+        ]
+
+    def _entries(self) -> list[PaletteEntry]:
+        return [
+            *self._command_entries(),
+            *self._toggle_entries(),
+            *self._binding_entries(),
+            *self._account_entries(),
+            *self._thread_entries(),
+        ]
+
+    async def discover(self) -> Hits:
+        for entry in self._entries():
+            yield DiscoveryHit(entry.title, entry.callback, help=entry.help)
+
+    async def search(self, query: str) -> Hits:
+        session_filter, query = SessionFilter.from_query(query)
+        entries = (
+            self._entries()
+            if session_filter.is_empty
+            else self._thread_entries(session_filter)
+        )
+        matcher = self.matcher(query)
+        for entry in entries:
+            if not query:
+                yield DiscoveryHit(entry.title, entry.callback, help=entry.help)
+            elif (score := matcher.match(f"{entry.title} {entry.help}")) > 0:
+                yield Hit(
+                    score,
+                    matcher.highlight(entry.title),
+                    entry.callback,
+                    help=entry.help,
+                )
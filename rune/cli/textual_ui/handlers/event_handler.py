@@ -1,25 +1,53 @@
 from __future__ import annotations
 
 from collections.abc import Callable
+from pathlib import Path
 from typing import TYPE_CHECKING
 
 from rune.cli.textual_ui.widgets.compact import CompactMessage
-from rune.cli.textual_ui.widgets.messages import AssistantMessage, ReasoningMessage
+from rune.cli.textual_ui.widgets.messages import (
+    AssistantMessage,
+    ReasoningMessage,
+    WarningMessage,
+)
 from rune.cli.textual_ui.widgets.no_markup_static import NoMarkupStatic
 from rune.cli.textual_ui.widgets.tools import ToolCallMessage, ToolResultMessage
+from rune.core.config_reload import describe_config_reload
+from rune.core.lmstudio import describe_download
+from rune.core.model_catalog import describe_model_list_change
+from rune.core.moderation import describe_verdict
+from rune.core.project_docs import display_path
 from rune.core.tools.ui import ToolUIDataAdapter
 from rune.core.types import (
+    ApiKeyWarningEvent,
     AssistantEvent,
     BaseEvent,
     CompactEndEvent,
     CompactStartEvent,
+    ConcurrentSessionEvent,
+    ConfigReloadedEvent,
+    ContextBudgetWarningEvent,
+    DeliverableEvent,
+    DeliverableKind,
+    LocalModelDownloadEvent,
+    ModelFallbackEvent,
+    ModelListChangedEvent,
+    ModelRoutedEvent,
+    ModerationEvent,
+    ProjectDocsLoadedEvent,
+    ProxyAuthFailedEvent,
     ReasoningEvent,
+    SecretsRedactedEvent,
+    StreamStalledEvent,
+    TokenCountEvent,
     ToolCallEvent,
+    ToolOutputEvent,
     ToolResultEvent,
     ToolStreamEvent,
     UserMessageEvent,
 )
 from rune.core.utils import TaggedText
+from rune.core.workspace_lease import describe_peers
 
 if TYPE_CHECKING:
     from rune.cli.textual_ui.widgets.loading import LoadingWidget
@@ -31,12 +59,17 @@ class EventHandler:
         mount_callback: Callable,
         scroll_callback: Callable,
         get_tools_collapsed: Callable[[], bool],
+        context_tokens_callback: Callable[[int], None] | None = None,
+        deliverable_callback: Callable[[DeliverableEvent], None] | None = None,
     ) -> None:
         self.mount_callback = mount_callback
         self.scroll_callback = scroll_callback
         self.get_tools_collapsed = get_tools_collapsed
+        self.context_tokens_callback = context_tokens_callback
+        self.deliverable_callback = deliverable_callback
         self.current_tool_call: ToolCallMessage | None = None
         self.current_compact: CompactMessage | None = None
+        self.current_download: NoMarkupStatic | None = None
 
     async def handle_event(
         self,
@@ -50,6 +83,8 @@ class EventHandler:
             case ToolResultEvent():
                 sanitized_event = self._sanitize_event(event)
                 await self._handle_tool_result(sanitized_event)
+            case ToolOutputEvent():
+                await self._handle_tool_output(event)
             case ToolStreamEvent():
                 await self._handle_tool_stream(event)
             case ReasoningEvent():
@@ -57,9 +92,40 @@ class EventHandler:
             case AssistantEvent():
                 await self._handle_assistant_message(event)
             case CompactStartEvent():
-                await self._handle_compact_start()
+                await self._handle_compact_start(event)
             case CompactEndEvent():
                 await self._handle_compact_end(event)
+            case ModelRoutedEvent():
+                await self._handle_model_routed(event)
+            case ModelFallbackEvent():
+                await self._handle_model_fallback(event)
+            case ModelListChangedEvent():
+                await self._handle_model_list_changed(event)
+            case ConfigReloadedEvent():
+                await self._handle_config_reloaded(event)
+            case ProjectDocsLoadedEvent():
+                await self._handle_project_docs_loaded(event)
+            case ApiKeyWarningEvent():
+                await self._handle_api_key_warning(event)
+            case ProxyAuthFailedEvent():
+                await self._handle_proxy_auth_failed(event)
+            case LocalModelDownloadEvent():
+                await self._handle_local_model_download(event)
+            case StreamStalledEvent():
+                await self._handle_stream_stalled(event)
+            case ModerationEvent():
+                await self._handle_moderation(event)
+            case ConcurrentSessionEvent():
+                await self._handle_concurrent_session(event)
+            case DeliverableEvent():
+                await self._handle_deliverable(event)
+            case ContextBudgetWarningEvent():
+                await self._handle_context_budget_warning(event)
+            case SecretsRedactedEvent():
+                await self._handle_secrets_redacted(event)
+            case TokenCountEvent():
+                if self.context_tokens_callback:
+                    self.context_tokens_callback(event.context_tokens)
             case UserMessageEvent():
                 pass
             case _:
@@ -112,6 +178,10 @@ class EventHandler:
         if self.current_tool_call:
             self.current_tool_call.set_stream_message(event.message)
 
+    async def _handle_tool_output(self, event: ToolOutputEvent) -> None:
+        if self.current_tool_call:
+            self.current_tool_call.append_output(event.message)
+
     async def _handle_assistant_message(self, event: AssistantEvent) -> None:
         await self.mount_callback(AssistantMessage(event.content))
 
@@ -121,8 +191,8 @@ class EventHandler:
             ReasoningMessage(event.content, collapsed=tools_collapsed)
         )
 
-    async def _handle_compact_start(self) -> None:
-        compact_msg = CompactMessage()
+    async def _handle_compact_start(self, event: CompactStartEvent) -> None:
+        compact_msg = CompactMessage(background=event.background)
         self.current_compact = compact_msg
         await self.mount_callback(compact_msg)
 
@@ -133,6 +203,128 @@ class EventHandler:
             )
             self.current_compact = None
 
+    async def _handle_model_routed(self, event: ModelRoutedEvent) -> None:
+        await self.mount_callback(
+            NoMarkupStatic(
+                f"Routed to {event.model} ({event.reason})", classes="model-routed"
+            )
+        )
+
+    async def _handle_model_fallback(self, event: ModelFallbackEvent) -> None:
+        await self.mount_callback(
+            WarningMessage(
+                f"{event.from_model} {event.reason}, "
+                f"switched to {event.to_model} for this turn",
+                show_border=False,
+            )
+        )
+
+    async def _handle_api_key_warning(self, event: ApiKeyWarningEvent) -> None:
+        await self.mount_callback(WarningMessage(event.message, show_border=False))
+
+    async def _handle_proxy_auth_failed(self, event: ProxyAuthFailedEvent) -> None:
+        proxy = event.proxy or "from HTTPS_PROXY"
+        await self.mount_callback(
+            WarningMessage(
+                f"The proxy {proxy} rejected the credentials for {event.provider}; "
+                "check network.proxy_username and proxy_password_env_var",
+                show_border=False,
+            )
+        )
+
+    async def _handle_model_list_changed(self, event: ModelListChangedEvent) -> None:
+        await self.mount_callback(
+            NoMarkupStatic(
+                describe_model_list_change(event), classes="model-list-changed"
+            )
+        )
+
+    async def _handle_config_reloaded(self, event: ConfigReloadedEvent) -> None:
+        await self.mount_callback(
+            NoMarkupStatic(describe_config_reload(event), classes="config-reloaded")
+        )
+
+    async def _handle_project_docs_loaded(self, event: ProjectDocsLoadedEvent) -> None:
+        names = [
+            display_path(Path(path), Path.cwd())
+            + (" (truncated)" if path in event.truncated else "")
+            for path in event.paths
+        ]
+        await self.mount_callback(
+            NoMarkupStatic(
+                f"Instructions from {', '.join(names)}", classes="project-docs"
+            )
+        )
+
+    async def _handle_deliverable(self, event: DeliverableEvent) -> None:
+        if self.deliverable_callback is None:
+            return
+        self.deliverable_callback(event)
+        if event.kind == DeliverableKind.COMMIT_MESSAGE:
+            text = "Commit message ready: ctrl+b to edit and commit"
+        else:
+            text = "Command ready: ctrl+b to edit and run"
+        await self.mount_callback(
+            NoMarkupStatic(
+                f"{text}, ctrl+s to save it to a file", classes="deliverable-hint"
+            )
+        )
+
+    async def _handle_local_model_download(
+        self, event: LocalModelDownloadEvent
+    ) -> None:
+        text = describe_download(event.model, event.fraction, event.done)
+        if self.current_download is None:
+            self.current_download = NoMarkupStatic(text, classes="model-list-changed")
+            await self.mount_callback(self.current_download)
+        else:
+            self.current_download.update(text)
+        if event.done:
+            self.current_download = None
+
+    async def _handle_stream_stalled(self, event: StreamStalledEvent) -> None:
+        text = f"Stream stalled for {event.idle_seconds:g}s, reconnecting"
+        if event.resumed:
+            text += " and continuing the reply"
+        elif event.discarded_output:
+            text += "; the reply above is discarded and will be regenerated"
+        await self.mount_callback(WarningMessage(text, show_border=False))
+
+    async def _handle_moderation(self, event: ModerationEvent) -> None:
+        text = describe_verdict(event.action, event.message)
+        await self.mount_callback(WarningMessage(text, show_border=False))
+
+    async def _handle_concurrent_session(self, event: ConcurrentSessionEvent) -> None:
+        text = describe_peers(event.workspace, event.peers)
+        await self.mount_callback(
+            WarningMessage(
+                f"{text} Press Ctrl+R for read-only mode, or use /fork to continue "
+                "in a git worktree."
+            )
+        )
+
+    async def _handle_context_budget_warning(
+        self, event: ContextBudgetWarningEvent
+    ) -> None:
+        await self.mount_callback(
+            WarningMessage(
+                f"Context is {event.utilization:.0%} full ({event.context_tokens:,} "
+                f"of {event.context_window:,} tokens). Run /compact to free space, "
+                "or /context to see what fills it.",
+                show_border=False,
+            )
+        )
+
+    async def _handle_secrets_redacted(self, event: SecretsRedactedEvent) -> None:
+        kinds = ", ".join(sorted(event.redactions))
+        await self.mount_callback(
+            NoMarkupStatic(
+                f"Masked {event.total} secret(s) ({kinds}) in the {event.tool_name} "
+                "output before sending it to the model",
+                classes="secrets-redacted",
+            )
+        )
+
     async def _handle_unknown_event(self, event: BaseEvent) -> None:
         await self.mount_callback(NoMarkupStatic(str(event), classes="unknown-event"))
 
@@ -1,5 +1,7 @@
 from __future__ import annotations
 
+from collections.abc import Awaitable, Callable
+from pathlib import Path
 from typing import ClassVar
 
 from pydantic import BaseModel
@@ -12,7 +14,11 @@ from textual.widgets import Static
 
 from rune.cli.textual_ui.widgets.no_markup_static import NoMarkupStatic
 from rune.cli.textual_ui.widgets.tool_widgets import get_approval_widget
+from rune.core.command_explainer import CommandExplanation
 from rune.core.config import RuneConfig
+from rune.core.critic import CriticReview
+
+type ExplainCallback = Callable[[], Awaitable[CommandExplanation | None]]
 
 
 class ApprovalApp(Container):
@@ -28,6 +34,7 @@ class ApprovalApp(Container):
         Binding("2", "select_2", "Always Tool Session", show=False),
         Binding("3", "select_3", "No", show=False),
         Binding("n", "select_3", "No", show=False),
+        Binding("e", "explain", "Explain", show=False),
     ]
 
     class ApprovalGranted(Message):
@@ -52,12 +59,23 @@ class ApprovalApp(Container):
             self.tool_args = tool_args
 
     def __init__(
-        self, tool_name: str, tool_args: BaseModel, config: RuneConfig
+        self,
+        tool_name: str,
+        tool_args: BaseModel,
+        config: RuneConfig,
+        critic_review: CriticReview | None = None,
+        write_grant: Path | None = None,
+        explain: ExplainCallback | None = None,
     ) -> None:
         super().__init__(id="approval-app")
         self.tool_name = tool_name
         self.tool_args = tool_args
         self.config = config
+        self.critic_review = critic_review
+        self.write_grant = write_grant
+        self.explain = explain
+        self.explanation_requested = False
+        self.explanation_widget: Static | None = None
         self.selected_option = 0
         self.content_container: Vertical | None = None
         self.title_widget: Static | None = None
@@ -73,8 +91,10 @@ class ApprovalApp(Container):
                 self.option_widgets.append(widget)
                 yield widget
             yield NoMarkupStatic("")
+            explain_help = "  E explain" if self.explain else ""
             self.help_widget = NoMarkupStatic(
-                "↑↓ navigate  Enter select  ESC reject", classes="approval-help"
+                f"↑↓ navigate  Enter select{explain_help}  ESC reject",
+                classes="approval-help",
             )
             yield self.help_widget
 
@@ -84,6 +104,24 @@ class ApprovalApp(Container):
             )
             yield self.title_widget
 
+            if self.write_grant:
+                yield NoMarkupStatic(
+                    "Writes outside the workspace. Approving grants write access to "
+                    f"{self.write_grant} for this session (see /permissions).",
+                    classes="approval-write-grant",
+                )
+
+            if review := self.critic_review:
+                yield NoMarkupStatic(
+                    f"Review by {review.model}: {review.verdict.upper()}"
+                    + (f"\n{review.summary}" if review.summary else ""),
+                    classes=f"approval-critic approval-critic-{review.verdict}",
+                )
+
+            self.explanation_widget = NoMarkupStatic("", classes="approval-explanation")
+            self.explanation_widget.display = False
+            yield self.explanation_widget
+
             with VerticalScroll(classes="approval-tool-info-scroll"):
                 self.tool_info_container = Vertical(
                     classes="approval-tool-info-container"
@@ -161,6 +199,35 @@ class ApprovalApp(Container):
         self.selected_option = 2
         self._handle_selection(2)
 
+    def action_explain(self) -> None:
+        if not self.explain or self.explanation_requested:
+            return
+        self.explanation_requested = True
+        if self.explanation_widget:
+            self.explanation_widget.update("Explaining the command…")
+            self.explanation_widget.display = True
+        self.run_worker(self._show_explanation(self.explain), exclusive=True)
+
+    async def _show_explanation(self, explain: ExplainCallback) -> None:
+        explanation = await explain()
+        if not self.explanation_widget:
+            return
+        if explanation is None:
+            self.explanation_widget.update("No explanation available.")
+            return
+        source = (
+            f"Explained by {explanation.model}"
+            if explanation.model
+            else "Offline estimate"
+        )
+        self.explanation_widget.update(
+            f"{source} · risk {explanation.risk_level} "
+            f"({explanation.risk_score}/100)\n{explanation.text}"
+        )
+        self.explanation_widget.add_class(
+            f"approval-explanation-{explanation.risk_level}"
+        )
+
     def action_reject(self) -> None:
         self.selected_option = 2
         self._handle_selection(2)
@@ -13,7 +13,10 @@ class CompletionPopup(Static):
         self.can_focus = False
 
     def update_suggestions(
-        self, suggestions: list[tuple[str, str]], selected: int
+        self,
+        suggestions: list[tuple[str, str]],
+        selected: int,
+        preview: str | None = None,
     ) -> None:
         if not suggestions:
             self.hide()
@@ -32,6 +35,10 @@ class CompletionPopup(Static):
                 text.append("  ")
                 text.append(description, style=description_style)
 
+        if preview:
+            text.append("\n\n")
+            text.append(preview, style="dim")
+
         self.update(text)
         self.show()
 
@@ -1,5 +1,6 @@
 from __future__ import annotations
 
+import asyncio
 from collections.abc import Callable
 from pathlib import Path
 from typing import Any
@@ -19,12 +20,15 @@ from rune.cli.textual_ui.widgets.chat_input.completion_popup import CompletionPo
 from rune.cli.textual_ui.widgets.chat_input.text_area import ChatTextArea
 from rune.core.agents import AgentSafety
 from rune.core.autocompletion.completers import CommandCompleter, PathCompleter
+from rune.core.autocompletion.path_prompt_adapter import build_mention_preview
+from rune.core.autocompletion.symbols import SymbolIndex
 
 SAFETY_BORDER_CLASSES: dict[AgentSafety, str] = {
     AgentSafety.SAFE: "border-safe",
     AgentSafety.DESTRUCTIVE: "border-warning",
     AgentSafety.YOLO: "border-error",
 }
+_PREVIEW_GROUP = "mention-preview"
 
 
 class ChatInputContainer(Vertical):
@@ -55,7 +59,7 @@ class ChatInputContainer(Vertical):
 
         self._completion_manager = MultiCompletionManager([
             SlashCommandController(CommandCompleter(self._get_slash_entries), self),
-            PathCompletionController(PathCompleter(), self),
+            PathCompletionController(PathCompleter(symbol_index=SymbolIndex()), self),
         ])
         self._completion_popup: CompletionPopup | None = None
         self._body: ChatInputBody | None = None
@@ -122,10 +126,33 @@ class ChatInputContainer(Vertical):
     def render_completion_suggestions(
         self, suggestions: list[tuple[str, str]], selected_index: int
     ) -> None:
-        if self._completion_popup:
-            self._completion_popup.update_suggestions(suggestions, selected_index)
+        if not self._completion_popup:
+            return
+
+        self._completion_popup.update_suggestions(suggestions, selected_index)
+        if 0 <= selected_index < len(suggestions):
+            label, _ = suggestions[selected_index]
+            if label.startswith("@"):
+                # Reading the file for the preview stays off the UI thread.
+                self.run_worker(
+                    self._show_mention_preview(suggestions, selected_index, label),
+                    group=_PREVIEW_GROUP,
+                    exclusive=True,
+                )
+
+    async def _show_mention_preview(
+        self, suggestions: list[tuple[str, str]], selected_index: int, label: str
+    ) -> None:
+        preview = await asyncio.to_thread(
+            build_mention_preview, label, base_dir=Path.cwd()
+        )
+        if preview and self._completion_popup:
+            self._completion_popup.update_suggestions(
+                suggestions, selected_index, preview
+            )
 
     def clear_completion_suggestions(self) -> None:
+        self.workers.cancel_group(self, _PREVIEW_GROUP)
         if self._completion_popup:
             self._completion_popup.hide()
 
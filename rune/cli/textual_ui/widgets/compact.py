@@ -12,15 +12,18 @@ class CompactMessage(StatusMessage):
             super().__init__()
             self.compact_widget = compact_widget
 
-    def __init__(self) -> None:
+    def __init__(self, background: bool = False) -> None:
         super().__init__()
         self.add_class("compact-message")
+        self.background = background
         self.old_tokens: int | None = None
         self.new_tokens: int | None = None
         self.error_message: str | None = None
 
     def get_content(self) -> str:
         if self._is_spinning:
+            if self.background:
+                return "Compacting conversation history in the background..."
             return "Compacting conversation history..."
 
         if self.error_message:
@@ -0,0 +1,65 @@
+from __future__ import annotations
+
+from pathlib import Path
+import re
+from typing import ClassVar
+from urllib.parse import quote
+
+from textual import events
+from textual.binding import Binding, BindingType
+from textual.message import Message
+
+from rune.cli.textual_ui.widgets.no_markup_static import NoMarkupStatic
+
+# A matching line under a file heading in grep output; context lines use '-'.
+GREP_MATCH_RE = re.compile(r"^(?P<line>\d+):")
+HUNK_RE = re.compile(r"^@@ -\d+(?:,\d+)? \+(?P<start>\d+)")
+
+
+def build_editor_uri(template: str, path: str, line: int = 1, column: int = 1) -> str:
+    """Fill an editor URI template such as ``vscode://file/{path}:{line}``."""
+    resolved = Path(path).expanduser().resolve().as_posix()
+    return template.format(path=quote(resolved, safe="/:"), line=line, column=column)
+
+
+def find_snippet_line(path: str, snippet: str) -> int | None:
+    """Return the 1-based line where ``snippet`` starts in the file, if found."""
+    if not snippet.strip():
+        return None
+    try:
+        text = Path(path).read_text(encoding="utf-8", errors="ignore")
+    except OSError:
+        return None
+    if (idx := text.find(snippet.strip("\n"))) < 0:
+        return None
+    return text.count("\n", 0, idx) + 1
+
+
+class EditorLink(NoMarkupStatic):
+    """A line of tool output that opens its file location in the user's editor."""
+
+    can_focus = True
+
+    BINDINGS: ClassVar[list[BindingType]] = [
+        Binding("enter", "open", "Open in editor", show=False)
+    ]
+
+    class Selected(Message):
+        def __init__(self, path: str, line: int) -> None:
+            super().__init__()
+            self.path = path
+            self.line = line
+
+    def __init__(
+        self, content: str, path: str, line: int = 1, classes: str | None = None
+    ) -> None:
+        super().__init__(content, classes=classes)
+        self.path = path
+        self.line = line
+        self.add_class("editor-link")
+
+    def action_open(self) -> None:
+        self.post_message(self.Selected(self.path, self.line))
+
+    def on_click(self, event: events.Click) -> None:
+        self.action_open()
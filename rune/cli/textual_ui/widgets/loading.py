@@ -13,18 +13,7 @@ from textual.widgets import Static
 
 from rune.cli.textual_ui.widgets.no_markup_static import NoMarkupStatic
 from rune.cli.textual_ui.widgets.spinner import SpinnerMixin, SpinnerType
-
-
-def _format_elapsed(seconds: int) -> str:
-    if seconds < 60:  # noqa: PLR2004
-        return f"{seconds}s"
-
-    minutes, secs = divmod(seconds, 60)
-    if minutes < 60:  # noqa: PLR2004
-        return f"{minutes}m{secs}s"
-
-    hours, mins = divmod(minutes, 60)
-    return f"{hours}h{mins}m{secs}s"
+from rune.core.utils import format_duration
 
 
 class LoadingWidget(SpinnerMixin, Static):
@@ -186,7 +175,7 @@ class LoadingWidget(SpinnerMixin, Static):
             if elapsed != self._last_elapsed:
                 self._last_elapsed = elapsed
                 self.hint_widget.update(
-                    f"({_format_elapsed(elapsed)} esc to interrupt)"
+                    f"({format_duration(elapsed)} esc to interrupt)"
                 )
 
 
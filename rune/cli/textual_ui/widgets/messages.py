@@ -44,9 +44,15 @@ class UserMessage(Static):
     def compose(self) -> ComposeResult:
         with Horizontal(classes="user-message-container"):
             yield NoMarkupStatic(self._content, classes="user-message-content")
+            yield NoMarkupStatic("", classes="user-message-timing")
             if self._pending:
                 self.add_class("pending")
 
+    def set_timing(self, text: str) -> None:
+        timing = self.query_one(".user-message-timing", NoMarkupStatic)
+        timing.update(text)
+        timing.display = bool(text)
+
     async def set_pending(self, pending: bool) -> None:
         if pending == self._pending:
             return
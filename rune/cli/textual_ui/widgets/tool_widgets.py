@@ -9,6 +9,12 @@ from textual.containers import Vertical
 from textual.widgets import Static
 
 from rune.cli.textual_ui.ansi_markdown import AnsiMarkdown as Markdown
+from rune.cli.textual_ui.widgets.editor_link import (
+    GREP_MATCH_RE,
+    HUNK_RE,
+    EditorLink,
+    find_snippet_line,
+)
 from rune.cli.textual_ui.widgets.no_markup_static import NoMarkupStatic
 from rune.core.tools.builtins.ask_user_question import AskUserQuestionResult
 from rune.core.tools.builtins.bash import BashArgs, BashResult
@@ -32,20 +38,28 @@ def _truncate_lines(content: str, max_lines: int) -> tuple[str, str | None]:
     return "\n".join(lines[:max_lines]), f"… ({remaining} more lines)"
 
 
+def parse_search_replace_blocks(content: str) -> list[tuple[str, list[str]]]:
+    """Parse SEARCH/REPLACE blocks into (replace text, unified diff lines) pairs."""
+    blocks: list[tuple[str, list[str]]] = []
+    for search_text, replace_text in SEARCH_REPLACE_BLOCK_RE.findall(content):
+        search_lines = search_text.strip().split("\n")
+        replace_lines = replace_text.strip().split("\n")
+        diff = difflib.unified_diff(search_lines, replace_lines, lineterm="", n=2)
+        blocks.append((replace_text, list(diff)[2:]))  # Skip file headers
+    return blocks
+
+
 def parse_search_replace_to_diff(content: str) -> list[str]:
     """Parse SEARCH/REPLACE blocks and generate unified diff lines."""
     all_diff_lines: list[str] = []
-    matches = SEARCH_REPLACE_BLOCK_RE.findall(content)
-    if not matches:
+    blocks = parse_search_replace_blocks(content)
+    if not blocks:
         return [content[:500]] if content else []
 
-    for i, (search_text, replace_text) in enumerate(matches):
+    for i, (_, diff_lines) in enumerate(blocks):
         if i > 0:
             all_diff_lines.append("")  # Separator between blocks
-        search_lines = search_text.strip().split("\n")
-        replace_lines = replace_text.strip().split("\n")
-        diff = difflib.unified_diff(search_lines, replace_lines, lineterm="", n=2)
-        all_diff_lines.extend(list(diff)[2:])  # Skip file headers
+        all_diff_lines.extend(diff_lines)
 
     return all_diff_lines
 
@@ -181,7 +195,11 @@ class WriteFileResultWidget(ToolResultWidget[WriteFileResult]):
                 yield Markdown(f"```{ext}\n{content}\n```")
             yield from self._footer(truncation_info)
             return
-        yield NoMarkupStatic(f"Path: {self.result.path}", classes="tool-result-detail")
+        yield EditorLink(
+            f"Path: {self.result.path}",
+            path=self.result.path,
+            classes="tool-result-detail",
+        )
         yield NoMarkupStatic(
             f"Bytes: {self.result.bytes_written}", classes="tool-result-detail"
         )
@@ -212,10 +230,26 @@ class SearchReplaceResultWidget(ToolResultWidget[SearchReplaceResult]):
         for warning in self.warnings:
             yield NoMarkupStatic(f"⚠ {warning}", classes="tool-result-warning")
         if self.result.content:
-            for line in parse_search_replace_to_diff(self.result.content):
-                yield render_diff_line(line)
+            yield from self._render_diff(self.result.file, self.result.content)
         yield from self._footer()
 
+    def _render_diff(self, path: str, content: str) -> ComposeResult:
+        if not (blocks := parse_search_replace_blocks(content)):
+            for line in parse_search_replace_to_diff(content):
+                yield render_diff_line(line)
+            return
+
+        for i, (replace_text, diff_lines) in enumerate(blocks):
+            if i > 0:
+                yield render_diff_line("")
+            offset = find_snippet_line(path, replace_text)
+            for line in diff_lines:
+                if offset is not None and (hunk := HUNK_RE.match(line)):
+                    target = offset + int(hunk["start"]) - 1
+                    yield EditorLink(line, path=path, line=target, classes="diff-range")
+                else:
+                    yield render_diff_line(line)
+
 
 class TodoApprovalWidget(ToolApprovalWidget[TodoArgs]):
     def compose(self) -> ComposeResult:
@@ -270,6 +304,11 @@ class ReadFileApprovalWidget(ToolApprovalWidget[ReadFileArgs]):
             yield NoMarkupStatic(
                 f"limit: {self.args.limit}", classes="approval-description"
             )
+        if self.args.start_line is not None or self.args.end_line is not None:
+            yield NoMarkupStatic(
+                f"lines: {self.args.start_line or 1}-{self.args.end_line or 'end'}",
+                classes="approval-description",
+            )
 
 
 class ReadFileResultWidget(ToolResultWidget[ReadFileResult]):
@@ -278,8 +317,10 @@ class ReadFileResultWidget(ToolResultWidget[ReadFileResult]):
             yield from self._footer()
             return
         if self.result:
-            yield NoMarkupStatic(
-                f"Path: {self.result.path}", classes="tool-result-detail"
+            yield EditorLink(
+                f"Path: {self.result.path}",
+                path=self.result.path,
+                classes="tool-result-detail",
             )
         for warning in self.warnings:
             yield NoMarkupStatic(f"⚠ {warning}", classes="tool-result-warning")
@@ -298,6 +339,13 @@ class GrepApprovalWidget(ToolApprovalWidget[GrepArgs]):
             f"pattern: {self.args.pattern}", classes="approval-description"
         )
         yield NoMarkupStatic(f"path: {self.args.path}", classes="approval-description")
+        if self.args.literal:
+            yield NoMarkupStatic("literal: true", classes="approval-description")
+        if self.args.context_lines:
+            yield NoMarkupStatic(
+                f"context_lines: {self.args.context_lines}",
+                classes="approval-description",
+            )
         if self.args.max_matches is not None:
             yield NoMarkupStatic(
                 f"max_matches: {self.args.max_matches}", classes="approval-description"
@@ -316,7 +364,21 @@ class GrepResultWidget(ToolResultWidget[GrepResult]):
             content, truncation_info = _truncate_lines(self.result.matches, max_lines)
         else:
             content, truncation_info = self.result.matches, None
-        yield NoMarkupStatic(content, classes="tool-result-detail")
+        path: str | None = None
+        for line in content.split("\n"):
+            if path and (location := GREP_MATCH_RE.match(line)):
+                yield EditorLink(
+                    line,
+                    path=path,
+                    line=int(location["line"]),
+                    classes="tool-result-detail",
+                )
+                continue
+            if not line:
+                path = None
+            elif path is None:
+                path = line
+            yield NoMarkupStatic(line, classes="tool-result-detail")
         yield from self._footer(truncation_info)
 
 
@@ -11,6 +11,10 @@ from rune.cli.textual_ui.widgets.tool_widgets import get_result_widget
 from rune.core.tools.ui import ToolUIDataAdapter
 from rune.core.types import ToolCallEvent, ToolResultEvent
 
+# Lines of a running command's output shown under the tool call.
+OUTPUT_TAIL_LINES = 8
+_OUTPUT_TAIL_CHARS = 4_000
+
 
 class ToolCallMessage(StatusMessage):
     def __init__(
@@ -23,6 +27,7 @@ class ToolCallMessage(StatusMessage):
         self._tool_name = tool_name or (event.tool_name if event else "unknown")
         self._is_history = event is None
         self._stream_widget: NoMarkupStatic | None = None
+        self._output = ""
 
         super().__init__()
         self.add_class("tool-call")
@@ -64,6 +69,14 @@ class ToolCallMessage(StatusMessage):
             self._stream_widget.update(f"→ {message}")
             self._stream_widget.display = True
 
+    def append_output(self, text: str) -> None:
+        """Add to a running command's output and show its last lines."""
+        self._output = (self._output + text)[-_OUTPUT_TAIL_CHARS:]
+        lines = self._output.rstrip("\n").splitlines()[-OUTPUT_TAIL_LINES:]
+        if self._stream_widget and lines:
+            self._stream_widget.update("\n".join(lines))
+            self._stream_widget.display = True
+
     def stop_spinning(self, success: bool = True) -> None:
         """Stop the spinner and hide the stream widget."""
         if self._stream_widget:
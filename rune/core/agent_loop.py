@@ -1,65 +1,154 @@
 from __future__ import annotations
 
 import asyncio
-from collections.abc import AsyncGenerator, Callable
+from collections import Counter
+from collections.abc import AsyncGenerator, Callable, Iterator
+from contextlib import AbstractContextManager
 from enum import StrEnum, auto
+from functools import partial
 from http import HTTPStatus
+from pathlib import Path
 from threading import Thread
 import time
-from typing import TYPE_CHECKING, cast
+from typing import TYPE_CHECKING, Any, cast
 from uuid import uuid4
 
+import anyio
 from pydantic import BaseModel
 
 from rune.core.agents.manager import AgentManager
-from rune.core.agents.models import AgentProfile, BuiltinAgentName
-from rune.core.config import RuneConfig
+from rune.core.agents.models import READ_ONLY_TOOLS, AgentProfile, BuiltinAgentName
+from rune.core.approval_audit import (
+    POLICY_RULE_PREFIX,
+    ApprovalAudit,
+    AuditEventKind,
+    AuditRecord,
+    command_hash,
+)
+from rune.core.auth.accounts import AccountError
+from rune.core.auth.key_status import key_status, record_key_result
+from rune.core.checkpoints import CheckpointManager, resolve_edit_path
+from rune.core.command_explainer import CommandExplainer
+from rune.core.compaction import (
+    BACKGROUND_COMPACTION_OVERSHOOT,
+    BACKGROUND_COMPACTION_WINDOW_SHARE,
+    BackgroundCompaction,
+    split_recent_turns,
+    supersede_tool_outputs,
+    truncate_oldest,
+)
+from rune.core.config import (
+    Backend,
+    CompactionStrategy,
+    ModelConfig,
+    ProviderConfig,
+    RuneConfig,
+)
+from rune.core.config_reload import ConfigReloader
+from rune.core.context_hooks import ContextHookMiddleware
+from rune.core.context_snapshots import ContextSnapshotStore
+from rune.core.critic import PatchCritic
+from rune.core.deliverables import detect_deliverable
 from rune.core.llm.backend.factory import BACKEND_FACTORY
-from rune.core.llm.exceptions import BackendError
+from rune.core.llm.backend.generic import supports_output_schema, supports_prefill
+from rune.core.llm.exceptions import BackendError, fallback_reason
 from rune.core.llm.format import APIToolFormatHandler, ResolvedMessage, ResolvedToolCall
+from rune.core.llm.stream_watchdog import StallRecovery, StreamStalledError, watch_idle
 from rune.core.llm.types import BackendLike
+from rune.core.lmstudio import DownloadProgress, LMStudioClient, LMStudioError
 from rune.core.middleware import (
+    DRY_RUN_REMINDER,
+    READ_ONLY_REMINDER,
     AutoCompactMiddleware,
     ContextWarningMiddleware,
     ConversationContext,
     MiddlewareAction,
     MiddlewarePipeline,
     MiddlewareResult,
+    ModeReminderMiddleware,
     PlanAgentMiddleware,
     PriceLimitMiddleware,
     ResetReason,
     TurnLimitMiddleware,
 )
+from rune.core.model_catalog import ModelSourceWatcher, merge_model_list
+from rune.core.moderation import ModerationHook, describe_verdict
+from rune.core.network import configured_proxy, proxy_auth_rejected
+from rune.core.paths.config_paths import use_project_dir
+from rune.core.project_docs import load_project_docs
 from rune.core.prompts import UtilityPrompt
+from rune.core.redaction import Redactor, reads_env_file
+from rune.core.rewind import Rewind
+from rune.core.router import ModelRouter
 from rune.core.session.session_logger import SessionLogger
 from rune.core.session.session_migration import migrate_sessions_entrypoint
+from rune.core.session.session_retention import session_housekeeping_entrypoint
+from rune.core.session.session_title import SessionTitler
 from rune.core.skills.manager import SkillManager
-from rune.core.system_prompt import get_universal_system_prompt
+from rune.core.status import ContextUsageStatus
+from rune.core.system_prompt import get_system_message
+from rune.core.tokenizers import (
+    ByteHeuristicTokenizer,
+    Tokenizer,
+    count_message_tokens,
+    load_tokenizer,
+)
 from rune.core.tools.base import (
     BaseTool,
     BaseToolConfig,
+    ImageResult,
     InvokeContext,
+    StopRequests,
     ToolError,
     ToolPermission,
     ToolPermissionError,
 )
+from rune.core.tools.cache import ToolCallCache
+from rune.core.tools.dry_run import dry_run_report
+from rune.core.tools.images import fit_image
 from rune.core.tools.manager import ToolManager
+from rune.core.tools.policy import (
+    TOOL_POLICY_KEY,
+    ToolRuntimeExceededError,
+    limit_runtime,
+    truncate_output,
+)
+from rune.core.tools.write_roots import WriteRootGrants
 from rune.core.types import (
     AgentStats,
+    ApiKeyWarningEvent,
     ApprovalCallback,
     ApprovalResponse,
     AssistantEvent,
     AsyncApprovalCallback,
+    AvailableTool,
     BaseEvent,
     CompactEndEvent,
     CompactStartEvent,
+    ConfigReloadedEvent,
+    ContextBudgetWarningEvent,
+    DeliverableEvent,
+    ImageContent,
     LLMChunk,
     LLMMessage,
     LLMUsage,
+    LocalModelDownloadEvent,
+    ModelFallbackEvent,
+    ModelListChangedEvent,
+    ModelRoutedEvent,
+    ModerationAction,
+    ModerationEvent,
+    OutputSchemaUnsupportedEvent,
+    ProjectDocsLoadedEvent,
+    ProxyAuthFailedEvent,
     RateLimitError,
     ReasoningEvent,
     Role,
+    SecretsRedactedEvent,
+    StreamStalledEvent,
+    StrToolChoice,
     SyncApprovalCallback,
+    TokenCountEvent,
     ToolCallEvent,
     ToolResultEvent,
     ToolStreamEvent,
@@ -73,7 +162,11 @@ from rune.core.utils import (
     get_user_agent,
     get_user_cancellation_message,
     is_user_cancellation_event,
+    logger,
+    utc_now,
 )
+from rune.core.workspace import Workspace
+from rune.core.workspace_lease import ThreadLease
 
 try:
     from rune.core.teleport.teleport import TeleportService as _TeleportService
@@ -89,6 +182,11 @@ if TYPE_CHECKING:
     from rune.core.teleport.types import TeleportPushResponseEvent, TeleportYieldEvent
 
 
+# Images kept in the history; older ones are dropped so screenshots from a
+# long browser session don't fill the context.
+MAX_CONTEXT_IMAGES = 3
+
+
 class ToolExecutionResponse(StrEnum):
     SKIP = auto()
     EXECUTE = auto()
@@ -97,6 +195,8 @@ class ToolExecutionResponse(StrEnum):
 class ToolDecision(BaseModel):
     verdict: ToolExecutionResponse
     feedback: str | None = None
+    # Id of the rule or mode that decided the call, None when a person did.
+    rule: str | None = None
 
 
 class AgentLoopError(Exception):
@@ -119,6 +219,31 @@ def _should_raise_rate_limit_error(e: Exception) -> bool:
     return isinstance(e, BackendError) and e.status == HTTPStatus.TOO_MANY_REQUESTS
 
 
+def _key_rejected(e: BaseException) -> bool:
+    current: BaseException | None = e
+    while current is not None:
+        if isinstance(current, BackendError):
+            return current.status in {HTTPStatus.UNAUTHORIZED, HTTPStatus.FORBIDDEN}
+        current = current.__cause__
+    return False
+
+
+# Events about how the turn runs rather than what the model said, shown right
+# away even while a response is held back for moderation.
+_NOTICE_EVENTS = (
+    ModelFallbackEvent,
+    OutputSchemaUnsupportedEvent,
+    ProxyAuthFailedEvent,
+    StreamStalledEvent,
+    TokenCountEvent,
+)
+
+
+def _honors_output_schema(provider: ProviderConfig) -> bool:
+    # Ollama and llama.cpp constrain decoding to the schema themselves.
+    return provider.backend != Backend.GENERIC or supports_output_schema(provider)
+
+
 class AgentLoop:
     def __init__(
         self,
@@ -129,20 +254,40 @@ class AgentLoop:
         max_price: float | None = None,
         backend: BackendLike | None = None,
         enable_streaming: bool = False,
+        cwd: Path | None = None,
     ) -> None:
         self._base_config = config
         self._max_turns = max_turns
         self._max_price = max_price
-
-        self.agent_manager = AgentManager(
-            lambda: self._base_config, initial_agent=agent_name
-        )
-        self.tool_manager = ToolManager(lambda: self.config)
-        self.skill_manager = SkillManager(lambda: self.config)
+        # Tools, prompts and project settings work from `cwd` rather than the
+        # process's directory, which ACP sessions share.
+        self.write_roots = WriteRootGrants(lambda: self.workspace.active.path)
+        self.workspace = Workspace(cwd or Path.cwd(), self.write_roots)
+        self._project_dir = self.workspace.active.path
+
+        with self._in_project():
+            self.agent_manager = AgentManager(
+                lambda: self._base_config, initial_agent=agent_name
+            )
+            self.tool_manager = ToolManager(lambda: self.config)
+            self.skill_manager = SkillManager(lambda: self.config)
         self.format_handler = APIToolFormatHandler()
 
         self.backend_factory = lambda: backend or self._select_backend()
         self.backend = self.backend_factory()
+        self._injected_backend = backend
+        self._routed_backends: dict[str, BackendLike] = {}
+        self.router = ModelRouter(lambda: self.config)
+        self._turn_model: ModelConfig | None = None
+        # JSON schema the final reply of the current turn must follow.
+        self._output_schema: dict[str, Any] | None = None
+        self._output_schema_unsupported: set[str] = set()
+        # Set while the final reply is asked for under the schema, without tools.
+        self._schema_step = False
+        # The model the router last sent a turn to, shown in the status.
+        self.last_routed_model: str | None = None
+        self.read_only = False
+        self.dry_run = False
 
         self.message_observer = message_observer
         self._last_observed_message_index: int = 0
@@ -150,29 +295,48 @@ class AgentLoop:
         self.middleware_pipeline = MiddlewarePipeline()
         self._setup_middleware()
 
-        system_prompt = get_universal_system_prompt(
-            self.tool_manager, self.config, self.skill_manager, self.agent_manager
-        )
-        self.messages = [LLMMessage(role=Role.system, content=system_prompt)]
+        self.messages = [self._system_message()]
 
         if self.message_observer:
             self.message_observer(self.messages[0])
             self._last_observed_message_index = 1
 
         self.stats = AgentStats()
-        try:
-            active_model = config.get_active_model()
-            self.stats.input_price_per_million = active_model.input_price
-            self.stats.output_price_per_million = active_model.output_price
-        except ValueError:
-            pass
+        self._refresh_pricing()
+        with self._in_project():
+            self.model_sources = ModelSourceWatcher()
+            self.config_reloader = ConfigReloader()
 
         self.approval_callback: ApprovalCallback | None = None
         self.user_input_callback: UserInputCallback | None = None
+        self.critic = PatchCritic(lambda: self.config)
+        self.explainer = CommandExplainer(lambda: self.config)
+        self.audit = ApprovalAudit(lambda: self.config)
+        self.checkpoints = CheckpointManager()
+        self.context_snapshots = ContextSnapshotStore()
+        self.rewind = Rewind(self)
+        self.moderation = ModerationHook(lambda: self.config)
+        self.lease = ThreadLease()
+        self.write_grant_requests: dict[str, Path] = {}
+        self.tool_stops = StopRequests()
+        self.tool_cache = ToolCallCache(lambda: self.config.tool_cache)
+        self.rate_limited_at: str | None = None
+        # (provider, model) pairs of local models known to be downloaded and
+        # loaded, so LM Studio is only asked once per session.
+        self._ready_local_models: set[tuple[str, str]] = set()
+        # By `tokenizer` setting; loading one may download it, so it's done off
+        # the event loop before the model's first step.
+        self._tokenizers: dict[str | None, Tokenizer] = {}
+        self._background_compaction: BackgroundCompaction | None = None
 
         self.session_id = str(uuid4())
 
-        self.session_logger = SessionLogger(config.session_logging, self.session_id)
+        self.session_logger = SessionLogger(
+            config.session_logging, self.session_id, self._project_dir
+        )
+        self.titler = SessionTitler(lambda: self.config)
+        self._titled_session: str | None = None
+        self._title_task: asyncio.Task[None] | None = None
         self._teleport_service: TeleportService | None = None
 
         thread = Thread(
@@ -182,6 +346,18 @@ class AgentLoop:
             name="migrate_sessions",
         )
         thread.start()
+        housekeeping_thread = Thread(
+            target=session_housekeeping_entrypoint,
+            args=(
+                config.session_logging,
+                config.session_retention,
+                config.session_sync,
+                config.network,
+            ),
+            daemon=True,
+            name="session_housekeeping",
+        )
+        housekeeping_thread.start()
 
     @property
     def agent_profile(self) -> AgentProfile:
@@ -195,19 +371,40 @@ class AgentLoop:
     def auto_approve(self) -> bool:
         return self.config.auto_approve
 
-    def set_tool_permission(
+    async def set_tool_permission(
         self, tool_name: str, permission: ToolPermission, save_permanently: bool = False
     ) -> None:
         if save_permanently:
-            RuneConfig.save_updates({
-                "tools": {tool_name: {"permission": permission.value}}
-            })
+            with self._in_project():
+                RuneConfig.save_updates({
+                    "tools": {tool_name: {"permission": permission.value}}
+                })
 
         if tool_name not in self.config.tools:
             self.config.tools[tool_name] = BaseToolConfig()
 
         self.config.tools[tool_name].permission = permission
-        self.tool_manager.invalidate_tool(tool_name)
+        await self.tool_manager.invalidate_tool(tool_name)
+
+    def _in_project(self) -> AbstractContextManager[None]:
+        return use_project_dir(self._project_dir)
+
+    def _system_message(self) -> LLMMessage:
+        with self._in_project():
+            return get_system_message(
+                self.tool_manager,
+                self.config,
+                self.skill_manager,
+                self.agent_manager,
+                self.workspace.active.path,
+            )
+
+    def _refresh_pricing(self) -> None:
+        try:
+            pricing = self.config.get_model_pricing(self.config.get_active_model())
+        except ValueError:
+            return
+        self.stats.update_pricing(pricing.input, pricing.output)
 
     def _select_backend(self) -> BackendLike:
         active_model = self.config.get_active_model()
@@ -215,6 +412,23 @@ class AgentLoop:
         timeout = self.config.api_timeout
         return BACKEND_FACTORY[provider.backend](provider=provider, timeout=timeout)
 
+    def _current_model(self) -> ModelConfig:
+        return self._turn_model or self.config.get_active_model()
+
+    def _backend_for(self, provider: ProviderConfig) -> BackendLike:
+        if self._turn_model is None or self._injected_backend is not None:
+            return self.backend
+        active_provider = self.config.get_provider_for_model(
+            self.config.get_active_model()
+        )
+        if provider.name == active_provider.name:
+            return self.backend
+        if provider.name not in self._routed_backends:
+            self._routed_backends[provider.name] = BACKEND_FACTORY[provider.backend](
+                provider=provider, timeout=self.config.api_timeout
+            )
+        return self._routed_backends[provider.name]
+
     def add_message(self, message: LLMMessage) -> None:
         self.messages.append(message)
 
@@ -240,10 +454,24 @@ class AgentLoop:
             self.message_observer(msg)
         self._last_observed_message_index = len(self.messages)
 
-    async def act(self, msg: str) -> AsyncGenerator[BaseEvent]:
+    async def act(
+        self, msg: str, *, output_schema: dict[str, Any] | None = None
+    ) -> AsyncGenerator[BaseEvent]:
         self._clean_message_history()
-        async for event in self._conversation_loop(msg):
-            yield event
+        self.stats.last_turn_model_time = 0.0
+        self.stats.last_turn_tool_time = 0.0
+        self._output_schema = output_schema
+        self._output_schema_unsupported.clear()
+        started = time.perf_counter()
+        try:
+            async for event in self._conversation_loop(msg):
+                yield event
+            self._schedule_auto_title()
+        finally:
+            self._output_schema = None
+            wall_time = time.perf_counter() - started
+            self.stats.last_turn_wall_time = wall_time
+            self.stats.session_wall_time += wall_time
 
     @property
     def teleport_service(self) -> TeleportService:
@@ -261,6 +489,7 @@ class AgentLoop:
                 nuage_base_url=self.config.nuage_base_url,
                 nuage_workflow_id=self.config.nuage_workflow_id,
                 nuage_api_key=self.config.nuage_api_key,
+                workdir=self.workspace.active.path,
             )
         return self._teleport_service
 
@@ -313,12 +542,35 @@ class AgentLoop:
             self.middleware_pipeline.add(
                 AutoCompactMiddleware(self.config.auto_compact_threshold)
             )
-            if self.config.context_warnings:
-                self.middleware_pipeline.add(
-                    ContextWarningMiddleware(0.5, self.config.auto_compact_threshold)
+        if self.config.context_warning_threshold:
+            self.middleware_pipeline.add(
+                ContextWarningMiddleware(
+                    self.config.context_warning_threshold,
+                    lambda: self._effective_context_window,
+                    warn_model=self.config.context_warnings,
                 )
+            )
 
         self.middleware_pipeline.add(PlanAgentMiddleware(lambda: self.agent_profile))
+        self.middleware_pipeline.add(
+            ModeReminderMiddleware(lambda: self.read_only, READ_ONLY_REMINDER)
+        )
+        self.middleware_pipeline.add(
+            ModeReminderMiddleware(lambda: self.dry_run, DRY_RUN_REMINDER)
+        )
+        self.middleware_pipeline.add(
+            ModeReminderMiddleware(
+                lambda: self.workspace.is_multi_root,
+                lambda: self.workspace.describe(),
+            )
+        )
+        self.middleware_pipeline.add(
+            ContextHookMiddleware(
+                lambda: self.config.context_hooks.pre_turn,
+                lambda: self.session_id,
+                lambda: self.workspace.active.path,
+            )
+        )
 
     async def _handle_middleware_result(
         self, result: MiddlewareResult
@@ -337,6 +589,21 @@ class AgentLoop:
                         last_msg.content += f"\n\n{result.message}"
                     else:
                         last_msg.content = result.message
+                if context_message := result.metadata.get("context_message"):
+                    self.messages.append(
+                        LLMMessage(role=Role.user, content=context_message)
+                    )
+                if warning := result.metadata.get("context_warning"):
+                    yield ContextBudgetWarningEvent(**warning)
+
+            case MiddlewareAction.COMPACT if self._compacts_in_background:
+                async for event in self._compact_in_background(
+                    result.metadata.get("old_tokens", self.stats.context_tokens),
+                    result.metadata.get(
+                        "threshold", self.config.auto_compact_threshold
+                    ),
+                ):
+                    yield event
 
             case MiddlewareAction.COMPACT:
                 old_tokens = result.metadata.get(
@@ -365,20 +632,57 @@ class AgentLoop:
             case MiddlewareAction.CONTINUE:
                 pass
 
+    def _project_docs_loaded(self) -> ProjectDocsLoadedEvent | None:
+        if not self.config.include_project_context:
+            return None
+        docs = load_project_docs(
+            self.workspace.active.path,
+            self.config.project_context.max_doc_bytes,
+            self.config.project_context.max_total_doc_bytes,
+        )
+        if not docs:
+            return None
+        return ProjectDocsLoadedEvent(
+            paths=[str(doc.path) for doc in docs],
+            truncated=[str(doc.path) for doc in docs if doc.truncated],
+        )
+
     def _get_context(self) -> ConversationContext:
         return ConversationContext(
             messages=self.messages, stats=self.stats, config=self.config
         )
 
     async def _conversation_loop(self, user_msg: str) -> AsyncGenerator[BaseEvent]:
+        if models_changed := await self._refresh_models():
+            yield models_changed
+        if config_reloaded := await self._refresh_config():
+            yield config_reloaded
+        async for download in self._ensure_local_model_ready():
+            yield download
+        async for event in self._splice_background_compaction():
+            yield event
+        if len(self.messages) == 1:
+            if docs_loaded := self._project_docs_loaded():
+                yield docs_loaded
+            if key_warning := self._check_api_key_status():
+                yield key_warning
+
         user_message = LLMMessage(role=Role.user, content=user_msg)
         self.messages.append(user_message)
         self.stats.steps += 1
+        # The user may have changed files since the cached calls ran.
+        self.tool_cache.clear()
 
         if user_message.message_id is None:
             raise AgentLoopError("User message must have a message_id")
 
         yield UserMessageEvent(content=user_msg, message_id=user_message.message_id)
+        if self.config.detect_concurrent_sessions and (
+            concurrent := await self.lease.check(
+                self.workspace.active.path, self.session_id, read_only=self.read_only
+            )
+        ):
+            yield concurrent
 
         try:
             should_break_loop = False
@@ -393,6 +697,7 @@ class AgentLoop:
                     return
 
                 self.stats.steps += 1
+                self.tool_cache.next_turn()
                 user_cancelled = False
                 async for event in self._perform_llm_turn():
                     if is_user_cancellation_event(event):
@@ -419,13 +724,47 @@ class AgentLoop:
             await self._flush_new_messages()
 
     async def _perform_llm_turn(self) -> AsyncGenerator[BaseEvent, None]:
-        if self.enable_streaming:
-            async for event in self._stream_assistant_events():
-                yield event
-        else:
-            assistant_event = await self._get_assistant_event()
-            if assistant_event.content:
-                yield assistant_event
+        if routed := self.router.route(self.messages, self.stats, self._max_price):
+            self._turn_model, decision = routed
+            logger.info("Routing turn to %s: %s", decision.model, decision.reason)
+            if decision.model != self.last_routed_model:
+                self.last_routed_model = decision.model
+                yield ModelRoutedEvent(model=decision.model, reason=decision.reason)
+        await self._load_tokenizer()
+
+        # With a moderation hook, output is held back until the hook has seen it.
+        moderated = self.moderation.enabled
+        # A schema also holds it back: a reply that ends the turn is replaced.
+        structured = self._output_schema is not None and _honors_output_schema(
+            self.config.get_provider_for_model(self._current_model())
+        )
+        held_events: list[BaseEvent] = []
+        try:
+            async for event in self._assistant_events_with_fallback():
+                if (moderated or structured) and not isinstance(event, _NOTICE_EVENTS):
+                    held_events.append(event)
+                else:
+                    yield event
+            if structured and not self.messages[-1].tool_calls:
+                held_events = [event async for event in self._structured_reply_events()]
+        finally:
+            self._turn_model = None
+
+        annotation: ModerationEvent | None = None
+        if moderated:
+            verdict = await self.moderation.check(self.messages[-1], self.session_id)
+            if verdict.action == ModerationAction.BLOCK:
+                self._block_last_response(verdict.message)
+                yield ModerationEvent(action=verdict.action, message=verdict.message)
+                return
+            if verdict.action == ModerationAction.ANNOTATE:
+                annotation = ModerationEvent(
+                    action=verdict.action, message=verdict.message
+                )
+        for event in held_events:
+            yield event
+        if annotation is not None:
+            yield annotation
 
         last_message = self.messages[-1]
 
@@ -433,14 +772,192 @@ class AgentLoop:
         resolved = self.format_handler.resolve_tool_calls(parsed, self.tool_manager)
 
         if not resolved.tool_calls and not resolved.failed_calls:
+            if deliverable := self._detect_deliverable(last_message):
+                yield deliverable
             return
 
         async for event in self._handle_tool_calls(resolved):
             yield event
 
+    async def _structured_reply_events(self) -> AsyncGenerator[BaseEvent]:
+        """Ask again for the reply that ended the turn, this time constrained to
+        the output schema.
+
+        Constrained decoding leaves the model no way to call a tool, so the
+        schema is only sent for this last step, and without tools.
+        """
+        self.messages.pop()
+        self._schema_step = True
+        try:
+            async for event in self._assistant_events_with_fallback():
+                yield event
+        finally:
+            self._schema_step = False
+
+    @property
+    def _effective_context_window(self) -> int | None:
+        """Tokens the thread can use before it is compacted or overflows the
+        model's context window.
+        """
+        limits = (
+            self._current_model().context_window,
+            self.config.auto_compact_threshold,
+        )
+        return min((limit for limit in limits if limit), default=None)
+
+    def context_usage(self) -> ContextUsageStatus:
+        by_role: dict[Role, list[LLMMessage]] = {}
+        for message in self.messages:
+            by_role.setdefault(message.role, []).append(message)
+        instructions = by_role.pop(Role.system, [])
+        tool_outputs = by_role.pop(Role.tool, [])
+        history = [message for messages in by_role.values() for message in messages]
+        return ContextUsageStatus(
+            instructions=self._estimate_tokens(instructions),
+            history=self._estimate_tokens(history),
+            tool_outputs=self._estimate_tokens(tool_outputs),
+            reported_tokens=self.stats.context_tokens,
+            context_window=self._effective_context_window,
+            warning_threshold=self.config.context_warning_threshold,
+        )
+
+    def _detect_deliverable(self, reply: LLMMessage) -> DeliverableEvent | None:
+        if not self.config.detect_deliverables or reply.role != Role.assistant:
+            return None
+        request = next(
+            (msg for msg in reversed(self.messages) if msg.role == Role.user), None
+        )
+        if request is None:
+            return None
+        deliverable = detect_deliverable(request.content or "", reply.content or "")
+        if deliverable is not None:
+            deliverable.message_id = reply.message_id
+        return deliverable
+
+    async def _assistant_events_with_fallback(self) -> AsyncGenerator[BaseEvent]:
+        """Run the completion, moving down `model_fallbacks` while the current
+        model fails in a way another model may not, and nothing was shown yet.
+        """
+        fallbacks = iter(self.config.model_fallbacks)
+        while True:
+            if (unsupported := self._check_output_schema()) is not None:
+                yield unsupported
+            produced = False
+            try:
+                if self.enable_streaming:
+                    async for event in self._stream_assistant_events():
+                        produced = produced or not isinstance(
+                            event, StreamStalledEvent | TokenCountEvent
+                        )
+                        yield event
+                else:
+                    assistant_event = await self._get_assistant_event()
+                    if assistant_event.content:
+                        yield assistant_event
+                await self._record_key_result(valid=True)
+                return
+            except Exception as e:
+                failed = self._current_model()
+                if _key_rejected(e):
+                    await self._record_key_result(valid=False)
+                if proxy_auth_rejected(e):
+                    # Every provider behind the same proxy would fail alike.
+                    yield self._proxy_auth_failed(failed)
+                    raise
+                reason = fallback_reason(e)
+                if produced or reason is None:
+                    raise
+                if (fallback := self._next_fallback(fallbacks, failed)) is None:
+                    raise
+                logger.warning(
+                    "%s failed (%s), retrying the turn on %s",
+                    failed.alias,
+                    reason,
+                    fallback.alias,
+                )
+                self._turn_model = fallback
+                yield ModelFallbackEvent(
+                    from_model=failed.alias, to_model=fallback.alias, reason=reason
+                )
+
+    def _check_api_key_status(self) -> ApiKeyWarningEvent | None:
+        provider = self.config.get_provider_for_model(self._current_model())
+        status = key_status(provider, rotation_days=self.config.api_key_rotation_days)
+        if not status.needs_attention:
+            return None
+        logger.warning("API key of %s: %s", provider.name, status.message)
+        return ApiKeyWarningEvent(
+            provider=provider.name,
+            account=status.account,
+            state=status.state,
+            message=status.message,
+        )
+
+    async def _record_key_result(self, *, valid: bool) -> None:
+        provider = self.config.get_provider_for_model(self._current_model())
+        try:
+            # Reads and may rewrite auth.json.
+            await asyncio.to_thread(record_key_result, provider, valid=valid)
+        except (OSError, AccountError) as e:
+            logger.warning("Could not record the key check of %s: %s", provider.name, e)
+
+    def _proxy_auth_failed(self, model: ModelConfig) -> ProxyAuthFailedEvent:
+        provider = self.config.get_provider_for_model(model)
+        proxy = configured_proxy(provider.network, provider.http_proxy)
+        logger.warning(
+            "The proxy (%s) rejected the credentials for %s",
+            proxy or "HTTPS_PROXY",
+            provider.name,
+        )
+        return ProxyAuthFailedEvent(provider=provider.name, proxy=proxy)
+
+    def _check_output_schema(self) -> OutputSchemaUnsupportedEvent | None:
+        """Report, once per model and turn, that the output schema won't be
+        enforced.
+        """
+        if self._output_schema is None:
+            return None
+        model = self._current_model()
+        provider = self.config.get_provider_for_model(model)
+        if model.alias in self._output_schema_unsupported or _honors_output_schema(
+            provider
+        ):
+            return None
+        self._output_schema_unsupported.add(model.alias)
+        logger.warning(
+            "%s can't enforce the requested output schema on %s",
+            provider.name,
+            model.alias,
+        )
+        return OutputSchemaUnsupportedEvent(model=model.alias, provider=provider.name)
+
+    def _next_fallback(
+        self, fallbacks: Iterator[str], failed: ModelConfig
+    ) -> ModelConfig | None:
+        for ref in fallbacks:
+            try:
+                model = self.config.resolve_model(ref)
+                self.config.get_provider_for_model(model)
+            except ValueError as e:
+                logger.warning("Skipping model fallback '%s': %s", ref, e)
+                continue
+            if (model.provider, model.name) != (failed.provider, failed.name):
+                return model
+        return None
+
+    def _block_last_response(self, reason: str) -> None:
+        blocked = self.messages[-1]
+        self.messages[-1] = LLMMessage(
+            role=Role.assistant,
+            content=f"[{describe_verdict(ModerationAction.BLOCK, reason)}]",
+            message_id=blocked.message_id,
+        )
+
     async def _stream_assistant_events(
         self,
-    ) -> AsyncGenerator[AssistantEvent | ReasoningEvent]:
+    ) -> AsyncGenerator[
+        AssistantEvent | ReasoningEvent | StreamStalledEvent | TokenCountEvent
+    ]:
         content_buffer = ""
         reasoning_buffer = ""
         chunks_with_content = 0
@@ -449,6 +966,23 @@ class AgentLoop:
         BATCH_SIZE = 5
 
         async for chunk in self._chat_streaming():
+            if isinstance(chunk, TokenCountEvent):
+                yield chunk
+                continue
+            if isinstance(chunk, StreamStalledEvent):
+                if reasoning_buffer:
+                    yield ReasoningEvent(
+                        content=reasoning_buffer, message_id=message_id
+                    )
+                if content_buffer:
+                    yield AssistantEvent(content=content_buffer, message_id=message_id)
+                reasoning_buffer = content_buffer = ""
+                chunks_with_reasoning = chunks_with_content = 0
+                if chunk.discarded_output:
+                    message_id = None
+                yield chunk
+                continue
+
             if message_id is None:
                 message_id = chunk.message.message_id
 
@@ -518,6 +1052,14 @@ class AgentLoop:
             )
 
         for tool_call in resolved.tool_calls:
+            if self.workspace.is_multi_root:
+                tool_call = tool_call.model_copy(
+                    update={
+                        "validated_args": self.workspace.expand_args(
+                            tool_call.validated_args
+                        )
+                    }
+                )
             yield ToolCallEvent(
                 tool_name=tool_call.tool_name,
                 tool_class=tool_call.tool_class,
@@ -535,12 +1077,18 @@ class AgentLoop:
                     error=error_msg,
                     tool_call_id=tool_call.call_id,
                 )
-                self._append_tool_response(tool_call, error_msg)
+                await self._append_tool_response(tool_call, error_msg)
                 continue
 
             decision = await self._should_execute_tool(
                 tool_instance, tool_call.validated_args, tool_call.call_id
             )
+            self._audit_decision(
+                tool_call.tool_name,
+                tool_call.validated_args,
+                tool_call.call_id,
+                decision,
+            )
 
             if decision.verdict == ToolExecutionResponse.SKIP:
                 self.stats.tool_calls_rejected += 1
@@ -557,38 +1105,74 @@ class AgentLoop:
                     skip_reason=skip_reason,
                     tool_call_id=tool_call.call_id,
                 )
-                self._append_tool_response(tool_call, skip_reason)
+                await self._append_tool_response(tool_call, skip_reason)
                 continue
 
             self.stats.tool_calls_agreed += 1
+            # Always allowing the tool while approving replaces its instance.
+            tool_instance = self.tool_manager.get(tool_call.tool_name)
+
+            if edit_path := resolve_edit_path(
+                tool_call.tool_name, tool_call.args_dict, self.workspace.active.path
+            ):
+                self.checkpoints.record_edit(edit_path)
 
+            policy = self.config.tool_policy
+            stop_requested = self.tool_stops.open(tool_call.call_id)
+            start_time = time.perf_counter()
             try:
-                start_time = time.perf_counter()
-                result_model = None
-
-                async for item in tool_instance.invoke(
-                    ctx=InvokeContext(
-                        tool_call_id=tool_call.call_id,
-                        approval_callback=self.approval_callback,
-                        agent_manager=self.agent_manager,
-                        user_input_callback=self.user_input_callback,
-                    ),
-                    **tool_call.args_dict,
-                ):
-                    if isinstance(item, ToolStreamEvent):
-                        yield item
-                    else:
-                        result_model = item
+                key = self.tool_cache.key(
+                    tool_call.tool_name,
+                    tool_call.args_dict,
+                    self.workspace.active.path,
+                )
+                result_model = self.tool_cache.get(key) if key else None
+                if result_model is not None:
+                    self.stats.tool_calls_cached += 1
+                else:
+                    events = limit_runtime(
+                        tool_instance.invoke(
+                            ctx=InvokeContext(
+                                tool_call_id=tool_call.call_id,
+                                approval_callback=self.approval_callback,
+                                agent_manager=self.agent_manager,
+                                user_input_callback=self.user_input_callback,
+                                write_roots=self.write_roots,
+                                stop_requested=stop_requested,
+                                cwd=self.workspace.active.path,
+                                network=self.config.network,
+                                output_limit=policy.output_limit(tool_call.tool_name),
+                            ),
+                            **tool_call.args_dict,
+                        ),
+                        tool_call.tool_name,
+                        policy.runtime_limit(tool_call.tool_name),
+                    )
+                    async for item in events:
+                        if isinstance(item, ToolStreamEvent):
+                            yield item
+                        else:
+                            result_model = item
+                    if key and result_model is not None:
+                        self.tool_cache.put(key, result_model)
 
                 duration = time.perf_counter() - start_time
 
                 if result_model is None:
                     raise ToolError("Tool did not yield a result")
 
-                text = "\n".join(
-                    f"{k}: {v}" for k, v in result_model.model_dump().items()
+                text = truncate_output(
+                    "\n".join(
+                        f"{k}: {v}" for k, v in result_model.model_dump().items()
+                    ),
+                    policy.output_limit(tool_call.tool_name),
+                )
+                images = (
+                    result_model.images if isinstance(result_model, ImageResult) else []
                 )
-                self._append_tool_response(tool_call, text)
+                redacted = await self._append_tool_response(tool_call, text, images)
+                if self.config.dedupe_tool_outputs:
+                    supersede_tool_outputs(self.messages, tool_call.call_id)
 
                 yield ToolResultEvent(
                     tool_name=tool_call.tool_name,
@@ -597,6 +1181,8 @@ class AgentLoop:
                     duration=duration,
                     tool_call_id=tool_call.call_id,
                 )
+                if redacted:
+                    yield redacted
 
                 self.stats.tool_calls_succeeded += 1
 
@@ -610,10 +1196,10 @@ class AgentLoop:
                     error=cancel,
                     tool_call_id=tool_call.call_id,
                 )
-                self._append_tool_response(tool_call, cancel)
+                await self._append_tool_response(tool_call, cancel)
                 raise
 
-            except (ToolError, ToolPermissionError) as exc:
+            except (ToolError, ToolPermissionError, ToolRuntimeExceededError) as exc:
                 error_msg = f"<{TOOL_ERROR_TAG}>{tool_instance.get_name()} failed: {exc}</{TOOL_ERROR_TAG}>"
 
                 yield ToolResultEvent(
@@ -628,26 +1214,106 @@ class AgentLoop:
                     self.stats.tool_calls_rejected += 1
                 else:
                     self.stats.tool_calls_failed += 1
-                self._append_tool_response(tool_call, error_msg)
+                if redacted := await self._append_tool_response(tool_call, error_msg):
+                    yield redacted
                 continue
 
-    def _append_tool_response(self, tool_call: ResolvedToolCall, text: str) -> None:
-        self.messages.append(
-            LLMMessage.model_validate(
-                self.format_handler.create_tool_response_message(tool_call, text)
+            finally:
+                self.tool_stops.close(tool_call.call_id)
+                self.tool_cache.forget_after(tool_call.tool_name)
+                tool_time = time.perf_counter() - start_time
+                self.stats.last_turn_tool_time += tool_time
+                self.stats.session_tool_time += tool_time
+
+    async def _append_tool_response(
+        self,
+        tool_call: ResolvedToolCall,
+        text: str,
+        images: list[ImageContent] | None = None,
+    ) -> SecretsRedactedEvent | None:
+        """Add a tool output to the history, with credentials masked first.
+
+        Images go along only if the model takes them, scaled down to its limits.
+
+        Returns the audit event listing what was masked, if anything was.
+        """
+        model = self._current_model()
+        if images and not model.accepts_images:
+            text += (
+                f"\n({len(images)} image(s) left out: the model doesn't take "
+                "image input)"
             )
+            images = []
+        elif images:
+            # Scaling decodes and re-encodes the image, which takes a while.
+            fitted = [
+                await anyio.to_thread.run_sync(
+                    partial(
+                        fit_image,
+                        image,
+                        max_side=model.max_image_side,
+                        max_bytes=model.max_image_bytes,
+                    )
+                )
+                for image in images
+            ]
+            images = [image for image in fitted if image is not None]
+            if dropped := len(fitted) - len(images):
+                text += (
+                    f"\n({dropped} image(s) left out: too large for the model "
+                    "even when scaled down)"
+                )
+        redactions: Counter[str] = Counter()
+        if (config := self.config.redaction).enabled:
+            redactor = Redactor(config.patterns, config.disabled_patterns)
+            text, redactions = redactor.redact(
+                text, env_file=reads_env_file(tool_call.tool_name, tool_call.args_dict)
+            )
+        message = LLMMessage.model_validate(
+            self.format_handler.create_tool_response_message(tool_call, text)
+        )
+        if images:
+            message.images = images
+        self.messages.append(message)
+        if message.images:
+            self._drop_old_images()
+        if not redactions:
+            return None
+        return SecretsRedactedEvent(
+            tool_name=tool_call.tool_name,
+            tool_call_id=tool_call.call_id,
+            redactions=dict(redactions),
+        )
+
+    def _drop_old_images(self) -> None:
+        kept = 0
+        for message in reversed(self.messages):
+            room = MAX_CONTEXT_IMAGES - kept
+            if len(message.images) > room:
+                message.images = message.images[len(message.images) - room :]
+            kept += len(message.images)
+
+    def _step_tools(
+        self,
+    ) -> tuple[list[AvailableTool] | None, StrToolChoice | AvailableTool | None]:
+        if self._schema_step:
+            return None, None
+        return (
+            self.format_handler.get_available_tools(self.tool_manager),
+            self.format_handler.get_tool_choice(),
         )
 
     async def _chat(self, max_tokens: int | None = None) -> LLMChunk:
-        active_model = self.config.get_active_model()
+        active_model = self._current_model()
         provider = self.config.get_provider_for_model(active_model)
+        backend = self._backend_for(provider)
 
-        available_tools = self.format_handler.get_available_tools(self.tool_manager)
-        tool_choice = self.format_handler.get_tool_choice()
+        available_tools, tool_choice = self._step_tools()
+        output_schema = self._output_schema if self._schema_step else None
 
         try:
             start_time = time.perf_counter()
-            result = await self.backend.complete(
+            result = await backend.complete(
                 model=active_model,
                 messages=self.messages,
                 temperature=active_model.temperature,
@@ -658,6 +1324,7 @@ class AgentLoop:
                     "x-affinity": self.session_id,
                 },
                 max_tokens=max_tokens,
+                output_schema=output_schema,
             )
             end_time = time.perf_counter()
 
@@ -675,6 +1342,7 @@ class AgentLoop:
 
         except Exception as e:
             if _should_raise_rate_limit_error(e):
+                self.rate_limited_at = utc_now().isoformat()
                 raise RateLimitError(provider.name, active_model.name) from e
 
             raise RuntimeError(
@@ -683,35 +1351,69 @@ class AgentLoop:
 
     async def _chat_streaming(
         self, max_tokens: int | None = None
-    ) -> AsyncGenerator[LLMChunk]:
-        active_model = self.config.get_active_model()
+    ) -> AsyncGenerator[LLMChunk | StreamStalledEvent | TokenCountEvent]:
+        active_model = self._current_model()
         provider = self.config.get_provider_for_model(active_model)
-
-        available_tools = self.format_handler.get_available_tools(self.tool_manager)
-        tool_choice = self.format_handler.get_tool_choice()
+        backend = self._backend_for(provider)
+
+        available_tools, tool_choice = self._step_tools()
+        output_schema = self._output_schema if self._schema_step else None
+        stalls = StallRecovery(
+            active_model.alias,
+            self.config.stream_stall_retries,
+            can_resume=provider.backend == Backend.GENERIC
+            and supports_prefill(provider),
+        )
         try:
             start_time = time.perf_counter()
             usage = LLMUsage()
             chunk_agg = LLMChunk(message=LLMMessage(role=Role.assistant))
-            async for chunk in self.backend.complete_streaming(
-                model=active_model,
-                messages=self.messages,
-                temperature=active_model.temperature,
-                tools=available_tools,
-                tool_choice=tool_choice,
-                extra_headers={
-                    "user-agent": get_user_agent(provider.backend),
-                    "x-affinity": self.session_id,
-                },
-                max_tokens=max_tokens,
-            ):
-                processed_message = self.format_handler.process_api_response_message(
-                    chunk.message
+            prompt_estimate = self._estimate_tokens(self.messages)
+            last_count = start_time
+            while True:
+                stream = backend.complete_streaming(
+                    model=active_model,
+                    messages=stalls.messages(self.messages, chunk_agg.message),
+                    temperature=active_model.temperature,
+                    tools=available_tools,
+                    tool_choice=tool_choice,
+                    extra_headers={
+                        "user-agent": get_user_agent(provider.backend),
+                        "x-affinity": self.session_id,
+                    },
+                    max_tokens=max_tokens,
+                    output_schema=output_schema,
                 )
-                processed_chunk = LLMChunk(message=processed_message, usage=chunk.usage)
-                chunk_agg += processed_chunk
-                usage += chunk.usage or LLMUsage()
-                yield processed_chunk
+                try:
+                    async for chunk in watch_idle(
+                        stream, self.config.stream_idle_timeout
+                    ):
+                        processed_message = (
+                            self.format_handler.process_api_response_message(
+                                chunk.message
+                            )
+                        )
+                        processed_chunk = LLMChunk(
+                            message=processed_message, usage=chunk.usage
+                        )
+                        chunk_agg += processed_chunk
+                        usage += chunk.usage or LLMUsage()
+                        yield processed_chunk
+
+                        interval = self.config.token_count_interval
+                        now = time.perf_counter()
+                        if interval > 0 and now - last_count >= interval:
+                            last_count = now
+                            yield self._token_count(
+                                usage, chunk_agg.message, prompt_estimate
+                            )
+                    break
+                except StreamStalledError as e:
+                    stalled = stalls.recover(e, chunk_agg.message)
+                    if not stalled.resumed:
+                        usage = LLMUsage()
+                        chunk_agg = LLMChunk(message=LLMMessage(role=Role.assistant))
+                    yield stalled
             end_time = time.perf_counter()
 
             if chunk_agg.usage is None:
@@ -724,52 +1426,227 @@ class AgentLoop:
 
         except Exception as e:
             if _should_raise_rate_limit_error(e):
+                self.rate_limited_at = utc_now().isoformat()
                 raise RateLimitError(provider.name, active_model.name) from e
 
             raise RuntimeError(
                 f"API error from {provider.name} (model: {active_model.name}): {e}"
             ) from e
 
+    def _estimate_tokens(self, messages: list[LLMMessage]) -> int:
+        """Token count until the provider reports usage, with the model's own
+        tokenizer once it is loaded.
+        """
+        tokenizer = self._tokenizers.get(self._current_model().tokenizer)
+        return count_message_tokens(tokenizer or ByteHeuristicTokenizer(), messages)
+
+    async def _load_tokenizer(self) -> None:
+        spec = self._current_model().tokenizer
+        if spec not in self._tokenizers:
+            self._tokenizers[spec] = await asyncio.to_thread(load_tokenizer, spec)
+
+    def _token_count(
+        self, usage: LLMUsage, reply: LLMMessage, prompt_estimate: int
+    ) -> TokenCountEvent:
+        # Providers that report usage mid-stream (e.g. in their first event)
+        # replace the estimates.
+        model = self._current_model()
+        estimate = LLMUsage(
+            prompt_tokens=usage.prompt_tokens or prompt_estimate,
+            completion_tokens=usage.completion_tokens
+            or self._estimate_tokens([reply]),
+            cached_tokens=usage.cached_tokens,
+        )
+        cost = self.config.get_model_pricing(model).cost(estimate)
+        return TokenCountEvent(
+            prompt_tokens=estimate.prompt_tokens,
+            completion_tokens=estimate.completion_tokens,
+            cached_tokens=estimate.cached_tokens,
+            context_window=model.context_window,
+            estimated=not (usage.prompt_tokens and usage.completion_tokens),
+            rate_limited_at=self.rate_limited_at,
+            cost=cost,
+            session_cost=self.stats.session_cost + cost,
+        )
+
     def _update_stats(self, usage: LLMUsage, time_seconds: float) -> None:
         self.stats.last_turn_duration = time_seconds
-        self.stats.last_turn_prompt_tokens = usage.prompt_tokens
-        self.stats.last_turn_completion_tokens = usage.completion_tokens
-        self.stats.session_prompt_tokens += usage.prompt_tokens
-        self.stats.session_completion_tokens += usage.completion_tokens
+        self.stats.last_turn_model_time += time_seconds
+        self.stats.session_model_time += time_seconds
+        pricing = self.config.get_model_pricing(self._current_model())
+        self.stats.record_usage(usage, pricing.cost(usage))
         self.stats.context_tokens = usage.prompt_tokens + usage.completion_tokens
         if time_seconds > 0 and usage.completion_tokens > 0:
             self.stats.tokens_per_second = usage.completion_tokens / time_seconds
 
+    async def _refresh_models(self) -> ModelListChangedEvent | None:
+        """Reload models, providers and prices if their config files changed
+        since the last turn, so model rollouts reach running sessions.
+        """
+        if not self.config.watch_models:
+            return None
+        with self._in_project():
+            if not self.model_sources.changed():
+                return None
+            try:
+                # Loading can fetch org defaults and the models registry.
+                fresh = await asyncio.to_thread(RuneConfig.load)
+            except Exception as e:
+                logger.warning("Could not reload the model list: %s", e)
+                return None
+        base_config, event = merge_model_list(self._base_config, fresh)
+        if event is not None:
+            await self.reload_with_initial_messages(base_config=base_config)
+        return event
+
+    async def _refresh_config(self) -> ConfigReloadedEvent | None:
+        """Apply the changes made to the config file since the last turn that a
+        running session can take: the active model, tools, skills and MCP
+        servers. Other changes are reported as needing a restart.
+        """
+        if not self.config.watch_config:
+            return None
+        with self._in_project():
+            base_config, event = self.config_reloader.reload(self._base_config)
+        if event is not None and event.applied:
+            await self.reload_with_initial_messages(base_config=base_config)
+        return event
+
+    async def _ensure_local_model_ready(
+        self,
+    ) -> AsyncGenerator[LocalModelDownloadEvent]:
+        model = self._current_model()
+        provider = self.config.get_provider_for_model(model)
+        options = provider.lmstudio
+        key = (provider.name, model.name)
+        if options is None or key in self._ready_local_models:
+            return
+        if not (options.auto_download or options.context_length):
+            return
+        try:
+            async for progress in LMStudioClient(provider).ensure_ready(model.name):
+                if isinstance(progress, DownloadProgress):
+                    yield LocalModelDownloadEvent(**progress.model_dump())
+        except LMStudioError as e:
+            # The completion request reports a missing model more plainly.
+            logger.warning("Could not prepare %s in LM Studio: %s", model.name, e)
+            return
+        self._ready_local_models.add(key)
+
+    def _is_allowed_in_read_only(self, tool: BaseTool, args: BaseModel) -> bool:
+        return (
+            tool.get_name() in READ_ONLY_TOOLS
+            or tool.check_allowlist_denylist(args, self.workspace.active.path)
+            == ToolPermission.ALWAYS
+        )
+
     async def _should_execute_tool(
         self, tool: BaseTool, args: BaseModel, tool_call_id: str
     ) -> ToolDecision:
-        if self.auto_approve:
-            return ToolDecision(verdict=ToolExecutionResponse.EXECUTE)
+        tool_name = tool.get_name()
+        policy = self.config.tool_policy
+        if policy.is_disabled(tool_name):
+            return ToolDecision(
+                verdict=ToolExecutionResponse.SKIP,
+                feedback=f"Tool '{tool_name}' is disabled by [tools.policy]",
+                rule=f"{POLICY_RULE_PREFIX}{TOOL_POLICY_KEY}.disabled",
+            )
+        # Tools the policy names always ask, whatever would approve them below.
+        needs_approval = policy.requires_approval(tool_name)
+
+        if self.dry_run and (report := dry_run_report(tool, args)) is not None:
+            return ToolDecision(
+                verdict=ToolExecutionResponse.SKIP, feedback=report, rule="dry_run"
+            )
+
+        if self.read_only and not self._is_allowed_in_read_only(tool, args):
+            return ToolDecision(
+                verdict=ToolExecutionResponse.SKIP,
+                feedback=f"Tool '{tool.get_name()}' is not allowed in read-only mode.",
+                rule="read_only",
+            )
+
+        cwd = self.workspace.active.path
+        if (
+            (target := tool.get_write_target(args, cwd))
+            and not self.write_roots.allows(target)
+            and tool.check_allowlist_denylist(args, cwd) != ToolPermission.NEVER
+        ):
+            return await self._ask_write_grant(
+                tool.get_name(), args, tool_call_id, target.parent
+            )
+
+        if self.auto_approve and not needs_approval:
+            return ToolDecision(
+                verdict=ToolExecutionResponse.EXECUTE, rule="auto_approve"
+            )
 
-        allowlist_denylist_result = tool.check_allowlist_denylist(args)
-        if allowlist_denylist_result == ToolPermission.ALWAYS:
-            return ToolDecision(verdict=ToolExecutionResponse.EXECUTE)
+        rule = f"{POLICY_RULE_PREFIX}{tool_name}"
+        allowlist_denylist_result = tool.check_allowlist_denylist(args, cwd)
+        if allowlist_denylist_result == ToolPermission.ALWAYS and not needs_approval:
+            return ToolDecision(
+                verdict=ToolExecutionResponse.EXECUTE, rule=f"{rule}.allowlist"
+            )
         elif allowlist_denylist_result == ToolPermission.NEVER:
             denylist_patterns = tool.config.denylist
             denylist_str = ", ".join(repr(pattern) for pattern in denylist_patterns)
             return ToolDecision(
                 verdict=ToolExecutionResponse.SKIP,
                 feedback=f"Tool '{tool.get_name()}' blocked by denylist: [{denylist_str}]",
+                rule=f"{rule}.denylist",
             )
 
-        tool_name = tool.get_name()
         perm = self.tool_manager.get_tool_config(tool_name).permission
 
-        if perm is ToolPermission.ALWAYS:
-            return ToolDecision(verdict=ToolExecutionResponse.EXECUTE)
+        if perm is ToolPermission.ALWAYS and not needs_approval:
+            return ToolDecision(
+                verdict=ToolExecutionResponse.EXECUTE, rule=f"{rule}.permission"
+            )
         if perm is ToolPermission.NEVER:
             return ToolDecision(
                 verdict=ToolExecutionResponse.SKIP,
                 feedback=f"Tool '{tool_name}' is permanently disabled",
+                rule=f"{rule}.permission",
             )
 
         return await self._ask_approval(tool_name, args, tool_call_id)
 
+    def _audit_decision(
+        self, tool_name: str, args: BaseModel, tool_call_id: str, decision: ToolDecision
+    ) -> None:
+        verdict = (
+            "allow" if decision.verdict == ToolExecutionResponse.EXECUTE else "deny"
+        )
+        record = AuditRecord(
+            kind=AuditEventKind.APPROVAL_DECIDED,
+            session_id=self.session_id,
+            tool=tool_name,
+            tool_call_id=tool_call_id,
+            command_hash=command_hash(args),
+            rule_id=decision.rule,
+            decision=verdict,
+        )
+        if decision.rule and decision.rule.startswith(POLICY_RULE_PREFIX):
+            self.audit.record(
+                record.model_copy(update={"kind": AuditEventKind.POLICY_MATCHED})
+            )
+        self.audit.record(record)
+
+    async def _ask_write_grant(
+        self, tool_name: str, args: BaseModel, tool_call_id: str, directory: Path
+    ) -> ToolDecision:
+        # Writes outside the workspace always ask, even in auto-approve mode, and an
+        # approval grants the whole directory for the rest of the session.
+        self.write_grant_requests[tool_call_id] = directory
+        try:
+            decision = await self._ask_approval(tool_name, args, tool_call_id)
+        finally:
+            self.write_grant_requests.pop(tool_call_id, None)
+
+        if decision.verdict == ToolExecutionResponse.EXECUTE:
+            self.write_roots.add(directory)
+        return decision
+
     async def _ask_approval(
         self, tool_name: str, args: BaseModel, tool_call_id: str
     ) -> ToolDecision:
@@ -777,13 +1654,26 @@ class AgentLoop:
             return ToolDecision(
                 verdict=ToolExecutionResponse.SKIP,
                 feedback="Tool execution not permitted.",
+                rule="no_approver",
             )
-        if asyncio.iscoroutinefunction(self.approval_callback):
-            async_callback = cast(AsyncApprovalCallback, self.approval_callback)
-            response, feedback = await async_callback(tool_name, args, tool_call_id)
-        else:
-            sync_callback = cast(SyncApprovalCallback, self.approval_callback)
-            response, feedback = sync_callback(tool_name, args, tool_call_id)
+
+        self.audit.record(
+            AuditRecord(
+                kind=AuditEventKind.APPROVAL_REQUESTED,
+                session_id=self.session_id,
+                tool=tool_name,
+                tool_call_id=tool_call_id,
+                command_hash=command_hash(args),
+            )
+        )
+
+        async with self.critic.reviewing(tool_name, args, tool_call_id, self.messages):
+            if asyncio.iscoroutinefunction(self.approval_callback):
+                async_callback = cast(AsyncApprovalCallback, self.approval_callback)
+                response, feedback = await async_callback(tool_name, args, tool_call_id)
+            else:
+                sync_callback = cast(SyncApprovalCallback, self.approval_callback)
+                response, feedback = sync_callback(tool_name, args, tool_call_id)
 
         match response:
             case ApprovalResponse.YES:
@@ -854,9 +1744,35 @@ class AgentLoop:
             empty_assistant_msg = LLMMessage(role=Role.assistant, content="Understood.")
             self.messages.append(empty_assistant_msg)
 
-    def _reset_session(self) -> None:
+    def _reset_session(self, *, keep_title: bool = False) -> None:
         self.session_id = str(uuid4())
-        self.session_logger.reset_session(self.session_id)
+        self.session_logger.reset_session(self.session_id, keep_title=keep_title)
+
+    def _schedule_auto_title(self) -> None:
+        if (
+            not self.config.auto_title.enabled
+            or not self.session_logger.enabled
+            or self.session_logger.has_title
+            or self._titled_session == self.session_id
+        ):
+            return
+        # One attempt per session, so a failing title model isn't retried on
+        # every turn.
+        self._titled_session = self.session_id
+        self._title_task = asyncio.create_task(
+            self._auto_title(self.session_id, list(self.messages))
+        )
+
+    async def _auto_title(self, session_id: str, messages: list[LLMMessage]) -> None:
+        if not (title := await self.titler.generate(messages)):
+            return
+        # The session may have been cleared or renamed while the title was written.
+        if session_id != self.session_id or self.session_logger.has_title:
+            return
+        try:
+            await self.session_logger.set_auto_title(title)
+        except RuntimeError as e:
+            logger.warning("Could not save session title: %s", e)
 
     def set_approval_callback(self, callback: ApprovalCallback) -> None:
         self.approval_callback = callback
@@ -865,6 +1781,7 @@ class AgentLoop:
         self.user_input_callback = callback
 
     async def clear_history(self) -> None:
+        self._cancel_background_compaction()
         await self.session_logger.save_interaction(
             self.messages,
             self.stats,
@@ -878,11 +1795,47 @@ class AgentLoop:
         self.stats.trigger_listeners()
 
         self.middleware_pipeline.reset()
-        self.tool_manager.reset_all()
+        await self.tool_manager.reset_all()
+        self.checkpoints.clear()
         self._reset_session()
 
+    async def load_history(
+        self, messages: list[LLMMessage], metadata: dict[str, Any] | None = None
+    ) -> None:
+        """Replace the conversation with previously saved messages.
+
+        The session's ``metadata``, when given, carries its name and title over.
+        """
+        self._cancel_background_compaction()
+        await self.session_logger.save_interaction(
+            self.messages,
+            self.stats,
+            self._base_config,
+            self.tool_manager,
+            self.agent_profile,
+        )
+        self.messages = [
+            self.messages[0],
+            *(msg for msg in messages if msg.role != Role.system),
+        ]
+        self._last_observed_message_index = len(self.messages)
+
+        self.stats = AgentStats()
+        self.stats.trigger_listeners()
+
+        self.middleware_pipeline.reset()
+        await self.tool_manager.reset_all()
+        self.checkpoints.clear()
+        self._reset_session()
+        metadata = metadata or {}
+        if name := metadata.get("name"):
+            await self.session_logger.set_name(name)
+        if auto_title := metadata.get("auto_title"):
+            await self.session_logger.set_auto_title(auto_title)
+
     async def compact(self) -> str:
         """Compact the conversation history."""
+        self._cancel_background_compaction()
         try:
             self._clean_message_history()
             await self.session_logger.save_interaction(
@@ -893,34 +1846,20 @@ class AgentLoop:
                 self.agent_profile,
             )
 
-            summary_request = UtilityPrompt.COMPACT.read()
-            self.messages.append(LLMMessage(role=Role.user, content=summary_request))
-            self.stats.steps += 1
-
-            summary_result = await self._chat()
-            if summary_result.usage is None:
-                raise AgentLoopLLMResponseError(
-                    "Usage data missing in compaction summary response"
-                )
-            summary_content = summary_result.message.content or ""
-
-            system_message = self.messages[0]
-            summary_message = LLMMessage(role=Role.user, content=summary_content)
-            self.messages = [system_message, summary_message]
-
-            active_model = self.config.get_active_model()
-            provider = self.config.get_provider_for_model(active_model)
-
-            actual_context_tokens = await self.backend.count_tokens(
-                model=active_model,
-                messages=self.messages,
-                tools=self.format_handler.get_available_tools(self.tool_manager),
-                extra_headers={"user-agent": get_user_agent(provider.backend)},
-            )
+            match self.config.compaction.strategy:
+                case CompactionStrategy.TRUNCATE_OLDEST:
+                    summary_content = self._truncate_history()
+                case CompactionStrategy.HYBRID:
+                    summary_content = await self._summarize_history(
+                        keep_recent_turns=self.config.compaction.keep_recent_turns
+                    )
+                case CompactionStrategy.SUMMARIZE:
+                    summary_content = await self._summarize_history()
 
-            self.stats.context_tokens = actual_context_tokens
+            await self._finish_compaction()
+            return summary_content or ""
 
-            self._reset_session()
+        except Exception:
             await self.session_logger.save_interaction(
                 self.messages,
                 self.stats,
@@ -928,12 +1867,108 @@ class AgentLoop:
                 self.tool_manager,
                 self.agent_profile,
             )
+            raise
 
-            self.middleware_pipeline.reset(reset_reason=ResetReason.COMPACT)
+    async def _finish_compaction(self) -> None:
+        """Recount the compacted context and continue it in a new session."""
+        active_model = self.config.get_active_model()
+        provider = self.config.get_provider_for_model(active_model)
 
-            return summary_content or ""
+        actual_context_tokens = await self.backend.count_tokens(
+            model=active_model,
+            messages=self.messages,
+            tools=self.format_handler.get_available_tools(self.tool_manager),
+            extra_headers={"user-agent": get_user_agent(provider.backend)},
+        )
 
-        except Exception:
+        self.stats.context_tokens = actual_context_tokens
+
+        self._reset_session(keep_title=True)
+        await self.session_logger.save_interaction(
+            self.messages,
+            self.stats,
+            self._base_config,
+            self.tool_manager,
+            self.agent_profile,
+        )
+
+        self.middleware_pipeline.reset(reset_reason=ResetReason.COMPACT)
+        self.checkpoints.clear()
+
+    @property
+    def _compacts_in_background(self) -> bool:
+        compaction = self.config.compaction
+        return (
+            compaction.background
+            and compaction.strategy != CompactionStrategy.TRUNCATE_OLDEST
+        )
+
+    async def _compact_in_background(
+        self, old_tokens: int, threshold: int
+    ) -> AsyncGenerator[BaseEvent]:
+        """Start summarizing the history so far without waiting for it, unless
+        the context is about to outgrow the model's window.
+        """
+        if self._background_compaction is None:
+            system_message, *history = self.messages
+            keep = (
+                self.config.compaction.keep_recent_turns
+                if self.config.compaction.strategy == CompactionStrategy.HYBRID
+                else 0
+            )
+            older, recent = split_recent_turns(history, keep)
+            if not older:
+                return
+            self._background_compaction = BackgroundCompaction(
+                task=asyncio.create_task(self._write_summary([system_message, *older])),
+                snapshot_length=len(self.messages),
+                recent=recent,
+                tool_call_id=str(uuid4()),
+                old_tokens=old_tokens,
+            )
+            yield CompactStartEvent(
+                tool_call_id=self._background_compaction.tool_call_id,
+                current_context_tokens=old_tokens,
+                threshold=threshold,
+                background=True,
+            )
+
+        window = self._current_model().context_window
+        hard_limit = (
+            int(window * BACKGROUND_COMPACTION_WINDOW_SHARE)
+            if window
+            else int(threshold * BACKGROUND_COMPACTION_OVERSHOOT)
+        )
+        if self.stats.context_tokens >= hard_limit:
+            async for event in self._splice_background_compaction(wait=True):
+                yield event
+
+    async def _write_summary(self, messages: list[LLMMessage]) -> str:
+        summary_request = LLMMessage(
+            role=Role.user, content=UtilityPrompt.COMPACT.read()
+        )
+        result = await self._complete_with(
+            self.config.compaction.get_model(self.config),
+            [*messages, summary_request],
+        )
+        return result.message.content or ""
+
+    async def _splice_background_compaction(
+        self, *, wait: bool = False
+    ) -> AsyncGenerator[BaseEvent]:
+        """Swap the background summary in for the messages it covers, keeping
+        those added since it started.
+        """
+        pending = self._background_compaction
+        if pending is None or (not wait and not pending.task.done()):
+            return
+        self._background_compaction = None
+        try:
+            summary = await pending.task
+        except Exception as e:
+            logger.warning("Background compaction failed: %s", e)
+            summary = ""
+        if summary:
             await self.session_logger.save_interaction(
                 self.messages,
                 self.stats,
@@ -941,7 +1976,161 @@ class AgentLoop:
                 self.tool_manager,
                 self.agent_profile,
             )
-            raise
+            self.messages = [
+                self.messages[0],
+                LLMMessage(role=Role.user, content=summary),
+                *pending.recent,
+                *self.messages[pending.snapshot_length :],
+            ]
+            self._last_observed_message_index = min(
+                self._last_observed_message_index, len(self.messages)
+            )
+            await self._finish_compaction()
+        yield CompactEndEvent(
+            tool_call_id=pending.tool_call_id,
+            old_context_tokens=pending.old_tokens,
+            new_context_tokens=self.stats.context_tokens,
+            summary_length=len(summary),
+        )
+
+    def _cancel_background_compaction(self) -> None:
+        if self._background_compaction is not None:
+            self._background_compaction.task.cancel()
+            self._background_compaction = None
+
+    def cancel_background_tasks(self) -> None:
+        """Stop the work that runs between turns, before the loop is dropped."""
+        self._cancel_background_compaction()
+        if self._title_task is not None:
+            self._title_task.cancel()
+            self._title_task = None
+
+    async def _summarize_history(self, keep_recent_turns: int = 0) -> str:
+        """Replace the history, but for its last ``keep_recent_turns`` turns,
+        with a summary.
+        """
+        system_message, *history = self.messages
+        older, recent = split_recent_turns(history, keep_recent_turns)
+        if not older:
+            return ""
+
+        summary_request = LLMMessage(
+            role=Role.user, content=UtilityPrompt.COMPACT.read()
+        )
+        self.stats.steps += 1
+        if not self.config.compaction.model:
+            self.messages = [system_message, *older, summary_request]
+            summary_result = await self._chat()
+            if summary_result.usage is None:
+                raise AgentLoopLLMResponseError(
+                    "Usage data missing in compaction summary response"
+                )
+        else:
+            summary_result = await self._complete_with(
+                self.config.compaction.get_model(self.config),
+                [system_message, *older, summary_request],
+            )
+        summary_content = summary_result.message.content or ""
+
+        summary_message = LLMMessage(role=Role.user, content=summary_content)
+        self.messages = [system_message, summary_message, *recent]
+        return summary_content
+
+    async def _complete_with(
+        self, model: ModelConfig, messages: list[LLMMessage]
+    ) -> LLMChunk:
+        """A one-off completion on ``model``, such as a cheaper model writing a
+        summary, that leaves the conversation untouched.
+        """
+        provider = self.config.get_provider_for_model(model)
+        active_provider = self.config.get_provider_for_model(
+            self.config.get_active_model()
+        )
+        if self._injected_backend is not None or provider.name == active_provider.name:
+            backend = self.backend
+        else:
+            if provider.name not in self._routed_backends:
+                self._routed_backends[provider.name] = BACKEND_FACTORY[
+                    provider.backend
+                ](provider=provider, timeout=self.config.api_timeout)
+            backend = self._routed_backends[provider.name]
+        result = await backend.complete(
+            model=model,
+            messages=messages,
+            temperature=model.temperature,
+            tools=self.format_handler.get_available_tools(self.tool_manager),
+            tool_choice=self.format_handler.get_tool_choice(),
+            extra_headers={"user-agent": get_user_agent(provider.backend)},
+            max_tokens=None,
+        )
+        if result.usage is None:
+            raise AgentLoopLLMResponseError(
+                "Usage data missing in compaction summary response"
+            )
+        pricing = self.config.get_model_pricing(model)
+        self.stats.record_usage(result.usage, pricing.cost(result.usage))
+        return result
+
+    def _truncate_history(self) -> str:
+        """Drop the oldest turns until the history fits the truncation target,
+        without asking a model.
+        """
+        system_message, *history = self.messages
+        budget = int(
+            self.config.auto_compact_threshold
+            * self.config.compaction.truncate_target
+        )
+        kept = truncate_oldest(
+            history,
+            budget - self._estimate_tokens([system_message]),
+            self._estimate_tokens,
+        )
+        self.messages = [system_message, *kept]
+        if dropped := len(history) - len(kept):
+            return f"Dropped the {dropped} oldest messages of the conversation."
+        return ""
+
+    async def replace_history(
+        self, messages: list[LLMMessage], context_tokens: int | None = None
+    ) -> None:
+        """Replace the conversation after the system message with ``messages``.
+
+        ``context_tokens`` defaults to an estimate of the new conversation.
+        """
+        if context_tokens is None:
+            context_tokens = self._estimate_tokens([self.messages[0], *messages])
+        self._cancel_background_compaction()
+        await self.session_logger.save_interaction(
+            self.messages,
+            self.stats,
+            self._base_config,
+            self.tool_manager,
+            self.agent_profile,
+        )
+        self.messages = [self.messages[0], *messages]
+        self._last_observed_message_index = min(
+            self._last_observed_message_index, len(self.messages)
+        )
+        self.stats.context_tokens = context_tokens
+        self.stats.trigger_listeners()
+        self.middleware_pipeline.reset()
+
+        # The replaced history continues in a new session so the old one stays intact.
+        self._reset_session(keep_title=True)
+        await self.session_logger.save_interaction(
+            self.messages,
+            self.stats,
+            self._base_config,
+            self.tool_manager,
+            self.agent_profile,
+        )
+
+    async def set_response_language(self, language: str) -> None:
+        await self.reload_with_initial_messages(
+            base_config=self._base_config.model_copy(
+                update={"response_language": language}
+            )
+        )
 
     async def switch_agent(self, agent_name: str) -> None:
         if agent_name == self.agent_profile.name:
@@ -974,21 +2163,20 @@ class AgentLoop:
             self.agent_manager.invalidate_config()
 
         self.backend = self.backend_factory()
+        self._routed_backends.clear()
+        self._refresh_pricing()
 
         if max_turns is not None:
             self._max_turns = max_turns
         if max_price is not None:
             self._max_price = max_price
 
-        self.tool_manager = ToolManager(lambda: self.config)
-        self.skill_manager = SkillManager(lambda: self.config)
-
-        new_system_prompt = get_universal_system_prompt(
-            self.tool_manager, self.config, self.skill_manager, self.agent_manager
-        )
+        with self._in_project():
+            self.tool_manager = ToolManager(lambda: self.config)
+            self.skill_manager = SkillManager(lambda: self.config)
 
         self.messages = [
-            LLMMessage(role=Role.system, content=new_system_prompt),
+            self._system_message(),
             *[msg for msg in self.messages if msg.role != Role.system],
         ]
 
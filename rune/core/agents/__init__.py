@@ -9,6 +9,7 @@ from rune.core.agents.models import (
     EXPLORE,
     PLAN,
     PLAN_AGENT_TOOLS,
+    READ_ONLY_TOOLS,
     AgentProfile,
     AgentSafety,
     AgentType,
@@ -23,6 +24,7 @@ __all__ = [
     "EXPLORE",
     "PLAN",
     "PLAN_AGENT_TOOLS",
+    "READ_ONLY_TOOLS",
     "AgentManager",
     "AgentProfile",
     "AgentSafety",
@@ -11,7 +11,7 @@ from rune.core.agents.models import (
     AgentType,
     BuiltinAgentName,
 )
-from rune.core.paths.config_paths import resolve_local_agents_dir
+from rune.core.paths.config_paths import project_dir, resolve_local_agents_dir
 from rune.core.paths.global_paths import GLOBAL_AGENTS_DIR
 from rune.core.utils import name_matches
 
@@ -86,7 +86,7 @@ class AgentManager:
         for path in config.agent_paths:
             if path.is_dir():
                 paths.append(path)
-        if (agents_dir := resolve_local_agents_dir(Path.cwd())) is not None:
+        if (agents_dir := resolve_local_agents_dir(project_dir())) is not None:
             paths.append(agents_dir)
         if GLOBAL_AGENTS_DIR.path.is_dir():
             paths.append(GLOBAL_AGENTS_DIR.path)
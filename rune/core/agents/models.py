@@ -70,6 +70,7 @@ class AgentProfile:
 
 
 PLAN_AGENT_TOOLS = ["grep", "read_file", "todo", "ask_user_question", "task"]
+READ_ONLY_TOOLS = ["grep", "read_file", "todo", "ask_user_question"]
 
 DEFAULT = AgentProfile(
     BuiltinAgentName.DEFAULT,
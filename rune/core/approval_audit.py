@@ -0,0 +1,146 @@
+from __future__ import annotations
+
+from collections.abc import Callable
+from enum import StrEnum
+import hashlib
+import time
+from typing import TYPE_CHECKING, Any
+
+from pydantic import BaseModel
+
+from rune.core.utils import logger
+
+if TYPE_CHECKING:
+    from rune.core.config import OtelConfig, RuneConfig
+
+INSTALL_HINT = (
+    "Exporting approval logs needs OpenTelemetry: "
+    "pip install 'rune-cli[otel]' (or uv tool install 'rune-cli[otel]')"
+)
+OTEL_LOGGER_NAME = "rune.approvals"
+# Rule ids of the per-tool rules from the config and shared policy bundles, as
+# opposed to session modes such as auto-approve.
+POLICY_RULE_PREFIX = "tools."
+
+
+class AuditEventKind(StrEnum):
+    APPROVAL_REQUESTED = "approval.requested"
+    APPROVAL_DECIDED = "approval.decided"
+    POLICY_MATCHED = "policy.matched"
+
+
+class AuditRecord(BaseModel):
+    kind: AuditEventKind
+    session_id: str
+    tool: str
+    tool_call_id: str
+    command_hash: str
+    rule_id: str | None = None
+    decision: str | None = None
+
+    def attributes(self) -> dict[str, str]:
+        fields = {
+            "event.name": self.kind.value,
+            "rune.session_id": self.session_id,
+            "rune.tool": self.tool,
+            "rune.tool_call_id": self.tool_call_id,
+            "rune.command_hash": self.command_hash,
+            "rune.rule_id": self.rule_id,
+            "rune.decision": self.decision,
+        }
+        return {key: value for key, value in fields.items() if value is not None}
+
+
+def command_hash(args: BaseModel) -> str:
+    """SHA-256 of the command a tool call runs, or of all its arguments.
+
+    Lets dashboards group identical calls without shipping their content.
+    """
+    command = getattr(args, "command", None)
+    payload = command if isinstance(command, str) else args.model_dump_json()
+    return hashlib.sha256(payload.encode()).hexdigest()
+
+
+class ApprovalAudit:
+    """Sends a structured log record of each approval request, decision and
+    policy rule match to an OpenTelemetry collector.
+    """
+
+    def __init__(
+        self,
+        config_getter: Callable[[], RuneConfig],
+        sink: Callable[[AuditRecord], None] | None = None,
+    ) -> None:
+        self._config_getter = config_getter
+        self._sink = sink
+        self._provider: Any = None
+        self._provider_key: tuple[Any, ...] | None = None
+        self._otel_logger: Any = None
+        self._unavailable = False
+
+    def record(self, record: AuditRecord) -> None:
+        if self._sink is not None:
+            self._sink(record)
+            return
+        config = self._config_getter().otel
+        if not config.enabled or self._unavailable:
+            return
+        try:
+            self._emit(config, record)
+        except Exception as e:
+            logger.warning("Failed to export approval log record: %s", e)
+
+    def _emit(self, config: OtelConfig, record: AuditRecord) -> None:
+        otel_logger = self._get_otel_logger(config)
+        if otel_logger is None:
+            return
+        from opentelemetry._logs import LogRecord, SeverityNumber
+
+        otel_logger.emit(
+            LogRecord(
+                timestamp=time.time_ns(),
+                severity_number=SeverityNumber.INFO,
+                severity_text="INFO",
+                body=record.kind.value,
+                attributes=record.attributes(),
+            )
+        )
+
+    def _get_otel_logger(self, config: OtelConfig) -> Any:
+        headers = tuple(sorted(config.headers.items()))
+        key = (config.logs_endpoint, config.service_name, headers)
+        if self._otel_logger is not None and key == self._provider_key:
+            return self._otel_logger
+        try:
+            from opentelemetry.exporter.otlp.proto.http._log_exporter import (
+                OTLPLogExporter,
+            )
+            from opentelemetry.sdk._logs import LoggerProvider
+            from opentelemetry.sdk._logs.export import BatchLogRecordProcessor
+            from opentelemetry.sdk.resources import Resource
+        except ImportError:
+            logger.warning(INSTALL_HINT)
+            self._unavailable = True
+            return None
+
+        self.shutdown()
+        provider = LoggerProvider(
+            resource=Resource.create({"service.name": config.service_name})
+        )
+        provider.add_log_record_processor(
+            BatchLogRecordProcessor(
+                OTLPLogExporter(endpoint=config.logs_endpoint, headers=config.headers)
+            )
+        )
+        self._provider = provider
+        self._provider_key = key
+        self._otel_logger = provider.get_logger(OTEL_LOGGER_NAME)
+        return self._otel_logger
+
+    def shutdown(self) -> None:
+        """Flush pending records; the provider also does it at exit."""
+        if self._provider is not None:
+            self._provider.shutdown()
+        self._provider = None
+        self._provider_key = None
+        self._otel_logger = None
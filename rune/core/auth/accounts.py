@@ -0,0 +1,362 @@
+from __future__ import annotations
+
+from enum import StrEnum, auto
+import json
+import os
+from pathlib import Path
+import re
+import shlex
+import subprocess
+import time
+
+import keyring
+import keyring.errors
+from pydantic import BaseModel, Field, ValidationError
+
+from rune.core.paths.global_paths import AUTH_FILE
+from rune.core.secrets import SecretsError, remember_secret
+
+ACCOUNT_LABEL = re.compile(r"^[A-Za-z0-9_.-]+$")
+KEYRING_SERVICE = "rune-accounts"
+CREDENTIALS_COMMAND_TIMEOUT_SECONDS = 30.0
+_FORMAT_VERSION = 1
+
+# Active accounts, credentials included, by auth file and provider, with the
+# auth file's state when they were read. Keyring and command stores are then
+# asked once per change of the file rather than on every request.
+_active_cache: dict[tuple[Path, str], tuple[tuple[int, int], Account | None]] = {}
+
+
+class AccountError(SecretsError):
+    pass
+
+
+class CredentialsStoreMode(StrEnum):
+    # In auth.json itself, readable only by its owner.
+    FILE = auto()
+    # The OS credential store: macOS Keychain, Secret Service or Windows
+    # Credential Manager.
+    KEYRING = auto()
+    # An external program, called as `<command> get|store|erase <label>`.
+    COMMAND = auto()
+
+
+class Account(BaseModel):
+    label: str
+    provider: str
+    # Empty in auth.json unless the account is stored there.
+    api_key: str = Field(default="", repr=False)
+    # Set for tokens from an OAuth login.
+    refresh_token: str | None = Field(default=None, repr=False)
+    expires_at: float | None = None
+    store: CredentialsStoreMode = CredentialsStoreMode.FILE
+    store_command: str | None = None
+    # Unix times: when the key was stored, and when the provider last accepted
+    # or turned it down.
+    created_at: float | None = None
+    validated_at: float | None = None
+    rejected_at: float | None = None
+
+    def expires_within(self, seconds: float) -> bool:
+        return self.expires_at is not None and self.expires_at - seconds <= time.time()
+
+
+class _AuthDocument(BaseModel):
+    version: int = _FORMAT_VERSION
+    accounts: list[Account] = Field(default_factory=list)
+    # Label of the account in use, by provider.
+    active: dict[str, str] = Field(default_factory=dict)
+
+
+class _Credentials(BaseModel):
+    api_key: str
+    refresh_token: str | None = None
+
+
+class AccountStore:
+    """Credentials stored with `rune login`, several per provider if need be.
+
+    `$RUNE_HOME/auth.json` lists the accounts; their keys are kept there too,
+    or in the store each account was saved to. One account per provider is
+    active. Its credentials are kept in memory until auth.json changes, so
+    switching, even from another process, takes effect in running sessions
+    too.
+    """
+
+    def __init__(
+        self,
+        path: Path | None = None,
+        *,
+        mode: CredentialsStoreMode = CredentialsStoreMode.FILE,
+        command: str = "",
+    ) -> None:
+        if mode == CredentialsStoreMode.COMMAND and not command:
+            raise AccountError(
+                "cli_auth_credentials_store_mode = 'command' needs "
+                "cli_auth_credentials_command"
+            )
+        self.path = path or AUTH_FILE.path
+        # Where new credentials go; existing ones stay where they were saved.
+        self.mode = mode
+        self.command = command
+
+    def _read(self) -> _AuthDocument:
+        try:
+            text = self.path.read_text(encoding="utf-8")
+        except FileNotFoundError:
+            return _AuthDocument()
+        except OSError as e:
+            raise AccountError(f"Could not read {self.path}: {e}") from e
+        try:
+            document = _AuthDocument.model_validate_json(text)
+        except ValidationError as e:
+            raise AccountError(f"Could not read {self.path}: {e}") from e
+        if document.version != _FORMAT_VERSION:
+            raise AccountError(f"Unsupported auth file version in {self.path}")
+        return document
+
+    def _write(self, document: _AuthDocument) -> None:
+        self.path.parent.mkdir(parents=True, exist_ok=True)
+        data = document.model_dump(mode="json", exclude_none=True)
+        fd = os.open(self.path, os.O_WRONLY | os.O_CREAT | os.O_TRUNC, 0o600)
+        with os.fdopen(fd, "w", encoding="utf-8") as f:
+            json.dump(data, f, indent=2)
+        # A switch, a refresh or a rejected key; don't wait for the mtime.
+        self.forget_cached()
+
+    def forget_cached(self) -> None:
+        """Drops the credentials kept in memory, so the next request reads
+        them again.
+        """
+        for key in [key for key in _active_cache if key[0] == self.path]:
+            _active_cache.pop(key, None)
+
+    def accounts(self) -> list[Account]:
+        """The accounts, without their credentials when stored elsewhere."""
+        return sorted(self._read().accounts, key=lambda a: (a.provider, a.label))
+
+    def active_labels(self) -> dict[str, str]:
+        return dict(self._read().active)
+
+    def add(
+        self,
+        label: str,
+        provider: str,
+        api_key: str,
+        *,
+        refresh_token: str | None = None,
+        expires_at: float | None = None,
+    ) -> Account:
+        """Stores an account and makes it the active one of its provider.
+
+        Logging in again with a known label replaces its key.
+        """
+        if not ACCOUNT_LABEL.match(label):
+            raise AccountError(
+                f"Invalid label '{label}': use letters, digits, '_', '-', '.'"
+            )
+        document = self._read()
+        if (known := _find(document, label)) and known.provider != provider:
+            raise AccountError(
+                f"'{label}' is already an account of provider '{known.provider}'"
+            )
+        account = Account(
+            label=label,
+            provider=provider,
+            api_key=api_key,
+            refresh_token=refresh_token,
+            expires_at=expires_at,
+            store=self.mode,
+            store_command=self.command
+            if self.mode == CredentialsStoreMode.COMMAND
+            else None,
+            created_at=time.time(),
+        )
+        if known is not None and known.store != account.store:
+            _erase_credentials(known)
+        listed = _save_credentials(account)
+        document.accounts = [a for a in document.accounts if a.label != label]
+        document.accounts.append(listed)
+        document.active[provider] = label
+        self._write(document)
+        return account
+
+    def switch(self, label: str) -> Account:
+        document = self._read()
+        if (account := _find(document, label)) is None:
+            raise AccountError(f"Unknown account '{label}'")
+        document.active[account.provider] = label
+        self._write(document)
+        return account
+
+    def remove(self, label: str) -> Account:
+        """Forgets an account. Another account of its provider, if there is
+        one, becomes the active one.
+        """
+        document = self._read()
+        if (account := _find(document, label)) is None:
+            raise AccountError(f"Unknown account '{label}'")
+        _erase_credentials(account)
+        document.accounts.remove(account)
+        if document.active.get(account.provider) == label:
+            others = [a for a in document.accounts if a.provider == account.provider]
+            if others:
+                document.active[account.provider] = others[0].label
+            else:
+                del document.active[account.provider]
+        self._write(document)
+        return account
+
+    def update_tokens(
+        self,
+        label: str,
+        access_token: str,
+        *,
+        refresh_token: str | None,
+        expires_at: float | None,
+    ) -> Account:
+        """Replaces the tokens of an OAuth account after a refresh, in the
+        store the account was saved to.
+        """
+        document = self._read()
+        if (account := _find(document, label)) is None:
+            raise AccountError(f"Unknown account '{label}'")
+        current = _load_credentials(account)
+        updated = current.model_copy(
+            update={
+                "api_key": access_token,
+                # Servers that don't rotate refresh tokens leave them out.
+                "refresh_token": refresh_token or current.refresh_token,
+                "expires_at": expires_at,
+            }
+        )
+        listed = _save_credentials(updated)
+        document.accounts = [listed if a is account else a for a in document.accounts]
+        self._write(document)
+        return updated
+
+    def record_validation(self, label: str, *, valid: bool) -> Account:
+        """Notes that the provider just accepted, or turned down, the key."""
+        document = self._read()
+        if (account := _find(document, label)) is None:
+            raise AccountError(f"Unknown account '{label}'")
+        field = "validated_at" if valid else "rejected_at"
+        updated = account.model_copy(update={field: time.time()})
+        document.accounts = [updated if a is account else a for a in document.accounts]
+        self._write(document)
+        return updated
+
+    def active_account(self, provider: str) -> Account | None:
+        """The active account of ``provider``, credentials included.
+
+        This may block on the keyring or a credentials command the first time
+        after auth.json changes; call it off the event loop.
+        """
+        try:
+            info = self.path.stat()
+            state = (info.st_mtime_ns, info.st_size)
+        except OSError:
+            state = (0, 0)
+        cached = _active_cache.get((self.path, provider))
+        if cached is not None and cached[0] == state:
+            return cached[1]
+        account = self._load_active(provider)
+        _active_cache[(self.path, provider)] = (state, account)
+        return account
+
+    def _load_active(self, provider: str) -> Account | None:
+        document = self._read()
+        if (label := document.active.get(provider)) is None:
+            return None
+        if (account := _find(document, label)) is None:
+            return None
+        return _load_credentials(account)
+
+    def active_api_key(self, provider: str) -> str | None:
+        if (account := self.active_account(provider)) is None:
+            return None
+        return remember_secret(account.api_key)
+
+
+def _find(document: _AuthDocument, label: str) -> Account | None:
+    return next((a for a in document.accounts if a.label == label), None)
+
+
+def _save_credentials(account: Account) -> Account:
+    """Saves the credentials of ``account`` to its store, and returns the
+    account as auth.json lists it.
+    """
+    if account.store == CredentialsStoreMode.FILE:
+        return account
+    credentials = _Credentials(
+        api_key=account.api_key, refresh_token=account.refresh_token
+    ).model_dump_json(exclude_none=True)
+    if account.store == CredentialsStoreMode.KEYRING:
+        try:
+            keyring.set_password(KEYRING_SERVICE, account.label, credentials)
+        except keyring.errors.KeyringError as e:
+            raise AccountError(f"Could not save to the system keyring: {e}") from e
+    else:
+        _run_credentials_command(account, "store", credentials)
+    return account.model_copy(update={"api_key": "", "refresh_token": None})
+
+
+def _load_credentials(account: Account) -> Account:
+    match account.store:
+        case CredentialsStoreMode.FILE:
+            return account
+        case CredentialsStoreMode.KEYRING:
+            try:
+                stored = keyring.get_password(KEYRING_SERVICE, account.label)
+            except keyring.errors.KeyringError as e:
+                raise AccountError(f"The system keyring is unavailable ({e})") from e
+        case CredentialsStoreMode.COMMAND:
+            stored = _run_credentials_command(account, "get") or None
+    if stored is None:
+        raise AccountError(
+            f"The credentials of account '{account.label}' are missing from "
+            f"its {account.store} store; log in again"
+        )
+    try:
+        credentials = _Credentials.model_validate_json(stored)
+    except ValidationError as e:
+        raise AccountError(
+            f"Unreadable credentials for account '{account.label}'"
+        ) from e
+    return account.model_copy(update=credentials.model_dump())
+
+
+def _erase_credentials(account: Account) -> None:
+    match account.store:
+        case CredentialsStoreMode.KEYRING:
+            try:
+                keyring.delete_password(KEYRING_SERVICE, account.label)
+            except keyring.errors.PasswordDeleteError:
+                pass
+            except keyring.errors.KeyringError as e:
+                raise AccountError(f"The system keyring is unavailable ({e})") from e
+        case CredentialsStoreMode.COMMAND:
+            _run_credentials_command(account, "erase")
+
+
+def _run_credentials_command(account: Account, action: str, stdin: str = "") -> str:
+    if not account.store_command:
+        raise AccountError(
+            f"Account '{account.label}' has no credentials command; log in again"
+        )
+    argv = [*shlex.split(account.store_command), action, account.label]
+    try:
+        result = subprocess.run(
+            argv,
+            input=stdin,
+            capture_output=True,
+            text=True,
+            timeout=CREDENTIALS_COMMAND_TIMEOUT_SECONDS,
+            check=False,
+        )
+    except (OSError, subprocess.TimeoutExpired) as e:
+        raise AccountError(f"Credentials command failed: {e}") from e
+    if result.returncode != 0:
+        message = result.stderr.strip() or f"exit status {result.returncode}"
+        raise AccountError(f"Credentials command failed to {action}: {message}")
+    return result.stdout.strip()
@@ -0,0 +1,163 @@
+from __future__ import annotations
+
+import asyncio
+from collections.abc import Awaitable, Callable
+from dataclasses import dataclass
+import time
+import types
+from typing import TYPE_CHECKING, Any
+
+import httpx
+
+if TYPE_CHECKING:
+    from rune.core.config import OAuthConfig
+
+DEVICE_CODE_GRANT = "urn:ietf:params:oauth:grant-type:device_code"
+# RFC 8628's defaults, for servers that leave them out.
+DEFAULT_INTERVAL_SECONDS = 5
+SLOW_DOWN_SECONDS = 5
+
+
+class DeviceFlowError(Exception):
+    pass
+
+
+@dataclass(frozen=True)
+class DeviceAuthorization:
+    device_code: str
+    user_code: str
+    verification_uri: str
+    # The URI with the code filled in, when the server offers one.
+    verification_uri_complete: str | None
+    expires_in: int
+    interval: int
+
+
+@dataclass(frozen=True)
+class OAuthToken:
+    access_token: str
+    refresh_token: str | None = None
+    # Unix time; None when the server doesn't say.
+    expires_at: float | None = None
+
+    @classmethod
+    def from_response(cls, data: dict[str, Any]) -> OAuthToken:
+        expires_in = data.get("expires_in")
+        return cls(
+            access_token=data["access_token"],
+            refresh_token=data.get("refresh_token"),
+            expires_at=time.time() + expires_in
+            if isinstance(expires_in, int | float)
+            else None,
+        )
+
+
+class DeviceCodeFlow:
+    """OAuth 2.0 device authorization (RFC 8628) against a provider's
+    authorization server, for machines without a browser: the user opens the
+    verification URI anywhere else and enters the code.
+    """
+
+    def __init__(
+        self,
+        oauth: OAuthConfig,
+        *,
+        client: httpx.AsyncClient | None = None,
+        client_options: dict[str, Any] | None = None,
+        sleep: Callable[[float], Awaitable[None]] = asyncio.sleep,
+    ) -> None:
+        self._oauth = oauth
+        self._client = client
+        # For the client made here: the provider's TLS and proxy settings.
+        self._client_options = client_options or {}
+        self._owns_client = client is None
+        self._sleep = sleep
+
+    async def __aenter__(self) -> DeviceCodeFlow:
+        if self._client is None:
+            self._client = self._new_client()
+        return self
+
+    async def __aexit__(
+        self,
+        exc_type: type[BaseException] | None,
+        exc_val: BaseException | None,
+        exc_tb: types.TracebackType | None,
+    ) -> None:
+        if self._owns_client and self._client:
+            await self._client.aclose()
+            self._client = None
+
+    def _new_client(self) -> httpx.AsyncClient:
+        options = {"timeout": httpx.Timeout(30.0)} | self._client_options
+        return httpx.AsyncClient(**options)
+
+    def _get_client(self) -> httpx.AsyncClient:
+        if self._client is None:
+            self._client = self._new_client()
+            self._owns_client = True
+        return self._client
+
+    async def _post(self, url: str, data: dict[str, str]) -> dict[str, Any]:
+        try:
+            response = await self._get_client().post(
+                url, data=data, headers={"Accept": "application/json"}
+            )
+            result = response.json()
+        except (httpx.HTTPError, ValueError) as e:
+            raise DeviceFlowError(f"Request to {url} failed: {e}") from e
+        if not isinstance(result, dict):
+            raise DeviceFlowError(f"Unexpected answer from {url}")
+        return result
+
+    async def start(self) -> DeviceAuthorization:
+        data = {"client_id": self._oauth.client_id}
+        if self._oauth.scopes:
+            data["scope"] = " ".join(self._oauth.scopes)
+        result = await self._post(self._oauth.device_authorization_url, data)
+        try:
+            return DeviceAuthorization(
+                device_code=result["device_code"],
+                user_code=result["user_code"],
+                verification_uri=result.get("verification_uri")
+                # Some servers predate the RFC's name for it.
+                or result["verification_url"],
+                verification_uri_complete=result.get("verification_uri_complete"),
+                expires_in=int(result["expires_in"]),
+                interval=int(result.get("interval", DEFAULT_INTERVAL_SECONDS)),
+            )
+        except (KeyError, TypeError, ValueError) as e:
+            message = result.get("error_description") or result.get("error") or e
+            raise DeviceFlowError(f"Could not start the login: {message}") from e
+
+    async def wait_for_token(self, authorization: DeviceAuthorization) -> OAuthToken:
+        """Polls until the user has entered the code, at the pace the server
+        asks for.
+        """
+        interval = authorization.interval
+        deadline = time.monotonic() + authorization.expires_in
+        while time.monotonic() < deadline:
+            await self._sleep(interval)
+            result = await self._post(
+                self._oauth.token_url,
+                {
+                    "client_id": self._oauth.client_id,
+                    "device_code": authorization.device_code,
+                    "grant_type": DEVICE_CODE_GRANT,
+                },
+            )
+            if "access_token" in result:
+                return OAuthToken.from_response(result)
+            match result.get("error"):
+                case "authorization_pending":
+                    continue
+                case "slow_down":
+                    interval += SLOW_DOWN_SECONDS
+                case "access_denied":
+                    raise DeviceFlowError("The login was denied")
+                case "expired_token":
+                    break
+                case error:
+                    message = result.get("error_description") or error
+                    raise DeviceFlowError(f"Login failed: {message}")
+        raise DeviceFlowError("The code expired before the login was completed")
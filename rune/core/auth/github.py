@@ -94,6 +94,15 @@ class GitHubAuthProvider:
         self.delete_token()
         return None
 
+    async def check_token(self) -> bool | None:
+        """Whether GitHub accepts the stored token, or None without one.
+
+        Unlike `get_valid_token`, a rejected token is kept.
+        """
+        if not (token := self.get_token()):
+            return None
+        return await self._is_token_valid(token)
+
     async def _is_token_valid(self, token: str) -> bool:
         client = self._get_client()
         try:
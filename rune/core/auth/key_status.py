@@ -0,0 +1,183 @@
+from __future__ import annotations
+
+from datetime import UTC, datetime
+from enum import StrEnum, auto
+import os
+import time
+from typing import TYPE_CHECKING
+
+from pydantic import BaseModel
+
+from rune.core.auth.accounts import Account, AccountError, AccountStore
+from rune.core.secrets import SecretsError, keychain_name
+
+if TYPE_CHECKING:
+    from rune.core.config import ProviderConfig
+
+# Tokens that can't be refreshed are reported this long before they expire.
+EXPIRY_WARNING_SECONDS = 24 * 3600
+# Successful requests update `validated_at` at most this often, so a busy
+# session doesn't rewrite auth.json on every turn.
+VALIDATION_RECORD_INTERVAL_SECONDS = 3600.0
+_DAY_SECONDS = 24 * 3600
+
+
+class KeySource(StrEnum):
+    ACCOUNT = auto()
+    KEYCHAIN = auto()
+    ENV = auto()
+    COMMAND = auto()
+    # The provider takes no key, e.g. a local server.
+    NONE = auto()
+
+
+class KeyState(StrEnum):
+    OK = auto()
+    MISSING = auto()
+    # The provider turned the key down the last time it was used.
+    INVALID = auto()
+    EXPIRING = auto()
+    EXPIRED = auto()
+    # Older than `api_key_rotation_days`.
+    STALE = auto()
+
+
+class KeyStatus(BaseModel):
+    provider: str
+    source: KeySource
+    state: KeyState
+    account: str | None = None
+    created_at: float | None = None
+    validated_at: float | None = None
+    expires_at: float | None = None
+    message: str = ""
+
+    @property
+    def needs_attention(self) -> bool:
+        return self.state != KeyState.OK
+
+
+def key_status(
+    provider: ProviderConfig,
+    *,
+    rotation_days: int = 0,
+    store: AccountStore | None = None,
+) -> KeyStatus:
+    """Where the provider's key comes from, and whether it is likely to work,
+    from what is known locally: nothing is sent to the provider.
+    """
+    name = provider.name
+    if keychain_name(provider.api_key):
+        try:
+            found = provider.get_api_key() is not None
+        except SecretsError as e:
+            return KeyStatus(
+                provider=name,
+                source=KeySource.KEYCHAIN,
+                state=KeyState.MISSING,
+                message=str(e),
+            )
+        state = KeyState.OK if found else KeyState.MISSING
+        return KeyStatus(provider=name, source=KeySource.KEYCHAIN, state=state)
+    try:
+        account = (store or AccountStore()).active_account(name)
+    except AccountError as e:
+        return KeyStatus(
+            provider=name,
+            source=KeySource.ACCOUNT,
+            state=KeyState.MISSING,
+            message=str(e),
+        )
+    if account is not None:
+        return _account_status(provider, account, rotation_days)
+    if provider.bearer_token_command:
+        return KeyStatus(provider=name, source=KeySource.COMMAND, state=KeyState.OK)
+    if not provider.api_key_env_var:
+        return KeyStatus(provider=name, source=KeySource.NONE, state=KeyState.OK)
+    if os.getenv(provider.api_key_env_var):
+        return KeyStatus(provider=name, source=KeySource.ENV, state=KeyState.OK)
+    return KeyStatus(
+        provider=name,
+        source=KeySource.ENV,
+        state=KeyState.MISSING,
+        message=f"{provider.api_key_env_var} is not set; run "
+        f"`rune login --provider {name}`",
+    )
+
+
+def _account_status(
+    provider: ProviderConfig, account: Account, rotation_days: int
+) -> KeyStatus:
+    now = time.time()
+    status = KeyStatus(
+        provider=provider.name,
+        source=KeySource.ACCOUNT,
+        state=KeyState.OK,
+        account=account.label,
+        created_at=account.created_at,
+        validated_at=account.validated_at,
+        expires_at=account.expires_at,
+    )
+    relogin = f"run `rune login --provider {provider.name} --label {account.label}`"
+    if account.rejected_at is not None and (
+        account.validated_at is None or account.rejected_at > account.validated_at
+    ):
+        status.state = KeyState.INVALID
+        status.message = (
+            f"{provider.name} rejected the key of account '{account.label}' on "
+            f"{_date(account.rejected_at)}; {relogin}"
+        )
+    elif account.expires_at is not None and not account.refresh_token:
+        if account.expires_at <= now:
+            status.state = KeyState.EXPIRED
+            status.message = (
+                f"The token of account '{account.label}' expired on "
+                f"{_date(account.expires_at)}; {relogin}"
+            )
+        elif account.expires_within(EXPIRY_WARNING_SECONDS):
+            status.state = KeyState.EXPIRING
+            hours = (account.expires_at - now) / 3600
+            status.message = (
+                f"The token of account '{account.label}' expires in "
+                f"{hours:.0f}h and can't be renewed; {relogin} before then"
+            )
+    if (
+        status.state == KeyState.OK
+        and rotation_days
+        and account.created_at is not None
+        and now - account.created_at > rotation_days * _DAY_SECONDS
+    ):
+        age = int((now - account.created_at) // _DAY_SECONDS)
+        status.state = KeyState.STALE
+        status.message = (
+            f"The key of account '{account.label}' was stored {age} days ago; "
+            f"rotate it and {relogin}"
+        )
+    return status
+
+
+def record_key_result(
+    provider: ProviderConfig, *, valid: bool, store: AccountStore | None = None
+) -> None:
+    """Notes on the provider's active account whether its key was just
+    accepted, so the next session can warn about a key that no longer works.
+    """
+    store = store or AccountStore()
+    labels = store.active_labels()
+    if (label := labels.get(provider.name)) is None:
+        return
+    account = next((a for a in store.accounts() if a.label == label), None)
+    if account is None:
+        return
+    if (
+        valid
+        and account.validated_at is not None
+        and (account.rejected_at or 0) < account.validated_at
+        and time.time() - account.validated_at < VALIDATION_RECORD_INTERVAL_SECONDS
+    ):
+        return
+    store.record_validation(label, valid=valid)
+
+
+def _date(timestamp: float) -> str:
+    return datetime.fromtimestamp(timestamp, UTC).strftime("%Y-%m-%d")
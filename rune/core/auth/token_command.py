@@ -0,0 +1,138 @@
+from __future__ import annotations
+
+import asyncio
+from collections.abc import AsyncGenerator, Generator
+from dataclasses import dataclass
+import shlex
+import subprocess
+import time
+
+import httpx
+
+from rune.core.secrets import SecretsError, remember_secret
+
+TOKEN_COMMAND_TIMEOUT_SECONDS = 60.0
+
+
+class TokenCommandError(SecretsError):
+    pass
+
+
+@dataclass(frozen=True)
+class _CachedToken:
+    token: str
+    fetched_at: float
+
+
+# Tokens by command, shared by every client of the process.
+_cache: dict[str, _CachedToken] = {}
+
+
+def _parse_output(command: str, returncode: int, stdout: str, stderr: str) -> str:
+    if returncode != 0:
+        message = stderr.strip() or f"exit status {returncode}"
+        raise TokenCommandError(f"bearer_token_command `{command}` failed: {message}")
+    if not (token := stdout.strip()):
+        raise TokenCommandError(f"bearer_token_command `{command}` printed no token")
+    return remember_secret(token)
+
+
+def run_token_command(command: str) -> str:
+    try:
+        result = subprocess.run(
+            shlex.split(command),
+            capture_output=True,
+            text=True,
+            timeout=TOKEN_COMMAND_TIMEOUT_SECONDS,
+            check=False,
+        )
+    except (OSError, subprocess.TimeoutExpired) as e:
+        raise TokenCommandError(f"bearer_token_command `{command}` failed: {e}") from e
+    return _parse_output(command, result.returncode, result.stdout, result.stderr)
+
+
+async def run_token_command_async(command: str) -> str:
+    try:
+        process = await asyncio.create_subprocess_exec(
+            *shlex.split(command),
+            stdout=asyncio.subprocess.PIPE,
+            stderr=asyncio.subprocess.PIPE,
+        )
+    except OSError as e:
+        raise TokenCommandError(f"bearer_token_command `{command}` failed: {e}") from e
+    try:
+        stdout, stderr = await asyncio.wait_for(
+            process.communicate(), TOKEN_COMMAND_TIMEOUT_SECONDS
+        )
+    except TimeoutError as e:
+        raise TokenCommandError(
+            f"bearer_token_command `{command}` timed out after "
+            f"{TOKEN_COMMAND_TIMEOUT_SECONDS:g}s"
+        ) from e
+    finally:
+        # Timed out or cancelled: don't leave the command running.
+        if process.returncode is None:
+            process.kill()
+            await process.wait()
+    return _parse_output(
+        command, process.returncode or 0, stdout.decode(), stderr.decode()
+    )
+
+
+class CommandTokenAuth(httpx.Auth):
+    """Sends a bearer token printed by an external command, e.g. an enterprise
+    SSO client.
+
+    The token is reused for ``ttl`` seconds. A 401 answer fetches a new one and
+    sends the request again, once.
+    """
+
+    def __init__(self, command: str, ttl: float) -> None:
+        self.command = command
+        self.ttl = ttl
+        self._lock = asyncio.Lock()
+
+    def _cached(self) -> str | None:
+        cached = _cache.get(self.command)
+        if cached is None or time.monotonic() - cached.fetched_at >= self.ttl:
+            return None
+        return cached.token
+
+    def _store(self, token: str) -> str:
+        _cache[self.command] = _CachedToken(token, time.monotonic())
+        return token
+
+    def invalidate(self, token: str) -> None:
+        if (cached := _cache.get(self.command)) and cached.token == token:
+            del _cache[self.command]
+
+    async def _token(self) -> str:
+        async with self._lock:
+            if token := self._cached():
+                return token
+            return self._store(await run_token_command_async(self.command))
+
+    def _token_sync(self) -> str:
+        return self._cached() or self._store(run_token_command(self.command))
+
+    async def async_auth_flow(
+        self, request: httpx.Request
+    ) -> AsyncGenerator[httpx.Request, httpx.Response]:
+        token = await self._token()
+        request.headers["Authorization"] = f"Bearer {token}"
+        response = yield request
+        if response.status_code == httpx.codes.UNAUTHORIZED:
+            self.invalidate(token)
+            request.headers["Authorization"] = f"Bearer {await self._token()}"
+            yield request
+
+    def sync_auth_flow(
+        self, request: httpx.Request
+    ) -> Generator[httpx.Request, httpx.Response, None]:
+        token = self._token_sync()
+        request.headers["Authorization"] = f"Bearer {token}"
+        response = yield request
+        if response.status_code == httpx.codes.UNAUTHORIZED:
+            self.invalidate(token)
+            request.headers["Authorization"] = f"Bearer {self._token_sync()}"
+            yield request
@@ -0,0 +1,94 @@
+from __future__ import annotations
+
+import asyncio
+from typing import TYPE_CHECKING
+
+import httpx
+
+from rune.core.auth.accounts import Account, AccountError, AccountStore
+from rune.core.auth.device_flow import OAuthToken
+from rune.core.llm.backend.transport import oauth_client_options
+from rune.core.secrets import remember_secret
+
+if TYPE_CHECKING:
+    from rune.core.config import OAuthConfig, ProviderConfig
+
+# Tokens are renewed this long before they expire, so a request sent just
+# before the expiry doesn't arrive just after it.
+REFRESH_MARGIN_SECONDS = 60.0
+REFRESH_TIMEOUT_SECONDS = 30.0
+
+# One refresh per account at a time; requests that wait for it then find the
+# new token.
+_locks: dict[str, asyncio.Lock] = {}
+
+
+async def refresh_oauth_token(
+    oauth: OAuthConfig, refresh_token: str, *, client: httpx.AsyncClient
+) -> OAuthToken:
+    try:
+        response = await client.post(
+            oauth.token_url,
+            data={
+                "client_id": oauth.client_id,
+                "grant_type": "refresh_token",
+                "refresh_token": refresh_token,
+            },
+            headers={"Accept": "application/json"},
+        )
+        result = response.json()
+    except (httpx.HTTPError, ValueError) as e:
+        raise AccountError(f"Could not refresh the token: {e}") from e
+    if not isinstance(result, dict) or "access_token" not in result:
+        error = result.get("error") if isinstance(result, dict) else None
+        raise AccountError(
+            f"Could not refresh the token ({error or response.status_code}); "
+            "log in again"
+        )
+    return OAuthToken.from_response(result)
+
+
+async def refresh_active_account(
+    provider: ProviderConfig, store: AccountStore | None = None
+) -> str | None:
+    """The token of the provider's active account, renewed first when it is
+    about to expire, using the provider's own OAuth settings. None when the
+    provider has no OAuth settings or no account.
+    """
+    if provider.oauth is None:
+        return None
+    store = store or AccountStore()
+    account = await asyncio.to_thread(store.active_account, provider.name)
+    if account is None:
+        return None
+    if not _needs_refresh(account):
+        return remember_secret(account.api_key)
+
+    async with _locks.setdefault(account.label, asyncio.Lock()):
+        # Another request may have refreshed it while this one waited.
+        account = await asyncio.to_thread(store.active_account, provider.name)
+        if account is None:
+            return None
+        if not _needs_refresh(account):
+            return remember_secret(account.api_key)
+        async with httpx.AsyncClient(
+            timeout=REFRESH_TIMEOUT_SECONDS,
+            **oauth_client_options(provider, provider.oauth.token_url),
+        ) as client:
+            token = await refresh_oauth_token(
+                provider.oauth, account.refresh_token, client=client
+            )
+        refreshed = await asyncio.to_thread(
+            store.update_tokens,
+            account.label,
+            token.access_token,
+            refresh_token=token.refresh_token,
+            expires_at=token.expires_at,
+        )
+    return remember_secret(refreshed.api_key)
+
+
+def _needs_refresh(account: Account) -> bool:
+    return bool(account.refresh_token) and account.expires_within(
+        REFRESH_MARGIN_SECONDS
+    )
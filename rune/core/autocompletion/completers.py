@@ -6,6 +6,7 @@ from typing import NamedTuple
 
 from rune.core.autocompletion.file_indexer import FileIndexer, IndexEntry
 from rune.core.autocompletion.fuzzy import fuzzy_match
+from rune.core.autocompletion.symbols import SymbolIndex
 
 DEFAULT_MAX_ENTRIES_TO_PROCESS = 32000
 DEFAULT_TARGET_MATCHES = 100
@@ -71,8 +72,10 @@ class PathCompleter(Completer):
         self,
         max_entries_to_process: int = DEFAULT_MAX_ENTRIES_TO_PROCESS,
         target_matches: int = DEFAULT_TARGET_MATCHES,
+        symbol_index: SymbolIndex | None = None,
     ) -> None:
         self._indexer = FileIndexer()
+        self._symbol_index = symbol_index
         self._max_entries_to_process = max_entries_to_process
         self._target_matches = target_matches
 
@@ -158,8 +161,8 @@ class PathCompleter(Completer):
 
     def _score_matches(
         self, entries: list[IndexEntry], context: _SearchContext
-    ) -> list[tuple[str, float]]:
-        scored_matches: list[tuple[str, float]] = []
+    ) -> list[tuple[str, str, float]]:
+        scored_matches: list[tuple[str, str, float]] = []
         MAX_MATCHES = 50
 
         for i, entry in enumerate(entries):
@@ -175,7 +178,7 @@ class PathCompleter(Completer):
             label = self._format_label(entry)
 
             if not context.search_pattern:
-                scored_matches.append((label, 0.0))
+                scored_matches.append((label, "", 0.0))
                 if len(scored_matches) >= self._target_matches:
                     break
                 continue
@@ -184,17 +187,41 @@ class PathCompleter(Completer):
                 context.search_pattern, entry.rel, entry.rel_lower
             )
             if match_result.matched:
-                scored_matches.append((label, match_result.score))
+                scored_matches.append((label, "", match_result.score))
                 if (
                     len(scored_matches) >= self._target_matches
                     and match_result.score > MAX_MATCHES
                 ):
                     break
 
-        scored_matches.sort(key=lambda x: (-x[1], x[0]))
+        scored_matches.sort(key=lambda x: (-x[2], x[0]))
         return scored_matches
 
-    def _collect_matches(self, text: str, cursor_pos: int) -> list[str]:
+    def _score_symbols(
+        self, entries: list[IndexEntry], context: _SearchContext
+    ) -> list[tuple[str, str, float]]:
+        if (
+            self._symbol_index is None
+            or not context.search_pattern
+            or "/" in context.search_pattern
+        ):
+            return []
+
+        pattern = context.search_pattern
+        scored_matches: list[tuple[str, str, float]] = []
+        for symbol in self._symbol_index.symbols(
+            entries[: self._max_entries_to_process]
+        ):
+            match_result = fuzzy_match(pattern, symbol.name, symbol.name.lower())
+            if match_result.matched:
+                scored_matches.append((
+                    f"@{symbol.rel}#{symbol.name}",
+                    f"{symbol.kind}, line {symbol.line}",
+                    match_result.score,
+                ))
+        return scored_matches
+
+    def _collect_matches(self, text: str, cursor_pos: int) -> list[tuple[str, str]]:
         before_cursor = text[:cursor_pos]
         partial_path = self._extract_partial(before_cursor)
         if partial_path is None:
@@ -209,14 +236,17 @@ class PathCompleter(Completer):
             return []
 
         scored_matches = self._score_matches(file_index, context)
-        return [path for path, _ in scored_matches]
+        if symbol_matches := self._score_symbols(file_index, context):
+            scored_matches = sorted(
+                [*scored_matches, *symbol_matches], key=lambda x: (-x[2], x[0])
+            )
+        return [(label, description) for label, description, _ in scored_matches]
 
     def get_completions(self, text: str, cursor_pos: int) -> list[str]:
-        return self._collect_matches(text, cursor_pos)
+        return [label for label, _ in self._collect_matches(text, cursor_pos)]
 
     def get_completion_items(self, text: str, cursor_pos: int) -> list[tuple[str, str]]:
-        matches = self._collect_matches(text, cursor_pos)
-        return [(completion, "") for completion in matches]
+        return self._collect_matches(text, cursor_pos)
 
     def get_replacement_range(
         self, text: str, cursor_pos: int
@@ -4,6 +4,8 @@ from dataclasses import dataclass
 import fnmatch
 from pathlib import Path
 
+from rune.core.ignore_files import IGNORE_FILES, read_ignore_patterns
+
 DEFAULT_IGNORE_PATTERNS: list[tuple[str, bool]] = [
     (".git/", True),
     ("__pycache__/", True),
@@ -99,23 +101,8 @@ class IgnoreRules:
                 )
             )
 
-        gitignore_path = root / ".gitignore"
-        if gitignore_path.exists():
-            try:
-                text = gitignore_path.read_text(encoding="utf-8")
-            except Exception:
-                return patterns
-
-            for line in text.splitlines():
-                raw = line.strip()
-                if not raw or raw.startswith("#"):
-                    continue
-
-                if "#" in raw:
-                    raw = raw.split("#", 1)[0].rstrip()
-                    if not raw:
-                        continue
-
+        for filename in IGNORE_FILES:
+            for raw in read_ignore_patterns(root / filename):
                 is_exclude = not raw.startswith("!")
                 if not is_exclude:
                     raw = raw[1:].lstrip()
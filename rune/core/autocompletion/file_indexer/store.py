@@ -7,6 +7,7 @@ from pathlib import Path
 
 from rune.core.autocompletion.file_indexer.ignore_rules import IgnoreRules
 from rune.core.autocompletion.file_indexer.watcher import Change
+from rune.core.ignore_files import IGNORE_FILES
 
 
 @dataclass(slots=True)
@@ -73,7 +74,12 @@ class FileIndexStore:
         if self._root is None:
             return
 
-        if len(changes) > self._mass_change_threshold:
+        if len(changes) > self._mass_change_threshold or any(
+            path.parent == self._root and path.name in IGNORE_FILES
+            for _, path in changes
+        ):
+            # Edited ignore files can hide or reveal any path.
+            self._ignore_rules.reset()
             self.rebuild(self._root)
             return
 
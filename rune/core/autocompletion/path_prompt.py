@@ -1,6 +1,6 @@
 from __future__ import annotations
 
-from dataclasses import dataclass
+from dataclasses import dataclass, replace
 from pathlib import Path
 from typing import Literal
 
@@ -10,6 +10,7 @@ class PathResource:
     path: Path
     alias: str
     kind: Literal["file", "directory"]
+    symbol: str | None = None
 
 
 @dataclass(frozen=True, slots=True)
@@ -34,6 +35,11 @@ def build_path_prompt_payload(
         if _is_path_anchor(message, pos):
             candidate, new_pos = _extract_candidate(message, pos + 1)
             if candidate and (resource := _to_resource(candidate, resolved_base)):
+                if resource.kind == "file":
+                    symbol, new_pos = _extract_symbol(message, new_pos)
+                    if symbol:
+                        resource = replace(resource, symbol=symbol)
+                        candidate = f"{candidate}#{symbol}"
                 resources.append(resource)
                 prompt_parts.append(candidate)
                 pos = new_pos
@@ -76,6 +82,20 @@ def _extract_candidate(message: str, start: int) -> tuple[str | None, int]:
     return message[start:end], end
 
 
+def _extract_symbol(message: str, start: int) -> tuple[str | None, int]:
+    if start >= len(message) or message[start] != "#":
+        return None, start
+
+    end = start + 1
+    while end < len(message) and (message[end].isalnum() or message[end] == "_"):
+        end += 1
+
+    if end == start + 1:
+        return None, start
+
+    return message[start + 1 : end], end
+
+
 def _is_path_char(char: str) -> bool:
     return char.isalnum() or char in "._/\\-()[]{}"
 
@@ -98,11 +118,12 @@ def _to_resource(candidate: str, base_dir: Path) -> PathResource | None:
 
 
 def _dedupe_resources(resources: list[PathResource]) -> list[PathResource]:
-    seen: set[Path] = set()
+    seen: set[tuple[Path, str | None]] = set()
     unique: list[PathResource] = []
     for resource in resources:
-        if resource.path in seen:
+        key = (resource.path, resource.symbol)
+        if key in seen:
             continue
-        seen.add(resource.path)
+        seen.add(key)
         unique.append(resource)
     return unique
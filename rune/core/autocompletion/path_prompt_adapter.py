@@ -1,7 +1,6 @@
 from __future__ import annotations
 
 from collections.abc import Sequence
-import mimetypes
 from pathlib import Path
 
 from rune.core.autocompletion.path_prompt import (
@@ -9,8 +8,11 @@ from rune.core.autocompletion.path_prompt import (
     PathResource,
     build_path_prompt_payload,
 )
+from rune.core.autocompletion.symbols import find_symbol_snippet
+from rune.core.tools.file_guards import is_probably_text
 
 DEFAULT_MAX_EMBED_BYTES = 256 * 1024
+DEFAULT_PREVIEW_LINES = 8
 
 ResourceBlock = dict[str, str | None]
 
@@ -34,7 +36,9 @@ def _path_prompt_to_content_blocks(
     for resource in payload.resources:
         match resource.kind:
             case "file":
-                embedded = _try_embed_text_resource(resource, max_embed_bytes)
+                embedded = _try_embed_symbol(resource) or _try_embed_text_resource(
+                    resource, max_embed_bytes
+                )
                 if embedded:
                     blocks.append(embedded)
                 else:
@@ -53,6 +57,21 @@ def _path_prompt_to_content_blocks(
     return blocks
 
 
+def _try_embed_symbol(resource: PathResource) -> ResourceBlock | None:
+    if not resource.symbol:
+        return None
+
+    snippet = find_symbol_snippet(resource.path, resource.symbol)
+    if snippet is None:
+        return None
+
+    return {
+        "type": "resource",
+        "uri": f"{resource.path.as_uri()}#L{snippet.start}-L{snippet.end}",
+        "text": snippet.text,
+    }
+
+
 def _try_embed_text_resource(
     resource: PathResource, max_embed_bytes: int | None
 ) -> ResourceBlock | None:
@@ -64,7 +83,7 @@ def _try_embed_text_resource(
     if max_embed_bytes is not None and len(data) > max_embed_bytes:
         return None
 
-    if not _is_probably_text(resource, data):
+    if not is_probably_text(resource.path.name, data):
         return None
 
     try:
@@ -75,6 +94,36 @@ def _try_embed_text_resource(
     return {"type": "resource", "uri": resource.path.as_uri(), "text": text}
 
 
+def build_mention_preview(
+    mention: str, *, base_dir: Path, max_lines: int = DEFAULT_PREVIEW_LINES
+) -> str | None:
+    """First lines of what an ``@`` completion would attach, for the popup."""
+    payload = build_path_prompt_payload(mention, base_dir=base_dir)
+    if not payload.resources:
+        return None
+
+    if (text := _preview_text(payload.resources[0])) is None:
+        return None
+
+    lines = text.splitlines()
+    if len(lines) > max_lines:
+        lines = [*lines[:max_lines], f"… {len(lines) - max_lines} more lines"]
+    return "\n".join(lines)
+
+
+def _preview_text(resource: PathResource) -> str | None:
+    if resource.kind == "directory":
+        try:
+            return "\n".join(sorted(child.name for child in resource.path.iterdir()))
+        except OSError:
+            return None
+
+    block = _try_embed_symbol(resource) or _try_embed_text_resource(
+        resource, DEFAULT_MAX_EMBED_BYTES
+    )
+    return (block.get("text") or "") if block else None
+
+
 def _content_blocks_to_prompt_text(blocks: Sequence[ResourceBlock]) -> str:
     parts = []
 
@@ -114,36 +163,3 @@ def _format_content_block(block: ResourceBlock) -> str | None:
 
         case _:
             return None
-
-
-BINARY_MIME_PREFIXES = (
-    "audio/",
-    "image/",
-    "video/",
-    "application/zip",
-    "application/x-zip-compressed",
-)
-
-
-def _is_probably_text(path: PathResource, data: bytes) -> bool:
-    mime_guess, _ = mimetypes.guess_type(path.path.name)
-    if mime_guess and mime_guess.startswith(BINARY_MIME_PREFIXES):
-        return False
-
-    if not data:
-        return True
-    if b"\x00" in data:
-        return False
-
-    DEL_CODE = 127
-    NON_PRINTABLE_MAX_PROPORTION = 0.1
-    NON_PRINTABLE_MAX_CODE = 31
-    NON_PRINTABLE_EXCEPTIONS = [9, 10, 11, 12]
-    non_text = sum(
-        1
-        for b in data
-        if b <= NON_PRINTABLE_MAX_CODE
-        and b not in NON_PRINTABLE_EXCEPTIONS
-        or b == DEL_CODE
-    )
-    return (non_text / len(data)) < NON_PRINTABLE_MAX_PROPORTION
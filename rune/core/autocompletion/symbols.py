@@ -0,0 +1,217 @@
+from __future__ import annotations
+
+from collections.abc import Iterable
+from dataclasses import dataclass
+from pathlib import Path
+import re
+from threading import Lock
+
+from rune.core.autocompletion.file_indexer import IndexEntry
+
+MAX_SYMBOL_FILE_BYTES = 512 * 1024
+MAX_SNIPPET_LINES = 200
+
+_PYTHON_DEFINITIONS = [
+    ("class", re.compile(r"^(\s*)class\s+(\w+)")),
+    ("function", re.compile(r"^(\s*)(?:async\s+)?def\s+(\w+)")),
+]
+_JS_DEFINITIONS = [
+    (
+        "class",
+        re.compile(r"^(\s*)(?:export\s+)?(?:default\s+)?(?:abstract\s+)?class\s+(\w+)"),
+    ),
+    (
+        "function",
+        re.compile(
+            r"^(\s*)(?:export\s+)?(?:default\s+)?(?:async\s+)?function\*?\s+(\w+)"
+        ),
+    ),
+    (
+        "function",
+        re.compile(r"^(\s*)(?:export\s+)?const\s+(\w+)\s*=\s*(?:async\s*)?\("),
+    ),
+    ("type", re.compile(r"^(\s*)(?:export\s+)?(?:interface|type|enum)\s+(\w+)")),
+]
+_RUST_DEFINITIONS = [
+    (
+        "function",
+        re.compile(
+            r"^(\s*)(?:pub(?:\([^)]*\))?\s+)?(?:const\s+)?(?:async\s+)?"
+            r"(?:unsafe\s+)?fn\s+(\w+)"
+        ),
+    ),
+    (
+        "type",
+        re.compile(
+            r"^(\s*)(?:pub(?:\([^)]*\))?\s+)?(?:struct|enum|trait|type)\s+(\w+)"
+        ),
+    ),
+]
+_GO_DEFINITIONS = [
+    ("function", re.compile(r"^()func\s+(?:\([^)]*\)\s*)?(\w+)")),
+    ("type", re.compile(r"^()type\s+(\w+)")),
+]
+_JVM_DEFINITIONS = [
+    (
+        "class",
+        re.compile(
+            r"^(\s*)(?:(?:public|private|protected|internal|abstract|final|"
+            r"sealed|static|data|open)\s+)*(?:class|interface|enum|record|object)"
+            r"\s+(\w+)"
+        ),
+    ),
+    ("function", re.compile(r"^(\s*)(?:\w+\s+)*fun\s+(\w+)")),
+]
+
+DEFINITION_PATTERNS: dict[str, list[tuple[str, re.Pattern[str]]]] = {
+    ".py": _PYTHON_DEFINITIONS,
+    ".pyi": _PYTHON_DEFINITIONS,
+    ".js": _JS_DEFINITIONS,
+    ".jsx": _JS_DEFINITIONS,
+    ".mjs": _JS_DEFINITIONS,
+    ".ts": _JS_DEFINITIONS,
+    ".tsx": _JS_DEFINITIONS,
+    ".rs": _RUST_DEFINITIONS,
+    ".go": _GO_DEFINITIONS,
+    ".java": _JVM_DEFINITIONS,
+    ".kt": _JVM_DEFINITIONS,
+    ".cs": _JVM_DEFINITIONS,
+}
+INDENT_SCOPED_SUFFIXES = {".py", ".pyi"}
+
+
+@dataclass(frozen=True, slots=True)
+class Symbol:
+    name: str
+    kind: str
+    rel: str
+    line: int
+
+
+@dataclass(frozen=True, slots=True)
+class SymbolSnippet:
+    start: int
+    end: int
+    text: str
+
+
+def supports_symbols(path: Path) -> bool:
+    return path.suffix in DEFINITION_PATTERNS
+
+
+def extract_symbols(text: str, suffix: str) -> list[tuple[str, str, int]]:
+    """Return ``(name, kind, line)`` for each definition found in the source."""
+    patterns = DEFINITION_PATTERNS.get(suffix)
+    if not patterns:
+        return []
+
+    found: list[tuple[str, str, int]] = []
+    for lineno, line in enumerate(text.splitlines(), start=1):
+        for kind, pattern in patterns:
+            if match := pattern.match(line):
+                found.append((match.group(2), kind, lineno))
+                break
+    return found
+
+
+def find_symbol_snippet(path: Path, name: str) -> SymbolSnippet | None:
+    try:
+        if path.stat().st_size > MAX_SYMBOL_FILE_BYTES:
+            return None
+        text = path.read_text(encoding="utf-8")
+    except (OSError, UnicodeDecodeError):
+        return None
+
+    for symbol_name, _, line in extract_symbols(text, path.suffix):
+        if symbol_name == name:
+            lines = text.splitlines()
+            end = _definition_end(lines, line - 1, path.suffix)
+            return SymbolSnippet(
+                start=line, end=end + 1, text="\n".join(lines[line - 1 : end + 1])
+            )
+    return None
+
+
+def _definition_end(lines: list[str], start: int, suffix: str) -> int:
+    limit = min(len(lines), start + MAX_SNIPPET_LINES) - 1
+    if suffix in INDENT_SCOPED_SUFFIXES:
+        return _indented_block_end(lines, start, limit)
+    return _braced_block_end(lines, start, limit)
+
+
+def _indented_block_end(lines: list[str], start: int, limit: int) -> int:
+    indent = len(lines[start]) - len(lines[start].lstrip())
+    end = start
+    for idx in range(start + 1, limit + 1):
+        line = lines[idx]
+        if not line.strip():
+            continue
+        if len(line) - len(line.lstrip()) <= indent:
+            break
+        end = idx
+    return end
+
+
+def _braced_block_end(lines: list[str], start: int, limit: int) -> int:
+    depth = 0
+    opened = False
+    for idx in range(start, limit + 1):
+        line = lines[idx]
+        depth += line.count("{") - line.count("}")
+        opened = opened or "{" in line
+        if opened and depth <= 0:
+            return idx
+        if not opened and line.rstrip().endswith(";"):
+            return idx
+    return limit
+
+
+class SymbolIndex:
+    """Top-level and nested definitions across the files of the path index.
+
+    Definitions are found with per-language patterns rather than a full parser,
+    and cached per file until its modification time changes.
+    """
+
+    def __init__(self, max_file_bytes: int = MAX_SYMBOL_FILE_BYTES) -> None:
+        self._max_file_bytes = max_file_bytes
+        self._cache: dict[Path, tuple[float, list[Symbol]]] = {}
+        self._lock = Lock()
+
+    def symbols(self, entries: Iterable[IndexEntry]) -> list[Symbol]:
+        results: list[Symbol] = []
+        seen: set[Path] = set()
+        with self._lock:
+            for entry in entries:
+                if entry.is_dir or not supports_symbols(entry.path):
+                    continue
+                seen.add(entry.path)
+                results.extend(self._symbols_for(entry))
+
+            for stale in self._cache.keys() - seen:
+                del self._cache[stale]
+        return results
+
+    def _symbols_for(self, entry: IndexEntry) -> list[Symbol]:
+        try:
+            stat = entry.path.stat()
+        except OSError:
+            return []
+
+        cached = self._cache.get(entry.path)
+        if cached and cached[0] == stat.st_mtime:
+            return cached[1]
+
+        symbols: list[Symbol] = []
+        if stat.st_size <= self._max_file_bytes:
+            try:
+                text = entry.path.read_text(encoding="utf-8")
+            except (OSError, UnicodeDecodeError):
+                text = ""
+            symbols = [
+                Symbol(name=name, kind=kind, rel=entry.rel, line=line)
+                for name, kind, line in extract_symbols(text, entry.path.suffix)
+            ]
+
+        self._cache[entry.path] = (stat.st_mtime, symbols)
+        return symbols
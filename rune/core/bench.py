@@ -0,0 +1,341 @@
+from __future__ import annotations
+
+from collections.abc import Callable, Iterator
+from contextlib import contextmanager
+import os
+from pathlib import Path
+import re
+import shutil
+import subprocess
+import tempfile
+import tomllib
+from typing import Any
+
+from pydantic import BaseModel, Field, ValidationError
+
+from rune.core.agent_loop import AgentLoop
+from rune.core.agents.models import BuiltinAgentName
+from rune.core.config import RuneConfig
+from rune.core.llm.types import BackendLike
+from rune.core.types import AssistantEvent, Role
+from rune.core.utils import is_windows
+
+DEFAULT_MAX_TURNS = 30
+CHECK_TIMEOUT_SECONDS = 600
+# Reports add a line comparing the variants when there are this many.
+_COMPARED_VARIANTS = 2
+
+
+class BenchError(Exception):
+    pass
+
+
+class BenchTask(BaseModel):
+    """A benchmark prompt. A `.md` task file is just the prompt; a `.toml` one
+    also says how to tell whether the run succeeded.
+    """
+
+    name: str
+    prompt: str
+    check: str | None = Field(
+        default=None,
+        description="Shell command run in the workspace afterwards; exit 0 is a pass.",
+    )
+    expect: list[str] = Field(
+        default_factory=list,
+        description="Regular expressions the final reply must all match.",
+    )
+    max_turns: int = Field(default=DEFAULT_MAX_TURNS, gt=0)
+
+    @classmethod
+    def load(cls, path: Path) -> BenchTask:
+        try:
+            if path.suffix == ".md":
+                return cls(name=path.stem, prompt=path.read_text(encoding="utf-8"))
+            with path.open("rb") as f:
+                return cls.model_validate({"name": path.stem, **tomllib.load(f)})
+        except (OSError, tomllib.TOMLDecodeError, ValidationError) as e:
+            raise BenchError(f"Invalid task file {path}: {e}") from e
+
+
+def load_tasks(directory: Path) -> list[BenchTask]:
+    if not directory.is_dir():
+        raise BenchError(f"Not a directory: {directory}")
+    tasks = [
+        BenchTask.load(path)
+        for path in sorted(directory.iterdir())
+        if path.suffix in {".md", ".toml"} and path.is_file()
+    ]
+    if not tasks:
+        raise BenchError(f"No .md or .toml task files in {directory}")
+    return tasks
+
+
+class Variant(BaseModel):
+    label: str
+    overrides: dict[str, Any] = Field(default_factory=dict)
+
+    @classmethod
+    def parse(cls, spec: str) -> Variant:
+        """A model alias, or a `.toml` file of config keys to override."""
+        if not spec.endswith(".toml"):
+            return cls(label=spec, overrides={"active_model": spec})
+        path = Path(spec).expanduser()
+        try:
+            with path.open("rb") as f:
+                return cls(label=path.stem, overrides=tomllib.load(f))
+        except (OSError, tomllib.TOMLDecodeError) as e:
+            raise BenchError(f"Invalid variant file {path}: {e}") from e
+
+
+class RunResult(BaseModel):
+    task: str
+    variant: str
+    success: bool
+    error: str | None = None
+    prompt_tokens: int = 0
+    completion_tokens: int = 0
+    cost: float = 0.0
+    duration: float = 0.0
+    steps: int = 0
+    tool_failures: int = 0
+
+    @property
+    def total_tokens(self) -> int:
+        return self.prompt_tokens + self.completion_tokens
+
+
+class VariantSummary(BaseModel):
+    variant: str
+    runs: int
+    successes: int
+    total_tokens: int
+    total_cost: float
+    mean_duration: float
+
+    @property
+    def success_rate(self) -> float:
+        return self.successes / self.runs if self.runs else 0.0
+
+
+class BenchReport(BaseModel):
+    variants: list[str]
+    results: list[RunResult] = Field(default_factory=list)
+
+    def summaries(self) -> list[VariantSummary]:
+        summaries = []
+        for variant in self.variants:
+            runs = [r for r in self.results if r.variant == variant]
+            summaries.append(
+                VariantSummary(
+                    variant=variant,
+                    runs=len(runs),
+                    successes=sum(r.success for r in runs),
+                    total_tokens=sum(r.total_tokens for r in runs),
+                    total_cost=sum(r.cost for r in runs),
+                    mean_duration=(
+                        sum(r.duration for r in runs) / len(runs) if runs else 0.0
+                    ),
+                )
+            )
+        return summaries
+
+
+def _last_reply(agent_loop: AgentLoop) -> str:
+    for message in reversed(agent_loop.messages):
+        if message.role == Role.assistant and message.content:
+            return message.content
+    return ""
+
+
+def _run_check(command: str, workdir: Path) -> str | None:
+    """Run a task's check command, returning why it failed, if it did."""
+    try:
+        result = subprocess.run(
+            command,
+            shell=True,
+            cwd=workdir,
+            capture_output=True,
+            stdin=subprocess.DEVNULL if is_windows() else None,
+            text=True,
+            timeout=CHECK_TIMEOUT_SECONDS,
+        )
+    except (OSError, subprocess.TimeoutExpired) as e:
+        return f"check failed to run: {e}"
+    if result.returncode != 0:
+        return f"check exited with {result.returncode}"
+    return None
+
+
+async def run_task(
+    task: BenchTask,
+    variant: Variant,
+    config: RuneConfig,
+    workdir: Path,
+    backend: BackendLike | None = None,
+) -> RunResult:
+    """Run ``task`` headlessly with auto-approval in ``workdir``, the current
+    directory, and judge the outcome.
+    """
+    agent_loop = AgentLoop(
+        config,
+        agent_name=BuiltinAgentName.AUTO_APPROVE,
+        max_turns=task.max_turns,
+        backend=backend,
+    )
+    error: str | None = None
+    try:
+        async for event in agent_loop.act(task.prompt):
+            if isinstance(event, AssistantEvent) and event.stopped_by_middleware:
+                error = f"stopped: {event.content}"
+    except Exception as e:
+        error = f"{type(e).__name__}: {e}"
+
+    if error is None:
+        reply = _last_reply(agent_loop)
+        missing = [p for p in task.expect if not re.search(p, reply, re.MULTILINE)]
+        if missing:
+            error = f"reply did not match {', '.join(missing)}"
+    if error is None and task.check:
+        error = _run_check(task.check, workdir)
+
+    stats = agent_loop.stats
+    return RunResult(
+        task=task.name,
+        variant=variant.label,
+        success=error is None,
+        error=error,
+        prompt_tokens=stats.session_prompt_tokens,
+        completion_tokens=stats.session_completion_tokens,
+        cost=stats.session_cost,
+        duration=stats.session_wall_time,
+        steps=stats.steps,
+        tool_failures=stats.tool_calls_failed,
+    )
+
+
+@contextmanager
+def isolated_workdir(source: Path) -> Iterator[Path]:
+    """A throwaway copy of ``source`` to run one task in, made current.
+
+    Git repositories get a detached worktree of HEAD, so uncommitted changes
+    are not part of it; other directories are copied.
+    """
+    previous = Path.cwd()
+    with tempfile.TemporaryDirectory(prefix="rune-bench-") as tmp:
+        workdir = Path(tmp) / source.name
+        repo = _git_toplevel(source)
+        if repo is not None:
+            _git(repo, "worktree", "add", "--detach", str(workdir), "HEAD")
+            cwd = workdir / source.resolve().relative_to(repo)
+        else:
+            shutil.copytree(source, workdir, symlinks=True)
+            cwd = workdir
+        try:
+            os.chdir(cwd)
+            yield cwd
+        finally:
+            os.chdir(previous)
+            if repo is not None:
+                _git(repo, "worktree", "remove", "--force", str(workdir))
+
+
+def _git_toplevel(path: Path) -> Path | None:
+    try:
+        return Path(_git(path, "rev-parse", "--show-toplevel")).resolve()
+    except BenchError:
+        return None
+
+
+def _git(cwd: Path, *args: str) -> str:
+    try:
+        result = subprocess.run(
+            ["git", *args],
+            cwd=cwd,
+            capture_output=True,
+            stdin=subprocess.DEVNULL if is_windows() else None,
+            text=True,
+        )
+    except OSError as e:
+        raise BenchError(f"git {args[0]} failed: {e}") from e
+    if result.returncode != 0:
+        raise BenchError(result.stderr.strip() or f"git {args[0]} failed")
+    return result.stdout.strip()
+
+
+async def run_bench(
+    tasks: list[BenchTask],
+    variants: list[Variant],
+    *,
+    source: Path,
+    repeat: int = 1,
+    isolate: bool = True,
+    load_config: Callable[[Variant], RuneConfig] | None = None,
+    backend_factory: Callable[[Variant], BackendLike | None] | None = None,
+    on_result: Callable[[RunResult], None] | None = None,
+) -> BenchReport:
+    """Run every task with every variant, ``repeat`` times, alternating variants
+    so slow periods of a provider don't all land on one of them.
+    """
+    load_config = load_config or (lambda v: RuneConfig.load(**v.overrides))
+    configs = {variant.label: load_config(variant) for variant in variants}
+    report = BenchReport(variants=[variant.label for variant in variants])
+    for task in tasks:
+        for _ in range(repeat):
+            for variant in variants:
+                backend = backend_factory(variant) if backend_factory else None
+                if isolate:
+                    with isolated_workdir(source) as workdir:
+                        result = await run_task(
+                            task, variant, configs[variant.label], workdir, backend
+                        )
+                else:
+                    result = await run_task(
+                        task, variant, configs[variant.label], source, backend
+                    )
+                report.results.append(result)
+                if on_result:
+                    on_result(result)
+    return report
+
+
+def _change(new: float, old: float) -> str:
+    if not old:
+        return "n/a"
+    return f"{(new - old) / old:+.0%}"
+
+
+def report_to_markdown(report: BenchReport) -> str:
+    lines = [
+        "| Task | Variant | Result | Tokens | Cost | Time |",
+        "| --- | --- | --- | ---: | ---: | ---: |",
+    ]
+    for r in report.results:
+        outcome = "pass" if r.success else f"fail ({r.error})"
+        lines.append(
+            f"| {r.task} | {r.variant} | {outcome} | {r.total_tokens:,} "
+            f"| ${r.cost:.4f} | {r.duration:.1f}s |"
+        )
+
+    summaries = report.summaries()
+    lines += [
+        "",
+        "| Variant | Passed | Tokens | Cost | Mean time |",
+        "| --- | ---: | ---: | ---: | ---: |",
+    ]
+    lines += [
+        f"| {s.variant} | {s.successes}/{s.runs} ({s.success_rate:.0%}) "
+        f"| {s.total_tokens:,} | ${s.total_cost:.4f} | {s.mean_duration:.1f}s |"
+        for s in summaries
+    ]
+    if len(summaries) == _COMPARED_VARIANTS:
+        base, other = summaries
+        lines += [
+            "",
+            f"{other.variant} vs {base.variant}: "
+            f"tokens {_change(other.total_tokens, base.total_tokens)}, "
+            f"cost {_change(other.total_cost, base.total_cost)}, "
+            f"time {_change(other.mean_duration, base.mean_duration)}, "
+            f"pass rate {other.success_rate - base.success_rate:+.0%} points",
+        ]
+    return "\n".join(lines) + "\n"
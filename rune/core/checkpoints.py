@@ -0,0 +1,93 @@
+from __future__ import annotations
+
+from pathlib import Path
+
+from pydantic import BaseModel, Field
+
+from rune.core.utils import logger
+
+# Tools whose edits can be rolled back, mapped to the argument holding the path.
+FILE_EDIT_TOOLS: dict[str, str] = {"write_file": "path", "search_replace": "file_path"}
+
+
+class CheckpointError(Exception):
+    pass
+
+
+class Checkpoint(BaseModel):
+    name: str
+    message_count: int
+    # Contents of each file as it was when the checkpoint was taken, captured lazily
+    # before its first edit. ``None`` means the file did not exist yet.
+    files: dict[Path, bytes | None] = Field(default_factory=dict)
+
+
+def resolve_edit_path(tool_name: str, args: dict, cwd: Path) -> Path | None:
+    if (arg := FILE_EDIT_TOOLS.get(tool_name)) is None:
+        return None
+    if not isinstance(raw := args.get(arg), str) or not raw:
+        return None
+    path = Path(raw).expanduser()
+    if not path.is_absolute():
+        path = cwd / path
+    return path.resolve()
+
+
+class CheckpointManager:
+    """Named markers in a conversation that conversation and files can return to.
+
+    Only edits made through ``FILE_EDIT_TOOLS`` are tracked; changes made by shell
+    commands are not rolled back.
+    """
+
+    def __init__(self) -> None:
+        self._checkpoints: dict[str, Checkpoint] = {}
+
+    @property
+    def names(self) -> list[str]:
+        return list(self._checkpoints)
+
+    def get(self, name: str) -> Checkpoint | None:
+        return self._checkpoints.get(name)
+
+    def create(self, name: str, message_count: int) -> Checkpoint:
+        # Re-using a name moves the marker, so drop it first to keep insertion order.
+        self._checkpoints.pop(name, None)
+        checkpoint = Checkpoint(name=name, message_count=message_count)
+        self._checkpoints[name] = checkpoint
+        return checkpoint
+
+    def record_edit(self, path: Path) -> None:
+        if not self._checkpoints:
+            return
+        try:
+            original = path.read_bytes() if path.is_file() else None
+        except OSError as e:
+            logger.warning("Could not snapshot %s for checkpoints: %s", path, e)
+            return
+        for checkpoint in self._checkpoints.values():
+            checkpoint.files.setdefault(path, original)
+
+    def restore(self, name: str) -> Checkpoint:
+        if (checkpoint := self._checkpoints.get(name)) is None:
+            raise CheckpointError(f"Checkpoint '{name}' not found.")
+
+        for path, content in checkpoint.files.items():
+            try:
+                if content is None:
+                    path.unlink(missing_ok=True)
+                else:
+                    path.parent.mkdir(parents=True, exist_ok=True)
+                    path.write_bytes(content)
+            except OSError as e:
+                raise CheckpointError(f"Failed to restore {path}: {e}") from e
+
+        # Checkpoints taken after this one point past the restored history.
+        names = list(self._checkpoints)
+        for later in names[names.index(name) + 1 :]:
+            del self._checkpoints[later]
+        checkpoint.files.clear()
+        return checkpoint
+
+    def clear(self) -> None:
+        self._checkpoints.clear()
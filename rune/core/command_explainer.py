@@ -0,0 +1,109 @@
+from __future__ import annotations
+
+from collections.abc import Callable
+from contextlib import nullcontext
+from typing import TYPE_CHECKING
+
+from pydantic import BaseModel
+
+from rune.core.llm.backend.factory import BACKEND_FACTORY
+from rune.core.prompts import UtilityPrompt
+from rune.core.tools.dry_run import CommandAssessment, assess_command, shell_command
+from rune.core.types import LLMMessage, Role
+from rune.core.utils import get_user_agent, logger
+
+if TYPE_CHECKING:
+    from rune.core.config import RuneConfig
+    from rune.core.llm.types import BackendLike
+    from rune.core.tools.base import BaseTool
+
+
+class CommandExplanation(BaseModel):
+    text: str
+    risk_level: str
+    risk_score: int
+    # Alias of the model that wrote the text, None for the offline heuristic.
+    model: str | None = None
+
+
+def offline_explanation(assessment: CommandAssessment) -> str:
+    lines = [
+        f"Classified as {', '.join(assessment.categories)} without asking a model:",
+        *(f"- {reason}" for reason in assessment.reasons),
+    ]
+    return "\n".join(lines)
+
+
+class CommandExplainer:
+    """Asks a model what a command awaiting approval does and what it risks.
+
+    When the model can't be reached, the explanation falls back to the offline
+    classification used by dry runs.
+    """
+
+    def __init__(
+        self,
+        config_getter: Callable[[], RuneConfig],
+        backend: BackendLike | None = None,
+    ) -> None:
+        self._config_getter = config_getter
+        self.backend = backend
+
+    async def explain_call(
+        self, tool: BaseTool, args: BaseModel
+    ) -> CommandExplanation | None:
+        """What a pending call's shell command does and risks, for approval
+        prompts; None when the tool doesn't run commands.
+        """
+        if (command := shell_command(tool, args)) is None:
+            return None
+        return await self.explain(command.text, powershell=command.powershell)
+
+    async def explain(
+        self, command: str, *, powershell: bool = False
+    ) -> CommandExplanation:
+        assessment = assess_command(command, powershell=powershell)
+        explanation = CommandExplanation(
+            text=offline_explanation(assessment),
+            risk_level=assessment.risk_level,
+            risk_score=assessment.risk_score,
+        )
+
+        config = self._config_getter()
+        shell = "PowerShell" if powershell else "POSIX shell"
+        try:
+            model = config.explain.get_model(config)
+            provider = config.get_provider_for_model(model)
+            context = (
+                nullcontext(self.backend)
+                if self.backend is not None
+                else BACKEND_FACTORY[provider.backend](
+                    provider=provider, timeout=config.api_timeout
+                )
+            )
+            async with context as backend:
+                result = await backend.complete(
+                    model=model,
+                    messages=[
+                        LLMMessage(
+                            role=Role.system,
+                            content=UtilityPrompt.EXPLAIN_COMMAND.read(),
+                        ),
+                        LLMMessage(
+                            role=Role.user,
+                            content=f"{shell} command:\n```\n{command}\n```",
+                        ),
+                    ],
+                    temperature=0.0,
+                    tools=None,
+                    tool_choice=None,
+                    extra_headers={"user-agent": get_user_agent(provider.backend)},
+                    max_tokens=config.explain.max_tokens,
+                )
+        except Exception as e:
+            logger.warning("Explaining command failed: %s", e)
+            return explanation
+
+        if not (text := (result.message.content or "").strip()):
+            return explanation
+        return explanation.model_copy(update={"text": text, "model": model.alias})
@@ -0,0 +1,120 @@
+from __future__ import annotations
+
+import asyncio
+from collections.abc import Callable
+from dataclasses import dataclass
+import json
+
+from rune.core.types import LLMMessage, Role
+
+# While a background summary is written, the conversation may grow to this
+# share of the model's context window (or, when that is unknown, this multiple
+# of auto_compact_threshold) before the turn waits for it.
+BACKGROUND_COMPACTION_WINDOW_SHARE = 0.9
+BACKGROUND_COMPACTION_OVERSHOOT = 1.25
+# Outputs shorter than this cost about as much as the stub replacing them.
+MIN_SUPERSEDED_LENGTH = 200
+
+
+@dataclass
+class BackgroundCompaction:
+    """A summary being written for the first ``snapshot_length`` messages."""
+
+    task: asyncio.Task[str]
+    snapshot_length: int
+    # Messages of the snapshot kept verbatim after the summary.
+    recent: list[LLMMessage]
+    tool_call_id: str
+    old_tokens: int
+
+
+def split_turns(history: list[LLMMessage]) -> list[list[LLMMessage]]:
+    """Group ``history`` (without the system message) into turns, each opened
+    by a user message, so tool calls stay with their results when turns are
+    dropped or kept.
+    """
+    turns: list[list[LLMMessage]] = []
+    for message in history:
+        if message.role == Role.user or not turns:
+            turns.append([message])
+        else:
+            turns[-1].append(message)
+    return turns
+
+
+def split_recent_turns(
+    history: list[LLMMessage], keep: int
+) -> tuple[list[LLMMessage], list[LLMMessage]]:
+    """``history`` split into older messages and its last ``keep`` turns."""
+    turns = split_turns(history)
+    cut = max(len(turns) - keep, 0)
+    older = [m for turn in turns[:cut] for m in turn]
+    recent = [m for turn in turns[cut:] for m in turn]
+    return older, recent
+
+
+def truncate_oldest(
+    history: list[LLMMessage],
+    budget: int,
+    count_tokens: Callable[[list[LLMMessage]], int],
+) -> list[LLMMessage]:
+    """The newest turns of ``history`` that fit in ``budget`` tokens.
+
+    The last turn is always kept, even when it alone is over budget.
+    """
+    turns = split_turns(history)
+    kept: list[list[LLMMessage]] = []
+    used = 0
+    for turn in reversed(turns):
+        used += count_tokens(turn)
+        if kept and used > budget:
+            break
+        kept.append(turn)
+    return [m for turn in reversed(kept) for m in turn]
+
+
+def _call_keys(history: list[LLMMessage]) -> dict[str, str]:
+    """The tool calls of ``history`` by id, keyed on their tool and arguments."""
+    keys: dict[str, str] = {}
+    for message in history:
+        for call in message.tool_calls or []:
+            if not call.id or not call.function.name:
+                continue
+            try:
+                args = json.loads(call.function.arguments or "{}")
+            except json.JSONDecodeError:
+                continue
+            keys[call.id] = f"{call.function.name}:{json.dumps(args, sort_keys=True)}"
+    return keys
+
+
+def supersede_tool_outputs(history: list[LLMMessage], latest_call_id: str) -> int:
+    """Replace the earlier outputs of the same call as ``latest_call_id`` (same
+    tool, same arguments) in ``history`` with a stub pointing at the latest
+    one. Returns how many outputs were replaced.
+
+    Re-reading a file or re-running a command makes its earlier outputs stale,
+    so only the latest is worth its context. Rewriting an earlier message
+    invalidates the provider's prompt cache from that message on, so the next
+    request pays full price for the rest of the history once.
+    """
+    keys = _call_keys(history)
+    if (key := keys.get(latest_call_id)) is None:
+        return 0
+    name = key.partition(":")[0]
+
+    superseded = 0
+    for message in history:
+        if (
+            message.role == Role.tool
+            and message.tool_call_id != latest_call_id
+            and message.tool_call_id is not None
+            and keys.get(message.tool_call_id) == key
+            and len(message.content or "") >= MIN_SUPERSEDED_LENGTH
+        ):
+            message.content = (
+                f"[Output superseded: `{name}` was called again with the same "
+                f"arguments; see the output of tool call {latest_call_id}.]"
+            )
+            superseded += 1
+    return superseded
@@ -0,0 +1,79 @@
+from __future__ import annotations
+
+from fnmatch import fnmatch
+import socket
+import sys
+from typing import Any
+
+WHEN_KEY = "when"
+# The names `os` takes, by `sys.platform`.
+_OS_NAMES = {"win32": "windows", "darwin": "macos", "linux": "linux"}
+_CONDITIONS = ("os", "hostname")
+
+
+def current_os() -> str:
+    return _OS_NAMES.get(sys.platform, sys.platform)
+
+
+def apply_when_blocks(
+    data: dict[str, Any], *, os_name: str | None = None, hostname: str | None = None
+) -> dict[str, Any]:
+    """`data` with the settings of each `[[when]]` block that matches this
+    machine merged in, in file order, and the blocks themselves removed.
+
+    A block matches when all its conditions do: `os` is one of "windows",
+    "macos" or "linux", `hostname` a glob such as "ci-*". Either can also be a
+    list of alternatives.
+    """
+    if WHEN_KEY not in data:
+        return data
+    blocks = data[WHEN_KEY]
+    if isinstance(blocks, dict):
+        blocks = [blocks]
+    if not isinstance(blocks, list) or not all(isinstance(b, dict) for b in blocks):
+        raise ValueError("'when' must be a list of [[when]] tables")
+
+    os_name = os_name or current_os()
+    hostname = (hostname or socket.gethostname()).lower()
+    resolved = {key: value for key, value in data.items() if key != WHEN_KEY}
+    for block in blocks:
+        if not _matches(block, os_name, hostname):
+            continue
+        settings = {k: v for k, v in block.items() if k not in _CONDITIONS}
+        resolved = _deep_merge(resolved, settings)
+    return resolved
+
+
+def _matches(block: dict[str, Any], os_name: str, hostname: str) -> bool:
+    for condition in _CONDITIONS:
+        if condition not in block:
+            continue
+        patterns = block[condition]
+        if isinstance(patterns, str):
+            patterns = [patterns]
+        if not isinstance(patterns, list) or not all(
+            isinstance(p, str) for p in patterns
+        ):
+            raise ValueError(f"'when.{condition}' must be a string or a list of them")
+        if condition == "os":
+            if unknown := set(patterns) - set(_OS_NAMES.values()):
+                raise ValueError(
+                    f"Unknown os {', '.join(sorted(unknown))}; "
+                    f"use {', '.join(sorted(_OS_NAMES.values()))}"
+                )
+            if os_name not in patterns:
+                return False
+        elif not any(fnmatch(hostname, p.lower()) for p in patterns):
+            return False
+    return True
+
+
+def _deep_merge(base: dict[str, Any], update: dict[str, Any]) -> dict[str, Any]:
+    # Tables merge key by key; anything else, lists included, is replaced.
+    merged = dict(base)
+    for key, value in update.items():
+        if isinstance(value, dict) and isinstance(merged.get(key), dict):
+            merged[key] = _deep_merge(merged[key], value)
+        else:
+            merged[key] = value
+    return merged
@@ -0,0 +1,274 @@
+from __future__ import annotations
+
+from collections.abc import Callable, Iterator
+from pathlib import Path
+import re
+import tomllib
+from typing import Any
+
+from pydantic import ValidationError
+import tomli_w
+
+from rune.core.config import (
+    MissingAPIKeyError,
+    OrgDefaultsSettingsSource,
+    RuneConfig,
+    TomlFileSettingsSource,
+)
+from rune.core.org_defaults import check_locked_keys
+from rune.core.paths.config_paths import CONFIG_FILE
+
+_BARE_KEY = re.compile(r"[A-Za-z0-9_-]+")
+_TABLE_HEADER = re.compile(r"^\s*\[")
+# Arrays of tables, like `[[providers]]`, have their entries addressed by
+# one of these keys: `providers.mistral.api_base`.
+_ENTRY_KEYS = ("name", "alias")
+
+
+class ConfigKeyError(ValueError):
+    pass
+
+
+def parse_value(raw: str) -> Any:
+    """``raw`` read as a TOML value, or as a string when it isn't one, so
+    `true`, `3` and `["a", "b"]` keep their types and bare words need no quotes.
+    """
+    try:
+        return tomllib.loads(f"value = {raw}")["value"]
+    except tomllib.TOMLDecodeError:
+        return raw
+
+
+def format_value(value: Any) -> str:
+    return tomli_w.dumps({"value": value}).removeprefix("value = ").rstrip("\n")
+
+
+def _split(key: str) -> list[str]:
+    parts = key.split(".")
+    if not all(parts):
+        raise ConfigKeyError(f"Invalid key '{key}'")
+    return parts
+
+
+def _step(node: Any, part: str, key: str) -> Any:
+    if isinstance(node, dict):
+        if part not in node:
+            raise ConfigKeyError(f"'{key}' is not set")
+        return node[part]
+    if isinstance(node, list):
+        for item in node:
+            if isinstance(item, dict) and _is_entry(item, part):
+                return item
+        if part.isdigit() and int(part) < len(node):
+            return node[int(part)]
+        raise ConfigKeyError(f"No entry named '{part}' in '{key}'")
+    raise ConfigKeyError(f"'{key}' is not a table")
+
+
+def _is_entry(item: dict[str, Any], name: str) -> bool:
+    return any(item.get(key) == name for key in _ENTRY_KEYS)
+
+
+def get_value(data: dict[str, Any], key: str) -> Any:
+    """The value at dotted ``key`` in ``data``."""
+    node: Any = data
+    for part in _split(key):
+        node = _step(node, part, key)
+    return node
+
+
+def set_value(text: str, key: str, value: Any) -> str:
+    """``text``, a TOML document, with ``key`` set to ``value``.
+
+    Simple keys of plain tables are edited in place, keeping the comments
+    and layout of the rest of the file; anything else rewrites the file.
+    """
+    data = tomllib.loads(text)
+    parts = _split(key)
+    expected = _with_value(data, parts, value, key)
+    # Tables don't fit on one line as tomli_w writes them.
+    if not isinstance(value, dict):
+        edited = _edit_line(text, parts, format_value(value))
+        if edited is not None and _parses_to(edited, expected):
+            return edited
+    return tomli_w.dumps(expected)
+
+
+def unset_value(text: str, key: str) -> str:
+    """``text`` without ``key``."""
+    data = tomllib.loads(text)
+    parts = _split(key)
+    parent = get_value(data, ".".join(parts[:-1])) if len(parts) > 1 else data
+    if not isinstance(parent, dict) or parts[-1] not in parent:
+        raise ConfigKeyError(f"'{key}' is not set")
+    del parent[parts[-1]]
+    edited = _edit_line(text, parts, None)
+    if edited is not None and _parses_to(edited, data):
+        return edited
+    return tomli_w.dumps(data)
+
+
+def read_config_key(key: str) -> Any:
+    """The effective value of ``key``, defaults and environment included."""
+    try:
+        config = RuneConfig.load()
+    except MissingAPIKeyError:
+        config = RuneConfig.model_construct()
+    return get_value(config.model_dump(mode="json"), key)
+
+
+def write_config_key(key: str, value: Any) -> Path:
+    """Set ``key`` in the config file. Returns the file written."""
+    _check_known(key)
+    _check_unlocked(key, value)
+    return _edit_config_file(lambda text: set_value(text, key, value))
+
+
+def remove_config_key(key: str) -> Path:
+    """Remove ``key`` from the config file, back to its default."""
+    return _edit_config_file(lambda text: unset_value(text, key))
+
+
+def _check_known(key: str) -> None:
+    # Unknown top-level keys are ignored on load, so a typo would do nothing.
+    if (top := key.split(".", 1)[0]) not in RuneConfig.model_fields:
+        raise ConfigKeyError(f"Unknown config key '{top}'")
+
+
+def _check_unlocked(key: str, value: Any) -> None:
+    # Locks overrule the user's config silently on load; saying so beats
+    # writing a value that won't apply.
+    org = OrgDefaultsSettingsSource(
+        RuneConfig, TomlFileSettingsSource(RuneConfig).toml_data
+    )
+    update = _with_value({}, _split(key), value, key)
+    check_locked_keys(org.locked, org.policy, {str(CONFIG_FILE.path): update})
+
+
+def _edit_config_file(edit: Callable[[str], str]) -> Path:
+    path = CONFIG_FILE.path
+    try:
+        old = path.read_text("utf-8")
+    except FileNotFoundError:
+        old = ""
+    try:
+        new = edit(old)
+    except tomllib.TOMLDecodeError as e:
+        raise ConfigKeyError(f"Invalid TOML in {path}: {e}") from e
+
+    path.parent.mkdir(parents=True, exist_ok=True)
+    path.write_text(new, "utf-8")
+    try:
+        RuneConfig.load()
+    except ValidationError as e:
+        path.write_text(old, "utf-8")
+        raise ConfigKeyError(f"The change would make the config invalid: {e}") from e
+    except MissingAPIKeyError:
+        pass
+    return path
+
+
+def _with_value(
+    data: dict[str, Any], parts: list[str], value: Any, key: str
+) -> dict[str, Any]:
+    node: Any = data
+    for part in parts[:-1]:
+        if isinstance(node, dict):
+            node = node.setdefault(part, {})
+        elif isinstance(node, list):
+            node = _step(node, part, key)
+        else:
+            break
+    if not isinstance(node, dict):
+        raise ConfigKeyError(f"'{key}' is inside a value that is not a table")
+    node[parts[-1]] = value
+    return data
+
+
+def _parses_to(text: str, expected: dict[str, Any]) -> bool:
+    try:
+        return tomllib.loads(text) == expected
+    except tomllib.TOMLDecodeError:
+        return False
+
+
+def _edit_line(text: str, parts: list[str], rendered: str | None) -> str | None:
+    """Set (or, with ``rendered`` None, remove) ``parts`` by editing only its
+    own line. None when the file's layout needs a full rewrite instead.
+    """
+    if not all(_BARE_KEY.fullmatch(part) for part in parts):
+        return None
+    *table, leaf = parts
+    lines = text.splitlines(keepends=True)
+    start, end = _table_region(lines, table)
+    if start is None:
+        if rendered is None:
+            return None
+        if not table:
+            return None
+        body = text.rstrip("\n")
+        separator = "\n\n" if body else ""
+        return f"{body}{separator}[{'.'.join(table)}]\n{leaf} = {rendered}\n"
+
+    key_line = re.compile(rf"^(\s*){re.escape(leaf)}\s*=(.*)$")
+    for index in range(start, end):
+        if not (match := key_line.match(lines[index].rstrip("\n"))):
+            continue
+        try:
+            # A value spanning several lines can't be replaced line by line.
+            tomllib.loads(f"value ={match.group(2)}")
+        except tomllib.TOMLDecodeError:
+            return None
+        if rendered is None:
+            del lines[index]
+        else:
+            lines[index] = f"{match.group(1)}{leaf} = {rendered}\n"
+        return "".join(lines)
+
+    if rendered is None:
+        return None
+    insert_at = end
+    while insert_at > start and not lines[insert_at - 1].strip():
+        insert_at -= 1
+    if insert_at > 0 and not lines[insert_at - 1].endswith("\n"):
+        lines[insert_at - 1] += "\n"
+    lines.insert(insert_at, f"{leaf} = {rendered}\n")
+    return "".join(lines)
+
+
+def _table_region(lines: list[str], table: list[str]) -> tuple[int | None, int]:
+    """The lines of ``table``'s own keys: from after its header to the next
+    header. The root table runs from the top of the file.
+    """
+    if not table:
+        return 0, _region_end(lines, 0)
+    for start in _headers(lines, table, array=False):
+        return start, _region_end(lines, start)
+    if len(table) == 2:
+        # `[[providers]]` entries, found by the name in their body.
+        for start in _headers(lines, table[:1], array=True):
+            end = _region_end(lines, start)
+            try:
+                entry = tomllib.loads("".join(lines[start:end]))
+            except tomllib.TOMLDecodeError:
+                continue
+            if _is_entry(entry, table[1]):
+                return start, end
+    return None, 0
+
+
+def _headers(lines: list[str], table: list[str], *, array: bool) -> Iterator[int]:
+    """The line after each header of ``table``."""
+    name = r"\s*\.\s*".join(map(re.escape, table))
+    open_, close = (r"\[\[", r"\]\]") if array else (r"\[", r"\]")
+    header = re.compile(rf"^\s*{open_}\s*{name}\s*{close}\s*(#.*)?$")
+    for index, line in enumerate(lines):
+        if header.match(line.rstrip("\n")):
+            yield index + 1
+
+
+def _region_end(lines: list[str], start: int) -> int:
+    return next(
+        (i for i in range(start, len(lines)) if _TABLE_HEADER.match(lines[i])),
+        len(lines),
+    )
@@ -0,0 +1,138 @@
+from __future__ import annotations
+
+from collections.abc import Callable, Iterator
+import copy
+from dataclasses import dataclass
+from pathlib import Path
+import tomllib
+from typing import Any
+
+# Each migration returns what it changed, as one line per key.
+Migration = Callable[[dict[str, Any]], list[str]]
+
+
+def _rename_wire_api(data: dict[str, Any]) -> list[str]:
+    changes = []
+    for provider in data.get("providers") or []:
+        if not isinstance(provider, dict) or "wire_api" not in provider:
+            continue
+        value = provider.pop("wire_api")
+        provider.setdefault("api_style", value)
+        name = provider.get("name", "?")
+        changes.append(f"providers.{name}.wire_api is now providers.{name}.api_style")
+    return changes
+
+
+# MIGRATIONS[n] upgrades a config from version n to version n + 1. Append new
+# migrations; never edit or reorder released ones.
+MIGRATIONS: list[Migration] = [_rename_wire_api]
+CONFIG_VERSION = len(MIGRATIONS)
+
+
+@dataclass(frozen=True)
+class ConfigMigration:
+    path: Path
+    backup: Path
+    from_version: int
+    changes: list[str]
+
+    def describe(self) -> str:
+        return (
+            f"Updated {self.path} to config version {CONFIG_VERSION} "
+            f"(the original is in {self.backup}): {'; '.join(self.changes)}"
+        )
+
+
+def config_version(data: dict[str, Any]) -> int:
+    version = data.get("config_version", 0)
+    return version if isinstance(version, int) else 0
+
+
+def migrate_config_data(data: dict[str, Any]) -> tuple[dict[str, Any], list[str]]:
+    """`data` brought up to `CONFIG_VERSION`, with what changed on the way."""
+    version = config_version(data)
+    if version >= CONFIG_VERSION:
+        return data, []
+    migrated = copy.deepcopy(data)
+    changes = [change for step in MIGRATIONS[version:] for change in step(migrated)]
+    migrated["config_version"] = CONFIG_VERSION
+    return migrated, changes
+
+
+def _changed_keys(old: Any, new: Any, key: str = "") -> Iterator[tuple[str, Any]]:
+    """The dotted keys that differ between `old` and `new`, each with its new
+    value, or None where the key is gone.
+    """
+    if isinstance(old, dict) and isinstance(new, dict):
+        for name in [*new, *(name for name in old if name not in new)]:
+            if old.get(name) != new.get(name):
+                child = f"{key}.{name}" if key else name
+                yield from _changed_keys(old.get(name), new.get(name), child)
+    elif (
+        isinstance(old, list)
+        and isinstance(new, list)
+        and len(old) == len(new)
+        and all(isinstance(item, dict) for item in [*old, *new])
+    ):
+        # Entries of `[[providers]]` and the like, addressed by their name.
+        for index, (before, after) in enumerate(zip(old, new, strict=True)):
+            if before != after:
+                entry = after.get("name", index)
+                yield from _changed_keys(before, after, f"{key}.{entry}")
+    else:
+        yield key, new
+
+
+def migrate_config_file(path: Path) -> ConfigMigration | None:
+    """Updates the config file at `path` for the current version, keeping the
+    original next to it. Only the migrated keys are edited, so comments and
+    layout elsewhere in the file survive.
+
+    Returns None when there was nothing to rewrite, so the warning about a
+    migration is shown only once. Files that can't be read are left for the
+    config loader to report.
+    """
+    try:
+        text = path.read_text(encoding="utf-8")
+        data = tomllib.loads(text)
+    except (OSError, tomllib.TOMLDecodeError):
+        return None
+    migrated, changes = migrate_config_data(data)
+    if not changes:
+        # Nothing deprecated; leave the file, comments and all, as it is.
+        return None
+
+    # Both import rune.core.config, which imports this module.
+    from rune.core.config_edit import set_value, unset_value
+    from rune.core.utils import logger
+
+    updated = text
+    try:
+        for key, value in _changed_keys(data, migrated):
+            if value is None:
+                updated = unset_value(updated, key)
+            else:
+                updated = set_value(updated, key, value)
+    except ValueError as e:
+        logger.warning("Could not update %s: %s", path, e)
+        return None
+
+    from_version = config_version(data)
+    backup = path.with_name(f"{path.name}.v{from_version}.bak")
+    header = "".join(f"# {change}\n" for change in changes)
+    try:
+        if not backup.exists():
+            backup.write_text(
+                f"# {path.name} before the update to config version "
+                f"{CONFIG_VERSION}:\n{header}\n{text}",
+                encoding="utf-8",
+            )
+        path.write_text(updated, encoding="utf-8")
+    except OSError as e:
+        logger.warning("Could not update %s: %s", path, e)
+        return None
+    migration = ConfigMigration(
+        path=path, backup=backup, from_version=from_version, changes=changes
+    )
+    logger.warning(migration.describe())
+    return migration
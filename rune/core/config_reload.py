@@ -0,0 +1,131 @@
+from __future__ import annotations
+
+from collections.abc import Callable
+from pathlib import Path
+from typing import Any
+
+from rune.core.config import RuneConfig, TomlFileSettingsSource
+from rune.core.model_catalog import ModelSourceWatcher
+from rune.core.paths.config_paths import project_dir
+from rune.core.paths.global_paths import GLOBAL_CONFIG_FILE
+from rune.core.types import ConfigReloadedEvent
+from rune.core.utils import logger
+
+# Keys a running session can take over. The reload rebuilds its tools and
+# skills and reconnects its MCP servers.
+LIVE_KEYS = frozenset({
+    "active_model",
+    "tools",
+    "enabled_tools",
+    "disabled_tools",
+    "enabled_skills",
+    "disabled_skills",
+    "mcp_servers",
+})
+# Reloaded on their own when `watch_models` is on.
+MODEL_LIST_KEYS = frozenset({"models", "providers", "pricing"})
+
+
+def _config_sources() -> list[Path]:
+    # A project config replaces the global one as soon as it appears.
+    return [GLOBAL_CONFIG_FILE.path, project_dir() / ".rune" / "config.toml"]
+
+
+def _read_config_file() -> dict[str, Any] | None:
+    try:
+        return TomlFileSettingsSource(RuneConfig).toml_data
+    except RuntimeError as e:
+        # Most likely saved halfway through an edit; the next save retries.
+        logger.warning("Could not read the config file: %s", e)
+        return None
+
+
+class ConfigReloader:
+    """Carries the changes made to the config file over to a running session.
+
+    Only the keys that changed on disk are taken, so what the session changed
+    for itself, such as a tool allowed for this session only, is kept.
+    """
+
+    def __init__(self, paths: Callable[[], list[Path]] = _config_sources) -> None:
+        self._watcher = ModelSourceWatcher(paths)
+        self._on_disk = _read_config_file() or {}
+
+    def reload(
+        self, current: RuneConfig
+    ) -> tuple[RuneConfig, ConfigReloadedEvent | None]:
+        if not self._watcher.changed() or (on_disk := _read_config_file()) is None:
+            return current, None
+        if on_disk == self._on_disk:
+            return current, None
+        try:
+            fresh = RuneConfig.load()
+        except Exception as e:
+            logger.warning("Could not reload the config: %s", e)
+            return current, None
+        before, self._on_disk = self._on_disk, on_disk
+        return merge_config_changes(current, fresh, before, on_disk)
+
+
+def merge_config_changes(
+    current: RuneConfig,
+    fresh: RuneConfig,
+    before: dict[str, Any],
+    after: dict[str, Any],
+) -> tuple[RuneConfig, ConfigReloadedEvent | None]:
+    """`current` with the live keys of `fresh` that changed between the file
+    contents `before` and `after`.
+    """
+    update: dict[str, Any] = {}
+    needs_restart: list[str] = []
+    for key in sorted(before.keys() | after.keys()):
+        if before.get(key) == after.get(key):
+            continue
+        if key in MODEL_LIST_KEYS and current.watch_models:
+            continue
+        if key not in LIVE_KEYS or (
+            key == "active_model"
+            and not any(model.alias == fresh.active_model for model in current.models)
+        ):
+            needs_restart.append(key)
+        elif key == "tools":
+            update[key] = _merge_tools(
+                current, fresh, before.get(key) or {}, after.get(key) or {}
+            )
+        else:
+            update[key] = getattr(fresh, key)
+
+    if not (update or needs_restart):
+        return current, None
+    updated = current.model_copy(update=update)
+    return updated, ConfigReloadedEvent(
+        applied=list(update),
+        needs_restart=needs_restart,
+        active_model=updated.active_model,
+    )
+
+
+def _merge_tools(
+    current: RuneConfig,
+    fresh: RuneConfig,
+    before: dict[str, Any],
+    after: dict[str, Any],
+) -> dict[str, Any]:
+    tools = dict(current.tools)
+    for name in before.keys() | after.keys():
+        if before.get(name) == after.get(name):
+            continue
+        if name in fresh.tools:
+            tools[name] = fresh.tools[name]
+        else:
+            tools.pop(name, None)
+    return tools
+
+
+def describe_config_reload(event: ConfigReloadedEvent) -> str:
+    text = "Config reloaded"
+    if event.applied:
+        text += f": applied {', '.join(event.applied)}"
+    if event.needs_restart:
+        text += f". Restart Rune to apply {', '.join(event.needs_restart)}"
+    return text
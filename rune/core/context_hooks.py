@@ -0,0 +1,113 @@
+from __future__ import annotations
+
+import asyncio
+from collections.abc import Callable
+import json
+from pathlib import Path
+from typing import TYPE_CHECKING, Any
+
+from rune.core.middleware import (
+    ConversationContext,
+    MiddlewareAction,
+    MiddlewareResult,
+    ResetReason,
+)
+from rune.core.types import Role
+from rune.core.utils import RUNE_CONTEXT_TAG, logger
+
+if TYPE_CHECKING:
+    from rune.core.config import ContextHookConfig
+
+
+class ContextHookError(Exception):
+    pass
+
+
+async def run_context_hook(
+    config: ContextHookConfig, payload: dict[str, Any], cwd: Path
+) -> str:
+    """The stdout of the hook's command run in ``cwd``, cut to
+    ``config.max_bytes``.
+    """
+    try:
+        proc = await asyncio.create_subprocess_exec(
+            *config.argv(),
+            cwd=cwd,
+            stdin=asyncio.subprocess.PIPE,
+            stdout=asyncio.subprocess.PIPE,
+            stderr=asyncio.subprocess.PIPE,
+        )
+    except OSError as e:
+        raise ContextHookError(f"could not start: {e}") from e
+    try:
+        stdout, stderr = await asyncio.wait_for(
+            proc.communicate(json.dumps(payload).encode()), timeout=config.timeout
+        )
+    except TimeoutError as e:
+        proc.kill()
+        await proc.wait()
+        raise ContextHookError(f"timed out after {config.timeout}s") from e
+
+    if proc.returncode != 0:
+        raise ContextHookError(
+            f"command exited with {proc.returncode}: "
+            f"{stderr.decode(errors='replace').strip()}"
+        )
+    output = stdout[: config.max_bytes].decode(errors="ignore").strip()
+    if len(stdout) > config.max_bytes:
+        output += f"\n[output cut at {config.max_bytes} bytes]"
+    return output
+
+
+class ContextHookMiddleware:
+    """Adds the output of the `[context_hooks.pre_turn]` command as a message of
+    its own after each user message, so teams can feed in ticket data, build
+    status and the like.
+
+    A failing hook is logged and skipped rather than holding up the turn.
+    """
+
+    def __init__(
+        self,
+        config_getter: Callable[[], ContextHookConfig | None],
+        session_id_getter: Callable[[], str],
+        cwd_getter: Callable[[], Path] = Path.cwd,
+    ) -> None:
+        self._config_getter = config_getter
+        self._session_id_getter = session_id_getter
+        self._cwd_getter = cwd_getter
+
+    async def before_turn(self, context: ConversationContext) -> MiddlewareResult:
+        config = self._config_getter()
+        # Later requests of the turn follow tool results; the hook ran already.
+        if (
+            config is None
+            or not context.messages
+            or context.messages[-1].role != Role.user
+        ):
+            return MiddlewareResult()
+        cwd = self._cwd_getter()
+        payload = {
+            "session_id": self._session_id_getter(),
+            "cwd": str(cwd),
+            "prompt": context.messages[-1].content or "",
+        }
+        try:
+            output = await run_context_hook(config, payload, cwd)
+        except ContextHookError as e:
+            logger.warning("Pre-turn context hook failed: %s", e)
+            return MiddlewareResult()
+        if not output:
+            return MiddlewareResult()
+        return MiddlewareResult(
+            action=MiddlewareAction.INJECT_MESSAGE,
+            metadata={
+                "context_message": f"<{RUNE_CONTEXT_TAG}>{output}</{RUNE_CONTEXT_TAG}>"
+            },
+        )
+
+    async def after_turn(self, context: ConversationContext) -> MiddlewareResult:
+        return MiddlewareResult()
+
+    def reset(self, reset_reason: ResetReason = ResetReason.STOP) -> None:
+        pass
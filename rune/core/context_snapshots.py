@@ -0,0 +1,64 @@
+from __future__ import annotations
+
+from datetime import UTC, datetime
+
+from pydantic import BaseModel, Field
+
+from rune.core.types import LLMMessage
+
+# Older snapshots are dropped past this many, as each holds a full history.
+MAX_CONTEXT_SNAPSHOTS = 20
+
+
+class ContextSnapshotError(Exception):
+    pass
+
+
+class ContextSnapshot(BaseModel):
+    id: str
+    # The conversation without its system message, which restores keep current.
+    messages: list[LLMMessage]
+    context_tokens: int = 0
+    created_at: datetime = Field(default_factory=lambda: datetime.now(UTC))
+
+    @property
+    def message_count(self) -> int:
+        return len(self.messages)
+
+
+class ContextSnapshotStore:
+    """Copies of the conversation that it can be rolled back to.
+
+    Unlike checkpoints, snapshots hold the messages themselves rather than a
+    position in them, so they outlive compaction and restoring one never
+    touches files.
+    """
+
+    def __init__(self, limit: int = MAX_CONTEXT_SNAPSHOTS) -> None:
+        self._snapshots: dict[str, ContextSnapshot] = {}
+        self._limit = limit
+        self._next_id = 1
+
+    @property
+    def snapshots(self) -> list[ContextSnapshot]:
+        return list(self._snapshots.values())
+
+    def take(self, messages: list[LLMMessage], context_tokens: int) -> ContextSnapshot:
+        snapshot = ContextSnapshot(
+            id=str(self._next_id),
+            messages=[m.model_copy(deep=True) for m in messages],
+            context_tokens=context_tokens,
+        )
+        self._next_id += 1
+        self._snapshots[snapshot.id] = snapshot
+        while len(self._snapshots) > self._limit:
+            del self._snapshots[next(iter(self._snapshots))]
+        return snapshot
+
+    def get(self, snapshot_id: str) -> ContextSnapshot:
+        if (snapshot := self._snapshots.get(snapshot_id)) is None:
+            raise ContextSnapshotError(f"Context snapshot '{snapshot_id}' not found.")
+        return snapshot
+
+    def clear(self) -> None:
+        self._snapshots.clear()
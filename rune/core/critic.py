@@ -0,0 +1,156 @@
+from __future__ import annotations
+
+from collections.abc import AsyncIterator, Callable
+from contextlib import asynccontextmanager, nullcontext
+from enum import StrEnum, auto
+import json
+from typing import TYPE_CHECKING
+
+from pydantic import BaseModel
+
+from rune.core.llm.backend.factory import BACKEND_FACTORY
+from rune.core.prompts import UtilityPrompt
+from rune.core.types import LLMMessage, Role
+from rune.core.utils import TOOL_ERROR_TAG, get_user_agent, logger, name_matches
+
+if TYPE_CHECKING:
+    from rune.core.config import RuneConfig
+    from rune.core.llm.types import BackendLike
+
+MAX_DIAGNOSTICS = 5
+
+
+class CriticVerdict(StrEnum):
+    APPROVE = auto()
+    CONCERNS = auto()
+    REJECT = auto()
+
+
+class CriticReview(BaseModel):
+    verdict: CriticVerdict
+    summary: str
+    model: str
+
+    @classmethod
+    def parse(cls, text: str, model: str) -> CriticReview:
+        first, _, rest = text.strip().partition("\n")
+        label = first.strip().removeprefix("VERDICT:").strip().lower()
+        try:
+            verdict = CriticVerdict(label)
+        except ValueError:
+            # An unparseable verdict still goes in front of the human, flagged.
+            return cls(
+                verdict=CriticVerdict.CONCERNS, summary=text.strip(), model=model
+            )
+        return cls(verdict=verdict, summary=rest.strip(), model=model)
+
+
+def _last_user_request(messages: list[LLMMessage]) -> str:
+    for msg in reversed(messages):
+        if msg.role == Role.user and msg.content:
+            return msg.content
+    return ""
+
+
+def _recent_diagnostics(messages: list[LLMMessage]) -> list[str]:
+    errors = [
+        msg.content
+        for msg in messages
+        if msg.role == Role.tool
+        and msg.content
+        and f"<{TOOL_ERROR_TAG}>" in msg.content
+    ]
+    return errors[-MAX_DIAGNOSTICS:]
+
+
+class PatchCritic:
+    """Asks a secondary model to review a proposed edit before it is approved.
+
+    The review is advisory: failures are logged and never block the tool call.
+    """
+
+    def __init__(
+        self,
+        config_getter: Callable[[], RuneConfig],
+        backend: BackendLike | None = None,
+    ) -> None:
+        self._config_getter = config_getter
+        self.backend = backend
+        # Reviews of the calls awaiting approval, by tool call id.
+        self.reviews: dict[str, CriticReview] = {}
+
+    @asynccontextmanager
+    async def reviewing(
+        self,
+        tool_name: str,
+        args: BaseModel,
+        tool_call_id: str,
+        messages: list[LLMMessage],
+    ) -> AsyncIterator[None]:
+        """Review the call, if the config asks for it, and keep the review in
+        ``reviews`` while its approval is pending.
+        """
+        if self.should_review(tool_name) and (
+            review := await self.review(tool_name, args, messages)
+        ):
+            self.reviews[tool_call_id] = review
+        try:
+            yield
+        finally:
+            self.reviews.pop(tool_call_id, None)
+
+    def should_review(self, tool_name: str) -> bool:
+        critic = self._config_getter().critic
+        return critic.enabled and name_matches(tool_name, critic.tools)
+
+    def _build_request(
+        self, tool_name: str, args: BaseModel, messages: list[LLMMessage]
+    ) -> str:
+        sections = [
+            f"## User request\n{_last_user_request(messages) or '(none)'}",
+            f"## Proposed change ({tool_name})\n"
+            f"```json\n{json.dumps(args.model_dump(mode='json'), indent=2)}\n```",
+        ]
+        if diagnostics := _recent_diagnostics(messages):
+            sections.append("## Known diagnostics\n" + "\n".join(diagnostics))
+        return "\n\n".join(sections)
+
+    async def review(
+        self, tool_name: str, args: BaseModel, messages: list[LLMMessage]
+    ) -> CriticReview | None:
+        config = self._config_getter()
+        try:
+            model = config.critic.get_model(config)
+            provider = config.get_provider_for_model(model)
+            context = (
+                nullcontext(self.backend)
+                if self.backend is not None
+                else BACKEND_FACTORY[provider.backend](
+                    provider=provider, timeout=config.api_timeout
+                )
+            )
+            async with context as backend:
+                result = await backend.complete(
+                    model=model,
+                    messages=[
+                        LLMMessage(
+                            role=Role.system, content=UtilityPrompt.CRITIC.read()
+                        ),
+                        LLMMessage(
+                            role=Role.user,
+                            content=self._build_request(tool_name, args, messages),
+                        ),
+                    ],
+                    temperature=0.0,
+                    tools=None,
+                    tool_choice=None,
+                    extra_headers={"user-agent": get_user_agent(provider.backend)},
+                    max_tokens=config.critic.max_tokens,
+                )
+        except Exception as e:
+            logger.warning("Critic review of %s failed: %s", tool_name, e)
+            return None
+
+        if not (content := result.message.content):
+            return None
+        return CriticReview.parse(content, model.alias)
@@ -0,0 +1,87 @@
+from __future__ import annotations
+
+from pathlib import Path
+import re
+import shlex
+
+from rune.core.paths.global_paths import DELIVERABLES_DIR
+from rune.core.types import DeliverableEvent, DeliverableKind
+
+_ASKED_FOR: dict[DeliverableKind, re.Pattern[str]] = {
+    DeliverableKind.COMMIT_MESSAGE: re.compile(
+        r"\bcommit\s+(?:message|msg|title)s?\b", re.IGNORECASE
+    ),
+    DeliverableKind.COMMAND: re.compile(
+        r"\b(?:command|one-?liner|invocation)s?\b", re.IGNORECASE
+    ),
+}
+_FENCED_BLOCK = re.compile(r"^```([\w+-]*)[^\n]*\n(.*?)^```", re.MULTILINE | re.DOTALL)
+SHELL_LANGUAGES = frozenset({
+    "",
+    "bash",
+    "sh",
+    "shell",
+    "zsh",
+    "fish",
+    "console",
+    "powershell",
+    "pwsh",
+    "ps1",
+    "cmd",
+    "bat",
+})
+_PROMPT = re.compile(r"^(?:\$|PS>|>)\s+")
+FILENAMES = {
+    DeliverableKind.COMMAND: "command.sh",
+    DeliverableKind.COMMIT_MESSAGE: "commit_message.txt",
+}
+
+
+def detect_deliverable(request: str, response: str) -> DeliverableEvent | None:
+    """The command or commit message ``response`` delivers for ``request``.
+
+    Only clear cases count: the user asked for one, and the reply puts it in
+    exactly one fenced block (a shell block, for commands). Anything else,
+    such as several alternatives, is left for the user to pick from.
+    """
+    blocks = _FENCED_BLOCK.findall(response)
+    if len(blocks) != 1:
+        return None
+    language, body = blocks[0][0].lower(), blocks[0][1].strip()
+    if not body:
+        return None
+
+    if _ASKED_FOR[DeliverableKind.COMMIT_MESSAGE].search(request):
+        return DeliverableEvent(
+            kind=DeliverableKind.COMMIT_MESSAGE,
+            content=body,
+            language=language or None,
+        )
+    if _ASKED_FOR[DeliverableKind.COMMAND].search(request) and (
+        language in SHELL_LANGUAGES
+    ):
+        # Prompts copied along with the command would break it when run.
+        lines = [_PROMPT.sub("", line) for line in body.splitlines()]
+        return DeliverableEvent(
+            kind=DeliverableKind.COMMAND,
+            content="\n".join(lines),
+            language=language or None,
+        )
+    return None
+
+
+def composer_text(deliverable: DeliverableEvent) -> str:
+    """Input that runs the deliverable as a shell command once submitted."""
+    if deliverable.kind == DeliverableKind.COMMIT_MESSAGE:
+        return f"!git commit -m {shlex.quote(deliverable.content)}"
+    return f"!{deliverable.content}"
+
+
+def save_deliverable(
+    deliverable: DeliverableEvent, directory: Path | None = None
+) -> Path:
+    directory = directory or DELIVERABLES_DIR.path
+    directory.mkdir(parents=True, exist_ok=True)
+    path = directory / FILENAMES[deliverable.kind]
+    path.write_text(deliverable.content + "\n", encoding="utf-8")
+    return path
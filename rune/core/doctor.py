@@ -0,0 +1,328 @@
+from __future__ import annotations
+
+from collections.abc import Callable
+from contextlib import closing
+from enum import StrEnum, auto
+import os
+from pathlib import Path
+import sqlite3
+import stat
+import sys
+
+import httpx
+from pydantic import BaseModel, ValidationError
+
+from rune.core.auth.github import GitHubAuthProvider
+from rune.core.config import (
+    Backend,
+    MissingAPIKeyError,
+    MissingPromptFileError,
+    ProviderConfig,
+    RuneConfig,
+)
+from rune.core.llm.backend.transport import TransportConfigError, client_options
+from rune.core.paths.config_paths import CONFIG_FILE
+from rune.core.paths.global_paths import (
+    GLOBAL_ENV_FILE,
+    GLOBAL_PLUGINS_DIR,
+    MEMORY_DB_FILE,
+    RUNE_HOME,
+    SECRETS_FILE,
+)
+from rune.core.secrets import SecretsError, keychain_name
+from rune.core.session.session_index import INDEX_FILENAME
+from rune.core.tools.manager import ToolManager
+from rune.core.tools.wasm import load_plugins
+from rune.core.utils import run_sync
+
+REACHABILITY_TIMEOUT_SECONDS = 5.0
+# Files holding credentials, which only their owner should read.
+_PRIVATE_FILES = (GLOBAL_ENV_FILE, SECRETS_FILE)
+
+
+class CheckStatus(StrEnum):
+    OK = auto()
+    WARN = auto()
+    FAIL = auto()
+    SKIP = auto()
+
+
+class DoctorCheck(BaseModel):
+    name: str
+    status: CheckStatus
+    detail: str
+    # What to do about a warning or failure.
+    fix: str | None = None
+
+
+def run_doctor(
+    *, online: bool = True, load_config: Callable[[], RuneConfig] = RuneConfig.load
+) -> list[DoctorCheck]:
+    """Check what Rune needs from this machine, each with a fix when it fails.
+
+    With ``online`` false, nothing is contacted and no MCP server is started.
+    """
+    checks = check_rune_home()
+    config, config_check = _load_config(load_config)
+    checks.append(config_check)
+    if config is None or not online:
+        checks.append(
+            DoctorCheck(
+                name="Providers and MCP servers",
+                status=CheckStatus.SKIP,
+                detail="Not checked offline"
+                if config is not None
+                else "Skipped until the config loads",
+            )
+        )
+    else:
+        checks.extend(check_providers(config))
+        checks.extend(check_mcp_servers(config))
+    checks.extend(check_github_auth(online))
+    checks.extend(check_databases(config))
+    if config is not None:
+        checks.extend(check_plugins(config))
+    return checks
+
+
+def check_rune_home() -> list[DoctorCheck]:
+    home = RUNE_HOME.path
+    if not home.is_dir():
+        return [
+            DoctorCheck(
+                name="Rune home",
+                status=CheckStatus.FAIL,
+                detail=f"{home} does not exist",
+                fix="Run `rune` once to create it, or point RUNE_HOME elsewhere",
+            )
+        ]
+    if not os.access(home, os.R_OK | os.W_OK | os.X_OK):
+        return [
+            DoctorCheck(
+                name="Rune home",
+                status=CheckStatus.FAIL,
+                detail=f"{home} is not readable and writable by you",
+                fix=f"chown -R $USER {home} && chmod u+rwx {home}",
+            )
+        ]
+    checks = [DoctorCheck(name="Rune home", status=CheckStatus.OK, detail=str(home))]
+    if sys.platform == "win32":
+        return checks
+    for private in _PRIVATE_FILES:
+        path = private.path
+        try:
+            mode = path.stat().st_mode
+        except OSError:
+            continue
+        if mode & (stat.S_IRWXG | stat.S_IRWXO):
+            checks.append(
+                DoctorCheck(
+                    name="Rune home",
+                    status=CheckStatus.WARN,
+                    detail=f"{path} is readable by other users",
+                    fix=f"chmod 600 {path}",
+                )
+            )
+    return checks
+
+
+def _load_config(
+    load_config: Callable[[], RuneConfig],
+) -> tuple[RuneConfig | None, DoctorCheck]:
+    name = "Config"
+    try:
+        path = CONFIG_FILE.path
+        config = load_config()
+    except MissingAPIKeyError as e:
+        return None, DoctorCheck(
+            name=name,
+            status=CheckStatus.FAIL,
+            detail=str(e),
+            fix=f"Set {e.env_key} in your environment or in {GLOBAL_ENV_FILE.path}",
+        )
+    except MissingPromptFileError as e:
+        return None, DoctorCheck(
+            name=name,
+            status=CheckStatus.FAIL,
+            detail=str(e),
+            fix="Set system_prompt_id to a built-in prompt or add the prompt file",
+        )
+    except (ValidationError, ValueError, RuntimeError) as e:
+        return None, DoctorCheck(
+            name=name,
+            status=CheckStatus.FAIL,
+            detail=" ".join(str(e).split())[:300],
+            fix="Fix the config file, or check single keys with `rune config get`",
+        )
+    return config, DoctorCheck(name=name, status=CheckStatus.OK, detail=str(path))
+
+
+def check_providers(config: RuneConfig) -> list[DoctorCheck]:
+    """Each provider a configured model uses: its API key, and whether its
+    server answers at all.
+    """
+    used = {model.provider for model in config.models}
+    return [
+        _check_provider(provider)
+        for provider in config.providers
+        if provider.name in used and provider.backend != Backend.LLAMA_CPP
+    ]
+
+
+def _check_provider(provider: ProviderConfig) -> DoctorCheck:
+    name = f"Provider {provider.name}"
+    try:
+        has_key = bool(provider.get_api_key())
+    except SecretsError as e:
+        return DoctorCheck(
+            name=name,
+            status=CheckStatus.FAIL,
+            detail=str(e),
+            fix=f"rune secrets set {keychain_name(provider.api_key)} --keychain",
+        )
+    if (provider.api_key or provider.api_key_env_var) and not has_key:
+        return DoctorCheck(
+            name=name,
+            status=CheckStatus.FAIL,
+            detail=f"{provider.api_key_env_var} is not set",
+            fix=f"Run `rune login --provider {provider.name}`, or set "
+            f"{provider.api_key_env_var} in your environment or in "
+            f"{GLOBAL_ENV_FILE.path}",
+        )
+    try:
+        # Any answer, even an error status, means the server is reachable.
+        with httpx.Client(
+            **client_options(provider, REACHABILITY_TIMEOUT_SECONDS)
+        ) as client:
+            response = client.get(provider.api_base)
+    except TransportConfigError as e:
+        return DoctorCheck(
+            name=name,
+            status=CheckStatus.FAIL,
+            detail=str(e),
+            fix="Fix the provider's connection settings in the config file",
+        )
+    except httpx.HTTPError as e:
+        return DoctorCheck(
+            name=name,
+            status=CheckStatus.FAIL,
+            detail=f"{provider.api_base} is unreachable: {e}",
+            fix="Check that the server is running and api_base is right; behind "
+            "a proxy, set network.proxy_url or HTTPS_PROXY",
+        )
+    return DoctorCheck(
+        name=name,
+        status=CheckStatus.OK,
+        detail=f"{provider.api_base} answered with HTTP {response.status_code}",
+    )
+
+
+def check_mcp_servers(config: RuneConfig) -> list[DoctorCheck]:
+    if not config.mcp_servers:
+        return []
+    statuses = ToolManager(lambda: config).mcp_server_status
+    checks = []
+    for server in config.mcp_servers:
+        status = statuses.get(server.name, "error: not started")
+        failed = status.startswith("error") or status == "unsupported transport"
+        checks.append(
+            DoctorCheck(
+                name=f"MCP server {server.name}",
+                status=CheckStatus.FAIL if failed else CheckStatus.OK,
+                detail=status,
+                fix=(
+                    "Run the server's command by hand to see why it fails, or "
+                    "raise startup_timeout_sec"
+                    if failed
+                    else None
+                ),
+            )
+        )
+    return checks
+
+
+def check_github_auth(online: bool) -> list[DoctorCheck]:
+    auth = GitHubAuthProvider()
+    if not auth.has_token():
+        return []
+    if not online:
+        return [
+            DoctorCheck(
+                name="GitHub sign-in",
+                status=CheckStatus.SKIP,
+                detail="Token stored; not checked offline",
+            )
+        ]
+    if run_sync(_check_token(auth)):
+        return [
+            DoctorCheck(name="GitHub sign-in", status=CheckStatus.OK, detail="Valid")
+        ]
+    return [
+        DoctorCheck(
+            name="GitHub sign-in",
+            status=CheckStatus.WARN,
+            detail="GitHub rejected the stored token, or could not be reached",
+            fix="The token was revoked or expired; /teleport asks you to sign "
+            "in again",
+        )
+    ]
+
+
+async def _check_token(auth: GitHubAuthProvider) -> bool | None:
+    async with auth:
+        return await auth.check_token()
+
+
+def check_databases(config: RuneConfig | None) -> list[DoctorCheck]:
+    """An integrity check of Rune's SQLite databases that exist."""
+    paths = {"Memory database": MEMORY_DB_FILE.path}
+    if config is not None:
+        paths["Session index"] = Path(config.session_logging.save_dir) / INDEX_FILENAME
+    checks = []
+    for name, path in paths.items():
+        if not path.is_file():
+            continue
+        try:
+            with closing(sqlite3.connect(f"file:{path}?mode=ro", uri=True)) as db:
+                (result,) = db.execute("PRAGMA quick_check").fetchone()
+        except sqlite3.Error as e:
+            result = str(e)
+        if result == "ok":
+            checks.append(
+                DoctorCheck(name=name, status=CheckStatus.OK, detail=str(path))
+            )
+            continue
+        checks.append(
+            DoctorCheck(
+                name=name,
+                status=CheckStatus.FAIL,
+                detail=f"{path}: {result}",
+                fix=(
+                    f"Delete {path}; it is rebuilt from the session logs"
+                    if name == "Session index"
+                    else f"Restore {path} from a backup, or move it aside to "
+                    "start with no memories"
+                ),
+            )
+        )
+    return checks
+
+
+def check_plugins(config: RuneConfig) -> list[DoctorCheck]:
+    checks = []
+    directory = GLOBAL_PLUGINS_DIR.path
+    for loaded in load_plugins(directory, config.plugins):
+        name = f"Plugin {loaded.name}"
+        if loaded.error is not None:
+            checks.append(
+                DoctorCheck(
+                    name=name,
+                    status=CheckStatus.FAIL,
+                    detail=loaded.error,
+                    fix=f"Rebuild or remove {directory / loaded.name}.wasm",
+                )
+            )
+            continue
+        tools = ", ".join(spec.name for spec in loaded.tools) or "no tools"
+        checks.append(DoctorCheck(name=name, status=CheckStatus.OK, detail=tools))
+    return checks
@@ -0,0 +1,112 @@
+from __future__ import annotations
+
+from concurrent.futures import ThreadPoolExecutor
+from functools import lru_cache
+import os
+from pathlib import Path
+import platform
+import re
+import shutil
+import subprocess
+
+from pydantic import BaseModel, Field
+
+from rune.core.utils import is_windows
+
+PROBE_TIMEOUT_SECONDS = 3.0
+# Toolchains worth telling the model about, and how to ask their version.
+TOOLCHAINS: dict[str, tuple[str, ...]] = {
+    "git": ("git", "--version"),
+    "python": ("python" if is_windows() else "python3", "--version"),
+    "node": ("node", "--version"),
+    "rustc": ("rustc", "--version"),
+    "cargo": ("cargo", "--version"),
+    "go": ("go", "version"),
+    "java": ("java", "-version"),
+    "docker": ("docker", "--version"),
+}
+_VERSION = re.compile(r"\d+\.\d+(?:\.\d+)?")
+_CONTAINER_CGROUPS = ("docker", "kubepods", "containerd", "lxc", "podman")
+
+
+class EnvironmentDescriptor(BaseModel):
+    """What the machine Rune runs on looks like, collected once per process so
+    the model doesn't have to probe it with `uname` or `--version`.
+    """
+
+    os: str
+    os_version: str
+    arch: str
+    # e.g. "docker", "podman", "kubernetes" or "wsl"; None on a plain host.
+    container: str | None = None
+    toolchains: dict[str, str] = Field(default_factory=dict)
+    missing: list[str] = Field(default_factory=list)
+
+    def render(self) -> str:
+        host = f"a {self.container} container" if self.container else "the host"
+        lines = [
+            "# Environment",
+            "",
+            f"- OS: {self.os} {self.os_version} ({self.arch}), running on {host}",
+        ]
+        if self.toolchains:
+            found = ", ".join(f"{k} {v}" for k, v in self.toolchains.items())
+            lines.append(f"- Installed: {found}")
+        if self.missing:
+            lines.append(f"- Not installed: {', '.join(self.missing)}")
+        lines += [
+            "",
+            "These were checked when the session started; don't run `uname` or "
+            "`--version` commands to find them out again.",
+        ]
+        return "\n".join(lines)
+
+
+@lru_cache(maxsize=1)
+def collect_environment() -> EnvironmentDescriptor:
+    with ThreadPoolExecutor(max_workers=len(TOOLCHAINS)) as pool:
+        versions = dict(zip(TOOLCHAINS, pool.map(_probe_version, TOOLCHAINS.values())))
+    return EnvironmentDescriptor(
+        os=platform.system() or "Unknown",
+        os_version=platform.release(),
+        arch=platform.machine() or "unknown",
+        container=detect_container(),
+        toolchains={name: v for name, v in versions.items() if v is not None},
+        missing=[name for name, v in versions.items() if v is None],
+    )
+
+
+def detect_container() -> str | None:
+    if os.environ.get("KUBERNETES_SERVICE_HOST"):
+        return "kubernetes"
+    if Path("/run/.containerenv").exists():
+        return "podman"
+    if Path("/.dockerenv").exists():
+        return "docker"
+    try:
+        cgroup = Path("/proc/1/cgroup").read_text(encoding="utf-8")
+    except OSError:
+        cgroup = ""
+    if name := next((n for n in _CONTAINER_CGROUPS if n in cgroup), None):
+        return name
+    if "microsoft" in platform.release().lower():
+        return "wsl"
+    return None
+
+
+def _probe_version(command: tuple[str, ...]) -> str | None:
+    if (program := shutil.which(command[0])) is None:
+        return None
+    try:
+        result = subprocess.run(
+            [program, *command[1:]],
+            capture_output=True,
+            text=True,
+            timeout=PROBE_TIMEOUT_SECONDS,
+            check=False,
+        )
+    except (OSError, subprocess.SubprocessError):
+        return "unknown"
+    # Some tools, such as java, print their version to stderr.
+    match = _VERSION.search(result.stdout or result.stderr)
+    return match.group(0) if match else "unknown"
@@ -0,0 +1,25 @@
+from __future__ import annotations
+
+from pathlib import Path
+
+RUNEIGNORE_FILE = ".runeignore"
+# Read in this order, so a `.runeignore` negation can bring back a path that
+# `.gitignore` excludes.
+IGNORE_FILES = (".gitignore", RUNEIGNORE_FILE)
+
+
+def read_ignore_patterns(path: Path) -> list[str]:
+    """The patterns of a gitignore-style file, without blanks and comments.
+
+    Trailing comments are stripped; a missing or unreadable file has none.
+    """
+    try:
+        text = path.read_text(encoding="utf-8")
+    except (OSError, UnicodeDecodeError):
+        return []
+
+    patterns = []
+    for line in text.splitlines():
+        if raw := line.split("#", 1)[0].strip():
+            patterns.append(raw)
+    return patterns
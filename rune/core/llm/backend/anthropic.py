@@ -0,0 +1,334 @@
+from __future__ import annotations
+
+import json
+from typing import TYPE_CHECKING, Any, ClassVar
+
+from rune.core.llm.backend.generic import (
+    BACKEND_ADAPTERS,
+    APIAdapter,
+    PreparedRequest,
+    parse_tool_arguments,
+    register_adapter,
+)
+from rune.core.types import (
+    AvailableTool,
+    FunctionCall,
+    LLMChunk,
+    LLMMessage,
+    LLMUsage,
+    Role,
+    StrToolChoice,
+    ToolCall,
+)
+
+if TYPE_CHECKING:
+    from rune.core.config import ProviderConfig
+
+ANTHROPIC_VERSION = "2023-06-01"
+# The Messages API requires max_tokens on every request.
+DEFAULT_MAX_TOKENS = 8192
+CACHE_CONTROL = {"type": "ephemeral"}
+# Breakpoints a request may carry; one is kept for the conversation.
+MAX_CACHE_POINTS = 4
+
+
+@register_adapter(BACKEND_ADAPTERS, "anthropic")
+class AnthropicAdapter(APIAdapter):
+    """Anthropic Messages API, streamed or not.
+
+    Tool calls map to ``tool_use`` blocks and tool results to ``tool_result``
+    blocks in the following user turn. Streamed tool input arrives as partial
+    JSON and is accumulated like chat-completions argument deltas, keyed by the
+    content block index.
+    """
+
+    endpoint: ClassVar[str] = "/messages"
+    supports_prefill: ClassVar[bool] = True
+
+    def prepare_request(
+        self,
+        *,
+        model_name: str,
+        messages: list[LLMMessage],
+        temperature: float,
+        tools: list[AvailableTool] | None,
+        max_tokens: int | None,
+        tool_choice: StrToolChoice | AvailableTool | None,
+        enable_streaming: bool,
+        provider: ProviderConfig,
+        api_key: str | None = None,
+        output_schema: dict[str, Any] | None = None,
+        reasoning_effort: str | None = None,
+    ) -> PreparedRequest:
+        system, converted_messages = self._convert_messages(
+            messages, cache=provider.prompt_caching
+        )
+
+        payload: dict[str, Any] = {
+            "model": model_name,
+            "messages": converted_messages,
+            "max_tokens": max_tokens or DEFAULT_MAX_TOKENS,
+            "temperature": temperature,
+        }
+        if system:
+            payload["system"] = system
+        if tools:
+            payload["tools"] = [
+                {
+                    "name": tool.function.name,
+                    "description": tool.function.description,
+                    "input_schema": tool.function.parameters,
+                }
+                for tool in tools
+            ]
+            if converted_choice := self._convert_tool_choice(tool_choice):
+                payload["tool_choice"] = converted_choice
+        if enable_streaming:
+            payload["stream"] = True
+
+        headers = {
+            "Content-Type": "application/json",
+            "anthropic-version": ANTHROPIC_VERSION,
+        }
+        if api_key:
+            headers["x-api-key"] = api_key
+
+        body = json.dumps(payload, ensure_ascii=False).encode("utf-8")
+        return PreparedRequest(self.endpoint, headers, body)
+
+    def _convert_tool_choice(
+        self, tool_choice: StrToolChoice | AvailableTool | None
+    ) -> dict[str, str] | None:
+        match tool_choice:
+            case "auto" | "none":
+                return {"type": tool_choice}
+            case "any" | "required":
+                return {"type": "any"}
+            case AvailableTool():
+                return {"type": "tool", "name": tool_choice.function.name}
+            case _:
+                return None
+
+    def _convert_messages(
+        self, messages: list[LLMMessage], *, cache: bool = False
+    ) -> tuple[str | list[dict[str, Any]], list[dict[str, Any]]]:
+        system_messages: list[LLMMessage] = []
+        converted: list[dict[str, Any]] = []
+
+        for msg in messages:
+            if msg.role == Role.system:
+                if msg.content:
+                    system_messages.append(msg)
+                continue
+
+            role, blocks = self._message_blocks(msg)
+            if not blocks:
+                continue
+            # Roles must alternate, so consecutive turns of one role (e.g. several
+            # tool results) are merged into a single message.
+            if converted and converted[-1]["role"] == role:
+                converted[-1]["content"].extend(blocks)
+            else:
+                converted.append({"role": role, "content": blocks})
+
+        if not cache:
+            return "\n\n".join(m.content or "" for m in system_messages), converted
+        # The conversation so far is cached too, so the next request of a long
+        # thread only pays full price for what was added since.
+        if converted:
+            converted[-1]["content"][-1]["cache_control"] = CACHE_CONTROL
+        return self._system_blocks(system_messages), converted
+
+    def _system_blocks(
+        self, system_messages: list[LLMMessage]
+    ) -> str | list[dict[str, Any]]:
+        """The system prompt split at the cache points its builder marked."""
+        text = "\n\n".join(m.content or "" for m in system_messages)
+        if not any(m.cache_breaks for m in system_messages):
+            return text
+
+        breaks: list[int] = []
+        offset = 0
+        for msg in system_messages:
+            breaks += [offset + b for b in msg.cache_breaks]
+            offset += len(msg.content or "") + 2
+        breaks = sorted({b for b in breaks if 0 < b <= len(text)})
+        breaks = breaks[-(MAX_CACHE_POINTS - 1) :]
+
+        blocks: list[dict[str, Any]] = []
+        start = 0
+        for end in breaks:
+            if segment := text[start:end]:
+                blocks.append(
+                    {"type": "text", "text": segment, "cache_control": CACHE_CONTROL}
+                )
+            start = end
+        if rest := text[start:]:
+            blocks.append({"type": "text", "text": rest})
+        return blocks
+
+    def _message_blocks(self, msg: LLMMessage) -> tuple[str, list[dict[str, Any]]]:
+        match msg.role:
+            case Role.tool:
+                content: str | list[dict[str, Any]] = msg.content or ""
+                if msg.images:
+                    content = [
+                        {"type": "text", "text": msg.content or ""},
+                        *_image_blocks(msg),
+                    ]
+                return "user", [
+                    {
+                        "type": "tool_result",
+                        "tool_use_id": msg.tool_call_id,
+                        "content": content,
+                    }
+                ]
+            case Role.assistant:
+                blocks: list[dict[str, Any]] = []
+                if msg.content:
+                    blocks.append({"type": "text", "text": msg.content})
+                for tool_call in msg.tool_calls or []:
+                    blocks.append({
+                        "type": "tool_use",
+                        "id": tool_call.id,
+                        "name": tool_call.function.name,
+                        "input": parse_tool_arguments(tool_call.function.arguments),
+                    })
+                return "assistant", blocks
+            case _:
+                blocks = [{"type": "text", "text": msg.content}] if msg.content else []
+                return "user", blocks + _image_blocks(msg)
+
+    def parse_response(
+        self, data: dict[str, Any], provider: ProviderConfig
+    ) -> LLMChunk:
+        if "type" in data and data["type"] != "message":
+            return self._parse_stream_event(data)
+
+        content: list[str] = []
+        reasoning: list[str] = []
+        tool_calls: list[ToolCall] = []
+        for index, block in enumerate(data.get("content") or []):
+            match block.get("type"):
+                case "text":
+                    content.append(block.get("text", ""))
+                case "thinking":
+                    reasoning.append(block.get("thinking", ""))
+                case "tool_use":
+                    tool_calls.append(
+                        ToolCall(
+                            id=block.get("id"),
+                            index=index,
+                            function=FunctionCall(
+                                name=block.get("name"),
+                                arguments=json.dumps(block.get("input") or {}),
+                            ),
+                        )
+                    )
+
+        return LLMChunk(
+            message=LLMMessage(
+                role=Role.assistant,
+                content="".join(content),
+                reasoning_content="".join(reasoning) or None,
+                tool_calls=tool_calls or None,
+            ),
+            usage=_usage(data.get("usage") or {}),
+        )
+
+    def _parse_stream_event(self, data: dict[str, Any]) -> LLMChunk:
+        message = LLMMessage(role=Role.assistant, content="")
+        usage: LLMUsage | None = None
+
+        match data["type"]:
+            # Input tokens are reported once up front and the running output
+            # count at the end, so each is taken from one event only.
+            case "message_start":
+                start_usage = _usage(data.get("message", {}).get("usage") or {})
+                usage = LLMUsage(
+                    prompt_tokens=start_usage.prompt_tokens,
+                    cached_tokens=start_usage.cached_tokens,
+                )
+            case "message_delta":
+                usage = LLMUsage(
+                    completion_tokens=(data.get("usage") or {}).get("output_tokens", 0)
+                )
+            case "content_block_start":
+                block = data.get("content_block") or {}
+                if block.get("type") == "tool_use":
+                    message = LLMMessage(
+                        role=Role.assistant,
+                        content="",
+                        tool_calls=[
+                            ToolCall(
+                                id=block.get("id"),
+                                index=data.get("index", 0),
+                                function=FunctionCall(
+                                    name=block.get("name"), arguments=""
+                                ),
+                            )
+                        ],
+                    )
+            case "content_block_delta":
+                message = self._parse_delta(data)
+            case "error":
+                error = data.get("error") or {}
+                raise ValueError(
+                    f"Anthropic stream error: {error.get('message', error)}"
+                )
+
+        return LLMChunk(message=message, usage=usage)
+
+    def _parse_delta(self, data: dict[str, Any]) -> LLMMessage:
+        delta = data.get("delta") or {}
+        match delta.get("type"):
+            case "text_delta":
+                return LLMMessage(role=Role.assistant, content=delta.get("text", ""))
+            case "thinking_delta":
+                return LLMMessage(
+                    role=Role.assistant,
+                    content="",
+                    reasoning_content=delta.get("thinking", ""),
+                )
+            case "input_json_delta":
+                return LLMMessage(
+                    role=Role.assistant,
+                    content="",
+                    tool_calls=[
+                        ToolCall(
+                            index=data.get("index", 0),
+                            function=FunctionCall(
+                                arguments=delta.get("partial_json", "")
+                            ),
+                        )
+                    ],
+                )
+            case _:
+                return LLMMessage(role=Role.assistant, content="")
+
+
+def _usage(usage: dict[str, Any]) -> LLMUsage:
+    prompt_tokens = (
+        usage.get("input_tokens", 0)
+        + usage.get("cache_creation_input_tokens", 0)
+        + usage.get("cache_read_input_tokens", 0)
+    )
+    return LLMUsage(
+        prompt_tokens=prompt_tokens,
+        completion_tokens=usage.get("output_tokens", 0),
+        cached_tokens=usage.get("cache_read_input_tokens", 0),
+    )
+
+
+def _image_blocks(msg: LLMMessage) -> list[dict[str, Any]]:
+    return [
+        {
+            "type": "image",
+            "source": {
+                "type": "base64",
+                "media_type": image.media_type,
+                "data": image.data,
+            },
+        }
+        for image in msg.images
+    ]
@@ -1,7 +1,15 @@
 from __future__ import annotations
 
 from rune.core.config import Backend
+
+# Imported for their side effect of registering API adapters.
+from rune.core.llm.backend import anthropic, gemini, openrouter  # noqa: F401
 from rune.core.llm.backend.generic import GenericBackend
+from rune.core.llm.backend.llama_cpp import LlamaCppBackend
 from rune.core.llm.backend.ollama import OllamaBackend
 
-BACKEND_FACTORY = {Backend.OLLAMA: OllamaBackend, Backend.GENERIC: GenericBackend}
+BACKEND_FACTORY = {
+    Backend.OLLAMA: OllamaBackend,
+    Backend.GENERIC: GenericBackend,
+    Backend.LLAMA_CPP: LlamaCppBackend,
+}
@@ -0,0 +1,213 @@
+from __future__ import annotations
+
+import json
+from typing import TYPE_CHECKING, Any, ClassVar
+from uuid import uuid4
+
+from rune.core.llm.backend.generic import (
+    BACKEND_ADAPTERS,
+    APIAdapter,
+    PreparedRequest,
+    parse_tool_arguments,
+    register_adapter,
+)
+from rune.core.types import (
+    AvailableTool,
+    FunctionCall,
+    LLMChunk,
+    LLMMessage,
+    LLMUsage,
+    Role,
+    StrToolChoice,
+    ToolCall,
+)
+
+if TYPE_CHECKING:
+    from rune.core.config import ProviderConfig
+
+
+@register_adapter(BACKEND_ADAPTERS, "gemini")
+class GeminiAdapter(APIAdapter):
+    """Google Gemini generateContent API, streamed over SSE or not.
+
+    Gemini sends each function call whole and without an index, so every call
+    gets a fresh id and is numbered by the backend in order of arrival; that
+    keeps calls from separate stream chunks from being merged into one.
+    """
+
+    unindexed_tool_calls: ClassVar[bool] = True
+
+    endpoint: ClassVar[str] = "/models/{model}:generateContent"
+    streaming_endpoint: ClassVar[str] = (
+        "/models/{model}:streamGenerateContent?alt=sse"
+    )
+
+    def prepare_request(
+        self,
+        *,
+        model_name: str,
+        messages: list[LLMMessage],
+        temperature: float,
+        tools: list[AvailableTool] | None,
+        max_tokens: int | None,
+        tool_choice: StrToolChoice | AvailableTool | None,
+        enable_streaming: bool,
+        provider: ProviderConfig,
+        api_key: str | None = None,
+        output_schema: dict[str, Any] | None = None,
+        reasoning_effort: str | None = None,
+    ) -> PreparedRequest:
+        system, contents = self._convert_messages(messages)
+
+        generation_config: dict[str, Any] = {"temperature": temperature}
+        if max_tokens is not None:
+            generation_config["maxOutputTokens"] = max_tokens
+
+        payload: dict[str, Any] = {
+            "contents": contents,
+            "generationConfig": generation_config,
+        }
+        if system:
+            payload["systemInstruction"] = {"parts": [{"text": system}]}
+        if tools:
+            payload["tools"] = [
+                {
+                    "functionDeclarations": [
+                        {
+                            "name": tool.function.name,
+                            "description": tool.function.description,
+                            # Unlike `parameters`, this takes full JSON Schema,
+                            # `$defs` and `$ref` included.
+                            "parametersJsonSchema": tool.function.parameters,
+                        }
+                        for tool in tools
+                    ]
+                }
+            ]
+            if tool_config := self._convert_tool_choice(tool_choice):
+                payload["toolConfig"] = tool_config
+
+        headers = {"Content-Type": "application/json"}
+        if api_key:
+            headers["x-goog-api-key"] = api_key
+
+        endpoint = self.streaming_endpoint if enable_streaming else self.endpoint
+        body = json.dumps(payload, ensure_ascii=False).encode("utf-8")
+        return PreparedRequest(endpoint.format(model=model_name), headers, body)
+
+    def _convert_tool_choice(
+        self, tool_choice: StrToolChoice | AvailableTool | None
+    ) -> dict[str, Any] | None:
+        match tool_choice:
+            case "auto":
+                mode: dict[str, Any] = {"mode": "AUTO"}
+            case "none":
+                mode = {"mode": "NONE"}
+            case "any" | "required":
+                mode = {"mode": "ANY"}
+            case AvailableTool():
+                mode = {
+                    "mode": "ANY",
+                    "allowedFunctionNames": [tool_choice.function.name],
+                }
+            case _:
+                return None
+        return {"functionCallingConfig": mode}
+
+    def _convert_messages(
+        self, messages: list[LLMMessage]
+    ) -> tuple[str, list[dict[str, Any]]]:
+        system_parts: list[str] = []
+        contents: list[dict[str, Any]] = []
+        call_names: dict[str, str] = {}
+
+        for msg in messages:
+            match msg.role:
+                case Role.system:
+                    if msg.content:
+                        system_parts.append(msg.content)
+                    continue
+                case Role.assistant:
+                    role = "model"
+                    parts: list[dict[str, Any]] = []
+                    if msg.content:
+                        parts.append({"text": msg.content})
+                    for tool_call in msg.tool_calls or []:
+                        name = tool_call.function.name or ""
+                        args = parse_tool_arguments(tool_call.function.arguments)
+                        if tool_call.id:
+                            call_names[tool_call.id] = name
+                        parts.append({"functionCall": {"name": name, "args": args}})
+                case Role.tool:
+                    role = "user"
+                    name = msg.name or call_names.get(msg.tool_call_id or "", "")
+                    parts = [
+                        {
+                            "functionResponse": {
+                                "name": name,
+                                "response": {"content": msg.content or ""},
+                            }
+                        }
+                    ]
+                case _:
+                    role = "user"
+                    parts = [{"text": msg.content}] if msg.content else []
+
+            parts += [
+                {"inlineData": {"mimeType": image.media_type, "data": image.data}}
+                for image in msg.images
+            ]
+            if not parts:
+                continue
+            if contents and contents[-1]["role"] == role:
+                contents[-1]["parts"].extend(parts)
+            else:
+                contents.append({"role": role, "parts": parts})
+
+        return "\n\n".join(system_parts), contents
+
+    def parse_response(
+        self, data: dict[str, Any], provider: ProviderConfig
+    ) -> LLMChunk:
+        if error := data.get("error"):
+            raise ValueError(f"Gemini error: {error.get('message', error)}")
+
+        content: list[str] = []
+        reasoning: list[str] = []
+        tool_calls: list[ToolCall] = []
+        candidates = data.get("candidates") or [{}]
+        for part in (candidates[0].get("content") or {}).get("parts") or []:
+            if call := part.get("functionCall"):
+                tool_calls.append(
+                    ToolCall(
+                        id=call.get("id") or f"call_{uuid4().hex[:24]}",
+                        function=FunctionCall(
+                            name=call.get("name"),
+                            arguments=json.dumps(call.get("args") or {}),
+                        ),
+                    )
+                )
+            elif "text" in part:
+                (reasoning if part.get("thought") else content).append(part["text"])
+
+        # Streamed chunks repeat the running totals, so usage is only taken from
+        # the chunk that finishes the candidate.
+        usage = None
+        usage_data = data.get("usageMetadata")
+        if usage_data and candidates[0].get("finishReason"):
+            usage = LLMUsage(
+                prompt_tokens=usage_data.get("promptTokenCount", 0),
+                completion_tokens=usage_data.get("candidatesTokenCount", 0)
+                + usage_data.get("thoughtsTokenCount", 0),
+                cached_tokens=usage_data.get("cachedContentTokenCount", 0),
+            )
+
+        return LLMChunk(
+            message=LLMMessage(
+                role=Role.assistant,
+                content="".join(content),
+                reasoning_content="".join(reasoning) or None,
+                tool_calls=tool_calls or None,
+            ),
+            usage=usage,
+        )
@@ -1,13 +1,18 @@
 from __future__ import annotations
 
-from collections.abc import AsyncGenerator, Callable
+import asyncio
+from collections.abc import AsyncGenerator, Callable, Iterator
+from itertools import count
 import json
-import os
 import types
 from typing import TYPE_CHECKING, Any, ClassVar, NamedTuple, Protocol, TypeVar
+from urllib.parse import quote, urlencode
 
 import httpx
 
+from rune.core.auth.accounts import AccountError
+from rune.core.auth.token_refresh import refresh_active_account
+from rune.core.llm.backend.transport import client_options
 from rune.core.llm.exceptions import BackendErrorBuilder
 from rune.core.types import (
     AvailableTool,
@@ -17,11 +22,14 @@ from rune.core.types import (
     Role,
     StrToolChoice,
 )
-from rune.core.utils import async_generator_retry, async_retry
+from rune.core.utils import async_generator_retry, async_retry, logger
 
 if TYPE_CHECKING:
     from rune.core.config import ModelConfig, ProviderConfig
 
+# Used for Azure OpenAI providers that don't pin an `api_version`.
+AZURE_DEFAULT_API_VERSION = "2024-10-21"
+
 
 class PreparedRequest(NamedTuple):
     endpoint: str
@@ -31,6 +39,14 @@ class PreparedRequest(NamedTuple):
 
 class APIAdapter(Protocol):
     endpoint: ClassVar[str]
+    # Whether a reply cut short can be continued by sending it back as the
+    # start of the assistant turn.
+    supports_prefill: ClassVar[bool] = False
+    # Whether `output_schema` is enforced by the API rather than ignored.
+    supports_output_schema: ClassVar[bool] = False
+    # Whether tool calls come whole and without an index, so the backend numbers
+    # them in order across the response.
+    unindexed_tool_calls: ClassVar[bool] = False
 
     def prepare_request(
         self,
@@ -44,6 +60,8 @@ class APIAdapter(Protocol):
         enable_streaming: bool,
         provider: ProviderConfig,
         api_key: str | None = None,
+        output_schema: dict[str, Any] | None = None,
+        reasoning_effort: str | None = None,
     ) -> PreparedRequest: ...
 
     def parse_response(
@@ -53,6 +71,11 @@ class APIAdapter(Protocol):
 
 BACKEND_ADAPTERS: dict[str, APIAdapter] = {}
 
+
+def _number_tool_calls(chunk: LLMChunk, indices: Iterator[int]) -> None:
+    for tool_call in chunk.message.tool_calls or []:
+        tool_call.index = next(indices)
+
 T = TypeVar("T", bound=APIAdapter)
 
 
@@ -67,9 +90,73 @@ def register_adapter(
     return decorator
 
 
+def supports_prefill(provider: ProviderConfig) -> bool:
+    adapter = BACKEND_ADAPTERS.get(provider.api_style)
+    return adapter is not None and adapter.supports_prefill
+
+
+def supports_output_schema(provider: ProviderConfig) -> bool:
+    adapter = BACKEND_ADAPTERS.get(provider.api_style)
+    return adapter is not None and adapter.supports_output_schema
+
+
+def parse_tool_arguments(arguments: str | None) -> dict[str, Any]:
+    """Tool call arguments as an object, for APIs that take them unserialized."""
+    if not arguments:
+        return {}
+    try:
+        parsed = json.loads(arguments)
+    except json.JSONDecodeError:
+        return {}
+    return parsed if isinstance(parsed, dict) else {}
+
+
+def attach_images(
+    messages: list[LLMMessage], converted: list[dict[str, Any]]
+) -> list[dict[str, Any]]:
+    """Chat Completions messages with their images added as `image_url` parts.
+
+    Tool messages only take text, so their images follow the tool results in a
+    user message.
+    """
+    result: list[dict[str, Any]] = []
+    pending: list[dict[str, Any]] = []
+    for msg, msg_dict in zip(messages, converted, strict=True):
+        if pending and msg.role != Role.tool:
+            result.append(_tool_images_message(pending))
+            pending = []
+        parts = [
+            {"type": "image_url", "image_url": {"url": image.data_url}}
+            for image in msg.images
+        ]
+        if parts and msg.role == Role.tool:
+            pending += parts
+        elif parts:
+            text = msg_dict.get("content") or ""
+            msg_dict["content"] = [{"type": "text", "text": text}, *parts]
+        result.append(msg_dict)
+    if pending:
+        result.append(_tool_images_message(pending))
+    return result
+
+
+def _tool_images_message(parts: list[dict[str, Any]]) -> dict[str, Any]:
+    return {
+        "role": "user",
+        "content": [{"type": "text", "text": "Images from the tool results."}, *parts],
+    }
+
+
 @register_adapter(BACKEND_ADAPTERS, "openai")
+@register_adapter(BACKEND_ADAPTERS, "chat")
 class OpenAIAdapter(APIAdapter):
+    """Chat Completions wire format, as spoken by OpenAI and most self-hosted
+    servers (vLLM, LiteLLM, llama.cpp server).
+    """
+
     endpoint: ClassVar[str] = "/chat/completions"
+    embeddings_endpoint: ClassVar[str] = "/embeddings"
+    supports_output_schema: ClassVar[bool] = True
 
     def build_payload(
         self,
@@ -99,12 +186,49 @@ class OpenAIAdapter(APIAdapter):
 
         return payload
 
-    def build_headers(self, api_key: str | None = None) -> dict[str, str]:
+    @staticmethod
+    def response_format(output_schema: dict[str, Any]) -> dict[str, Any]:
+        # Not strict: strict mode only accepts schemas that close every object
+        # with `additionalProperties: false`, which most user schemas don't.
+        return {
+            "type": "json_schema",
+            "json_schema": {
+                "name": "final_output",
+                "schema": output_schema,
+                "strict": False,
+            },
+        }
+
+    def build_headers(
+        self, api_key: str | None = None, *, azure: bool = False
+    ) -> dict[str, str]:
         headers = {"Content-Type": "application/json"}
-        if api_key:
+        if api_key and azure:
+            headers["api-key"] = api_key
+        elif api_key:
             headers["Authorization"] = f"Bearer {api_key}"
         return headers
 
+    def provider_payload(self, provider: ProviderConfig) -> dict[str, Any]:
+        """Request body fields specific to the provider, for subclasses."""
+        return {}
+
+    def provider_headers(self, provider: ProviderConfig) -> dict[str, str]:
+        """Headers specific to the provider, for subclasses."""
+        return {}
+
+    def build_endpoint(
+        self, model_name: str, provider: ProviderConfig, path: str | None = None
+    ) -> str:
+        endpoint = path or self.endpoint
+        api_version = provider.api_version
+        if provider.azure:
+            endpoint = f"/openai/deployments/{quote(model_name, safe='')}{endpoint}"
+            api_version = api_version or AZURE_DEFAULT_API_VERSION
+        if api_version:
+            endpoint = f"{endpoint}?{urlencode({'api-version': api_version})}"
+        return endpoint
+
     def _reasoning_to_api(
         self, msg_dict: dict[str, Any], field_name: str
     ) -> dict[str, Any]:
@@ -117,6 +241,27 @@ class OpenAIAdapter(APIAdapter):
     ) -> dict[str, Any]:
         if field_name != "reasoning_content" and field_name in msg_dict:
             msg_dict["reasoning_content"] = msg_dict.pop(field_name)
+        elif isinstance(msg_dict.get("reasoning"), str):
+            # vLLM and LiteLLM stream reasoning as `reasoning`.
+            msg_dict.setdefault("reasoning_content", msg_dict.pop("reasoning"))
+        return msg_dict
+
+    def _normalize_message(
+        self, msg_dict: dict[str, Any], field_name: str
+    ) -> dict[str, Any]:
+        """Smooth over the ways chat-completions servers differ from OpenAI."""
+        msg_dict = self._reasoning_from_api(msg_dict, field_name)
+        if msg_dict.get("role") is None:
+            msg_dict["role"] = "assistant"
+
+        for position, tool_call in enumerate(msg_dict.get("tool_calls") or []):
+            if tool_call.get("index") is None:
+                tool_call["index"] = position
+            function = tool_call.get("function") or {}
+            if isinstance(function.get("arguments"), dict):
+                function["arguments"] = json.dumps(
+                    function["arguments"], ensure_ascii=False
+                )
         return msg_dict
 
     def prepare_request(
@@ -131,6 +276,8 @@ class OpenAIAdapter(APIAdapter):
         enable_streaming: bool,
         provider: ProviderConfig,
         api_key: str | None = None,
+        output_schema: dict[str, Any] | None = None,
+        reasoning_effort: str | None = None,
     ) -> PreparedRequest:
         field_name = provider.reasoning_field_name
         converted_messages = [
@@ -139,10 +286,17 @@ class OpenAIAdapter(APIAdapter):
             )
             for msg in messages
         ]
+        if any(msg.images for msg in messages):
+            converted_messages = attach_images(messages, converted_messages)
 
         payload = self.build_payload(
             model_name, converted_messages, temperature, tools, max_tokens, tool_choice
         )
+        if output_schema is not None:
+            payload["response_format"] = self.response_format(output_schema)
+        if reasoning_effort is not None:
+            payload["reasoning_effort"] = reasoning_effort
+        payload.update(self.provider_payload(provider))
 
         if enable_streaming:
             payload["stream"] = True
@@ -151,10 +305,11 @@ class OpenAIAdapter(APIAdapter):
                 stream_options["stream_tool_calls"] = True
             payload["stream_options"] = stream_options
 
-        headers = self.build_headers(api_key)
+        headers = self.build_headers(api_key, azure=provider.azure)
+        headers.update(self.provider_headers(provider))
         body = json.dumps(payload, ensure_ascii=False).encode("utf-8")
 
-        return PreparedRequest(self.endpoint, headers, body)
+        return PreparedRequest(self.build_endpoint(model_name, provider), headers, body)
 
     def _parse_message(
         self, data: dict[str, Any], field_name: str
@@ -162,18 +317,18 @@ class OpenAIAdapter(APIAdapter):
         if data.get("choices"):
             choice = data["choices"][0]
             if "message" in choice:
-                msg_dict = self._reasoning_from_api(choice["message"], field_name)
+                msg_dict = self._normalize_message(choice["message"], field_name)
                 return LLMMessage.model_validate(msg_dict)
             if "delta" in choice:
-                msg_dict = self._reasoning_from_api(choice["delta"], field_name)
+                msg_dict = self._normalize_message(choice["delta"], field_name)
                 return LLMMessage.model_validate(msg_dict)
             raise ValueError("Invalid response data: missing message or delta")
 
         if "message" in data:
-            msg_dict = self._reasoning_from_api(data["message"], field_name)
+            msg_dict = self._normalize_message(data["message"], field_name)
             return LLMMessage.model_validate(msg_dict)
         if "delta" in data:
-            msg_dict = self._reasoning_from_api(data["delta"], field_name)
+            msg_dict = self._normalize_message(data["delta"], field_name)
             return LLMMessage.model_validate(msg_dict)
 
         return None
@@ -189,6 +344,9 @@ class OpenAIAdapter(APIAdapter):
         usage = LLMUsage(
             prompt_tokens=usage_data.get("prompt_tokens", 0),
             completion_tokens=usage_data.get("completion_tokens", 0),
+            cached_tokens=(usage_data.get("prompt_tokens_details") or {}).get(
+                "cached_tokens", 0
+            ),
         )
 
         return LLMChunk(message=message, usage=usage)
@@ -215,8 +373,7 @@ class GenericBackend:
     async def __aenter__(self) -> GenericBackend:
         if self._client is None:
             self._client = httpx.AsyncClient(
-                timeout=httpx.Timeout(self._timeout),
-                limits=httpx.Limits(max_keepalive_connections=5, max_connections=10),
+                **client_options(self._provider, self._timeout)
             )
         return self
 
@@ -233,12 +390,23 @@ class GenericBackend:
     def _get_client(self) -> httpx.AsyncClient:
         if self._client is None:
             self._client = httpx.AsyncClient(
-                timeout=httpx.Timeout(self._timeout),
-                limits=httpx.Limits(max_keepalive_connections=5, max_connections=10),
+                **client_options(self._provider, self._timeout)
             )
             self._owns_client = True
         return self._client
 
+    async def _api_key(self) -> str | None:
+        # One lookup per request: OAuth providers get their account's token
+        # from the refresh, which has read it anyway.
+        try:
+            if token := await refresh_active_account(self._provider):
+                return token
+        except AccountError as e:
+            # The request goes out with the old token, and its error says why.
+            logger.warning("Token refresh for %s failed: %s", self._provider.name, e)
+        # Keyring and command stores block, if only when auth.json changed.
+        return await asyncio.to_thread(self._provider.get_api_key)
+
     async def complete(
         self,
         *,
@@ -249,12 +417,9 @@ class GenericBackend:
         max_tokens: int | None = None,
         tool_choice: StrToolChoice | AvailableTool | None = None,
         extra_headers: dict[str, str] | None = None,
+        output_schema: dict[str, Any] | None = None,
     ) -> LLMChunk:
-        api_key = (
-            os.getenv(self._provider.api_key_env_var)
-            if self._provider.api_key_env_var
-            else None
-        )
+        api_key = await self._api_key()
 
         api_style = getattr(self._provider, "api_style", "openai")
         adapter = BACKEND_ADAPTERS[api_style]
@@ -269,6 +434,8 @@ class GenericBackend:
             enable_streaming=False,
             provider=self._provider,
             api_key=api_key,
+            output_schema=output_schema,
+            reasoning_effort=model.reasoning_effort,
         )
 
         if extra_headers:
@@ -278,7 +445,10 @@ class GenericBackend:
 
         try:
             res_data, _ = await self._make_request(url, body, headers)
-            return adapter.parse_response(res_data, self._provider)
+            chunk = adapter.parse_response(res_data, self._provider)
+            if adapter.unindexed_tool_calls:
+                _number_tool_calls(chunk, count())
+            return chunk
 
         except httpx.HTTPStatusError as e:
             raise BackendErrorBuilder.build_http_error(
@@ -314,12 +484,9 @@ class GenericBackend:
         max_tokens: int | None = None,
         tool_choice: StrToolChoice | AvailableTool | None = None,
         extra_headers: dict[str, str] | None = None,
+        output_schema: dict[str, Any] | None = None,
     ) -> AsyncGenerator[LLMChunk, None]:
-        api_key = (
-            os.getenv(self._provider.api_key_env_var)
-            if self._provider.api_key_env_var
-            else None
-        )
+        api_key = await self._api_key()
 
         api_style = getattr(self._provider, "api_style", "openai")
         adapter = BACKEND_ADAPTERS[api_style]
@@ -334,6 +501,8 @@ class GenericBackend:
             enable_streaming=True,
             provider=self._provider,
             api_key=api_key,
+            output_schema=output_schema,
+            reasoning_effort=model.reasoning_effort,
         )
 
         if extra_headers:
@@ -341,9 +510,13 @@ class GenericBackend:
 
         url = f"{self._provider.api_base}{endpoint}"
 
+        indices = count()
         try:
             async for res_data in self._make_streaming_request(url, body, headers):
-                yield adapter.parse_response(res_data, self._provider)
+                chunk = adapter.parse_response(res_data, self._provider)
+                if adapter.unindexed_tool_calls:
+                    _number_tool_calls(chunk, indices)
+                yield chunk
 
         except httpx.HTTPStatusError as e:
             raise BackendErrorBuilder.build_http_error(
@@ -399,14 +572,15 @@ class GenericBackend:
                     continue
 
                 DELIM_CHAR = ":"
-                if f"{DELIM_CHAR} " not in line:
+                if DELIM_CHAR not in line:
                     raise ValueError(
                         f"Stream chunk improperly formatted. "
                         f"Expected `key{DELIM_CHAR} value`, received `{line}`"
                     )
-                delim_index = line.find(DELIM_CHAR)
-                key = line[0:delim_index]
-                value = line[delim_index + 2 :]
+                key, _, value = line.partition(DELIM_CHAR)
+                # The space after the colon is optional in SSE, and some
+                # chat-completions servers leave it out.
+                value = value.removeprefix(" ")
 
                 if key != "data":
                     # This might be the case with openrouter, so we just ignore it
@@ -443,6 +617,52 @@ class GenericBackend:
 
         return result.usage.prompt_tokens
 
+    async def embed(self, *, model: ModelConfig, texts: list[str]) -> list[list[float]]:
+        adapter = BACKEND_ADAPTERS[self._provider.api_style]
+        if not isinstance(adapter, OpenAIAdapter):
+            raise ValueError(
+                f"Provider '{self._provider.name}' ({self._provider.api_style} API) "
+                "does not serve embeddings."
+            )
+        api_key = await self._api_key()
+        endpoint = adapter.build_endpoint(
+            model.name, self._provider, adapter.embeddings_endpoint
+        )
+        headers = adapter.build_headers(api_key, azure=self._provider.azure)
+        headers.update(adapter.provider_headers(self._provider))
+        body = json.dumps({"model": model.name, "input": texts}).encode("utf-8")
+        url = f"{self._provider.api_base}{endpoint}"
+
+        try:
+            res_data, _ = await self._make_request(url, body, headers)
+        except httpx.HTTPStatusError as e:
+            raise BackendErrorBuilder.build_http_error(
+                provider=self._provider.name,
+                endpoint=url,
+                response=e.response,
+                headers=e.response.headers,
+                model=model.name,
+                messages=[],
+                temperature=0.0,
+                has_tools=False,
+                tool_choice=None,
+            ) from e
+        except httpx.RequestError as e:
+            raise BackendErrorBuilder.build_request_error(
+                provider=self._provider.name,
+                endpoint=url,
+                error=e,
+                model=model.name,
+                messages=[],
+                temperature=0.0,
+                has_tools=False,
+                tool_choice=None,
+            ) from e
+
+        # Servers may return the vectors out of order; `index` is authoritative.
+        data = sorted(res_data.get("data") or [], key=lambda item: item["index"])
+        return [item["embedding"] for item in data]
+
     async def close(self) -> None:
         if self._owns_client and self._client:
             await self._client.aclose()
@@ -0,0 +1,243 @@
+from __future__ import annotations
+
+import asyncio
+from collections.abc import AsyncGenerator, Iterator
+from pathlib import Path
+import threading
+import types
+from typing import TYPE_CHECKING, Any
+
+from rune.core.llm.backend.generic import OpenAIAdapter
+from rune.core.llm.exceptions import BackendErrorBuilder
+from rune.core.types import (
+    AvailableTool,
+    LLMChunk,
+    LLMMessage,
+    LLMUsage,
+    Role,
+    StrToolChoice,
+)
+
+if TYPE_CHECKING:
+    from llama_cpp import Llama
+
+    from rune.core.config import ModelConfig, ProviderConfig
+
+INSTALL_HINT = (
+    "The llama_cpp backend needs llama-cpp-python: "
+    'install it with `uv tool install "rune-cli[local-inference]"` '
+    'or `pip install "rune-cli[local-inference]"`.'
+)
+
+# Loading a GGUF file takes seconds and a lot of memory, so models are shared
+# by every backend instance of the process.
+_MODELS: dict[Path, Llama] = {}
+_MODELS_LOCK = threading.Lock()
+
+
+class LocalModelError(RuntimeError):
+    pass
+
+
+class LlamaCppBackend:
+    """Runs GGUF models in-process with llama.cpp, for machines without an
+    inference server. Model names are paths to `.gguf` files, relative ones
+    resolved against the provider's `api_base`.
+    """
+
+    def __init__(self, provider: ProviderConfig, timeout: float = 720.0) -> None:
+        # `timeout` is accepted like other backends, but local generation has
+        # no connection to time out and can't be interrupted mid-call.
+        self._provider = provider
+        self._adapter = OpenAIAdapter()
+        # llama.cpp contexts are not safe to use from two threads at once.
+        self._lock = asyncio.Lock()
+
+    async def __aenter__(self) -> LlamaCppBackend:
+        return self
+
+    async def __aexit__(
+        self,
+        exc_type: type[BaseException] | None,
+        exc_val: BaseException | None,
+        exc_tb: types.TracebackType | None,
+    ) -> None:
+        return None
+
+    def model_path(self, model: ModelConfig) -> Path:
+        path = Path(model.name).expanduser()
+        if not path.is_absolute() and self._provider.api_base:
+            path = Path(self._provider.api_base).expanduser() / path
+        return path.resolve()
+
+    def _load(self, model: ModelConfig) -> Llama:
+        path = self.model_path(model)
+        with _MODELS_LOCK:
+            if (llm := _MODELS.get(path)) is not None:
+                return llm
+            try:
+                from llama_cpp import Llama
+            except ImportError as e:
+                raise LocalModelError(INSTALL_HINT) from e
+            if not path.is_file():
+                raise LocalModelError(f"GGUF model not found: {path}")
+
+            options = self._provider.local_inference
+            llm = Llama(
+                model_path=str(path),
+                n_ctx=options.n_ctx,
+                n_gpu_layers=options.n_gpu_layers,
+                n_threads=options.n_threads,
+                chat_format=options.chat_format,
+                verbose=False,
+            )
+            _MODELS[path] = llm
+            return llm
+
+    def _request(
+        self,
+        model: ModelConfig,
+        messages: list[LLMMessage],
+        temperature: float,
+        tools: list[AvailableTool] | None,
+        max_tokens: int | None,
+        tool_choice: StrToolChoice | AvailableTool | None,
+        output_schema: dict[str, Any] | None = None,
+    ) -> dict[str, Any]:
+        converted = [
+            msg.model_dump(exclude_none=True, exclude={"message_id"})
+            for msg in messages
+        ]
+        payload = self._adapter.build_payload(
+            model.name, converted, temperature, tools, max_tokens, tool_choice
+        )
+        payload.pop("model")
+        if output_schema is not None:
+            # llama-cpp-python compiles the schema into a grammar.
+            payload["response_format"] = {
+                "type": "json_object",
+                "schema": output_schema,
+            }
+        return payload
+
+    async def complete(
+        self,
+        *,
+        model: ModelConfig,
+        messages: list[LLMMessage],
+        temperature: float,
+        tools: list[AvailableTool] | None,
+        max_tokens: int | None,
+        tool_choice: StrToolChoice | AvailableTool | None,
+        extra_headers: dict[str, str] | None,
+        output_schema: dict[str, Any] | None = None,
+    ) -> LLMChunk:
+        request = self._request(
+            model, messages, temperature, tools, max_tokens, tool_choice, output_schema
+        )
+        try:
+            async with self._lock:
+                llm = await asyncio.to_thread(self._load, model)
+                response = await asyncio.to_thread(
+                    llm.create_chat_completion, **request
+                )
+            return self._adapter.parse_response(response, self._provider)
+        except Exception as e:
+            raise self._error(
+                e, model, messages, temperature, tools, tool_choice
+            ) from e
+
+    async def complete_streaming(
+        self,
+        *,
+        model: ModelConfig,
+        messages: list[LLMMessage],
+        temperature: float,
+        tools: list[AvailableTool] | None,
+        max_tokens: int | None,
+        tool_choice: StrToolChoice | AvailableTool | None,
+        extra_headers: dict[str, str] | None,
+        output_schema: dict[str, Any] | None = None,
+    ) -> AsyncGenerator[LLMChunk, None]:
+        request = self._request(
+            model, messages, temperature, tools, max_tokens, tool_choice, output_schema
+        )
+        try:
+            async with self._lock:
+                llm = await asyncio.to_thread(self._load, model)
+                stream: Iterator[dict[str, Any]] = await asyncio.to_thread(
+                    llm.create_chat_completion, **request, stream=True
+                )
+                generated: list[str] = []
+                while True:
+                    data = await asyncio.to_thread(next, stream, None)
+                    if data is None:
+                        break
+                    chunk = self._adapter.parse_response(data, self._provider)
+                    generated.append(chunk.message.content or "")
+                    generated.extend(
+                        tool_call.function.arguments or ""
+                        for tool_call in chunk.message.tool_calls or []
+                    )
+                    yield chunk
+                yield LLMChunk(
+                    message=LLMMessage(role=Role.assistant, content=""),
+                    usage=self._stream_usage(llm, "".join(generated)),
+                )
+        except Exception as e:
+            raise self._error(
+                e, model, messages, temperature, tools, tool_choice
+            ) from e
+
+    @staticmethod
+    def _stream_usage(llm: Llama, generated: str) -> LLMUsage:
+        # llama.cpp doesn't report usage when streaming; the context it just
+        # evaluated holds the prompt followed by the reply.
+        completion = len(llm.tokenize(generated.encode(), add_bos=False))
+        return LLMUsage(
+            prompt_tokens=max(llm.n_tokens - completion, 0),
+            completion_tokens=completion,
+        )
+
+    async def count_tokens(
+        self,
+        *,
+        model: ModelConfig,
+        messages: list[LLMMessage],
+        temperature: float = 0.0,
+        tools: list[AvailableTool] | None = None,
+        tool_choice: StrToolChoice | AvailableTool | None = None,
+        extra_headers: dict[str, str] | None = None,
+    ) -> int:
+        result = await self.complete(
+            model=model,
+            messages=messages,
+            temperature=temperature,
+            tools=tools,
+            max_tokens=1,
+            tool_choice=tool_choice,
+            extra_headers=extra_headers,
+        )
+        return result.usage.prompt_tokens if result.usage else 0
+
+    def _error(
+        self,
+        error: Exception,
+        model: ModelConfig,
+        messages: list[LLMMessage],
+        temperature: float,
+        tools: list[AvailableTool] | None,
+        tool_choice: StrToolChoice | AvailableTool | None,
+    ) -> Exception:
+        if isinstance(error, LocalModelError):
+            return error
+        return BackendErrorBuilder.build_request_error(
+            provider=self._provider.name,
+            endpoint=str(self.model_path(model)),
+            error=error,
+            model=model.name,
+            messages=messages,
+            temperature=temperature,
+            has_tools=bool(tools),
+            tool_choice=tool_choice,
+        )
@@ -8,6 +8,7 @@ from typing import TYPE_CHECKING, Any
 from ollama import AsyncClient, ResponseError
 import httpx
 
+from rune.core.llm.backend.transport import client_options
 from rune.core.llm.exceptions import BackendErrorBuilder
 from rune.core.types import (
     AvailableTool,
@@ -32,7 +33,9 @@ class OllamaBackend:
         self._api_base = provider.api_base
 
     async def __aenter__(self) -> OllamaBackend:
-        self._client = AsyncClient(host=self._api_base, timeout=self._timeout)
+        self._client = AsyncClient(
+            host=self._api_base, **client_options(self._provider, self._timeout)
+        )
         return self
 
     async def __aexit__(self, exc_type, exc_val, exc_tb) -> None:
@@ -42,7 +45,10 @@ class OllamaBackend:
 
     def _get_client(self) -> AsyncClient:
         if self._client is None:
-            self._client = AsyncClient(host=self._api_base, timeout=self._timeout)
+            self._client = AsyncClient(
+                host=self._api_base,
+                **client_options(self._provider, self._timeout),
+            )
         return self._client
 
     def _prepare_messages(self, messages: list[LLMMessage]) -> list[dict[str, Any]]:
@@ -58,6 +64,8 @@ class OllamaBackend:
                          }
                      } for tc in msg.tool_calls
                  ]
+            if msg.images:
+                m["images"] = [image.data for image in msg.images]
             json_messages.append(m)
         return json_messages
 
@@ -85,6 +93,7 @@ class OllamaBackend:
         max_tokens: int | None,
         tool_choice: StrToolChoice | AvailableTool | None,
         extra_headers: dict[str, str] | None,
+        output_schema: dict[str, Any] | None = None,
     ) -> LLMChunk:
         try:
             options = {"temperature": temperature}
@@ -96,6 +105,7 @@ class OllamaBackend:
                 messages=self._prepare_messages(messages),
                 tools=self._prepare_tools(tools),
                 options=options,
+                format=output_schema,
                 stream=False,
             )
 
@@ -147,6 +157,7 @@ class OllamaBackend:
         max_tokens: int | None,
         tool_choice: StrToolChoice | AvailableTool | None,
         extra_headers: dict[str, str] | None,
+        output_schema: dict[str, Any] | None = None,
     ) -> AsyncGenerator[LLMChunk, None]:
         try:
             options = {"temperature": temperature}
@@ -158,6 +169,7 @@ class OllamaBackend:
                 messages=self._prepare_messages(messages),
                 tools=self._prepare_tools(tools),
                 options=options,
+                format=output_schema,
                 stream=True,
             ):
                 message = chunk.message
@@ -228,3 +240,19 @@ class OllamaBackend:
         if result.usage is None:
             return 0 # Should not happen
         return result.usage.prompt_tokens
+
+    async def embed(self, *, model: ModelConfig, texts: list[str]) -> list[list[float]]:
+        try:
+            response = await self._get_client().embed(model=model.name, input=texts)
+        except Exception as e:
+            raise BackendErrorBuilder.build_request_error(
+                provider=self._provider.name,
+                endpoint=self._api_base,
+                error=e,
+                model=model.name,
+                messages=[],
+                temperature=0.0,
+                has_tools=False,
+                tool_choice=None,
+            ) from e
+        return [list(vector) for vector in response.embeddings]
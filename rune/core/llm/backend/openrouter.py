@@ -0,0 +1,80 @@
+from __future__ import annotations
+
+from typing import TYPE_CHECKING, Any
+
+from rune.core.llm.backend.generic import (
+    BACKEND_ADAPTERS,
+    OpenAIAdapter,
+    PreparedRequest,
+    register_adapter,
+)
+from rune.core.types import AvailableTool, LLMMessage, StrToolChoice
+
+if TYPE_CHECKING:
+    from rune.core.config import ProviderConfig
+
+LITELLM_PREFIX = "openrouter/"
+
+
+def openrouter_model_name(name: str) -> str:
+    """The OpenRouter id of ``name``, which is ``vendor/model[:variant]``.
+
+    Names copied from LiteLLM configs carry an extra ``openrouter/`` prefix,
+    which is dropped; ``openrouter/auto`` is a model of its own and is kept.
+    """
+    if name.startswith(LITELLM_PREFIX) and name.count("/") > 1:
+        return name.removeprefix(LITELLM_PREFIX)
+    return name
+
+
+@register_adapter(BACKEND_ADAPTERS, "openrouter")
+class OpenRouterAdapter(OpenAIAdapter):
+    """Chat Completions through OpenRouter, which fronts many hosted models
+    behind one key.
+
+    Requests carry the app attribution headers and the provider routing
+    preferences from the provider's `openrouter` settings.
+    """
+
+    def provider_payload(self, provider: ProviderConfig) -> dict[str, Any]:
+        payload: dict[str, Any] = {}
+        if preferences := provider.openrouter.provider_preferences():
+            payload["provider"] = preferences
+        return payload
+
+    def provider_headers(self, provider: ProviderConfig) -> dict[str, str]:
+        headers: dict[str, str] = {}
+        if provider.openrouter.app_url:
+            headers["HTTP-Referer"] = provider.openrouter.app_url
+        if provider.openrouter.app_title:
+            headers["X-Title"] = provider.openrouter.app_title
+        return headers
+
+    def prepare_request(
+        self,
+        *,
+        model_name: str,
+        messages: list[LLMMessage],
+        temperature: float,
+        tools: list[AvailableTool] | None,
+        max_tokens: int | None,
+        tool_choice: StrToolChoice | AvailableTool | None,
+        enable_streaming: bool,
+        provider: ProviderConfig,
+        api_key: str | None = None,
+        output_schema: dict[str, Any] | None = None,
+        reasoning_effort: str | None = None,
+    ) -> PreparedRequest:
+        return super().prepare_request(
+            model_name=openrouter_model_name(model_name),
+            messages=messages,
+            temperature=temperature,
+            tools=tools,
+            max_tokens=max_tokens,
+            tool_choice=tool_choice,
+            enable_streaming=enable_streaming,
+            provider=provider,
+            api_key=api_key,
+            output_schema=output_schema,
+            reasoning_effort=reasoning_effort,
+        )
@@ -0,0 +1,100 @@
+from __future__ import annotations
+
+from importlib.util import find_spec
+from pathlib import Path
+import ssl
+from typing import TYPE_CHECKING, Any, Protocol
+
+import httpx
+
+from rune.core.auth.token_command import CommandTokenAuth
+from rune.core.network import proxy_options
+
+if TYPE_CHECKING:
+    from rune.core.config import ProviderConfig
+
+MAX_KEEPALIVE_CONNECTIONS = 5
+MAX_CONNECTIONS = 10
+# httpx's default.
+KEEPALIVE_EXPIRY = 5.0
+
+
+class TransportConfigError(ValueError):
+    pass
+
+
+class TlsSettings(Protocol):
+    client_cert: Path | None
+    client_key: Path | None
+    ca_bundle: Path | None
+
+
+def client_options(provider: ProviderConfig, timeout: float) -> dict[str, Any]:
+    """`httpx.AsyncClient` arguments for a provider's connection settings.
+
+    ``timeout`` covers whatever the provider doesn't set on its own. The
+    provider's ``http_proxy`` wins over the ``[network]`` proxy, and both over
+    the ``HTTPS_PROXY`` environment.
+    """
+    if provider.http2 and find_spec("h2") is None:
+        raise TransportConfigError(
+            f"Provider '{provider.name}' sets http2 = true, which needs the h2 "
+            'package: install it with `pip install "httpx[http2]"`'
+        )
+
+    keepalive = provider.keepalive_timeout_ms
+    keepalive_connections = 0 if keepalive == 0 else MAX_KEEPALIVE_CONNECTIONS
+    options: dict[str, Any] = {
+        "timeout": httpx.Timeout(
+            timeout,
+            connect=_seconds(provider.connect_timeout_ms, timeout),
+            read=_seconds(provider.read_timeout_ms, timeout),
+        ),
+        "limits": httpx.Limits(
+            max_keepalive_connections=keepalive_connections,
+            max_connections=MAX_CONNECTIONS,
+            keepalive_expiry=_seconds(keepalive, KEEPALIVE_EXPIRY),
+        ),
+        "http2": provider.http2,
+    }
+    options.update(provider_tls_options(provider))
+    options.update(
+        proxy_options(provider.network, provider.api_base, provider.http_proxy)
+    )
+    if provider.bearer_token_command:
+        options["auth"] = CommandTokenAuth(
+            provider.bearer_token_command, provider.bearer_token_ttl_seconds
+        )
+    return options
+
+
+def oauth_client_options(provider: ProviderConfig, url: str) -> dict[str, Any]:
+    """`httpx` client arguments for the provider's OAuth server at ``url``: its
+    TLS and proxy settings, without the API's timeouts or bearer token.
+    """
+    return provider_tls_options(provider) | proxy_options(
+        provider.network, url, provider.http_proxy
+    )
+
+
+def provider_tls_options(provider: ProviderConfig) -> dict[str, Any]:
+    return tls_options(provider, f"Provider '{provider.name}'")
+
+
+def tls_options(settings: TlsSettings, owner: str) -> dict[str, Any]:
+    """`httpx` client arguments for a client certificate and CA bundle, for
+    clients that need only these, like OAuth logins to a provider's server.
+    """
+    if not (settings.client_cert or settings.ca_bundle):
+        return {}
+    try:
+        context = ssl.create_default_context(cafile=settings.ca_bundle)
+        if settings.client_cert:
+            context.load_cert_chain(settings.client_cert, settings.client_key)
+    except (OSError, ssl.SSLError) as e:
+        raise TransportConfigError(f"{owner} has unusable TLS settings: {e}") from e
+    return {"verify": context}
+
+
+def _seconds(milliseconds: int | None, default: float) -> float:
+    return default if milliseconds is None else milliseconds / 1000
@@ -0,0 +1,69 @@
+from __future__ import annotations
+
+from typing import TYPE_CHECKING, cast
+
+from rune.core.llm.backend.factory import BACKEND_FACTORY
+
+if TYPE_CHECKING:
+    from rune.core.config import ModelConfig, RuneConfig
+    from rune.core.llm.types import EmbeddingBackend
+
+
+class EmbeddingError(Exception):
+    pass
+
+
+async def embed(
+    config: RuneConfig, texts: list[str], backend: EmbeddingBackend | None = None
+) -> list[list[float]]:
+    """Embed `texts` with the configured embedding model, one vector per text.
+
+    Building block for retrieval features such as repository indexing and
+    memory lookup. Requests are split into batches of `embeddings.batch_size`.
+    """
+    if not texts:
+        return []
+    try:
+        model = config.embeddings.get_model(config)
+        provider = config.get_provider_for_model(model)
+    except ValueError as e:
+        raise EmbeddingError(str(e)) from e
+
+    if backend is not None:
+        return await _embed_batches(backend, model, texts, config)
+
+    created = BACKEND_FACTORY[provider.backend](
+        provider=provider, timeout=config.api_timeout
+    )
+    if not hasattr(created, "embed"):
+        raise EmbeddingError(
+            f"The {provider.backend} backend of provider '{provider.name}' "
+            "does not support embeddings."
+        )
+    async with created:
+        return await _embed_batches(
+            cast("EmbeddingBackend", created), model, texts, config
+        )
+
+
+async def _embed_batches(
+    backend: EmbeddingBackend,
+    model: ModelConfig,
+    texts: list[str],
+    config: RuneConfig,
+) -> list[list[float]]:
+    batch_size = config.embeddings.batch_size
+    vectors: list[list[float]] = []
+    for start in range(0, len(texts), batch_size):
+        batch = texts[start : start + batch_size]
+        try:
+            result = await backend.embed(model=model, texts=batch)
+        except ValueError as e:
+            raise EmbeddingError(str(e)) from e
+        if len(result) != len(batch):
+            raise EmbeddingError(
+                f"Model '{model.alias}' returned {len(result)} embeddings "
+                f"for {len(batch)} texts."
+            )
+        vectors.extend(result)
+    return vectors
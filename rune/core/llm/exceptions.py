@@ -8,6 +8,7 @@ from typing import Any
 import httpx
 from pydantic import BaseModel, ConfigDict, ValidationError
 
+from rune.core.llm.stream_watchdog import StreamStalledError
 from rune.core.types import AvailableTool, LLMMessage, StrToolChoice
 
 
@@ -80,6 +81,36 @@ class BackendError(RuntimeError):
         return s[:n] + ("…" if len(s) > n else "")
 
 
+# Failures another model may not share: limits, quotas and outages that
+# outlived the backend's own retries.
+_FALLBACK_STATUSES: dict[int, str] = {
+    HTTPStatus.PAYMENT_REQUIRED: "quota exceeded",
+    HTTPStatus.NOT_FOUND: "model unavailable",
+    HTTPStatus.TOO_MANY_REQUESTS: "rate limited",
+    HTTPStatus.INTERNAL_SERVER_ERROR: "provider error",
+    HTTPStatus.BAD_GATEWAY: "provider unavailable",
+    HTTPStatus.SERVICE_UNAVAILABLE: "provider unavailable",
+    HTTPStatus.GATEWAY_TIMEOUT: "provider timed out",
+    529: "provider overloaded",
+}
+
+
+def fallback_reason(error: BaseException) -> str | None:
+    """Why a failed request is worth retrying on a fallback model, if it is."""
+    current: BaseException | None = error
+    while current is not None:
+        if isinstance(current, BackendError):
+            if current.status is None:
+                return "provider unreachable"
+            return _FALLBACK_STATUSES.get(current.status)
+        if isinstance(current, httpx.TimeoutException | httpx.NetworkError):
+            return "provider unreachable"
+        if isinstance(current, StreamStalledError):
+            return "stream stalled"
+        current = current.__cause__
+    return None
+
+
 class ErrorResponse(BaseModel):
     model_config = ConfigDict(extra="ignore")
 
@@ -0,0 +1,106 @@
+from __future__ import annotations
+
+import asyncio
+from collections.abc import AsyncGenerator, AsyncIterator
+
+from rune.core.types import LLMMessage, Role, StreamStalledEvent
+from rune.core.utils import logger
+
+
+class StreamStalledError(RuntimeError):
+    def __init__(self, idle_seconds: float) -> None:
+        self.idle_seconds = idle_seconds
+        super().__init__(f"No data received from the model for {idle_seconds:g}s")
+
+
+async def watch_idle[T](
+    stream: AsyncIterator[T], idle_seconds: float
+) -> AsyncGenerator[T]:
+    """Re-yield ``stream``, raising `StreamStalledError` once no item arrives for
+    ``idle_seconds``. A timeout of 0 or less waits forever.
+
+    SSE keep-alive comments are consumed by the backends and never reach here,
+    so a connection that is kept open without producing deltas counts as idle.
+    The stream is closed when the watchdog is, stalled or not.
+    """
+    try:
+        while True:
+            try:
+                if idle_seconds > 0:
+                    item = await asyncio.wait_for(anext(stream), idle_seconds)
+                else:
+                    item = await anext(stream)
+            except StopAsyncIteration:
+                return
+            except TimeoutError:
+                raise StreamStalledError(idle_seconds) from None
+            yield item
+    finally:
+        if isinstance(stream, AsyncGenerator):
+            await stream.aclose()
+
+
+class StallRecovery:
+    """Reconnects the stream of one reply when it stalls, up to ``retries``
+    times.
+
+    When the provider can continue a prefilled assistant turn (``can_resume``)
+    and the reply so far is plain text, the request is sent again ending with
+    that text, so the model picks up where it stopped. Otherwise the reply
+    starts over.
+    """
+
+    def __init__(self, model: str, retries: int, *, can_resume: bool) -> None:
+        self._model = model
+        self._retries = retries
+        self._can_resume = can_resume
+        self._stalls = 0
+
+    def messages(
+        self, history: list[LLMMessage], reply: LLMMessage
+    ) -> list[LLMMessage]:
+        """``history``, followed by the text of ``reply`` received so far."""
+        if received_text := reply.content:
+            return [*history, LLMMessage(role=Role.assistant, content=received_text)]
+        return history
+
+    def recover(
+        self, error: StreamStalledError, reply: LLMMessage
+    ) -> StreamStalledEvent:
+        """Count the stall of ``reply``, raising ``error`` once the retries are
+        used up.
+
+        A resumed reply loses its trailing whitespace, which prefilled assistant
+        turns can't end with; the caller drops one that isn't resumed.
+        """
+        self._stalls += 1
+        if self._stalls > self._retries:
+            raise error
+        resumed = self._can_resume and _is_plain_text(reply)
+        if resumed:
+            reply.content = (reply.content or "").rstrip()
+        logger.warning(
+            "Stream from %s stalled after %gs, reconnecting (%d/%d)",
+            self._model,
+            error.idle_seconds,
+            self._stalls,
+            self._retries,
+        )
+        return StreamStalledEvent(
+            idle_seconds=error.idle_seconds,
+            attempt=self._stalls,
+            resumed=resumed,
+            discarded_output=_has_output(reply) and not resumed,
+        )
+
+
+def _has_output(message: LLMMessage) -> bool:
+    return bool(message.content or message.reasoning_content or message.tool_calls)
+
+
+def _is_plain_text(message: LLMMessage) -> bool:
+    return bool(
+        (message.content or "").strip()
+        and not message.reasoning_content
+        and not message.tool_calls
+    )
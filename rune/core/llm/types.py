@@ -2,7 +2,7 @@ from __future__ import annotations
 
 from collections.abc import AsyncGenerator
 import types
-from typing import TYPE_CHECKING, Protocol
+from typing import TYPE_CHECKING, Any, Protocol
 
 from rune.core.types import AvailableTool, LLMChunk, LLMMessage, StrToolChoice
 
@@ -35,6 +35,7 @@ class BackendLike(Protocol):
         max_tokens: int | None,
         tool_choice: StrToolChoice | AvailableTool | None,
         extra_headers: dict[str, str] | None,
+        output_schema: dict[str, Any] | None = None,
     ) -> LLMChunk:
         """Complete a chat conversation using the specified model and provider.
 
@@ -46,6 +47,8 @@ class BackendLike(Protocol):
             max_tokens: Maximum tokens to generate
             tool_choice: How to choose tools (auto, none, or specific tool)
             extra_headers: Additional HTTP headers to include
+            output_schema: JSON schema the reply must follow, when the
+                provider can enforce it
 
         Returns:
             LLMChunk containing the response message and usage information
@@ -68,6 +71,7 @@ class BackendLike(Protocol):
         max_tokens: int | None,
         tool_choice: StrToolChoice | AvailableTool | None,
         extra_headers: dict[str, str] | None,
+        output_schema: dict[str, Any] | None = None,
     ) -> AsyncGenerator[LLMChunk, None]:
         """Equivalent of the complete method, but yields LLMEvent objects
         instead of a single LLMEvent.
@@ -80,6 +84,8 @@ class BackendLike(Protocol):
             max_tokens: Maximum tokens to generate
             tool_choice: How to choose tools (auto, none, or specific tool)
             extra_headers: Additional HTTP headers to include
+            output_schema: JSON schema the reply must follow, when the
+                provider can enforce it
 
         Returns:
             AsyncGenerator[LLMEvent, None] yielding LLMEvent objects
@@ -118,3 +124,18 @@ class BackendLike(Protocol):
             The number of prompt tokens
         """
         ...
+
+
+class EmbeddingBackend(Protocol):
+    """Backends that can turn text into vectors, for retrieval features."""
+
+    async def embed(self, *, model: ModelConfig, texts: list[str]) -> list[list[float]]:
+        """Embed each text with the given embedding model.
+
+        Returns:
+            One vector per text, in the order of `texts`
+
+        Raises:
+            BackendError: If the API request fails
+        """
+        ...
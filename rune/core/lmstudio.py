@@ -0,0 +1,211 @@
+from __future__ import annotations
+
+import asyncio
+from collections.abc import AsyncGenerator
+from pathlib import Path
+import re
+import shutil
+from typing import TYPE_CHECKING
+
+import httpx
+from pydantic import BaseModel
+
+from rune.core.llm.backend.transport import TransportConfigError, client_options
+
+if TYPE_CHECKING:
+    from rune.core.config import LMStudioConfig, ProviderConfig, RuneConfig
+
+REQUEST_TIMEOUT_SECONDS = 10.0
+# Where LM Studio installs its CLI when it isn't on PATH.
+LMS_INSTALL_DIRS = (
+    Path.home() / ".lmstudio" / "bin",
+    Path.home() / ".cache" / "lm-studio" / "bin",
+)
+_PERCENT = re.compile(r"(\d{1,3}(?:\.\d+)?)\s*%")
+
+
+class LMStudioError(Exception):
+    pass
+
+
+class LMStudioModel(BaseModel):
+    """A model downloaded in LM Studio, as listed by its REST API."""
+
+    id: str
+    type: str = "llm"
+    publisher: str | None = None
+    arch: str | None = None
+    quantization: str | None = None
+    state: str = "not-loaded"
+    max_context_length: int | None = None
+
+    @property
+    def loaded(self) -> bool:
+        return self.state == "loaded"
+
+    def describe(self) -> str:
+        details = [self.type]
+        if self.quantization:
+            details.append(self.quantization)
+        if self.max_context_length:
+            details.append(f"{self.max_context_length} ctx")
+        if self.loaded:
+            details.append("loaded")
+        return f"`{self.id}` ({', '.join(details)})"
+
+
+class DownloadProgress(BaseModel):
+    model: str
+    # None while LM Studio resolves the download and hasn't reported a size.
+    fraction: float | None = None
+    done: bool = False
+
+
+def describe_download(model: str, fraction: float | None, done: bool) -> str:
+    if done:
+        return f"Downloaded {model} in LM Studio"
+    if fraction is None:
+        return f"Downloading {model} in LM Studio..."
+    return f"Downloading {model} in LM Studio: {fraction:.0%}"
+
+
+def rest_base(provider: ProviderConfig) -> str:
+    """LM Studio's native REST API, next to its OpenAI-compatible `/v1`."""
+    return provider.api_base.rstrip("/").removesuffix("/v1") + "/api/v0"
+
+
+def lmstudio_provider(config: RuneConfig) -> ProviderConfig:
+    """The active model's provider when LM Studio serves it, else the first
+    LM Studio provider.
+    """
+    try:
+        active = config.get_provider_for_model(config.get_active_model())
+    except ValueError:
+        active = None
+    if active is not None and active.lmstudio is not None:
+        return active
+    for provider in config.providers:
+        if provider.lmstudio is not None:
+            return provider
+    raise LMStudioError("No provider is configured with `[providers.lmstudio]`")
+
+
+def find_lms() -> str:
+    if found := shutil.which("lms"):
+        return found
+    for directory in LMS_INSTALL_DIRS:
+        if (candidate := directory / "lms").is_file():
+            return str(candidate)
+    raise LMStudioError(
+        "The `lms` CLI was not found. Open LM Studio once, or run "
+        "`~/.lmstudio/bin/lms bootstrap`, to install it."
+    )
+
+
+class LMStudioClient:
+    def __init__(
+        self, provider: ProviderConfig, client: httpx.AsyncClient | None = None
+    ) -> None:
+        if provider.lmstudio is None:
+            raise LMStudioError(
+                f"Provider '{provider.name}' is not an LM Studio server"
+            )
+        self.provider = provider
+        self.options: LMStudioConfig = provider.lmstudio
+        self._client = client
+
+    async def list_models(self) -> list[LMStudioModel]:
+        url = f"{rest_base(self.provider)}/models"
+        try:
+            if self._client is not None:
+                response = await self._client.get(url)
+            else:
+                async with httpx.AsyncClient(
+                    **client_options(self.provider, REQUEST_TIMEOUT_SECONDS)
+                ) as client:
+                    response = await client.get(url)
+            response.raise_for_status()
+        except (httpx.HTTPError, TransportConfigError) as e:
+            raise LMStudioError(
+                f"Could not reach LM Studio at {self.provider.api_base}: {e}"
+            ) from e
+        return [
+            LMStudioModel.model_validate(item)
+            for item in response.json().get("data") or []
+        ]
+
+    async def find_model(self, model_id: str) -> LMStudioModel | None:
+        return next((m for m in await self.list_models() if m.id == model_id), None)
+
+    async def download(self, model: str) -> AsyncGenerator[DownloadProgress]:
+        """Download ``model`` (a catalog name or Hugging Face URL) with `lms get`,
+        reporting progress as it goes.
+        """
+        proc = await asyncio.create_subprocess_exec(
+            find_lms(),
+            "get",
+            model,
+            "--yes",
+            stdout=asyncio.subprocess.PIPE,
+            stderr=asyncio.subprocess.STDOUT,
+        )
+        if proc.stdout is None:
+            raise LMStudioError("`lms get` has no output to follow")
+        output = ""
+        yield DownloadProgress(model=model)
+        try:
+            while chunk := await proc.stdout.read(4096):
+                text = chunk.decode("utf-8", errors="replace")
+                output = (output + text)[-2000:]
+                # Progress bars redraw in place, so the last figure is current.
+                if percents := _PERCENT.findall(text):
+                    fraction = min(float(percents[-1]) / 100, 1.0)
+                    yield DownloadProgress(model=model, fraction=fraction)
+            if await proc.wait() != 0:
+                last_line = (output.strip().splitlines() or ["no output"])[-1]
+                raise LMStudioError(f"`lms get {model}` failed: {last_line}")
+        finally:
+            if proc.returncode is None:
+                proc.kill()
+                await proc.wait()
+        yield DownloadProgress(model=model, fraction=1.0, done=True)
+
+    async def load(self, model_id: str) -> None:
+        """Load ``model_id`` with the provider's context length and TTL, rather
+        than waiting for LM Studio to load it just in time.
+        """
+        cmd = [find_lms(), "load", model_id, "--yes"]
+        if self.options.context_length:
+            cmd += ["--context-length", str(self.options.context_length)]
+        if self.options.ttl_seconds:
+            cmd += ["--ttl", str(self.options.ttl_seconds)]
+        proc = await asyncio.create_subprocess_exec(
+            *cmd, stdout=asyncio.subprocess.PIPE, stderr=asyncio.subprocess.STDOUT
+        )
+        stdout, _ = await proc.communicate()
+        if proc.returncode != 0:
+            output = stdout.decode("utf-8", errors="replace").strip()
+            raise LMStudioError(f"`lms load {model_id}` failed: {output}")
+
+    async def ensure_ready(
+        self, model_id: str
+    ) -> AsyncGenerator[DownloadProgress | LMStudioModel]:
+        """Make ``model_id`` available: download it when missing and allowed,
+        and load it when a context length is configured. Yields download
+        progress, then the model.
+        """
+        model = await self.find_model(model_id)
+        if model is None:
+            if not self.options.auto_download:
+                raise LMStudioError(
+                    f"'{model_id}' is not downloaded in LM Studio. Run "
+                    f"`/local pull {model_id}`, or set `auto_download = true`."
+                )
+            async for progress in self.download(model_id):
+                yield progress
+            if (model := await self.find_model(model_id)) is None:
+                raise LMStudioError(f"'{model_id}' is still missing after download")
+        if self.options.context_length and not model.loaded:
+            await self.load(model_id)
+            model = model.model_copy(update={"state": "loaded"})
+        yield model
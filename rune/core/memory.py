@@ -0,0 +1,157 @@
+from __future__ import annotations
+
+from collections.abc import Iterator
+from contextlib import closing, contextmanager
+from datetime import UTC, datetime
+from pathlib import Path
+import re
+import sqlite3
+
+from pydantic import BaseModel, Field
+
+from rune.core.paths.global_paths import MEMORY_DB_FILE
+
+MAX_MEMORY_LENGTH = 2_000
+_WORD = re.compile(r"\w{3,}")
+
+_SCHEMA = """
+CREATE TABLE IF NOT EXISTS memories (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    project TEXT NOT NULL,
+    content TEXT NOT NULL,
+    tags TEXT NOT NULL DEFAULT '',
+    created_at TEXT NOT NULL,
+    last_used_at TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS memories_project ON memories (project, last_used_at);
+"""
+
+
+class MemoryStoreError(Exception):
+    pass
+
+
+class Memory(BaseModel):
+    id: int
+    content: str
+    tags: list[str] = Field(default_factory=list)
+    created_at: datetime
+    last_used_at: datetime
+
+    def render(self) -> str:
+        tags = f" ({', '.join(self.tags)})" if self.tags else ""
+        return f"- [{self.id}] {self.content}{tags}"
+
+
+def project_key(path: Path) -> str:
+    return str(path.resolve())
+
+
+class MemoryStore:
+    """Durable notes the agent keeps about a project, across sessions.
+
+    Notes are kept per project (its directory) in a SQLite database under
+    `$RUNE_HOME`, so every thread started there can see them.
+    """
+
+    def __init__(self, path: Path | None = None, *, project: Path) -> None:
+        self.path = path or MEMORY_DB_FILE.path
+        self.project = project_key(project)
+
+    @contextmanager
+    def _connect(self) -> Iterator[sqlite3.Connection]:
+        self.path.parent.mkdir(parents=True, exist_ok=True)
+        try:
+            with closing(sqlite3.connect(self.path)) as db, db:
+                db.row_factory = sqlite3.Row
+                db.executescript(_SCHEMA)
+                yield db
+        except sqlite3.Error as e:
+            raise MemoryStoreError(f"Memory store {self.path} failed: {e}") from e
+
+    def save(self, content: str, tags: list[str] | None = None) -> Memory:
+        content = content.strip()
+        if not content:
+            raise MemoryStoreError("A memory can't be empty")
+        if len(content) > MAX_MEMORY_LENGTH:
+            raise MemoryStoreError(
+                f"A memory can't be longer than {MAX_MEMORY_LENGTH} characters"
+            )
+        now = datetime.now(UTC).isoformat()
+        tag_text = ",".join(t.strip().lower() for t in tags or [] if t.strip())
+        with self._connect() as db:
+            cursor = db.execute(
+                "INSERT INTO memories (project, content, tags, created_at, "
+                "last_used_at) VALUES (?, ?, ?, ?, ?)",
+                (self.project, content, tag_text, now, now),
+            )
+            row = db.execute(
+                "SELECT * FROM memories WHERE id = ?", (cursor.lastrowid,)
+            ).fetchone()
+        return _to_memory(row)
+
+    def recent(self, limit: int | None = None) -> list[Memory]:
+        """The project's memories, most recently used first."""
+        if not self.path.exists():
+            return []
+        with self._connect() as db:
+            rows = db.execute(
+                "SELECT * FROM memories WHERE project = ? "
+                "ORDER BY last_used_at DESC, id DESC LIMIT ?",
+                (self.project, -1 if limit is None else limit),
+            ).fetchall()
+        return [_to_memory(row) for row in rows]
+
+    def search(self, query: str, limit: int = 10) -> list[Memory]:
+        """The project's memories sharing the most words with ``query``.
+
+        Found memories count as used, so they stay among those shown at the
+        start of the next thread.
+        """
+        words = _words(query)
+        scored = [(len(words & _words(m.content, *m.tags)), m) for m in self.recent()]
+        # The sort is stable, so equally relevant memories stay in recency order.
+        scored.sort(key=lambda item: -item[0])
+        found = [m for score, m in scored if score][:limit]
+        if found:
+            with self._connect() as db:
+                db.executemany(
+                    "UPDATE memories SET last_used_at = ? WHERE id = ?",
+                    [(datetime.now(UTC).isoformat(), m.id) for m in found],
+                )
+        return found
+
+    def delete(self, memory_id: int) -> None:
+        with self._connect() as db:
+            cursor = db.execute(
+                "DELETE FROM memories WHERE id = ? AND project = ?",
+                (memory_id, self.project),
+            )
+        if cursor.rowcount == 0:
+            raise MemoryStoreError(f"No memory {memory_id} in this project")
+
+
+def _words(*texts: str) -> set[str]:
+    return {w for text in texts for w in _WORD.findall(text.lower())}
+
+
+def _to_memory(row: sqlite3.Row) -> Memory:
+    return Memory(
+        id=row["id"],
+        content=row["content"],
+        tags=[t for t in row["tags"].split(",") if t],
+        created_at=datetime.fromisoformat(row["created_at"]),
+        last_used_at=datetime.fromisoformat(row["last_used_at"]),
+    )
+
+
+def render_memories(memories: list[Memory]) -> str:
+    return "\n".join([
+        "# Memories",
+        "",
+        "Notes you saved in earlier sessions in this project, most recently "
+        "used first. Use the `memory` tool to search for more, save new ones "
+        "or forget outdated ones.",
+        "",
+        *(m.render() for m in memories),
+    ])
@@ -110,19 +110,28 @@ class AutoCompactMiddleware:
 
 
 class ContextWarningMiddleware:
+    """Warns once the context passes ``threshold_percent`` of ``max_context``.
+    Clients always get the warning, through the ``context_warning`` metadata;
+    the model only gets it when ``warn_model`` is set.
+    """
+
     def __init__(
-        self, threshold_percent: float = 0.5, max_context: int | None = None
+        self,
+        threshold_percent: float,
+        max_context: Callable[[], int | None],
+        warn_model: bool = True,
     ) -> None:
         self.threshold_percent = threshold_percent
         self.max_context = max_context
+        self.warn_model = warn_model
         self.has_warned = False
 
     async def before_turn(self, context: ConversationContext) -> MiddlewareResult:
         if self.has_warned:
             return MiddlewareResult()
 
-        max_context = self.max_context
-        if max_context is None:
+        max_context = self.max_context()
+        if not max_context:
             return MiddlewareResult()
 
         if context.stats.context_tokens >= max_context * self.threshold_percent:
@@ -132,7 +141,15 @@ class ContextWarningMiddleware:
             warning_msg = f"<{RUNE_WARNING_TAG}>You have used {percentage_used:.0f}% of your total context ({context.stats.context_tokens:,}/{max_context:,} tokens)</{RUNE_WARNING_TAG}>"
 
             return MiddlewareResult(
-                action=MiddlewareAction.INJECT_MESSAGE, message=warning_msg
+                action=MiddlewareAction.INJECT_MESSAGE,
+                message=warning_msg if self.warn_model else None,
+                metadata={
+                    "context_warning": {
+                        "context_tokens": context.stats.context_tokens,
+                        "context_window": max_context,
+                        "threshold": self.threshold_percent,
+                    }
+                },
             )
 
         return MiddlewareResult()
@@ -175,6 +192,33 @@ class PlanAgentMiddleware:
         pass
 
 
+DRY_RUN_REMINDER = f"""<{RUNE_WARNING_TAG}>Dry-run mode is active. Shell commands are not executed: each one is classified and risk-scored instead, and returned to you as a failed call. Keep working through the task as you normally would so the user can audit what you would run.</{RUNE_WARNING_TAG}>"""
+
+READ_ONLY_REMINDER = f"""<{RUNE_WARNING_TAG}>Read-only mode is active. The user wants analysis only: do not edit files, run commands that change the system, or make commits. Tools that modify state will be rejected until the user turns read-only mode off.</{RUNE_WARNING_TAG}>"""
+
+
+class ModeReminderMiddleware:
+    """Reminds the model every turn while a session mode such as read-only is on."""
+
+    def __init__(
+        self, is_active: Callable[[], bool], reminder: str | Callable[[], str]
+    ) -> None:
+        self._is_active = is_active
+        self.reminder = reminder
+
+    async def before_turn(self, context: ConversationContext) -> MiddlewareResult:
+        if not self._is_active():
+            return MiddlewareResult()
+        message = self.reminder if isinstance(self.reminder, str) else self.reminder()
+        return MiddlewareResult(action=MiddlewareAction.INJECT_MESSAGE, message=message)
+
+    async def after_turn(self, context: ConversationContext) -> MiddlewareResult:
+        return MiddlewareResult()
+
+    def reset(self, reset_reason: ResetReason = ResetReason.STOP) -> None:
+        pass
+
+
 class MiddlewarePipeline:
     def __init__(self) -> None:
         self.middlewares: list[ConversationMiddleware] = []
@@ -192,17 +236,22 @@ class MiddlewarePipeline:
 
     async def run_before_turn(self, context: ConversationContext) -> MiddlewareResult:
         messages_to_inject = []
+        metadata: dict[str, Any] = {}
 
         for mw in self.middlewares:
             result = await mw.before_turn(context)
-            if result.action == MiddlewareAction.INJECT_MESSAGE and result.message:
-                messages_to_inject.append(result.message)
+            if result.action == MiddlewareAction.INJECT_MESSAGE:
+                if result.message:
+                    messages_to_inject.append(result.message)
+                metadata.update(result.metadata)
             elif result.action in {MiddlewareAction.STOP, MiddlewareAction.COMPACT}:
                 return result
-        if messages_to_inject:
-            combined_message = "\n\n".join(messages_to_inject)
+        if messages_to_inject or metadata:
+            combined_message = "\n\n".join(messages_to_inject) or None
             return MiddlewareResult(
-                action=MiddlewareAction.INJECT_MESSAGE, message=combined_message
+                action=MiddlewareAction.INJECT_MESSAGE,
+                message=combined_message,
+                metadata=metadata,
             )
 
         return MiddlewareResult()
@@ -0,0 +1,116 @@
+from __future__ import annotations
+
+from collections.abc import Callable
+from pathlib import Path
+
+from rune.core.config import RuneConfig
+from rune.core.paths.config_paths import CONFIG_FILE
+from rune.core.paths.global_paths import (
+    MODELS_REGISTRY_CACHE_FILE,
+    ORG_DEFAULTS_CACHE_FILE,
+)
+from rune.core.types import ModelListChangedEvent
+
+type FileSignature = tuple[int, int] | None
+
+
+def _model_sources() -> list[Path]:
+    # The org defaults and models registry caches are rewritten whenever they
+    # are fetched again, which is how a fleet-wide model rollout reaches
+    # running sessions.
+    return [
+        CONFIG_FILE.path,
+        ORG_DEFAULTS_CACHE_FILE.path,
+        MODELS_REGISTRY_CACHE_FILE.path,
+    ]
+
+
+class ModelSourceWatcher:
+    """Tells when the files the model list is loaded from change on disk."""
+
+    def __init__(self, paths: Callable[[], list[Path]] = _model_sources) -> None:
+        self._paths = paths
+        self._signature = self._read_signature()
+
+    def _read_signature(self) -> tuple[FileSignature, ...]:
+        signature: list[FileSignature] = []
+        for path in self._paths():
+            try:
+                stat = path.stat()
+            except OSError:
+                signature.append(None)
+                continue
+            signature.append((stat.st_mtime_ns, stat.st_size))
+        return tuple(signature)
+
+    def changed(self) -> bool:
+        signature = self._read_signature()
+        if signature == self._signature:
+            return False
+        self._signature = signature
+        return True
+
+
+def merge_model_list(
+    current: RuneConfig, fresh: RuneConfig
+) -> tuple[RuneConfig, ModelListChangedEvent | None]:
+    """`current` with the models, providers and prices of `fresh`.
+
+    The active model follows `upgrade_to` entries of the new list. When it was
+    removed without a replacement, its entry is kept so the session can go on.
+    """
+    models = list(fresh.models)
+    if not any(model.alias == current.active_model for model in models):
+        try:
+            models.append(current.get_active_model())
+        except ValueError:
+            pass
+    updated = current.model_copy(
+        update={
+            "models": models,
+            "providers": fresh.providers,
+            "pricing": fresh.pricing,
+        }
+    )
+    updated.active_model = updated.upgraded_model(current.active_model)
+
+    old = {model.alias: model for model in current.models}
+    new = {model.alias: model for model in fresh.models}
+    event = ModelListChangedEvent(
+        added=[alias for alias in new if alias not in old],
+        removed=[alias for alias in old if alias not in new],
+        changed=[
+            alias
+            for alias, model in new.items()
+            if alias in old and old[alias] != model
+        ],
+        active_model=updated.active_model,
+        upgraded_from=(
+            current.active_model
+            if updated.active_model != current.active_model
+            else None
+        ),
+    )
+    if (
+        not (event.added or event.removed or event.changed or event.upgraded_from)
+        and current.providers == fresh.providers
+        and current.pricing == fresh.pricing
+    ):
+        return current, None
+    return updated, event
+
+
+def describe_model_list_change(event: ModelListChangedEvent) -> str:
+    parts = []
+    if event.added:
+        parts.append(f"added {', '.join(event.added)}")
+    if event.removed:
+        parts.append(f"removed {', '.join(event.removed)}")
+    if event.changed:
+        parts.append(f"updated {', '.join(event.changed)}")
+    text = "Model list reloaded"
+    if parts:
+        text += f": {'; '.join(parts)}"
+    if event.upgraded_from:
+        text += f". Switched from {event.upgraded_from} to {event.active_model}"
+    return text
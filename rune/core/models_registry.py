@@ -0,0 +1,209 @@
+from __future__ import annotations
+
+from collections.abc import Callable
+from functools import partial
+import json
+from logging import getLogger
+from pathlib import Path
+import threading
+import time
+from typing import TYPE_CHECKING, Any, Literal, NamedTuple
+from urllib.parse import urlparse
+
+import httpx
+from pydantic import BaseModel, Field, ValidationError
+
+from rune.core.network import proxy_options, require_https
+from rune.core.paths.global_paths import MODELS_REGISTRY_CACHE_FILE
+
+if TYPE_CHECKING:
+    from rune.core.config import NetworkConfig
+
+logger = getLogger("rune")
+
+DEFAULT_REGISTRY_TTL_SECONDS = 3600
+FETCH_TIMEOUT_SECONDS = 10.0
+
+
+class ModelsRegistryError(Exception):
+    pass
+
+
+class ModelsRegistry(BaseModel):
+    """Model presets an organization serves to all its machines.
+
+    Entries are `[[models]]` and `[[providers]]` tables as they would appear in
+    config.toml, as JSON. They are validated one by one when merged into the
+    config, so a malformed entry only drops itself.
+    """
+
+    models: list[dict[str, Any]] = Field(default_factory=list)
+    providers: list[dict[str, Any]] = Field(default_factory=list)
+
+    @classmethod
+    def parse(cls, text: str) -> ModelsRegistry:
+        try:
+            return cls.model_validate_json(text)
+        except ValidationError as e:
+            raise ModelsRegistryError(f"Invalid models registry: {e}") from e
+
+    def validated_entries[T: BaseModel](
+        self,
+        field: Literal["models", "providers"],
+        schema: type[T],
+        key: str,
+        taken: set[str],
+    ) -> list[T]:
+        """The valid entries of ``field`` whose ``key`` isn't in ``taken``.
+
+        Entries the user already configured under the same alias or name are
+        left out, so local config always wins over the registry.
+        """
+        entries: list[T] = []
+        for raw in getattr(self, field):
+            try:
+                entry = schema.model_validate(raw)
+            except ValidationError as e:
+                logger.warning("Ignoring models registry %s entry: %s", field, e)
+                continue
+            if (value := getattr(entry, key)) in taken:
+                continue
+            taken.add(value)
+            entries.append(entry)
+        return entries
+
+
+class FetchResult(NamedTuple):
+    # None when the server answered 304 Not Modified.
+    text: str | None
+    etag: str | None
+
+
+def load_models_registry(
+    url: str,
+    *,
+    ttl: float = DEFAULT_REGISTRY_TTL_SECONDS,
+    cache_file: Path | None = None,
+    fetch: Callable[[str, str | None], FetchResult] | None = None,
+    network: NetworkConfig | None = None,
+    refresh_in_background: bool = False,
+) -> ModelsRegistry | None:
+    """Fetch and cache the models registry at ``url``.
+
+    The cached copy is reused for ``ttl`` seconds, then revalidated with its
+    ETag so an unchanged registry isn't downloaded again. When the URL can't be
+    reached, the cached copy is used however old.
+
+    With ``refresh_in_background``, an expired copy is returned right away and
+    revalidated by a background thread for the next load.
+    """
+    cache_file = cache_file or MODELS_REGISTRY_CACHE_FILE.path
+    cached = _read_cache(cache_file, url)
+    if cached is not None and time.time() - cached.fetched_at < ttl:
+        return cached.registry
+    fetch = fetch or partial(_fetch, options=proxy_options(network, url))
+    if cached is not None and refresh_in_background:
+        if _refresh_lock.acquire(blocking=False):
+            threading.Thread(
+                target=_refresh_locked,
+                args=(url, cached, cache_file, fetch),
+                name="models-registry-refresh",
+                daemon=True,
+            ).start()
+        return cached.registry
+    return _refresh(url, cached, cache_file, fetch)
+
+
+# Held while a background refresh runs, so loads don't start one each.
+_refresh_lock = threading.Lock()
+
+
+def _refresh_locked(
+    url: str,
+    cached: _CachedRegistry,
+    cache_file: Path,
+    fetch: Callable[[str, str | None], FetchResult],
+) -> None:
+    try:
+        _refresh(url, cached, cache_file, fetch)
+    finally:
+        _refresh_lock.release()
+
+
+def _refresh(
+    url: str,
+    cached: _CachedRegistry | None,
+    cache_file: Path,
+    fetch: Callable[[str, str | None], FetchResult],
+) -> ModelsRegistry | None:
+    try:
+        if urlparse(url).scheme != "https":
+            raise ModelsRegistryError(
+                f"The models registry must be served over HTTPS: {url}"
+            )
+        text, etag = fetch(url, cached.etag if cached else None)
+        if text is None:
+            if cached is None:
+                raise ModelsRegistryError("Not Modified without a cached registry")
+            text, etag = cached.text, etag or cached.etag
+        registry = ModelsRegistry.parse(text)
+    except (httpx.HTTPError, ModelsRegistryError) as e:
+        logger.warning("Could not load the models registry from %s: %s", url, e)
+        return cached.registry if cached else None
+
+    _write_cache(cache_file, url, text, etag)
+    return registry
+
+
+def _fetch(url: str, etag: str | None, *, options: dict[str, Any]) -> FetchResult:
+    headers = {"If-None-Match": etag} if etag else {}
+    with httpx.Client(
+        timeout=FETCH_TIMEOUT_SECONDS,
+        follow_redirects=True,
+        event_hooks={"request": [require_https]},
+        **options,
+    ) as client:
+        response = client.get(url, headers=headers)
+    if response.status_code == httpx.codes.NOT_MODIFIED:
+        return FetchResult(None, response.headers.get("etag"))
+    response.raise_for_status()
+    return FetchResult(response.text, response.headers.get("etag"))
+
+
+class _CachedRegistry(NamedTuple):
+    registry: ModelsRegistry
+    text: str
+    etag: str | None
+    fetched_at: float
+
+
+def _read_cache(cache_file: Path, url: str) -> _CachedRegistry | None:
+    try:
+        data = json.loads(cache_file.read_text(encoding="utf-8"))
+        if data.get("url") != url:
+            return None
+        text = data["document"]
+        return _CachedRegistry(
+            ModelsRegistry.parse(text),
+            text,
+            data.get("etag"),
+            float(data["fetched_at"]),
+        )
+    except (OSError, ValueError, KeyError, TypeError, ModelsRegistryError):
+        return None
+
+
+def _write_cache(cache_file: Path, url: str, text: str, etag: str | None) -> None:
+    try:
+        cache_file.parent.mkdir(parents=True, exist_ok=True)
+        cache_file.write_text(
+            json.dumps({
+                "url": url,
+                "etag": etag,
+                "fetched_at": time.time(),
+                "document": text,
+            }),
+            encoding="utf-8",
+        )
+    except OSError as e:
+        logger.warning("Could not cache the models registry: %s", e)
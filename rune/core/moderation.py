@@ -0,0 +1,126 @@
+from __future__ import annotations
+
+import asyncio
+from collections.abc import Callable
+import json
+from typing import TYPE_CHECKING, Any
+
+import httpx
+from pydantic import BaseModel, ValidationError
+
+from rune.core.llm.backend.transport import TransportConfigError, tls_options
+from rune.core.network import proxy_options
+from rune.core.types import LLMMessage, ModerationAction
+from rune.core.utils import logger
+
+if TYPE_CHECKING:
+    from rune.core.config import ModerationConfig, NetworkConfig, RuneConfig
+
+
+class ModerationError(Exception):
+    pass
+
+
+class ModerationVerdict(BaseModel):
+    action: ModerationAction = ModerationAction.ALLOW
+    message: str = ""
+
+
+def describe_verdict(action: ModerationAction, message: str) -> str:
+    label = (
+        "Response blocked by moderation hook"
+        if action == ModerationAction.BLOCK
+        else "Moderation note"
+    )
+    return f"{label}: {message}" if message else label
+
+
+def build_payload(message: LLMMessage, session_id: str) -> dict[str, Any]:
+    return {
+        "session_id": session_id,
+        "content": message.content or "",
+        "tool_calls": [
+            {"name": call.function.name, "arguments": call.function.arguments}
+            for call in message.tool_calls or []
+        ],
+    }
+
+
+class ModerationHook:
+    """Sends each assistant response to an external checker before it is used.
+
+    The checker is either a command (payload on stdin, verdict on stdout) or an
+    HTTP endpoint (payload as the POST body, verdict as the JSON response). The
+    verdict is ``{"action": "allow" | "annotate" | "block", "message": "..."}``.
+    """
+
+    def __init__(self, config_getter: Callable[[], RuneConfig]) -> None:
+        self._config_getter = config_getter
+
+    @property
+    def enabled(self) -> bool:
+        return self._config_getter().moderation.enabled
+
+    async def check(self, message: LLMMessage, session_id: str) -> ModerationVerdict:
+        rune_config = self._config_getter()
+        config = rune_config.moderation
+        payload = build_payload(message, session_id)
+        try:
+            raw = (
+                await self._run_command(config, payload)
+                if config.command
+                else await self._post(config, rune_config.network, payload)
+            )
+            return ModerationVerdict.model_validate_json(raw or "{}")
+        except (
+            ModerationError,
+            ValidationError,
+            TransportConfigError,
+            httpx.HTTPError,
+            OSError,
+        ) as e:
+            logger.warning("Moderation hook failed: %s", e)
+            if config.fail_closed:
+                return ModerationVerdict(
+                    action=ModerationAction.BLOCK,
+                    message=f"Moderation hook failed: {e}",
+                )
+            return ModerationVerdict()
+
+    async def _run_command(
+        self, config: ModerationConfig, payload: dict[str, Any]
+    ) -> str:
+        proc = await asyncio.create_subprocess_exec(
+            *config.command,
+            stdin=asyncio.subprocess.PIPE,
+            stdout=asyncio.subprocess.PIPE,
+            stderr=asyncio.subprocess.PIPE,
+        )
+        try:
+            stdout, stderr = await asyncio.wait_for(
+                proc.communicate(json.dumps(payload).encode()), timeout=config.timeout
+            )
+        except TimeoutError as e:
+            proc.kill()
+            raise ModerationError(f"timed out after {config.timeout}s") from e
+
+        if proc.returncode != 0:
+            raise ModerationError(
+                f"command exited with {proc.returncode}: "
+                f"{stderr.decode(errors='replace').strip()}"
+            )
+        return stdout.decode(errors="replace").strip()
+
+    async def _post(
+        self, config: ModerationConfig, network: NetworkConfig, payload: dict[str, Any]
+    ) -> str:
+        async with httpx.AsyncClient(
+            timeout=config.timeout,
+            **tls_options(config, "The moderation hook"),
+            **proxy_options(network, config.url),
+        ) as client:
+            response = await client.post(
+                config.url, json=payload, headers=config.headers
+            )
+            response.raise_for_status()
+            return response.text
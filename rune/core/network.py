@@ -0,0 +1,87 @@
+from __future__ import annotations
+
+from http import HTTPStatus
+import os
+from typing import TYPE_CHECKING, Any
+from urllib.parse import urlsplit
+from urllib.request import proxy_bypass_environment
+
+import httpx
+
+if TYPE_CHECKING:
+    from rune.core.config import NetworkConfig
+
+
+def proxy_options(
+    network: NetworkConfig | None, url: str, proxy_url: str | None = None
+) -> dict[str, Any]:
+    """`httpx` client arguments to reach ``url`` through the configured proxy.
+
+    ``proxy_url`` overrides ``network.proxy_url``. Without either, httpx falls
+    back to the ``HTTPS_PROXY`` environment on its own, and credentials from
+    ``network`` are not added to it.
+    """
+    no_proxy = list(network.no_proxy) if network else []
+    if _bypassed(url, no_proxy):
+        # Keeps httpx from picking the environment's proxy instead.
+        return {"trust_env": False}
+    proxy_url = proxy_url or (network.proxy_url if network else "")
+    if not proxy_url:
+        return {}
+    auth = network.proxy_auth() if network else None
+    return {"proxy": httpx.Proxy(proxy_url, auth=auth)}
+
+
+def _bypassed(url: str, no_proxy: list[str]) -> bool:
+    host = urlsplit(url).netloc.rpartition("@")[2]
+    if not host:
+        return False
+    entries = [*no_proxy]
+    if env := os.environ.get("NO_PROXY") or os.environ.get("no_proxy"):
+        entries.append(env)
+    if not entries:
+        return False
+    return bool(proxy_bypass_environment(host, {"no": ",".join(entries)}))
+
+
+def require_https(request: httpx.Request) -> None:
+    """Request event hook that refuses plain-HTTP requests, so a redirect can't
+    downgrade a fetch that was checked to be HTTPS.
+    """
+    if request.url.scheme != "https":
+        raise httpx.UnsupportedProtocol(
+            f"Refusing to fetch {request.url} over plain HTTP", request=request
+        )
+
+
+def describe_proxy(url: str) -> str:
+    """``url`` without the credentials it may hold."""
+    parts = urlsplit(url)
+    return parts._replace(netloc=parts.netloc.rpartition("@")[2]).geturl()
+
+
+def configured_proxy(
+    network: NetworkConfig | None, proxy_url: str | None = None
+) -> str | None:
+    """The proxy set in the config, as it can be shown to the user."""
+    if url := proxy_url or (network.proxy_url if network else ""):
+        return describe_proxy(url)
+    return None
+
+
+def proxy_auth_rejected(error: BaseException) -> bool:
+    """Whether a request failed because the proxy refused its credentials."""
+    current: BaseException | None = error
+    while current is not None:
+        status = getattr(current, "status", None)
+        if status == HTTPStatus.PROXY_AUTHENTICATION_REQUIRED:
+            return True
+        if isinstance(current, httpx.ProxyError) and "407" in str(current):
+            return True
+        # MCP clients fail from inside anyio task groups.
+        if isinstance(current, BaseExceptionGroup) and any(
+            proxy_auth_rejected(e) for e in current.exceptions
+        ):
+            return True
+        current = current.__cause__ or current.__context__
+    return False
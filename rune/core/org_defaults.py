@@ -0,0 +1,332 @@
+from __future__ import annotations
+
+from collections.abc import Callable
+from functools import partial
+import json
+from logging import getLogger
+import os
+from pathlib import Path
+import threading
+import time
+import tomllib
+from typing import TYPE_CHECKING, Annotated, Any
+from urllib.parse import urlparse
+
+from cryptography.hazmat.primitives.asymmetric.ed25519 import Ed25519PublicKey
+import httpx
+from pydantic import BaseModel, Field, TypeAdapter, ValidationError
+import tomli_w
+
+from rune.core.network import proxy_options, require_https
+from rune.core.paths.global_paths import ORG_DEFAULTS_CACHE_FILE
+from rune.core.policy_bundle import (
+    PolicyBundleError,
+    load_trusted_keys,
+    sign_payload,
+    verify_payload,
+)
+
+if TYPE_CHECKING:
+    from rune.core.config import NetworkConfig
+
+logger = getLogger("rune")
+
+ORG_DEFAULTS_URL_ENV = "RUNE_ORG_DEFAULTS_URL"
+DEFAULT_TTL_SECONDS = 3600
+FETCH_TIMEOUT_SECONDS = 10.0
+# After a failed fetch the URL isn't tried again for this long, at most the TTL,
+# so an unreachable server doesn't stall every config load.
+FAILURE_TTL_SECONDS = 300
+# Settings that only make sense per machine, or would let the document point
+# itself somewhere else.
+_FORBIDDEN_KEYS = frozenset({"org_defaults_url", "org_defaults_ttl"})
+
+
+class OrgDefaultsError(Exception):
+    pass
+
+
+class LockedConfigError(ValueError):
+    pass
+
+
+class OrgDefaults(BaseModel):
+    """Config defaults an organization publishes for all its developer machines.
+
+    The document is signed like a policy bundle, and ``defaults`` holds config
+    keys as they would appear in config.toml. Keys listed in ``locked``, dotted
+    for nested ones, are enforced: nothing on the machine can set them to
+    another value.
+    """
+
+    name: str = ""
+    defaults: dict[str, Any] = Field(default_factory=dict)
+    locked: list[str] = Field(default_factory=list)
+    signed_by: str | None = None
+    signature: str | None = None
+
+    @classmethod
+    def parse(cls, text: str) -> OrgDefaults:
+        try:
+            return cls.model_validate(tomllib.loads(text))
+        except (tomllib.TOMLDecodeError, ValidationError) as e:
+            raise OrgDefaultsError(f"Invalid org defaults document: {e}") from e
+
+    def to_toml(self) -> str:
+        return tomli_w.dumps(self.model_dump(mode="json", exclude_none=True))
+
+    def payload(self) -> bytes:
+        data = self.model_dump(mode="json", exclude={"signed_by", "signature"})
+        if not self.locked:
+            # Documents signed before locks existed keep verifying.
+            del data["locked"]
+        return json.dumps(data, sort_keys=True, separators=(",", ":")).encode()
+
+    def sign(self, private_key_pem: bytes) -> OrgDefaults:
+        signed_by, signature = sign_payload(self.payload(), private_key_pem)
+        return self.model_copy(update={"signed_by": signed_by, "signature": signature})
+
+    def verify(self, trusted_keys: list[Ed25519PublicKey]) -> str:
+        return verify_payload(
+            self.payload(), self.signature, self.signed_by, trusted_keys
+        )
+
+    def validated_defaults(self, schema: type[BaseModel]) -> dict[str, Any]:
+        """The defaults that are valid settings of ``schema``.
+
+        Entries are checked one by one, so a key this version of Rune doesn't
+        know or a bad value is dropped instead of breaking every machine's
+        config.
+        """
+        defaults: dict[str, Any] = {}
+        for key, value in self.defaults.items():
+            field = schema.model_fields.get(key)
+            if key in _FORBIDDEN_KEYS or field is None:
+                logger.warning("Ignoring org default '%s': not a config key", key)
+                continue
+            annotation = field.annotation
+            if field.metadata:
+                annotation = Annotated[annotation, *field.metadata]
+            try:
+                TypeAdapter(annotation).validate_python(value)
+            except ValidationError as e:
+                logger.warning("Ignoring org default '%s': %s", key, e)
+                continue
+            defaults[key] = value
+        return defaults
+
+    def locked_values(self, defaults: dict[str, Any]) -> dict[str, Any]:
+        """The locked keys and the values they are locked to, taken from the
+        validated ``defaults``.
+        """
+        values: dict[str, Any] = {}
+        for key in self.locked:
+            try:
+                values[key] = _lookup(defaults, key)
+            except KeyError:
+                logger.warning("Ignoring lock on '%s': no valid default for it", key)
+        return values
+
+
+def check_locked_keys(
+    locked: dict[str, Any], policy: str, sources: dict[str, dict[str, Any]]
+) -> None:
+    """Raise if one of ``sources``, config data by where it comes from, sets
+    a locked key to anything but its locked value.
+    """
+    for key, value in locked.items():
+        for where, data in sources.items():
+            try:
+                current = _lookup(data, key)
+            except KeyError:
+                continue
+            if not _same(current, value):
+                raise LockedConfigError(
+                    f"'{key}' is locked to {json.dumps(value)} by {policy}; "
+                    f"remove it from {where}"
+                )
+
+
+def locked_settings(locked: dict[str, Any]) -> dict[str, Any]:
+    """``locked`` as nested config data."""
+    settings: dict[str, Any] = {}
+    for key, value in locked.items():
+        *tables, leaf = key.split(".")
+        node = settings
+        for table in tables:
+            node = node.setdefault(table, {})
+        node[leaf] = value
+    return settings
+
+
+def _lookup(data: dict[str, Any], key: str) -> Any:
+    node: Any = data
+    for part in key.split("."):
+        if not isinstance(node, dict) or part not in node:
+            raise KeyError(key)
+        node = node[part]
+    return node
+
+
+def _same(current: Any, value: Any) -> bool:
+    if current == value:
+        return True
+    # Environment variables hold every value as text.
+    if isinstance(current, str) and not isinstance(value, str):
+        try:
+            return json.loads(current) == value
+        except ValueError:
+            return False
+    return False
+
+
+def org_defaults_url(user_config: dict[str, Any]) -> str | None:
+    return os.getenv(ORG_DEFAULTS_URL_ENV) or user_config.get("org_defaults_url")
+
+
+def load_org_defaults(
+    url: str,
+    *,
+    ttl: float = DEFAULT_TTL_SECONDS,
+    trusted_keys: list[Ed25519PublicKey] | None = None,
+    cache_file: Path | None = None,
+    fetch: Callable[[str], str] | None = None,
+    refresh_in_background: bool = False,
+    network: NetworkConfig | None = None,
+) -> OrgDefaults | None:
+    """Fetch, verify and cache the org defaults document at ``url``.
+
+    A verified copy is cached and reused for ``ttl`` seconds. When the URL can't
+    be fetched or serves a document that fails verification, the last verified
+    copy is used instead, however old, so machines keep their defaults offline.
+    A failed fetch isn't retried for ``FAILURE_TTL_SECONDS``.
+
+    With ``refresh_in_background``, an expired copy is returned right away and
+    refreshed by a background thread for the next load; only a machine without
+    any cached copy waits for the fetch.
+
+    ``network`` is the user's `[network]` table, for its proxy.
+    """
+    fetch = fetch or partial(_fetch, network=network)
+    cache_file = cache_file or ORG_DEFAULTS_CACHE_FILE.path
+    trusted_keys = load_trusted_keys() if trusted_keys is None else trusted_keys
+    cached, fetched_at, failed_at = _read_cache(cache_file, url, trusted_keys)
+    now = time.time()
+    if cached is not None and now - fetched_at < ttl:
+        return cached
+    if now - failed_at < min(ttl, FAILURE_TTL_SECONDS):
+        return cached
+    if cached is not None and refresh_in_background:
+        if _refresh_lock.acquire(blocking=False):
+            threading.Thread(
+                target=_refresh_locked,
+                args=(url, trusted_keys, cache_file, fetch),
+                name="org-defaults-refresh",
+                daemon=True,
+            ).start()
+        return cached
+    return _refresh(url, trusted_keys, cache_file, fetch) or cached
+
+
+# Held while a background refresh runs, so loads don't start one each.
+_refresh_lock = threading.Lock()
+
+
+def _refresh_locked(
+    url: str,
+    trusted_keys: list[Ed25519PublicKey],
+    cache_file: Path,
+    fetch: Callable[[str], str] | None,
+) -> None:
+    try:
+        _refresh(url, trusted_keys, cache_file, fetch)
+    finally:
+        _refresh_lock.release()
+
+
+def _refresh(
+    url: str,
+    trusted_keys: list[Ed25519PublicKey],
+    cache_file: Path,
+    fetch: Callable[[str], str] | None,
+) -> OrgDefaults | None:
+    try:
+        if urlparse(url).scheme != "https":
+            raise OrgDefaultsError(f"Org defaults must be served over HTTPS: {url}")
+        text = (fetch or _fetch)(url)
+        document = OrgDefaults.parse(text)
+        _verify(document, trusted_keys)
+    except (httpx.HTTPError, OrgDefaultsError) as e:
+        logger.warning("Could not load org defaults from %s: %s", url, e)
+        _record_failure(cache_file, url)
+        return None
+
+    _write_cache(cache_file, url, text)
+    return document
+
+
+def _fetch(url: str, network: NetworkConfig | None = None) -> str:
+    with httpx.Client(
+        timeout=FETCH_TIMEOUT_SECONDS,
+        follow_redirects=True,
+        event_hooks={"request": [require_https]},
+        **proxy_options(network, url),
+    ) as client:
+        response = client.get(url)
+    response.raise_for_status()
+    return response.text
+
+
+def _verify(document: OrgDefaults, trusted_keys: list[Ed25519PublicKey]) -> None:
+    try:
+        document.verify(trusted_keys)
+    except PolicyBundleError as e:
+        raise OrgDefaultsError(f"Signature check failed: {e}") from e
+
+
+def _read_cache(
+    cache_file: Path, url: str, trusted_keys: list[Ed25519PublicKey]
+) -> tuple[OrgDefaults | None, float, float]:
+    """The cached document with when it was fetched, and when a fetch last
+    failed.
+    """
+    data = _read_cache_data(cache_file, url)
+    try:
+        failed_at = float(data.get("failed_at", 0.0))
+    except (ValueError, TypeError):
+        failed_at = 0.0
+    try:
+        # The cache is re-verified, so editing it doesn't bypass the signature.
+        document = OrgDefaults.parse(data["document"])
+        _verify(document, trusted_keys)
+        return document, float(data["fetched_at"]), failed_at
+    except (ValueError, KeyError, TypeError, OrgDefaultsError):
+        return None, 0.0, failed_at
+
+
+def _read_cache_data(cache_file: Path, url: str) -> dict[str, Any]:
+    try:
+        data = json.loads(cache_file.read_text(encoding="utf-8"))
+    except (OSError, ValueError):
+        return {}
+    if not isinstance(data, dict) or data.get("url") != url:
+        return {}
+    return data
+
+
+def _write_cache(cache_file: Path, url: str, text: str) -> None:
+    _store_cache(cache_file, {"url": url, "fetched_at": time.time(), "document": text})
+
+
+def _record_failure(cache_file: Path, url: str) -> None:
+    # The last verified copy is kept next to the failure.
+    data = _read_cache_data(cache_file, url)
+    _store_cache(cache_file, {**data, "url": url, "failed_at": time.time()})
+
+
+def _store_cache(cache_file: Path, data: dict[str, Any]) -> None:
+    try:
+        cache_file.parent.mkdir(parents=True, exist_ok=True)
+        cache_file.write_text(json.dumps(data), encoding="utf-8")
+    except OSError as e:
+        logger.warning("Could not cache org defaults: %s", e)
@@ -3,8 +3,9 @@ from __future__ import annotations
 from abc import ABC, abstractmethod
 import json
 import sys
-from typing import TextIO
+from typing import Any, TextIO
 
+from rune.core.status import UsageStatus
 from rune.core.types import AssistantEvent, BaseEvent, LLMMessage, OutputFormat
 
 
@@ -13,6 +14,16 @@ class OutputFormatter(ABC):
         self.stream = stream
         self._messages: list[LLMMessage] = []
         self._final_response: str | None = None
+        self._usage: UsageStatus | None = None
+
+    def on_usage(self, usage: UsageStatus) -> None:
+        """Token usage and cost of the run, reported before `finalize`."""
+        self._usage = usage
+
+    def _usage_record(self) -> dict[str, Any] | None:
+        if self._usage is None:
+            return None
+        return {"type": "usage", **self._usage.model_dump(mode="json", by_alias=True)}
 
     @abstractmethod
     def on_message_added(self, message: LLMMessage) -> None:
@@ -52,7 +63,11 @@ class JsonOutputFormatter(OutputFormatter):
         pass
 
     def finalize(self) -> str | None:
-        messages_data = [msg.model_dump(mode="json") for msg in self._messages]
+        messages_data: list[dict[str, Any]] = [
+            msg.model_dump(mode="json") for msg in self._messages
+        ]
+        if usage := self._usage_record():
+            messages_data.append(usage)
         json.dump(messages_data, self.stream, indent=2, ensure_ascii=False)
         self.stream.write("\n")
         self.stream.flush()
@@ -69,6 +84,10 @@ class StreamingJsonOutputFormatter(OutputFormatter):
         pass
 
     def finalize(self) -> str | None:
+        if usage := self._usage_record():
+            json.dump(usage, self.stream, ensure_ascii=False)
+            self.stream.write("\n")
+            self.stream.flush()
         return None
 
 
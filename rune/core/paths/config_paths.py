@@ -1,5 +1,8 @@
 from __future__ import annotations
 
+from collections.abc import Iterator
+from contextlib import contextmanager
+from contextvars import ContextVar
 from pathlib import Path
 from typing import Literal
 
@@ -7,6 +10,9 @@ from rune.core.paths.global_paths import RUNE_HOME, GlobalPath
 from rune.core.trusted_folders import trusted_folders_manager
 
 _config_paths_locked: bool = True
+# Set while working for a thread started somewhere other than the process's
+# working directory, such as an ACP session.
+_project_dir: ContextVar[Path | None] = ContextVar("project_dir", default=None)
 
 
 class ConfigPath(GlobalPath):
@@ -17,8 +23,23 @@ class ConfigPath(GlobalPath):
         return super().path
 
 
+def project_dir() -> Path:
+    """The directory whose `.rune` folder holds the project's settings."""
+    return _project_dir.get() or Path.cwd()
+
+
+@contextmanager
+def use_project_dir(path: Path) -> Iterator[None]:
+    """Resolve project settings under `path` until the block ends."""
+    token = _project_dir.set(path)
+    try:
+        yield
+    finally:
+        _project_dir.reset(token)
+
+
 def _resolve_config_path(basename: str, type: Literal["file", "dir"]) -> Path:
-    cwd = Path.cwd()
+    cwd = project_dir()
     is_folder_trusted = trusted_folders_manager.is_trusted(cwd)
     if not is_folder_trusted:
         return RUNE_HOME.path / basename
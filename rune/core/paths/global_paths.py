@@ -29,11 +29,21 @@ RUNE_HOME = GlobalPath(_get_rune_home)
 GLOBAL_CONFIG_FILE = GlobalPath(lambda: RUNE_HOME.path / "config.toml")
 GLOBAL_ENV_FILE = GlobalPath(lambda: RUNE_HOME.path / ".env")
 GLOBAL_TOOLS_DIR = GlobalPath(lambda: RUNE_HOME.path / "tools")
+GLOBAL_PLUGINS_DIR = GlobalPath(lambda: RUNE_HOME.path / "plugins")
 GLOBAL_SKILLS_DIR = GlobalPath(lambda: RUNE_HOME.path / "skills")
 GLOBAL_AGENTS_DIR = GlobalPath(lambda: RUNE_HOME.path / "agents")
 GLOBAL_PROMPTS_DIR = GlobalPath(lambda: RUNE_HOME.path / "prompts")
 SESSION_LOG_DIR = GlobalPath(lambda: RUNE_HOME.path / "logs" / "session")
 TRUSTED_FOLDERS_FILE = GlobalPath(lambda: RUNE_HOME.path / "trusted_folders.toml")
+TRUSTED_KEYS_DIR = GlobalPath(lambda: RUNE_HOME.path / "trusted_keys")
+ORG_DEFAULTS_CACHE_FILE = GlobalPath(lambda: RUNE_HOME.path / "org_defaults.json")
+MODELS_REGISTRY_CACHE_FILE = GlobalPath(lambda: RUNE_HOME.path / "models_registry.json")
+DELIVERABLES_DIR = GlobalPath(lambda: RUNE_HOME.path / "deliverables")
+SECRETS_FILE = GlobalPath(lambda: RUNE_HOME.path / "secrets.toml")
+AUTH_FILE = GlobalPath(lambda: RUNE_HOME.path / "auth.json")
+MEMORY_DB_FILE = GlobalPath(lambda: RUNE_HOME.path / "memory.sqlite3")
+DAEMON_DIR = GlobalPath(lambda: RUNE_HOME.path / "daemons")
+WORKSPACE_LEASES_DIR = GlobalPath(lambda: RUNE_HOME.path / "leases")
 LOG_DIR = GlobalPath(lambda: RUNE_HOME.path / "logs")
 LOG_FILE = GlobalPath(lambda: RUNE_HOME.path / "rune.log")
 
@@ -0,0 +1,186 @@
+from __future__ import annotations
+
+import base64
+import binascii
+from enum import StrEnum, auto
+import hashlib
+import json
+from pathlib import Path
+import tomllib
+from typing import Any
+
+from cryptography.exceptions import InvalidSignature
+from cryptography.hazmat.primitives import serialization
+from cryptography.hazmat.primitives.asymmetric.ed25519 import (
+    Ed25519PrivateKey,
+    Ed25519PublicKey,
+)
+from pydantic import BaseModel, Field, ValidationError
+import tomli_w
+
+from rune.core.paths.global_paths import TRUSTED_KEYS_DIR
+from rune.core.tools.base import ToolPermission
+
+
+class PolicyBundleError(Exception):
+    pass
+
+
+class BundleSignatureError(PolicyBundleError):
+    pass
+
+
+class BundleKind(StrEnum):
+    APPROVALS = auto()
+    POLICY = auto()
+
+
+class ToolRules(BaseModel):
+    permission: ToolPermission | None = None
+    allowlist: list[str] = Field(default_factory=list)
+    denylist: list[str] = Field(default_factory=list)
+
+
+class PolicyBundle(BaseModel):
+    """A portable set of tool rules that can be shared and merged into a config.
+
+    Approval bundles carry what runs without asking (permissions and allowlists);
+    policy bundles carry what must never run (denylists and enabled/disabled tools).
+    """
+
+    kind: BundleKind
+    name: str = ""
+    tools: dict[str, ToolRules] = Field(default_factory=dict)
+    enabled_tools: list[str] = Field(default_factory=list)
+    disabled_tools: list[str] = Field(default_factory=list)
+    signed_by: str | None = None
+    signature: str | None = None
+
+    @classmethod
+    def from_config_data(
+        cls, kind: BundleKind, data: dict[str, Any], name: str = ""
+    ) -> PolicyBundle:
+        tools: dict[str, ToolRules] = {}
+        for tool_name, raw in (data.get("tools") or {}).items():
+            if not isinstance(raw, dict):
+                continue
+            if kind == BundleKind.APPROVALS:
+                rules = ToolRules(
+                    permission=raw.get("permission"), allowlist=raw.get("allowlist", [])
+                )
+            else:
+                rules = ToolRules(denylist=raw.get("denylist", []))
+            if rules.permission or rules.allowlist or rules.denylist:
+                tools[tool_name] = rules
+
+        if kind == BundleKind.APPROVALS:
+            return cls(kind=kind, name=name, tools=tools)
+        return cls(
+            kind=kind,
+            name=name,
+            tools=tools,
+            enabled_tools=data.get("enabled_tools", []),
+            disabled_tools=data.get("disabled_tools", []),
+        )
+
+    @classmethod
+    def load(cls, path: Path) -> PolicyBundle:
+        try:
+            with path.open("rb") as f:
+                return cls.model_validate(tomllib.load(f))
+        except (OSError, tomllib.TOMLDecodeError, ValidationError) as e:
+            raise PolicyBundleError(f"Invalid bundle {path}: {e}") from e
+
+    def to_toml(self) -> str:
+        return tomli_w.dumps(self.model_dump(mode="json", exclude_none=True))
+
+    def payload(self) -> bytes:
+        data = self.model_dump(mode="json", exclude={"signed_by", "signature"})
+        return json.dumps(data, sort_keys=True, separators=(",", ":")).encode()
+
+    def sign(self, private_key_pem: bytes) -> PolicyBundle:
+        signed_by, signature = sign_payload(self.payload(), private_key_pem)
+        return self.model_copy(update={"signed_by": signed_by, "signature": signature})
+
+    def verify(self, trusted_keys: list[Ed25519PublicKey]) -> str:
+        """Return the fingerprint of the trusted key that signed this bundle."""
+        return verify_payload(
+            self.payload(), self.signature, self.signed_by, trusted_keys
+        )
+
+    def to_config_updates(self) -> dict[str, Any]:
+        updates: dict[str, Any] = {
+            "tools": {
+                name: rules.model_dump(mode="json", exclude_none=True)
+                for name, rules in self.tools.items()
+            }
+        }
+        if self.enabled_tools:
+            updates["enabled_tools"] = self.enabled_tools
+        if self.disabled_tools:
+            updates["disabled_tools"] = self.disabled_tools
+        return updates
+
+
+def sign_payload(payload: bytes, private_key_pem: bytes) -> tuple[str, str]:
+    """Sign ``payload``, returning the key fingerprint and base64 signature."""
+    private_key = serialization.load_pem_private_key(private_key_pem, password=None)
+    if not isinstance(private_key, Ed25519PrivateKey):
+        raise PolicyBundleError("Bundles must be signed with an Ed25519 key")
+    signature = private_key.sign(payload)
+    return (
+        key_fingerprint(private_key.public_key()),
+        base64.b64encode(signature).decode("ascii"),
+    )
+
+
+def verify_payload(
+    payload: bytes,
+    signature: str | None,
+    signed_by: str | None,
+    trusted_keys: list[Ed25519PublicKey],
+) -> str:
+    """Return the fingerprint of the trusted key that signed ``payload``."""
+    if not signature:
+        raise BundleSignatureError("Bundle is not signed")
+    try:
+        raw_signature = base64.b64decode(signature, validate=True)
+    except (binascii.Error, ValueError) as e:
+        raise BundleSignatureError("Bundle signature is not valid base64") from e
+
+    for key in trusted_keys:
+        fingerprint = key_fingerprint(key)
+        if signed_by and signed_by != fingerprint:
+            continue
+        try:
+            key.verify(raw_signature, payload)
+        except InvalidSignature:
+            continue
+        return fingerprint
+    raise BundleSignatureError(
+        f"Bundle signature does not match any trusted key (signed by "
+        f"{signed_by or 'unknown'})"
+    )
+
+
+def key_fingerprint(key: Ed25519PublicKey) -> str:
+    raw = key.public_bytes(
+        encoding=serialization.Encoding.Raw, format=serialization.PublicFormat.Raw
+    )
+    return hashlib.sha256(raw).hexdigest()[:16]
+
+
+def load_trusted_keys(directory: Path | None = None) -> list[Ed25519PublicKey]:
+    directory = directory or TRUSTED_KEYS_DIR.path
+    if not directory.is_dir():
+        return []
+
+    keys: list[Ed25519PublicKey] = []
+    for pem in sorted(directory.glob("*.pem")):
+        try:
+            key = serialization.load_pem_public_key(pem.read_bytes())
+        except (OSError, ValueError):
+            continue
+        if isinstance(key, Ed25519PublicKey):
+            keys.append(key)
+    return keys
@@ -0,0 +1,127 @@
+from __future__ import annotations
+
+from collections.abc import Callable
+from dataclasses import dataclass
+from typing import TYPE_CHECKING, Any
+
+from pydantic import BaseModel
+
+if TYPE_CHECKING:
+    from rune.core.config import RuneConfig
+
+# Bumped when a category is added or starts collecting more, so that users are
+# shown the notice again.
+PRIVACY_NOTICE_VERSION = 1
+
+
+class PrivacyError(Exception):
+    pass
+
+
+@dataclass(frozen=True)
+class DataCategory:
+    name: str
+    title: str
+    collects: str
+    # Dotted config key of the toggle; None when the category is configured by
+    # pointing it somewhere, usually by an administrator.
+    key: str | None
+    enabled: Callable[[RuneConfig], bool]
+    destination: Callable[[RuneConfig], str]
+
+
+class CategoryStatus(BaseModel):
+    name: str
+    title: str
+    collects: str
+    enabled: bool
+    destination: str
+    key: str | None
+
+
+CATEGORIES: list[DataCategory] = [
+    DataCategory(
+        name="update_checks",
+        title="Update checks",
+        collects="Your installed version and IP address, when looking for a "
+        "newer release.",
+        key="enable_update_checks",
+        enabled=lambda config: config.enable_update_checks,
+        destination=lambda _: "PyPI and GitHub",
+    ),
+    DataCategory(
+        name="session_logs",
+        title="Session logs",
+        collects="Full transcripts of your sessions, for --continue and --resume.",
+        key="session_logging.enabled",
+        enabled=lambda config: config.session_logging.enabled,
+        destination=lambda config: "this machine, in "
+        + config.session_logging.save_dir,
+    ),
+    DataCategory(
+        name="session_titles",
+        title="Session titles",
+        collects="The opening exchange of each session, to name it.",
+        key="auto_title.enabled",
+        enabled=lambda config: config.auto_title.enabled,
+        destination=lambda config: "the provider of "
+        + (config.auto_title.model or "the active model"),
+    ),
+    DataCategory(
+        name="approval_audit",
+        title="Approval audit logs",
+        collects="Each approval decision with the tool name, session id and a hash "
+        "of the command, never the command itself.",
+        key=None,
+        enabled=lambda config: config.otel.enabled,
+        destination=lambda config: config.otel.logs_endpoint
+        or "set otel.logs_endpoint to enable",
+    ),
+    DataCategory(
+        name="org_defaults",
+        title="Organization defaults",
+        collects="Nothing but the request for the signed defaults document.",
+        key=None,
+        enabled=lambda config: bool(config.org_defaults_url),
+        destination=lambda config: config.org_defaults_url
+        or "set org_defaults_url to enable",
+    ),
+]
+
+
+def category_status(config: RuneConfig) -> list[CategoryStatus]:
+    return [
+        CategoryStatus(
+            name=category.name,
+            title=category.title,
+            collects=category.collects,
+            enabled=category.enabled(config),
+            destination=category.destination(config),
+            key=category.key,
+        )
+        for category in CATEGORIES
+    ]
+
+
+def toggle_update(name: str, enabled: bool) -> dict[str, Any]:
+    """The config update that turns a category on or off."""
+    category = next((c for c in CATEGORIES if c.name == name), None)
+    if category is None:
+        names = ", ".join(c.name for c in CATEGORIES)
+        raise PrivacyError(f"Unknown category '{name}'. Categories: {names}")
+    if category.key is None:
+        raise PrivacyError(
+            f"{category.title} is turned on by configuring where it is sent, "
+            "not with a toggle"
+        )
+    update: dict[str, Any] = {}
+    target = update
+    *parents, leaf = category.key.split(".")
+    for parent in parents:
+        target = target.setdefault(parent, {})
+    target[leaf] = enabled
+    return update
+
+
+def needs_consent(config: RuneConfig) -> bool:
+    return config.privacy_notice_version < PRIVACY_NOTICE_VERSION
@@ -1,12 +1,23 @@
 from __future__ import annotations
 
 import asyncio
+from pathlib import Path
+import sys
+from typing import Any
 
 from rune.core.agent_loop import AgentLoop
 from rune.core.agents.models import BuiltinAgentName
 from rune.core.config import RuneConfig
 from rune.core.output_formatters import create_formatter
-from rune.core.types import AssistantEvent, LLMMessage, OutputFormat, Role
+from rune.core.status import UsageStatus
+from rune.core.types import (
+    ApiKeyWarningEvent,
+    AssistantEvent,
+    LLMMessage,
+    OutputFormat,
+    OutputSchemaUnsupportedEvent,
+    Role,
+)
 from rune.core.utils import ConversationLimitException, logger
 
 
@@ -18,6 +29,8 @@ def run_programmatic(
     output_format: OutputFormat = OutputFormat.TEXT,
     previous_messages: list[LLMMessage] | None = None,
     agent_name: str = BuiltinAgentName.AUTO_APPROVE,
+    workspace_roots: list[Path] | None = None,
+    output_schema: dict[str, Any] | None = None,
 ) -> str | None:
     formatter = create_formatter(output_format)
 
@@ -29,6 +42,8 @@ def run_programmatic(
         max_price=max_price,
         enable_streaming=False,
     )
+    for root in workspace_roots or []:
+        agent_loop.workspace.add(root)
     logger.info("USER: %s", prompt)
 
     async def _async_run() -> str | None:
@@ -41,11 +56,20 @@ def run_programmatic(
                 "Loaded %d messages from previous session", len(non_system_messages)
             )
 
-        async for event in agent_loop.act(prompt):
+        async for event in agent_loop.act(prompt, output_schema=output_schema):
             formatter.on_event(event)
+            if isinstance(event, OutputSchemaUnsupportedEvent):
+                print(
+                    f"Warning: provider '{event.provider}' can't enforce the output "
+                    f"schema, the reply of {event.model} may not match it",
+                    file=sys.stderr,
+                )
+            if isinstance(event, ApiKeyWarningEvent):
+                print(f"Warning: {event.message}", file=sys.stderr)
             if isinstance(event, AssistantEvent) and event.stopped_by_middleware:
                 raise ConversationLimitException(event.content)
 
+        formatter.on_usage(UsageStatus.from_stats(agent_loop.stats, config))
         return formatter.finalize()
 
     return asyncio.run(_async_run())
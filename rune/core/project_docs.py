@@ -0,0 +1,94 @@
+from __future__ import annotations
+
+from pathlib import Path
+
+from pydantic import BaseModel
+
+from rune.core.trusted_folders import TRUSTABLE_FILENAMES, trusted_folders_manager
+
+# Read in this order from each directory, after the instruction files of its
+# parent directories.
+PROJECT_DOC_FILENAMES = [*TRUSTABLE_FILENAMES, ".rune/instructions.md"]
+
+
+class ProjectDoc(BaseModel):
+    path: Path
+    content: str
+    truncated: bool = False
+
+
+def project_root(cwd: Path) -> Path:
+    """The repository ``cwd`` is in, or ``cwd`` itself outside of one."""
+    for directory in [cwd, *cwd.parents]:
+        if (directory / ".git").exists():
+            return directory
+    return cwd
+
+
+def discover_project_docs(cwd: Path) -> list[Path]:
+    """Instruction files from the repository root down to ``cwd``, so the
+    ones closest to ``cwd`` come last.
+    """
+    cwd = cwd.resolve()
+    root = project_root(cwd)
+    directories = [cwd, *cwd.parents]
+    directories = directories[: directories.index(root) + 1]
+    return [
+        directory / name
+        for directory in reversed(directories)
+        for name in PROJECT_DOC_FILENAMES
+        if (directory / name).is_file()
+    ]
+
+
+def load_project_docs(
+    cwd: Path, max_doc_bytes: int, max_total_bytes: int
+) -> list[ProjectDoc]:
+    """The instruction files of ``cwd``, each cut to ``max_doc_bytes``.
+
+    When together they exceed ``max_total_bytes``, the files farthest from
+    ``cwd`` are cut or left out first, as the closer ones take precedence.
+    """
+    if not trusted_folders_manager.is_trusted(cwd):
+        return []
+    docs: list[ProjectDoc] = []
+    budget = max_total_bytes
+    for path in reversed(discover_project_docs(cwd)):
+        if budget <= 0:
+            break
+        try:
+            content = path.read_text("utf-8", errors="ignore")
+        except OSError:
+            continue
+        if not content.strip():
+            continue
+        limit = min(max_doc_bytes, budget)
+        docs.append(
+            ProjectDoc(
+                path=path, content=content[:limit], truncated=len(content) > limit
+            )
+        )
+        budget -= len(docs[-1].content)
+    return docs[::-1]
+
+
+def render_project_docs(docs: list[ProjectDoc], cwd: Path) -> str:
+    sections = [
+        "# Project instructions",
+        "",
+        "Instruction files from the repository root down to the working "
+        "directory. Where they disagree, the later, more specific file wins.",
+    ]
+    for doc in docs:
+        sections += ["", f"## {display_path(doc.path, cwd)}", "", doc.content]
+        if doc.truncated:
+            sections += ["", "(truncated)"]
+    return "\n".join(sections)
+
+
+def display_path(path: Path, cwd: Path) -> str:
+    root = project_root(cwd.resolve())
+    try:
+        return str(path.relative_to(root))
+    except ValueError:
+        return str(path)
@@ -24,8 +24,11 @@ class SystemPrompt(Prompt):
 
 class UtilityPrompt(Prompt):
     COMPACT = auto()
+    CRITIC = auto()
     DANGEROUS_DIRECTORY = auto()
+    EXPLAIN_COMMAND = auto()
     PROJECT_CONTEXT = auto()
+    SESSION_TITLE = auto()
 
 
 __all__ = ["SystemPrompt", "UtilityPrompt"]
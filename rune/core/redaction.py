@@ -0,0 +1,101 @@
+from __future__ import annotations
+
+from collections import Counter
+from fnmatch import fnmatch
+from pathlib import PurePath
+import re
+from typing import Any
+
+from rune.core.secrets import REDACTED, scrub_secrets
+
+# Patterns with a `secret` group only mask that group, keeping what names it.
+SECRET_PATTERNS: dict[str, re.Pattern[str]] = {
+    "api_key": re.compile(r"\b(sk|pk|rk)-[A-Za-z0-9_-]{16,}"),
+    "github_token": re.compile(r"\bgh[pousr]_[A-Za-z0-9]{20,}"),
+    "slack_token": re.compile(r"\bxox[abprs]-[A-Za-z0-9-]{10,}"),
+    "aws_access_key": re.compile(r"\bAKIA[0-9A-Z]{16}\b"),
+    "aws_secret_key": re.compile(
+        r"(?i)\baws_secret_access_key\b[\"']?\s*[:=]\s*[\"']?"
+        r"(?P<secret>[A-Za-z0-9/+=]{40})(?![A-Za-z0-9/+=])"
+    ),
+    "google_api_key": re.compile(r"\bAIza[0-9A-Za-z_-]{35}"),
+    "bearer_token": re.compile(r"(?i)\bbearer\s+[A-Za-z0-9._~+/-]{16,}=*"),
+    "private_key": re.compile(
+        r"-----BEGIN [A-Z ]*PRIVATE KEY-----.*?-----END [A-Z ]*PRIVATE KEY-----",
+        re.DOTALL,
+    ),
+    # `.env` style assignments of literal values to variables named like
+    # credentials. A value that calls or indexes anything is code, not a secret.
+    "env_secret": re.compile(
+        r"(?m)^[ \t]*(?:export[ \t]+)?[A-Za-z0-9_]*"
+        r"(?:KEY|TOKEN|SECRET|PASSWORD|PASSWD|CREDENTIALS?)[A-Za-z0-9_]*"
+        r"[ \t]*=[ \t]*(?P<quote>[\"']?)(?P<secret>[^\s\"'#()\[\]{},;]{8,})"
+        r"(?P=quote)[ \t]*(?:#.*)?$"
+    ),
+}
+# Patterns that only make sense in env and ini files. Source code assigns names
+# like `API_KEY` all the time, so file reads only apply them to such files.
+ENV_FILE_PATTERNS = frozenset({"env_secret"})
+_ENV_FILE_NAMES = (
+    ".env", ".env.*", "*.env", "*.ini", "*.cfg", "*.conf", "*.properties"
+)
+_FILE_TOOLS = frozenset({"read_file", "grep"})
+
+
+def reads_env_file(tool_name: str, args: dict[str, Any]) -> bool:
+    """Whether the output of this tool call may hold an env or ini file. A file
+    read does if its name says so; search results are treated as source code.
+    """
+    if tool_name not in _FILE_TOOLS:
+        return True
+    if tool_name == "grep" or not (path := args.get("path")):
+        return False
+    name = PurePath(str(path)).name
+    return any(fnmatch(name, pattern) for pattern in _ENV_FILE_NAMES)
+
+
+class Redactor:
+    """Masks credentials in text before it leaves the machine."""
+
+    def __init__(
+        self,
+        extra_patterns: dict[str, str] | None = None,
+        disabled: list[str] | None = None,
+    ) -> None:
+        self.patterns = {
+            name: pattern
+            for name, pattern in SECRET_PATTERNS.items()
+            if name not in (disabled or [])
+        }
+        for name, source in (extra_patterns or {}).items():
+            self.patterns[name] = re.compile(source)
+
+    def redact(self, text: str, *, env_file: bool = True) -> tuple[str, Counter[str]]:
+        """``text`` with its secrets masked, and how many of each kind there were.
+        Unless ``env_file`` is set, the patterns for env files are skipped.
+        """
+        found: Counter[str] = Counter()
+        # Values of `${secret:NAME}` references resolved by this process.
+        scrubbed = scrub_secrets(text)
+        if scrubbed != text:
+            found["rune_secret"] += scrubbed.count(REDACTED) - text.count(REDACTED)
+            text = scrubbed
+
+        for name, pattern in self.patterns.items():
+            if not env_file and name in ENV_FILE_PATTERNS:
+                continue
+
+            def mask(match: re.Match[str], name: str = name) -> str:
+                whole = match.group(0)
+                if "secret" not in match.re.groupindex:
+                    found[name] += 1
+                    return REDACTED
+                # Already masked by an earlier pattern.
+                if match.group("secret") == REDACTED:
+                    return whole
+                found[name] += 1
+                start, end = (i - match.start() for i in match.span("secret"))
+                return whole[:start] + REDACTED + whole[end:]
+
+            text = pattern.sub(mask, text)
+        return text, found
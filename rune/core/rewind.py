@@ -0,0 +1,84 @@
+from __future__ import annotations
+
+from typing import TYPE_CHECKING
+
+from rune.core.compaction import split_turns
+from rune.core.context_snapshots import ContextSnapshot, ContextSnapshotError
+from rune.core.session.session_fork import fork_title, history_until_turn
+
+if TYPE_CHECKING:
+    from rune.core.agent_loop import AgentLoop
+    from rune.core.checkpoints import Checkpoint
+
+
+class Rewind:
+    """Takes a thread's conversation back: to a checkpoint, a context snapshot
+    or the end of an earlier turn.
+
+    The shortened conversation continues in a new session, so the one it was
+    taken from stays logged as it was.
+    """
+
+    def __init__(self, agent_loop: AgentLoop) -> None:
+        self._loop = agent_loop
+
+    def create_checkpoint(self, name: str) -> Checkpoint:
+        return self._loop.checkpoints.create(name, len(self._loop.messages))
+
+    async def restore_checkpoint(self, name: str) -> Checkpoint:
+        """Roll the conversation and tracked file edits back to a checkpoint."""
+        loop = self._loop
+        checkpoint = loop.checkpoints.restore(name)
+        await loop.replace_history(loop.messages[1 : checkpoint.message_count])
+        return checkpoint
+
+    def snapshot_context(self) -> ContextSnapshot:
+        loop = self._loop
+        return loop.context_snapshots.take(loop.messages[1:], loop.stats.context_tokens)
+
+    async def restore_context(self, snapshot_id: str) -> ContextSnapshot:
+        """Replace the conversation with a snapshot of it. Files are untouched."""
+        loop = self._loop
+        snapshot = loop.context_snapshots.get(snapshot_id)
+        await loop.replace_history(
+            [m.model_copy(deep=True) for m in snapshot.messages],
+            snapshot.context_tokens,
+        )
+        # Checkpoints point at positions in the replaced history.
+        loop.checkpoints.clear()
+        return snapshot
+
+    async def undo_turns(self, count: int = 1) -> ContextSnapshot:
+        """Drop the last ``count`` turns of the conversation.
+
+        The conversation is snapshotted first, and the snapshot returned, so the
+        undo can itself be undone with ``restore_context``.
+        """
+        loop = self._loop
+        turns = split_turns(loop.messages[1:])
+        if count < 1 or not turns:
+            raise ContextSnapshotError("There are no turns to undo.")
+        snapshot = self.snapshot_context()
+        kept = [m for turn in turns[: max(len(turns) - count, 0)] for m in turn]
+        await loop.replace_history(kept)
+        loop.checkpoints.clear()
+        return snapshot
+
+    async def fork_at_turn(
+        self, turn: int | None = None, source: AgentLoop | None = None
+    ) -> str:
+        """Continue in a new session from the end of user turn ``turn`` (the
+        last one by default) of this conversation, or of ``source``'s. The
+        forked session stays logged as it was. Returns the new session id.
+        """
+        loop = self._loop
+        source = source or loop
+        messages = history_until_turn(source.messages, turn)
+        metadata = source.session_logger.session_metadata
+        forked_from = source.session_id
+        title = fork_title(source.session_logger.get_title(source.messages))
+        await loop.load_history(messages, {"auto_title": title})
+        if metadata is not None:
+            await loop.session_logger.set_tags(metadata.tags)
+        await loop.session_logger.set_forked_from(forked_from, turn)
+        return loop.session_id
@@ -0,0 +1,98 @@
+from __future__ import annotations
+
+from collections.abc import Callable
+import re
+from typing import TYPE_CHECKING
+
+from pydantic import BaseModel
+
+from rune.core.types import AgentStats, LLMMessage, Role
+from rune.core.utils import TOOL_ERROR_TAG, logger
+
+if TYPE_CHECKING:
+    from rune.core.config import ModelConfig, RuneConfig
+
+_CODE_PATTERN = re.compile(
+    r"```|Traceback \(most recent call last\)"
+    r"|^\s*(def|class|import|from|function|const|let|fn|pub|func)\b",
+    re.MULTILINE,
+)
+
+
+class RoutingDecision(BaseModel):
+    model: str
+    reason: str
+
+
+def _current_turn(messages: list[LLMMessage]) -> tuple[str, list[LLMMessage]]:
+    for idx in range(len(messages) - 1, -1, -1):
+        if messages[idx].role == Role.user:
+            return messages[idx].content or "", messages[idx + 1 :]
+    return "", []
+
+
+def _count_failures(turn_messages: list[LLMMessage]) -> int:
+    return sum(
+        1
+        for msg in turn_messages
+        if msg.role == Role.tool
+        and msg.content
+        and f"<{TOOL_ERROR_TAG}>" in msg.content
+    )
+
+
+class ModelRouter:
+    """Picks a light or heavy model for each LLM call from cheap heuristics."""
+
+    def __init__(self, config_getter: Callable[[], RuneConfig]) -> None:
+        self._config_getter = config_getter
+
+    def _find_model(self, alias: str) -> ModelConfig:
+        config = self._config_getter()
+        for model in config.models:
+            if alias and model.alias == alias:
+                return model
+        if alias:
+            logger.warning(
+                "Router model '%s' not found in configuration, using the active model",
+                alias,
+            )
+        return config.get_active_model()
+
+    def route(
+        self,
+        messages: list[LLMMessage],
+        stats: AgentStats,
+        max_price: float | None = None,
+    ) -> tuple[ModelConfig, RoutingDecision] | None:
+        router = self._config_getter().router
+        if not router.enabled or not router.light_model:
+            return None
+
+        prompt, turn_messages = _current_turn(messages)
+        light = self._find_model(router.light_model)
+        heavy = self._find_model(router.heavy_model)
+
+        if max_price and stats.session_cost >= max_price * (1 - router.budget_floor):
+            return light, RoutingDecision(
+                model=light.alias, reason="remaining budget is low"
+            )
+
+        failures = _count_failures(turn_messages)
+        if (
+            router.escalate_after_failures
+            and failures >= router.escalate_after_failures
+        ):
+            return heavy, RoutingDecision(
+                model=heavy.alias, reason=f"{failures} failed tool call(s) this turn"
+            )
+
+        if router.escalate_on_code and _CODE_PATTERN.search(prompt):
+            return heavy, RoutingDecision(
+                model=heavy.alias, reason="request contains code"
+            )
+
+        if len(prompt) > router.max_light_prompt_chars:
+            return heavy, RoutingDecision(model=heavy.alias, reason="long request")
+
+        return light, RoutingDecision(model=light.alias, reason="short request")
@@ -0,0 +1,234 @@
+from __future__ import annotations
+
+import base64
+import binascii
+from collections.abc import Mapping
+import os
+from pathlib import Path
+import re
+import tomllib
+from typing import Any
+
+from cryptography.exceptions import InvalidTag
+from cryptography.hazmat.primitives.ciphers.aead import AESGCM
+import keyring
+import keyring.errors
+import tomli_w
+
+from rune.core.paths.global_paths import SECRETS_FILE
+
+REDACTED = "[REDACTED]"
+# Base64 of a 256-bit key, for machines without a system keyring (CI,
+# containers) or to share one secrets.toml across a team.
+SECRETS_KEY_ENV = "RUNE_SECRETS_KEY"
+SECRET_REF = re.compile(r"\$\{secret:([A-Za-z0-9_.-]+)\}")
+SECRET_NAME = re.compile(r"^[A-Za-z0-9_.-]+$")
+# Config values naming an entry of the OS credential store instead of holding
+# the secret, e.g. `api_key = "keychain:openai"`.
+KEYCHAIN_PREFIX = "keychain:"
+KEYCHAIN_SERVICE = "rune-keys"
+
+_SERVICE_NAME = "rune"
+_KEYRING_USERNAME = "secrets_key"
+_FORMAT_VERSION = 1
+_KEY_SIZE = 32
+_NONCE_SIZE = 12
+# Shorter values are too likely to appear by accident to scrub.
+_MIN_SCRUB_LENGTH = 4
+
+# Values handed out this process, scrubbed from whatever Rune persists.
+_resolved_values: set[str] = set()
+
+
+class SecretsError(Exception):
+    pass
+
+
+def _decode_key(encoded: str, source: str) -> bytes:
+    try:
+        key = base64.b64decode(encoded, validate=True)
+    except (binascii.Error, ValueError) as e:
+        raise SecretsError(f"The secrets key in {source} is not base64") from e
+    if len(key) != _KEY_SIZE:
+        raise SecretsError(f"The secrets key in {source} is not 256 bits")
+    return key
+
+
+def _load_key(*, create: bool) -> bytes:
+    if encoded := os.environ.get(SECRETS_KEY_ENV):
+        return _decode_key(encoded, SECRETS_KEY_ENV)
+    try:
+        encoded = keyring.get_password(_SERVICE_NAME, _KEYRING_USERNAME)
+        if encoded is None and create:
+            encoded = base64.b64encode(AESGCM.generate_key(bit_length=256)).decode()
+            keyring.set_password(_SERVICE_NAME, _KEYRING_USERNAME, encoded)
+    except keyring.errors.KeyringError as e:
+        raise SecretsError(
+            f"The system keyring is unavailable ({e}); set {SECRETS_KEY_ENV} "
+            "to a base64 256-bit key instead"
+        ) from e
+    if encoded is None:
+        raise SecretsError(
+            "No secrets key in the system keyring; add a secret with "
+            f"`rune secrets set` or set {SECRETS_KEY_ENV}"
+        )
+    return _decode_key(encoded, "the system keyring")
+
+
+class SecretsStore:
+    """Named secrets in `$RUNE_HOME/secrets.toml`, each encrypted with AES-GCM
+    under a key kept in the system keyring.
+
+    Only names are readable in the file, so it can be backed up or synced
+    without exposing the values.
+    """
+
+    def __init__(self, path: Path | None = None) -> None:
+        self.path = path or SECRETS_FILE.path
+
+    def _read(self) -> dict[str, str]:
+        try:
+            data = tomllib.loads(self.path.read_text(encoding="utf-8"))
+        except FileNotFoundError:
+            return {}
+        except (OSError, tomllib.TOMLDecodeError) as e:
+            raise SecretsError(f"Could not read {self.path}: {e}") from e
+        if data.get("version", _FORMAT_VERSION) != _FORMAT_VERSION:
+            raise SecretsError(f"Unsupported secrets file version in {self.path}")
+        return dict(data.get("secrets") or {})
+
+    def _write(self, entries: dict[str, str]) -> None:
+        self.path.parent.mkdir(parents=True, exist_ok=True)
+        document = tomli_w.dumps({"version": _FORMAT_VERSION, "secrets": entries})
+        fd = os.open(self.path, os.O_WRONLY | os.O_CREAT | os.O_TRUNC, 0o600)
+        with os.fdopen(fd, "w", encoding="utf-8") as f:
+            f.write(document)
+
+    def names(self) -> list[str]:
+        return sorted(self._read())
+
+    def get(self, name: str) -> str:
+        entries = self._read()
+        if name not in entries:
+            raise SecretsError(f"Unknown secret '{name}'")
+        try:
+            blob = base64.b64decode(entries[name], validate=True)
+            # The name is authenticated too, so values can't be swapped around.
+            value = AESGCM(_load_key(create=False)).decrypt(
+                blob[:_NONCE_SIZE], blob[_NONCE_SIZE:], name.encode()
+            )
+        except (binascii.Error, ValueError, InvalidTag) as e:
+            raise SecretsError(
+                f"Could not decrypt secret '{name}': wrong key or corrupted entry"
+            ) from e
+        return value.decode("utf-8")
+
+    def set(self, name: str, value: str) -> None:
+        if not SECRET_NAME.match(name):
+            raise SecretsError(
+                f"Invalid secret name '{name}': use letters, digits, '_', '-', '.'"
+            )
+        nonce = os.urandom(_NONCE_SIZE)
+        ciphertext = AESGCM(_load_key(create=True)).encrypt(
+            nonce, value.encode("utf-8"), name.encode()
+        )
+        entries = self._read()
+        entries[name] = base64.b64encode(nonce + ciphertext).decode()
+        self._write(entries)
+
+    def delete(self, name: str) -> None:
+        entries = self._read()
+        if entries.pop(name, None) is None:
+            raise SecretsError(f"Unknown secret '{name}'")
+        self._write(entries)
+
+
+def keychain_name(reference: str) -> str | None:
+    """The entry a `keychain:NAME` reference names, or None for other values."""
+    if not reference.startswith(KEYCHAIN_PREFIX):
+        return None
+    return reference.removeprefix(KEYCHAIN_PREFIX)
+
+
+def read_keychain(name: str) -> str:
+    """The secret stored as ``name`` in the OS credential store."""
+    try:
+        value = keyring.get_password(KEYCHAIN_SERVICE, name)
+    except keyring.errors.KeyringError as e:
+        raise SecretsError(f"The system keyring is unavailable ({e})") from e
+    if value is None:
+        raise SecretsError(
+            f"No '{name}' in the system keyring; add it with "
+            f"`rune secrets set {name} --keychain`"
+        )
+    return remember_secret(value)
+
+
+def write_keychain(name: str, value: str) -> None:
+    if not SECRET_NAME.match(name):
+        raise SecretsError(
+            f"Invalid secret name '{name}': use letters, digits, '_', '-', '.'"
+        )
+    try:
+        keyring.set_password(KEYCHAIN_SERVICE, name, value)
+    except keyring.errors.KeyringError as e:
+        raise SecretsError(f"Could not save '{name}' to the keyring: {e}") from e
+
+
+def delete_keychain(name: str) -> None:
+    try:
+        keyring.delete_password(KEYCHAIN_SERVICE, name)
+    except keyring.errors.PasswordDeleteError as e:
+        raise SecretsError(f"No '{name}' in the system keyring") from e
+    except keyring.errors.KeyringError as e:
+        raise SecretsError(f"The system keyring is unavailable ({e})") from e
+
+
+def resolve_secret_refs(value: str, store: SecretsStore | None = None) -> str:
+    """``value`` with its `${secret:NAME}` references replaced by the secrets."""
+    if not SECRET_REF.search(value):
+        return value
+    store = store or SecretsStore()
+
+    def replace(match: re.Match[str]) -> str:
+        return remember_secret(store.get(match.group(1)))
+
+    return SECRET_REF.sub(replace, value)
+
+
+def resolve_secret_env(
+    env: Mapping[str, str], store: SecretsStore | None = None
+) -> dict[str, str]:
+    """Environment variables or headers with their secret references resolved,
+    right before they are handed to a process or a server.
+    """
+    return {name: resolve_secret_refs(value, store) for name, value in env.items()}
+
+
+def remember_secret(value: str) -> str:
+    """``value``, to be scrubbed from whatever Rune persists from now on."""
+    if len(value) >= _MIN_SCRUB_LENGTH:
+        _resolved_values.add(value)
+    return value
+
+
+def scrub_secrets(text: str) -> str:
+    """``text`` without any secret value resolved by this process."""
+    for value in sorted(_resolved_values, key=len, reverse=True):
+        text = text.replace(value, REDACTED)
+    return text
+
+
+def scrub_secrets_in(data: Any) -> Any:
+    """``data``, a JSON-like structure, with secrets scrubbed from its strings."""
+    if not _resolved_values:
+        return data
+    match data:
+        case str():
+            return scrub_secrets(data)
+        case dict():
+            return {key: scrub_secrets_in(value) for key, value in data.items()}
+        case list():
+            return [scrub_secrets_in(item) for item in data]
+        case _:
+            return data
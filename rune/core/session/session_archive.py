@@ -0,0 +1,54 @@
+from __future__ import annotations
+
+from pathlib import Path
+import shutil
+from typing import TYPE_CHECKING
+
+from rune.core.session.session_index import SessionIndex, SessionIndexError
+from rune.core.utils import logger
+
+if TYPE_CHECKING:
+    from rune.core.config import SessionLoggingConfig
+
+ARCHIVE_DIRNAME = "archived"
+
+
+class SessionArchiveError(Exception):
+    pass
+
+
+def archive_dir(config: SessionLoggingConfig) -> Path:
+    """Where archived sessions are kept, out of the resume picker and search."""
+    return Path(config.save_dir) / ARCHIVE_DIRNAME
+
+
+def archived_config(config: SessionLoggingConfig) -> SessionLoggingConfig:
+    """``config`` pointed at the archive, to find and list archived sessions
+    with the session loader.
+    """
+    return config.model_copy(update={"save_dir": str(archive_dir(config))})
+
+
+def archive_session(session_dir: Path, config: SessionLoggingConfig) -> Path:
+    target = archive_dir(config) / session_dir.name
+    if target.exists():
+        raise SessionArchiveError(f"{target} is already archived")
+    target.parent.mkdir(parents=True, exist_ok=True)
+    shutil.move(session_dir, target)
+    return target
+
+
+def unarchive_session(archived_dir: Path, config: SessionLoggingConfig) -> Path:
+    target = Path(config.save_dir) / archived_dir.name
+    if target.exists():
+        raise SessionArchiveError(f"{target} already exists")
+    shutil.move(archived_dir, target)
+    return target
+
+
+def refresh_index(config: SessionLoggingConfig) -> None:
+    """Drop archived sessions from search, and add restored ones back."""
+    try:
+        SessionIndex(config).refresh()
+    except SessionIndexError as e:
+        logger.warning("Could not update the session index: %s", e)
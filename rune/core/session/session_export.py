@@ -0,0 +1,224 @@
+from __future__ import annotations
+
+import difflib
+from enum import StrEnum
+import html
+import json
+from pathlib import Path
+import re
+from typing import Any, NamedTuple
+
+from rune.core.session.session_loader import SessionLoader
+from rune.core.session.session_trace import redact_secrets
+from rune.core.tools.builtins.search_replace import SEARCH_REPLACE_BLOCK_RE
+from rune.core.types import LLMMessage, Role, ToolCall
+
+# Tool outputs are cut to this many lines; the transcript is for reading.
+MAX_OUTPUT_LINES = 40
+
+
+class ExportFormat(StrEnum):
+    MARKDOWN = "md"
+    HTML = "html"
+    JSON = "json"
+
+
+class TranscriptBlock(NamedTuple):
+    # "user", "assistant", "reasoning", "command", "diff", "tool" or "output".
+    kind: str
+    title: str
+    text: str
+    lang: str = ""
+
+
+def export_session(
+    session_dir: Path, export_format: ExportFormat, *, redact: bool = True
+) -> str:
+    """A readable transcript of the session logged in ``session_dir``."""
+    messages, metadata = SessionLoader.load_session(session_dir)
+    if export_format == ExportFormat.JSON:
+        dump = {
+            "redacted": redact,
+            "metadata": {
+                key: metadata.get(key)
+                for key in (
+                    "session_id",
+                    "name",
+                    "title",
+                    "start_time",
+                    "end_time",
+                    "environment",
+                )
+            },
+            "messages": [
+                m.model_dump(mode="json", exclude_none=True) for m in messages
+            ],
+        }
+        text = json.dumps(dump, indent=2, ensure_ascii=False) + "\n"
+        return redact_secrets(text) if redact else text
+
+    blocks = transcript_blocks(messages)
+    if redact:
+        blocks = [block._replace(text=redact_secrets(block.text)) for block in blocks]
+    if export_format == ExportFormat.HTML:
+        return render_html(blocks, metadata)
+    return render_markdown(blocks, metadata)
+
+
+def transcript_blocks(messages: list[LLMMessage]) -> list[TranscriptBlock]:
+    blocks: list[TranscriptBlock] = []
+    for message in messages:
+        match message.role:
+            case Role.user:
+                blocks.append(TranscriptBlock("user", "You", message.content or ""))
+            case Role.assistant:
+                if message.reasoning_content:
+                    blocks.append(
+                        TranscriptBlock(
+                            "reasoning", "Reasoning", message.reasoning_content
+                        )
+                    )
+                if message.content:
+                    blocks.append(TranscriptBlock("assistant", "Rune", message.content))
+                blocks.extend(map(_tool_call_block, message.tool_calls or []))
+            case Role.tool:
+                blocks.append(
+                    TranscriptBlock(
+                        "output",
+                        f"Output of {message.name or 'tool'}",
+                        _truncate(message.content or ""),
+                    )
+                )
+    return blocks
+
+
+def _tool_call_block(call: ToolCall) -> TranscriptBlock:
+    name = call.function.name or "tool"
+    raw = call.function.arguments or ""
+    try:
+        arguments: dict[str, Any] = json.loads(raw or "{}")
+    except json.JSONDecodeError:
+        arguments = {}
+    match name:
+        case "bash" if "command" in arguments:
+            return TranscriptBlock("command", "Command", arguments["command"], "bash")
+        case "search_replace" if "content" in arguments:
+            return TranscriptBlock(
+                "diff",
+                f"Edit {arguments.get('file_path', '')}",
+                _search_replace_diff(arguments["content"]),
+                "diff",
+            )
+        case "write_file" if "content" in arguments:
+            return TranscriptBlock(
+                "diff",
+                f"Write {arguments.get('path', '')}",
+                "\n".join(f"+{line}" for line in arguments["content"].splitlines()),
+                "diff",
+            )
+    text = json.dumps(arguments, indent=2) if arguments else raw
+    return TranscriptBlock("tool", f"Call {name}", text, "json")
+
+
+def _search_replace_diff(content: str) -> str:
+    hunks = []
+    for search, replace in SEARCH_REPLACE_BLOCK_RE.findall(content):
+        diff = difflib.unified_diff(
+            search.splitlines(), replace.splitlines(), lineterm="", n=2
+        )
+        hunks.append("\n".join(list(diff)[2:]))
+    return "\n\n".join(hunks) or content
+
+
+def _truncate(text: str) -> str:
+    lines = text.splitlines()
+    if len(lines) <= MAX_OUTPUT_LINES:
+        return text
+    rest = len(lines) - MAX_OUTPUT_LINES
+    return "\n".join([*lines[:MAX_OUTPUT_LINES], f"… ({rest} more lines)"])
+
+
+def _heading(metadata: dict[str, Any]) -> tuple[str, str]:
+    title = str(metadata.get("title") or "Untitled session")
+    details = [f"Session {str(metadata.get('session_id', ''))[:8]}"]
+    if start := metadata.get("start_time"):
+        details.append(str(start)[:16].replace("T", " "))
+    return title, " · ".join(details)
+
+
+def _fence(text: str, lang: str = "") -> str:
+    # A fence longer than any backtick run inside the text.
+    longest = max((len(run) for run in re.findall("`+", text)), default=0)
+    ticks = "`" * max(3, longest + 1)
+    return f"{ticks}{lang}\n{text}\n{ticks}"
+
+
+def render_markdown(blocks: list[TranscriptBlock], metadata: dict[str, Any]) -> str:
+    title, details = _heading(metadata)
+    parts = [f"# {title}", f"_{details}_"]
+    for block in blocks:
+        match block.kind:
+            case "user" | "assistant":
+                parts += [f"## {block.title}", block.text]
+            case "reasoning" | "output":
+                parts.append(
+                    f"<details>\n<summary>{html.escape(block.title)}</summary>\n\n"
+                    f"{_fence(block.text)}\n\n</details>"
+                )
+            case _:
+                parts += [f"**{block.title}**", _fence(block.text, block.lang)]
+    return "\n\n".join(parts) + "\n"
+
+
+_HTML_STYLE = """
+body { font-family: system-ui, sans-serif; max-width: 50rem; margin: 2rem auto;
+       padding: 0 1rem; line-height: 1.5; }
+.meta { color: #666; }
+.user { background: #f3f6fb; border-radius: 6px; padding: 0.5rem 1rem; }
+.message { white-space: pre-wrap; }
+pre { background: #f6f8fa; padding: 0.75rem; overflow-x: auto; }
+.add { color: #1a7f37; } .del { color: #cf222e; }
+"""
+
+
+def render_html(blocks: list[TranscriptBlock], metadata: dict[str, Any]) -> str:
+    title, details = _heading(metadata)
+    body = [
+        f"<h1>{html.escape(title)}</h1>",
+        f'<p class="meta">{html.escape(details)}</p>',
+    ]
+    for block in blocks:
+        heading = html.escape(block.title)
+        match block.kind:
+            case "user" | "assistant":
+                body.append(
+                    f'<section class="{block.kind}"><h2>{heading}</h2>'
+                    f'<div class="message">{html.escape(block.text)}</div></section>'
+                )
+            case "reasoning" | "output":
+                body.append(
+                    f"<details><summary>{heading}</summary>"
+                    f"<pre>{html.escape(block.text)}</pre></details>"
+                )
+            case "diff":
+                body.append(f"<h3>{heading}</h3><pre>{_html_diff(block.text)}</pre>")
+            case _:
+                body.append(f"<h3>{heading}</h3><pre>{html.escape(block.text)}</pre>")
+    return (
+        "<!DOCTYPE html>\n"
+        '<html><head><meta charset="utf-8">'
+        f"<title>{html.escape(title)}</title><style>{_HTML_STYLE}</style></head>\n"
+        "<body>\n" + "\n".join(body) + "\n</body></html>\n"
+    )
+
+
+def _html_diff(diff: str) -> str:
+    lines = []
+    for line in diff.splitlines():
+        escaped = html.escape(line)
+        if line.startswith("+"):
+            escaped = f'<span class="add">{escaped}</span>'
+        elif line.startswith("-"):
+            escaped = f'<span class="del">{escaped}</span>'
+        lines.append(escaped)
+    return "\n".join(lines)
@@ -0,0 +1,59 @@
+from __future__ import annotations
+
+from pathlib import Path
+from typing import TYPE_CHECKING
+
+from rune.core.session.session_import import write_session
+from rune.core.session.session_loader import SessionLoader
+from rune.core.types import LLMMessage, Role
+
+if TYPE_CHECKING:
+    from rune.core.config import SessionLoggingConfig
+
+
+class SessionForkError(Exception):
+    pass
+
+
+def history_until_turn(
+    messages: list[LLMMessage], turn: int | None = None
+) -> list[LLMMessage]:
+    """``messages`` up to the end of user turn ``turn`` (counted from 1), or
+    all of them without one.
+    """
+    messages = [m for m in messages if m.role != Role.system]
+    user_indices = [i for i, m in enumerate(messages) if m.role == Role.user]
+    if turn is None:
+        return messages
+    if not 1 <= turn <= len(user_indices):
+        raise SessionForkError(
+            f"Turn {turn} does not exist: the session has {len(user_indices)} turn(s)"
+        )
+    end = user_indices[turn] if turn < len(user_indices) else len(messages)
+    return messages[:end]
+
+
+def fork_title(title: str | None) -> str:
+    return f"{title or 'Untitled session'} (fork)"
+
+
+def fork_session(
+    session_dir: Path, config: SessionLoggingConfig, at_turn: int | None = None
+) -> tuple[str, Path]:
+    """Log the session in ``session_dir``, up to the end of ``at_turn``, as a
+    new session to take in another direction. Returns its id and directory.
+    """
+    messages, metadata = SessionLoader.load_session(session_dir)
+    return write_session(
+        config,
+        history_until_turn(messages, at_turn),
+        {
+            "username": metadata.get("username") or "unknown",
+            "environment": metadata.get("environment") or {},
+            "auto_title": fork_title(metadata.get("title")),
+            "title": fork_title(metadata.get("title")),
+            "tags": metadata.get("tags") or [],
+            "forked_from": metadata.get("session_id"),
+            "forked_at_turn": at_turn,
+        },
+    )
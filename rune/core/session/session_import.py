@@ -0,0 +1,196 @@
+from __future__ import annotations
+
+import asyncio
+import json
+from pathlib import Path
+import re
+from typing import TYPE_CHECKING, Any
+from uuid import uuid4
+
+from pydantic import ValidationError
+import zstandard
+
+from rune.core.session.session_index import SessionIndex, SessionIndexError
+from rune.core.session.session_logger import (
+    METADATA_FILENAME,
+    SessionLogger,
+    messages_file,
+    read_message_lines,
+)
+from rune.core.types import FunctionCall, LLMMessage, Role
+from rune.core.utils import logger, utc_now
+
+if TYPE_CHECKING:
+    from rune.core.config import SessionLoggingConfig
+
+
+class SessionImportError(Exception):
+    pass
+
+
+def read_session_file(source: Path) -> tuple[list[dict[str, Any]], dict[str, Any]]:
+    """The messages and metadata of ``source``: a session directory, its
+    messages file, or a `rune export --format json` dump.
+    """
+    try:
+        if source.is_dir():
+            metadata_path = source / METADATA_FILENAME
+            metadata = (
+                json.loads(metadata_path.read_text("utf-8"))
+                if metadata_path.is_file()
+                else {}
+            )
+            lines = read_message_lines(messages_file(source))
+            return [json.loads(line) for line in lines if line.strip()], metadata
+        if source.suffix == ".json":
+            dump = json.loads(source.read_text("utf-8"))
+            if not isinstance(dump, dict) or not isinstance(dump.get("messages"), list):
+                raise SessionImportError(
+                    f"{source} is not a session exported with --format json"
+                )
+            if dump.get("redacted"):
+                # Masked tool outputs and arguments would mislead the model.
+                raise SessionImportError(
+                    f"{source} was exported with its secrets redacted; export it "
+                    "again with --no-redact to import it"
+                )
+            return dump["messages"], dump.get("metadata") or {}
+        lines = read_message_lines(source)
+        return [json.loads(line) for line in lines if line.strip()], {}
+    except (OSError, ValueError, zstandard.ZstdError) as e:
+        raise SessionImportError(f"Could not read {source}: {e}") from e
+
+
+def import_session(
+    source: Path, config: SessionLoggingConfig, workdir: Path | None = None
+) -> tuple[str, Path]:
+    """Copy the session in ``source`` into the local session logs under a new
+    id, so it can be resumed here. Returns the new id and session directory.
+
+    Paths under the session's original working directory are moved to
+    ``workdir`` (the current directory by default).
+    """
+    raw_messages, metadata = read_session_file(source)
+    messages = _validate(raw_messages)
+
+    workdir = (workdir or Path.cwd()).resolve()
+    old_workdir = (metadata.get("environment") or {}).get("working_directory")
+    if old_workdir and old_workdir != str(workdir):
+        messages = [_move_paths(m, old_workdir, str(workdir)) for m in messages]
+
+    return write_session(
+        config,
+        messages,
+        {
+            "start_time": metadata.get("start_time"),
+            "username": metadata.get("username") or "unknown",
+            "environment": {"working_directory": str(workdir)},
+            "name": metadata.get("name"),
+            "auto_title": metadata.get("auto_title"),
+            "title": metadata.get("title") or _first_prompt(messages),
+            "imported_from": str(metadata.get("session_id") or source.name),
+        },
+    )
+
+
+def write_session(
+    config: SessionLoggingConfig, messages: list[LLMMessage], metadata: dict[str, Any]
+) -> tuple[str, Path]:
+    """Log ``messages`` as a new session, with ``metadata`` on top of the
+    fields every session has. Returns its id and directory.
+    """
+    session_id = str(uuid4())
+    timestamp = utc_now().strftime("%Y%m%d_%H%M%S")
+    session_dir = (
+        Path(config.save_dir) / f"{config.session_prefix}_{timestamp}_{session_id[:8]}"
+    )
+    session_dir.mkdir(parents=True)
+    now = utc_now().isoformat()
+    new_metadata = {
+        "git_commit": None,
+        "git_branch": None,
+        "username": "unknown",
+        **metadata,
+        "session_id": session_id,
+        "start_time": metadata.get("start_time") or now,
+        "end_time": now,
+        "total_messages": len(messages),
+    }
+    asyncio.run(_persist(session_dir, messages, new_metadata, config.compress))
+
+    try:
+        SessionIndex(config).refresh()
+    except SessionIndexError as e:
+        logger.warning("Could not index the new session: %s", e)
+    return session_id, session_dir
+
+
+def _validate(raw_messages: list[Any]) -> list[LLMMessage]:
+    try:
+        messages = [
+            LLMMessage.model_validate(m)
+            for m in raw_messages
+            if not (isinstance(m, dict) and m.get("role") == Role.system)
+        ]
+    except ValidationError as e:
+        raise SessionImportError(f"The session has an invalid message: {e}") from e
+    if not any(m.role == Role.user for m in messages):
+        raise SessionImportError("The session has no user messages")
+    call_ids = {c.id for m in messages for c in m.tool_calls or []}
+    for message in messages:
+        if message.role == Role.tool and message.tool_call_id not in call_ids:
+            raise SessionImportError(
+                f"Tool result {message.tool_call_id} answers no tool call"
+            )
+    return messages
+
+
+def _move_paths(message: LLMMessage, old: str, new: str) -> LLMMessage:
+    update: dict[str, Any] = {}
+    if message.content:
+        update["content"] = _replace_path(message.content, old, new)
+    if message.tool_calls:
+        # Arguments are JSON, where paths may have escaped characters.
+        old_json, new_json = json.dumps(old)[1:-1], json.dumps(new)[1:-1]
+        update["tool_calls"] = [
+            call.model_copy(
+                update={
+                    "function": FunctionCall(
+                        name=call.function.name,
+                        arguments=_replace_path(
+                            call.function.arguments or "", old_json, new_json
+                        ),
+                    )
+                }
+            )
+            for call in message.tool_calls
+        ]
+    return message.model_copy(update=update)
+
+
+def _replace_path(text: str, old: str, new: str) -> str:
+    """``text`` with ``old`` replaced where it is a whole path, or the leading
+    components of one: `/src/app` moves, `/src/apps` and `/x/src/app` don't.
+    """
+    pattern = re.compile(rf"(?<![\w.-]){re.escape(old)}(?![\w-]|\.\w)")
+    return pattern.sub(lambda _: new, text)
+
+
+def _first_prompt(messages: list[LLMMessage]) -> str:
+    prompt = next((m.content for m in messages if m.role == Role.user), None)
+    return (prompt or "Imported session").splitlines()[0][:50]
+
+
+async def _persist(
+    session_dir: Path,
+    messages: list[LLMMessage],
+    metadata: dict[str, Any],
+    compress: bool,
+) -> None:
+    await SessionLogger.persist_messages(
+        [m.model_dump(exclude_none=True) for m in messages],
+        session_dir,
+        compress=compress,
+    )
+    await SessionLogger.persist_metadata(metadata, session_dir)
+
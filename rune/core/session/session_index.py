@@ -0,0 +1,272 @@
+from __future__ import annotations
+
+from collections.abc import Iterator
+from contextlib import closing, contextmanager
+from datetime import UTC, datetime, timedelta
+import json
+from pathlib import Path
+import sqlite3
+from typing import TYPE_CHECKING, Any
+
+from pydantic import BaseModel
+import zstandard
+
+from rune.core.session.session_logger import (
+    METADATA_FILENAME,
+    messages_file,
+    read_message_lines,
+)
+
+if TYPE_CHECKING:
+    from rune.core.config import SessionLoggingConfig
+
+INDEX_FILENAME = "search_index.sqlite3"
+# Tools whose command line is worth finding a session by.
+COMMAND_TOOLS = {"bash": "command"}
+
+_SCHEMA = """
+CREATE TABLE IF NOT EXISTS indexed_sessions (
+    dir TEXT PRIMARY KEY,
+    mtime REAL NOT NULL,
+    session_id TEXT NOT NULL,
+    title TEXT NOT NULL
+);
+CREATE VIRTUAL TABLE IF NOT EXISTS session_text USING fts5(
+    dir UNINDEXED, kind UNINDEXED, content, tokenize = 'unicode61'
+);
+CREATE TABLE IF NOT EXISTS index_state (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL
+);
+"""
+# Background maintenance runs at most this often.
+MAINTENANCE_INTERVAL = timedelta(days=7)
+
+
+class SessionIndexError(Exception):
+    pass
+
+
+class MaintenanceReport(BaseModel):
+    pruned_sessions: int
+    pruned_rows: int
+    indexed_sessions: int
+    size_before: int
+    size_after: int
+
+
+class SessionSearchHit(BaseModel):
+    session_dir: Path
+    session_id: str
+    title: str
+    # "user", "assistant" or "command".
+    kind: str
+    snippet: str
+
+
+class SessionIndex:
+    """A full-text index over the logged sessions: your prompts, the replies
+    and the commands that were run.
+
+    It lives next to the sessions and catches up with their logs, by
+    modification time, before each search.
+    """
+
+    def __init__(self, config: SessionLoggingConfig) -> None:
+        self.config = config
+        self.save_dir = Path(config.save_dir)
+        self.path = self.save_dir / INDEX_FILENAME
+
+    @contextmanager
+    def _connect(self) -> Iterator[sqlite3.Connection]:
+        self.save_dir.mkdir(parents=True, exist_ok=True)
+        try:
+            with closing(sqlite3.connect(self.path)) as db, db:
+                db.executescript(_SCHEMA)
+                yield db
+        except sqlite3.Error as e:
+            raise SessionIndexError(f"Session index {self.path} failed: {e}") from e
+
+    def refresh(self) -> int:
+        """Index the sessions logged or extended since the last refresh, and
+        forget deleted ones. Returns how many sessions were (re)indexed.
+        """
+        sessions: dict[str, float] = {}
+        for session_dir in self.save_dir.glob(f"{self.config.session_prefix}_*"):
+            try:
+                sessions[str(session_dir)] = messages_file(session_dir).stat().st_mtime
+            except OSError:
+                continue
+
+        with self._connect() as db:
+            indexed = dict(db.execute("SELECT dir, mtime FROM indexed_sessions"))
+            for gone in indexed.keys() - sessions.keys():
+                _forget(db, gone)
+            stale = [d for d, mtime in sessions.items() if indexed.get(d) != mtime]
+            for session_dir in stale:
+                _forget(db, session_dir)
+                _index(db, Path(session_dir), sessions[session_dir])
+        return len(stale)
+
+    def indexed_sessions(self) -> dict[Path, str]:
+        """The indexed session directories, with the session id of each."""
+        with self._connect() as db:
+            rows = db.execute("SELECT dir, session_id FROM indexed_sessions")
+            return {Path(session_dir): session_id for session_dir, session_id in rows}
+
+    def rebuild(self) -> int:
+        """Index every session again from its logs."""
+        with self._connect() as db:
+            db.execute("DELETE FROM session_text")
+            db.execute("DELETE FROM indexed_sessions")
+        return self.refresh()
+
+    def maintain(self) -> MaintenanceReport:
+        """Drop the entries of sessions whose logs are gone, then optimize,
+        analyze and vacuum the database to give the space back.
+        """
+        size_before = self._size()
+        with self._connect() as db:
+            orphans = [
+                session_dir
+                for (session_dir,) in db.execute("SELECT dir FROM indexed_sessions")
+                if not messages_file(Path(session_dir)).is_file()
+            ]
+            for session_dir in orphans:
+                _forget(db, session_dir)
+            # Text left behind by a session whose row was already dropped.
+            pruned_rows = db.execute(
+                "DELETE FROM session_text "
+                "WHERE dir NOT IN (SELECT dir FROM indexed_sessions)"
+            ).rowcount
+            db.execute("INSERT INTO session_text (session_text) VALUES ('optimize')")
+            db.execute("ANALYZE")
+            db.execute(
+                "INSERT OR REPLACE INTO index_state (key, value) "
+                "VALUES ('last_maintenance', ?)",
+                (datetime.now(UTC).isoformat(),),
+            )
+            (indexed,) = db.execute("SELECT COUNT(*) FROM indexed_sessions").fetchone()
+        try:
+            # VACUUM can't run inside a transaction.
+            with closing(sqlite3.connect(self.path, isolation_level=None)) as db:
+                db.execute("VACUUM")
+        except sqlite3.Error as e:
+            raise SessionIndexError(f"Session index {self.path} failed: {e}") from e
+        return MaintenanceReport(
+            pruned_sessions=len(orphans),
+            pruned_rows=max(pruned_rows, 0),
+            indexed_sessions=indexed,
+            size_before=size_before,
+            size_after=self._size(),
+        )
+
+    def maintenance_due(self, now: datetime | None = None) -> bool:
+        if not self.path.exists():
+            return False
+        with self._connect() as db:
+            row = db.execute(
+                "SELECT value FROM index_state WHERE key = 'last_maintenance'"
+            ).fetchone()
+        if row is None:
+            return True
+        now = now or datetime.now(UTC)
+        return now - datetime.fromisoformat(row[0]) >= MAINTENANCE_INTERVAL
+
+    def _size(self) -> int:
+        try:
+            return self.path.stat().st_size
+        except OSError:
+            return 0
+
+    def search(self, query: str, limit: int = 20) -> list[SessionSearchHit]:
+        """The sessions best matching ``query``, each with its best match."""
+        if not (terms := _fts_query(query)):
+            return []
+        self.refresh()
+        with self._connect() as db:
+            rows = db.execute(
+                "SELECT t.dir, s.session_id, s.title, t.kind, "
+                "snippet(session_text, 2, '[', ']', '…', 12) "
+                "FROM session_text t JOIN indexed_sessions s ON s.dir = t.dir "
+                "WHERE session_text MATCH ? ORDER BY rank",
+                (terms,),
+            ).fetchall()
+
+        hits: dict[str, SessionSearchHit] = {}
+        for session_dir, session_id, title, kind, snippet in rows:
+            if session_dir in hits:
+                continue
+            hits[session_dir] = SessionSearchHit(
+                session_dir=Path(session_dir),
+                session_id=session_id,
+                title=title,
+                kind=kind,
+                snippet=" ".join(snippet.split()),
+            )
+            if len(hits) >= limit:
+                break
+        return list(hits.values())
+
+
+def _fts_query(query: str) -> str:
+    # Quoted terms keep FTS operators and punctuation in the query literal.
+    return " ".join(
+        '"' + term.replace('"', '""') + '"'
+        for term in query.split()
+        if any(char.isalnum() for char in term)
+    )
+
+
+def _forget(db: sqlite3.Connection, session_dir: str) -> None:
+    db.execute("DELETE FROM session_text WHERE dir = ?", (session_dir,))
+    db.execute("DELETE FROM indexed_sessions WHERE dir = ?", (session_dir,))
+
+
+def _index(db: sqlite3.Connection, session_dir: Path, mtime: float) -> None:
+    try:
+        lines = read_message_lines(messages_file(session_dir))
+        metadata = json.loads(
+            (session_dir / METADATA_FILENAME).read_text("utf-8", errors="ignore")
+        )
+    except (OSError, ValueError, zstandard.ZstdError):
+        # Logs being written or corrupted; retried when they change.
+        return
+
+    rows = []
+    for line in lines:
+        try:
+            message = json.loads(line)
+        except json.JSONDecodeError:
+            continue
+        rows.extend(_searchable_text(message))
+    db.executemany(
+        "INSERT INTO session_text (dir, kind, content) VALUES (?, ?, ?)",
+        [(str(session_dir), kind, text) for kind, text in rows],
+    )
+    db.execute(
+        "INSERT INTO indexed_sessions (dir, mtime, session_id, title) "
+        "VALUES (?, ?, ?, ?)",
+        (
+            str(session_dir),
+            mtime,
+            str(metadata.get("session_id", "")),
+            str(metadata.get("title") or "Untitled session"),
+        ),
+    )
+
+
+def _searchable_text(message: dict[str, Any]) -> Iterator[tuple[str, str]]:
+    role = message.get("role")
+    if role in {"user", "assistant"} and (content := message.get("content")):
+        yield role, str(content)
+    for call in message.get("tool_calls") or []:
+        function = call.get("function") or {}
+        if (argument := COMMAND_TOOLS.get(function.get("name", ""))) is None:
+            continue
+        try:
+            arguments = json.loads(function.get("arguments") or "{}")
+        except json.JSONDecodeError:
+            continue
+        if isinstance(arguments, dict) and (command := arguments.get(argument)):
+            yield "command", str(command)
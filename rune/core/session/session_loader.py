@@ -4,19 +4,75 @@ import json
 from pathlib import Path
 from typing import TYPE_CHECKING, Any
 
-from rune.core.session.session_logger import MESSAGES_FILENAME, METADATA_FILENAME
+from pydantic import BaseModel
+import zstandard
+
+from rune.core.session.session_logger import (
+    METADATA_FILENAME,
+    messages_file,
+    normalize_tags,
+    read_message_lines,
+)
 from rune.core.types import LLMMessage
 
 if TYPE_CHECKING:
     from rune.core.config import SessionLoggingConfig
 
 
+class SessionFilter(BaseModel):
+    """Which sessions to list: all ``tags``, started in ``cwd`` or below it,
+    with ``model`` active.
+    """
+
+    tags: list[str] = []
+    cwd: Path | None = None
+    model: str | None = None
+
+    @property
+    def is_empty(self) -> bool:
+        return not (self.tags or self.cwd or self.model)
+
+    @classmethod
+    def from_query(cls, query: str) -> tuple[SessionFilter, str]:
+        """Split the `tag:`, `cwd:` and `model:` terms off a picker query."""
+        tags: list[str] = []
+        fields: dict[str, Any] = {}
+        rest: list[str] = []
+        for term in query.split():
+            prefix, _, value = term.partition(":")
+            match prefix.casefold():
+                case "tag" if value:
+                    tags.append(value)
+                case "cwd" if value:
+                    fields["cwd"] = Path(value).expanduser()
+                case "model" if value:
+                    fields["model"] = value
+                case _:
+                    rest.append(term)
+        return cls(tags=normalize_tags(tags), **fields), " ".join(rest)
+
+    def matches(self, metadata: dict[str, Any]) -> bool:
+        session_tags = metadata.get("tags") or []
+        if any(tag not in session_tags for tag in normalize_tags(self.tags)):
+            return False
+        if self.cwd is not None:
+            workdir = (metadata.get("environment") or {}).get("working_directory")
+            cwd = self.cwd.resolve()
+            if not workdir or not Path(workdir).is_relative_to(cwd):
+                return False
+        if self.model is not None:
+            model = (metadata.get("config") or {}).get("active_model")
+            if not isinstance(model, str) or model.casefold() != self.model.casefold():
+                return False
+        return True
+
+
 class SessionLoader:
     @staticmethod
     def _is_valid_session(session_dir: Path) -> bool:
         """Check if a session directory contains valid metadata and messages."""
         metadata_path = session_dir / METADATA_FILENAME
-        messages_path = session_dir / MESSAGES_FILENAME
+        messages_path = messages_file(session_dir)
 
         if not metadata_path.is_file() or not messages_path.is_file():
             return False
@@ -27,16 +83,14 @@ class SessionLoader:
             if not isinstance(metadata, dict):
                 return False
 
-            with messages_path.open("r", encoding="utf-8", errors="ignore") as f:
-                has_messages = False
-                for line in f:
-                    has_messages = True
-                    message = json.loads(line)
-                    if not isinstance(message, dict):
-                        return False
-            if not has_messages:
+            lines = read_message_lines(messages_path)
+            if not lines:
                 return False
-        except (OSError, UnicodeDecodeError, json.JSONDecodeError):
+            for line in lines:
+                message = json.loads(line)
+                if not isinstance(message, dict):
+                    return False
+        except (OSError, UnicodeDecodeError, json.JSONDecodeError, zstandard.ZstdError):
             return False
 
         return True
@@ -45,7 +99,7 @@ class SessionLoader:
     def latest_session(session_dirs: list[Path]) -> Path | None:
         sessions_with_mtime: list[tuple[Path, float]] = []
         for session in session_dirs:
-            messages_path = session / MESSAGES_FILENAME
+            messages_path = messages_file(session)
             if not messages_path.is_file():
                 continue
             try:
@@ -76,6 +130,48 @@ class SessionLoader:
 
         return SessionLoader.latest_session(session_dirs)
 
+    @staticmethod
+    def list_recent_sessions(
+        config: SessionLoggingConfig,
+        limit: int = 10,
+        session_filter: SessionFilter | None = None,
+    ) -> list[tuple[Path, dict[str, Any]]]:
+        """Return up to ``limit`` valid sessions with their metadata, newest first,
+        keeping those ``session_filter`` matches.
+        """
+        save_dir = Path(config.save_dir)
+        if not save_dir.exists():
+            return []
+
+        sessions_with_mtime: list[tuple[Path, float]] = []
+        for session in save_dir.glob(f"{config.session_prefix}_*"):
+            try:
+                mtime = messages_file(session).stat().st_mtime
+            except OSError:
+                continue
+            sessions_with_mtime.append((session, mtime))
+        sessions_with_mtime.sort(key=lambda x: x[1], reverse=True)
+
+        recent: list[tuple[Path, dict[str, Any]]] = []
+        for session, _mtime in sessions_with_mtime:
+            if len(recent) >= limit:
+                break
+            # Reading the metadata is cheap; validating every message isn't.
+            try:
+                with (session / METADATA_FILENAME).open(
+                    "r", encoding="utf-8", errors="ignore"
+                ) as f:
+                    metadata = json.load(f)
+            except (OSError, json.JSONDecodeError):
+                continue
+            if not isinstance(metadata, dict):
+                continue
+            if session_filter is not None and not session_filter.matches(metadata):
+                continue
+            if SessionLoader._is_valid_session(session):
+                recent.append((session, metadata))
+        return recent
+
     @staticmethod
     def find_session_by_id(
         session_id: str, config: SessionLoggingConfig
@@ -84,6 +180,29 @@ class SessionLoader:
 
         return SessionLoader.latest_session(matches)
 
+    @staticmethod
+    def find_session_by_name(name: str, config: SessionLoggingConfig) -> Path | None:
+        """Find the most recent session named ``name``, ignoring case."""
+        save_dir = Path(config.save_dir)
+        if not save_dir.exists():
+            return None
+
+        wanted = name.strip().casefold()
+        matches: list[Path] = []
+        for session_dir in save_dir.glob(f"{config.session_prefix}_*"):
+            try:
+                with (session_dir / METADATA_FILENAME).open(
+                    "r", encoding="utf-8", errors="ignore"
+                ) as f:
+                    metadata = json.load(f)
+            except (OSError, json.JSONDecodeError):
+                continue
+            session_name = metadata.get("name") if isinstance(metadata, dict) else None
+            if isinstance(session_name, str) and session_name.casefold() == wanted:
+                matches.append(session_dir)
+
+        return SessionLoader.latest_session(matches)
+
     @staticmethod
     def does_session_exist(
         session_id: str, config: SessionLoggingConfig
@@ -91,7 +210,7 @@ class SessionLoader:
         for session_dir in SessionLoader._find_session_dirs_by_short_id(
             session_id, config
         ):
-            if (session_dir / MESSAGES_FILENAME).is_file():
+            if messages_file(session_dir).is_file():
                 return session_dir
         return None
 
@@ -108,12 +227,11 @@ class SessionLoader:
 
     @staticmethod
     def load_session(filepath: Path) -> tuple[list[LLMMessage], dict[str, Any]]:
-        # Load session messages from MESSAGES_FILENAME
-        messages_filepath = filepath / MESSAGES_FILENAME
+        # Load session messages from MESSAGES_FILENAME, or its compressed variant
+        messages_filepath = messages_file(filepath)
 
         try:
-            with messages_filepath.open("r", encoding="utf-8", errors="ignore") as f:
-                content = f.readlines()
+            content = read_message_lines(messages_filepath)
         except Exception as e:
             raise ValueError(
                 f"Error reading session messages at {filepath}: {e}"
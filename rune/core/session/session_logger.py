@@ -9,7 +9,9 @@ import subprocess
 from typing import TYPE_CHECKING, Any
 
 from anyio import NamedTemporaryFile, Path as AsyncPath
+import zstandard
 
+from rune.core.secrets import scrub_secrets_in
 from rune.core.types import AgentStats, LLMMessage, Role, SessionMetadata
 from rune.core.utils import is_windows, utc_now
 
@@ -21,12 +23,52 @@ if TYPE_CHECKING:
 
 METADATA_FILENAME = "meta.json"
 MESSAGES_FILENAME = "messages.jsonl"
+COMPRESSED_MESSAGES_FILENAME = "messages.jsonl.zst"
+
+
+def normalize_tags(tags: list[str]) -> list[str]:
+    """``tags`` lowercased, without a leading `#` and without duplicates."""
+    normalized = (tag.strip().lstrip("#").strip().casefold() for tag in tags)
+    return list(dict.fromkeys(tag for tag in normalized if tag))
+
+
+def messages_file(session_dir: Path, compress: bool = False) -> Path:
+    """The messages file of ``session_dir``. A session keeps the format it was
+    started in; ``compress`` only picks the one of a new session.
+    """
+    compressed = session_dir / COMPRESSED_MESSAGES_FILENAME
+    plain = session_dir / MESSAGES_FILENAME
+    if compressed.is_file() or (compress and not plain.is_file()):
+        return compressed
+    return plain
+
+
+def read_message_lines(path: Path) -> list[str]:
+    """The lines of a messages file, decompressing it if needed."""
+    if path.name != COMPRESSED_MESSAGES_FILENAME:
+        with path.open("r", encoding="utf-8", errors="ignore") as f:
+            return f.readlines()
+    # Every save appends a zstd frame of its own.
+    with (
+        path.open("rb") as f,
+        zstandard.ZstdDecompressor().stream_reader(
+            f, read_across_frames=True
+        ) as reader,
+    ):
+        return reader.readall().decode("utf-8", errors="ignore").splitlines()
 
 
 class SessionLogger:
-    def __init__(self, session_config: SessionLoggingConfig, session_id: str) -> None:
+    def __init__(
+        self,
+        session_config: SessionLoggingConfig,
+        session_id: str,
+        cwd: Path | None = None,
+    ) -> None:
         self.session_config = session_config
         self.enabled = session_config.enabled
+        # The directory the session works in, which is where it is listed.
+        self.cwd = cwd or Path.cwd()
 
         if not self.enabled:
             self.save_dir: Path | None = None
@@ -71,13 +113,14 @@ class SessionLogger:
             raise RuntimeError(
                 "Cannot get session messages filepath when logging is disabled"
             )
-        return self.session_dir / MESSAGES_FILENAME
+        return messages_file(self.session_dir, self.session_config.compress)
 
     @property
     def git_commit(self) -> str | None:
         try:
             result = subprocess.run(
                 ["git", "rev-parse", "HEAD"],
+                cwd=self.cwd,
                 capture_output=True,
                 stdin=subprocess.DEVNULL if is_windows() else None,
                 text=True,
@@ -94,6 +137,7 @@ class SessionLogger:
         try:
             result = subprocess.run(
                 ["git", "rev-parse", "--abbrev-ref", "HEAD"],
+                cwd=self.cwd,
                 capture_output=True,
                 stdin=subprocess.DEVNULL if is_windows() else None,
                 text=True,
@@ -124,10 +168,25 @@ class SessionLogger:
             git_commit=git_commit,
             git_branch=git_branch,
             username=user_name,
-            environment={"working_directory": str(Path.cwd())},
+            environment={"working_directory": str(self.cwd)},
         )
 
-    def _get_title(self, messages: list[LLMMessage]) -> str:
+    @property
+    def tags(self) -> list[str]:
+        metadata = self.session_metadata
+        return list(metadata.tags) if metadata is not None else []
+
+    @property
+    def has_title(self) -> bool:
+        metadata = self.session_metadata
+        return metadata is not None and bool(metadata.name or metadata.auto_title)
+
+    def get_title(self, messages: list[LLMMessage]) -> str:
+        if self.session_metadata is not None and (
+            title := self.session_metadata.name or self.session_metadata.auto_title
+        ):
+            return title
+
         first_user_message = None
         for message in messages:
             if message.role == Role.user:
@@ -176,17 +235,32 @@ class SessionLogger:
                 temp_metadata_filepath.unlink()
 
     @staticmethod
-    async def persist_messages(messages: list[dict], session_dir: Path) -> None:
-        messages_filepath = session_dir / "messages.jsonl"
+    async def persist_messages(
+        messages: list[dict], session_dir: Path, *, compress: bool = False
+    ) -> None:
+        messages_filepath = messages_file(session_dir, compress)
+        # Tool output can echo secrets resolved into its environment.
+        lines = [
+            json.dumps(scrub_secrets_in(message), ensure_ascii=False) + "\n"
+            for message in messages
+        ]
         try:
+            if messages_filepath.name == COMPRESSED_MESSAGES_FILENAME:
+                frame = zstandard.ZstdCompressor().compress("".join(lines).encode())
+                async with await AsyncPath(messages_filepath).open("ab") as f:
+                    await f.write(frame)
+                    await f.flush()
+                    os.fsync(f.wrapped.fileno())
+                return
+
             if not messages_filepath.exists():
                 messages_filepath.touch()
 
             async with await AsyncPath(messages_filepath).open(
                 "a", encoding="utf-8"
             ) as f:
-                for message in messages:
-                    await f.write(json.dumps(message, ensure_ascii=False) + "\n")
+                for line in lines:
+                    await f.write(line)
                     await f.flush()
                     os.fsync(f.wrapped.fileno())
         except Exception as e:
@@ -240,7 +314,9 @@ class SessionLogger:
                 return
 
             messages_data = [m.model_dump(exclude_none=True) for m in new_messages]
-            await SessionLogger.persist_messages(messages_data, self.session_dir)
+            await SessionLogger.persist_messages(
+                messages_data, self.session_dir, compress=self.session_config.compress
+            )
 
             # If message update succeeded, write metadata
             tools_available = [
@@ -255,7 +331,7 @@ class SessionLogger:
                 for tool_class in tool_manager.available_tools.values()
             ]
 
-            title = self._get_title(messages)
+            title = self.get_title(messages)
             system_prompt = (
                 messages[0].model_dump()
                 if len(messages) > 0 and messages[0].role == Role.system
@@ -286,15 +362,63 @@ class SessionLogger:
         finally:
             self.cleanup_tmp_files()
 
-    def reset_session(self, session_id: str) -> None:
-        """Clear existing session info and setup a new session"""
+    async def set_name(self, name: str) -> None:
+        await self._update_metadata(name=name)
+
+    async def set_auto_title(self, title: str) -> None:
+        await self._update_metadata(auto_title=title)
+
+    async def set_tags(self, tags: list[str]) -> list[str]:
+        """Replace the session's tags with ``tags``, normalized, and return them."""
+        tags = normalize_tags(tags)
+        await self._update_metadata(tags=tags)
+        return tags
+
+    async def set_forked_from(self, session_id: str, turn: int | None) -> None:
+        await self._update_metadata(forked_from=session_id, forked_at_turn=turn)
+
+    async def _update_metadata(self, **fields: Any) -> None:
+        if not self.enabled or self.session_metadata is None:
+            return
+
+        self.session_metadata = self.session_metadata.model_copy(update=fields)
+        if self.session_dir is None or not self.metadata_filepath.exists():
+            # Written with the first saved interaction.
+            return
+
+        try:
+            async with await AsyncPath(self.metadata_filepath).open(
+                encoding="utf-8", errors="ignore"
+            ) as f:
+                metadata = json.loads(await f.read())
+        except Exception as e:
+            raise RuntimeError(
+                f"Failed to read session metadata at {self.metadata_filepath}: {e}"
+            ) from e
+
+        metadata.update(fields)
+        if title := self.session_metadata.name or self.session_metadata.auto_title:
+            metadata["title"] = title
+        await SessionLogger.persist_metadata(metadata, self.session_dir)
+
+    def reset_session(self, session_id: str, *, keep_title: bool = False) -> None:
+        """Clear existing session info and setup a new session
+
+        With ``keep_title``, the new session keeps the name, title and tags of
+        the old one, as when it continues the same conversation.
+        """
         if not self.enabled:
             return
 
+        previous = self.session_metadata
         self.session_id = session_id
         self.session_start_time = utc_now().isoformat()
         self.session_dir = self.save_folder
         self.session_metadata = self._initialize_session_metadata()
+        if keep_title and previous is not None:
+            self.session_metadata.name = previous.name
+            self.session_metadata.auto_title = previous.auto_title
+            self.session_metadata.tags = previous.tags
 
     def cleanup_tmp_files(self) -> None:
         """Delete temporary files created more than 5 minutes ago"""
@@ -0,0 +1,127 @@
+from __future__ import annotations
+
+from datetime import UTC, datetime, timedelta
+from pathlib import Path
+import shutil
+from typing import TYPE_CHECKING, NamedTuple
+
+from rune.core.session.session_archive import (
+    SessionArchiveError,
+    archive_session,
+    refresh_index,
+)
+from rune.core.session.session_index import SessionIndex
+from rune.core.session.session_logger import messages_file
+from rune.core.session.session_sync import sync_sessions_entrypoint
+from rune.core.utils import logger
+
+if TYPE_CHECKING:
+    from rune.core.config import (
+        NetworkConfig,
+        SessionLoggingConfig,
+        SessionRetentionConfig,
+        SessionSyncConfig,
+    )
+
+# Sessions used this recently may be open in another Rune, so they are kept.
+ACTIVE_SESSION_GRACE = timedelta(days=1)
+_MB = 1024 * 1024
+
+
+class _Session(NamedTuple):
+    path: Path
+    last_used: datetime
+    size: int
+
+
+def prune_sessions(
+    logging_config: SessionLoggingConfig,
+    retention: SessionRetentionConfig,
+    now: datetime | None = None,
+) -> list[Path]:
+    """Delete, or archive, the sessions the retention policy no longer keeps:
+    those unused for ``max_age_days``, then the oldest ones until the rest fit
+    in ``max_total_mb``. Returns the pruned session directories.
+    """
+    if not logging_config.enabled or not (
+        retention.max_age_days or retention.max_total_mb
+    ):
+        return []
+    now = now or datetime.now(UTC)
+    sessions = sorted(_sessions(logging_config), key=lambda s: s.last_used)
+
+    doomed: list[_Session] = []
+    if retention.max_age_days:
+        cutoff = now - timedelta(days=retention.max_age_days)
+        doomed = [s for s in sessions if s.last_used < cutoff]
+    if retention.max_total_mb:
+        kept = [s for s in sessions if s not in doomed]
+        excess = sum(s.size for s in kept) - retention.max_total_mb * _MB
+        for session in kept:
+            if excess <= 0:
+                break
+            doomed.append(session)
+            excess -= session.size
+
+    pruned = []
+    for session in doomed:
+        if now - session.last_used < ACTIVE_SESSION_GRACE:
+            continue
+        try:
+            if retention.archive:
+                archive_session(session.path, logging_config)
+            else:
+                shutil.rmtree(session.path)
+        except (OSError, SessionArchiveError) as e:
+            logger.warning("Could not prune session %s: %s", session.path, e)
+            continue
+        pruned.append(session.path)
+
+    if pruned:
+        logger.info("Pruned %d session(s) past their retention", len(pruned))
+        refresh_index(logging_config)
+    return pruned
+
+
+def session_housekeeping_entrypoint(
+    logging_config: SessionLoggingConfig,
+    retention: SessionRetentionConfig,
+    sync_config: SessionSyncConfig,
+    network: NetworkConfig | None = None,
+) -> None:
+    """Sync sessions when set up to, prune those past their retention, and
+    maintain the search index when it is due.
+    """
+    sync_sessions_entrypoint(logging_config, sync_config, network)
+    try:
+        prune_sessions(logging_config, retention)
+    except Exception as e:
+        logger.warning("Session pruning failed: %s", e)
+    if not logging_config.enabled:
+        return
+    try:
+        index = SessionIndex(logging_config)
+        if index.maintenance_due():
+            report = index.maintain()
+            logger.info(
+                "Maintained the session index: %d stale session(s) dropped",
+                report.pruned_sessions,
+            )
+    except Exception as e:
+        logger.warning("Session index maintenance failed: %s", e)
+
+
+def _sessions(config: SessionLoggingConfig) -> list[_Session]:
+    sessions = []
+    for path in Path(config.save_dir).glob(f"{config.session_prefix}_*"):
+        try:
+            if not path.is_dir():
+                continue
+            # A session that never saved a message only has its folder's time.
+            messages = messages_file(path)
+            mtime = (messages if messages.exists() else path).stat().st_mtime
+            size = sum(f.stat().st_size for f in path.rglob("*") if f.is_file())
+        except OSError:
+            continue
+        sessions.append(_Session(path, datetime.fromtimestamp(mtime, UTC), size))
+    return sessions
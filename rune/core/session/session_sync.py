@@ -0,0 +1,549 @@
+from __future__ import annotations
+
+from abc import ABC, abstractmethod
+from datetime import UTC, datetime
+import hashlib
+import hmac
+import json
+import os
+from pathlib import Path
+from typing import TYPE_CHECKING, Any
+from urllib.parse import quote, unquote, urlsplit
+from uuid import uuid4
+import xml.etree.ElementTree as ET
+
+import httpx
+from pydantic import BaseModel, field_validator
+
+from rune.core.network import proxy_options
+from rune.core.session.session_archive import refresh_index
+from rune.core.session.session_logger import (
+    COMPRESSED_MESSAGES_FILENAME,
+    MESSAGES_FILENAME,
+    METADATA_FILENAME,
+    messages_file,
+)
+from rune.core.utils import logger, utc_now
+
+if TYPE_CHECKING:
+    from rune.core.config import NetworkConfig, SessionLoggingConfig, SessionSyncConfig
+
+SYNC_STATE_FILENAME = "sync_state.json"
+HEAD_FILENAME = "head.json"
+CHUNKS_DIRNAME = "chunks"
+REQUEST_TIMEOUT_SECONDS = 30.0
+EMPTY_SHA256 = hashlib.sha256(b"").hexdigest()
+_S3_NAMESPACE = {"s3": "http://s3.amazonaws.com/doc/2006-03-01/"}
+_DAV_NAMESPACE = {"d": "DAV:"}
+_HTTP_NOT_FOUND = 404
+_HTTP_METHOD_NOT_ALLOWED = 405
+
+
+class SessionSyncError(Exception):
+    pass
+
+
+class SyncBackend(ABC):
+    """A store of files under ``/``-separated keys."""
+
+    @abstractmethod
+    def get(self, key: str) -> bytes | None: ...
+
+    @abstractmethod
+    def put(self, key: str, data: bytes) -> None: ...
+
+    @abstractmethod
+    def delete(self, key: str) -> None: ...
+
+    @abstractmethod
+    def children(self, prefix: str) -> list[str]:
+        """The names of the files and folders right under ``prefix``."""
+
+
+def sign_v4(
+    method: str,
+    url: httpx.URL,
+    headers: dict[str, str],
+    access_key: str,
+    secret_key: str,
+    region: str,
+    service: str = "s3",
+) -> str:
+    """The AWS Signature Version 4 `Authorization` header of a request, which
+    must carry its `x-amz-date` and `x-amz-content-sha256` in ``headers``.
+    """
+    amz_date = headers["x-amz-date"]
+    scope = f"{amz_date[:8]}/{region}/{service}/aws4_request"
+    signed = {name.lower(): " ".join(value.split()) for name, value in headers.items()}
+    signed["host"] = url.netloc.decode()
+    names = sorted(signed)
+    query = sorted(
+        (quote(k, safe="-_.~"), quote(v, safe="-_.~")) for k, v in url.params.items()
+    )
+    canonical_request = "\n".join([
+        method,
+        quote(url.path, safe="/-_.~"),
+        "&".join(f"{k}={v}" for k, v in query),
+        "".join(f"{name}:{signed[name]}\n" for name in names),
+        ";".join(names),
+        headers["x-amz-content-sha256"],
+    ])
+    string_to_sign = "\n".join([
+        "AWS4-HMAC-SHA256",
+        amz_date,
+        scope,
+        hashlib.sha256(canonical_request.encode()).hexdigest(),
+    ])
+    key = f"AWS4{secret_key}".encode()
+    for part in (amz_date[:8], region, service, "aws4_request"):
+        key = hmac.new(key, part.encode(), hashlib.sha256).digest()
+    signature = hmac.new(key, string_to_sign.encode(), hashlib.sha256).hexdigest()
+    return (
+        f"AWS4-HMAC-SHA256 Credential={access_key}/{scope}, "
+        f"SignedHeaders={';'.join(names)}, Signature={signature}"
+    )
+
+
+class S3Backend(SyncBackend):
+    """An S3 bucket, or one of an S3-compatible store, addressed path-style."""
+
+    def __init__(
+        self,
+        endpoint: str,
+        bucket: str,
+        region: str,
+        access_key: str,
+        secret_key: str,
+        client: httpx.Client | None = None,
+        network: NetworkConfig | None = None,
+    ) -> None:
+        self.endpoint = endpoint.rstrip("/")
+        self.bucket = bucket
+        self.region = region
+        self.access_key = access_key
+        self.secret_key = secret_key
+        self.client = client or httpx.Client(
+            timeout=REQUEST_TIMEOUT_SECONDS, **proxy_options(network, self.endpoint)
+        )
+
+    def _request(
+        self,
+        method: str,
+        key: str = "",
+        params: dict[str, str] | None = None,
+        content: bytes = b"",
+    ) -> httpx.Response:
+        url = httpx.URL(
+            f"{self.endpoint}/{self.bucket}/{quote(key, safe='/-_.~')}",
+            params=params,
+        )
+        headers = {
+            "x-amz-date": datetime.now(UTC).strftime("%Y%m%dT%H%M%SZ"),
+            "x-amz-content-sha256": hashlib.sha256(content).hexdigest(),
+        }
+        headers["Authorization"] = sign_v4(
+            method, url, headers, self.access_key, self.secret_key, self.region
+        )
+        try:
+            response = self.client.request(
+                method, url, headers=headers, content=content
+            )
+        except httpx.HTTPError as e:
+            raise SessionSyncError(f"S3 {method} {key} failed: {e}") from e
+        if response.status_code != _HTTP_NOT_FOUND and response.is_error:
+            raise SessionSyncError(
+                f"S3 {method} {key} failed: {response.status_code} {response.text}"
+            )
+        return response
+
+    def get(self, key: str) -> bytes | None:
+        response = self._request("GET", key)
+        return None if response.status_code == _HTTP_NOT_FOUND else response.content
+
+    def put(self, key: str, data: bytes) -> None:
+        self._request("PUT", key, content=data)
+
+    def delete(self, key: str) -> None:
+        self._request("DELETE", key)
+
+    def children(self, prefix: str) -> list[str]:
+        prefix = prefix.rstrip("/") + "/"
+        names: list[str] = []
+        params = {"list-type": "2", "prefix": prefix, "delimiter": "/"}
+        while True:
+            root = ET.fromstring(self._request("GET", params=params).content)
+            for element in [
+                *root.findall("s3:CommonPrefixes/s3:Prefix", _S3_NAMESPACE),
+                *root.findall("s3:Contents/s3:Key", _S3_NAMESPACE),
+            ]:
+                names.append((element.text or "")[len(prefix) :].rstrip("/"))
+            token = root.find("s3:NextContinuationToken", _S3_NAMESPACE)
+            if token is None or not token.text:
+                return [name for name in names if name]
+            params["continuation-token"] = token.text
+
+
+class WebDavBackend(SyncBackend):
+    def __init__(
+        self,
+        url: str,
+        username: str = "",
+        password: str = "",
+        client: httpx.Client | None = None,
+        network: NetworkConfig | None = None,
+    ) -> None:
+        self.url = url.rstrip("/")
+        self.client = client or httpx.Client(
+            timeout=REQUEST_TIMEOUT_SECONDS,
+            auth=(username, password) if username else None,
+            **proxy_options(network, self.url),
+        )
+        self._collections: set[str] = set()
+
+    def _request(self, method: str, key: str, **kwargs: Any) -> httpx.Response:
+        try:
+            response = self.client.request(method, f"{self.url}/{quote(key)}", **kwargs)
+        except httpx.HTTPError as e:
+            raise SessionSyncError(f"WebDAV {method} {key} failed: {e}") from e
+        if response.is_error and response.status_code not in {
+            _HTTP_NOT_FOUND,
+            _HTTP_METHOD_NOT_ALLOWED,
+        }:
+            raise SessionSyncError(
+                f"WebDAV {method} {key} failed: {response.status_code}"
+            )
+        return response
+
+    def get(self, key: str) -> bytes | None:
+        response = self._request("GET", key)
+        return None if response.status_code == _HTTP_NOT_FOUND else response.content
+
+    def put(self, key: str, data: bytes) -> None:
+        # Collections are created one level at a time; 405 means one exists.
+        parts = key.split("/")[:-1]
+        for depth in range(1, len(parts) + 1):
+            collection = "/".join(parts[:depth])
+            if collection not in self._collections:
+                self._request("MKCOL", collection)
+                self._collections.add(collection)
+        self._request("PUT", key, content=data)
+
+    def delete(self, key: str) -> None:
+        self._request("DELETE", key)
+
+    def children(self, prefix: str) -> list[str]:
+        prefix = prefix.rstrip("/")
+        response = self._request("PROPFIND", prefix + "/", headers={"Depth": "1"})
+        if response.status_code == _HTTP_NOT_FOUND:
+            return []
+        own_path = urlsplit(f"{self.url}/{quote(prefix)}/").path.rstrip("/")
+        names = []
+        root = ET.fromstring(response.content)
+        for href in root.iterfind(".//d:href", _DAV_NAMESPACE):
+            path = urlsplit(href.text or "").path.rstrip("/")
+            if path and path != own_path:
+                names.append(unquote(path.rsplit("/", 1)[-1]))
+        return names
+
+
+def make_backend(
+    config: SessionSyncConfig, network: NetworkConfig | None = None
+) -> SyncBackend:
+    if not config.url:
+        raise SessionSyncError("Set session_sync.url to sync sessions")
+    match config.backend:
+        case "s3":
+            access_key = os.getenv(config.access_key_env_var, "")
+            secret_key = os.getenv(config.secret_key_env_var, "")
+            if not (config.bucket and access_key and secret_key):
+                raise SessionSyncError(
+                    "S3 sync needs session_sync.bucket, "
+                    f"{config.access_key_env_var} and {config.secret_key_env_var}"
+                )
+            return S3Backend(
+                config.url,
+                config.bucket,
+                config.region,
+                access_key,
+                secret_key,
+                network=network,
+            )
+        case "webdav":
+            return WebDavBackend(
+                config.url,
+                config.username,
+                os.getenv(config.password_env_var, ""),
+                network=network,
+            )
+
+
+class SyncReport(BaseModel):
+    pushed: list[str] = []
+    pulled: list[str] = []
+    # Copies kept of remote versions that diverged from the local session.
+    conflicts: list[str] = []
+    errors: list[str] = []
+
+
+class _Version(BaseModel):
+    file: str = ""
+    size: int = 0
+    sha256: str = EMPTY_SHA256
+    # Bumped when a conflict replaces the remote copy, which then no longer
+    # extends what other machines have.
+    generation: int = 0
+
+    @field_validator("file")
+    @classmethod
+    def _check_file(cls, file: str) -> str:
+        # Remote heads name the local file their bytes are written to.
+        if file not in {"", MESSAGES_FILENAME, COMPRESSED_MESSAGES_FILENAME}:
+            raise ValueError(f"{file!r} is not a messages file")
+        return file
+
+
+class SessionSync:
+    """Syncs session logs with a remote store, so a session started on one
+    machine can be resumed on another.
+
+    Messages files only grow, so each sync uploads or downloads the bytes
+    added since the last one, as a chunk named by its offset. When both
+    copies of a session changed since they were last in sync, the local one
+    wins and the remote one is kept as a new local session.
+    """
+
+    def __init__(
+        self,
+        logging_config: SessionLoggingConfig,
+        sync_config: SessionSyncConfig,
+        backend: SyncBackend | None = None,
+        network: NetworkConfig | None = None,
+    ) -> None:
+        self.logging_config = logging_config
+        self.sync_config = sync_config
+        self.backend = backend or make_backend(sync_config, network)
+        self.save_dir = Path(logging_config.save_dir)
+        self.state_path = self.save_dir / SYNC_STATE_FILENAME
+        self.state = self._load_state()
+
+    def run(self) -> SyncReport:
+        report = SyncReport()
+        remote = set(self.backend.children(self.sync_config.prefix))
+        local = {
+            path.name
+            for path in self.save_dir.glob(f"{self.logging_config.session_prefix}_*")
+            if path.is_dir()
+        }
+        names = sorted(
+            {name for name in local if self._syncs(self.save_dir / name)}
+            | {name for name in remote if self._is_session_name(name)}
+        )
+        names = [name for name in names if name not in self.state["forgotten"]]
+        for name in names:
+            try:
+                self._sync_session(name, report)
+            except (OSError, ValueError, SessionSyncError) as e:
+                report.errors.append(f"{name}: {e}")
+        self._save_state()
+        if report.pulled or report.conflicts:
+            refresh_index(self.logging_config)
+        return report
+
+    def _syncs(self, session_dir: Path) -> bool:
+        """Whether the session is in a synced project, or was synced before."""
+        if session_dir.name in self.state["sessions"]:
+            return True
+        try:
+            metadata = json.loads((session_dir / METADATA_FILENAME).read_text("utf-8"))
+        except (OSError, ValueError):
+            return False
+        workdir = (metadata.get("environment") or {}).get("working_directory")
+        return bool(workdir) and any(
+            Path(workdir).is_relative_to(project)
+            for project in self.sync_config.projects
+        )
+
+    def _is_session_name(self, name: str) -> bool:
+        # Remote names become local paths.
+        return (
+            name.startswith(f"{self.logging_config.session_prefix}_")
+            and Path(name).name == name
+        )
+
+    def _sync_session(self, name: str, report: SyncReport) -> None:
+        session_dir = self.save_dir / name
+        key = f"{self.sync_config.prefix}/{name}"
+        local = _local_version(session_dir)
+        head = self.backend.get(f"{key}/{HEAD_FILENAME}")
+        remote = _Version.model_validate_json(head) if head else _Version()
+        synced = self.state["sessions"].get(name)
+        known = _Version.model_validate(synced) if synced else None
+
+        if known is not None and not session_dir.exists():
+            # Deleted or archived here, so not downloaded again.
+            del self.state["sessions"][name]
+            self.state["forgotten"].append(name)
+            return
+        if local.sha256 == remote.sha256:
+            generation = remote.generation
+        elif (known is None and remote.size == 0) or (
+            known is not None and remote.sha256 == known.sha256
+        ):
+            generation = remote.generation
+            if _extends(session_dir, local, remote):
+                self._push(session_dir, key, local, remote.size, generation)
+            else:
+                # Rewritten here, e.g. compacted or compressed, so the remote
+                # bytes no longer start it.
+                generation += 1
+                self._drop_chunks(key)
+                self._push(session_dir, key, local, 0, generation)
+            report.pushed.append(name)
+        elif (known is None and local.size == 0) or (
+            known is not None and local.sha256 == known.sha256
+        ):
+            rewritten = known is None or known.generation != remote.generation
+            local = self._pull(session_dir, key, remote, 0 if rewritten else local.size)
+            generation = remote.generation
+            report.pulled.append(name)
+        else:
+            report.conflicts.append(self._keep_remote_copy(session_dir, key, remote))
+            generation = remote.generation + 1
+            self._drop_chunks(key)
+            self._push(session_dir, key, local, 0, generation)
+            report.pushed.append(name)
+        self.state["sessions"][name] = {**local.model_dump(), "generation": generation}
+
+    def _push(
+        self,
+        session_dir: Path,
+        key: str,
+        local: _Version,
+        offset: int,
+        generation: int,
+    ) -> None:
+        """Upload the bytes of the local messages file from ``offset`` on."""
+        with (session_dir / local.file).open("rb") as f:
+            f.seek(offset)
+            delta = f.read(local.size - offset)
+        self.backend.put(f"{key}/{CHUNKS_DIRNAME}/{offset:016d}", delta)
+        metadata = session_dir / METADATA_FILENAME
+        if metadata.is_file():
+            self.backend.put(f"{key}/{METADATA_FILENAME}", metadata.read_bytes())
+        head = {
+            **local.model_dump(),
+            "generation": generation,
+            "device": self.state["device_id"],
+        }
+        self.backend.put(f"{key}/{HEAD_FILENAME}", json.dumps(head).encode())
+
+    def _drop_chunks(self, key: str) -> None:
+        for chunk in self.backend.children(f"{key}/{CHUNKS_DIRNAME}"):
+            self.backend.delete(f"{key}/{CHUNKS_DIRNAME}/{chunk}")
+
+    def _pull(
+        self, session_dir: Path, key: str, remote: _Version, offset: int
+    ) -> _Version:
+        """Download the remote messages from ``offset`` on, over the local
+        ones past it.
+        """
+        data = self._download(key, start=offset)
+        if offset + len(data) != remote.size:
+            raise SessionSyncError(f"{key} should have {remote.size} bytes")
+        session_dir.mkdir(parents=True, exist_ok=True)
+        with (session_dir / remote.file).open("r+b" if offset else "wb") as f:
+            f.seek(offset)
+            f.write(data)
+            f.truncate()
+        if (metadata := self.backend.get(f"{key}/{METADATA_FILENAME}")) is not None:
+            (session_dir / METADATA_FILENAME).write_bytes(metadata)
+        return _local_version(session_dir)
+
+    def _download(self, key: str, start: int = 0) -> bytes:
+        data = b""
+        offset = start
+        for chunk in sorted(self.backend.children(f"{key}/{CHUNKS_DIRNAME}")):
+            if int(chunk) < start:
+                continue
+            if int(chunk) != offset:
+                raise SessionSyncError(f"{key} is missing the bytes at {offset}")
+            content = self.backend.get(f"{key}/{CHUNKS_DIRNAME}/{chunk}") or b""
+            data += content
+            offset += len(content)
+        return data
+
+    def _keep_remote_copy(self, session_dir: Path, key: str, remote: _Version) -> str:
+        """Save the remote version of the session under a new id, and return
+        its directory name.
+        """
+        session_id = str(uuid4())
+        timestamp = utc_now().strftime("%Y%m%d_%H%M%S")
+        prefix = self.logging_config.session_prefix
+        copy_dir = self.save_dir / f"{prefix}_{timestamp}_{session_id[:8]}"
+        copy_dir.mkdir(parents=True)
+        (copy_dir / remote.file).write_bytes(self._download(key))
+        raw_metadata = self.backend.get(f"{key}/{METADATA_FILENAME}")
+        metadata = json.loads(raw_metadata) if raw_metadata else {}
+        metadata.update(session_id=session_id, conflict_of=metadata.get("session_id"))
+        (copy_dir / METADATA_FILENAME).write_text(json.dumps(metadata, indent=2))
+        # Pushed as a session of its own from now on.
+        self.state["sessions"].setdefault(copy_dir.name, None)
+        logger.warning(
+            "Session %s changed on two machines; kept the remote one as %s",
+            session_dir.name,
+            copy_dir.name,
+        )
+        return copy_dir.name
+
+    def _load_state(self) -> dict[str, Any]:
+        try:
+            state = json.loads(self.state_path.read_text("utf-8"))
+        except (OSError, ValueError):
+            state = {}
+        state.setdefault("device_id", str(uuid4()))
+        state.setdefault("sessions", {})
+        state.setdefault("forgotten", [])
+        return state
+
+    def _save_state(self) -> None:
+        self.save_dir.mkdir(parents=True, exist_ok=True)
+        tmp = self.state_path.with_suffix(".tmp")
+        tmp.write_text(json.dumps(self.state, indent=2), "utf-8")
+        tmp.replace(self.state_path)
+
+
+def _local_version(session_dir: Path) -> _Version:
+    path = messages_file(session_dir)
+    if not path.is_file():
+        return _Version()
+    data = path.read_bytes()
+    return _Version(
+        file=path.name, size=len(data), sha256=hashlib.sha256(data).hexdigest()
+    )
+
+
+def _extends(session_dir: Path, local: _Version, remote: _Version) -> bool:
+    """Whether the local messages file starts with the remote bytes."""
+    if remote.size == 0:
+        return True
+    if local.file != remote.file or local.size < remote.size:
+        return False
+    with (session_dir / local.file).open("rb") as f:
+        prefix = f.read(remote.size)
+    return hashlib.sha256(prefix).hexdigest() == remote.sha256
+
+
+def sync_sessions_entrypoint(
+    logging_config: SessionLoggingConfig,
+    sync_config: SessionSyncConfig,
+    network: NetworkConfig | None = None,
+) -> None:
+    if not (logging_config.enabled and sync_config.enabled):
+        return
+    try:
+        report = SessionSync(logging_config, sync_config, network=network).run()
+    except Exception as e:
+        logger.warning("Session sync failed: %s", e)
+        return
+    for error in report.errors:
+        logger.warning("Session sync: %s", error)
@@ -0,0 +1,90 @@
+from __future__ import annotations
+
+from collections.abc import Callable
+from contextlib import nullcontext
+from typing import TYPE_CHECKING
+
+from rune.core.llm.backend.factory import BACKEND_FACTORY
+from rune.core.prompts import UtilityPrompt
+from rune.core.types import LLMMessage, Role
+from rune.core.utils import get_user_agent, logger
+
+if TYPE_CHECKING:
+    from rune.core.config import RuneConfig
+    from rune.core.llm.types import BackendLike
+
+# Only the opening of the exchange is sent: it is what the title is about, and
+# it keeps the request cheap however long the first turn ran.
+MAX_EXCERPT_CHARS = 2000
+MAX_TITLE_LENGTH = 60
+
+
+def _opening_exchange(messages: list[LLMMessage]) -> str:
+    sections: list[str] = []
+    for role, label in ((Role.user, "User"), (Role.assistant, "Assistant")):
+        content = next(
+            (m.content for m in messages if m.role == role and m.content), None
+        )
+        if content:
+            sections.append(f"## {label}\n{content[:MAX_EXCERPT_CHARS]}")
+    return "\n\n".join(sections)
+
+
+def clean_title(text: str) -> str:
+    title = text.strip().splitlines()[0] if text.strip() else ""
+    title = title.removeprefix("Title:").strip().strip("\"'`*").rstrip(".!")
+    if len(title) > MAX_TITLE_LENGTH:
+        title = title[:MAX_TITLE_LENGTH].rstrip() + "…"
+    return title
+
+
+class SessionTitler:
+    """Asks a model for a short title summarizing a session's first turn.
+
+    Titling is best effort: failures are logged and the session keeps the
+    title derived from its first message.
+    """
+
+    def __init__(
+        self,
+        config_getter: Callable[[], RuneConfig],
+        backend: BackendLike | None = None,
+    ) -> None:
+        self._config_getter = config_getter
+        self.backend = backend
+
+    async def generate(self, messages: list[LLMMessage]) -> str | None:
+        if not (exchange := _opening_exchange(messages)):
+            return None
+
+        config = self._config_getter()
+        try:
+            model = config.auto_title.get_model(config)
+            provider = config.get_provider_for_model(model)
+            context = (
+                nullcontext(self.backend)
+                if self.backend is not None
+                else BACKEND_FACTORY[provider.backend](
+                    provider=provider, timeout=config.api_timeout
+                )
+            )
+            async with context as backend:
+                result = await backend.complete(
+                    model=model,
+                    messages=[
+                        LLMMessage(
+                            role=Role.system, content=UtilityPrompt.SESSION_TITLE.read()
+                        ),
+                        LLMMessage(role=Role.user, content=exchange),
+                    ],
+                    temperature=0.0,
+                    tools=None,
+                    tool_choice=None,
+                    extra_headers={"user-agent": get_user_agent(provider.backend)},
+                    max_tokens=config.auto_title.max_tokens,
+                )
+        except Exception as e:
+            logger.warning("Session titling failed: %s", e)
+            return None
+
+        return clean_title(result.message.content or "") or None
@@ -0,0 +1,154 @@
+from __future__ import annotations
+
+from collections.abc import Mapping
+from fnmatch import fnmatchcase
+import json
+import os
+from pathlib import Path
+from typing import Any
+
+from pydantic import BaseModel
+
+from rune.core.redaction import Redactor
+from rune.core.secrets import REDACTED
+from rune.core.session.session_loader import SessionLoader
+from rune.core.tools.exec_env import DEFAULT_EXCLUDED_ENV
+from rune.core.types import LLMMessage, Role
+
+# Values shorter than this are too likely to appear by accident to redact.
+_MIN_SECRET_LENGTH = 8
+
+
+class TraceError(ValueError):
+    pass
+
+
+class TurnTrace(BaseModel):
+    """The prompt sent for one model request of a logged session."""
+
+    session_id: str
+    turn: int
+    request: int
+    requests_in_turn: int
+    system_prompt: str | None
+    messages: list[dict[str, Any]]
+    tools: list[dict[str, Any]]
+
+    def redacted(
+        self, *, secrets: bool = True, tool_outputs: bool = False
+    ) -> TurnTrace:
+        environ_secrets = _environ_secrets(os.environ) if secrets else []
+
+        def scrub(text: str | None) -> str | None:
+            return redact_secrets(text, environ_secrets) if text and secrets else text
+
+        messages: list[dict[str, Any]] = []
+        for message in self.messages:
+            message = dict(message)
+            content = message.get("content")
+            if tool_outputs and message.get("role") == Role.tool and content:
+                message["content"] = f"[{len(content)} characters of tool output]"
+            else:
+                message["content"] = scrub(content)
+            if secrets and message.get("tool_calls"):
+                message["tool_calls"] = json.loads(
+                    scrub(json.dumps(message["tool_calls"])) or "[]"
+                )
+            messages.append(message)
+
+        return self.model_copy(
+            update={"system_prompt": scrub(self.system_prompt), "messages": messages}
+        )
+
+    def to_text(self) -> str:
+        lines = [
+            f"# Session {self.session_id}, turn {self.turn}, "
+            f"request {self.request} of {self.requests_in_turn}",
+            "",
+            "## Instructions",
+            "",
+            self.system_prompt or "(none logged)",
+            "",
+            f"## Tools ({len(self.tools)})",
+            "",
+        ]
+        for tool in self.tools:
+            function = tool.get("function", {})
+            lines.append(f"### {function.get('name', '?')}")
+            lines.append(function.get("description", ""))
+            lines.append(json.dumps(function.get("parameters", {}), indent=2))
+            lines.append("")
+        lines += [f"## Messages ({len(self.messages)})", ""]
+        for message in self.messages:
+            header = f"### {message.get('role', '?')}"
+            if tool_call_id := message.get("tool_call_id"):
+                header += f" ({tool_call_id})"
+            lines.append(header)
+            if content := message.get("content"):
+                lines.append(str(content))
+            for tool_call in message.get("tool_calls") or []:
+                function = tool_call.get("function", {})
+                lines.append(
+                    f"-> {function.get('name')}({function.get('arguments') or ''})"
+                )
+            lines.append("")
+        return "\n".join(lines).rstrip() + "\n"
+
+
+def build_turn_trace(session_dir: Path, turn: int, request: int = 1) -> TurnTrace:
+    """Rebuild the prompt of the ``request``-th model call of user turn ``turn``.
+
+    Sessions are logged again from scratch after a compaction, so the history of
+    a session directory is what the model saw, compacted summary included.
+    """
+    messages, metadata = SessionLoader.load_session(session_dir)
+    user_indices = [i for i, m in enumerate(messages) if m.role == Role.user]
+    if not 1 <= turn <= len(user_indices):
+        raise TraceError(
+            f"Turn {turn} does not exist: the session has {len(user_indices)} turn(s)"
+        )
+
+    start = user_indices[turn - 1]
+    end = user_indices[turn] if turn < len(user_indices) else len(messages)
+    replies = [i for i in range(start, end) if messages[i].role == Role.assistant]
+    if not 1 <= request <= max(len(replies), 1):
+        raise TraceError(
+            f"Request {request} does not exist: turn {turn} made "
+            f"{len(replies)} model request(s)"
+        )
+    cutoff = replies[request - 1] if replies else end
+
+    system_prompt = (metadata.get("system_prompt") or {}).get("content")
+    return TurnTrace(
+        session_id=str(metadata.get("session_id", session_dir.name)),
+        turn=turn,
+        request=request,
+        requests_in_turn=len(replies),
+        system_prompt=system_prompt,
+        messages=[_dump(m) for m in messages[:cutoff]],
+        tools=list(metadata.get("tools_available") or []),
+    )
+
+
+def redact_secrets(text: str, extra: list[str] | None = None) -> str:
+    for value in extra or []:
+        text = text.replace(value, REDACTED)
+    return Redactor().redact(text)[0]
+
+
+def _environ_secrets(environ: Mapping[str, str]) -> list[str]:
+    patterns = [pattern.upper() for pattern in DEFAULT_EXCLUDED_ENV]
+    return sorted(
+        (
+            value
+            for name, value in environ.items()
+            if len(value) >= _MIN_SECRET_LENGTH
+            and any(fnmatchcase(name.upper(), pattern) for pattern in patterns)
+        ),
+        key=len,
+        reverse=True,
+    )
+
+
+def _dump(message: LLMMessage) -> dict[str, Any]:
+    return message.model_dump(mode="json", exclude_none=True, exclude={"message_id"})
@@ -0,0 +1,212 @@
+from __future__ import annotations
+
+from enum import StrEnum
+import json
+from pathlib import Path
+import shutil
+from typing import TYPE_CHECKING
+
+from pydantic import BaseModel
+import zstandard
+
+from rune.core.session.session_index import SessionIndex
+from rune.core.session.session_logger import (
+    COMPRESSED_MESSAGES_FILENAME,
+    METADATA_FILENAME,
+    messages_file,
+    read_message_lines,
+)
+
+if TYPE_CHECKING:
+    from rune.core.config import SessionLoggingConfig
+
+# The messages file as it was before a repair cut its truncated last line.
+BACKUP_SUFFIX = ".bak"
+
+
+class ProblemKind(StrEnum):
+    MISSING_MESSAGES = "missing_messages"
+    UNREADABLE_MESSAGES = "unreadable_messages"
+    TRUNCATED_LINE = "truncated_line"
+    CORRUPT_LINE = "corrupt_line"
+    MISSING_METADATA = "missing_metadata"
+    INVALID_METADATA = "invalid_metadata"
+    ID_MISMATCH = "id_mismatch"
+    INDEX_MISMATCH = "index_mismatch"
+    INDEX_STALE = "index_stale"
+
+
+# Fixed by `rune sessions verify --repair`; the rest need a look by hand.
+REPAIRABLE = {
+    ProblemKind.TRUNCATED_LINE,
+    ProblemKind.INDEX_MISMATCH,
+    ProblemKind.INDEX_STALE,
+}
+
+
+class SessionProblem(BaseModel):
+    session_dir: Path
+    kind: ProblemKind
+    detail: str
+
+    @property
+    def repairable(self) -> bool:
+        return self.kind in REPAIRABLE
+
+
+def verify_sessions(config: SessionLoggingConfig) -> list[SessionProblem]:
+    """Problems in the logged sessions, as a crash or a full disk leaves them:
+    cut-off or corrupt messages, missing or inconsistent metadata, and search
+    index entries that no longer match their session.
+    """
+    save_dir = Path(config.save_dir)
+    if not save_dir.exists():
+        return []
+    session_ids: dict[Path, str] = {}
+    problems: list[SessionProblem] = []
+    for session_dir in sorted(save_dir.glob(f"{config.session_prefix}_*")):
+        if not session_dir.is_dir():
+            continue
+        problems += _check_messages(session_dir)
+        metadata_problems, session_id = _check_metadata(session_dir)
+        problems += metadata_problems
+        if session_id is not None:
+            session_ids[session_dir] = session_id
+    return problems + _check_index(config, session_ids)
+
+
+def repair_sessions(
+    config: SessionLoggingConfig, problems: list[SessionProblem]
+) -> list[SessionProblem]:
+    """Fix the repairable ``problems``: cut truncated last lines, keeping a
+    backup of the file, and rebuild the search index. Returns those fixed.
+    """
+    repaired = [p for p in problems if p.repairable]
+    for problem in repaired:
+        if problem.kind == ProblemKind.TRUNCATED_LINE:
+            _cut_last_line(messages_file(problem.session_dir))
+    if repaired:
+        # Also indexes the sessions whose last line was cut.
+        SessionIndex(config).rebuild()
+    return repaired
+
+
+def _check_messages(session_dir: Path) -> list[SessionProblem]:
+    path = messages_file(session_dir)
+    if not path.is_file():
+        return [
+            SessionProblem(
+                session_dir=session_dir,
+                kind=ProblemKind.MISSING_MESSAGES,
+                detail=f"{path.name} is missing",
+            )
+        ]
+    try:
+        lines = read_message_lines(path)
+    except (OSError, zstandard.ZstdError) as e:
+        return [
+            SessionProblem(
+                session_dir=session_dir,
+                kind=ProblemKind.UNREADABLE_MESSAGES,
+                detail=f"{path.name} can't be read: {e}",
+            )
+        ]
+
+    problems = []
+    for number, line in enumerate(lines, start=1):
+        if not line.strip():
+            continue
+        try:
+            valid = isinstance(json.loads(line), dict)
+        except json.JSONDecodeError:
+            valid = False
+        if valid:
+            continue
+        # An interrupted write leaves a partial last line without its newline.
+        truncated = (
+            number == len(lines)
+            and path.name != COMPRESSED_MESSAGES_FILENAME
+            and not line.endswith("\n")
+        )
+        if truncated:
+            kind, detail = ProblemKind.TRUNCATED_LINE, "is cut off"
+        else:
+            kind, detail = ProblemKind.CORRUPT_LINE, "is not a message"
+        problems.append(
+            SessionProblem(
+                session_dir=session_dir,
+                kind=kind,
+                detail=f"line {number} of {path.name} {detail}",
+            )
+        )
+    return problems
+
+
+def _check_metadata(session_dir: Path) -> tuple[list[SessionProblem], str | None]:
+    path = session_dir / METADATA_FILENAME
+    if not path.is_file():
+        return [
+            SessionProblem(
+                session_dir=session_dir,
+                kind=ProblemKind.MISSING_METADATA,
+                detail=f"{METADATA_FILENAME} is missing",
+            )
+        ], None
+    try:
+        metadata = json.loads(path.read_text("utf-8", errors="ignore"))
+    except (OSError, json.JSONDecodeError) as e:
+        metadata, error = None, str(e)
+    else:
+        error = "it has no session_id"
+    session_id = metadata.get("session_id") if isinstance(metadata, dict) else None
+    if not isinstance(session_id, str) or not session_id:
+        return [
+            SessionProblem(
+                session_dir=session_dir,
+                kind=ProblemKind.INVALID_METADATA,
+                detail=f"{METADATA_FILENAME} is invalid: {error}",
+            )
+        ], None
+    # Session directories end with the first 8 characters of their id.
+    if not session_dir.name.endswith(f"_{session_id[:8]}"):
+        return [
+            SessionProblem(
+                session_dir=session_dir,
+                kind=ProblemKind.ID_MISMATCH,
+                detail=f"the directory doesn't match session id {session_id}",
+            )
+        ], session_id
+    return [], session_id
+
+
+def _check_index(
+    config: SessionLoggingConfig, session_ids: dict[Path, str]
+) -> list[SessionProblem]:
+    index = SessionIndex(config)
+    if not index.path.exists():
+        return []
+    problems = []
+    for session_dir, indexed_id in index.indexed_sessions().items():
+        if not session_dir.exists():
+            problems.append(
+                SessionProblem(
+                    session_dir=session_dir,
+                    kind=ProblemKind.INDEX_STALE,
+                    detail="the search index lists a session that no longer exists",
+                )
+            )
+        elif (session_id := session_ids.get(session_dir)) and session_id != indexed_id:
+            problems.append(
+                SessionProblem(
+                    session_dir=session_dir,
+                    kind=ProblemKind.INDEX_MISMATCH,
+                    detail=f"the search index has it as session {indexed_id}",
+                )
+            )
+    return problems
+
+
+def _cut_last_line(path: Path) -> None:
+    shutil.copy2(path, path.with_name(path.name + BACKUP_SUFFIX))
+    content = path.read_bytes()
+    path.write_bytes(content[: content.rfind(b"\n") + 1])
@@ -5,7 +5,7 @@ from logging import getLogger
 from pathlib import Path
 from typing import TYPE_CHECKING
 
-from rune.core.paths.config_paths import resolve_local_skills_dir
+from rune.core.paths.config_paths import project_dir, resolve_local_skills_dir
 from rune.core.paths.global_paths import GLOBAL_SKILLS_DIR
 from rune.core.skills.models import SkillInfo, SkillMetadata
 from rune.core.skills.parser import SkillParseError, parse_frontmatter
@@ -58,7 +58,7 @@ class SkillManager:
             if path.is_dir():
                 paths.append(path)
 
-        if (skills_dir := resolve_local_skills_dir(Path.cwd())) is not None:
+        if (skills_dir := resolve_local_skills_dir(project_dir())) is not None:
             paths.append(skills_dir)
 
         if GLOBAL_SKILLS_DIR.path.is_dir():
@@ -0,0 +1,159 @@
+from __future__ import annotations
+
+from typing import TYPE_CHECKING
+
+from pydantic import BaseModel, ConfigDict, computed_field
+from pydantic.alias_generators import to_camel
+
+if TYPE_CHECKING:
+    from rune.core.agent_loop import AgentLoop
+    from rune.core.config import RuneConfig
+    from rune.core.types import AgentStats
+
+
+class _StatusModel(BaseModel):
+    # camelCase aliases so the snapshot can be returned over ACP as-is.
+    model_config = ConfigDict(alias_generator=to_camel, populate_by_name=True)
+
+
+class ModelStatus(_StatusModel):
+    alias: str
+    name: str
+    provider: str
+    temperature: float
+    reasoning_effort: str | None = None
+    routed_to: str | None = None
+
+
+class ApprovalStatus(_StatusModel):
+    agent: str
+    safety: str
+    auto_approve: bool
+    read_only: bool
+    dry_run: bool
+    write_roots: list[str]
+
+
+class UsageStatus(_StatusModel):
+    steps: int
+    prompt_tokens: int
+    completion_tokens: int
+    total_tokens: int
+    last_turn_tokens: int
+    cached_tokens: int
+    context_tokens: int
+    auto_compact_threshold: int
+    cost: float
+    last_turn_cost: float
+    wall_time: float
+    model_time: float
+    tool_time: float
+    last_turn_wall_time: float
+    last_turn_model_time: float
+    last_turn_tool_time: float
+
+    @classmethod
+    def from_stats(cls, stats: AgentStats, config: RuneConfig) -> UsageStatus:
+        return cls(
+            steps=stats.steps,
+            prompt_tokens=stats.session_prompt_tokens,
+            completion_tokens=stats.session_completion_tokens,
+            total_tokens=stats.session_total_llm_tokens,
+            last_turn_tokens=stats.last_turn_total_tokens,
+            cached_tokens=stats.session_cached_tokens,
+            context_tokens=stats.context_tokens,
+            auto_compact_threshold=config.auto_compact_threshold,
+            cost=stats.session_cost,
+            last_turn_cost=stats.last_turn_cost,
+            wall_time=stats.session_wall_time,
+            model_time=stats.session_model_time,
+            tool_time=stats.session_tool_time,
+            last_turn_wall_time=stats.last_turn_wall_time,
+            last_turn_model_time=stats.last_turn_model_time,
+            last_turn_tool_time=stats.last_turn_tool_time,
+        )
+
+
+class ContextUsageStatus(_StatusModel):
+    """Estimated tokens of what the next request sends, by category."""
+
+    instructions: int
+    history: int
+    tool_outputs: int
+    # What the provider reported for the last request, when it did.
+    reported_tokens: int
+    context_window: int | None
+    warning_threshold: float
+
+    @computed_field
+    @property
+    def total(self) -> int:
+        return self.instructions + self.history + self.tool_outputs
+
+    @computed_field
+    @property
+    def utilization(self) -> float | None:
+        if not self.context_window:
+            return None
+        return max(self.total, self.reported_tokens) / self.context_window
+
+
+class StatusSnapshot(_StatusModel):
+    """Everything `/status` shows, gathered in one place."""
+
+    session_id: str
+    cwd: str
+    workspace_roots: dict[str, str]
+    model: ModelStatus
+    approvals: ApprovalStatus
+    mcp_servers: dict[str, str]
+    usage: UsageStatus
+    rate_limited_at: str | None
+    features: dict[str, bool]
+    response_language: str
+
+    @classmethod
+    def from_agent_loop(cls, agent_loop: AgentLoop) -> StatusSnapshot:
+        config = agent_loop.config
+        model = config.get_active_model()
+        stats = agent_loop.stats
+        profile = agent_loop.agent_profile
+        return cls(
+            session_id=agent_loop.session_id,
+            cwd=str(agent_loop.workspace.active.path),
+            workspace_roots={
+                root.name: str(root.path) for root in agent_loop.workspace.roots
+            },
+            model=ModelStatus(
+                alias=model.alias,
+                name=model.name,
+                provider=model.provider,
+                temperature=model.temperature,
+                reasoning_effort=model.reasoning_effort,
+                routed_to=agent_loop.last_routed_model,
+            ),
+            approvals=ApprovalStatus(
+                agent=profile.name,
+                safety=profile.safety,
+                auto_approve=config.auto_approve,
+                read_only=agent_loop.read_only,
+                dry_run=agent_loop.dry_run,
+                write_roots=[str(root) for root in agent_loop.write_roots.roots],
+            ),
+            mcp_servers={
+                srv.name: agent_loop.tool_manager.mcp_server_status.get(
+                    srv.name, "not started"
+                )
+                for srv in config.mcp_servers
+            },
+            usage=UsageStatus.from_stats(stats, config),
+            rate_limited_at=agent_loop.rate_limited_at,
+            features={
+                "streaming": agent_loop.enable_streaming,
+                "critic": config.critic.enabled,
+                "router": config.router.enabled,
+                "moderation": config.moderation.enabled,
+                "sessionLogging": config.session_logging.enabled,
+            },
+            response_language=config.response_language,
+        )
@@ -3,16 +3,20 @@ from __future__ import annotations
 from collections.abc import Generator
 import fnmatch
 import html
-import os
 from pathlib import Path
 import subprocess
 import sys
 import time
 from typing import TYPE_CHECKING
 
+from rune.core.environment import collect_environment
+from rune.core.ignore_files import IGNORE_FILES, read_ignore_patterns
+from rune.core.memory import MemoryStore, MemoryStoreError, render_memories
+from rune.core.project_docs import load_project_docs, render_project_docs
 from rune.core.prompts import UtilityPrompt
-from rune.core.trusted_folders import TRUSTABLE_FILENAMES, trusted_folders_manager
-from rune.core.utils import is_dangerous_directory, is_windows
+from rune.core.tools.builtins.bash import BashToolConfig, get_default_shell
+from rune.core.types import LLMMessage, Role
+from rune.core.utils import is_dangerous_directory, is_windows, logger
 
 if TYPE_CHECKING:
     from rune.core.agents import AgentManager
@@ -21,18 +25,6 @@ if TYPE_CHECKING:
     from rune.core.tools.manager import ToolManager
 
 
-def _load_project_doc(workdir: Path, max_bytes: int) -> str:
-    if not trusted_folders_manager.is_trusted(workdir):
-        return ""
-    for name in TRUSTABLE_FILENAMES:
-        path = workdir / name
-        try:
-            return path.read_text("utf-8", errors="ignore")[:max_bytes]
-        except (FileNotFoundError, OSError):
-            continue
-    return ""
-
-
 class ProjectContextProvider:
     def __init__(
         self, config: ProjectContextConfig, root_path: str | Path = "."
@@ -44,18 +36,9 @@ class ProjectContextProvider:
         self._start_time = 0.0
 
     def _load_gitignore_patterns(self) -> list[str]:
-        gitignore_path = self.root_path / ".gitignore"
         patterns = []
-
-        if gitignore_path.exists():
-            try:
-                patterns.extend(
-                    line.strip()
-                    for line in gitignore_path.read_text(encoding="utf-8").splitlines()
-                    if line.strip() and not line.startswith("#")
-                )
-            except Exception as e:
-                print(f"Warning: Could not read .gitignore: {e}", file=sys.stderr)
+        for filename in IGNORE_FILES:
+            patterns.extend(read_ignore_patterns(self.root_path / filename))
 
         default_patterns = [
             ".git",
@@ -325,19 +308,15 @@ def _get_platform_name() -> str:
     return platform_names.get(sys.platform, "Unix-like")
 
 
-def _get_default_shell() -> str:
-    """Get the default shell used by asyncio.create_subprocess_shell.
+def _get_command_shell(tool_manager: ToolManager) -> str:
+    if "bash" in tool_manager.available_tools:
+        config = tool_manager.get_tool_config("bash")
+        if isinstance(config, BashToolConfig) and config.shell_profile.program:
+            return config.shell_profile.program
+    return get_default_shell()
 
-    On Unix, uses $SHELL env var and default to sh.
-    On Windows, this is COMSPEC or cmd.exe.
-    """
-    if is_windows():
-        return os.environ.get("COMSPEC", "cmd.exe")
-    return os.environ.get("SHELL", "sh")
 
-
-def _get_os_system_prompt() -> str:
-    shell = _get_default_shell()
+def _get_os_system_prompt(shell: str) -> str:
     platform_name = _get_platform_name()
     prompt = f"The operating system is {platform_name} with shell `{shell}`"
 
@@ -371,6 +350,13 @@ def _add_commit_signature() -> str:
     )
 
 
+def _get_response_language_prompt(language: str) -> str:
+    return (
+        f"Always respond to the user in {language}, whatever language they write in. "
+        "Keep code, identifiers, file paths, and shell commands unchanged."
+    )
+
+
 def _get_available_skills_section(skill_manager: SkillManager) -> str:
     skills = skill_manager.available_skills
     if not skills:
@@ -412,12 +398,12 @@ def _get_available_subagents_section(agent_manager: AgentManager) -> str:
     return "\n".join(lines)
 
 
-def get_universal_system_prompt(
+def _instruction_sections(
     tool_manager: ToolManager,
     config: RuneConfig,
     skill_manager: SkillManager,
     agent_manager: AgentManager,
-) -> str:
+) -> list[str]:
     sections = [config.system_prompt]
 
     if config.include_commit_signature:
@@ -426,8 +412,13 @@ def get_universal_system_prompt(
     if config.include_model_info:
         sections.append(f"Your model name is: `{config.active_model}`")
 
+    if config.response_language:
+        sections.append(_get_response_language_prompt(config.response_language))
+
     if config.include_prompt_detail:
-        sections.append(_get_os_system_prompt())
+        sections.append(_get_os_system_prompt(_get_command_shell(tool_manager)))
+        if config.include_environment:
+            sections.append(collect_environment().render())
         tool_prompts = []
         for tool_class in tool_manager.available_tools.values():
             if prompt := tool_class.get_tool_prompt():
@@ -443,24 +434,72 @@ def get_universal_system_prompt(
         if subagents_section:
             sections.append(subagents_section)
 
+    return sections
+
+
+def _context_sections(config: RuneConfig, cwd: Path) -> list[str]:
+    sections: list[str] = []
     if config.include_project_context:
-        is_dangerous, reason = is_dangerous_directory()
+        is_dangerous, reason = is_dangerous_directory(cwd)
         if is_dangerous:
             template = UtilityPrompt.DANGEROUS_DIRECTORY.read()
-            context = template.format(
-                reason=reason.lower(), abs_path=Path(".").resolve()
-            )
+            context = template.format(reason=reason.lower(), abs_path=cwd.resolve())
         else:
             context = ProjectContextProvider(
-                config=config.project_context, root_path=Path.cwd()
+                config=config.project_context, root_path=cwd
             ).get_full_context()
 
         sections.append(context)
 
-        project_doc = _load_project_doc(
-            Path.cwd(), config.project_context.max_doc_bytes
-        )
-        if project_doc.strip():
-            sections.append(project_doc)
+        if docs := load_project_docs(
+            cwd,
+            config.project_context.max_doc_bytes,
+            config.project_context.max_total_doc_bytes,
+        ):
+            sections.append(render_project_docs(docs, cwd))
 
-    return "\n\n".join(sections)
+    if config.memory.inject_limit:
+        try:
+            memories = MemoryStore(project=cwd).recent(config.memory.inject_limit)
+        except MemoryStoreError as e:
+            logger.warning("Could not load memories: %s", e)
+            memories = []
+        if memories:
+            sections.append(render_memories(memories))
+
+    return sections
+
+
+def get_system_message(
+    tool_manager: ToolManager,
+    config: RuneConfig,
+    skill_manager: SkillManager,
+    agent_manager: AgentManager,
+    cwd: Path,
+) -> LLMMessage:
+    """The system prompt, with cache points after the base instructions and
+    after the project context of `cwd`, which stay the same for the turns of a
+    thread.
+    """
+    instructions = "\n\n".join(
+        _instruction_sections(tool_manager, config, skill_manager, agent_manager)
+    )
+    cache_breaks = [len(instructions)]
+    content = instructions
+    if context := "\n\n".join(_context_sections(config, cwd)):
+        content = f"{instructions}\n\n{context}"
+        cache_breaks.append(len(content))
+    return LLMMessage(role=Role.system, content=content, cache_breaks=cache_breaks)
+
+
+def get_universal_system_prompt(
+    tool_manager: ToolManager,
+    config: RuneConfig,
+    skill_manager: SkillManager,
+    agent_manager: AgentManager,
+    cwd: Path | None = None,
+) -> str:
+    message = get_system_message(
+        tool_manager, config, skill_manager, agent_manager, cwd or Path.cwd()
+    )
+    return message.content or ""
@@ -0,0 +1,125 @@
+from __future__ import annotations
+
+from functools import lru_cache
+from logging import getLogger
+import math
+from pathlib import Path
+from typing import TYPE_CHECKING, Any, Protocol
+
+if TYPE_CHECKING:
+    from rune.core.types import LLMMessage
+
+logger = getLogger("rune")
+
+# Rough average for prose and code. Counting bytes rather than characters
+# keeps the estimate on the high side for non-Latin scripts.
+BYTES_PER_TOKEN = 4
+
+
+class TokenizerError(Exception):
+    pass
+
+
+class Tokenizer(Protocol):
+    name: str
+
+    def count(self, text: str) -> int: ...
+
+
+class ByteHeuristicTokenizer:
+    name = "bytes"
+
+    def count(self, text: str) -> int:
+        return math.ceil(len(text.encode("utf-8")) / BYTES_PER_TOKEN)
+
+
+class TiktokenTokenizer:
+    """OpenAI encodings, by encoding name (`o200k_base`) or model name."""
+
+    def __init__(self, encoding: str) -> None:
+        try:
+            import tiktoken
+        except ImportError as e:
+            raise TokenizerError(
+                'tiktoken is not installed; run `pip install "rune-cli[tokenizers]"`'
+            ) from e
+        try:
+            self._encoding = tiktoken.get_encoding(encoding)
+        except ValueError:
+            try:
+                self._encoding = tiktoken.encoding_for_model(encoding)
+            except KeyError as e:
+                raise TokenizerError(f"Unknown tiktoken encoding '{encoding}'") from e
+        self.name = f"tiktoken:{encoding}"
+
+    def count(self, text: str) -> int:
+        return len(self._encoding.encode(text, disallowed_special=()))
+
+
+class HuggingFaceTokenizer:
+    """A `tokenizer.json`, from a local path or a Hugging Face Hub repository."""
+
+    def __init__(self, source: str) -> None:
+        try:
+            from tokenizers import Tokenizer as HFTokenizer
+        except ImportError as e:
+            raise TokenizerError(
+                'tokenizers is not installed; run `pip install "rune-cli[tokenizers]"`'
+            ) from e
+        path = Path(source).expanduser()
+        try:
+            if path.is_dir():
+                path = path / "tokenizer.json"
+            self._tokenizer: Any = (
+                HFTokenizer.from_file(str(path))
+                if path.is_file()
+                else HFTokenizer.from_pretrained(source)
+            )
+        except Exception as e:
+            raise TokenizerError(f"Could not load tokenizer '{source}': {e}") from e
+        self.name = f"hf:{source}"
+
+    def count(self, text: str) -> int:
+        return len(self._tokenizer.encode(text, add_special_tokens=False).ids)
+
+
+TOKENIZER_BACKENDS: dict[str, type[TiktokenTokenizer] | type[HuggingFaceTokenizer]] = {
+    "tiktoken": TiktokenTokenizer,
+    "hf": HuggingFaceTokenizer,
+}
+
+
+@lru_cache(maxsize=16)
+def load_tokenizer(spec: str | None) -> Tokenizer:
+    """The tokenizer named by a model's `tokenizer` setting.
+
+    `spec` is `tiktoken:<encoding or model>` or `hf:<repo id or path>`. Unset,
+    `bytes`, or a tokenizer that can't be loaded fall back to the byte heuristic,
+    so counting never fails a turn.
+    """
+    if not spec or spec == ByteHeuristicTokenizer.name:
+        return ByteHeuristicTokenizer()
+    kind, _, source = spec.partition(":")
+    try:
+        if (backend := TOKENIZER_BACKENDS.get(kind)) is None or not source:
+            kinds = ", ".join(TOKENIZER_BACKENDS)
+            raise TokenizerError(
+                f"Expected '<kind>:<name>' with kind one of {kinds} or 'bytes'"
+            )
+        return backend(source)
+    except TokenizerError as e:
+        logger.warning("Using estimated token counts instead of '%s': %s", spec, e)
+        return ByteHeuristicTokenizer()
+
+
+def count_message_tokens(tokenizer: Tokenizer, messages: list[LLMMessage]) -> int:
+    """Tokens of the text in ``messages``, without per-message framing."""
+    return sum(
+        tokenizer.count(message.content or "")
+        + tokenizer.count(message.reasoning_content or "")
+        + sum(
+            tokenizer.count(call.function.arguments or "")
+            for call in message.tool_calls or []
+        )
+        for message in messages
+    )
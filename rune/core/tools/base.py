@@ -1,8 +1,10 @@
 from __future__ import annotations
 
 from abc import ABC, abstractmethod
+import asyncio
 from collections.abc import AsyncGenerator
 from dataclasses import dataclass, field
+from datetime import datetime
 from enum import StrEnum, auto
 import functools
 import inspect
@@ -22,11 +24,14 @@ from typing import (
 )
 
 from pydantic import BaseModel, ConfigDict, Field, ValidationError
+from pydantic.alias_generators import to_camel
 
-from rune.core.types import ToolStreamEvent
+from rune.core.types import ImageContent, ToolStreamEvent
 
 if TYPE_CHECKING:
     from rune.core.agents.manager import AgentManager
+    from rune.core.config import NetworkConfig
+    from rune.core.tools.write_roots import WriteRootGrants
     from rune.core.types import ApprovalCallback, UserInputCallback
 
 ARGS_COUNT = 4
@@ -40,6 +45,61 @@ class InvokeContext:
     approval_callback: ApprovalCallback | None = field(default=None)
     agent_manager: AgentManager | None = field(default=None)
     user_input_callback: UserInputCallback | None = field(default=None)
+    write_roots: WriteRootGrants | None = field(default=None)
+    # Set when the user asks to stop the running command but keep the turn
+    # going; tools that run processes watch it and return what they have.
+    stop_requested: asyncio.Event | None = field(default=None)
+    # The thread's working directory. Threads sharing a process can work in
+    # different ones, so tools start processes here rather than in the
+    # process's own; None means the process's.
+    cwd: Path | None = field(default=None)
+    # The `[network]` settings, for tools that make HTTP requests.
+    network: NetworkConfig | None = field(default=None)
+    # Characters of the result `[tools.policy]` lets through to the model, for
+    # tools that return long content in parts.
+    output_limit: int | None = field(default=None)
+
+
+def working_dir(ctx: InvokeContext | None) -> Path:
+    return ctx.cwd if ctx is not None and ctx.cwd is not None else Path.cwd()
+
+
+def absolute_path(raw: str, cwd: Path | None = None) -> Path:
+    """Expand `~` in `raw` and anchor a relative path at `cwd`."""
+    path = Path(raw).expanduser()
+    return path if path.is_absolute() else (cwd or Path.cwd()) / path
+
+
+class StopRequests:
+    """The events that stop the commands of the tool calls in progress, by tool
+    call id, handed to the tools as `InvokeContext.stop_requested`.
+    """
+
+    def __init__(self) -> None:
+        self._events: dict[str, asyncio.Event] = {}
+
+    def open(self, tool_call_id: str) -> asyncio.Event:
+        self._events[tool_call_id] = event = asyncio.Event()
+        return event
+
+    def close(self, tool_call_id: str) -> None:
+        self._events.pop(tool_call_id, None)
+
+    def stop(self, tool_call_id: str | None = None) -> bool:
+        """Stop the command a tool call is running, or every running one,
+        without ending the turn.
+
+        The tool returns the output it has so far and the agent carries on.
+        Returns whether a matching tool call was running.
+        """
+        if tool_call_id is None:
+            events = list(self._events.values())
+        else:
+            event = self._events.get(tool_call_id)
+            events = [event] if event else []
+        for event in events:
+            event.set()
+        return bool(events)
 
 
 class ToolError(Exception):
@@ -95,6 +155,30 @@ class BaseToolConfig(BaseModel):
     denylist: list[str] = Field(default_factory=list)
 
 
+class ImageResult(BaseModel):
+    """Base for tool results that carry images, such as screenshots.
+
+    The images are left out of the text the model reads and sent alongside it
+    to models whose `input_modalities` include images.
+    """
+
+    images: list[ImageContent] = Field(default_factory=list, exclude=True)
+
+
+class BackgroundProcess(BaseModel):
+    """A process a tool keeps running between calls, such as a Python kernel
+    or a browser.
+    """
+
+    model_config = ConfigDict(alias_generator=to_camel, populate_by_name=True)
+
+    id: str
+    tool_name: str
+    description: str
+    pid: int | None = None
+    started_at: datetime | None = None
+
+
 class BaseToolState(BaseModel):
     model_config = ConfigDict(
         extra="forbid", validate_default=True, arbitrary_types_allowed=True
@@ -316,6 +400,15 @@ class BaseTool[
         snake_case = re.sub(r"(?<!^)(?=[A-Z])", "_", name).lower()
         return snake_case
 
+    @classmethod
+    def get_config_name(cls) -> str:
+        """The `[tools.<name>]` table holding the tool's settings.
+
+        Settings under the tool's own name, such as a saved permission, apply
+        on top of that table.
+        """
+        return cls.get_name()
+
     @classmethod
     def create_config_with_permission(
         cls, permission: ToolPermission
@@ -323,9 +416,14 @@ class BaseTool[
         config_class = cls._get_tool_config_class()
         return config_class(permission=permission)
 
-    def check_allowlist_denylist(self, args: ToolArgs) -> ToolPermission | None:
+    def check_allowlist_denylist(
+        self, args: ToolArgs, cwd: Path | None = None
+    ) -> ToolPermission | None:
         """Check if args match allowlist/denylist patterns.
 
+        Relative paths in `args` are relative to `cwd`, the thread's working
+        directory, or to the process's when it is None.
+
         Returns:
             ToolPermission.ALWAYS if allowlisted
             ToolPermission.NEVER if denylisted
@@ -334,3 +432,25 @@ class BaseTool[
         Base implementation returns None. Override in subclasses for specific logic.
         """
         return None
+
+    def get_write_target(self, args: ToolArgs, cwd: Path | None = None) -> Path | None:
+        """Return the file this call writes to, if it must stay within write roots.
+
+        Writes outside the workspace need an explicit per-session grant.
+        """
+        return None
+
+    def list_background_processes(self) -> list[BackgroundProcess]:
+        """Processes this tool keeps running between calls.
+
+        They last until the session ends unless stopped through
+        `kill_background_process`.
+        """
+        return []
+
+    async def kill_background_process(self, process_id: str) -> bool:
+        """Stop one of the processes `list_background_processes` returned.
+
+        Returns False if the tool has no process with that id.
+        """
+        return False
@@ -4,7 +4,7 @@ import asyncio
 from collections.abc import AsyncGenerator
 from functools import lru_cache
 import os
-import signal
+from pathlib import Path
 import sys
 from typing import ClassVar, Literal, final
 
@@ -19,18 +19,35 @@ from rune.core.tools.base import (
     InvokeContext,
     ToolError,
     ToolPermission,
+    working_dir,
 )
+from rune.core.tools.exec_env import ExecEnvPolicy
+from rune.core.tools.powershell import extract_powershell_commands, is_powershell
+from rune.core.tools.process_output import ProcessOutput
+from rune.core.tools.shell_profile import ShellProfile, select_shell_profile
+from rune.core.tools.subprocesses import get_base_env, kill_process_tree
 from rune.core.tools.ui import ToolCallDisplay, ToolResultDisplay, ToolUIData
-from rune.core.types import ToolCallEvent, ToolResultEvent, ToolStreamEvent
+from rune.core.types import (
+    ToolCallEvent,
+    ToolOutputEvent,
+    ToolResultEvent,
+    ToolStreamEvent,
+)
 from rune.core.utils import is_windows
 
+# Seconds to wait for the last output of a stopped command.
+_STOP_GRACE = 1.0
+
 
 @lru_cache(maxsize=1)
 def _get_parser() -> Parser:
     return Parser(Language(tsbash.language()))
 
 
-def _extract_commands(command: str) -> list[str]:
+def extract_commands(command: str, *, powershell: bool = False) -> list[str]:
+    if powershell:
+        return extract_powershell_commands(command)
+
     parser = _get_parser()
     tree = parser.parse(command.encode("utf-8"))
 
@@ -71,61 +88,29 @@ def _get_shell_executable() -> str | None:
     return os.environ.get("SHELL")
 
 
-def _get_base_env() -> dict[str, str]:
-    base_env = {
-        **os.environ,
-        "CI": "true",
-        "NONINTERACTIVE": "1",
-        "NO_TTY": "1",
-        "NO_COLOR": "1",
-    }
-
+def get_default_shell() -> str:
+    """The shell asyncio.create_subprocess_shell runs commands with."""
     if is_windows():
-        base_env["GIT_PAGER"] = "more"
-        base_env["PAGER"] = "more"
-    else:
-        base_env["TERM"] = "dumb"
-        base_env["DEBIAN_FRONTEND"] = "noninteractive"
-        base_env["GIT_PAGER"] = "cat"
-        base_env["PAGER"] = "cat"
-        base_env["LESS"] = "-FX"
-        base_env["LC_ALL"] = "en_US.UTF-8"
-
-    return base_env
-
-
-async def _kill_process_tree(proc: asyncio.subprocess.Process) -> None:
-    if proc.returncode is not None:
-        return
-
-    try:
-        if sys.platform == "win32":
-            try:
-                subprocess_proc = await asyncio.create_subprocess_exec(
-                    "taskkill",
-                    "/F",
-                    "/T",
-                    "/PID",
-                    str(proc.pid),
-                    stdout=asyncio.subprocess.DEVNULL,
-                    stderr=asyncio.subprocess.DEVNULL,
-                )
-                await subprocess_proc.wait()
-            except (FileNotFoundError, OSError):
-                proc.terminate()
-        else:
-            os.killpg(os.getpgid(proc.pid), signal.SIGKILL)
-
-        await proc.wait()
-    except (ProcessLookupError, PermissionError, OSError):
-        pass
+        return os.environ.get("COMSPEC", "cmd.exe")
+    return os.environ.get("SHELL", "sh")
 
 
 def _get_default_allowlist() -> list[str]:
     common = ["echo", "find", "git diff", "git log", "git status", "tree", "whoami"]
 
     if is_windows():
-        return common + ["dir", "findstr", "more", "type", "ver", "where"]
+        return common + [
+            "dir",
+            "findstr",
+            "more",
+            "type",
+            "ver",
+            "where",
+            "Get-ChildItem",
+            "Get-Content",
+            "Get-Location",
+            "Select-String",
+        ]
     else:
         return common + [
             "cat",
@@ -179,6 +164,10 @@ class BashToolConfig(BaseToolConfig):
     default_timeout: int = Field(
         default=300, description="Default timeout for commands in seconds."
     )
+    stream_interval: float = Field(
+        default=0.1,
+        description="Seconds between two updates of a running command's output.",
+    )
     allowlist: list[str] = Field(
         default_factory=_get_default_allowlist,
         description="Command prefixes that are automatically allowed",
@@ -191,6 +180,26 @@ class BashToolConfig(BaseToolConfig):
         default_factory=_get_default_denylist_standalone,
         description="Commands that are denied only when run without arguments",
     )
+    env: ExecEnvPolicy = Field(
+        default_factory=ExecEnvPolicy,
+        description="Environment variables passed to commands",
+    )
+    shell: ShellProfile = Field(
+        default_factory=ShellProfile, description="Shell that runs commands"
+    )
+    platform_shells: dict[str, ShellProfile] = Field(
+        default_factory=dict,
+        description="Per-platform shells keyed by sys.platform "
+        "('linux', 'darwin', 'win32'), used instead of shell",
+    )
+
+    @property
+    def shell_profile(self) -> ShellProfile:
+        return select_shell_profile(self.shell, self.platform_shells)
+
+    @property
+    def uses_powershell(self) -> bool:
+        return is_powershell(self.shell_profile.program or get_default_shell())
 
 
 class BashArgs(BaseModel):
@@ -205,6 +214,9 @@ class BashResult(BaseModel):
     stdout: str
     stderr: str
     returncode: int
+    stopped: bool = Field(
+        default=False, description="The user stopped the command before it ended."
+    )
 
 
 class Bash(
@@ -227,22 +239,37 @@ class Bash(
                 success=False, message=event.error or event.skip_reason or "No result"
             )
 
+        if event.result.stopped:
+            return ToolResultDisplay(
+                success=True,
+                message=f"Ran {event.result.command}",
+                warnings=["Stopped before it finished"],
+            )
         return ToolResultDisplay(success=True, message=f"Ran {event.result.command}")
 
     @classmethod
     def get_status_text(cls) -> str:
         return "Running command"
 
-    def check_allowlist_denylist(self, args: BashArgs) -> ToolPermission | None:
-        if is_windows():
+    def check_allowlist_denylist(
+        self, args: BashArgs, cwd: Path | None = None
+    ) -> ToolPermission | None:
+        powershell = self.config.uses_powershell
+        if is_windows() and not powershell:
             return None
 
-        command_parts = _extract_commands(args.command)
+        command_parts = extract_commands(args.command, powershell=powershell)
         if not command_parts:
             return None
 
+        def matches(command: str, pattern: str) -> bool:
+            # PowerShell command names are case-insensitive.
+            if powershell:
+                return command.lower().startswith(pattern.lower())
+            return command.startswith(pattern)
+
         def is_denylisted(command: str) -> bool:
-            return any(command.startswith(pattern) for pattern in self.config.denylist)
+            return any(matches(command, pattern) for pattern in self.config.denylist)
 
         def is_standalone_denylisted(command: str) -> bool:
             parts = command.split()
@@ -262,7 +289,7 @@ class Bash(
             return False
 
         def is_allowlisted(command: str) -> bool:
-            return any(command.startswith(pattern) for pattern in self.config.allowlist)
+            return any(matches(command, pattern) for pattern in self.config.allowlist)
 
         for part in command_parts:
             if is_denylisted(part):
@@ -281,9 +308,15 @@ class Bash(
 
     @final
     def _build_result(
-        self, *, command: str, stdout: str, stderr: str, returncode: int
+        self,
+        *,
+        command: str,
+        stdout: str,
+        stderr: str,
+        returncode: int,
+        stopped: bool = False,
     ) -> BashResult:
-        if returncode != 0:
+        if returncode != 0 and not stopped:
             error_msg = f"Command failed: {command!r}\n"
             error_msg += f"Return code: {returncode}"
             if stderr:
@@ -293,14 +326,59 @@ class Bash(
             raise ToolError(error_msg.strip())
 
         return BashResult(
-            command=command, stdout=stdout, stderr=stderr, returncode=returncode
+            command=command,
+            stdout=stdout,
+            stderr=stderr,
+            returncode=returncode,
+            stopped=stopped,
         )
 
+    async def _spawn(
+        self, command: str, cwd: Path, kwargs: dict[Literal["start_new_session"], bool]
+    ) -> asyncio.subprocess.Process:
+        env = get_base_env(self.config.env)
+        profile = self.config.shell_profile
+        if profile.is_default:
+            return await asyncio.create_subprocess_shell(
+                command,
+                stdout=asyncio.subprocess.PIPE,
+                stderr=asyncio.subprocess.PIPE,
+                stdin=asyncio.subprocess.DEVNULL,
+                env=env,
+                cwd=cwd,
+                executable=_get_shell_executable(),
+                **kwargs,
+            )
+
+        return await asyncio.create_subprocess_exec(
+            *profile.argv(command, get_default_shell()),
+            stdout=asyncio.subprocess.PIPE,
+            stderr=asyncio.subprocess.PIPE,
+            stdin=asyncio.subprocess.DEVNULL,
+            env=env,
+            cwd=cwd,
+            **kwargs,
+        )
+
+    def _output_events(
+        self, output: ProcessOutput, tool_call_id: str
+    ) -> list[ToolOutputEvent]:
+        return [
+            ToolOutputEvent(
+                tool_name=self.get_name(),
+                tool_call_id=tool_call_id,
+                message=text,
+                stream=stream,
+            )
+            for stream, text in output.take()
+        ]
+
     async def run(
         self, args: BashArgs, ctx: InvokeContext | None = None
     ) -> AsyncGenerator[ToolStreamEvent | BashResult, None]:
         timeout = args.timeout or self.config.default_timeout
         max_bytes = self.config.max_output_bytes
+        tool_call_id = ctx.tool_call_id if ctx else ""
 
         proc = None
         try:
@@ -309,44 +387,42 @@ class Bash(
                 {} if is_windows() else {"start_new_session": True}
             )
 
-            proc = await asyncio.create_subprocess_shell(
-                args.command,
-                stdout=asyncio.subprocess.PIPE,
-                stderr=asyncio.subprocess.PIPE,
-                stdin=asyncio.subprocess.DEVNULL,
-                env=_get_base_env(),
-                executable=_get_shell_executable(),
-                **kwargs,
-            )
-
-            try:
-                stdout_bytes, stderr_bytes = await asyncio.wait_for(
-                    proc.communicate(), timeout=timeout
+            proc = await self._spawn(args.command, working_dir(ctx), kwargs)
+
+            loop = asyncio.get_running_loop()
+            deadline = loop.time() + timeout
+            async with ProcessOutput(
+                proc,
+                max_chars=max_bytes,
+                encoding=_get_subprocess_encoding(),
+                stop=ctx.stop_requested if ctx else None,
+            ) as output:
+                # Output is sent at most once per interval, however much the
+                # command writes in between.
+                while not output.finished and not output.stop_requested:
+                    if loop.time() >= deadline:
+                        await kill_process_tree(proc)
+                        raise self._build_timeout_error(args.command, timeout)
+                    await output.wait(
+                        min(self.config.stream_interval, deadline - loop.time())
+                    )
+                    for event in self._output_events(output, tool_call_id):
+                        yield event
+
+                stopped = not output.finished
+                if stopped:
+                    await kill_process_tree(proc)
+                    await output.wait_finished(_STOP_GRACE)
+                    for event in self._output_events(output, tool_call_id):
+                        yield event
+
+                yield self._build_result(
+                    command=args.command,
+                    stdout=output.stdout,
+                    stderr=output.stderr,
+                    returncode=proc.returncode or 0,
+                    stopped=stopped,
                 )
-            except TimeoutError:
-                await _kill_process_tree(proc)
-                raise self._build_timeout_error(args.command, timeout)
-
-            encoding = _get_subprocess_encoding()
-            stdout = (
-                stdout_bytes.decode(encoding, errors="replace")[:max_bytes]
-                if stdout_bytes
-                else ""
-            )
-            stderr = (
-                stderr_bytes.decode(encoding, errors="replace")[:max_bytes]
-                if stderr_bytes
-                else ""
-            )
-
-            returncode = proc.returncode or 0
-
-            yield self._build_result(
-                command=args.command,
-                stdout=stdout,
-                stderr=stderr,
-                returncode=returncode,
-            )
 
         except (ToolError, asyncio.CancelledError):
             raise
@@ -354,4 +430,4 @@ class Bash(
             raise ToolError(f"Error running command {args.command!r}: {exc}") from exc
         finally:
             if proc is not None:
-                await _kill_process_tree(proc)
+                await kill_process_tree(proc)
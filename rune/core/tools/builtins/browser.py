@@ -0,0 +1,340 @@
+from __future__ import annotations
+
+import asyncio
+from collections.abc import AsyncGenerator
+from enum import StrEnum, auto
+import fnmatch
+import json
+from pathlib import Path
+from typing import TYPE_CHECKING, ClassVar
+from urllib.parse import urlsplit
+
+from pydantic import BaseModel, Field
+
+from rune.core.tools.base import (
+    BackgroundProcess,
+    BaseTool,
+    BaseToolConfig,
+    BaseToolState,
+    ImageResult,
+    InvokeContext,
+    ToolError,
+    ToolPermission,
+)
+from rune.core.tools.builtins.fetch_url import public_address
+from rune.core.tools.cdp import CDPError, Chromium, find_chromium
+from rune.core.tools.html_markdown import html_to_markdown
+from rune.core.tools.ui import ToolCallDisplay, ToolResultDisplay, ToolUIData
+from rune.core.types import ImageContent, ToolStreamEvent
+
+if TYPE_CHECKING:
+    from rune.core.types import ToolCallEvent, ToolResultEvent
+
+# Time given to the page to react to a click or typed text before reporting.
+SETTLE_SECONDS = 0.5
+_SCHEMES = {"http", "https"}
+# The element's center, after scrolling it into view, or null if no element
+# matches.
+_LOCATE = """(() => {{
+  const el = document.querySelector({selector});
+  if (!el) return null;
+  el.scrollIntoView({{block: "center", inline: "center"}});
+  const r = el.getBoundingClientRect();
+  return {{x: r.x + r.width / 2, y: r.y + r.height / 2, visible: r.width > 0}};
+}})()"""
+_PAGE = "({url: location.href, title: document.title})"
+_FOCUS = """(() => {{
+  const el = document.querySelector({selector});
+  if (!el) return false;
+  el.focus();
+  return true;
+}})()"""
+_OUTER_HTML = """(() => {{
+  const el = {selector} === null
+    ? document.documentElement
+    : document.querySelector({selector});
+  return el ? el.outerHTML : null;
+}})()"""
+
+
+class BrowserAction(StrEnum):
+    NAVIGATE = auto()
+    SCREENSHOT = auto()
+    READ = auto()
+    CLICK = auto()
+    TYPE = auto()
+
+
+class BrowserToolConfig(BaseToolConfig):
+    permission: ToolPermission = ToolPermission.ASK
+
+    enabled: bool = Field(
+        default=False, description="Offer the tool; it needs Chromium or Chrome."
+    )
+    executable: str | None = Field(
+        default=None,
+        description="Browser to run. Defaults to the first Chromium, Chrome or "
+        "Edge found.",
+    )
+    headless: bool = True
+    viewport_width: int = 1280
+    viewport_height: int = 800
+    timeout: float = Field(
+        default=30.0, description="Seconds to wait for page loads and commands."
+    )
+    max_content_chars: int = Field(
+        default=40_000, description="Characters of page content returned by read."
+    )
+    allow_private_addresses: bool = Field(
+        default=False,
+        description="Allow pages on loopback, private or link-local addresses, "
+        "such as a local dev server.",
+    )
+
+
+class BrowserState(BaseToolState):
+    browser: Chromium | None = None
+
+
+class BrowserArgs(BaseModel):
+    action: BrowserAction
+    url: str | None = Field(default=None, description="Page to open, for navigate.")
+    selector: str | None = Field(
+        default=None,
+        description="CSS selector of the element to click, type into or read. "
+        "read takes the whole page without one.",
+    )
+    text: str | None = Field(default=None, description="Text to type, for type.")
+    html: bool = Field(
+        default=False,
+        description="For read: return the element's HTML instead of its text "
+        "as markdown.",
+    )
+
+
+class BrowserResult(ImageResult):
+    action: BrowserAction
+    url: str
+    title: str
+    content: str = ""
+    console: list[str] = Field(
+        default_factory=list,
+        description="Console messages and uncaught exceptions since the last "
+        "browser call.",
+    )
+
+
+class Browser(
+    BaseTool[BrowserArgs, BrowserResult, BrowserToolConfig, BrowserState],
+    ToolUIData[BrowserArgs, BrowserResult],
+):
+    description: ClassVar[str] = (
+        "Drive a headless browser: open a page, take a screenshot, read the page "
+        "or an element, click an element and type into it. The page stays open "
+        "between calls, and each result lists new console messages."
+    )
+
+    async def run(
+        self, args: BrowserArgs, ctx: InvokeContext | None = None
+    ) -> AsyncGenerator[ToolStreamEvent | BrowserResult, None]:
+        try:
+            browser = await self._browser()
+            content, images = await self._perform(browser, args)
+            page = await browser.evaluate(_PAGE)
+        except CDPError as e:
+            raise ToolError(str(e)) from e
+        yield BrowserResult(
+            action=args.action,
+            url=page["url"],
+            title=page["title"],
+            content=content,
+            console=browser.take_console(),
+            images=images,
+        )
+
+    async def _browser(self) -> Chromium:
+        if self.state.browser is not None and self.state.browser.alive:
+            return self.state.browser
+        if self.state.browser is not None:
+            await self.state.browser.close()
+        self.state.browser = await Chromium.launch(
+            find_chromium(self.config.executable),
+            headless=self.config.headless,
+            width=self.config.viewport_width,
+            height=self.config.viewport_height,
+            timeout=self.config.timeout,
+        )
+        return self.state.browser
+
+    async def _perform(
+        self, browser: Chromium, args: BrowserArgs
+    ) -> tuple[str, list[ImageContent]]:
+        selector = json.dumps(args.selector)
+        match args.action:
+            case BrowserAction.NAVIGATE:
+                if not args.url:
+                    raise ToolError("navigate needs a url")
+                await self._check_url(args.url)
+                await browser.navigate(args.url)
+                await self._check_landing(browser, args.url)
+                return "", []
+            case BrowserAction.SCREENSHOT:
+                image = ImageContent(data=await browser.screenshot())
+                return "Screenshot of the visible part of the page attached.", [image]
+            case BrowserAction.READ:
+                html = await browser.evaluate(_OUTER_HTML.format(selector=selector))
+                if html is None:
+                    raise ToolError(f"No element matches {args.selector}")
+                if not args.html:
+                    url = await browser.evaluate("location.href")
+                    html = html_to_markdown(html, url).markdown
+                return self._truncate(html), []
+            case BrowserAction.CLICK:
+                if not args.selector:
+                    raise ToolError("click needs a selector")
+                box = await browser.evaluate(_LOCATE.format(selector=selector))
+                if box is None:
+                    raise ToolError(f"No element matches {args.selector}")
+                if not box["visible"]:
+                    raise ToolError(f"{args.selector} is not visible")
+                before = await browser.evaluate("location.href")
+                await browser.click(box["x"], box["y"])
+                await asyncio.sleep(SETTLE_SECONDS)
+                await self._check_landing(browser, before)
+                return f"Clicked {args.selector}", []
+            case BrowserAction.TYPE:
+                if not args.selector or args.text is None:
+                    raise ToolError("type needs a selector and text")
+                if not await browser.evaluate(_FOCUS.format(selector=selector)):
+                    raise ToolError(f"No element matches {args.selector}")
+                before = await browser.evaluate("location.href")
+                await browser.insert_text(args.text)
+                await asyncio.sleep(SETTLE_SECONDS)
+                await self._check_landing(browser, before)
+                return f"Typed into {args.selector}", []
+
+    async def _check_url(self, url: str) -> None:
+        parts = urlsplit(url)
+        if parts.scheme not in _SCHEMES:
+            raise ToolError(f"Only http and https URLs: {url}")
+        if not parts.hostname:
+            raise ToolError(f"No host in URL: {url}")
+        if not self.config.allow_private_addresses:
+            await public_address(parts.hostname, parts.port)
+
+    async def _check_landing(self, browser: Chromium, before: str) -> None:
+        """Holds a page that a redirect, a click or a form led to on another
+        host to the rules of navigate. Nobody was asked about that host, so it
+        must be allowlisted, unless the tool is always allowed.
+        """
+        url = await browser.evaluate("location.href")
+        host = _host(url)
+        # Hostless pages like about:blank load nothing from the network.
+        if not host or host == _host(before):
+            return
+        try:
+            await self._check_url(url)
+            permission = self.check_allowlist_denylist(
+                BrowserArgs(action=BrowserAction.NAVIGATE, url=url)
+            )
+            if permission == ToolPermission.NEVER:
+                raise ToolError(f"The page went on to {host}, which is denylisted")
+            if (
+                permission != ToolPermission.ALWAYS
+                and self.config.permission != ToolPermission.ALWAYS
+            ):
+                raise ToolError(
+                    f"The page went on to {host}, which was not approved; "
+                    "navigate to it to ask"
+                )
+        except ToolError:
+            await browser.navigate("about:blank")
+            raise
+
+    def _truncate(self, content: str) -> str:
+        limit = self.config.max_content_chars
+        if len(content) <= limit:
+            return content
+        return (
+            content[:limit]
+            + f"\n\n[{len(content) - limit} more characters; read a narrower selector]"
+        )
+
+    def check_allowlist_denylist(
+        self, args: BrowserArgs, cwd: Path | None = None
+    ) -> ToolPermission | None:
+        if args.action != BrowserAction.NAVIGATE or not args.url:
+            return None
+        # Host patterns say nothing about URLs without a host.
+        if urlsplit(args.url).scheme not in _SCHEMES or not (host := _host(args.url)):
+            return None
+        for pattern in self.config.denylist:
+            if fnmatch.fnmatch(host, pattern):
+                return ToolPermission.NEVER
+        for pattern in self.config.allowlist:
+            if fnmatch.fnmatch(host, pattern):
+                return ToolPermission.ALWAYS
+        return None
+
+    def list_background_processes(self) -> list[BackgroundProcess]:
+        browser = self.state.browser
+        if browser is None or not browser.alive:
+            return []
+        return [
+            BackgroundProcess(
+                id=self.get_name(),
+                tool_name=self.get_name(),
+                description="Headless Chromium" if self.config.headless else "Chromium",
+                pid=browser.pid,
+                started_at=browser.started_at,
+            )
+        ]
+
+    async def kill_background_process(self, process_id: str) -> bool:
+        if process_id != self.get_name() or self.state.browser is None:
+            return False
+        await self.state.browser.close()
+        self.state.browser = None
+        return True
+
+    @classmethod
+    def get_call_display(cls, event: ToolCallEvent) -> ToolCallDisplay:
+        if not isinstance(event.args, BrowserArgs):
+            return ToolCallDisplay(summary="browser")
+        args = event.args
+        match args.action:
+            case BrowserAction.NAVIGATE:
+                summary = f"Opening {args.url}"
+            case BrowserAction.SCREENSHOT:
+                summary = "Taking a screenshot"
+            case BrowserAction.READ:
+                summary = f"Reading {args.selector or 'the page'}"
+            case BrowserAction.CLICK:
+                summary = f"Clicking {args.selector}"
+            case BrowserAction.TYPE:
+                summary = f"Typing into {args.selector}"
+        return ToolCallDisplay(summary=summary)
+
+    @classmethod
+    def get_result_display(cls, event: ToolResultEvent) -> ToolResultDisplay:
+        if not isinstance(event.result, BrowserResult):
+            return ToolResultDisplay(
+                success=False, message=event.error or event.skip_reason or "No result"
+            )
+        result = event.result
+        errors = [
+            line
+            for line in result.console
+            if line.startswith(("error:", "exception:"))
+        ]
+        return ToolResultDisplay(
+            success=True, message=result.title or result.url, warnings=errors
+        )
+
+    @classmethod
+    def get_status_text(cls) -> str:
+        return "Using the browser"
+
+
+def _host(url: str) -> str:
+    return (urlsplit(url).hostname or "").lower()
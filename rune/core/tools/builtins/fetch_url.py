@@ -0,0 +1,299 @@
+from __future__ import annotations
+
+from collections.abc import AsyncGenerator
+from contextlib import AbstractAsyncContextManager
+import fnmatch
+import ipaddress
+from pathlib import Path
+import socket
+from typing import TYPE_CHECKING, ClassVar, NamedTuple
+from urllib.parse import urljoin, urlsplit
+
+import anyio
+import httpx
+from pydantic import BaseModel, Field
+
+from rune.core.network import proxy_options
+from rune.core.tools.base import (
+    BaseTool,
+    BaseToolConfig,
+    BaseToolState,
+    InvokeContext,
+    ToolError,
+    ToolPermission,
+)
+from rune.core.tools.html_markdown import html_to_markdown
+from rune.core.tools.ui import ToolCallDisplay, ToolResultDisplay, ToolUIData
+from rune.core.types import ToolStreamEvent
+
+if TYPE_CHECKING:
+    from rune.core.config import NetworkConfig
+    from rune.core.types import ToolCallEvent, ToolResultEvent
+
+MAX_REDIRECTS = 5
+# Room for the result's fields besides the URL, title and content.
+_FIELDS_MARGIN = 200
+_TEXT_TYPES = ("text/", "application/json", "application/xml", "application/xhtml")
+
+
+class FetchUrlToolConfig(BaseToolConfig):
+    """Rune has no network allowlist of its own, so the domain limits here
+    are what keeps fetches in bounds.
+    """
+
+    permission: ToolPermission = ToolPermission.ASK
+
+    allowed_domains: list[str] = Field(
+        default_factory=list,
+        description="Only these domains and their subdomains can be fetched, e.g. "
+        "'docs.python.org'. Empty allows any domain.",
+    )
+    denied_domains: list[str] = Field(
+        default_factory=list,
+        description="Domains, with their subdomains, that are never fetched.",
+    )
+    allow_private_addresses: bool = Field(
+        default=False,
+        description="Allow URLs that resolve to loopback, private or link-local "
+        "addresses, such as a local dev server.",
+    )
+    max_download_bytes: int = Field(
+        default=2_000_000, description="Bytes downloaded at most per page."
+    )
+    max_content_chars: int = Field(
+        default=40_000,
+        description="Characters of the page returned per call; the rest is "
+        "reached with start_index. Lowered to fit [tools.policy] max_output_chars.",
+    )
+    timeout: float = Field(default=30.0, description="Timeout per request in seconds.")
+    user_agent: str = "rune-fetch-url"
+
+
+class FetchUrlState(BaseToolState):
+    pass
+
+
+class FetchUrlArgs(BaseModel):
+    url: str = Field(description="http or https URL of the page.")
+    start_index: int = Field(
+        default=0,
+        description="Character of the extracted content to start from; pass the "
+        "next_start_index of an earlier call to read on.",
+    )
+
+
+class FetchUrlResult(BaseModel):
+    url: str = Field(description="The URL the page was served from, after redirects.")
+    status_code: int
+    content_type: str
+    title: str | None = None
+    content: str
+    total_chars: int
+    next_start_index: int | None = Field(
+        default=None, description="Set when more of the page remains."
+    )
+    download_truncated: bool = Field(
+        default=False,
+        description="True if the page was larger than max_download_bytes and "
+        "was cut short.",
+    )
+
+
+class _Page(NamedTuple):
+    url: str
+    response: httpx.Response
+    body: bytes
+    truncated: bool
+
+
+class FetchUrl(
+    BaseTool[FetchUrlArgs, FetchUrlResult, FetchUrlToolConfig, FetchUrlState],
+    ToolUIData[FetchUrlArgs, FetchUrlResult],
+):
+    description: ClassVar[str] = (
+        "Download a web page and return its main content as markdown, without "
+        "navigation, scripts and other boilerplate. Long pages are returned in "
+        "parts: pass next_start_index as start_index to read on."
+    )
+
+    async def run(
+        self, args: FetchUrlArgs, ctx: InvokeContext | None = None
+    ) -> AsyncGenerator[ToolStreamEvent | FetchUrlResult, None]:
+        if args.start_index < 0:
+            raise ToolError("start_index cannot be negative")
+        page = await self._download(args.url, ctx.network if ctx else None)
+
+        content_type = page.response.headers.get("content-type", "")
+        mime = content_type.partition(";")[0].strip().lower()
+        if mime and not mime.startswith(_TEXT_TYPES):
+            raise ToolError(f"Unsupported content type {mime} at {page.url}")
+        text = page.body.decode(page.response.encoding or "utf-8", errors="replace")
+        title = None
+        if mime in {"text/html", "application/xhtml+xml"} or (
+            not mime and "<html" in text[:1000].lower()
+        ):
+            title, text = html_to_markdown(text, page.url)
+
+        end = args.start_index + self._part_size(page.url, title, ctx)
+        yield FetchUrlResult(
+            url=page.url,
+            status_code=page.response.status_code,
+            content_type=mime,
+            title=title or None,
+            content=text[args.start_index : end],
+            total_chars=len(text),
+            next_start_index=end if end < len(text) else None,
+            download_truncated=page.truncated,
+        )
+
+    def _part_size(self, url: str, title: str | None, ctx: InvokeContext | None) -> int:
+        size = self.config.max_content_chars
+        if ctx is None or ctx.output_limit is None:
+            return size
+        # [tools.policy] cuts the whole result, which would lose
+        # next_start_index; keep each part under it instead.
+        room = ctx.output_limit - len(url) - len(title or "") - _FIELDS_MARGIN
+        return max(min(size, room), 1)
+
+    async def _download(self, url: str, network: NetworkConfig | None) -> _Page:
+        for _ in range(MAX_REDIRECTS + 1):
+            address = await self._check_url(url)
+            # Redirects may lead to hosts that the proxy settings treat apart.
+            async with httpx.AsyncClient(
+                timeout=self.config.timeout,
+                headers={"User-Agent": self.config.user_agent},
+                **proxy_options(network, url),
+            ) as client:
+                try:
+                    async with _get(client, url, address) as response:
+                        if response.is_redirect and "location" in response.headers:
+                            url = urljoin(url, response.headers["location"])
+                            continue
+                        if response.is_error:
+                            raise ToolError(
+                                f"HTTP {response.status_code} fetching {url}"
+                            )
+                        body, truncated = await self._read_body(response)
+                        return _Page(url, response, body, truncated)
+                except httpx.HTTPError as exc:
+                    raise ToolError(f"Could not fetch {url}: {exc}") from exc
+        raise ToolError(f"Too many redirects fetching {url}")
+
+    async def _read_body(self, response: httpx.Response) -> tuple[bytes, bool]:
+        limit = self.config.max_download_bytes
+        chunks: list[bytes] = []
+        size = 0
+        async for chunk in response.aiter_bytes():
+            chunks.append(chunk)
+            size += len(chunk)
+            if size > limit:
+                return b"".join(chunks)[:limit], True
+        return b"".join(chunks), False
+
+    async def _check_url(self, url: str) -> str | None:
+        """Refuses URLs outside the allowed domains, and, unless allowed, those
+        reaching into the local network.
+
+        Returns the address checked for the host, which the request must then
+        go to; None when private addresses are allowed and nothing was checked.
+        """
+        parts = urlsplit(url)
+        if parts.scheme not in {"http", "https"}:
+            raise ToolError(f"Only http and https URLs can be fetched: {url}")
+        host = (parts.hostname or "").lower().rstrip(".")
+        if not host:
+            raise ToolError(f"No host in URL: {url}")
+        if any(_in_domain(host, d) for d in self.config.denied_domains):
+            raise ToolError(f"{host} is in denied_domains")
+        if self.config.allowed_domains and not any(
+            _in_domain(host, d) for d in self.config.allowed_domains
+        ):
+            raise ToolError(f"{host} is not in allowed_domains")
+        if self.config.allow_private_addresses:
+            return None
+        return await public_address(host, parts.port)
+
+    def check_allowlist_denylist(
+        self, args: FetchUrlArgs, cwd: Path | None = None
+    ) -> ToolPermission | None:
+        host = (urlsplit(args.url).hostname or "").lower()
+        if not host:
+            return None
+        for pattern in self.config.denylist:
+            if fnmatch.fnmatch(host, pattern):
+                return ToolPermission.NEVER
+        for pattern in self.config.allowlist:
+            if fnmatch.fnmatch(host, pattern):
+                return ToolPermission.ALWAYS
+        return None
+
+    @classmethod
+    def get_call_display(cls, event: ToolCallEvent) -> ToolCallDisplay:
+        if not isinstance(event.args, FetchUrlArgs):
+            return ToolCallDisplay(summary="fetch_url")
+        summary = f"Fetching {event.args.url}"
+        if event.args.start_index:
+            summary += f" (from character {event.args.start_index})"
+        return ToolCallDisplay(summary=summary)
+
+    @classmethod
+    def get_result_display(cls, event: ToolResultEvent) -> ToolResultDisplay:
+        if not isinstance(event.result, FetchUrlResult):
+            return ToolResultDisplay(
+                success=False, message=event.error or event.skip_reason or "No result"
+            )
+        result = event.result
+        message = f"Fetched {result.title or result.url}"
+        if result.next_start_index is not None:
+            message += " (more available)"
+        warnings = []
+        if result.download_truncated:
+            warnings.append("The page exceeded max_download_bytes and was cut short")
+        return ToolResultDisplay(success=True, message=message, warnings=warnings)
+
+    @classmethod
+    def get_status_text(cls) -> str:
+        return "Fetching page"
+
+
+async def public_address(host: str, port: int | None) -> str:
+    """The address ``host`` resolves to, refusing hosts with a loopback,
+    private or link-local one, so a page can't steer requests into the local
+    network.
+    """
+    try:
+        addresses = await anyio.getaddrinfo(host, port or 0, type=socket.SOCK_STREAM)
+    except OSError as exc:
+        raise ToolError(f"Could not resolve {host}: {exc}") from exc
+    for *_, sockaddr in addresses:
+        address = ipaddress.ip_address(str(sockaddr[0]).partition("%")[0])
+        if not address.is_global:
+            raise ToolError(
+                f"{host} resolves to the non-public address {address}; set "
+                "allow_private_addresses to reach it"
+            )
+    return str(addresses[0][4][0]).partition("%")[0]
+
+
+def _in_domain(host: str, domain: str) -> bool:
+    domain = domain.lower().strip(".")
+    return host == domain or host.endswith(f".{domain}")
+
+
+def _get(
+    client: httpx.AsyncClient, url: str, address: str | None
+) -> AbstractAsyncContextManager[httpx.Response]:
+    """GET ``url`` from ``address`` rather than from wherever the host resolves
+    to by now, so a DNS answer that changed since the check (DNS rebinding)
+    can't point the request into the local network. TLS still checks the
+    certificate against the host name.
+    """
+    if address is None:
+        return client.stream("GET", url)
+    target = httpx.URL(url)
+    return client.stream(
+        "GET",
+        target.copy_with(host=address),
+        headers={"Host": target.netloc.decode("ascii")},
+        extensions={"sni_hostname": target.raw_host.decode("ascii")},
+    )
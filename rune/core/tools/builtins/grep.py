@@ -1,14 +1,19 @@
 from __future__ import annotations
 
 import asyncio
+import base64
 from collections.abc import AsyncGenerator
+from dataclasses import dataclass, field
 from enum import StrEnum, auto
+import json
 from pathlib import Path
+import re
 import shutil
 from typing import TYPE_CHECKING, ClassVar
 
 from pydantic import BaseModel, Field
 
+from rune.core.ignore_files import RUNEIGNORE_FILE, read_ignore_patterns
 from rune.core.tools.base import (
     BaseTool,
     BaseToolConfig,
@@ -16,7 +21,9 @@ from rune.core.tools.base import (
     InvokeContext,
     ToolError,
     ToolPermission,
+    working_dir,
 )
+from rune.core.tools.file_guards import DEFAULT_MAX_FILE_BYTES, guard_text_file
 from rune.core.tools.ui import ToolCallDisplay, ToolResultDisplay, ToolUIData
 from rune.core.types import ToolStreamEvent
 
@@ -33,10 +40,17 @@ class GrepToolConfig(BaseToolConfig):
     permission: ToolPermission = ToolPermission.ALWAYS
 
     max_output_bytes: int = Field(
-        default=64_000, description="Hard cap for the total size of matched lines."
+        default=64_000,
+        description="Hard cap for the total size of matched and context lines.",
     )
     default_max_matches: int = Field(
-        default=100, description="Default maximum number of matches to return."
+        default=100,
+        description="Default maximum number of matches to return, across all files.",
+    )
+    max_file_bytes: int = Field(
+        default=DEFAULT_MAX_FILE_BYTES,
+        description="Files larger than this are skipped (ripgrep) or refused when "
+        "searched directly.",
     )
     default_timeout: int = Field(
         default=60, description="Default timeout for the search command in seconds."
@@ -70,7 +84,7 @@ class GrepToolConfig(BaseToolConfig):
         description="List of glob patterns to exclude from search (dirs should end with /).",
     )
     codeignore_file: str = Field(
-        default=".runeignore",
+        default=RUNEIGNORE_FILE,
         description="Name of the file to read for additional exclusion patterns.",
     )
 
@@ -82,8 +96,17 @@ class GrepState(BaseToolState):
 class GrepArgs(BaseModel):
     pattern: str
     path: str = "."
+    literal: bool = Field(
+        default=False,
+        description="Match the pattern as plain text instead of a regular expression.",
+    )
+    context_lines: int = Field(
+        default=0, description="Lines to show before and after each match."
+    )
     max_matches: int | None = Field(
-        default=None, description="Override the default maximum number of matches."
+        default=None,
+        description="Override the default maximum number of matches, counted "
+        "across all files.",
     )
     use_default_ignore: bool = Field(
         default=True, description="Whether to respect .gitignore and .ignore files."
@@ -91,20 +114,41 @@ class GrepArgs(BaseModel):
 
 
 class GrepResult(BaseModel):
-    matches: str
+    matches: str = Field(
+        description="Matches grouped by file: the path on its own line, then "
+        "`<line>:<text>` for matching lines and `<line>-<text>` for context."
+    )
     match_count: int
+    file_count: int = 0
     was_truncated: bool = Field(
         description="True if output was cut short by max_matches or max_output_bytes."
     )
 
 
+@dataclass
+class _Line:
+    number: int
+    text: str
+    is_match: bool
+
+
+@dataclass
+class _FileMatches:
+    path: str
+    lines: list[_Line] = field(default_factory=list)
+
+
+_GNU_GREP_LINE_RE = re.compile(r"(?P<number>\d+)(?P<sep>[:-])(?P<text>.*)", re.DOTALL)
+
+
 class Grep(
     BaseTool[GrepArgs, GrepResult, GrepToolConfig, GrepState],
     ToolUIData[GrepArgs, GrepResult],
 ):
     description: ClassVar[str] = (
-        "Recursively search files for a regex pattern using ripgrep (rg) or grep. "
-        "Respects .gitignore and .codeignore files by default when using ripgrep."
+        "Recursively search files for a regex or literal pattern using ripgrep "
+        "(rg) or grep. Respects .gitignore and .runeignore files by default and "
+        "returns the matches grouped by file."
     )
 
     def _detect_backend(self) -> GrepBackend:
@@ -121,47 +165,48 @@ class Grep(
         self, args: GrepArgs, ctx: InvokeContext | None = None
     ) -> AsyncGenerator[ToolStreamEvent | GrepResult, None]:
         backend = self._detect_backend()
-        self._validate_args(args)
+        cwd = working_dir(ctx)
+        self._validate_args(args, cwd)
         self.state.search_history.append(args.pattern)
 
-        exclude_patterns = self._collect_exclude_patterns()
+        exclude_patterns = self._collect_exclude_patterns(cwd)
         cmd = self._build_command(args, exclude_patterns, backend)
-        stdout = await self._execute_search(cmd)
+        stdout = await self._execute_search(cmd, cwd)
 
-        yield self._parse_output(
-            stdout, args.max_matches or self.config.default_max_matches
+        if backend == GrepBackend.RIPGREP:
+            files = self._parse_ripgrep_output(stdout)
+        else:
+            files = self._parse_gnu_grep_output(stdout)
+            if args.use_default_ignore:
+                files = await self._drop_git_ignored(files, cwd)
+
+        yield self._build_result(
+            files,
+            args.max_matches or self.config.default_max_matches,
+            args.context_lines,
         )
 
-    def _validate_args(self, args: GrepArgs) -> None:
+    def _validate_args(self, args: GrepArgs, cwd: Path) -> None:
         if not args.pattern.strip():
             raise ToolError("Empty search pattern provided.")
+        if args.context_lines < 0:
+            raise ToolError("context_lines cannot be negative.")
 
         path_obj = Path(args.path).expanduser()
         if not path_obj.is_absolute():
-            path_obj = Path.cwd() / path_obj
+            path_obj = cwd / path_obj
 
         if not path_obj.exists():
             raise ToolError(f"Path does not exist: {args.path}")
+        if path_obj.is_file():
+            guard_text_file(
+                path_obj, action="search", max_bytes=self.config.max_file_bytes
+            )
 
-    def _collect_exclude_patterns(self) -> list[str]:
+    def _collect_exclude_patterns(self, cwd: Path) -> list[str]:
         patterns = list(self.config.exclude_patterns)
 
-        codeignore_path = Path.cwd() / self.config.codeignore_file
-        if codeignore_path.is_file():
-            patterns.extend(self._load_codeignore_patterns(codeignore_path))
-
-        return patterns
-
-    def _load_codeignore_patterns(self, codeignore_path: Path) -> list[str]:
-        patterns = []
-        try:
-            content = codeignore_path.read_text("utf-8")
-            for line in content.splitlines():
-                line = line.strip()
-                if line and not line.startswith("#"):
-                    patterns.append(line)
-        except OSError:
-            pass
+        patterns.extend(read_ignore_patterns(cwd / self.config.codeignore_file))
 
         return patterns
 
@@ -179,15 +224,20 @@ class Grep(
 
         cmd = [
             "rg",
-            "--line-number",
-            "--no-heading",
+            "--json",
             "--smart-case",
             "--no-binary",
+            "--max-filesize",
+            str(self.config.max_file_bytes),
             # Request one extra to detect truncation
             "--max-count",
             str(max_matches + 1),
         ]
 
+        if args.literal:
+            cmd.append("--fixed-strings")
+        if args.context_lines:
+            cmd.extend(["--context", str(args.context_lines)])
         if not args.use_default_ignore:
             cmd.append("--no-ignore")
 
@@ -203,10 +253,22 @@ class Grep(
     ) -> list[str]:
         max_matches = args.max_matches or self.config.default_max_matches
 
-        cmd = ["grep", "-r", "-n", "-I", "-E", f"--max-count={max_matches + 1}"]
+        # -Z ends file names with a NUL so paths containing ':' or '-' parse.
+        cmd = [
+            "grep",
+            "-r",
+            "-n",
+            "-H",
+            "-Z",
+            "-I",
+            "-F" if args.literal else "-E",
+            f"--max-count={max_matches + 1}",
+        ]
 
         if args.pattern.islower():
             cmd.append("-i")
+        if args.context_lines:
+            cmd.append(f"--context={args.context_lines}")
 
         for pattern in exclude_patterns:
             if pattern.endswith("/"):
@@ -219,10 +281,13 @@ class Grep(
 
         return cmd
 
-    async def _execute_search(self, cmd: list[str]) -> str:
+    async def _execute_search(self, cmd: list[str], cwd: Path) -> str:
         try:
             proc = await asyncio.create_subprocess_exec(
-                *cmd, stdout=asyncio.subprocess.PIPE, stderr=asyncio.subprocess.PIPE
+                *cmd,
+                stdout=asyncio.subprocess.PIPE,
+                stderr=asyncio.subprocess.PIPE,
+                cwd=cwd,
             )
 
             try:
@@ -254,22 +319,103 @@ class Grep(
         except Exception as exc:
             raise ToolError(f"Error running grep: {exc}") from exc
 
-    def _parse_output(self, stdout: str, max_matches: int) -> GrepResult:
-        output_lines = stdout.splitlines() if stdout else []
-
-        truncated_lines = output_lines[:max_matches]
-        truncated_output = "\n".join(truncated_lines)
-
-        was_truncated = (
-            len(output_lines) > max_matches
-            or len(truncated_output) > self.config.max_output_bytes
-        )
-
-        final_output = truncated_output[: self.config.max_output_bytes]
+    def _parse_ripgrep_output(self, stdout: str) -> list[_FileMatches]:
+        files: dict[str, _FileMatches] = {}
+        for raw in stdout.split("\n"):
+            if not raw:
+                continue
+            event = json.loads(raw)
+            if event.get("type") not in {"match", "context"}:
+                continue
+            data = event["data"]
+            path = _ripgrep_text(data["path"])
+            file = files.setdefault(path, _FileMatches(path))
+            file.lines.append(
+                _Line(
+                    number=data["line_number"],
+                    text=_ripgrep_text(data["lines"]).rstrip("\r\n"),
+                    is_match=event["type"] == "match",
+                )
+            )
+        return sorted(files.values(), key=lambda f: f.path)
+
+    def _parse_gnu_grep_output(self, stdout: str) -> list[_FileMatches]:
+        files: dict[str, _FileMatches] = {}
+        for raw in stdout.split("\n"):
+            path, sep, rest = raw.partition("\0")
+            if not sep or not (line := _GNU_GREP_LINE_RE.fullmatch(rest)):
+                continue
+            file = files.setdefault(path, _FileMatches(path))
+            file.lines.append(
+                _Line(
+                    number=int(line["number"]),
+                    text=line["text"].rstrip("\r"),
+                    is_match=line["sep"] == ":",
+                )
+            )
+        return sorted(files.values(), key=lambda f: f.path)
+
+    async def _drop_git_ignored(
+        self, files: list[_FileMatches], cwd: Path
+    ) -> list[_FileMatches]:
+        """GNU grep has no notion of .gitignore, so ask git which of the files
+        it matched are ignored. Outside a repository nothing is dropped.
+        """
+        if not files or not shutil.which("git"):
+            return files
+        try:
+            proc = await asyncio.create_subprocess_exec(
+                "git",
+                "check-ignore",
+                "--stdin",
+                "-z",
+                stdin=asyncio.subprocess.PIPE,
+                stdout=asyncio.subprocess.PIPE,
+                stderr=asyncio.subprocess.DEVNULL,
+                cwd=cwd,
+            )
+            stdout, _ = await asyncio.wait_for(
+                proc.communicate("\0".join(f.path for f in files).encode()),
+                timeout=self.config.default_timeout,
+            )
+        except (OSError, TimeoutError):
+            return files
+        # 1 means none of the paths are ignored, 128 that this isn't a repository.
+        if proc.returncode != 0:
+            return files
+        ignored = set(stdout.decode("utf-8", errors="ignore").split("\0"))
+        return [f for f in files if f.path not in ignored]
+
+    def _build_result(
+        self, files: list[_FileMatches], max_matches: int, context_lines: int
+    ) -> GrepResult:
+        kept, was_truncated = _limit_matches(files, max_matches, context_lines)
+
+        blocks: list[str] = []
+        # Each line counts its newline, each file after the first the blank line.
+        size = -1
+        match_count = 0
+        for file in kept:
+            size += 1
+            lines: list[str] = []
+            file_matches = 0
+            for text, is_match in _render_file(file):
+                size += len(text) + 1
+                if size > self.config.max_output_bytes:
+                    break
+                lines.append(text)
+                file_matches += is_match
+            if file_matches:
+                blocks.append("\n".join(lines))
+                match_count += file_matches
+            if size > self.config.max_output_bytes:
+                was_truncated = True
+                break
 
         return GrepResult(
-            matches=final_output,
-            match_count=len(truncated_lines),
+            matches="\n\n".join(blocks),
+            match_count=match_count,
+            file_count=len(blocks),
             was_truncated=was_truncated,
         )
 
@@ -279,8 +425,12 @@ class Grep(
             return ToolCallDisplay(summary="grep")
 
         summary = f"Grepping '{event.args.pattern}'"
+        if event.args.literal:
+            summary += " (literal)"
         if event.args.path != ".":
             summary += f" in {event.args.path}"
+        if event.args.context_lines:
+            summary += f" with {event.args.context_lines} lines of context"
         if event.args.max_matches:
             summary += f" (max {event.args.max_matches} matches)"
         if not event.args.use_default_ignore:
@@ -296,6 +446,8 @@ class Grep(
             )
 
         message = f"Found {event.result.match_count} matches"
+        if event.result.file_count:
+            message += f" in {event.result.file_count} files"
         if event.result.was_truncated:
             message += " (truncated)"
 
@@ -308,3 +460,50 @@ class Grep(
     @classmethod
     def get_status_text(cls) -> str:
         return "Searching files"
+
+
+def _ripgrep_text(value: dict[str, str]) -> str:
+    """Text from ripgrep's JSON, which base64-encodes anything that isn't UTF-8."""
+    if "text" in value:
+        return value["text"]
+    return base64.b64decode(value.get("bytes", "")).decode("utf-8", errors="replace")
+
+
+def _limit_matches(
+    files: list[_FileMatches], max_matches: int, context_lines: int
+) -> tuple[list[_FileMatches], bool]:
+    """Keep the first `max_matches` matches across all files, with their context."""
+    kept: list[_FileMatches] = []
+    remaining = max_matches
+    for file in files:
+        if remaining == 0:
+            return kept, True
+        lines: list[_Line] = []
+        last_match = 0
+        for line in file.lines:
+            if line.is_match:
+                if remaining == 0:
+                    break
+                remaining -= 1
+                last_match = line.number
+            lines.append(line)
+        else:
+            kept.append(file)
+            continue
+        # Cut short: drop the context that only belongs to the matches left out.
+        lines = [line for line in lines if line.number <= last_match + context_lines]
+        kept.append(_FileMatches(file.path, lines))
+        return kept, True
+    return kept, False
+
+
+def _render_file(file: _FileMatches) -> list[tuple[str, bool]]:
+    rendered = [(file.path, False)]
+    previous: int | None = None
+    for line in file.lines:
+        if previous is not None and line.number > previous + 1:
+            rendered.append(("--", False))
+        separator = ":" if line.is_match else "-"
+        rendered.append((f"{line.number}{separator}{line.text}", line.is_match))
+        previous = line.number
+    return rendered
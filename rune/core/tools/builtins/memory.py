@@ -0,0 +1,110 @@
+from __future__ import annotations
+
+from collections.abc import AsyncGenerator
+from typing import ClassVar, Literal
+
+from pydantic import BaseModel, Field
+
+from rune.core.memory import MemoryStore, MemoryStoreError
+from rune.core.tools.base import (
+    BaseTool,
+    BaseToolConfig,
+    BaseToolState,
+    InvokeContext,
+    ToolError,
+    ToolPermission,
+    working_dir,
+)
+from rune.core.tools.ui import ToolCallDisplay, ToolResultDisplay, ToolUIData
+from rune.core.types import ToolCallEvent, ToolResultEvent, ToolStreamEvent
+
+
+class MemoryArgs(BaseModel):
+    action: Literal["save", "search", "forget"] = Field(
+        description="'save' a new note, 'search' saved notes or 'forget' one."
+    )
+    content: str | None = Field(
+        default=None, description="The note to save, or the words to search for."
+    )
+    tags: list[str] = Field(
+        default_factory=list, description="Optional tags for a saved note."
+    )
+    id: int | None = Field(default=None, description="The note to forget.")
+
+
+class MemoryNote(BaseModel):
+    id: int
+    content: str
+    tags: list[str]
+
+
+class MemoryResult(BaseModel):
+    message: str
+    notes: list[MemoryNote] = Field(default_factory=list)
+
+
+class MemoryToolConfig(BaseToolConfig):
+    permission: ToolPermission = ToolPermission.ASK
+    search_limit: int = 10
+
+
+class Memory(
+    BaseTool[MemoryArgs, MemoryResult, MemoryToolConfig, BaseToolState],
+    ToolUIData[MemoryArgs, MemoryResult],
+):
+    description: ClassVar[str] = (
+        "Keep durable notes about this project across sessions: save, search "
+        "or forget them."
+    )
+
+    @classmethod
+    def get_call_display(cls, event: ToolCallEvent) -> ToolCallDisplay:
+        if not isinstance(event.args, MemoryArgs):
+            return ToolCallDisplay(summary="Invalid arguments")
+
+        args = event.args
+        match args.action:
+            case "save":
+                return ToolCallDisplay(summary="Saving a memory")
+            case "search":
+                return ToolCallDisplay(summary=f"Searching memories: {args.content}")
+            case "forget":
+                return ToolCallDisplay(summary=f"Forgetting memory {args.id}")
+
+    @classmethod
+    def get_result_display(cls, event: ToolResultEvent) -> ToolResultDisplay:
+        if not isinstance(event.result, MemoryResult):
+            return ToolResultDisplay(success=True, message="Success")
+        return ToolResultDisplay(success=True, message=event.result.message)
+
+    @classmethod
+    def get_status_text(cls) -> str:
+        return "Consulting memory"
+
+    async def run(
+        self, args: MemoryArgs, ctx: InvokeContext | None = None
+    ) -> AsyncGenerator[ToolStreamEvent | MemoryResult, None]:
+        store = MemoryStore(project=working_dir(ctx))
+        try:
+            match args.action:
+                case "save":
+                    if not args.content:
+                        raise ToolError("'content' is required to save a memory")
+                    saved = store.save(args.content, args.tags)
+                    yield MemoryResult(
+                        message=f"Saved memory {saved.id}",
+                        notes=[MemoryNote(**saved.model_dump())],
+                    )
+                case "search":
+                    found = store.search(args.content or "", self.config.search_limit)
+                    yield MemoryResult(
+                        message=f"Found {len(found)} memories",
+                        notes=[MemoryNote(**m.model_dump()) for m in found],
+                    )
+                case "forget":
+                    if args.id is None:
+                        raise ToolError("'id' is required to forget a memory")
+                    store.delete(args.id)
+                    yield MemoryResult(message=f"Forgot memory {args.id}")
+        except MemoryStoreError as e:
+            raise ToolError(str(e)) from e
@@ -0,0 +1,220 @@
+from __future__ import annotations
+
+from collections.abc import AsyncGenerator
+from pathlib import Path
+from typing import TYPE_CHECKING, ClassVar
+
+from pydantic import BaseModel, Field
+
+from rune.core.tools.base import (
+    BackgroundProcess,
+    BaseTool,
+    BaseToolConfig,
+    BaseToolState,
+    ImageResult,
+    InvokeContext,
+    ToolError,
+    ToolPermission,
+    working_dir,
+)
+from rune.core.tools.exec_env import ExecEnvPolicy
+from rune.core.tools.python_kernel import KernelError, PythonKernel, find_interpreter
+from rune.core.tools.subprocesses import get_base_env
+from rune.core.tools.ui import ToolCallDisplay, ToolResultDisplay, ToolUIData
+from rune.core.types import ImageContent, ToolStreamEvent
+
+if TYPE_CHECKING:
+    from rune.core.types import ToolCallEvent, ToolResultEvent
+
+_MAX_RESULT_CHARS = 10_000
+
+
+class PythonToolConfig(BaseToolConfig):
+    permission: ToolPermission = ToolPermission.ASK
+    interpreter: str | None = Field(
+        default=None,
+        description="Python to run code with. Defaults to the project's .venv, "
+        "then the first python3 on PATH.",
+    )
+    default_timeout: int = Field(
+        default=300, description="Seconds a run may take before it is interrupted."
+    )
+    max_output_bytes: int = Field(
+        default=16_000, description="Maximum bytes to capture from stdout and stderr."
+    )
+    env: ExecEnvPolicy = Field(
+        default_factory=ExecEnvPolicy,
+        description="Environment variables passed to the kernel",
+    )
+
+
+class PythonState(BaseToolState):
+    kernel: PythonKernel | None = None
+
+
+class PythonArgs(BaseModel):
+    code: str = Field(
+        default="",
+        description="Code to run. The value of a final expression is returned.",
+    )
+    reset: bool = Field(
+        default=False,
+        description="Restart the kernel, clearing all variables and imports, "
+        "before running the code.",
+    )
+    timeout: int | None = Field(
+        default=None, description="Override the default timeout in seconds."
+    )
+
+
+class PythonResult(ImageResult):
+    stdout: str = ""
+    stderr: str = ""
+    result: str | None = Field(
+        default=None, description="repr() of the final expression's value."
+    )
+    error: str | None = Field(
+        default=None, description="Traceback of the exception the code raised."
+    )
+    fresh_kernel: bool = Field(
+        default=False,
+        description="The code ran in a new kernel; earlier variables are gone.",
+    )
+    output_truncated: bool = False
+    interrupted: bool = Field(
+        default=False,
+        description="The code hit the timeout and was interrupted; the kernel "
+        "and its variables were kept.",
+    )
+    stopped: bool = Field(
+        default=False,
+        description="The user stopped the code before it ended; the kernel and "
+        "its variables were kept.",
+    )
+
+
+class Python(
+    BaseTool[PythonArgs, PythonResult, PythonToolConfig, PythonState],
+    ToolUIData[PythonArgs, PythonResult],
+):
+    description: ClassVar[str] = (
+        "Run Python code in a persistent kernel. Variables, imports and loaded "
+        "data carry over between calls until the kernel is reset."
+    )
+
+    async def run(
+        self, args: PythonArgs, ctx: InvokeContext | None = None
+    ) -> AsyncGenerator[ToolStreamEvent | PythonResult, None]:
+        if args.reset:
+            await self._stop_kernel()
+        if not args.code.strip():
+            if not args.reset:
+                raise ToolError("Nothing to run; pass code, or reset to start over")
+            yield PythonResult(fresh_kernel=True)
+            return
+
+        fresh = self.state.kernel is None or not self.state.kernel.alive
+        cwd = working_dir(ctx)
+        stop = ctx.stop_requested if ctx else None
+        try:
+            kernel = await self._kernel(cwd)
+            reply = await kernel.execute(
+                args.code,
+                cwd=cwd,
+                timeout=args.timeout or self.config.default_timeout,
+                max_output_bytes=self.config.max_output_bytes,
+                stop=stop,
+            )
+        except KernelError as e:
+            await self._stop_kernel()
+            raise ToolError(f"{e}. The next call starts a fresh kernel.") from e
+
+        result = reply.result
+        if result is not None and len(result) > _MAX_RESULT_CHARS:
+            result = result[:_MAX_RESULT_CHARS] + "... [truncated]"
+        stopped = reply.interrupted and stop is not None and stop.is_set()
+        yield PythonResult(
+            stdout=reply.stdout,
+            stderr=reply.stderr,
+            result=result,
+            error=reply.error,
+            fresh_kernel=fresh,
+            output_truncated=reply.output_truncated,
+            interrupted=reply.interrupted and not stopped,
+            stopped=stopped,
+            images=[ImageContent(data=data) for data in reply.images],
+        )
+
+    async def _kernel(self, cwd: Path) -> PythonKernel:
+        if self.state.kernel is not None and self.state.kernel.alive:
+            return self.state.kernel
+        await self._stop_kernel()
+        self.state.kernel = await PythonKernel.start(
+            find_interpreter(self.config.interpreter, cwd),
+            env=get_base_env(self.config.env),
+            cwd=cwd,
+        )
+        return self.state.kernel
+
+    async def _stop_kernel(self) -> None:
+        if self.state.kernel is not None:
+            await self.state.kernel.close()
+            self.state.kernel = None
+
+    def list_background_processes(self) -> list[BackgroundProcess]:
+        kernel = self.state.kernel
+        if kernel is None or not kernel.alive:
+            return []
+        return [
+            BackgroundProcess(
+                id=self.get_name(),
+                tool_name=self.get_name(),
+                description=f"Python kernel ({kernel.interpreter})",
+                pid=kernel.pid,
+                started_at=kernel.started_at,
+            )
+        ]
+
+    async def kill_background_process(self, process_id: str) -> bool:
+        if process_id != self.get_name() or self.state.kernel is None:
+            return False
+        await self._stop_kernel()
+        return True
+
+    @classmethod
+    def get_call_display(cls, event: ToolCallEvent) -> ToolCallDisplay:
+        if not isinstance(event.args, PythonArgs):
+            return ToolCallDisplay(summary="python")
+        lines = event.args.code.strip().splitlines()
+        if not lines:
+            return ToolCallDisplay(summary="Restarting the Python kernel")
+        summary = lines[0] + (f" (+{len(lines) - 1} lines)" if len(lines) > 1 else "")
+        return ToolCallDisplay(summary=f"python: {summary}")
+
+    @classmethod
+    def get_result_display(cls, event: ToolResultEvent) -> ToolResultDisplay:
+        if not isinstance(event.result, PythonResult):
+            return ToolResultDisplay(
+                success=False, message=event.error or event.skip_reason or "No result"
+            )
+        result = event.result
+        warnings = []
+        if result.interrupted:
+            warnings.append("Interrupted after hitting the timeout")
+        if result.stopped:
+            warnings.append("Stopped before it finished")
+        if result.output_truncated:
+            warnings.append("Output truncated")
+        if result.error:
+            last_line = result.error.strip().splitlines()[-1]
+            return ToolResultDisplay(
+                success=False, message=last_line, warnings=warnings
+            )
+        message = "Ran in a fresh kernel" if result.fresh_kernel else "Ran"
+        if result.images:
+            message += f", {len(result.images)} figure(s)"
+        return ToolResultDisplay(success=True, message=message, warnings=warnings)
+
+    @classmethod
+    def get_status_text(cls) -> str:
+        return "Running Python"
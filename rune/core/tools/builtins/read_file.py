@@ -1,8 +1,10 @@
 from __future__ import annotations
 
+import codecs
 from collections.abc import AsyncGenerator
+from datetime import UTC, datetime
 from pathlib import Path
-from typing import TYPE_CHECKING, ClassVar, NamedTuple, final
+from typing import TYPE_CHECKING, Any, ClassVar, NamedTuple, final
 
 import anyio
 from pydantic import BaseModel, Field
@@ -11,10 +13,22 @@ from rune.core.tools.base import (
     BaseTool,
     BaseToolConfig,
     BaseToolState,
+    ImageResult,
     InvokeContext,
     ToolError,
     ToolPermission,
+    absolute_path,
+    working_dir,
 )
+from rune.core.tools.file_guards import (
+    DEFAULT_MAX_FILE_BYTES,
+    FileGuardError,
+    FileInfo,
+    RefusalReason,
+    format_size,
+    guard_text_file,
+)
+from rune.core.tools.images import ATTACHABLE_IMAGE_TYPES, image_from_file
 from rune.core.tools.ui import ToolCallDisplay, ToolResultDisplay, ToolUIData
 from rune.core.types import ToolStreamEvent
 
@@ -22,10 +36,22 @@ if TYPE_CHECKING:
     from rune.core.types import ToolCallEvent, ToolResultEvent
 
 
+_UTF8_CONTINUATION_MASK = 0xC0
+_UTF8_CONTINUATION = 0x80
+_MAX_UTF8_CONTINUATION_BYTES = 3
+
+
 class _ReadResult(NamedTuple):
     lines: list[str]
     bytes_read: int
     was_truncated: bool
+    total_lines: int | None = None
+
+
+class _ByteReadResult(NamedTuple):
+    text: str
+    start: int
+    end: int
 
 
 class ReadFileArgs(BaseModel):
@@ -37,15 +63,102 @@ class ReadFileArgs(BaseModel):
     limit: int | None = Field(
         default=None, description="Maximum number of lines to read."
     )
+    start_line: int | None = Field(
+        default=None,
+        description="First line of an explicit range (1-indexed, inclusive). "
+        "Takes precedence over offset.",
+    )
+    end_line: int | None = Field(
+        default=None,
+        description="Last line of an explicit range (1-indexed, inclusive). "
+        "Takes precedence over limit.",
+    )
+    cursor: str | None = Field(
+        default=None,
+        description="Continue an earlier read that did not reach the end of the "
+        "file: pass the next_cursor value from its result.",
+    )
+    byte_offset: int | None = Field(
+        default=None,
+        description="Read from this byte of the file instead of by lines, e.g. "
+        "to jump into a log too large to read by lines. Not combined with line "
+        "positions.",
+    )
+    byte_limit: int | None = Field(
+        default=None,
+        description="Bytes to read from byte_offset, at most the configured limit.",
+    )
+    encoding: str | None = Field(
+        default=None,
+        description="Decode the file with this encoding instead of the detected one.",
+    )
 
 
-class ReadFileResult(BaseModel):
+class ReadFileResult(ImageResult):
     path: str
     content: str
     lines_read: int
     was_truncated: bool = Field(
         description="True if the reading was stopped due to the max_read_bytes limit."
     )
+    start_line: int | None = Field(
+        default=None, description="First line returned (1-indexed)."
+    )
+    end_line: int | None = Field(
+        default=None, description="Last line returned (1-indexed)."
+    )
+    total_lines: int | None = Field(
+        default=None, description="Number of lines in the file, when known."
+    )
+    next_cursor: str | None = Field(
+        default=None,
+        description="Set when more of the file remains. Pass it as cursor to read "
+        "the next page.",
+    )
+    byte_offset: int | None = Field(
+        default=None, description="First byte returned, for reads by byte_offset."
+    )
+    next_byte_offset: int | None = Field(
+        default=None,
+        description="Set when a read by byte_offset did not reach the end of the "
+        "file. Pass it as byte_offset to continue.",
+    )
+    size_bytes: int | None = None
+    modified_at: str | None = Field(
+        default=None, description="Last modification time, ISO 8601 in UTC."
+    )
+    encoding: str | None = Field(
+        default=None, description="Encoding the content was decoded with."
+    )
+    binary: bool = Field(
+        default=False,
+        description="The file is binary; its content is left out, see hint.",
+    )
+    mime: str | None = None
+    hint: str | None = None
+
+
+def encode_cursor(offset: int, file_path: Path) -> str:
+    return f"{offset}:{_mtime_ns(file_path)}"
+
+
+def decode_cursor(cursor: str, file_path: Path) -> int:
+    offset, _, mtime = cursor.partition(":")
+    if not offset.isdigit() or not mtime.isdigit():
+        raise ToolError(f"Invalid cursor: {cursor!r}")
+    if int(mtime) != _mtime_ns(file_path):
+        raise ToolError(
+            "The file changed since this cursor was issued; "
+            "read it again with start_line instead."
+        )
+    return int(offset)
+
+
+def _mtime_ns(file_path: Path) -> int:
+    try:
+        return file_path.stat().st_mtime_ns
+    except OSError:
+        return 0
 
 
 class ReadFileToolConfig(BaseToolConfig):
@@ -57,6 +170,16 @@ class ReadFileToolConfig(BaseToolConfig):
     max_state_history: int = Field(
         default=10, description="Number of recently read files to remember in state."
     )
+    max_file_bytes: int = Field(
+        default=DEFAULT_MAX_FILE_BYTES,
+        description="Files larger than this are refused instead of being scanned, "
+        "unless read by byte_offset.",
+    )
+    max_image_file_bytes: int = Field(
+        default=20 * 1024 * 1024,
+        description="Image files up to this size on disk are attached for models "
+        "that take images; they are scaled down to the model's limits first.",
+    )
 
 
 class ReadFileState(BaseToolState):
@@ -68,34 +191,220 @@ class ReadFile(
     ToolUIData[ReadFileArgs, ReadFileResult],
 ):
     description: ClassVar[str] = (
-        "Read a UTF-8 file, returning content from a specific line range. "
-        "Reading is capped by a byte limit for safety."
+        "Read a text file, returning content from a specific line or byte range. "
+        "Reading is capped by a byte limit for safety; when a read stops before "
+        "the end of the file, the result has a next_cursor to continue from."
     )
 
     @final
     async def run(
         self, args: ReadFileArgs, ctx: InvokeContext | None = None
     ) -> AsyncGenerator[ToolStreamEvent | ReadFileResult, None]:
-        file_path = self._prepare_and_validate_path(args)
-
-        read_result = await self._read_file(args, file_path)
+        file_path = self._prepare_and_validate_path(args, working_dir(ctx))
+        # A byte range reads only its part, however large the file.
+        by_bytes = args.byte_offset is not None or args.byte_limit is not None
+        try:
+            info = guard_text_file(
+                file_path,
+                action="read",
+                max_bytes=None if by_bytes else self.config.max_file_bytes,
+            )
+        except FileGuardError as e:
+            if e.reason is not RefusalReason.BINARY:
+                raise
+            if e.info.mime in ATTACHABLE_IMAGE_TYPES:
+                yield await self._image_result(e.info)
+            else:
+                yield self._binary_result(e.info)
+            return
+
+        details = self._file_details(file_path, info, args.encoding)
+        if by_bytes:
+            result = await self._read_byte_range(args, file_path, details)
+            self._update_state_history(file_path)
+            yield result
+            return
+
+        window = self._resolve_window(args, file_path).model_copy(
+            update={"encoding": details["encoding"]}
+        )
+        read_result = await self._read_file(window, file_path)
 
         self._update_state_history(file_path)
 
+        lines_read = len(read_result.lines)
+        next_offset = window.offset + lines_read
+        has_more = lines_read > 0 and (
+            read_result.total_lines is None or next_offset < read_result.total_lines
+        )
+        next_cursor = encode_cursor(next_offset, file_path) if has_more else None
+
         yield ReadFileResult(
             path=str(file_path),
             content="".join(read_result.lines),
-            lines_read=len(read_result.lines),
+            lines_read=lines_read,
             was_truncated=read_result.was_truncated,
+            start_line=window.offset + 1 if lines_read else None,
+            end_line=next_offset if lines_read else None,
+            total_lines=read_result.total_lines,
+            next_cursor=next_cursor,
+            hint=self._pagination_hint(
+                window.offset, next_offset, read_result.total_lines, next_cursor
+            ),
+            **details,
+        )
+
+    @staticmethod
+    def _file_details(
+        file_path: Path, info: FileInfo, encoding: str | None
+    ) -> dict[str, Any]:
+        if encoding is None:
+            encoding = info.encoding or "utf-8"
+        else:
+            try:
+                encoding = codecs.lookup(encoding).name
+            except LookupError:
+                raise ToolError(f"Unknown encoding: {encoding!r}") from None
+        try:
+            modified_at = datetime.fromtimestamp(
+                file_path.stat().st_mtime, UTC
+            ).isoformat(timespec="seconds")
+        except OSError:
+            modified_at = None
+        return {
+            "size_bytes": info.size,
+            "modified_at": modified_at,
+            "encoding": None if info.is_binary else encoding,
+            "mime": info.mime,
+        }
+
+    def _binary_result(self, info: FileInfo) -> ReadFileResult:
+        return ReadFileResult(
+            path=str(info.path),
+            content="",
+            lines_read=0,
+            was_truncated=False,
+            binary=True,
+            **self._file_details(info.path, info, None),
+            hint=f"{info.path.name} is a binary file ({info.mime}, "
+            f"{format_size(info.size)}); its content can't be shown as text. "
+            "Inspect it with a purpose-built command (e.g. `file`, "
+            "`xxd | head`) instead.",
+        )
+
+    async def _image_result(self, info: FileInfo) -> ReadFileResult:
+        result = self._binary_result(info)
+        if info.size > self.config.max_image_file_bytes:
+            return result
+        try:
+            image = await anyio.to_thread.run_sync(
+                image_from_file, info.path, info.mime
+            )
+        except OSError as e:
+            raise ToolError(f"Error reading {info.path}: {e}") from e
+        return result.model_copy(
+            update={
+                "images": [image],
+                "hint": f"{info.path.name} is an image ({info.mime}, "
+                f"{format_size(info.size)}); it is attached to this result.",
+            }
+        )
+
+    async def _read_byte_range(
+        self, args: ReadFileArgs, file_path: Path, details: dict[str, Any]
+    ) -> ReadFileResult:
+        limit = self.config.max_read_bytes
+        if args.byte_limit is not None:
+            limit = min(args.byte_limit, limit)
+        size = details["size_bytes"]
+        read = await self._read_bytes(
+            file_path, args.byte_offset or 0, limit, details["encoding"]
+        )
+        more = read.end < size
+        hint = (
+            f"Showing bytes {read.start}-{read.end} of {size}. Call read_file "
+            f"again with byte_offset={read.end} to continue."
+            if more
+            else None
+        )
+        return ReadFileResult(
+            path=str(file_path),
+            content=read.text,
+            lines_read=len(read.text.splitlines()),
+            was_truncated=more and limit == self.config.max_read_bytes,
+            byte_offset=read.start,
+            next_byte_offset=read.end if more else None,
+            hint=hint,
+            **details,
+        )
+
+    async def _read_bytes(
+        self, file_path: Path, offset: int, limit: int, encoding: str
+    ) -> _ByteReadResult:
+        try:
+            async with await anyio.open_file(file_path, "rb") as f:
+                await f.seek(offset)
+                data = await f.read(limit)
+                at_end = not await f.read(1)
+        except OSError as exc:
+            raise ToolError(f"Error reading {file_path}: {exc}") from exc
+
+        start = 0
+        if codecs.lookup(encoding).name.startswith("utf-8"):
+            # Start at a character boundary rather than inside one.
+            while (
+                start < min(len(data), _MAX_UTF8_CONTINUATION_BYTES)
+                and data[start] & _UTF8_CONTINUATION_MASK == _UTF8_CONTINUATION
+            ):
+                start += 1
+        decoder = codecs.getincrementaldecoder(encoding)(errors="replace")
+        text = decoder.decode(data[start:], final=at_end)
+        # A character cut off by the limit is left for the next read.
+        pending, _ = decoder.getstate()
+        return _ByteReadResult(
+            text=text, start=offset + start, end=offset + len(data) - len(pending)
+        )
+
+    def _resolve_window(self, args: ReadFileArgs, file_path: Path) -> ReadFileArgs:
+        offset, limit = args.offset, args.limit
+        if args.cursor:
+            offset = decode_cursor(args.cursor, file_path)
+        elif args.start_line is not None:
+            offset = args.start_line - 1
+
+        if args.end_line is not None:
+            limit = args.end_line - offset
+            if limit <= 0:
+                raise ToolError("end_line must not be before the first line read")
+
+        return args.model_copy(
+            update={
+                "offset": offset,
+                "limit": limit,
+                "start_line": None,
+                "end_line": None,
+                "cursor": None,
+            }
+        )
+
+    @staticmethod
+    def _pagination_hint(
+        offset: int, next_offset: int, total_lines: int | None, cursor: str | None
+    ) -> str | None:
+        if cursor is None:
+            return None
+        of_total = f" of {total_lines}" if total_lines is not None else ""
+        return (
+            f"Showing lines {offset + 1}-{next_offset}{of_total}. "
+            f'Call read_file again with cursor="{cursor}" to continue.'
         )
 
-    def check_allowlist_denylist(self, args: ReadFileArgs) -> ToolPermission | None:
+    def check_allowlist_denylist(
+        self, args: ReadFileArgs, cwd: Path | None = None
+    ) -> ToolPermission | None:
         import fnmatch
 
-        file_path = Path(args.path).expanduser()
-        if not file_path.is_absolute():
-            file_path = Path.cwd() / file_path
-        file_str = str(file_path)
+        file_str = str(absolute_path(args.path, cwd))
 
         for pattern in self.config.denylist:
             if fnmatch.fnmatch(file_str, pattern):
@@ -107,14 +416,12 @@ class ReadFile(
 
         return None
 
-    def _prepare_and_validate_path(self, args: ReadFileArgs) -> Path:
+    def _prepare_and_validate_path(self, args: ReadFileArgs, cwd: Path) -> Path:
         self._validate_inputs(args)
 
-        file_path = Path(args.path).expanduser()
-        if not file_path.is_absolute():
-            file_path = Path.cwd() / file_path
+        file_path = absolute_path(args.path, cwd)
 
-        self._validate_path(file_path)
+        self._validate_path(file_path, cwd)
         return file_path
 
     async def _read_file(self, args: ReadFileArgs, file_path: Path) -> _ReadResult:
@@ -122,32 +429,37 @@ class ReadFile(
             lines_to_return: list[str] = []
             bytes_read = 0
             was_truncated = False
+            # The line count is only known when the window reaches the end of
+            # the file; paging doesn't scan the rest of it to find out.
+            at_eof = True
 
             async with await anyio.Path(file_path).open(
-                encoding="utf-8", errors="ignore"
+                encoding=args.encoding or "utf-8", errors="replace"
             ) as f:
                 line_index = 0
                 async for line in f:
-                    if line_index < args.offset:
-                        line_index += 1
+                    line_index += 1
+                    if line_index <= args.offset:
                         continue
 
                     if args.limit is not None and len(lines_to_return) >= args.limit:
+                        at_eof = False
                         break
 
                     line_bytes = len(line.encode("utf-8"))
                     if bytes_read + line_bytes > self.config.max_read_bytes:
                         was_truncated = True
+                        at_eof = False
                         break
 
                     lines_to_return.append(line)
                     bytes_read += line_bytes
-                    line_index += 1
 
             return _ReadResult(
                 lines=lines_to_return,
                 bytes_read=bytes_read,
                 was_truncated=was_truncated,
+                total_lines=line_index if at_eof else None,
             )
 
         except OSError as exc:
@@ -160,13 +472,33 @@ class ReadFile(
             raise ToolError("Offset cannot be negative")
         if args.limit is not None and args.limit <= 0:
             raise ToolError("Limit, if provided, must be a positive number")
-
-    def _validate_path(self, file_path: Path) -> None:
+        if args.start_line is not None and args.start_line < 1:
+            raise ToolError("start_line must be 1 or greater")
+        if args.cursor and args.start_line is not None:
+            raise ToolError("Use either cursor or start_line, not both")
+        if args.byte_offset is not None or args.byte_limit is not None:
+            if args.byte_offset is not None and args.byte_offset < 0:
+                raise ToolError("byte_offset cannot be negative")
+            if args.byte_limit is not None and args.byte_limit <= 0:
+                raise ToolError("byte_limit, if provided, must be a positive number")
+            if (
+                args.offset
+                or args.limit is not None
+                or args.start_line is not None
+                or args.end_line is not None
+                or args.cursor
+            ):
+                raise ToolError(
+                    "byte_offset and byte_limit can't be combined with line "
+                    "positions (offset, limit, start_line, end_line, cursor)"
+                )
+
+    def _validate_path(self, file_path: Path, cwd: Path) -> None:
         try:
             resolved_path = file_path.resolve()
         except ValueError:
             raise ToolError(
-                f"Security error: Cannot read path '{file_path}' outside of the project directory '{Path.cwd()}'."
+                f"Security error: Cannot read path '{file_path}' outside of the project directory '{cwd}'."
             )
         except FileNotFoundError:
             raise ToolError(f"File not found at: {file_path}")
@@ -186,13 +518,24 @@ class ReadFile(
         if not isinstance(event.args, ReadFileArgs):
             return ToolCallDisplay(summary="read_file")
 
-        summary = f"Reading {event.args.path}"
-        if event.args.offset > 0 or event.args.limit is not None:
-            parts = []
-            if event.args.offset > 0:
-                parts.append(f"from line {event.args.offset}")
-            if event.args.limit is not None:
-                parts.append(f"limit {event.args.limit} lines")
+        args = event.args
+        summary = f"Reading {args.path}"
+        parts = []
+        if args.byte_offset is not None:
+            parts.append(f"from byte {args.byte_offset}")
+        elif args.cursor:
+            parts.append("continuing")
+        elif args.start_line is not None:
+            parts.append(f"from line {args.start_line}")
+        elif args.offset > 0:
+            parts.append(f"from line {args.offset}")
+        if args.end_line is not None:
+            parts.append(f"to line {args.end_line}")
+        elif args.limit is not None:
+            parts.append(f"limit {args.limit} lines")
+        elif args.byte_limit is not None:
+            parts.append(f"limit {args.byte_limit} bytes")
+        if parts:
             summary += f" ({', '.join(parts)})"
 
         return ToolCallDisplay(summary=summary)
@@ -205,9 +548,20 @@ class ReadFile(
             )
 
         path_obj = Path(event.result.path)
+        if event.result.images:
+            return ToolResultDisplay(
+                success=True, message=f"Attached {path_obj.name} as an image"
+            )
+        if event.result.binary:
+            return ToolResultDisplay(
+                success=True,
+                message=f"{path_obj.name} is binary ({event.result.mime})",
+            )
         message = f"Read {event.result.lines_read} line{'' if event.result.lines_read <= 1 else 's'} from {path_obj.name}"
         if event.result.was_truncated:
             message += " (truncated)"
+        elif event.result.next_cursor:
+            message += " (more available)"
 
         return ToolResultDisplay(
             success=True,
@@ -16,7 +16,10 @@ from rune.core.tools.base import (
     BaseToolState,
     InvokeContext,
     ToolError,
+    absolute_path,
+    working_dir,
 )
+from rune.core.tools.file_guards import DEFAULT_MAX_FILE_BYTES, guard_text_file
 from rune.core.tools.ui import ToolCallDisplay, ToolResultDisplay, ToolUIData
 from rune.core.types import ToolCallEvent, ToolResultEvent, ToolStreamEvent
 
@@ -66,6 +69,7 @@ class SearchReplaceConfig(BaseToolConfig):
     max_content_size: int = 100_000
     create_backup: bool = False
     fuzzy_threshold: float = 0.9
+    max_file_bytes: int = DEFAULT_MAX_FILE_BYTES
 
 
 class SearchReplaceState(BaseToolState):
@@ -112,11 +116,20 @@ class SearchReplace(
     def get_status_text(cls) -> str:
         return "Editing files"
 
+    def get_write_target(
+        self, args: SearchReplaceArgs, cwd: Path | None = None
+    ) -> Path | None:
+        if not args.file_path.strip():
+            return None
+        return absolute_path(args.file_path.strip(), cwd).resolve()
+
     @final
     async def run(
         self, args: SearchReplaceArgs, ctx: InvokeContext | None = None
     ) -> AsyncGenerator[ToolStreamEvent | SearchReplaceResult, None]:
-        file_path, search_replace_blocks = self._prepare_and_validate_args(args)
+        file_path, search_replace_blocks = self._prepare_and_validate_args(
+            args, working_dir(ctx)
+        )
 
         original_content = await self._read_file(file_path)
 
@@ -165,7 +178,7 @@ class SearchReplace(
 
     @final
     def _prepare_and_validate_args(
-        self, args: SearchReplaceArgs
+        self, args: SearchReplaceArgs, cwd: Path
     ) -> tuple[Path, list[SearchReplaceBlock]]:
         file_path_str = args.file_path.strip()
         content = args.content.strip()
@@ -182,11 +195,7 @@ class SearchReplace(
         if not content:
             raise ToolError("Empty content provided")
 
-        project_root = Path.cwd()
-        file_path = Path(file_path_str).expanduser()
-        if not file_path.is_absolute():
-            file_path = project_root / file_path
-        file_path = file_path.resolve()
+        file_path = absolute_path(file_path_str, cwd).resolve()
 
         if not file_path.exists():
             raise ToolError(f"File does not exist: {file_path}")
@@ -194,6 +203,8 @@ class SearchReplace(
         if not file_path.is_file():
             raise ToolError(f"Path is not a file: {file_path}")
 
+        guard_text_file(file_path, action="edit", max_bytes=self.config.max_file_bytes)
+
         search_replace_blocks = self._parse_search_replace_blocks(content)
         if not search_replace_blocks:
             raise ToolError(
@@ -0,0 +1,233 @@
+from __future__ import annotations
+
+from collections.abc import AsyncGenerator
+from pathlib import Path
+from typing import TYPE_CHECKING, Any, ClassVar
+
+import anyio
+from pydantic import BaseModel, Field, model_validator
+
+from rune.core.tools.base import (
+    BaseTool,
+    BaseToolConfig,
+    BaseToolState,
+    InvokeContext,
+    ToolError,
+    ToolPermission,
+    working_dir,
+)
+from rune.core.tools.databases import (
+    Database,
+    DatabaseError,
+    TableInfo,
+    open_database,
+    to_json_value,
+)
+from rune.core.tools.ui import ToolCallDisplay, ToolResultDisplay, ToolUIData
+from rune.core.types import ToolStreamEvent
+
+if TYPE_CHECKING:
+    from rune.core.types import ToolCallEvent, ToolResultEvent
+
+
+class SqlConnection(BaseModel):
+    url: str = Field(
+        description="sqlite:///path/to/file.db (relative to the session's working "
+        "directory) or postgres://user@host/db; ${secret:NAME} references are "
+        "resolved on connect."
+    )
+    read_only: bool = True
+    description: str = Field(
+        default="", description="What the database holds, shown to the model."
+    )
+
+
+class SqlQueryToolConfig(BaseToolConfig):
+    """Settings of the `sql_query` tool, read from `[tools.sql]`.
+
+    Every table under it, such as `[tools.sql.analytics]`, declares a
+    connection.
+    """
+
+    permission: ToolPermission = ToolPermission.ASK
+    connections: dict[str, SqlConnection] = Field(default_factory=dict)
+    max_rows: int = Field(default=200, description="Rows returned per query.")
+    max_cell_chars: int = Field(
+        default=1_000, description="Characters kept of each text value."
+    )
+    timeout: float = Field(
+        default=30.0, description="Seconds a query may run before it is stopped."
+    )
+
+    @model_validator(mode="before")
+    @classmethod
+    def _collect_connections(cls, data: Any) -> Any:
+        if not isinstance(data, dict):
+            return data
+        connections = dict(data.get("connections") or {})
+        settings = {}
+        for key, value in data.items():
+            if key not in cls.model_fields and isinstance(value, dict):
+                connections[key] = value
+            else:
+                settings[key] = value
+        return {**settings, "connections": connections}
+
+
+class SqlQueryArgs(BaseModel):
+    connection: str | None = Field(
+        default=None,
+        description="Name of the connection to use. Optional when only one is "
+        "configured.",
+    )
+    query: str | None = Field(
+        default=None,
+        description="One SQL statement to run. Leave out to list the tables "
+        "and their columns.",
+    )
+    table: str | None = Field(
+        default=None, description="Without a query: describe only this table."
+    )
+    max_rows: int | None = Field(
+        default=None, description="Return fewer rows than the configured limit."
+    )
+
+
+class SqlQueryResult(BaseModel):
+    connection: str
+    read_only: bool
+    columns: list[str] = Field(default_factory=list)
+    rows: list[list[Any]] = Field(default_factory=list)
+    row_count: int = Field(
+        default=0,
+        description="Rows returned, or changed by a statement that returns none.",
+    )
+    truncated: bool = Field(
+        default=False, description="The query returned more rows than were kept."
+    )
+    tables: list[TableInfo] | None = None
+
+
+class SqlQuery(
+    BaseTool[SqlQueryArgs, SqlQueryResult, SqlQueryToolConfig, BaseToolState],
+    ToolUIData[SqlQueryArgs, SqlQueryResult],
+):
+    description: ClassVar[str] = (
+        "Query a database configured by the user, or list its tables and "
+        "columns. Results are limited to a number of rows."
+    )
+
+    @classmethod
+    def get_config_name(cls) -> str:
+        return "sql"
+
+    async def run(
+        self, args: SqlQueryArgs, ctx: InvokeContext | None = None
+    ) -> AsyncGenerator[ToolStreamEvent | SqlQueryResult, None]:
+        name, connection = self._connection(args.connection)
+        try:
+            result = await anyio.to_thread.run_sync(
+                self._run_sync, name, connection, args, working_dir(ctx)
+            )
+        except DatabaseError as e:
+            raise ToolError(f"{name}: {e}") from e
+        yield result
+
+    def _connection(self, name: str | None) -> tuple[str, SqlConnection]:
+        connections = self.config.connections
+        if not connections:
+            raise ToolError(
+                "No databases are configured; add one as "
+                '[tools.sql.<name>] with url = "..."'
+            )
+        if name is None and len(connections) == 1:
+            return next(iter(connections.items()))
+        if name in connections:
+            return name, connections[name]
+        available = ", ".join(
+            f"{key} ({c.description})" if c.description else key
+            for key, c in connections.items()
+        )
+        if name is None:
+            raise ToolError(f"Pick a connection: {available}")
+        raise ToolError(f"Unknown connection {name!r}; configured: {available}")
+
+    def _run_sync(
+        self, name: str, connection: SqlConnection, args: SqlQueryArgs, cwd: Path
+    ) -> SqlQueryResult:
+        database = open_database(
+            connection.url,
+            cwd=cwd,
+            read_only=connection.read_only,
+            timeout=self.config.timeout,
+        )
+        try:
+            return self._execute(database, name, connection, args)
+        finally:
+            database.close()
+
+    def _execute(
+        self,
+        database: Database,
+        name: str,
+        connection: SqlConnection,
+        args: SqlQueryArgs,
+    ) -> SqlQueryResult:
+        if not args.query:
+            tables = database.tables(args.table)
+            if args.table and not tables:
+                raise DatabaseError(f"No table named {args.table}")
+            return SqlQueryResult(
+                connection=name, read_only=connection.read_only, tables=tables
+            )
+
+        max_rows = min(args.max_rows or self.config.max_rows, self.config.max_rows)
+        rows = database.query(args.query, max_rows)
+        limit = self.config.max_cell_chars
+        return SqlQueryResult(
+            connection=name,
+            read_only=connection.read_only,
+            columns=rows.columns,
+            rows=[[to_json_value(v, limit) for v in row] for row in rows.rows],
+            row_count=rows.row_count,
+            truncated=rows.truncated,
+        )
+
+    @classmethod
+    def get_call_display(cls, event: ToolCallEvent) -> ToolCallDisplay:
+        if not isinstance(event.args, SqlQueryArgs):
+            return ToolCallDisplay(summary="sql_query")
+        args = event.args
+        target = args.connection or "database"
+        if not args.query:
+            return ToolCallDisplay(
+                summary=f"Describing {args.table or 'tables'} in {target}"
+            )
+        query = " ".join(args.query.split())
+        if len(query) > 80:
+            query = query[:77] + "..."
+        return ToolCallDisplay(summary=f"{target}: {query}")
+
+    @classmethod
+    def get_result_display(cls, event: ToolResultEvent) -> ToolResultDisplay:
+        if not isinstance(event.result, SqlQueryResult):
+            return ToolResultDisplay(
+                success=False, message=event.error or event.skip_reason or "No result"
+            )
+        result = event.result
+        if result.tables is not None:
+            return ToolResultDisplay(
+                success=True, message=f"{len(result.tables)} table(s)"
+            )
+        warnings = (
+            [f"Only the first {result.row_count} rows were returned"]
+            if result.truncated
+            else []
+        )
+        return ToolResultDisplay(
+            success=True, message=f"{result.row_count} row(s)", warnings=warnings
+        )
+
+    @classmethod
+    def get_status_text(cls) -> str:
+        return "Querying the database"
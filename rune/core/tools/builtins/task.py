@@ -8,6 +8,7 @@ from pydantic import BaseModel, Field
 from rune.core.agent_loop import AgentLoop
 from rune.core.agents.models import AgentType
 from rune.core.config import RuneConfig, SessionLoggingConfig
+from rune.core.paths.config_paths import use_project_dir
 from rune.core.tools.base import (
     BaseTool,
     BaseToolConfig,
@@ -15,6 +16,7 @@ from rune.core.tools.base import (
     InvokeContext,
     ToolError,
     ToolPermission,
+    working_dir,
 )
 from rune.core.tools.ui import (
     ToolCallDisplay,
@@ -107,10 +109,12 @@ class Task(
                 f"This is a security constraint to prevent recursive spawning."
             )
 
-        base_config = RuneConfig.load(
-            session_logging=SessionLoggingConfig(enabled=False)
-        )
-        subagent_loop = AgentLoop(config=base_config, agent_name=args.agent)
+        cwd = working_dir(ctx)
+        with use_project_dir(cwd):
+            base_config = RuneConfig.load(
+                session_logging=SessionLoggingConfig(enabled=False)
+            )
+        subagent_loop = AgentLoop(config=base_config, agent_name=args.agent, cwd=cwd)
 
         if ctx and ctx.approval_callback:
             subagent_loop.set_approval_callback(ctx.approval_callback)
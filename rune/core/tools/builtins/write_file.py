@@ -14,8 +14,11 @@ from rune.core.tools.base import (
     InvokeContext,
     ToolError,
     ToolPermission,
+    absolute_path,
+    working_dir,
 )
 from rune.core.tools.ui import ToolCallDisplay, ToolResultDisplay, ToolUIData
+from rune.core.tools.write_roots import WriteRootGrants
 from rune.core.types import ToolCallEvent, ToolResultEvent, ToolStreamEvent
 
 
@@ -78,13 +81,12 @@ class WriteFile(
     def get_status_text(cls) -> str:
         return "Writing file"
 
-    def check_allowlist_denylist(self, args: WriteFileArgs) -> ToolPermission | None:
+    def check_allowlist_denylist(
+        self, args: WriteFileArgs, cwd: Path | None = None
+    ) -> ToolPermission | None:
         import fnmatch
 
-        file_path = Path(args.path).expanduser()
-        if not file_path.is_absolute():
-            file_path = Path.cwd() / file_path
-        file_str = str(file_path)
+        file_str = str(absolute_path(args.path, cwd))
 
         for pattern in self.config.denylist:
             if fnmatch.fnmatch(file_str, pattern):
@@ -96,11 +98,20 @@ class WriteFile(
 
         return None
 
+    def get_write_target(
+        self, args: WriteFileArgs, cwd: Path | None = None
+    ) -> Path | None:
+        if not args.path.strip():
+            return None
+        return absolute_path(args.path, cwd).resolve()
+
     @final
     async def run(
         self, args: WriteFileArgs, ctx: InvokeContext | None = None
     ) -> AsyncGenerator[ToolStreamEvent | WriteFileResult, None]:
-        file_path, file_existed, content_bytes = self._prepare_and_validate_path(args)
+        file_path, file_existed, content_bytes = self._prepare_and_validate_path(
+            args, working_dir(ctx), ctx.write_roots if ctx else None
+        )
 
         await self._write_file(args, file_path)
 
@@ -116,7 +127,9 @@ class WriteFile(
             content=args.content,
         )
 
-    def _prepare_and_validate_path(self, args: WriteFileArgs) -> tuple[Path, bool, int]:
+    def _prepare_and_validate_path(
+        self, args: WriteFileArgs, cwd: Path, write_roots: WriteRootGrants | None = None
+    ) -> tuple[Path, bool, int]:
         if not args.path.strip():
             raise ToolError("Path cannot be empty")
 
@@ -126,14 +139,10 @@ class WriteFile(
                 f"Content exceeds {self.config.max_write_bytes} bytes limit"
             )
 
-        file_path = Path(args.path).expanduser()
-        if not file_path.is_absolute():
-            file_path = Path.cwd() / file_path
-        file_path = file_path.resolve()
+        file_path = absolute_path(args.path, cwd).resolve()
 
-        try:
-            file_path.relative_to(Path.cwd().resolve())
-        except ValueError:
+        write_roots = write_roots or WriteRootGrants(lambda: cwd)
+        if not write_roots.allows(file_path):
             raise ToolError(f"Cannot write outside project directory: {file_path}")
 
         file_existed = file_path.exists()
@@ -0,0 +1,90 @@
+from __future__ import annotations
+
+from collections.abc import Callable
+import json
+from pathlib import Path
+from stat import S_ISREG
+from typing import TYPE_CHECKING, Any
+
+from pydantic import BaseModel
+
+from rune.core.agents.models import READ_ONLY_TOOLS
+
+if TYPE_CHECKING:
+    from rune.core.config import ToolCacheConfig
+
+
+class ToolCallCache:
+    """Results of read-only tool calls, so a call repeated with the same
+    arguments is answered without running the tool again.
+
+    It is cleared whenever the workspace may have changed: when a tool that
+    isn't read-only ran (see `forget_after`), or the caller clears it because
+    the user sent a message.
+    """
+
+    def __init__(self, config_getter: Callable[[], ToolCacheConfig]) -> None:
+        self._config_getter = config_getter
+        self._entries: dict[str, tuple[int, BaseModel]] = {}
+        self._turn = 0
+
+    def key(self, tool_name: str, args: dict[str, Any], cwd: Path) -> str | None:
+        """The key of a call, None unless the config caches it."""
+        config = self._config_getter()
+        if not config.enabled or tool_name not in config.tools:
+            return None
+        return cache_key(tool_name, args, cwd)
+
+    def next_turn(self) -> None:
+        self._turn += 1
+
+    def clear(self) -> None:
+        self._entries.clear()
+
+    def get(self, key: str) -> BaseModel | None:
+        """The result stored under ``key``, unless it was stored ``max_turns``
+        or more turns ago.
+        """
+        if (entry := self._entries.get(key)) is None:
+            return None
+        turn, result = entry
+        if self._turn - turn >= self._config_getter().max_turns:
+            del self._entries[key]
+            return None
+        return result
+
+    def put(self, key: str, result: BaseModel) -> None:
+        self._entries[key] = (self._turn, result)
+
+    def forget_after(self, tool_name: str) -> None:
+        """Clear the cache once a call of ``tool_name`` is done, unless the tool
+        only reads.
+        """
+        if tool_name not in READ_ONLY_TOOLS:
+            self.clear()
+
+
+def cache_key(tool_name: str, args: dict[str, Any], cwd: Path) -> str | None:
+    """A call's tool and arguments, with the state of what it reads: the
+    thread's working directory `cwd` and the size and modification time of the
+    file its `path` argument names.
+
+    Calls without a file to check aren't cached: a directory's time doesn't
+    move when a file under it is edited, so a search of it could go stale.
+    """
+    if not isinstance(path := args.get("path"), str):
+        return None
+    if (stat := _file_stat(cwd / Path(path).expanduser())) is None:
+        return None
+    key = {"tool": tool_name, "args": args, "cwd": str(cwd), "stat": stat}
+    return json.dumps(key, sort_keys=True, default=str)
+
+
+def _file_stat(path: Path) -> list[int] | None:
+    try:
+        stat = path.stat()
+    except OSError:
+        return None
+    if not S_ISREG(stat.st_mode):
+        return None
+    return [stat.st_mtime_ns, stat.st_size]
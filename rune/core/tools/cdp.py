@@ -0,0 +1,297 @@
+from __future__ import annotations
+
+import asyncio
+import atexit
+from collections import deque
+import contextlib
+from datetime import UTC, datetime
+import itertools
+import json
+from pathlib import Path
+import shutil
+import subprocess
+import sys
+import tempfile
+import time
+from typing import Any
+
+from rune.core.utils import logger
+
+_EXECUTABLES = (
+    "chromium",
+    "chromium-browser",
+    "google-chrome",
+    "google-chrome-stable",
+    "chrome",
+    "microsoft-edge",
+    "msedge",
+)
+_APP_PATHS = {
+    "darwin": (
+        "/Applications/Google Chrome.app/Contents/MacOS/Google Chrome",
+        "/Applications/Chromium.app/Contents/MacOS/Chromium",
+        "/Applications/Microsoft Edge.app/Contents/MacOS/Microsoft Edge",
+    ),
+    "win32": (
+        r"C:\Program Files\Google\Chrome\Application\chrome.exe",
+        r"C:\Program Files (x86)\Google\Chrome\Application\chrome.exe",
+        r"C:\Program Files (x86)\Microsoft\Edge\Application\msedge.exe",
+    ),
+}
+# Console messages kept between two tool calls.
+MAX_CONSOLE_MESSAGES = 200
+
+
+class CDPError(Exception):
+    """Raised when Chromium can't be started or a DevTools command fails."""
+
+
+def find_chromium(configured: str | None = None) -> str:
+    if configured:
+        if found := shutil.which(configured) or (
+            configured if Path(configured).is_file() else None
+        ):
+            return found
+        raise CDPError(f"Browser executable not found: {configured}")
+    for name in _EXECUTABLES:
+        if found := shutil.which(name):
+            return found
+    for path in _APP_PATHS.get(sys.platform, ()):
+        if Path(path).is_file():
+            return path
+    raise CDPError(
+        "No Chromium or Chrome found; install one or set `executable` in "
+        "[tools.browser]"
+    )
+
+
+class Chromium:
+    """A Chromium process driven over the DevTools protocol, with one page
+    that every command goes to.
+    """
+
+    def __init__(
+        self, process: subprocess.Popen[bytes], profile_dir: Path, timeout: float
+    ) -> None:
+        self._process = process
+        self.started_at = datetime.now(UTC)
+        self._profile_dir = profile_dir
+        self._timeout = timeout
+        self._ids = itertools.count(1)
+        self._pending: dict[int, asyncio.Future[dict[str, Any]]] = {}
+        self._session_id: str | None = None
+        self._loaded = asyncio.Event()
+        self._console: deque[str] = deque(maxlen=MAX_CONSOLE_MESSAGES)
+        self._websocket: Any = None
+        self._reader: asyncio.Task[None] | None = None
+        atexit.register(self._kill)
+
+    @classmethod
+    async def launch(
+        cls,
+        executable: str,
+        *,
+        headless: bool = True,
+        width: int = 1280,
+        height: int = 800,
+        timeout: float = 30.0,
+    ) -> Chromium:
+        try:
+            from websockets.asyncio.client import connect
+        except ImportError as e:
+            raise CDPError(
+                'websockets is not installed; run `pip install "rune-cli[browser]"`'
+            ) from e
+
+        profile_dir = Path(tempfile.mkdtemp(prefix="rune-browser-"))
+        args = [
+            executable,
+            "--remote-debugging-port=0",
+            f"--user-data-dir={profile_dir}",
+            f"--window-size={width},{height}",
+            "--no-first-run",
+            "--no-default-browser-check",
+            "--disable-extensions",
+            "about:blank",
+        ]
+        if headless:
+            args.insert(1, "--headless=new")
+        try:
+            process = subprocess.Popen(
+                args, stdout=subprocess.DEVNULL, stderr=subprocess.DEVNULL
+            )
+        except OSError as e:
+            shutil.rmtree(profile_dir, ignore_errors=True)
+            raise CDPError(f"Could not start {executable}: {e}") from e
+
+        browser = cls(process, profile_dir, timeout)
+        try:
+            url = await browser._wait_for_endpoint()
+            browser._websocket = await connect(url, max_size=None)
+            browser._reader = asyncio.create_task(browser._read())
+            await browser._open_page(width, height)
+        except Exception:
+            await browser.close()
+            raise
+        return browser
+
+    @property
+    def alive(self) -> bool:
+        return self._process.poll() is None and self._session_id is not None
+
+    @property
+    def pid(self) -> int:
+        return self._process.pid
+
+    async def _wait_for_endpoint(self) -> str:
+        # Chromium writes the port it picked, and the browser target's path,
+        # to this file once it listens.
+        port_file = self._profile_dir / "DevToolsActivePort"
+        deadline = time.monotonic() + self._timeout
+        while time.monotonic() < deadline:
+            if self._process.poll() is not None:
+                raise CDPError(
+                    f"The browser exited on start with code {self._process.returncode}"
+                )
+            with contextlib.suppress(OSError, ValueError):
+                port, path = port_file.read_text("utf-8").split()[:2]
+                return f"ws://127.0.0.1:{int(port)}{path}"
+            await asyncio.sleep(0.1)
+        raise CDPError("The browser didn't open its DevTools port in time")
+
+    async def _open_page(self, width: int, height: int) -> None:
+        target = await self.send("Target.createTarget", {"url": "about:blank"})
+        attached = await self.send(
+            "Target.attachToTarget", {"targetId": target["targetId"], "flatten": True}
+        )
+        self._session_id = attached["sessionId"]
+        await self.send("Page.enable")
+        await self.send("Runtime.enable")
+        await self.send(
+            "Emulation.setDeviceMetricsOverride",
+            {"width": width, "height": height, "deviceScaleFactor": 1, "mobile": False},
+        )
+
+    async def send(
+        self, method: str, params: dict[str, Any] | None = None
+    ) -> dict[str, Any]:
+        """Runs a DevTools command: on the page once it is open, on the browser
+        before.
+        """
+        message: dict[str, Any] = {
+            "id": next(self._ids),
+            "method": method,
+            "params": params or {},
+        }
+        if self._session_id is not None:
+            message["sessionId"] = self._session_id
+        future = asyncio.get_running_loop().create_future()
+        self._pending[message["id"]] = future
+        try:
+            await self._websocket.send(json.dumps(message))
+            return await asyncio.wait_for(future, self._timeout)
+        except TimeoutError as e:
+            raise CDPError(f"The browser didn't answer {method} in time") from e
+        finally:
+            self._pending.pop(message["id"], None)
+
+    async def _read(self) -> None:
+        try:
+            async for raw in self._websocket:
+                message = json.loads(raw)
+                if "id" in message:
+                    future = self._pending.get(message["id"])
+                    if future is None or future.done():
+                        continue
+                    if error := message.get("error"):
+                        future.set_exception(CDPError(error.get("message", error)))
+                    else:
+                        future.set_result(message.get("result", {}))
+                else:
+                    self._on_event(message.get("method", ""), message.get("params", {}))
+        except Exception as e:
+            logger.debug("DevTools connection lost: %s", e)
+        finally:
+            self._session_id = None
+            for future in self._pending.values():
+                if not future.done():
+                    future.set_exception(CDPError("The browser closed the connection"))
+
+    def _on_event(self, method: str, params: dict[str, Any]) -> None:
+        match method:
+            case "Page.loadEventFired":
+                self._loaded.set()
+            case "Runtime.consoleAPICalled":
+                values = " ".join(_describe(arg) for arg in params.get("args", []))
+                self._console.append(f"{params.get('type', 'log')}: {values}")
+            case "Runtime.exceptionThrown":
+                details = params.get("exceptionDetails", {})
+                exception = details.get("exception") or {}
+                text = exception.get("description") or details.get("text", "")
+                self._console.append(f"exception: {text}")
+
+    async def navigate(self, url: str) -> None:
+        self._loaded.clear()
+        result = await self.send("Page.navigate", {"url": url})
+        if error := result.get("errorText"):
+            raise CDPError(f"Could not load {url}: {error}")
+        # Pages that keep loading are read as they are when the time is up.
+        with contextlib.suppress(TimeoutError):
+            await asyncio.wait_for(self._loaded.wait(), self._timeout)
+
+    async def evaluate(self, expression: str) -> Any:
+        result = await self.send(
+            "Runtime.evaluate",
+            {"expression": expression, "returnByValue": True, "awaitPromise": True},
+        )
+        if details := result.get("exceptionDetails"):
+            exception = details.get("exception") or {}
+            raise CDPError(exception.get("description") or details.get("text", ""))
+        return result.get("result", {}).get("value")
+
+    async def screenshot(self) -> str:
+        """The visible part of the page, as base64 PNG data."""
+        result = await self.send("Page.captureScreenshot", {"format": "png"})
+        return result["data"]
+
+    async def click(self, x: float, y: float) -> None:
+        for event in ("mouseMoved", "mousePressed", "mouseReleased"):
+            await self.send(
+                "Input.dispatchMouseEvent",
+                {"type": event, "x": x, "y": y, "button": "left", "clickCount": 1},
+            )
+
+    async def insert_text(self, text: str) -> None:
+        await self.send("Input.insertText", {"text": text})
+
+    def take_console(self) -> list[str]:
+        """Console messages and uncaught exceptions since the last call."""
+        messages = list(self._console)
+        self._console.clear()
+        return messages
+
+    async def close(self) -> None:
+        if self._websocket is not None:
+            with contextlib.suppress(Exception):
+                self._session_id = None
+                await asyncio.wait_for(self.send("Browser.close"), 5)
+            with contextlib.suppress(Exception):
+                await self._websocket.close()
+        if self._reader is not None:
+            self._reader.cancel()
+        self._kill()
+        atexit.unregister(self._kill)
+
+    def _kill(self) -> None:
+        if self._process.poll() is None:
+            self._process.kill()
+            with contextlib.suppress(subprocess.TimeoutExpired):
+                self._process.wait(5)
+        shutil.rmtree(self._profile_dir, ignore_errors=True)
+
+
+def _describe(remote_object: dict[str, Any]) -> str:
+    if "value" in remote_object:
+        value = remote_object["value"]
+        return value if isinstance(value, str) else json.dumps(value)
+    return remote_object.get("description") or remote_object.get("type", "")
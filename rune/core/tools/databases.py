@@ -0,0 +1,275 @@
+from __future__ import annotations
+
+from abc import ABC, abstractmethod
+import contextlib
+import datetime
+from decimal import Decimal
+from pathlib import Path
+import sqlite3
+import time
+from typing import Any, NamedTuple
+from urllib.parse import unquote, urlsplit
+
+from pydantic import BaseModel
+
+from rune.core.secrets import resolve_secret_refs
+
+_SQLITE_SCHEME = "sqlite"
+_POSTGRES_SCHEMES = {"postgres", "postgresql"}
+# SQLite instructions between two checks of the deadline.
+_SQLITE_PROGRESS_STEPS = 10_000
+
+
+class DatabaseError(Exception):
+    """Raised when a database can't be opened or a query fails."""
+
+
+class ColumnInfo(BaseModel):
+    name: str
+    type: str
+    nullable: bool = True
+    primary_key: bool = False
+
+
+class TableInfo(BaseModel):
+    name: str
+    columns: list[ColumnInfo]
+
+
+class QueryRows(NamedTuple):
+    columns: list[str]
+    rows: list[list[Any]]
+    truncated: bool
+    row_count: int
+
+
+def to_json_value(value: Any, max_chars: int) -> Any:
+    """A cell value as something JSON can hold, long text shortened."""
+    match value:
+        case None | bool() | int() | float():
+            return value
+        case bytes() | bytearray() | memoryview():
+            return f"<{len(bytes(value))} bytes>"
+        case Decimal():
+            return str(value)
+        case datetime.date() | datetime.time():
+            return value.isoformat()
+        case _:
+            text = value if isinstance(value, str) else str(value)
+            if len(text) > max_chars:
+                return text[:max_chars] + f"... [{len(text) - max_chars} more chars]"
+            return text
+
+
+class Database(ABC):
+    """A connection opened for one call of the sql_query tool."""
+
+    @abstractmethod
+    def query(self, sql: str, max_rows: int) -> QueryRows: ...
+
+    @abstractmethod
+    def tables(self, name: str | None = None) -> list[TableInfo]: ...
+
+    @abstractmethod
+    def close(self) -> None: ...
+
+
+def _deny_attach(action: int, *_: str | None) -> int:
+    return sqlite3.SQLITE_DENY if action == sqlite3.SQLITE_ATTACH else sqlite3.SQLITE_OK
+
+
+class SqliteDatabase(Database):
+    def __init__(self, path: Path, *, read_only: bool, timeout: float) -> None:
+        if read_only and not path.is_file():
+            raise DatabaseError(f"No SQLite database at {path}")
+        mode = "ro" if read_only else "rwc"
+        try:
+            self._conn = sqlite3.connect(
+                f"{path.resolve().as_uri()}?mode={mode}",
+                uri=True,
+                timeout=timeout,
+                check_same_thread=False,
+            )
+        except sqlite3.Error as e:
+            raise DatabaseError(f"Could not open {path}: {e}") from e
+        if read_only:
+            self._conn.execute("PRAGMA query_only = ON")
+            # ATTACH would open, and create, other files in read-write mode.
+            self._conn.set_authorizer(_deny_attach)
+        self._read_only = read_only
+        deadline = time.monotonic() + timeout
+        self._conn.set_progress_handler(
+            lambda: time.monotonic() > deadline, _SQLITE_PROGRESS_STEPS
+        )
+
+    def query(self, sql: str, max_rows: int) -> QueryRows:
+        try:
+            cursor = self._conn.execute(sql)
+            if cursor.description is None:
+                if not self._read_only:
+                    self._conn.commit()
+                return QueryRows([], [], False, max(cursor.rowcount, 0))
+            columns = [d[0] for d in cursor.description]
+            rows = cursor.fetchmany(max_rows + 1)
+        except sqlite3.OperationalError as e:
+            if str(e) == "interrupted":
+                raise DatabaseError("The query took too long and was stopped") from e
+            raise DatabaseError(str(e)) from e
+        except sqlite3.Error as e:
+            raise DatabaseError(str(e)) from e
+        return QueryRows(
+            columns,
+            [list(row) for row in rows[:max_rows]],
+            len(rows) > max_rows,
+            min(len(rows), max_rows),
+        )
+
+    def tables(self, name: str | None = None) -> list[TableInfo]:
+        try:
+            names = [
+                row[0]
+                for row in self._conn.execute(
+                    "SELECT name FROM sqlite_master WHERE type IN ('table', 'view') "
+                    "AND name NOT LIKE 'sqlite_%' ORDER BY name"
+                )
+                if name is None or row[0] == name
+            ]
+            return [
+                TableInfo(
+                    name=table,
+                    columns=[
+                        ColumnInfo(
+                            name=column[1],
+                            type=column[2],
+                            nullable=not column[3],
+                            primary_key=bool(column[5]),
+                        )
+                        for column in self._conn.execute(
+                            "SELECT * FROM pragma_table_info(?)", (table,)
+                        )
+                    ],
+                )
+                for table in names
+            ]
+        except sqlite3.Error as e:
+            raise DatabaseError(str(e)) from e
+
+    def close(self) -> None:
+        self._conn.close()
+
+
+class PostgresDatabase(Database):
+    def __init__(self, url: str, *, read_only: bool, timeout: float) -> None:
+        try:
+            import psycopg
+        except ImportError as e:
+            raise DatabaseError(
+                'psycopg is not installed; run `pip install "rune-cli[sql]"`'
+            ) from e
+        self._errors = psycopg.Error
+        options = f"-c statement_timeout={int(timeout * 1000)}"
+        if read_only:
+            options += " -c default_transaction_read_only=on"
+        try:
+            self._conn = psycopg.connect(
+                url, connect_timeout=max(int(timeout), 1), options=options
+            )
+        except psycopg.Error as e:
+            raise DatabaseError(f"Could not connect: {e}") from e
+        # Every query runs in a transaction of its own, begun READ ONLY when
+        # the connection is. Queries are prepared, which takes one statement,
+        # so a query can't COMMIT that transaction and go on to write.
+        self._conn.read_only = read_only
+        self._read_only = read_only
+
+    def query(self, sql: str, max_rows: int) -> QueryRows:
+        try:
+            with self._conn.cursor() as cursor:
+                cursor.execute(sql, prepare=True)
+                if cursor.description is None:
+                    row_count = max(cursor.rowcount, 0)
+                    self._finish()
+                    return QueryRows([], [], False, row_count)
+                columns = [d.name for d in cursor.description]
+                rows = cursor.fetchmany(max_rows + 1)
+            self._finish()
+        except self._errors as e:
+            with contextlib.suppress(self._errors):
+                self._conn.rollback()
+            raise DatabaseError(str(e).strip()) from e
+        return QueryRows(
+            columns,
+            [list(row) for row in rows[:max_rows]],
+            len(rows) > max_rows,
+            min(len(rows), max_rows),
+        )
+
+    def _finish(self) -> None:
+        if self._read_only:
+            self._conn.rollback()
+        else:
+            self._conn.commit()
+
+    def tables(self, name: str | None = None) -> list[TableInfo]:
+        sql = (
+            "SELECT c.table_schema, c.table_name, c.column_name, c.data_type, "
+            "c.is_nullable = 'YES', "
+            "EXISTS (SELECT 1 FROM information_schema.key_column_usage k "
+            "JOIN information_schema.table_constraints t "
+            "ON t.constraint_name = k.constraint_name "
+            "AND t.table_schema = k.table_schema "
+            "WHERE t.constraint_type = 'PRIMARY KEY' "
+            "AND k.table_schema = c.table_schema AND k.table_name = c.table_name "
+            "AND k.column_name = c.column_name) "
+            "FROM information_schema.columns c "
+            "WHERE c.table_schema NOT IN ('pg_catalog', 'information_schema') "
+            "ORDER BY c.table_schema, c.table_name, c.ordinal_position"
+        )
+        try:
+            with self._conn.cursor() as cursor:
+                cursor.execute(sql)
+                rows = cursor.fetchall()
+            self._conn.rollback()
+        except self._errors as e:
+            raise DatabaseError(str(e).strip()) from e
+
+        tables: dict[str, TableInfo] = {}
+        for schema, table, column, data_type, nullable, primary_key in rows:
+            qualified = table if schema == "public" else f"{schema}.{table}"
+            if name is not None and name not in {table, qualified}:
+                continue
+            tables.setdefault(qualified, TableInfo(name=qualified, columns=[]))
+            tables[qualified].columns.append(
+                ColumnInfo(
+                    name=column,
+                    type=data_type,
+                    nullable=nullable,
+                    primary_key=primary_key,
+                )
+            )
+        return list(tables.values())
+
+    def close(self) -> None:
+        with contextlib.suppress(self._errors):
+            self._conn.close()
+
+
+def open_database(url: str, *, cwd: Path, read_only: bool, timeout: float) -> Database:
+    """Opens `url`: `sqlite:///relative/path.db`, `sqlite:////absolute/path.db`
+    or a `postgres://` URL, with `${secret:NAME}` references resolved.
+
+    A relative SQLite path is resolved against `cwd`, the working directory of
+    the session running the query, not the process's.
+    """
+    url = resolve_secret_refs(url)
+    scheme = urlsplit(url).scheme.lower()
+    if scheme == _SQLITE_SCHEME:
+        if not url.startswith("sqlite:///"):
+            raise DatabaseError("SQLite URLs look like sqlite:///path/to/file.db")
+        path = cwd / Path(unquote(url.removeprefix("sqlite:///"))).expanduser()
+        return SqliteDatabase(path, read_only=read_only, timeout=timeout)
+    if scheme in _POSTGRES_SCHEMES:
+        return PostgresDatabase(url, read_only=read_only, timeout=timeout)
+    raise DatabaseError(
+        f"Unsupported database URL scheme {scheme!r}; use sqlite or postgres"
+    )
@@ -0,0 +1,227 @@
+from __future__ import annotations
+
+from enum import StrEnum, auto
+import os
+import re
+from typing import TYPE_CHECKING, NamedTuple
+
+from pydantic import BaseModel
+
+from rune.core.tools.builtins.bash import extract_commands
+
+if TYPE_CHECKING:
+    from rune.core.tools.base import BaseTool
+
+EXEC_TOOLS = {"bash"}
+
+
+class CommandCategory(StrEnum):
+    READ = auto()
+    VCS = auto()
+    WRITE = auto()
+    PACKAGE = auto()
+    NETWORK = auto()
+    PROCESS = auto()
+    UNKNOWN = auto()
+    PRIVILEGED = auto()
+    DESTRUCTIVE = auto()
+
+
+HIGH_RISK_SCORE = 70
+MEDIUM_RISK_SCORE = 30
+RISKY_CATEGORY_SCORE = 40
+
+CATEGORY_RISK: dict[CommandCategory, int] = {
+    CommandCategory.READ: 5,
+    CommandCategory.VCS: 20,
+    CommandCategory.WRITE: 40,
+    CommandCategory.PACKAGE: 50,
+    CommandCategory.UNKNOWN: 50,
+    CommandCategory.NETWORK: 60,
+    CommandCategory.PROCESS: 60,
+    CommandCategory.PRIVILEGED: 90,
+    CommandCategory.DESTRUCTIVE: 95,
+}
+
+_CATEGORY_COMMANDS: dict[CommandCategory, str] = {
+    CommandCategory.READ: (
+        "cat cd diff du echo env file find grep head less ls pwd rg stat tail tree "
+        "uname wc which whoami"
+    ),
+    CommandCategory.VCS: "git",
+    CommandCategory.WRITE: "chmod cp ln mkdir mv patch sed tee touch",
+    CommandCategory.PACKAGE: (
+        "apt apt-get brew cargo gem go npm npx pip pip3 pnpm poetry uv yarn"
+    ),
+    CommandCategory.NETWORK: "curl nc rsync scp ssh wget",
+    CommandCategory.PROCESS: "crontab kill killall nohup pkill",
+    CommandCategory.PRIVILEGED: "chown doas mount su sudo systemctl umount",
+    CommandCategory.DESTRUCTIVE: "dd mkfs reboot rm rmdir shred shutdown truncate",
+}
+# PowerShell cmdlets and their common aliases, matched case-insensitively.
+_POWERSHELL_COMMANDS: dict[CommandCategory, str] = {
+    CommandCategory.READ: (
+        "get-childitem get-content get-date get-item get-location gci gc "
+        "measure-object select-object select-string sls test-path where-object "
+        "write-host write-output"
+    ),
+    CommandCategory.WRITE: (
+        "add-content copy-item copy move-item move new-item ni out-file "
+        "rename-item set-content set-location"
+    ),
+    CommandCategory.NETWORK: "invoke-restmethod invoke-webrequest irm iwr",
+    CommandCategory.PROCESS: "start-process stop-process start-job saps spps",
+    CommandCategory.PRIVILEGED: "set-executionpolicy start-service stop-service",
+    CommandCategory.DESTRUCTIVE: (
+        "clear-content del erase format-volume iex invoke-expression rd ri "
+        "remove-item restart-computer stop-computer"
+    ),
+}
+COMMAND_CATEGORIES: dict[str, CommandCategory] = {
+    name: category
+    for categories in (_CATEGORY_COMMANDS, _POWERSHELL_COMMANDS)
+    for category, names in categories.items()
+    for name in names.split()
+}
+
+ESCALATIONS: list[tuple[re.Pattern[str], CommandCategory, str]] = [
+    (
+        re.compile(r"^rm\s+(-\w*[rR]\w*f|-\w*f\w*[rR]|.*--recursive.*--force)"),
+        CommandCategory.DESTRUCTIVE,
+        "recursive forced delete",
+    ),
+    (
+        re.compile(r"^git\s+(push\s+.*(--force|-f\b)|reset\s+--hard|clean\s+-\w*f)"),
+        CommandCategory.DESTRUCTIVE,
+        "discards or overwrites git history or work",
+    ),
+    (
+        re.compile(r"^git\s+(add|commit|merge|rebase|checkout|switch|stash|apply)\b"),
+        CommandCategory.WRITE,
+        "changes the working tree or repository",
+    ),
+    (
+        re.compile(r"^git\s+(push|pull|fetch|clone)\b"),
+        CommandCategory.NETWORK,
+        "talks to a git remote",
+    ),
+    (
+        re.compile(r"^sed\s+(-\w*i|--in-place)"),
+        CommandCategory.WRITE,
+        "edits files in place",
+    ),
+    (
+        re.compile(r"^(curl|wget)\b.*\s(-X\s*(POST|PUT|DELETE|PATCH)|--data|-d\s)"),
+        CommandCategory.NETWORK,
+        "sends data to a remote server",
+    ),
+]
+
+_REDIRECT_PATTERN = re.compile(r"(?:^|[^<>&\d])>>?\s*(?!&|/dev/null|\$null)[^\s&|;]")
+_PIPE_TO_SHELL_PATTERN = re.compile(r"\|\s*(sudo\s+)?(ba|z|da)?sh\b")
+
+
+class CommandAssessment(BaseModel):
+    command: str
+    categories: list[CommandCategory]
+    risk_score: int
+    reasons: list[str]
+
+    @property
+    def risk_level(self) -> str:
+        if self.risk_score >= HIGH_RISK_SCORE:
+            return "high"
+        if self.risk_score >= MEDIUM_RISK_SCORE:
+            return "medium"
+        return "low"
+
+
+def _classify_part(part: str) -> tuple[CommandCategory, str]:
+    for pattern, category, reason in ESCALATIONS:
+        if pattern.search(part):
+            return category, reason
+
+    name = os.path.basename(part.split()[0])
+    if category := COMMAND_CATEGORIES.get(name) or COMMAND_CATEGORIES.get(name.lower()):
+        return category, f"`{name}` is a {category} command"
+    return CommandCategory.UNKNOWN, f"`{name}` is not a known command"
+
+
+def assess_command(command: str, *, powershell: bool = False) -> CommandAssessment:
+    parts = [
+        part
+        for part in extract_commands(command, powershell=powershell)
+        if part.strip()
+    ] or [command]
+
+    categories: list[CommandCategory] = []
+    reasons: list[str] = []
+    for part in parts:
+        category, reason = _classify_part(part.strip())
+        if category not in categories:
+            categories.append(category)
+        if reason not in reasons:
+            reasons.append(reason)
+
+    if _REDIRECT_PATTERN.search(command):
+        categories.append(CommandCategory.WRITE)
+        reasons.append("redirects output into a file")
+    if _PIPE_TO_SHELL_PATTERN.search(command):
+        categories.append(CommandCategory.DESTRUCTIVE)
+        reasons.append("pipes content into a shell")
+
+    categories = list(dict.fromkeys(categories))
+    risk_score = max(CATEGORY_RISK[category] for category in categories)
+    # Mixing several risky kinds of command is riskier than the worst of them.
+    risky = sum(
+        1 for category in categories if CATEGORY_RISK[category] >= RISKY_CATEGORY_SCORE
+    )
+    risk_score = min(100, risk_score + 5 * max(0, risky - 1))
+
+    return CommandAssessment(
+        command=command,
+        categories=categories,
+        risk_score=risk_score,
+        reasons=reasons,
+    )
+
+
+def format_dry_run_result(assessment: CommandAssessment) -> str:
+    lines = [
+        "Dry run: the command was NOT executed.",
+        f"Command: {assessment.command}",
+        f"Classification: {', '.join(assessment.categories)} "
+        f"(risk {assessment.risk_score}/100, {assessment.risk_level})",
+        *(f"- {reason}" for reason in assessment.reasons),
+        "Treat this as a failed call: explain what you expected the command to do "
+        "and continue without relying on its output.",
+    ]
+    return "\n".join(lines)
+
+
+class ShellCommand(NamedTuple):
+    text: str
+    powershell: bool
+
+
+def shell_command(tool: BaseTool, args: BaseModel) -> ShellCommand | None:
+    """The command a call of ``tool`` would run, None when the tool doesn't run
+    shell commands.
+    """
+    if tool.get_name() not in EXEC_TOOLS:
+        return None
+    return ShellCommand(
+        str(getattr(args, "command", "")),
+        getattr(tool.config, "uses_powershell", False),
+    )
+
+
+def dry_run_report(tool: BaseTool, args: BaseModel) -> str | None:
+    """What a dry run answers a call with instead of running it, None for tools
+    that don't run shell commands, which dry runs leave to the usual approval.
+    """
+    if (command := shell_command(tool, args)) is None:
+        return None
+    return format_dry_run_result(
+        assess_command(command.text, powershell=command.powershell)
+    )
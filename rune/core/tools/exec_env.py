@@ -0,0 +1,100 @@
+from __future__ import annotations
+
+from collections.abc import Mapping
+from enum import StrEnum, auto
+from fnmatch import fnmatchcase
+
+from pydantic import BaseModel, Field
+
+from rune.core.secrets import resolve_secret_env
+
+# Variables that usually carry credentials. Matched case-insensitively.
+DEFAULT_EXCLUDED_ENV = [
+    "*TOKEN*",
+    "*SECRET*",
+    "*PASSWORD*",
+    "*PASSWD*",
+    "*CREDENTIAL*",
+    "*APIKEY*",
+    "*_KEY",
+    "*_KEY_ID",
+]
+
+# What `inherit = "core"` keeps: enough for shells, compilers and package
+# managers to find their tools, home directory and locale.
+CORE_ENV = [
+    "HOME",
+    "PATH",
+    "SHELL",
+    "USER",
+    "LOGNAME",
+    "LANG",
+    "LC_*",
+    "TZ",
+    "TMPDIR",
+    "TEMP",
+    "TMP",
+    "PWD",
+    "SSH_AUTH_SOCK",
+    "SYSTEMROOT",
+    "COMSPEC",
+    "PATHEXT",
+    "WINDIR",
+    "USERPROFILE",
+    "APPDATA",
+    "LOCALAPPDATA",
+]
+
+
+class EnvInherit(StrEnum):
+    ALL = auto()
+    CORE = auto()
+    NONE = auto()
+
+
+def _matches(name: str, patterns: list[str]) -> bool:
+    upper = name.upper()
+    return any(fnmatchcase(upper, pattern.upper()) for pattern in patterns)
+
+
+class ExecEnvPolicy(BaseModel):
+    """Which of the user's environment variables a tool subprocess sees."""
+
+    inherit: EnvInherit = Field(
+        default=EnvInherit.ALL,
+        description="Start from all variables, a minimal core set, or none.",
+    )
+    exclude: list[str] = Field(
+        default_factory=lambda: list(DEFAULT_EXCLUDED_ENV),
+        description="Glob patterns of variables to drop. Defaults to names that "
+        "look like credentials; set to [] to pass everything through.",
+    )
+    allow: list[str] = Field(
+        default_factory=list,
+        description="Glob patterns of variables always passed through, even when "
+        "excluded or outside the inherited set.",
+    )
+    overrides: dict[str, str] = Field(
+        default_factory=dict,
+        description="Variables to set for every command. Values may reference "
+        "secrets from `rune secrets` as ${secret:NAME}.",
+    )
+
+    def apply(self, environ: Mapping[str, str]) -> dict[str, str]:
+        env: dict[str, str] = {}
+        for name, value in environ.items():
+            if _matches(name, self.allow) or (
+                self._inherits(name) and not _matches(name, self.exclude)
+            ):
+                env[name] = value
+        env.update(resolve_secret_env(self.overrides))
+        return env
+
+    def _inherits(self, name: str) -> bool:
+        match self.inherit:
+            case EnvInherit.ALL:
+                return True
+            case EnvInherit.CORE:
+                return _matches(name, CORE_ENV)
+            case EnvInherit.NONE:
+                return False
@@ -0,0 +1,200 @@
+from __future__ import annotations
+
+import codecs
+from dataclasses import dataclass
+from enum import StrEnum, auto
+import mimetypes
+from pathlib import Path
+
+from rune.core.tools.base import ToolError
+
+SNIFF_BYTES = 8192
+DEFAULT_MAX_FILE_BYTES = 50 * 1024 * 1024
+
+BINARY_MIME_PREFIXES = (
+    "audio/",
+    "image/",
+    "video/",
+    "font/",
+    "application/zip",
+    "application/x-zip-compressed",
+    "application/gzip",
+    "application/x-tar",
+    "application/pdf",
+    "application/octet-stream",
+)
+# Text formats under the binary prefixes above.
+TEXT_MIME_TYPES = frozenset({"image/svg+xml"})
+
+_DEL_CODE = 127
+_NON_PRINTABLE_MAX_CODE = 31
+_NON_PRINTABLE_EXCEPTIONS = frozenset({9, 10, 11, 12, 13, 27})
+_NON_PRINTABLE_MAX_PROPORTION = 0.1
+_BYTES_PER_KIB = 1024
+
+# The UTF-32 LE mark starts with the UTF-16 LE one, so it is checked first.
+_BOM_ENCODINGS = (
+    (codecs.BOM_UTF32_LE, "utf-32"),
+    (codecs.BOM_UTF32_BE, "utf-32"),
+    (codecs.BOM_UTF8, "utf-8-sig"),
+    (codecs.BOM_UTF16_LE, "utf-16"),
+    (codecs.BOM_UTF16_BE, "utf-16"),
+)
+
+
+class RefusalReason(StrEnum):
+    BINARY = auto()
+    TOO_LARGE = auto()
+
+
+@dataclass(frozen=True, slots=True)
+class FileInfo:
+    path: Path
+    size: int
+    mime: str
+    is_binary: bool
+    encoding: str | None = None
+
+
+class FileGuardError(ToolError):
+    """A file was refused before its content reached the model or got patched."""
+
+    def __init__(self, info: FileInfo, reason: RefusalReason, action: str) -> None:
+        self.info = info
+        self.reason = reason
+        self.action = action
+        super().__init__(format_refusal(info, reason, action))
+
+    @property
+    def metadata(self) -> dict[str, str | int]:
+        return {
+            "path": str(self.info.path),
+            "reason": self.reason,
+            "size": self.info.size,
+            "mime": self.info.mime,
+        }
+
+
+def is_probably_text(name: str, data: bytes) -> bool:
+    mime_guess, _ = mimetypes.guess_type(name)
+    if (
+        mime_guess
+        and mime_guess.startswith(BINARY_MIME_PREFIXES)
+        and mime_guess not in TEXT_MIME_TYPES
+    ):
+        return False
+
+    if not data:
+        return True
+    if _bom_encoding(data) is not None:
+        return True
+    if b"\x00" in data:
+        return False
+
+    non_text = sum(
+        1
+        for b in data
+        if (b <= _NON_PRINTABLE_MAX_CODE and b not in _NON_PRINTABLE_EXCEPTIONS)
+        or b == _DEL_CODE
+    )
+    return (non_text / len(data)) < _NON_PRINTABLE_MAX_PROPORTION
+
+
+def _bom_encoding(data: bytes) -> str | None:
+    for bom, encoding in _BOM_ENCODINGS:
+        if data.startswith(bom):
+            return encoding
+    return None
+
+
+def detect_encoding(data: bytes) -> str:
+    """The encoding of text starting with ``data``: the one its byte order mark
+    names, else UTF-8 if it decodes as such, else Windows-1252 or Latin-1.
+    """
+    if encoding := _bom_encoding(data):
+        return encoding
+    try:
+        # Not final: the sample may end in the middle of a character.
+        codecs.getincrementaldecoder("utf-8")().decode(data)
+        return "utf-8"
+    except UnicodeDecodeError:
+        pass
+    try:
+        data.decode("cp1252")
+        return "cp1252"
+    except UnicodeDecodeError:
+        return "latin-1"
+
+
+def inspect_file(path: Path) -> FileInfo:
+    """Size, mime type, encoding and a binary sniff of the first few KiB of
+    ``path``.
+    """
+    size = path.stat().st_size
+    with path.open("rb") as f:
+        head = f.read(SNIFF_BYTES)
+
+    is_binary = not is_probably_text(path.name, head)
+    mime, _ = mimetypes.guess_type(path.name)
+    if mime is None:
+        mime = "application/octet-stream" if is_binary else "text/plain"
+    return FileInfo(
+        path=path,
+        size=size,
+        mime=mime,
+        is_binary=is_binary,
+        encoding=None if is_binary else detect_encoding(head),
+    )
+
+
+def guard_text_file(
+    path: Path, *, action: str, max_bytes: int | None = DEFAULT_MAX_FILE_BYTES
+) -> FileInfo:
+    """Raise FileGuardError unless ``path`` is a text file within ``max_bytes``.
+
+    Files that can't be inspected are let through so the caller reports its
+    own, more specific error.
+    """
+    try:
+        info = inspect_file(path)
+    except OSError:
+        return FileInfo(path=path, size=0, mime="text/plain", is_binary=False)
+
+    if info.is_binary:
+        raise FileGuardError(info, RefusalReason.BINARY, action)
+    if max_bytes is not None and info.size > max_bytes:
+        raise FileGuardError(info, RefusalReason.TOO_LARGE, action)
+    return info
+
+
+def format_size(size: int) -> str:
+    value = float(size)
+    for unit in ("B", "KiB", "MiB"):
+        if value < _BYTES_PER_KIB:
+            return f"{value:.0f} {unit}" if unit == "B" else f"{value:.1f} {unit}"
+        value /= _BYTES_PER_KIB
+    return f"{value:.1f} GiB"
+
+
+def format_refusal(info: FileInfo, reason: RefusalReason, action: str) -> str:
+    match reason:
+        case RefusalReason.BINARY:
+            summary = f"Refused to {action} binary file {info.path}"
+            advice = (
+                "Binary content can't be read or edited as text. Inspect it with "
+                "a purpose-built command (e.g. `file`, `xxd | head`) instead."
+            )
+        case RefusalReason.TOO_LARGE:
+            summary = f"Refused to {action} {info.path}: file is too large"
+            advice = (
+                "Look at part of it with a shell command instead, e.g. `head`, "
+                "`tail` or `rg -m 20 <pattern>`."
+            )
+
+    return "\n".join([
+        summary,
+        f"reason: {reason}",
+        f"size: {info.size} bytes ({format_size(info.size)})",
+        f"mime: {info.mime}",
+        advice,
+    ])
@@ -0,0 +1,212 @@
+from __future__ import annotations
+
+from html.parser import HTMLParser
+import re
+from typing import NamedTuple
+from urllib.parse import urljoin
+
+# Never part of the readable text.
+_SKIPPED = {"script", "style", "noscript", "template", "svg", "iframe", "canvas"}
+# Page chrome around the content; dropped when the page marks its content with
+# <main> or <article>.
+_BOILERPLATE = {"nav", "header", "footer", "aside", "form", "menu"}
+_BOILERPLATE_HINTS = re.compile(
+    r"\b(nav|navbar|menu|sidebar|footer|header|breadcrumbs?|cookie|banner|"
+    r"advert|ads|social|share|related)\b",
+    re.IGNORECASE,
+)
+_BLOCKS = {
+    "p",
+    "div",
+    "section",
+    "article",
+    "main",
+    "table",
+    "tr",
+    "ul",
+    "ol",
+    "dl",
+    "blockquote",
+    "figure",
+    "figcaption",
+    "hr",
+}
+_LIST_ITEM = re.compile(r"^ +(-|\d+\.) ")
+_VOID = {"br", "hr", "img", "input", "meta", "link", "source", "wbr", "col"}
+
+
+class ExtractedPage(NamedTuple):
+    title: str
+    markdown: str
+
+
+def html_to_markdown(html: str, base_url: str = "") -> ExtractedPage:
+    """The readable part of an HTML page as markdown: headings, paragraphs,
+    lists, links and code, without scripts, navigation and other chrome.
+    """
+    main = _Converter(base_url, content_only=True)
+    main.feed(html)
+    main.close()
+    if main.found_content:
+        return ExtractedPage(main.title, main.markdown())
+    page = _Converter(base_url, content_only=False)
+    page.feed(html)
+    page.close()
+    return ExtractedPage(page.title, page.markdown())
+
+
+class _Converter(HTMLParser):
+    def __init__(self, base_url: str, *, content_only: bool) -> None:
+        super().__init__(convert_charrefs=True)
+        self._base_url = base_url
+        self._content_only = content_only
+        self._out: list[str] = []
+        # Depth inside <main>/<article>, and inside skipped elements.
+        self._content_depth = 0
+        self._skip_stack: list[str] = []
+        self._list_stack: list[str] = []
+        self._ordinals: list[int] = []
+        self._links: list[str | None] = []
+        self._in_pre = False
+        self._in_title = False
+        self.title = ""
+        self.found_content = False
+
+    @property
+    def _writing(self) -> bool:
+        if self._skip_stack:
+            return False
+        return not self._content_only or self._content_depth > 0
+
+    def _emit(self, text: str) -> None:
+        if self._writing:
+            self._out.append(text)
+
+    def _block(self, prefix: str = "") -> None:
+        self._emit("\n\n" + prefix)
+
+    def handle_starttag(self, tag: str, attrs: list[tuple[str, str | None]]) -> None:
+        attributes = dict(attrs)
+        if self._skip_stack:
+            if tag not in _VOID:
+                self._skip_stack.append(tag)
+            return
+        hints = f"{attributes.get('class') or ''} {attributes.get('id') or ''}"
+        if (
+            tag in _SKIPPED
+            or tag in _BOILERPLATE
+            or attributes.get("hidden") is not None
+            or attributes.get("aria-hidden") == "true"
+            or attributes.get("role") in {"navigation", "banner", "contentinfo"}
+            or (tag in {"div", "section", "ul"} and _BOILERPLATE_HINTS.search(hints))
+        ):
+            self._skip_stack.append(tag)
+            return
+        if tag == "title":
+            self._in_title = True
+        elif tag in {"main", "article"}:
+            self._content_depth += 1
+            self.found_content = True
+        if tag in {"h1", "h2", "h3", "h4", "h5", "h6"}:
+            self._block("#" * int(tag[1]) + " ")
+        elif tag in {"ul", "ol"}:
+            self._list_stack.append(tag)
+            self._ordinals.append(0)
+        elif tag == "li":
+            indent = "  " * max(len(self._list_stack) - 1, 0)
+            if self._list_stack and self._list_stack[-1] == "ol":
+                self._ordinals[-1] += 1
+                self._emit(f"\n{indent}{self._ordinals[-1]}. ")
+            else:
+                self._emit(f"\n{indent}- ")
+        elif tag == "pre":
+            self._in_pre = True
+            self._block("```\n")
+        elif tag == "code" and not self._in_pre:
+            self._emit("`")
+        elif tag in {"strong", "b"}:
+            self._emit("**")
+        elif tag in {"em", "i"}:
+            self._emit("*")
+        elif tag == "a":
+            href = attributes.get("href")
+            if href and not href.startswith(("javascript:", "#")):
+                self._links.append(urljoin(self._base_url, href))
+                self._emit("[")
+            else:
+                self._links.append(None)
+        elif tag == "img":
+            if alt := (attributes.get("alt") or "").strip():
+                self._emit(f"[image: {alt}]")
+        elif tag == "br":
+            self._emit("\n")
+        elif tag == "hr":
+            self._block("---")
+        elif tag in {"td", "th"}:
+            self._emit(" | ")
+        elif tag == "blockquote":
+            self._block("> ")
+        elif tag in _BLOCKS:
+            self._block()
+
+    def handle_endtag(self, tag: str) -> None:
+        if self._skip_stack:
+            if self._skip_stack[-1] == tag:
+                self._skip_stack.pop()
+            elif tag in self._skip_stack:
+                # Unclosed children: drop them with their parent.
+                del self._skip_stack[self._skip_stack.index(tag) :]
+            return
+        if tag == "title":
+            self._in_title = False
+        elif tag in {"h1", "h2", "h3", "h4", "h5", "h6"}:
+            self._block()
+        elif tag in {"ul", "ol"} and self._list_stack:
+            self._list_stack.pop()
+            self._ordinals.pop()
+            if not self._list_stack:
+                self._block()
+        elif tag == "pre":
+            ends_line = bool(self._out) and self._out[-1].endswith("\n")
+            self._emit("```" if ends_line else "\n```")
+            self._in_pre = False
+            self._block()
+        elif tag == "code" and not self._in_pre:
+            self._emit("`")
+        elif tag in {"strong", "b"}:
+            self._emit("**")
+        elif tag in {"em", "i"}:
+            self._emit("*")
+        elif tag == "a" and self._links:
+            if (href := self._links.pop()) is not None:
+                self._emit(f"]({href})")
+        elif tag in _BLOCKS:
+            self._block()
+        if tag in {"main", "article"} and self._content_depth:
+            self._content_depth -= 1
+
+    def handle_data(self, data: str) -> None:
+        if self._in_title:
+            self.title += data.strip()
+            return
+        if self._in_pre:
+            self._emit(data)
+        else:
+            self._emit(re.sub(r"\s+", " ", data))
+
+    def markdown(self) -> str:
+        text = "".join(self._out)
+        lines = []
+        in_code = False
+        for line in text.split("\n"):
+            if line.startswith("```"):
+                in_code = not in_code
+            if not in_code:
+                # Nested list items keep their indentation.
+                line = line.rstrip() if _LIST_ITEM.match(line) else line.strip()
+            lines.append(line)
+        text = "\n".join(lines)
+        text = re.sub(r"\n{3,}", "\n\n", text)
+        # Links and emphasis around nothing, left by stripped content.
+        text = re.sub(r"\[\]\([^)]*\)|\*\*\*\*", "", text)
+        return text.strip()
@@ -0,0 +1,75 @@
+from __future__ import annotations
+
+import base64
+import io
+from pathlib import Path
+from typing import Any
+
+from rune.core.types import ImageContent
+
+# What the providers take as image input; anything else is left as a binary file.
+ATTACHABLE_IMAGE_TYPES = ("image/png", "image/jpeg", "image/gif", "image/webp")
+
+# Below this the image says little, so it's dropped rather than shrunk further.
+_MIN_SIDE = 64
+_SHRINK_STEP = 0.75
+_JPEG_QUALITY = 85
+
+
+def image_from_file(path: Path, media_type: str) -> ImageContent:
+    return ImageContent(
+        media_type=media_type, data=base64.b64encode(path.read_bytes()).decode()
+    )
+
+
+def fit_image(
+    image: ImageContent, *, max_side: int, max_bytes: int
+) -> ImageContent | None:
+    """Scale ``image`` down until its longest side is at most ``max_side``
+    pixels and it takes at most ``max_bytes``, or None if it can't be.
+
+    Scaling needs Pillow (`pip install "rune-cli[images]"`); without it an image
+    is only checked against ``max_bytes``.
+    """
+    raw = base64.b64decode(image.data)
+    try:
+        from PIL import Image
+    except ImportError:
+        return image if len(raw) <= max_bytes else None
+
+    try:
+        with Image.open(io.BytesIO(raw)) as picture:
+            if max(picture.size) <= max_side and len(raw) <= max_bytes:
+                return image
+            picture.load()
+            return _shrink(picture, image.media_type, max_side, max_bytes)
+    except (OSError, Image.DecompressionBombError):
+        return image if len(raw) <= max_bytes else None
+
+
+def _shrink(
+    picture: Any, media_type: str, max_side: int, max_bytes: int
+) -> ImageContent | None:
+    # Screenshots and plots stay PNG so text stays sharp; photos become JPEG.
+    if media_type != "image/png":
+        media_type = "image/jpeg"
+    side = min(max_side, max(picture.size))
+    while side >= _MIN_SIDE:
+        scaled = picture.copy()
+        scaled.thumbnail((side, side))
+        data = _encode(scaled, media_type)
+        if len(data) <= max_bytes:
+            return ImageContent(
+                media_type=media_type, data=base64.b64encode(data).decode()
+            )
+        side = int(side * _SHRINK_STEP)
+    return None
+
+
+def _encode(picture: Any, media_type: str) -> bytes:
+    buffer = io.BytesIO()
+    if media_type == "image/png":
+        picture.save(buffer, format="PNG", optimize=True)
+    else:
+        picture.convert("RGB").save(buffer, format="JPEG", quality=_JPEG_QUALITY)
+    return buffer.getvalue()
@@ -0,0 +1,129 @@
+"""Runs the code sent by the python tool in one long-lived namespace.
+
+Started by `PythonKernel` under the interpreter the tool uses, so it must run
+on any Python 3.8+ and import nothing from rune. It reads one JSON request per
+line from stdin and writes one JSON reply per line to the original stdout.
+Anything the code prints, subprocesses included, goes to temporary files
+that are read back after each run.
+"""
+
+from __future__ import annotations
+
+import ast
+import base64
+import io
+import json
+import os
+import signal
+import sys
+import tempfile
+import traceback
+from typing import IO, Any
+
+CELL = "<cell>"
+
+
+def _read_back(capture: IO[bytes], limit: int) -> tuple[str, bool]:
+    sys.stdout.flush()
+    sys.stderr.flush()
+    size = os.fstat(capture.fileno()).st_size
+    capture.seek(0)
+    data = capture.read(limit)
+    capture.seek(0)
+    capture.truncate()
+    return data.decode("utf-8", "replace"), size > limit
+
+
+def _png(data: bytes | str) -> str:
+    if isinstance(data, str):
+        return data
+    return base64.b64encode(data).decode("ascii")
+
+
+def _figures() -> list[str]:
+    pyplot = sys.modules.get("matplotlib.pyplot")
+    if pyplot is None:
+        return []
+    images = []
+    for number in pyplot.get_fignums():
+        buffer = io.BytesIO()
+        pyplot.figure(number).savefig(buffer, format="png", bbox_inches="tight")
+        images.append(_png(buffer.getvalue()))
+    pyplot.close("all")
+    return images
+
+
+def _display(value: Any, images: list[str]) -> str:
+    repr_png = getattr(value, "_repr_png_", None)
+    if callable(repr_png) and not isinstance(value, type):
+        try:
+            data = repr_png()
+        except Exception:
+            data = None
+        if data:
+            images.append(_png(data))
+    return repr(value)
+
+
+def _run(code: str, namespace: dict[str, Any], cwd: str | None) -> dict[str, Any]:
+    reply: dict[str, Any] = {"result": None, "error": None, "images": []}
+    try:
+        # The session the kernel serves may have moved to another directory.
+        if cwd:
+            os.chdir(cwd)
+        tree = ast.parse(code, CELL, "exec")
+        last = None
+        if tree.body and isinstance(tree.body[-1], ast.Expr):
+            last = ast.Expression(tree.body.pop().value)
+        exec(compile(tree, CELL, "exec"), namespace)
+        if last is not None:
+            value = eval(compile(last, CELL, "eval"), namespace)
+            if value is not None:
+                namespace["_"] = value
+                reply["result"] = _display(value, reply["images"])
+    except SyntaxError as e:
+        reply["error"] = "".join(traceback.format_exception_only(type(e), e))
+    except BaseException as e:
+        # Leave this function's frame out of the traceback.
+        tb = e.__traceback__.tb_next if e.__traceback__ else None
+        reply["error"] = "".join(traceback.format_exception(type(e), e, tb))
+    try:
+        reply["images"] += _figures()
+    except Exception as e:
+        reply["error"] = (reply["error"] or "") + f"Could not render figures: {e}"
+    return reply
+
+
+def main() -> None:
+    requests = os.fdopen(os.dup(0), "r", encoding="utf-8")
+    replies = os.fdopen(os.dup(1), "w", encoding="utf-8")
+    stdin = os.open(os.devnull, os.O_RDONLY)
+    os.dup2(stdin, 0)
+    stdout = tempfile.TemporaryFile()
+    stderr = tempfile.TemporaryFile()
+    os.dup2(stdout.fileno(), 1)
+    os.dup2(stderr.fileno(), 2)
+    signal.signal(signal.SIGINT, signal.default_int_handler)
+    sys.path[0] = ""
+
+    namespace: dict[str, Any] = {"__name__": "__main__", "__builtins__": __builtins__}
+    while True:
+        try:
+            line = requests.readline()
+            if not line:
+                return
+            request = json.loads(line)
+            reply = _run(request["code"], namespace, request.get("cwd"))
+            limit = request.get("max_output_bytes", 16000)
+            reply["stdout"], stdout_truncated = _read_back(stdout, limit)
+            reply["stderr"], stderr_truncated = _read_back(stderr, limit)
+            reply["output_truncated"] = stdout_truncated or stderr_truncated
+            replies.write(json.dumps(reply) + "\n")
+            replies.flush()
+        except KeyboardInterrupt:
+            # An interrupt that came in after the code finished.
+            continue
+
+
+if __name__ == "__main__":
+    main()
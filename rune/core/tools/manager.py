@@ -1,6 +1,6 @@
 from __future__ import annotations
 
-from collections.abc import Callable, Iterator
+from collections.abc import Awaitable, Callable, Iterator
 import hashlib
 import importlib.util
 import inspect
@@ -10,9 +10,14 @@ import re
 import sys
 from typing import TYPE_CHECKING, Any
 
-from rune.core.paths.config_paths import resolve_local_tools_dir
-from rune.core.paths.global_paths import DEFAULT_TOOL_DIR, GLOBAL_TOOLS_DIR
-from rune.core.tools.base import BaseTool, BaseToolConfig
+from rune.core.paths.config_paths import project_dir, resolve_local_tools_dir
+from rune.core.paths.global_paths import (
+    DEFAULT_TOOL_DIR,
+    GLOBAL_PLUGINS_DIR,
+    GLOBAL_TOOLS_DIR,
+)
+from rune.core.secrets import SecretsError, resolve_secret_env
+from rune.core.tools.base import BackgroundProcess, BaseTool, BaseToolConfig
 from rune.core.tools.mcp import (
     RemoteTool,
     create_mcp_http_proxy_tool_class,
@@ -20,6 +25,7 @@ from rune.core.tools.mcp import (
     list_tools_http,
     list_tools_stdio,
 )
+from rune.core.tools.wasm import create_plugin_tool_class, load_plugins
 from rune.core.utils import name_matches, run_sync
 
 logger = getLogger("rune")
@@ -27,6 +33,28 @@ logger = getLogger("rune")
 if TYPE_CHECKING:
     from rune.core.config import MCPHttp, MCPStdio, MCPStreamableHttp, RuneConfig
 
+# Tool lists of MCP servers, by server config, once a long-lived process such as
+# `rune daemon` enables it. Left off, every session discovers tools anew.
+_mcp_discovery_cache: dict[str, list[RemoteTool]] | None = None
+
+
+def enable_mcp_discovery_cache() -> None:
+    global _mcp_discovery_cache
+    if _mcp_discovery_cache is None:
+        _mcp_discovery_cache = {}
+
+
+async def _discover_cached(
+    srv: MCPHttp | MCPStreamableHttp | MCPStdio,
+    discover: Callable[[], Awaitable[list[RemoteTool]]],
+) -> list[RemoteTool]:
+    if _mcp_discovery_cache is None:
+        return await discover()
+    key = srv.model_dump_json()
+    if key not in _mcp_discovery_cache:
+        _mcp_discovery_cache[key] = await discover()
+    return _mcp_discovery_cache[key]
+
 
 def _try_canonical_module_name(path: Path) -> str | None:
     """Extract canonical module name for rune package files.
@@ -81,7 +109,9 @@ class ToolManager:
         self._available: dict[str, type[BaseTool]] = {
             cls.get_name(): cls for cls in self._iter_tool_classes(self._search_paths)
         }
+        self.mcp_server_status: dict[str, str] = {}
         self._integrate_mcp()
+        self._integrate_plugins()
 
     @property
     def _config(self) -> RuneConfig:
@@ -93,7 +123,7 @@ class ToolManager:
 
         paths.extend(config.tool_paths)
 
-        if (tools_dir := resolve_local_tools_dir(Path.cwd())) is not None:
+        if (tools_dir := resolve_local_tools_dir(project_dir())) is not None:
             paths.append(tools_dir)
 
         paths.append(GLOBAL_TOOLS_DIR.path)
@@ -168,9 +198,9 @@ class ToolManager:
         defaults: dict[str, dict[str, Any]] = {}
         for cls in ToolManager._iter_tool_classes(search_paths):
             try:
-                tool_name = cls.get_name()
+                config_name = cls.get_config_name()
                 config_class = cls._get_tool_config_class()
-                defaults[tool_name] = config_class().model_dump(exclude_none=True)
+                defaults[config_name] = config_class().model_dump(exclude_none=True)
             except Exception as e:
                 logger.warning(
                     "Failed to get defaults for tool %s: %s", cls.__name__, e
@@ -180,19 +210,44 @@ class ToolManager:
 
     @property
     def available_tools(self) -> dict[str, type[BaseTool]]:
+        # Opt-in tools, like `browser`, default to `enabled = false` in their
+        # config and are only offered once it is set.
+        policy = self._config.tool_policy
+        tools = {
+            name: cls
+            for name, cls in self._available.items()
+            if getattr(self.get_tool_config(name), "enabled", True)
+            and not policy.is_disabled(name)
+        }
         if self._config.enabled_tools:
             return {
                 name: cls
-                for name, cls in self._available.items()
+                for name, cls in tools.items()
                 if name_matches(name, self._config.enabled_tools)
             }
         if self._config.disabled_tools:
             return {
                 name: cls
-                for name, cls in self._available.items()
+                for name, cls in tools.items()
                 if not name_matches(name, self._config.disabled_tools)
             }
-        return dict(self._available)
+        return tools
+
+    def _integrate_plugins(self) -> None:
+        for loaded in load_plugins(GLOBAL_PLUGINS_DIR.path, self._config.plugins):
+            if loaded.plugin is None:
+                continue
+            for spec in loaded.tools:
+                tool_cls = create_plugin_tool_class(loaded.plugin, spec)
+                name = tool_cls.get_name()
+                if name in self._available:
+                    logger.warning(
+                        "Plugin %s: skipping tool %s, which another tool already uses",
+                        loaded.name,
+                        name,
+                    )
+                    continue
+                self._available[name] = tool_cls
 
     def _integrate_mcp(self) -> None:
         if not self._config.mcp_servers:
@@ -212,6 +267,7 @@ class ToolManager:
                         stdio_count += await self._register_stdio_server(srv)
                     case _:
                         logger.warning("Unsupported MCP transport: %r", srv.transport)
+                        self.mcp_server_status[srv.name] = "unsupported transport"
 
             logger.info(
                 "MCP integration registered %d tools (http=%d, stdio=%d)",
@@ -226,15 +282,31 @@ class ToolManager:
         url = (srv.url or "").strip()
         if not url:
             logger.warning("MCP server '%s' missing url for http transport", srv.name)
+            self.mcp_server_status[srv.name] = "error: missing url"
             return 0
 
-        headers = srv.http_headers()
         try:
-            tools: list[RemoteTool] = await list_tools_http(
-                url, headers=headers, startup_timeout_sec=srv.startup_timeout_sec
+            headers = resolve_secret_env(srv.http_headers())
+        except SecretsError as exc:
+            logger.warning(
+                "MCP server '%s' has an unresolved secret: %s", srv.name, exc
+            )
+            self.mcp_server_status[srv.name] = f"error: {exc}"
+            return 0
+
+        try:
+            tools = await _discover_cached(
+                srv,
+                lambda: list_tools_http(
+                    url,
+                    headers=headers,
+                    startup_timeout_sec=srv.startup_timeout_sec,
+                    network=self._config.network,
+                ),
             )
         except Exception as exc:
             logger.warning("MCP HTTP discovery failed for %s: %s", url, exc)
+            self.mcp_server_status[srv.name] = f"error: {exc}"
             return 0
 
         added = 0
@@ -248,6 +320,7 @@ class ToolManager:
                     headers=headers,
                     startup_timeout_sec=srv.startup_timeout_sec,
                     tool_timeout_sec=srv.tool_timeout_sec,
+                    network=self._config.network,
                 )
                 self._available[proxy_cls.get_name()] = proxy_cls
                 added += 1
@@ -258,20 +331,35 @@ class ToolManager:
                     url,
                     exc,
                 )
+        self.mcp_server_status[srv.name] = f"connected ({added} tools)"
         return added
 
     async def _register_stdio_server(self, srv: MCPStdio) -> int:
         cmd = srv.argv()
         if not cmd:
             logger.warning("MCP stdio server '%s' has invalid/empty command", srv.name)
+            self.mcp_server_status[srv.name] = "error: empty command"
             return 0
 
         try:
-            tools: list[RemoteTool] = await list_tools_stdio(
-                cmd, env=srv.env or None, startup_timeout_sec=srv.startup_timeout_sec
+            env = resolve_secret_env(srv.env)
+        except SecretsError as exc:
+            logger.warning(
+                "MCP server '%s' has an unresolved secret: %s", srv.name, exc
+            )
+            self.mcp_server_status[srv.name] = f"error: {exc}"
+            return 0
+
+        try:
+            tools = await _discover_cached(
+                srv,
+                lambda: list_tools_stdio(
+                    cmd, env=env or None, startup_timeout_sec=srv.startup_timeout_sec
+                ),
             )
         except Exception as exc:
             logger.warning("MCP stdio discovery failed for %r: %s", cmd, exc)
+            self.mcp_server_status[srv.name] = f"error: {exc}"
             return 0
 
         added = 0
@@ -282,7 +370,7 @@ class ToolManager:
                     remote=remote,
                     alias=srv.name,
                     server_hint=srv.prompt,
-                    env=srv.env or None,
+                    env=env or None,
                     startup_timeout_sec=srv.startup_timeout_sec,
                     tool_timeout_sec=srv.tool_timeout_sec,
                 )
@@ -295,6 +383,7 @@ class ToolManager:
                     cmd,
                     exc,
                 )
+        self.mcp_server_status[srv.name] = f"connected ({added} tools)"
         return added
 
     def get_tool_config(self, tool_name: str) -> BaseToolConfig:
@@ -307,11 +396,11 @@ class ToolManager:
             config_class = BaseToolConfig
             default_config = BaseToolConfig()
 
-        user_overrides = self._config.tools.get(tool_name)
-        if user_overrides is None:
-            merged_dict = default_config.model_dump()
-        else:
-            merged_dict = {**default_config.model_dump(), **user_overrides.model_dump()}
+        merged_dict = default_config.model_dump()
+        config_name = tool_class.get_config_name() if tool_class else tool_name
+        for name in dict.fromkeys((config_name, tool_name)):
+            if (user_overrides := self._config.tools.get(name)) is not None:
+                merged_dict |= user_overrides.model_dump()
 
         return config_class.model_validate(merged_dict)
 
@@ -334,8 +423,32 @@ class ToolManager:
         self._instances[tool_name] = tool_class.from_config(tool_config)
         return self._instances[tool_name]
 
-    def reset_all(self) -> None:
+    async def reset_all(self) -> None:
+        """Drop every tool instance, stopping the processes they keep running."""
+        instances = list(self._instances.values())
         self._instances.clear()
+        for tool in instances:
+            await self._stop_background_processes(tool)
+
+    def list_background_processes(self) -> list[BackgroundProcess]:
+        """Processes that tools used this session keep running between calls."""
+        return [
+            process
+            for tool in self._instances.values()
+            for process in tool.list_background_processes()
+        ]
+
+    async def kill_background_process(self, process_id: str) -> bool:
+        for tool in list(self._instances.values()):
+            if await tool.kill_background_process(process_id):
+                return True
+        return False
+
+    async def invalidate_tool(self, tool_name: str) -> None:
+        if (tool := self._instances.pop(tool_name, None)) is not None:
+            await self._stop_background_processes(tool)
 
-    def invalidate_tool(self, tool_name: str) -> None:
-        self._instances.pop(tool_name, None)
+    @staticmethod
+    async def _stop_background_processes(tool: BaseTool) -> None:
+        for process in tool.list_background_processes():
+            await tool.kill_background_process(process.id)
@@ -1,16 +1,18 @@
 from __future__ import annotations
 
-from collections.abc import AsyncGenerator
+from collections.abc import AsyncGenerator, Callable
 from datetime import timedelta
 import hashlib
 from pathlib import Path
 from typing import TYPE_CHECKING, Any, ClassVar
 
+import httpx
 from mcp import ClientSession
 from mcp.client.stdio import StdioServerParameters, stdio_client
 from mcp.client.streamable_http import streamablehttp_client
 from pydantic import BaseModel, ConfigDict, Field, field_validator
 
+from rune.core.network import proxy_auth_rejected, proxy_options
 from rune.core.tools.base import (
     BaseTool,
     BaseToolConfig,
@@ -22,8 +24,12 @@ from rune.core.tools.ui import ToolCallDisplay, ToolResultDisplay
 from rune.core.types import ToolStreamEvent
 
 if TYPE_CHECKING:
+    from rune.core.config import NetworkConfig
     from rune.core.types import ToolCallEvent, ToolResultEvent
 
+# What the MCP SDK uses when it isn't given a timeout.
+_DEFAULT_HTTP_TIMEOUT = httpx.Timeout(30.0, read=300.0)
+
 
 class _OpenArgs(BaseModel):
     model_config = ConfigDict(extra="allow")
@@ -112,14 +118,39 @@ def _parse_call_result(server: str, tool: str, result_obj: Any) -> MCPToolResult
     return MCPToolResult(server=server, tool=tool, text=text, structured=None)
 
 
+def _http_client_factory(
+    url: str, network: NetworkConfig | None
+) -> Callable[..., httpx.AsyncClient]:
+    """Makes the SDK's HTTP clients go through the configured proxy."""
+    options = proxy_options(network, url)
+
+    def create_client(
+        headers: dict[str, str] | None = None,
+        timeout: httpx.Timeout | None = None,
+        auth: httpx.Auth | None = None,
+    ) -> httpx.AsyncClient:
+        return httpx.AsyncClient(
+            headers=headers,
+            timeout=timeout or _DEFAULT_HTTP_TIMEOUT,
+            auth=auth,
+            follow_redirects=True,
+            **options,
+        )
+
+    return create_client
+
+
 async def list_tools_http(
     url: str,
     *,
     headers: dict[str, str] | None = None,
     startup_timeout_sec: float | None = None,
+    network: NetworkConfig | None = None,
 ) -> list[RemoteTool]:
     timeout = timedelta(seconds=startup_timeout_sec) if startup_timeout_sec else None
-    async with streamablehttp_client(url, headers=headers) as (read, write, _):
+    async with streamablehttp_client(
+        url, headers=headers, httpx_client_factory=_http_client_factory(url, network)
+    ) as (read, write, _):
         async with ClientSession(read, write, read_timeout_seconds=timeout) as session:
             await session.initialize()
             tools_resp = await session.list_tools()
@@ -134,12 +165,15 @@ async def call_tool_http(
     headers: dict[str, str] | None = None,
     startup_timeout_sec: float | None = None,
     tool_timeout_sec: float | None = None,
+    network: NetworkConfig | None = None,
 ) -> MCPToolResult:
     init_timeout = (
         timedelta(seconds=startup_timeout_sec) if startup_timeout_sec else None
     )
     call_timeout = timedelta(seconds=tool_timeout_sec) if tool_timeout_sec else None
-    async with streamablehttp_client(url, headers=headers) as (read, write, _):
+    async with streamablehttp_client(
+        url, headers=headers, httpx_client_factory=_http_client_factory(url, network)
+    ) as (read, write, _):
         async with ClientSession(
             read, write, read_timeout_seconds=init_timeout
         ) as session:
@@ -159,6 +193,7 @@ def create_mcp_http_proxy_tool_class(
     headers: dict[str, str] | None = None,
     startup_timeout_sec: float | None = None,
     tool_timeout_sec: float | None = None,
+    network: NetworkConfig | None = None,
 ) -> type[BaseTool[_OpenArgs, MCPToolResult, BaseToolConfig, BaseToolState]]:
     from urllib.parse import urlparse
 
@@ -184,6 +219,7 @@ def create_mcp_http_proxy_tool_class(
         _headers: ClassVar[dict[str, str]] = dict(headers or {})
         _startup_timeout_sec: ClassVar[float | None] = startup_timeout_sec
         _tool_timeout_sec: ClassVar[float | None] = tool_timeout_sec
+        _network: ClassVar[NetworkConfig | None] = network
 
         @classmethod
         def get_name(cls) -> str:
@@ -205,8 +241,13 @@ def create_mcp_http_proxy_tool_class(
                     headers=self._headers,
                     startup_timeout_sec=self._startup_timeout_sec,
                     tool_timeout_sec=self._tool_timeout_sec,
+                    network=self._network,
                 )
             except Exception as exc:
+                if proxy_auth_rejected(exc):
+                    raise ToolError(
+                        "MCP call failed: the proxy rejected its credentials"
+                    ) from exc
                 raise ToolError(f"MCP call failed: {exc}") from exc
 
         @classmethod
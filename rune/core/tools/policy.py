@@ -0,0 +1,107 @@
+from __future__ import annotations
+
+import asyncio
+from collections.abc import AsyncGenerator
+
+from pydantic import BaseModel, ConfigDict, Field
+
+from rune.core.utils import name_matches
+
+# The table under `[tools]` that holds the policy rather than a tool's settings.
+TOOL_POLICY_KEY = "policy"
+
+
+class ToolRuntimeExceededError(Exception):
+    def __init__(self, tool_name: str, seconds: float) -> None:
+        self.seconds = seconds
+        super().__init__(
+            f"{tool_name} was stopped after running for {seconds:g}s, the limit "
+            "set in [tools.policy]"
+        )
+
+
+class ToolPolicyConfig(BaseModel):
+    """`[tools.policy]`: rules that apply to tools by name, built-in, MCP and
+    plugin tools alike, whatever their own settings and the approval mode say.
+
+    Names may be glob patterns (`mcp_github_*`) or regexes (`re:^sql_.*`). For
+    the limits, an exact name wins over the patterns, which are tried in order.
+    """
+
+    model_config = ConfigDict(extra="forbid")
+
+    disabled: list[str] = Field(
+        default_factory=list, description="Tools that are never offered or run."
+    )
+    require_approval: list[str] = Field(
+        default_factory=list,
+        description="Tools that always ask, even in auto-approve mode or when "
+        "allowlisted.",
+    )
+    max_runtime: dict[str, float] = Field(
+        default_factory=dict,
+        description="Seconds a tool call may run before it is stopped.",
+    )
+    max_output_chars: dict[str, int] = Field(
+        default_factory=dict,
+        description="Characters of a tool's output that reach the model.",
+    )
+
+    def is_disabled(self, tool_name: str) -> bool:
+        return name_matches(tool_name, self.disabled)
+
+    def requires_approval(self, tool_name: str) -> bool:
+        return name_matches(tool_name, self.require_approval)
+
+    def runtime_limit(self, tool_name: str) -> float | None:
+        return _limit_for(self.max_runtime, tool_name)
+
+    def output_limit(self, tool_name: str) -> int | None:
+        return _limit_for(self.max_output_chars, tool_name)
+
+
+def _limit_for[T](limits: dict[str, T], tool_name: str) -> T | None:
+    if tool_name in limits:
+        return limits[tool_name]
+    for pattern, limit in limits.items():
+        if name_matches(tool_name, [pattern]):
+            return limit
+    return None
+
+
+async def limit_runtime[T](
+    items: AsyncGenerator[T], tool_name: str, seconds: float | None
+) -> AsyncGenerator[T]:
+    """Re-yield a tool's events, cancelling the tool once it has run for
+    ``seconds`` in all.
+
+    Time spent by the caller between two events doesn't count.
+    """
+    if seconds is None:
+        async for item in items:
+            yield item
+        return
+    remaining = seconds
+    loop = asyncio.get_running_loop()
+    try:
+        while True:
+            started = loop.time()
+            try:
+                item = await asyncio.wait_for(anext(items), max(remaining, 0))
+            except StopAsyncIteration:
+                return
+            except TimeoutError:
+                raise ToolRuntimeExceededError(tool_name, seconds) from None
+            remaining -= loop.time() - started
+            yield item
+    finally:
+        await items.aclose()
+
+
+def truncate_output(text: str, limit: int | None) -> str:
+    if limit is None or len(text) <= limit:
+        return text
+    return (
+        text[:limit]
+        + f"\n... [{len(text) - limit} more characters cut by [tools.policy]]"
+    )
@@ -0,0 +1,231 @@
+from __future__ import annotations
+
+import base64
+import binascii
+from pathlib import PurePath
+import re
+
+POWERSHELL_NAMES = frozenset({"pwsh", "powershell"})
+
+# Statements that only structure the script; the commands inside their
+# conditions and blocks are extracted separately.
+_KEYWORDS = frozenset({
+    "begin",
+    "catch",
+    "do",
+    "else",
+    "elseif",
+    "end",
+    "finally",
+    "for",
+    "foreach",
+    "function",
+    "if",
+    "param",
+    "process",
+    "return",
+    "switch",
+    "trap",
+    "try",
+    "until",
+    "while",
+})
+_COMMAND_FLAGS = frozenset({"-command", "-c"})
+_ENCODED_FLAGS = frozenset({"-encodedcommand", "-enc", "-ec", "-e"})
+_GROUPS = {"(": ")", "{": "}"}
+_ASSIGNMENT = re.compile(r"^\$[\w:]+\s*[-+*/]?=\s*")
+_CALL_OPERATOR = re.compile(r"^[&.]\s+")
+
+
+def is_powershell(program: str) -> bool:
+    return PurePath(program).stem.lower() in POWERSHELL_NAMES
+
+
+def extract_powershell_commands(script: str) -> list[str]:
+    """The commands a PowerShell script runs, in the shape `extract_commands`
+    returns them for bash: one string per command, arguments included.
+
+    Commands nested in `$(...)`, `(...)` and script blocks are listed on their
+    own, and `powershell -Command ...` / `-EncodedCommand` wrappers are unwrapped.
+    """
+    commands: list[str] = []
+    for statement in _split_statements(script):
+        if (command := _command_of(statement)) is None:
+            continue
+        if (inner := unwrap_powershell(command)) is not None:
+            commands.extend(extract_powershell_commands(inner))
+        else:
+            commands.append(command)
+    return commands
+
+
+def unwrap_powershell(command: str) -> str | None:
+    """The script a `powershell`/`pwsh` invocation runs, if it is given inline."""
+    args = _split_args(command)
+    if not args or not is_powershell(args[0]):
+        return None
+    for position, arg in enumerate(args[1:], start=1):
+        flag = arg.lower()
+        if flag in _COMMAND_FLAGS:
+            return " ".join(args[position + 1 :])
+        if flag in _ENCODED_FLAGS and position + 1 < len(args):
+            try:
+                return base64.b64decode(args[position + 1]).decode("utf-16-le")
+            except (binascii.Error, UnicodeDecodeError):
+                return None
+    return None
+
+
+def _split_statements(script: str) -> list[str]:
+    statements: list[str] = []
+    nested: list[str] = []
+    current: list[str] = []
+    i, n = 0, len(script)
+
+    def flush() -> None:
+        if statement := "".join(current).strip():
+            statements.append(statement)
+        current.clear()
+
+    while i < n:
+        char = script[i]
+        if script.startswith(("@'", '@"'), i) and _at_line_end(script, i + 2):
+            end = _here_string_end(script, i)
+            current.append(script[i:end])
+            i = end
+        elif char in "'\"":
+            end = _quote_end(script, i)
+            if char == '"':
+                nested.extend(_subexpressions(script[i:end]))
+            current.append(script[i:end])
+            i = end
+        elif char == "`":
+            current.append(script[i : i + 2])
+            i += 2
+        elif script.startswith("<#", i):
+            end = script.find("#>", i + 2)
+            i = n if end == -1 else end + 2
+        elif char == "#" and (i == 0 or script[i - 1].isspace()):
+            end = script.find("\n", i)
+            i = n if end == -1 else end
+        elif char in _GROUPS:
+            end = _group_end(script, i)
+            nested.extend(_split_statements(script[i + 1 : end - 1]))
+            current.append(script[i:end])
+            i = end
+        elif script.startswith(("&&", "||"), i):
+            flush()
+            i += 2
+        elif char in ";|\n":
+            flush()
+            i += 1
+        else:
+            current.append(char)
+            i += 1
+    flush()
+    return [*statements, *nested]
+
+
+def _command_of(statement: str) -> str | None:
+    statement = _ASSIGNMENT.sub("", statement)
+    statement = _CALL_OPERATOR.sub("", statement).strip()
+    if not statement:
+        return None
+    first = statement[0]
+    # Bare literals and groups only produce values; anything they run is
+    # already listed as a nested command.
+    if statement.startswith(("@'", '@"')) or (
+        first in "'\"" and _quote_end(statement, 0) == len(statement)
+    ):
+        return None
+    if first in _GROUPS and _group_end(statement, 0) == len(statement):
+        return None
+    if statement.startswith(("$(", "@(")) and (
+        _group_end(statement, 1) == len(statement)
+    ):
+        return None
+    if re.split(r"[\s({]", statement, maxsplit=1)[0].lower() in _KEYWORDS:
+        return None
+    return statement
+
+
+def _subexpressions(text: str) -> list[str]:
+    commands: list[str] = []
+    start = text.find("$(")
+    while start != -1:
+        end = _group_end(text, start + 1)
+        commands.extend(_split_statements(text[start + 2 : end - 1]))
+        start = text.find("$(", end)
+    return commands
+
+
+def _at_line_end(script: str, i: int) -> bool:
+    rest = script[i:]
+    line = rest.split("\n", 1)[0]
+    return not line.strip()
+
+
+def _here_string_end(script: str, start: int) -> int:
+    terminator = "\n" + script[start + 1] + "@"
+    end = script.find(terminator, start + 2)
+    return len(script) if end == -1 else end + len(terminator)
+
+
+def _quote_end(script: str, start: int) -> int:
+    quote = script[start]
+    i = start + 1
+    while i < len(script):
+        char = script[i]
+        if quote == '"' and char == "`":
+            i += 2
+            continue
+        if char == quote:
+            if script.startswith(quote * 2, i):
+                i += 2
+                continue
+            return i + 1
+        i += 1
+    return len(script)
+
+
+def _group_end(script: str, start: int) -> int:
+    """Index just past the bracket that closes the one at ``start``."""
+    stack = [_GROUPS[script[start]]]
+    i = start + 1
+    while i < len(script) and stack:
+        char = script[i]
+        if char in "'\"":
+            i = _quote_end(script, i)
+            continue
+        if char == "`":
+            i += 2
+            continue
+        if char in _GROUPS:
+            stack.append(_GROUPS[char])
+        elif char == stack[-1]:
+            stack.pop()
+        i += 1
+    return i
+
+
+def _split_args(command: str) -> list[str]:
+    args: list[str] = []
+    current: list[str] = []
+    i = 0
+    while i < len(command):
+        char = command[i]
+        if char.isspace():
+            if current:
+                args.append("".join(current))
+                current.clear()
+            i += 1
+        elif char in "'\"":
+            end = _quote_end(command, i)
+            current.append(command[i + 1 : end - 1])
+            i = end
+        else:
+            current.append(char)
+            i += 1
+    if current:
+        args.append("".join(current))
+    return args
@@ -0,0 +1,148 @@
+from __future__ import annotations
+
+import asyncio
+import codecs
+import contextlib
+from typing import Literal
+
+type StreamName = Literal["stdout", "stderr"]
+
+_READ_SIZE = 4096
+# Text read but not yet streamed, per pipe. When the reader of the events falls
+# further behind than this, the oldest text is dropped, never the process.
+MAX_PENDING_CHARS = 32_000
+
+
+class _Pipe:
+    def __init__(
+        self,
+        name: StreamName,
+        reader: asyncio.StreamReader | None,
+        *,
+        max_chars: int,
+        encoding: str,
+    ) -> None:
+        self.name = name
+        self._reader = reader
+        self._max_chars = max_chars
+        self._decoder = codecs.getincrementaldecoder(encoding)(errors="replace")
+        self._captured: list[str] = []
+        self._captured_chars = 0
+        self._pending = ""
+        self._skipped = 0
+
+    @property
+    def captured(self) -> str:
+        return "".join(self._captured)
+
+    async def pump(self) -> None:
+        if self._reader is None:
+            return
+        while chunk := await self._reader.read(_READ_SIZE):
+            self._add(self._decoder.decode(chunk))
+        self._add(self._decoder.decode(b"", final=True))
+
+    def _add(self, text: str) -> None:
+        if not text:
+            return
+        if self._captured_chars < self._max_chars:
+            kept = text[: self._max_chars - self._captured_chars]
+            self._captured.append(kept)
+            self._captured_chars += len(kept)
+        self._pending += text
+        if len(self._pending) > MAX_PENDING_CHARS:
+            self._skipped += len(self._pending) - MAX_PENDING_CHARS
+            self._pending = self._pending[-MAX_PENDING_CHARS:]
+
+    def take(self) -> str:
+        text = self._pending
+        if self._skipped:
+            text = f"[... {self._skipped} characters not shown ...]\n{text}"
+        self._pending = ""
+        self._skipped = 0
+        return text
+
+
+class ProcessOutput:
+    """Reads a process's stdout and stderr while it runs.
+
+    The first `max_chars` of each are kept for the tool result, and everything
+    is queued until `take()` so the caller can stream it at its own pace.
+    """
+
+    def __init__(
+        self,
+        proc: asyncio.subprocess.Process,
+        *,
+        max_chars: int,
+        encoding: str,
+        stop: asyncio.Event | None = None,
+    ) -> None:
+        self._proc = proc
+        self._pipes = (
+            _Pipe("stdout", proc.stdout, max_chars=max_chars, encoding=encoding),
+            _Pipe("stderr", proc.stderr, max_chars=max_chars, encoding=encoding),
+        )
+        self._stop = stop
+        self._done: asyncio.Task[None] | None = None
+        self._stop_waiter: asyncio.Task[bool] | None = None
+
+    async def __aenter__(self) -> ProcessOutput:
+        self._done = asyncio.create_task(self._read_all())
+        if self._stop is not None:
+            self._stop_waiter = asyncio.create_task(self._stop.wait())
+        return self
+
+    async def __aexit__(self, *_: object) -> None:
+        for task in (self._done, self._stop_waiter):
+            if task is not None and not task.done():
+                task.cancel()
+                with contextlib.suppress(asyncio.CancelledError):
+                    await task
+
+    async def _read_all(self) -> None:
+        await asyncio.gather(*(pipe.pump() for pipe in self._pipes))
+        await self._proc.wait()
+
+    @property
+    def finished(self) -> bool:
+        """Both pipes are closed and the process has exited."""
+        return self._done is not None and self._done.done()
+
+    @property
+    def stop_requested(self) -> bool:
+        return self._stop is not None and self._stop.is_set()
+
+    @property
+    def stdout(self) -> str:
+        return self._pipes[0].captured
+
+    @property
+    def stderr(self) -> str:
+        return self._pipes[1].captured
+
+    async def wait(self, timeout: float) -> None:
+        """Return once the process has finished, a stop was requested or
+        `timeout` seconds have passed.
+        """
+        waiters = [t for t in (self._done, self._stop_waiter) if t is not None]
+        await asyncio.wait(
+            waiters, timeout=max(timeout, 0), return_when=asyncio.FIRST_COMPLETED
+        )
+        if self._done is not None and self._done.done():
+            # Surface errors from reading the pipes.
+            self._done.result()
+
+    async def wait_finished(self, timeout: float) -> None:
+        """Return once the process has finished or `timeout` seconds have
+        passed, even after a stop was requested.
+        """
+        if self._done is None:
+            return
+        await asyncio.wait([self._done], timeout=max(timeout, 0))
+        if self._done.done():
+            self._done.result()
+
+    def take(self) -> list[tuple[StreamName, str]]:
+        """The output read since the last call, per stream."""
+        return [(pipe.name, text) for pipe in self._pipes if (text := pipe.take())]
@@ -0,0 +1,202 @@
+from __future__ import annotations
+
+import asyncio
+import atexit
+import contextlib
+from datetime import UTC, datetime
+import json
+import os
+from pathlib import Path
+import shutil
+import signal
+import sys
+from typing import Literal
+
+from pydantic import BaseModel, Field, ValidationError
+
+from rune.core.tools.subprocesses import kill_process_tree
+from rune.core.utils import is_windows
+
+DRIVER = Path(__file__).with_name("kernel_driver.py")
+# Seconds an interrupted run gets to stop before the kernel is restarted.
+INTERRUPT_GRACE = 5.0
+# Replies carry rendered figures, so lines can be far longer than asyncio's
+# default 64 KiB.
+_READ_LIMIT = 64 * 1024 * 1024
+
+
+class KernelError(Exception):
+    """Raised when the kernel can't be started or dies, losing its state."""
+
+
+class KernelReply(BaseModel):
+    stdout: str = ""
+    stderr: str = ""
+    result: str | None = None
+    error: str | None = None
+    images: list[str] = Field(
+        default_factory=list, description="Base64 PNG data of figures."
+    )
+    output_truncated: bool = False
+    interrupted: bool = False
+
+
+def find_interpreter(configured: str | None = None, cwd: Path | None = None) -> str:
+    """The interpreter to run code with: the configured one, else the
+    project's virtualenv, else the first Python on PATH.
+    """
+    if configured:
+        if found := shutil.which(configured) or (
+            configured if Path(configured).is_file() else None
+        ):
+            return found
+        raise KernelError(f"Python interpreter not found: {configured}")
+    venv = (cwd or Path.cwd()) / ".venv"
+    for candidate in (venv / "bin" / "python", venv / "Scripts" / "python.exe"):
+        if candidate.is_file():
+            return str(candidate)
+    return shutil.which("python3") or shutil.which("python") or sys.executable
+
+
+class PythonKernel:
+    """A Python process that runs code in one namespace until it is closed,
+    so variables and imports carry over from one run to the next.
+    """
+
+    def __init__(self, process: asyncio.subprocess.Process, interpreter: str) -> None:
+        self._process = process
+        self.interpreter = interpreter
+        self.started_at = datetime.now(UTC)
+        self._lock = asyncio.Lock()
+        atexit.register(self._kill)
+
+    @classmethod
+    async def start(
+        cls, interpreter: str, *, env: dict[str, str], cwd: Path | None = None
+    ) -> PythonKernel:
+        # start_new_session is Unix-only, on Windows it's ignored
+        kwargs: dict[Literal["start_new_session"], bool] = (
+            {} if is_windows() else {"start_new_session": True}
+        )
+        try:
+            process = await asyncio.create_subprocess_exec(
+                interpreter,
+                "-u",
+                str(DRIVER),
+                stdin=asyncio.subprocess.PIPE,
+                stdout=asyncio.subprocess.PIPE,
+                stderr=asyncio.subprocess.PIPE,
+                env={**env, "MPLBACKEND": "Agg", "PYTHONIOENCODING": "utf-8"},
+                cwd=cwd,
+                limit=_READ_LIMIT,
+                **kwargs,
+            )
+        except OSError as e:
+            raise KernelError(f"Could not start {interpreter}: {e}") from e
+        return cls(process, interpreter)
+
+    @property
+    def alive(self) -> bool:
+        return self._process.returncode is None
+
+    @property
+    def pid(self) -> int:
+        return self._process.pid
+
+    async def execute(
+        self,
+        code: str,
+        *,
+        cwd: Path,
+        timeout: float,
+        max_output_bytes: int,
+        stop: asyncio.Event | None = None,
+    ) -> KernelReply:
+        """Runs `code` in `cwd`. A run that takes longer than `timeout`, or is
+        still going when `stop` is set, is interrupted, keeping the namespace,
+        and the kernel is restarted only if it doesn't stop.
+        """
+        async with self._lock:
+            if not self.alive:
+                raise KernelError("The Python kernel is not running")
+            request = {
+                "code": code,
+                "cwd": str(cwd),
+                "max_output_bytes": max_output_bytes,
+            }
+            assert self._process.stdin is not None
+            self._process.stdin.write(json.dumps(request).encode() + b"\n")
+            try:
+                await self._process.stdin.drain()
+                reply = await asyncio.wait_for(self._reply_unless(stop), timeout)
+            except TimeoutError:
+                return await self._interrupt(f"after {timeout}s")
+            except (BrokenPipeError, ConnectionResetError):
+                # The kernel died; the reply read reports how.
+                return await self._read_reply()
+            if reply is None:
+                return await self._interrupt("when it was stopped")
+            return reply
+
+    async def _reply_unless(self, stop: asyncio.Event | None) -> KernelReply | None:
+        """The reply to the running code, or None if `stop` is set first."""
+        if stop is None:
+            return await self._read_reply()
+        reply = asyncio.ensure_future(self._read_reply())
+        stopped = asyncio.ensure_future(stop.wait())
+        try:
+            await asyncio.wait({reply, stopped}, return_when=asyncio.FIRST_COMPLETED)
+        finally:
+            stopped.cancel()
+            if not reply.done():
+                reply.cancel()
+                # Only one read of the kernel's replies can wait at a time.
+                with contextlib.suppress(asyncio.CancelledError):
+                    await reply
+        return None if reply.cancelled() else reply.result()
+
+    async def _interrupt(self, when: str) -> KernelReply:
+        if not is_windows():
+            with contextlib.suppress(ProcessLookupError):
+                self._process.send_signal(signal.SIGINT)
+            with contextlib.suppress(TimeoutError):
+                reply = await asyncio.wait_for(self._read_reply(), INTERRUPT_GRACE)
+                return reply.model_copy(update={"interrupted": True})
+        await self.close()
+        raise KernelError(
+            f"The code was still running {when} and couldn't be interrupted, "
+            "so the kernel was stopped and its state is lost"
+        )
+
+    async def _read_reply(self) -> KernelReply:
+        assert self._process.stdout is not None
+        line = await self._process.stdout.readline()
+        if not line:
+            await self._process.wait()
+            stderr = b""
+            if self._process.stderr is not None:
+                stderr = await self._process.stderr.read()
+            message = f"The Python kernel exited with code {self._process.returncode}"
+            if details := stderr.decode("utf-8", "replace").strip():
+                message += f": {details}"
+            raise KernelError(message)
+        try:
+            return KernelReply.model_validate_json(line)
+        except ValidationError as e:
+            await self.close()
+            raise KernelError(f"The Python kernel sent an invalid reply: {e}") from e
+
+    async def close(self) -> None:
+        if self._process.stdin is not None:
+            self._process.stdin.close()
+        await kill_process_tree(self._process)
+        atexit.unregister(self._kill)
+
+    def _kill(self) -> None:
+        if self._process.returncode is not None:
+            return
+        with contextlib.suppress(OSError):
+            if is_windows():
+                self._process.kill()
+            else:
+                os.killpg(self._process.pid, signal.SIGKILL)
@@ -0,0 +1,78 @@
+from __future__ import annotations
+
+from pathlib import PurePath
+import sys
+
+from pydantic import BaseModel, Field
+
+# Flags that make a shell run one command string, and the extra flags that
+# skip its startup files, keyed by executable name.
+_COMMAND_FLAGS: dict[str, list[str]] = {
+    "bash": ["-c"],
+    "sh": ["-c"],
+    "dash": ["-c"],
+    "zsh": ["-c"],
+    "fish": ["-c"],
+    "nu": ["-c"],
+    "pwsh": ["-NonInteractive", "-Command"],
+    "powershell": ["-NonInteractive", "-Command"],
+    "cmd": ["/d", "/s", "/c"],
+}
+_NO_RC_FLAGS: dict[str, list[str]] = {
+    "bash": ["--noprofile", "--norc"],
+    "zsh": ["--no-rcs"],
+    "fish": ["--no-config"],
+    "nu": ["--no-config-file"],
+    "pwsh": ["-NoProfile"],
+    "powershell": ["-NoProfile"],
+}
+
+
+class ShellProfile(BaseModel):
+    """The shell that runs commands for the bash tool."""
+
+    program: str | None = Field(
+        default=None,
+        description="Shell executable, e.g. 'bash', 'zsh', 'pwsh' or 'nu'. "
+        "Defaults to $SHELL (COMSPEC on Windows).",
+    )
+    args: list[str] | None = Field(
+        default=None,
+        description="Flags placed before the command string. Defaults to what "
+        "the shell needs to run one command, e.g. ['-c'].",
+    )
+    load_rc: bool = Field(
+        default=True,
+        description="Let the shell read its startup files. Set to false for "
+        "'bash --noprofile --norc' and the equivalents of other shells.",
+    )
+    wrapper: list[str] = Field(
+        default_factory=list,
+        description="Command the shell is run through, e.g. ['direnv', 'exec', '.'].",
+    )
+
+    @property
+    def is_default(self) -> bool:
+        return (
+            self.program is None
+            and self.args is None
+            and self.load_rc
+            and not self.wrapper
+        )
+
+    def argv(self, command: str, default_program: str) -> list[str]:
+        program = self.program or default_program
+        name = PurePath(program).stem.lower()
+        args = self.args
+        if args is None:
+            args = _COMMAND_FLAGS.get(name, ["-c"])
+            if not self.load_rc:
+                args = [*_NO_RC_FLAGS.get(name, []), *args]
+        return [*self.wrapper, program, *args, command]
+
+
+def select_shell_profile(
+    default: ShellProfile, per_platform: dict[str, ShellProfile]
+) -> ShellProfile:
+    """The profile for this platform (a ``sys.platform`` value), or the default."""
+    return per_platform.get(sys.platform, default)
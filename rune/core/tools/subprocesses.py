@@ -0,0 +1,66 @@
+from __future__ import annotations
+
+import asyncio
+import os
+import signal
+import sys
+
+from rune.core.tools.exec_env import ExecEnvPolicy
+from rune.core.utils import is_windows
+
+
+def get_base_env(policy: ExecEnvPolicy | None = None) -> dict[str, str]:
+    """The environment for processes tools start: what `policy` lets through,
+    with pagers, colours and prompts turned off.
+    """
+    inherited = policy.apply(os.environ) if policy else dict(os.environ)
+    base_env = {
+        **inherited,
+        "CI": "true",
+        "NONINTERACTIVE": "1",
+        "NO_TTY": "1",
+        "NO_COLOR": "1",
+    }
+
+    if is_windows():
+        base_env["GIT_PAGER"] = "more"
+        base_env["PAGER"] = "more"
+    else:
+        base_env["TERM"] = "dumb"
+        base_env["DEBIAN_FRONTEND"] = "noninteractive"
+        base_env["GIT_PAGER"] = "cat"
+        base_env["PAGER"] = "cat"
+        base_env["LESS"] = "-FX"
+        base_env["LC_ALL"] = "en_US.UTF-8"
+
+    return base_env
+
+
+async def kill_process_tree(proc: asyncio.subprocess.Process) -> None:
+    """Kill `proc` with its children and wait for it; tools start processes in
+    their own process group so that this reaches them all.
+    """
+    if proc.returncode is not None:
+        return
+
+    try:
+        if sys.platform == "win32":
+            try:
+                subprocess_proc = await asyncio.create_subprocess_exec(
+                    "taskkill",
+                    "/F",
+                    "/T",
+                    "/PID",
+                    str(proc.pid),
+                    stdout=asyncio.subprocess.DEVNULL,
+                    stderr=asyncio.subprocess.DEVNULL,
+                )
+                await subprocess_proc.wait()
+            except (FileNotFoundError, OSError):
+                proc.terminate()
+        else:
+            os.killpg(os.getpgid(proc.pid), signal.SIGKILL)
+
+        await proc.wait()
+    except (ProcessLookupError, PermissionError, OSError):
+        pass
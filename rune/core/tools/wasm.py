@@ -0,0 +1,312 @@
+from __future__ import annotations
+
+from collections.abc import AsyncGenerator
+import functools
+import json
+import os
+from pathlib import Path
+import re
+import threading
+from typing import TYPE_CHECKING, Any, ClassVar, NamedTuple
+
+import anyio
+from pydantic import BaseModel, ConfigDict, Field, ValidationError
+
+from rune.core.tools.base import (
+    BaseTool,
+    BaseToolConfig,
+    BaseToolState,
+    InvokeContext,
+    ToolError,
+    working_dir,
+)
+from rune.core.tools.ui import ToolCallDisplay, ToolResultDisplay
+from rune.core.types import ToolStreamEvent
+from rune.core.utils import logger
+
+if TYPE_CHECKING:
+    import wasmtime
+
+    from rune.core.config import PluginGrants, PluginsConfig
+    from rune.core.types import ToolCallEvent, ToolResultEvent
+
+# Where a plugin with `read_workspace` sees the working directory.
+WORKSPACE_MOUNT = "/workspace"
+_MAX_LOG_CHARS = 2000
+# Plugin and tool names both end up in the published tool name.
+_NAME_PATTERN = r"^[A-Za-z0-9_-]+$"
+
+# The compiled module of each plugin file and the version it was compiled from,
+# so a file is compiled once per process rather than once per tool manager, and
+# an edited file replaces its old module instead of accumulating beside it.
+_modules: dict[Path, tuple[tuple[int, int], Any]] = {}
+
+
+class PluginError(Exception):
+    """Raised when a plugin can't be loaded or a call into it fails."""
+
+
+class PluginToolSpec(BaseModel):
+    name: str = Field(pattern=_NAME_PATTERN)
+    description: str = ""
+    parameters: dict[str, Any] = Field(
+        default_factory=lambda: {"type": "object", "properties": {}}
+    )
+
+
+class PluginToolResult(BaseModel):
+    plugin: str
+    tool: str
+    output: Any = None
+
+
+class _OpenArgs(BaseModel):
+    model_config = ConfigDict(extra="allow")
+
+
+def _wasmtime() -> Any:
+    try:
+        import wasmtime
+    except ImportError as e:
+        raise PluginError(
+            'wasmtime is not installed; run `pip install "rune-cli[plugins]"`'
+        ) from e
+    return wasmtime
+
+
+@functools.cache
+def _engine() -> wasmtime.Engine:
+    wasmtime = _wasmtime()
+    config = wasmtime.Config()
+    config.consume_fuel = True
+    return wasmtime.Engine(config)
+
+
+def _compile(path: Path) -> wasmtime.Module:
+    stat = path.stat()
+    key, version = path.resolve(), (stat.st_mtime_ns, stat.st_size)
+    cached = _modules.get(key)
+    if cached is not None and cached[0] == version:
+        return cached[1]
+    module = _wasmtime().Module.from_file(_engine(), str(path))
+    _modules[key] = (version, module)
+    return module
+
+
+class WasmPlugin:
+    """A WebAssembly plugin module.
+
+    Plugins are core WebAssembly modules with a small ABI of Rune's own, not
+    components. A plugin exports `memory`, `rune_alloc(size) -> ptr`,
+    `rune_tools()` and `rune_call(name_ptr, name_len, args_ptr, args_len)`; the
+    last two return `ptr << 32 | len` of a UTF-8 JSON reply. It may import
+    `rune.log(ptr, len)` and WASI (preview 1), which sees only what its grants
+    allow.
+
+    Every call runs in a fresh instance, so calls share no state and a trap
+    leaves nothing behind.
+    """
+
+    def __init__(
+        self,
+        path: Path,
+        *,
+        grants: PluginGrants,
+        max_memory_mb: int = 64,
+        max_fuel: int = 1_000_000_000,
+    ) -> None:
+        wasmtime = _wasmtime()
+        if not re.match(_NAME_PATTERN, path.stem):
+            raise PluginError(
+                f"{path.name}: plugin names may only use letters, digits, _ and -"
+            )
+        self.name = path.stem
+        self._grants = grants
+        self._max_memory = max_memory_mb * 1024 * 1024
+        self._max_fuel = max_fuel
+        self._lock = threading.Lock()
+
+        self._engine = _engine()
+        try:
+            self._module = _compile(path)
+        except wasmtime.WasmtimeError as e:
+            raise PluginError(
+                f"{path.name} is not a valid WebAssembly module: {e}"
+            ) from e
+        self._linker = wasmtime.Linker(self._engine)
+        self._linker.define_wasi()
+        i32 = wasmtime.ValType.i32()
+        self._linker.define_func(
+            "rune",
+            "log",
+            wasmtime.FuncType([i32, i32], []),
+            self._log,
+            access_caller=True,
+        )
+
+    def tools(self) -> list[PluginToolSpec]:
+        reply = self._invoke("rune_tools")
+        try:
+            return [PluginToolSpec.model_validate(spec) for spec in reply]
+        except (TypeError, ValidationError) as e:
+            raise PluginError(f"{self.name} listed invalid tools: {e}") from e
+
+    def call(self, tool: str, args: dict[str, Any], cwd: Path | None = None) -> Any:
+        """Call `tool`; a `read_workspace` grant sees `cwd`, or the process's
+        working directory without one.
+        """
+        reply = self._invoke(
+            "rune_call", tool.encode(), json.dumps(args).encode(), cwd=cwd
+        )
+        if not isinstance(reply, dict):
+            raise PluginError(f"{self.name} replied with {type(reply).__name__}")
+        if (error := reply.get("error")) is not None:
+            raise PluginError(str(error))
+        return reply.get("output")
+
+    def _invoke(self, export: str, *inputs: bytes, cwd: Path | None = None) -> Any:
+        wasmtime = _wasmtime()
+        with self._lock:
+            store = wasmtime.Store(self._engine)
+            store.set_fuel(self._max_fuel)
+            store.set_limits(memory_size=self._max_memory)
+            store.set_wasi(self._wasi_config(cwd or Path.cwd()))
+            try:
+                exports = self._linker.instantiate(store, self._module).exports(store)
+                memory = exports["memory"]
+                args: list[int] = []
+                for data in inputs:
+                    ptr = exports["rune_alloc"](store, len(data))
+                    memory.write(store, data, ptr)
+                    args += [ptr, len(data)]
+                packed = exports[export](store, *args) & 0xFFFF_FFFF_FFFF_FFFF
+                ptr, length = packed >> 32, packed & 0xFFFF_FFFF
+                reply = bytes(memory.read(store, ptr, ptr + length))
+            except KeyError as e:
+                raise PluginError(f"{self.name} doesn't export {e}") from e
+            except wasmtime.Trap as e:
+                if store.get_fuel() == 0:
+                    raise PluginError(
+                        f"{self.name} ran out of fuel; raise [plugins] max_fuel"
+                    ) from e
+                raise PluginError(f"{self.name} crashed: {e.message}") from e
+            except wasmtime.WasmtimeError as e:
+                raise PluginError(f"{self.name} failed: {e}") from e
+        try:
+            return json.loads(reply)
+        except ValueError as e:
+            raise PluginError(f"{self.name} returned invalid JSON from {export}") from e
+
+    def _wasi_config(self, cwd: Path) -> wasmtime.WasiConfig:
+        wasmtime = _wasmtime()
+        wasi = wasmtime.WasiConfig()
+        wasi.env = [
+            (name, os.environ[name]) for name in self._grants.env if name in os.environ
+        ]
+        if self._grants.read_workspace:
+            wasi.preopen_dir(
+                str(cwd),
+                WORKSPACE_MOUNT,
+                wasmtime.DirPerms.READ_ONLY,
+                wasmtime.FilePerms.READ_ONLY,
+            )
+        return wasi
+
+    def _log(self, caller: wasmtime.Caller, ptr: int, length: int) -> None:
+        memory = caller.get("memory")
+        if memory is None:
+            return
+        text = bytes(memory.read(caller, ptr, ptr + length))
+        logger.info(
+            "plugin %s: %s",
+            self.name,
+            text.decode("utf-8", "replace")[:_MAX_LOG_CHARS],
+        )
+
+
+class LoadedPlugin(NamedTuple):
+    name: str
+    plugin: WasmPlugin | None
+    tools: list[PluginToolSpec]
+    error: str | None = None
+
+
+def load_plugins(directory: Path, config: PluginsConfig) -> list[LoadedPlugin]:
+    """The `*.wasm` plugins in `directory`, with their tools, or why they
+    couldn't be loaded.
+    """
+    if not config.enabled or not directory.is_dir():
+        return []
+    loaded = []
+    for path in sorted(directory.glob("*.wasm")):
+        try:
+            plugin = WasmPlugin(
+                path,
+                grants=config.grants_for(path.stem),
+                max_memory_mb=config.max_memory_mb,
+                max_fuel=config.max_fuel,
+            )
+            loaded.append(LoadedPlugin(path.stem, plugin, plugin.tools()))
+        except PluginError as e:
+            logger.warning("Failed to load plugin %s: %s", path.name, e)
+            loaded.append(LoadedPlugin(path.stem, None, [], str(e)))
+    return loaded
+
+
+def create_plugin_tool_class(
+    plugin: WasmPlugin, spec: PluginToolSpec
+) -> type[BaseTool[_OpenArgs, PluginToolResult, BaseToolConfig, BaseToolState]]:
+    published_name = f"{plugin.name}_{spec.name}"
+
+    class PluginTool(
+        BaseTool[_OpenArgs, PluginToolResult, BaseToolConfig, BaseToolState]
+    ):
+        description: ClassVar[str] = (
+            f"[{plugin.name}] " + (spec.description or f"Plugin tool '{spec.name}'")
+        )
+        _plugin: ClassVar[WasmPlugin] = plugin
+        _spec: ClassVar[PluginToolSpec] = spec
+
+        @classmethod
+        def get_name(cls) -> str:
+            return published_name
+
+        @classmethod
+        def get_parameters(cls) -> dict[str, Any]:
+            return dict(cls._spec.parameters)
+
+        async def run(
+            self, args: _OpenArgs, ctx: InvokeContext | None = None
+        ) -> AsyncGenerator[ToolStreamEvent | PluginToolResult, None]:
+            payload = args.model_dump(exclude_none=True)
+            try:
+                output = await anyio.to_thread.run_sync(
+                    self._plugin.call, self._spec.name, payload, working_dir(ctx)
+                )
+            except PluginError as exc:
+                raise ToolError(f"Plugin call failed: {exc}") from exc
+            yield PluginToolResult(
+                plugin=self._plugin.name, tool=self._spec.name, output=output
+            )
+
+        @classmethod
+        def get_call_display(cls, event: ToolCallEvent) -> ToolCallDisplay:
+            return ToolCallDisplay(summary=published_name)
+
+        @classmethod
+        def get_result_display(cls, event: ToolResultEvent) -> ToolResultDisplay:
+            if not isinstance(event.result, PluginToolResult):
+                return ToolResultDisplay(
+                    success=False,
+                    message=event.error or event.skip_reason or "No result",
+                )
+            return ToolResultDisplay(
+                success=True, message=f"Plugin tool {event.result.tool} completed"
+            )
+
+        @classmethod
+        def get_status_text(cls) -> str:
+            return f"Calling plugin tool {spec.name}"
+
+    PluginTool.__name__ = f"Plugin_{plugin.name}__{spec.name}"
+    return PluginTool
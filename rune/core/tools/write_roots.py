@@ -0,0 +1,44 @@
+from __future__ import annotations
+
+from collections.abc import Callable
+from pathlib import Path
+
+
+class WriteRootGrants:
+    """Directories outside the workspace that tools may write to this session.
+
+    The workspace (the thread's working directory, given by ``workspace``) is
+    always writable; everything else has to be granted, either by approving a
+    write there or via ``/permissions add``.
+    """
+
+    def __init__(self, workspace: Callable[[], Path] = Path.cwd) -> None:
+        self._workspace = workspace
+        self._roots: list[Path] = []
+
+    @property
+    def roots(self) -> list[Path]:
+        return list(self._roots)
+
+    def allows(self, path: Path) -> bool:
+        resolved = path.expanduser().resolve()
+        return any(
+            resolved.is_relative_to(root)
+            for root in [self._workspace().resolve(), *self._roots]
+        )
+
+    def add(self, path: Path) -> Path:
+        root = path.expanduser().resolve()
+        if root not in self._roots:
+            self._roots.append(root)
+        return root
+
+    def remove(self, path: Path) -> bool:
+        root = path.expanduser().resolve()
+        if root not in self._roots:
+            return False
+        self._roots.remove(root)
+        return True
+
+    def clear(self) -> None:
+        self._roots.clear()
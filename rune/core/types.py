@@ -28,22 +28,41 @@ class AgentStats(BaseModel):
     steps: int = 0
     session_prompt_tokens: int = 0
     session_completion_tokens: int = 0
+    session_cached_tokens: int = 0
     tool_calls_agreed: int = 0
     tool_calls_rejected: int = 0
     tool_calls_failed: int = 0
     tool_calls_succeeded: int = 0
+    tool_calls_cached: int = 0
 
     context_tokens: int = 0
 
     last_turn_prompt_tokens: int = 0
     last_turn_completion_tokens: int = 0
+    last_turn_cost: float = 0.0
     last_turn_duration: float = 0.0
     tokens_per_second: float = 0.0
 
+    # Wall-clock time of the last user turn and of the whole session, split into
+    # time spent waiting on the model and time spent running tools.
+    last_turn_wall_time: float = 0.0
+    last_turn_model_time: float = 0.0
+    last_turn_tool_time: float = 0.0
+    session_wall_time: float = 0.0
+    session_model_time: float = 0.0
+    session_tool_time: float = 0.0
+
     # Pricing information (per million tokens)
     input_price_per_million: float = 0.0
     output_price_per_million: float = 0.0
 
+    # Cost of each request at the price of the model that served it, and the
+    # tokens it covers. Tokens counted without a price (e.g. stats of older
+    # sessions) are charged at the current prices.
+    accrued_cost: float = 0.0
+    accrued_prompt_tokens: int = 0
+    accrued_completion_tokens: int = 0
+
     _listeners: dict[str, Callable[[AgentStats], None]] = PrivateAttr(
         default_factory=dict
     )
@@ -75,10 +94,24 @@ class AgentStats(BaseModel):
     @computed_field
     @property
     def session_cost(self) -> float:
-        """Calculate the total session cost based on current pricing."""
-        input_cost = (self.session_prompt_tokens / 1_000_000) * self.input_price_per_million
-        output_cost = (self.session_completion_tokens / 1_000_000) * self.output_price_per_million
-        return input_cost + output_cost
+        """The accrued cost, plus tokens not accrued at current pricing."""
+        prompt = self.session_prompt_tokens - self.accrued_prompt_tokens
+        completion = self.session_completion_tokens - self.accrued_completion_tokens
+        input_cost = (prompt / 1_000_000) * self.input_price_per_million
+        output_cost = (completion / 1_000_000) * self.output_price_per_million
+        return self.accrued_cost + input_cost + output_cost
+
+    def record_usage(self, usage: LLMUsage, cost: float) -> None:
+        """Add the usage of one request and what it cost."""
+        self.last_turn_prompt_tokens = usage.prompt_tokens
+        self.last_turn_completion_tokens = usage.completion_tokens
+        self.last_turn_cost = cost
+        self.session_prompt_tokens += usage.prompt_tokens
+        self.session_completion_tokens += usage.completion_tokens
+        self.session_cached_tokens += usage.cached_tokens
+        self.accrued_prompt_tokens += usage.prompt_tokens
+        self.accrued_completion_tokens += usage.completion_tokens
+        self.accrued_cost += cost
 
     def update_pricing(self, input_price: float, output_price: float) -> None:
         """Update the pricing information for token costs."""
@@ -94,6 +127,7 @@ class AgentStats(BaseModel):
         self.context_tokens = 0
         self.last_turn_prompt_tokens = 0
         self.last_turn_completion_tokens = 0
+        self.last_turn_cost = 0.0
         self.last_turn_duration = 0.0
         self.tokens_per_second = 0.0
 
@@ -114,6 +148,11 @@ class SessionMetadata(BaseModel):
     git_branch: str | None
     environment: dict[str, str | None]
     username: str
+    name: str | None = None
+    auto_title: str | None = None
+    tags: list[str] = Field(default_factory=list)
+    forked_from: str | None = None
+    forked_at_turn: int | None = None
 
 
 StrToolChoice = Literal["auto", "none", "any", "required"]
@@ -171,6 +210,16 @@ class ApprovalResponse(StrEnum):
     NO = "n"
 
 
+class ImageContent(BaseModel):
+    media_type: str = "image/png"
+    # Base64, without a data: prefix.
+    data: str
+
+    @property
+    def data_url(self) -> str:
+        return f"data:{self.media_type};base64,{self.data}"
+
+
 class LLMMessage(BaseModel):
     model_config = ConfigDict(extra="ignore")
 
@@ -181,6 +230,11 @@ class LLMMessage(BaseModel):
     name: str | None = None
     tool_call_id: str | None = None
     message_id: str | None = None
+    # Offsets in `content` that end a prefix worth caching on the provider side.
+    cache_breaks: list[int] = Field(default_factory=list, exclude=True)
+    # Images for models that take them, e.g. a screenshot a tool took. Each
+    # backend sends them its own way, and sessions don't save them.
+    images: list[ImageContent] = Field(default_factory=list, exclude=True)
 
     @model_validator(mode="before")
     @classmethod
@@ -260,11 +314,14 @@ class LLMUsage(BaseModel):
     model_config = ConfigDict(frozen=True)
     prompt_tokens: int = 0
     completion_tokens: int = 0
+    # Part of the prompt tokens read from the provider's prompt cache.
+    cached_tokens: int = 0
 
     def __add__(self, other: LLMUsage) -> LLMUsage:
         return LLMUsage(
             prompt_tokens=self.prompt_tokens + other.prompt_tokens,
             completion_tokens=self.completion_tokens + other.completion_tokens,
+            cached_tokens=self.cached_tokens + other.cached_tokens,
         )
 
 
@@ -333,6 +390,197 @@ class ToolStreamEvent(BaseEvent):
     tool_call_id: str
 
 
+class ToolOutputEvent(ToolStreamEvent):
+    """Output a running command wrote since the last event; `message` holds
+    the new text.
+    """
+
+    stream: Literal["stdout", "stderr"]
+
+
+class ModelRoutedEvent(BaseEvent):
+    model: str
+    reason: str
+
+
+class ModelFallbackEvent(BaseEvent):
+    from_model: str
+    to_model: str
+    reason: str
+
+
+class ApiKeyWarningEvent(BaseEvent):
+    """At session start, the active provider's key is missing, was turned
+    down, expires soon or is due for rotation.
+    """
+
+    provider: str
+    account: str | None
+    state: str
+    message: str
+
+
+class ProxyAuthFailedEvent(BaseEvent):
+    """The proxy turned down the credentials of a request to a provider."""
+
+    provider: str
+    # Without its credentials; None when it came from the environment.
+    proxy: str | None
+
+
+class ProjectDocsLoadedEvent(BaseEvent):
+    """Instruction files put into the system prompt of a new thread."""
+
+    paths: list[str]
+    # Those cut to fit the size limits.
+    truncated: list[str] = Field(default_factory=list)
+
+
+class ModelListChangedEvent(BaseEvent):
+    """The configured models changed on disk and were reloaded for new turns."""
+
+    added: list[str] = Field(default_factory=list)
+    removed: list[str] = Field(default_factory=list)
+    changed: list[str] = Field(default_factory=list)
+    active_model: str
+    # Alias the session was on before an `upgrade_to` entry moved it.
+    upgraded_from: str | None = None
+
+
+class ConfigReloadedEvent(BaseEvent):
+    """The config file changed on disk and its safe changes were applied for
+    new turns.
+    """
+
+    applied: list[str] = Field(default_factory=list)
+    # Changed keys that only take effect in a new session.
+    needs_restart: list[str] = Field(default_factory=list)
+    active_model: str
+
+
+class OutputSchemaUnsupportedEvent(BaseEvent):
+    """The turn asked for a structured final output, but the model's provider
+    can't constrain its replies to a JSON schema.
+    """
+
+    model: str
+    provider: str
+
+
+class StreamStalledEvent(BaseEvent):
+    idle_seconds: float
+    attempt: int
+    # The partial reply is continued rather than generated again.
+    resumed: bool
+    # Output streamed before the stall is thrown away and will be replaced.
+    discarded_output: bool
+
+
+class TokenCountEvent(BaseEvent):
+    """Token usage of the request being streamed, sent periodically until the
+    provider's own numbers replace it in the stats at the end of the request.
+    """
+
+    prompt_tokens: int
+    completion_tokens: int
+    context_window: int | None = None
+    # Some counts are approximated from the text sent and streamed so far.
+    estimated: bool = True
+    rate_limited_at: str | None = None
+    cached_tokens: int = 0
+    # Cost of the request so far, and of the session including it.
+    cost: float = 0.0
+    session_cost: float = 0.0
+
+    @property
+    def context_tokens(self) -> int:
+        return self.prompt_tokens + self.completion_tokens
+
+    @property
+    def context_utilization(self) -> float | None:
+        if not self.context_window:
+            return None
+        return self.context_tokens / self.context_window
+
+
+class WorkspacePeer(BaseModel):
+    """Another live Rune thread working in the same directory."""
+
+    lease_id: str
+    session_id: str
+    pid: int
+    hostname: str
+    started_at: str
+    read_only: bool = False
+
+
+class ConcurrentSessionEvent(BaseEvent):
+    workspace: str
+    peers: list[WorkspacePeer]
+
+
+class ContextBudgetWarningEvent(BaseEvent):
+    """The context crossed the warning threshold of its effective window."""
+
+    context_tokens: int
+    # The smaller of the model's context window and the auto-compact threshold.
+    context_window: int
+    threshold: float
+
+    @property
+    def utilization(self) -> float:
+        return self.context_tokens / self.context_window
+
+
+class LocalModelDownloadEvent(BaseEvent):
+    """The active local model is being downloaded before the turn starts."""
+
+    model: str
+    # None until the size of the download is known.
+    fraction: float | None = None
+    done: bool = False
+
+
+class DeliverableKind(StrEnum):
+    COMMAND = auto()
+    COMMIT_MESSAGE = auto()
+
+
+class DeliverableEvent(BaseEvent):
+    """The final reply holds the command or commit message the user asked for,
+    ready to be used as is.
+    """
+
+    kind: DeliverableKind
+    content: str
+    language: str | None = None
+    message_id: str | None = None
+
+
+class SecretsRedactedEvent(BaseEvent):
+    """Credentials were masked in a tool output before it reached the model."""
+
+    tool_name: str
+    tool_call_id: str
+    # How many secrets of each kind, e.g. {"aws_access_key": 1}.
+    redactions: dict[str, int]
+
+    @property
+    def total(self) -> int:
+        return sum(self.redactions.values())
+
+
+class ModerationAction(StrEnum):
+    ALLOW = auto()
+    ANNOTATE = auto()
+    BLOCK = auto()
+
+
+class ModerationEvent(BaseEvent):
+    action: ModerationAction
+    message: str
+
+
 class CompactStartEvent(BaseEvent):
     current_context_tokens: int
     threshold: int
@@ -341,6 +589,9 @@ class CompactStartEvent(BaseEvent):
     # should be represented.
     # [RFD](https://agentclientprotocol.com/rfds/session-usage)
     tool_call_id: str
+    # The conversation goes on while the summary is written; the matching
+    # CompactEndEvent comes once it is swapped in, on a later turn.
+    background: bool = False
 
 
 class CompactEndEvent(BaseEvent):
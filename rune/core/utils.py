@@ -24,8 +24,15 @@ CANCELLATION_TAG = "user_cancellation"
 TOOL_ERROR_TAG = "tool_error"
 RUNE_STOP_EVENT_TAG = "rune_stop_event"
 RUNE_WARNING_TAG = "rune_warning"
+RUNE_CONTEXT_TAG = "rune_context"
 
-KNOWN_TAGS = [CANCELLATION_TAG, TOOL_ERROR_TAG, RUNE_STOP_EVENT_TAG, RUNE_WARNING_TAG]
+KNOWN_TAGS = [
+    CANCELLATION_TAG,
+    TOOL_ERROR_TAG,
+    RUNE_STOP_EVENT_TAG,
+    RUNE_WARNING_TAG,
+    RUNE_CONTEXT_TAG,
+]
 
 
 class TaggedText:
@@ -347,5 +354,27 @@ def compact_reduction_display(old_tokens: int | None, new_tokens: int | None) ->
     )
 
 
+_SECONDS_PER_MINUTE = 60
+_MINUTES_PER_HOUR = 60
+
+
+def format_duration(seconds: float) -> str:
+    """`seconds` rounded to the second, as e.g. `42s`, `3m7s` or `1h2m7s`."""
+    minutes, secs = divmod(round(seconds), _SECONDS_PER_MINUTE)
+    if not minutes:
+        return f"{secs}s"
+    hours, mins = divmod(minutes, _MINUTES_PER_HOUR)
+    if not hours:
+        return f"{mins}m{secs}s"
+    return f"{hours}h{mins}m{secs}s"
+
+
+def turn_timing_display(wall_time: float, model_time: float, tool_time: float) -> str:
+    parts = [format_duration(wall_time), f"model {format_duration(model_time)}"]
+    if tool_time:
+        parts.append(f"tools {format_duration(tool_time)}")
+    return " · ".join(parts)
+
+
 def utc_now() -> datetime:
     return datetime.now(UTC)
@@ -0,0 +1,168 @@
+from __future__ import annotations
+
+from dataclasses import dataclass
+from pathlib import Path
+import re
+from typing import TYPE_CHECKING, Any
+
+from pydantic import BaseModel
+
+from rune.core.workspace_lease import create_worktree
+
+if TYPE_CHECKING:
+    from rune.core.tools.write_roots import WriteRootGrants
+
+# Tool arguments that hold a path and may use a `root:relative/path` prefix.
+PATH_ARGS = ("path", "file_path")
+
+# Root names are at least two characters long, so `C:\...` stays a drive letter.
+_MIN_SLUG_LEN = 2
+_ROOT_NAME = re.compile(r"[A-Za-z][\w.-]+")
+_ROOT_PREFIX = re.compile(rf"^(?P<name>{_ROOT_NAME.pattern}):(?P<rest>.*)$")
+
+
+@dataclass(frozen=True, slots=True)
+class WorkspaceRoot:
+    name: str
+    path: Path
+
+
+class Workspace:
+    """The directories a thread works across, e.g. packages of a monorepo.
+
+    One root is active at a time: it is the thread's working directory, so
+    relative paths and shell commands resolve against it. Other roots are
+    addressed explicitly with a ``name:relative/path`` prefix. The process's
+    working directory is left alone, as other threads may share the process.
+
+    With ``write_roots``, the roots added and the ones switched away from stay
+    writable.
+    """
+
+    def __init__(
+        self, primary: Path, write_roots: WriteRootGrants | None = None
+    ) -> None:
+        primary = primary.expanduser().resolve()
+        self._roots: dict[str, WorkspaceRoot] = {}
+        self._active = self._insert(primary, None).name
+        self._write_roots = write_roots
+
+    @property
+    def roots(self) -> list[WorkspaceRoot]:
+        return list(self._roots.values())
+
+    @property
+    def active(self) -> WorkspaceRoot:
+        return self._roots[self._active]
+
+    @property
+    def is_multi_root(self) -> bool:
+        return len(self._roots) > 1
+
+    def get(self, name: str) -> WorkspaceRoot | None:
+        return self._roots.get(name)
+
+    def add(self, path: Path, name: str | None = None) -> WorkspaceRoot:
+        resolved = path.expanduser().resolve()
+        if not resolved.is_dir():
+            raise ValueError(f"Not a directory: {resolved}")
+        for root in self._roots.values():
+            if root.path == resolved:
+                return root
+        if name is not None:
+            if not _ROOT_NAME.fullmatch(name):
+                raise ValueError(
+                    f"Invalid root name '{name}': use at least two letters, "
+                    "digits, '.', '_' or '-', starting with a letter"
+                )
+            if name in self._roots:
+                raise ValueError(f"A root named '{name}' already exists")
+        root = self._insert(resolved, name)
+        if self._write_roots is not None:
+            self._write_roots.add(root.path)
+        return root
+
+    def remove(self, name: str) -> bool:
+        if name == self._active or name not in self._roots:
+            return False
+        del self._roots[name]
+        return True
+
+    def use(self, name: str) -> WorkspaceRoot:
+        """Make ``name`` the active root."""
+        if (root := self._roots.get(name)) is None:
+            raise ValueError(f"Unknown workspace root: {name}")
+        if self._write_roots is not None:
+            self._write_roots.add(self.active.path)
+        self._active = name
+        return root
+
+    def fork_worktree(self, session_id: str) -> WorkspaceRoot:
+        """Continue in a new git worktree of the active root, on a branch named
+        after ``session_id``, so the thread's edits can't collide with another
+        thread's.
+        """
+        path = create_worktree(self.active.path, f"rune/{session_id[:8]}")
+        root = self._insert(path.resolve(), None)
+        # Unlike switching roots, the old checkout is not kept writable: leaving
+        # it alone is the point of forking.
+        self._active = root.name
+        return root
+
+    def resolve(self, raw: str) -> str:
+        """Expand a ``root:relative/path`` reference into an absolute path."""
+        if (match := _ROOT_PREFIX.match(raw)) and (
+            root := self._roots.get(match["name"])
+        ):
+            return str(root.path / match["rest"].lstrip("/\\"))
+        return raw
+
+    def expand_args[T: BaseModel](self, args: T) -> T:
+        update: dict[str, Any] = {}
+        for field in PATH_ARGS:
+            value = getattr(args, field, None)
+            if not isinstance(value, str) or not value:
+                continue
+            resolved = self.resolve(value)
+            if not Path(resolved).expanduser().is_absolute():
+                # Tools would resolve it against the process's directory.
+                resolved = str(self.active.path / resolved)
+            if resolved != value:
+                update[field] = resolved
+        return args.model_copy(update=update) if update else args
+
+    def describe(self) -> str:
+        lines = [
+            f"- {root.name}: {root.path}"
+            + (" (active, the working directory)" if root.name == self._active else "")
+            for root in self._roots.values()
+        ]
+        return "\n".join([
+            "This thread works across several workspace roots:",
+            *lines,
+            "Relative paths resolve against the active root. Address a file in "
+            "another root as `name:relative/path`, e.g. "
+            f"`{self._other_root_name()}:README.md`; shell commands run in the "
+            "active root, so use absolute paths there.",
+        ])
+
+    def _other_root_name(self) -> str:
+        return next(
+            (name for name in self._roots if name != self._active), self._active
+        )
+
+    def _insert(self, path: Path, name: str | None) -> WorkspaceRoot:
+        base = name or _slug(path.name) or "root"
+        unique, counter = base, 2
+        while unique in self._roots:
+            unique, counter = f"{base}-{counter}", counter + 1
+        root = WorkspaceRoot(name=unique, path=path)
+        self._roots[unique] = root
+        return root
+
+
+def _slug(name: str) -> str:
+    slug = re.sub(r"[^\w.-]", "-", name).strip("-.")
+    if len(slug) < _MIN_SLUG_LEN or not slug[0].isalpha():
+        slug = f"r-{slug}" if slug else ""
+    return slug
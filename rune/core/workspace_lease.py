@@ -0,0 +1,204 @@
+from __future__ import annotations
+
+import asyncio
+from collections.abc import Callable
+import hashlib
+import json
+import os
+from pathlib import Path
+import socket
+import subprocess
+import time
+from typing import Any
+from uuid import uuid4
+import weakref
+
+from pydantic import ValidationError
+
+from rune.core.paths.global_paths import WORKSPACE_LEASES_DIR
+from rune.core.types import ConcurrentSessionEvent, WorkspacePeer
+from rune.core.utils import is_windows, logger, utc_now
+
+# Processes on other machines (a workspace on a network share) can't be probed,
+# so their leases expire when not refreshed for this long.
+REMOTE_LEASE_TTL_SECONDS = 15 * 60
+GIT_TIMEOUT_SECONDS = 30
+
+
+class WorktreeError(RuntimeError):
+    pass
+
+
+class WorkspaceLease:
+    """Records that a thread of this process works in ``workspace``, so threads
+    started in the same directory can find each other.
+
+    Leases are kept under RUNE_HOME, one directory per workspace and one file
+    per thread, rather than in the workspace itself. A lease stays live while
+    its process runs; the ones crashed processes leave behind are removed when
+    found.
+    """
+
+    def __init__(
+        self, workspace: Path, lease_id: str, leases_dir: Path | None = None
+    ) -> None:
+        self.workspace = workspace.resolve()
+        self.lease_id = lease_id
+        digest = hashlib.sha256(str(self.workspace).encode()).hexdigest()[:16]
+        self._dir = (leases_dir or WORKSPACE_LEASES_DIR.path) / digest
+        self._started_at = utc_now().isoformat()
+        self._reported: set[str] = set()
+
+    @property
+    def path(self) -> Path:
+        return self._dir / f"{self.lease_id}.json"
+
+    def refresh(
+        self, session_id: str, *, read_only: bool = False
+    ) -> list[WorkspacePeer]:
+        """Write this lease and return the live peers not returned before."""
+        record = WorkspacePeer(
+            lease_id=self.lease_id,
+            session_id=session_id,
+            pid=os.getpid(),
+            hostname=socket.gethostname(),
+            started_at=self._started_at,
+            read_only=read_only,
+        )
+        try:
+            self._dir.mkdir(parents=True, exist_ok=True)
+            self.path.write_text(
+                json.dumps({
+                    **record.model_dump(),
+                    "workspace": str(self.workspace),
+                    "heartbeat_at": time.time(),
+                }),
+                encoding="utf-8",
+            )
+        except OSError as e:
+            logger.warning("Could not write workspace lease %s: %s", self.path, e)
+
+        new_peers = [p for p in self.peers() if p.lease_id not in self._reported]
+        self._reported.update(p.lease_id for p in new_peers)
+        return new_peers
+
+    def peers(self) -> list[WorkspacePeer]:
+        live: list[WorkspacePeer] = []
+        for lease_file in self._dir.glob("*.json"):
+            if lease_file == self.path:
+                continue
+            try:
+                data = json.loads(lease_file.read_text(encoding="utf-8"))
+                peer = WorkspacePeer.model_validate(data)
+                heartbeat_at = float(data["heartbeat_at"])
+            except (OSError, ValueError, ValidationError, KeyError, TypeError):
+                continue
+            if _is_live(peer, heartbeat_at):
+                live.append(peer)
+                continue
+            try:
+                lease_file.unlink(missing_ok=True)
+            except OSError:
+                pass
+        return sorted(live, key=lambda peer: peer.started_at)
+
+    def release(self) -> None:
+        try:
+            self.path.unlink(missing_ok=True)
+        except OSError:
+            pass
+
+
+class ThreadLease:
+    """The lease of one thread, moved along when the thread changes working
+    directory.
+
+    Lease files are read and written off the event loop, as RUNE_HOME can sit
+    on a slow network share.
+    """
+
+    def __init__(self) -> None:
+        self._lease: WorkspaceLease | None = None
+        self._release: Callable[[], Any] | None = None
+
+    async def check(
+        self, workspace: Path, session_id: str, *, read_only: bool
+    ) -> ConcurrentSessionEvent | None:
+        """Renew the lease on ``workspace`` and report the peers found there
+        since the last check.
+        """
+        if self._lease is None or self._lease.workspace != workspace.resolve():
+            if self._release is not None:
+                await asyncio.to_thread(self._release)
+            self._lease = WorkspaceLease(workspace, str(uuid4()))
+            # Also runs at exit, and when the thread is garbage collected.
+            self._release = weakref.finalize(self, self._lease.release)
+
+        peers = await asyncio.to_thread(
+            self._lease.refresh, session_id, read_only=read_only
+        )
+        if not peers:
+            return None
+        return ConcurrentSessionEvent(workspace=str(workspace), peers=peers)
+
+
+def describe_peers(workspace: str, peers: list[WorkspacePeer]) -> str:
+    threads = ", ".join(
+        f"session {peer.session_id[:8]} (pid {peer.pid}"
+        + (f" on {peer.hostname}" if peer.hostname != socket.gethostname() else "")
+        + (", read-only" if peer.read_only else "")
+        + ")"
+        for peer in peers
+    )
+    noun = "thread is" if len(peers) == 1 else "threads are"
+    return (
+        f"Another Rune {noun} also working in {workspace}: {threads}. "
+        "Edits from both can silently conflict."
+    )
+
+
+def _is_live(peer: WorkspacePeer, heartbeat_at: float) -> bool:
+    if peer.hostname == socket.gethostname() and not is_windows():
+        return _pid_running(peer.pid)
+    return time.time() - heartbeat_at < REMOTE_LEASE_TTL_SECONDS
+
+
+def _pid_running(pid: int) -> bool:
+    # Signal 0 only checks that the process exists. On Windows os.kill would
+    # terminate it instead, so leases there rely on their heartbeat.
+    try:
+        os.kill(pid, 0)
+    except PermissionError:
+        return True
+    except OSError:
+        return False
+    return True
+
+
+def create_worktree(workspace: Path, branch: str) -> Path:
+    """Check out a new ``branch`` from HEAD in a worktree next to the repository
+    containing ``workspace``, and return the directory matching ``workspace``
+    in it. Uncommitted changes stay in the original checkout.
+    """
+    workspace = workspace.resolve()
+    repo = Path(_git(workspace, "rev-parse", "--show-toplevel")).resolve()
+    target = repo.parent / f"{repo.name}-{branch.replace('/', '-')}"
+    _git(workspace, "worktree", "add", "-b", branch, str(target), "HEAD")
+    return target / workspace.relative_to(repo)
+
+
+def _git(cwd: Path, *args: str) -> str:
+    try:
+        result = subprocess.run(
+            ["git", *args],
+            cwd=cwd,
+            capture_output=True,
+            stdin=subprocess.DEVNULL if is_windows() else None,
+            text=True,
+            timeout=GIT_TIMEOUT_SECONDS,
+        )
+    except (OSError, subprocess.TimeoutExpired) as e:
+        raise WorktreeError(f"git {args[0]} failed: {e}") from e
+    if result.returncode != 0:
+        raise WorktreeError(result.stderr.strip() or f"git {args[0]} failed")
+    return result.stdout.strip()
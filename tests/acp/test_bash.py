@@ -8,7 +8,7 @@ import pytest
 
 from tests.mock.utils import collect_result
 from rune.acp.tools.builtins.bash import AcpBashState, Bash
-from rune.core.tools.base import ToolError
+from rune.core.tools.base import InvokeContext, ToolError
 from rune.core.tools.builtins.bash import BashArgs, BashResult, BashToolConfig
 
 
@@ -324,6 +324,28 @@ class TestAcpBashTimeout:
         assert str(exc_info.value) == "Command timed out after 1s: 'slow_command'"
 
 
+class TestAcpBashStop:
+    @pytest.mark.asyncio
+    async def test_stop_kills_the_terminal_and_keeps_the_output(
+        self, mock_client: MockClient, acp_bash_tool: Bash
+    ) -> None:
+        handle = MockTerminalHandle(output="partial output", wait_delay=20)
+        mock_client._terminal_handle = handle
+        stop = asyncio.Event()
+        asyncio.get_running_loop().call_later(0.05, stop.set)
+
+        result = await collect_result(
+            acp_bash_tool.run(
+                BashArgs(command="make test"),
+                InvokeContext(tool_call_id="test_call", stop_requested=stop),
+            )
+        )
+
+        assert result.stopped
+        assert result.stdout == "partial output"
+        assert handle._killed
+
+
 class TestAcpBashEmbedding:
     @pytest.mark.asyncio
     async def test_run_with_embedding(self, mock_client: MockClient) -> None:
@@ -0,0 +1,130 @@
+from __future__ import annotations
+
+import asyncio
+import json
+from pathlib import Path
+
+from acp import PROTOCOL_VERSION
+import pytest
+
+from rune.acp.daemon import (
+    NO_DAEMON_ENV,
+    daemon_paths,
+    daemon_pid,
+    running_daemon_socket,
+    serve,
+)
+
+pytestmark = pytest.mark.skipif(
+    not hasattr(asyncio, "start_unix_server"), reason="needs Unix sockets"
+)
+
+
+async def _wait_for_socket(project: Path) -> Path:
+    for _ in range(100):
+        if (socket_path := running_daemon_socket(project)) is not None:
+            return socket_path
+        await asyncio.sleep(0.05)
+    raise TimeoutError("The daemon did not start")
+
+
+def test_each_project_gets_its_own_socket(tmp_path: Path) -> None:
+    first = daemon_paths(tmp_path / "a")
+    second = daemon_paths(tmp_path / "b")
+
+    assert first.socket != second.socket
+    assert first.socket.with_suffix(".pid") == first.pid_file
+
+
+@pytest.mark.asyncio
+async def test_daemon_serves_acp_over_its_socket() -> None:
+    project = Path.cwd()
+    server = asyncio.create_task(serve(project))
+    try:
+        socket_path = await _wait_for_socket(project)
+        reader, writer = await asyncio.open_unix_connection(socket_path)
+        request = {
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "initialize",
+            "params": {"protocolVersion": PROTOCOL_VERSION},
+        }
+        writer.write(json.dumps(request).encode() + b"\n")
+        await writer.drain()
+
+        response = json.loads(await asyncio.wait_for(reader.readline(), 5.0))
+        writer.close()
+
+        assert response["id"] == 1
+        assert response["result"]["protocolVersion"] == PROTOCOL_VERSION
+    finally:
+        server.cancel()
+        with pytest.raises(asyncio.CancelledError):
+            await server
+
+    assert daemon_pid(project) is None
+    assert not daemon_paths(project).socket.exists()
+
+
+@pytest.mark.asyncio
+async def test_clients_can_opt_out_of_the_daemon(
+    monkeypatch: pytest.MonkeyPatch,
+) -> None:
+    project = Path.cwd()
+    server = asyncio.create_task(serve(project))
+    try:
+        await _wait_for_socket(project)
+        monkeypatch.setenv(NO_DAEMON_ENV, "1")
+
+        assert running_daemon_socket(project) is None
+    finally:
+        server.cancel()
+        with pytest.raises(asyncio.CancelledError):
+            await server
+
+
+@pytest.mark.asyncio
+async def test_daemon_files_are_private_to_their_user() -> None:
+    project = Path.cwd()
+    server = asyncio.create_task(serve(project))
+    try:
+        socket_path = await _wait_for_socket(project)
+
+        assert socket_path.parent.stat().st_mode & 0o777 == 0o700
+        assert socket_path.stat().st_mode & 0o777 == 0o600
+    finally:
+        server.cancel()
+        with pytest.raises(asyncio.CancelledError):
+            await server
+
+
+@pytest.mark.asyncio
+async def test_sessions_outside_the_project_are_refused(tmp_path: Path) -> None:
+    project = Path.cwd()
+    server = asyncio.create_task(serve(project))
+    try:
+        socket_path = await _wait_for_socket(project)
+        reader, writer = await asyncio.open_unix_connection(socket_path)
+        for request in (
+            {
+                "id": 1,
+                "method": "initialize",
+                "params": {"protocolVersion": PROTOCOL_VERSION},
+            },
+            {
+                "id": 2,
+                "method": "session/new",
+                "params": {"cwd": str(tmp_path), "mcpServers": []},
+            },
+        ):
+            writer.write(json.dumps({"jsonrpc": "2.0", **request}).encode() + b"\n")
+            await writer.drain()
+            response = json.loads(await asyncio.wait_for(reader.readline(), 5.0))
+        writer.close()
+
+        assert response["id"] == 2
+        assert "only serves" in response["error"]["data"]["cwd"]
+    finally:
+        server.cancel()
+        with pytest.raises(asyncio.CancelledError):
+            await server
@@ -0,0 +1,120 @@
+from __future__ import annotations
+
+from acp import PROTOCOL_VERSION, RequestError
+import pytest
+
+from tests.stubs.fake_client import FakeClient
+from rune.acp import deprecations
+from rune.acp.acp_agent_loop import RuneAcpAgentLoop
+from rune.acp.deprecations import (
+    DEPRECATION_NOTIFICATION,
+    ProtocolDeprecation,
+    Stage,
+    describe_deprecation,
+)
+
+
+@pytest.fixture
+def registry(monkeypatch: pytest.MonkeyPatch) -> list[ProtocolDeprecation]:
+    entries = [
+        ProtocolDeprecation(
+            name="rune/oldStatus",
+            kind="method",
+            stage=Stage.DEPRECATED,
+            replacement="rune/status",
+            removal_version="3.0.0",
+        ),
+        ProtocolDeprecation(
+            name="rune/legacyUsage",
+            kind="method",
+            stage=Stage.REMOVED,
+            replacement="rune/contextUsage",
+        ),
+        ProtocolDeprecation(
+            name="rune/oldWarning",
+            kind="notification",
+            stage=Stage.REMOVED,
+            replacement="rune/contextWarning",
+        ),
+    ]
+    monkeypatch.setattr(deprecations, "DEPRECATIONS", entries)
+    return entries
+
+
+def _client(acp_agent_loop: RuneAcpAgentLoop) -> FakeClient:
+    return acp_agent_loop.client  # pyright: ignore[reportReturnType]
+
+
+def test_describe_deprecation_mentions_replacement_and_version(
+    registry: list[ProtocolDeprecation],
+) -> None:
+    assert describe_deprecation(registry[0]) == (
+        "The method `rune/oldStatus` is deprecated and will be removed in "
+        "Rune 3.0.0; use `rune/status` instead."
+    )
+    assert describe_deprecation(registry[1]) == (
+        "The method `rune/legacyUsage` was removed; "
+        "use `rune/contextUsage` instead."
+    )
+
+
+class TestACPDeprecations:
+    @pytest.mark.asyncio
+    async def test_initialize_lists_deprecations(
+        self,
+        acp_agent_loop: RuneAcpAgentLoop,
+        registry: list[ProtocolDeprecation],
+    ) -> None:
+        response = await acp_agent_loop.initialize(protocol_version=PROTOCOL_VERSION)
+
+        assert response.field_meta is not None
+        listed = response.field_meta["deprecations"]
+        assert [d["name"] for d in listed] == [d.name for d in registry]
+        assert listed[0]["removalVersion"] == "3.0.0"
+        assert listed[0]["replacement"] == "rune/status"
+
+    @pytest.mark.asyncio
+    async def test_deprecated_method_is_announced_once(
+        self,
+        acp_agent_loop: RuneAcpAgentLoop,
+        registry: list[ProtocolDeprecation],
+    ) -> None:
+        for _ in range(2):
+            with pytest.raises(RequestError):
+                # Not a real method, so it still ends as method-not-found.
+                await acp_agent_loop.ext_method("rune/oldStatus", {})
+
+        notifications = _client(acp_agent_loop)._ext_notifications
+        assert len(notifications) == 1
+        method, params = notifications[0]
+        assert method == DEPRECATION_NOTIFICATION
+        assert params["name"] == "rune/oldStatus"
+        assert params["stage"] == "deprecated"
+        assert params["replacement"] == "rune/status"
+        assert params["removalVersion"] == "3.0.0"
+        assert "removed in Rune 3.0.0" in params["description"]
+
+    @pytest.mark.asyncio
+    async def test_removed_method_is_rejected(
+        self,
+        acp_agent_loop: RuneAcpAgentLoop,
+        registry: list[ProtocolDeprecation],
+    ) -> None:
+        with pytest.raises(RequestError):
+            await acp_agent_loop.ext_method("rune/legacyUsage", {})
+
+        [(method, params)] = _client(acp_agent_loop)._ext_notifications
+        assert method == DEPRECATION_NOTIFICATION
+        assert params["stage"] == "removed"
+
+    @pytest.mark.asyncio
+    async def test_removed_notification_is_not_sent(
+        self,
+        acp_agent_loop: RuneAcpAgentLoop,
+        registry: list[ProtocolDeprecation],
+    ) -> None:
+        await acp_agent_loop._ext_notify("rune/oldWarning", {"sessionId": "s"})
+        await acp_agent_loop._ext_notify("rune/contextWarning", {"sessionId": "s"})
+
+        methods = [m for m, _ in _client(acp_agent_loop)._ext_notifications]
+        assert methods == [DEPRECATION_NOTIFICATION, "rune/contextWarning"]
@@ -0,0 +1,235 @@
+from __future__ import annotations
+
+from pathlib import Path
+
+from acp import RequestError
+import pytest
+
+from rune.acp.acp_agent_loop import RuneAcpAgentLoop
+from rune.core.tools.base import BackgroundProcess
+from rune.core.types import LLMUsage
+
+
+class TestACPExtMethod:
+    @pytest.mark.asyncio
+    async def test_set_read_only_toggles_session(
+        self, acp_agent_loop: RuneAcpAgentLoop
+    ) -> None:
+        session_response = await acp_agent_loop.new_session(
+            cwd=str(Path.cwd()), mcp_servers=[]
+        )
+        session_id = session_response.session_id
+        agent_loop = acp_agent_loop.sessions[session_id].agent_loop
+
+        response = await acp_agent_loop.ext_method(
+            "rune/setReadOnly", {"sessionId": session_id, "readOnly": True}
+        )
+
+        assert response == {"readOnly": True}
+        assert agent_loop.read_only is True
+
+        response = await acp_agent_loop.ext_method(
+            "rune/setReadOnly", {"sessionId": session_id, "readOnly": False}
+        )
+
+        assert response == {"readOnly": False}
+        assert agent_loop.read_only is False
+
+    @pytest.mark.asyncio
+    async def test_fork_session_starts_where_its_source_did(
+        self,
+        acp_agent_loop: RuneAcpAgentLoop,
+        tmp_path: Path,
+        monkeypatch: pytest.MonkeyPatch,
+    ) -> None:
+        monkeypatch.chdir(tmp_path)
+        project = tmp_path / "project"
+        project.mkdir()
+        source = await acp_agent_loop.new_session(cwd=str(project), mcp_servers=[])
+
+        response = await acp_agent_loop.ext_method(
+            "rune/forkSession", {"sessionId": source.session_id}
+        )
+
+        fork = acp_agent_loop.sessions[response["sessionId"]]
+        assert fork.cwd == str(project)
+        assert fork.agent_loop.workspace.active.path == project.resolve()
+        assert Path.cwd() == tmp_path
+
+    @pytest.mark.asyncio
+    async def test_set_dry_run_toggles_session(
+        self, acp_agent_loop: RuneAcpAgentLoop
+    ) -> None:
+        session_response = await acp_agent_loop.new_session(
+            cwd=str(Path.cwd()), mcp_servers=[]
+        )
+        session_id = session_response.session_id
+
+        response = await acp_agent_loop.ext_method(
+            "rune/setDryRun", {"sessionId": session_id, "dryRun": True}
+        )
+
+        assert response == {"dryRun": True}
+        assert acp_agent_loop.sessions[session_id].agent_loop.dry_run is True
+
+    @pytest.mark.asyncio
+    async def test_stop_command_stops_the_running_tool_call(
+        self, acp_agent_loop: RuneAcpAgentLoop
+    ) -> None:
+        session_response = await acp_agent_loop.new_session(
+            cwd=str(Path.cwd()), mcp_servers=[]
+        )
+        session_id = session_response.session_id
+        agent_loop = acp_agent_loop.sessions[session_id].agent_loop
+
+        response = await acp_agent_loop.ext_method(
+            "rune/stopCommand", {"sessionId": session_id}
+        )
+        assert response == {"stopped": False}
+
+        stop = agent_loop.tool_stops.open("call_1")
+        response = await acp_agent_loop.ext_method(
+            "rune/stopCommand", {"sessionId": session_id, "toolCallId": "call_1"}
+        )
+
+        assert response == {"stopped": True}
+        assert stop.is_set()
+
+    @pytest.mark.asyncio
+    async def test_background_processes_are_listed_and_killed(
+        self, acp_agent_loop: RuneAcpAgentLoop, monkeypatch: pytest.MonkeyPatch
+    ) -> None:
+        session_response = await acp_agent_loop.new_session(
+            cwd=str(Path.cwd()), mcp_servers=[]
+        )
+        session_id = session_response.session_id
+        tool_manager = acp_agent_loop.sessions[session_id].agent_loop.tool_manager
+        process = BackgroundProcess(
+            id="python", tool_name="python", description="Python kernel", pid=42
+        )
+        killed: list[str] = []
+
+        async def kill(process_id: str) -> bool:
+            killed.append(process_id)
+            return process_id == "python"
+
+        monkeypatch.setattr(
+            tool_manager, "list_background_processes", lambda: [process]
+        )
+        monkeypatch.setattr(tool_manager, "kill_background_process", kill)
+
+        listed = await acp_agent_loop.ext_method(
+            "rune/listBackgroundProcesses", {"sessionId": session_id}
+        )
+        response = await acp_agent_loop.ext_method(
+            "rune/killBackgroundProcess", {"sessionId": session_id, "id": "python"}
+        )
+
+        assert listed == {
+            "processes": [
+                {
+                    "id": "python",
+                    "toolName": "python",
+                    "description": "Python kernel",
+                    "pid": 42,
+                    "startedAt": None,
+                }
+            ]
+        }
+        assert response == {"killed": True}
+        assert killed == ["python"]
+        with pytest.raises(RequestError):
+            await acp_agent_loop.ext_method(
+                "rune/killBackgroundProcess", {"sessionId": session_id}
+            )
+
+    @pytest.mark.asyncio
+    async def test_status_returns_structured_snapshot(
+        self, acp_agent_loop: RuneAcpAgentLoop
+    ) -> None:
+        session_response = await acp_agent_loop.new_session(
+            cwd=str(Path.cwd()), mcp_servers=[]
+        )
+        session_id = session_response.session_id
+        agent_loop = acp_agent_loop.sessions[session_id].agent_loop
+        agent_loop.read_only = True
+
+        response = await acp_agent_loop.ext_method(
+            "rune/status", {"sessionId": session_id}
+        )
+
+        assert response["sessionId"] == agent_loop.session_id
+        assert response["cwd"] == str(Path.cwd())
+        assert response["model"]["alias"] == agent_loop.config.get_active_model().alias
+        assert response["approvals"]["readOnly"] is True
+        assert response["approvals"]["writeRoots"] == []
+        assert response["usage"]["steps"] == agent_loop.stats.steps
+        assert response["mcpServers"] == {}
+        assert response["rateLimitedAt"] is None
+        assert set(response["features"]) >= {"critic", "router", "moderation"}
+
+    @pytest.mark.asyncio
+    async def test_read_usage_returns_tokens_and_cost(
+        self, acp_agent_loop: RuneAcpAgentLoop
+    ) -> None:
+        session_response = await acp_agent_loop.new_session(
+            cwd=str(Path.cwd()), mcp_servers=[]
+        )
+        session_id = session_response.session_id
+        stats = acp_agent_loop.sessions[session_id].agent_loop.stats
+        stats.record_usage(
+            LLMUsage(prompt_tokens=100, completion_tokens=20, cached_tokens=60),
+            cost=0.25,
+        )
+
+        response = await acp_agent_loop.ext_method(
+            "rune/readUsage", {"sessionId": session_id}
+        )
+
+        assert response["promptTokens"] == 100
+        assert response["cachedTokens"] == 60
+        assert response["cost"] == pytest.approx(0.25)
+        assert response["lastTurnCost"] == pytest.approx(0.25)
+
+    @pytest.mark.asyncio
+    async def test_set_session_name_requires_a_name(
+        self, acp_agent_loop: RuneAcpAgentLoop
+    ) -> None:
+        session_response = await acp_agent_loop.new_session(
+            cwd=str(Path.cwd()), mcp_servers=[]
+        )
+        session_id = session_response.session_id
+
+        response = await acp_agent_loop.ext_method(
+            "rune/setSessionName", {"sessionId": session_id, "name": " Triage "}
+        )
+
+        assert response == {"name": "Triage"}
+        with pytest.raises(RequestError):
+            await acp_agent_loop.ext_method(
+                "rune/setSessionName", {"sessionId": session_id, "name": ""}
+            )
+
+    @pytest.mark.asyncio
+    async def test_archiving_an_unknown_session_is_rejected(
+        self, acp_agent_loop: RuneAcpAgentLoop
+    ) -> None:
+        session_response = await acp_agent_loop.new_session(
+            cwd=str(Path.cwd()), mcp_servers=[]
+        )
+
+        with pytest.raises(RequestError):
+            await acp_agent_loop.ext_method(
+                "rune/archiveSession",
+                {
+                    "sessionId": session_response.session_id,
+                    "targetSessionId": "00000000",
+                },
+            )
+
+    @pytest.mark.asyncio
+    async def test_unknown_method_is_rejected(
+        self, acp_agent_loop: RuneAcpAgentLoop
+    ) -> None:
+        with pytest.raises(RequestError):
+            await acp_agent_loop.ext_method("rune/doesNotExist", {})
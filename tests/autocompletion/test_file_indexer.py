@@ -219,6 +219,29 @@ def test_watcher_failure_does_not_break_existing_index(
     )
 
 
+def test_runeignore_hides_paths_and_applies_when_edited(
+    tmp_path: Path, monkeypatch: pytest.MonkeyPatch, file_indexer: FileIndexer
+) -> None:
+    monkeypatch.chdir(tmp_path)
+    (tmp_path / "weights").mkdir()
+    (tmp_path / "weights" / "model.bin").write_text("", encoding="utf-8")
+    (tmp_path / "dump.csv").write_text("", encoding="utf-8")
+    (tmp_path / "main.py").write_text("", encoding="utf-8")
+    (tmp_path / ".runeignore").write_text("weights/\n", encoding="utf-8")
+
+    rels = {entry.rel for entry in file_indexer.get_index(Path("."))}
+    assert "main.py" in rels and "dump.csv" in rels
+    assert not any(rel.startswith("weights") for rel in rels)
+
+    (tmp_path / ".runeignore").write_text("weights/\n*.csv\n", encoding="utf-8")
+
+    assert _wait_for(
+        lambda: all(
+            entry.rel != "dump.csv" for entry in file_indexer.get_index(Path("."))
+        )
+    )
+
+
 def test_shutdown_cleans_up_resources(
     tmp_path: Path, monkeypatch: pytest.MonkeyPatch
 ) -> None:
@@ -0,0 +1,129 @@
+from __future__ import annotations
+
+from pathlib import Path
+
+import pytest
+
+from rune.core.autocompletion.completers import PathCompleter
+from rune.core.autocompletion.path_prompt_adapter import (
+    build_mention_preview,
+    render_path_prompt,
+)
+from rune.core.autocompletion.symbols import (
+    SymbolIndex,
+    extract_symbols,
+    find_symbol_snippet,
+)
+
+PYTHON_SOURCE = """\
+import os
+
+
+class AgentLoop:
+    def act(self) -> None:
+        pass
+
+    def run(self) -> None:
+        pass
+
+
+def helper() -> int:
+    return 1
+"""
+
+RUST_SOURCE = """\
+pub struct Config {
+    name: String,
+}
+
+pub fn load_config(path: &str) -> Config {
+    Config { name: path.to_string() }
+}
+"""
+
+
+@pytest.fixture()
+def source_tree(tmp_path: Path, monkeypatch: pytest.MonkeyPatch) -> Path:
+    (tmp_path / "src").mkdir()
+    (tmp_path / "src" / "agent.py").write_text(PYTHON_SOURCE, encoding="utf-8")
+    (tmp_path / "src" / "config.rs").write_text(RUST_SOURCE, encoding="utf-8")
+    monkeypatch.chdir(tmp_path)
+    return tmp_path
+
+
+def test_extracts_python_definitions() -> None:
+    assert extract_symbols(PYTHON_SOURCE, ".py") == [
+        ("AgentLoop", "class", 4),
+        ("act", "function", 5),
+        ("run", "function", 8),
+        ("helper", "function", 12),
+    ]
+
+
+def test_extracts_nothing_for_unknown_languages() -> None:
+    assert extract_symbols(PYTHON_SOURCE, ".txt") == []
+
+
+def test_python_snippet_covers_the_indented_block(source_tree: Path) -> None:
+    snippet = find_symbol_snippet(source_tree / "src" / "agent.py", "AgentLoop")
+
+    assert snippet is not None
+    assert (snippet.start, snippet.end) == (4, 9)
+    assert snippet.text.splitlines()[-1] == "        pass"
+
+
+def test_braced_snippet_ends_at_the_closing_brace(source_tree: Path) -> None:
+    snippet = find_symbol_snippet(source_tree / "src" / "config.rs", "load_config")
+
+    assert snippet is not None
+    assert (snippet.start, snippet.end) == (5, 7)
+
+
+def test_completer_suggests_symbols_with_their_file(source_tree: Path) -> None:
+    completer = PathCompleter(symbol_index=SymbolIndex())
+
+    items = completer.get_completion_items("look at @AgentL", cursor_pos=15)
+
+    assert ("@src/agent.py#AgentLoop", "class, line 4") in items
+
+
+def test_completer_skips_symbols_when_browsing_directories(source_tree: Path) -> None:
+    completer = PathCompleter(symbol_index=SymbolIndex())
+
+    results = completer.get_completions("@src/", cursor_pos=5)
+
+    assert not any("#" in result for result in results)
+
+
+def test_symbol_mention_attaches_only_the_definition(source_tree: Path) -> None:
+    agent_py = source_tree / "src" / "agent.py"
+
+    rendered = render_path_prompt(
+        "Explain @src/agent.py#helper please", base_dir=source_tree
+    )
+
+    expected = (
+        "Explain src/agent.py#helper please\n\n"
+        f"{agent_py.as_uri()}#L12-L13\n```\n"
+        "def helper() -> int:\n    return 1\n```"
+    )
+    assert rendered == expected
+
+
+def test_unknown_symbol_falls_back_to_the_whole_file(source_tree: Path) -> None:
+    rendered = render_path_prompt("See @src/agent.py#missing", base_dir=source_tree)
+
+    assert "class AgentLoop:" in rendered
+    assert "import os" in rendered
+
+
+def test_preview_truncates_long_content(source_tree: Path) -> None:
+    preview = build_mention_preview("@src/agent.py", base_dir=source_tree, max_lines=3)
+
+    assert preview == "import os\n\n\n… 10 more lines"
+
+
+def test_preview_of_symbol_shows_its_snippet(source_tree: Path) -> None:
+    preview = build_mention_preview("@src/agent.py#helper", base_dir=source_tree)
+
+    assert preview == "def helper() -> int:\n    return 1"
@@ -0,0 +1,241 @@
+from __future__ import annotations
+
+import json
+
+import httpx
+import pytest
+import respx
+
+from rune.core.config import ModelConfig, ProviderConfig
+from rune.core.llm.backend.anthropic import AnthropicAdapter
+from rune.core.llm.backend.generic import GenericBackend
+from rune.core.types import (
+    AvailableFunction,
+    AvailableTool,
+    FunctionCall,
+    ImageContent,
+    LLMChunk,
+    LLMMessage,
+    Role,
+    ToolCall,
+)
+
+BASE_URL = "https://api.anthropic.com"
+PROVIDER = ProviderConfig(
+    name="anthropic",
+    api_base=f"{BASE_URL}/v1",
+    api_key_env_var="ANTHROPIC_API_KEY",
+    api_style="anthropic",
+)
+MODEL = ModelConfig(name="claude-sonnet-4-5", provider="anthropic", alias="sonnet")
+GREP_TOOL = AvailableTool(
+    function=AvailableFunction(
+        name="grep", description="Search", parameters={"type": "object"}
+    )
+)
+
+
+def _sse(*events: dict) -> bytes:
+    return b"\n\n".join(
+        f"event: {event['type']}\ndata: {json.dumps(event)}".encode()
+        for event in events
+    )
+
+
+def test_request_translates_system_tools_and_tool_results() -> None:
+    messages = [
+        LLMMessage(role=Role.system, content="Be brief."),
+        LLMMessage(role=Role.user, content="Find TODOs"),
+        LLMMessage(
+            role=Role.assistant,
+            content="Searching.",
+            tool_calls=[
+                ToolCall(
+                    id="toolu_1",
+                    index=0,
+                    function=FunctionCall(name="grep", arguments='{"pattern": "TODO"}'),
+                )
+            ],
+        ),
+        LLMMessage(role=Role.tool, tool_call_id="toolu_1", content="a.py:1: TODO"),
+    ]
+
+    request = AnthropicAdapter().prepare_request(
+        model_name=MODEL.name,
+        messages=messages,
+        temperature=0.2,
+        tools=[GREP_TOOL],
+        max_tokens=None,
+        tool_choice="required",
+        enable_streaming=True,
+        provider=PROVIDER,
+        api_key="secret",
+    )
+    payload = json.loads(request.body)
+
+    assert request.endpoint == "/messages"
+    assert request.headers["x-api-key"] == "secret"
+    assert payload["system"] == "Be brief."
+    assert payload["stream"] is True
+    assert payload["max_tokens"] > 0
+    assert payload["tool_choice"] == {"type": "any"}
+    assert payload["tools"][0]["input_schema"] == {"type": "object"}
+    assert [m["role"] for m in payload["messages"]] == ["user", "assistant", "user"]
+    assert payload["messages"][1]["content"][1] == {
+        "type": "tool_use",
+        "id": "toolu_1",
+        "name": "grep",
+        "input": {"pattern": "TODO"},
+    }
+    assert payload["messages"][2]["content"][0]["type"] == "tool_result"
+
+
+@pytest.mark.asyncio
+async def test_streaming_accumulates_text_and_tool_use(monkeypatch) -> None:
+    monkeypatch.setenv("ANTHROPIC_API_KEY", "secret")
+    stream = _sse(
+        {"type": "message_start", "message": {"usage": {"input_tokens": 12}}},
+        {"type": "content_block_start", "index": 0, "content_block": {"type": "text"}},
+        {
+            "type": "content_block_delta",
+            "index": 0,
+            "delta": {"type": "text_delta", "text": "Let me look."},
+        },
+        {
+            "type": "content_block_start",
+            "index": 1,
+            "content_block": {"type": "tool_use", "id": "toolu_1", "name": "grep"},
+        },
+        {
+            "type": "content_block_delta",
+            "index": 1,
+            "delta": {"type": "input_json_delta", "partial_json": '{"pattern": '},
+        },
+        {
+            "type": "content_block_delta",
+            "index": 1,
+            "delta": {"type": "input_json_delta", "partial_json": '"TODO"}'},
+        },
+        {"type": "message_delta", "usage": {"output_tokens": 30}},
+        {"type": "message_stop"},
+    )
+
+    with respx.mock(base_url=BASE_URL) as mock_api:
+        route = mock_api.post("/v1/messages").mock(
+            return_value=httpx.Response(
+                status_code=200,
+                stream=httpx.ByteStream(stream=stream),
+                headers={"Content-Type": "text/event-stream"},
+            )
+        )
+        backend = GenericBackend(provider=PROVIDER)
+
+        result: LLMChunk | None = None
+        async for chunk in backend.complete_streaming(
+            model=MODEL,
+            messages=[LLMMessage(role=Role.user, content="Find TODOs")],
+            tools=[GREP_TOOL],
+        ):
+            result = chunk if result is None else result + chunk
+
+    assert route.calls.last.request.headers["anthropic-version"]
+    assert result is not None
+    assert result.message.content == "Let me look."
+    tool_calls = result.message.tool_calls or []
+    assert len(tool_calls) == 1
+    assert tool_calls[0].id == "toolu_1"
+    assert tool_calls[0].function.name == "grep"
+    assert json.loads(tool_calls[0].function.arguments or "") == {"pattern": "TODO"}
+    assert result.usage is not None
+    assert (result.usage.prompt_tokens, result.usage.completion_tokens) == (12, 30)
+
+
+def test_non_streaming_response_is_parsed() -> None:
+    chunk = AnthropicAdapter().parse_response(
+        {
+            "type": "message",
+            "content": [
+                {"type": "thinking", "thinking": "Hmm."},
+                {"type": "text", "text": "Done."},
+            ],
+            "usage": {"input_tokens": 5, "output_tokens": 2},
+        },
+        PROVIDER,
+    )
+
+    assert chunk.message.content == "Done."
+    assert chunk.message.reasoning_content == "Hmm."
+    assert chunk.usage is not None
+    assert chunk.usage.prompt_tokens == 5
+
+
+def test_cache_points_mark_the_system_prompt_and_the_conversation() -> None:
+    instructions, context = "Be brief.", "Project: rune"
+    system = LLMMessage(
+        role=Role.system,
+        content=f"{instructions}\n\n{context}",
+        cache_breaks=[len(instructions), len(instructions) + 2 + len(context)],
+    )
+    messages = [system, LLMMessage(role=Role.user, content="Find TODOs")]
+
+    def prepare(provider: ProviderConfig) -> dict:
+        request = AnthropicAdapter().prepare_request(
+            model_name=MODEL.name,
+            messages=messages,
+            temperature=0.2,
+            tools=None,
+            max_tokens=None,
+            tool_choice=None,
+            enable_streaming=False,
+            provider=provider,
+        )
+        return json.loads(request.body)
+
+    payload = prepare(PROVIDER)
+
+    ephemeral = {"type": "ephemeral"}
+    assert payload["system"] == [
+        {"type": "text", "text": instructions, "cache_control": ephemeral},
+        {"type": "text", "text": f"\n\n{context}", "cache_control": ephemeral},
+    ]
+    assert payload["messages"][-1]["content"][-1]["cache_control"] == ephemeral
+
+    payload = prepare(PROVIDER.model_copy(update={"prompt_caching": False}))
+
+    assert payload["system"] == f"{instructions}\n\n{context}"
+    assert "cache_control" not in payload["messages"][-1]["content"][-1]
+
+
+def test_tool_result_images_are_sent_as_image_blocks() -> None:
+    messages = [
+        LLMMessage(
+            role=Role.tool,
+            tool_call_id="toolu_1",
+            content="Screenshot attached.",
+            images=[ImageContent(data="iVBORw0KGgo=")],
+        )
+    ]
+
+    request = AnthropicAdapter().prepare_request(
+        model_name=MODEL.name,
+        messages=messages,
+        temperature=0.2,
+        tools=None,
+        max_tokens=None,
+        tool_choice=None,
+        enable_streaming=False,
+        provider=PROVIDER,
+    )
+    (tool_result,) = json.loads(request.body)["messages"][0]["content"]
+
+    assert tool_result["content"] == [
+        {"type": "text", "text": "Screenshot attached."},
+        {
+            "type": "image",
+            "source": {
+                "type": "base64",
+                "media_type": "image/png",
+                "data": "iVBORw0KGgo=",
+            },
+        },
+    ]
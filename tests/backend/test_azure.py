@@ -0,0 +1,91 @@
+from __future__ import annotations
+
+import json
+
+import httpx
+import pytest
+import respx
+
+from rune.core.config import ModelConfig, ProviderConfig
+from rune.core.llm.backend.generic import (
+    AZURE_DEFAULT_API_VERSION,
+    GenericBackend,
+    OpenAIAdapter,
+)
+from rune.core.types import LLMMessage, Role
+
+BASE_URL = "https://my-resource.openai.azure.com"
+PROVIDER = ProviderConfig(
+    name="azure",
+    api_base=BASE_URL,
+    api_key_env_var="AZURE_OPENAI_API_KEY",
+    azure=True,
+)
+MODEL = ModelConfig(name="gpt-4o-prod", provider="azure", alias="gpt-4o")
+
+
+def _prepare(provider: ProviderConfig, model_name: str = MODEL.name):
+    return OpenAIAdapter().prepare_request(
+        model_name=model_name,
+        messages=[LLMMessage(role=Role.user, content="hi")],
+        temperature=0.2,
+        tools=None,
+        max_tokens=None,
+        tool_choice=None,
+        enable_streaming=False,
+        provider=provider,
+        api_key="secret",
+    )
+
+
+def test_azure_uses_deployment_urls_and_api_key_header() -> None:
+    request = _prepare(PROVIDER)
+
+    assert request.endpoint == (
+        "/openai/deployments/gpt-4o-prod/chat/completions"
+        f"?api-version={AZURE_DEFAULT_API_VERSION}"
+    )
+    assert request.headers["api-key"] == "secret"
+    assert "Authorization" not in request.headers
+
+
+def test_api_version_is_added_for_any_provider_that_sets_it() -> None:
+    provider = ProviderConfig(
+        name="proxy", api_base="http://localhost:4000", api_version="2025-01-01"
+    )
+
+    request = _prepare(provider, "my/model")
+
+    assert request.endpoint == "/chat/completions?api-version=2025-01-01"
+    assert request.headers["Authorization"] == "Bearer secret"
+
+
+@pytest.mark.asyncio
+async def test_complete_calls_the_deployment(monkeypatch) -> None:
+    monkeypatch.setenv("AZURE_OPENAI_API_KEY", "secret")
+    provider = PROVIDER.model_copy(update={"api_version": "2024-06-01"})
+
+    with respx.mock(base_url=BASE_URL) as mock_api:
+        route = mock_api.post(
+            "/openai/deployments/gpt-4o-prod/chat/completions",
+            params={"api-version": "2024-06-01"},
+        ).mock(
+            return_value=httpx.Response(
+                status_code=200,
+                json={
+                    "choices": [
+                        {"message": {"role": "assistant", "content": "Hello"}}
+                    ],
+                    "usage": {"prompt_tokens": 5, "completion_tokens": 1},
+                },
+            )
+        )
+        backend = GenericBackend(provider=provider)
+        result = await backend.complete(
+            model=MODEL, messages=[LLMMessage(role=Role.user, content="hi")]
+        )
+
+    assert result.message.content == "Hello"
+    request = route.calls.last.request
+    assert request.headers["api-key"] == "secret"
+    assert json.loads(request.content)["model"] == "gpt-4o-prod"
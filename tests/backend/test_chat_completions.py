@@ -0,0 +1,138 @@
+from __future__ import annotations
+
+import json
+
+import httpx
+import pytest
+import respx
+
+from rune.core.config import ModelConfig, ProviderConfig
+from rune.core.llm.backend.generic import (
+    BACKEND_ADAPTERS,
+    GenericBackend,
+    OpenAIAdapter,
+)
+from rune.core.types import ImageContent, LLMChunk, LLMMessage, Role
+
+BASE_URL = "http://localhost:8000"
+
+
+def _provider() -> ProviderConfig:
+    return ProviderConfig.model_validate({
+        "name": "vllm",
+        "api_base": f"{BASE_URL}/v1",
+        "wire_api": "chat",
+    })
+
+
+async def _stream(lines: list[bytes]) -> LLMChunk:
+    with respx.mock(base_url=BASE_URL) as mock_api:
+        mock_api.post("/v1/chat/completions").mock(
+            return_value=httpx.Response(
+                status_code=200,
+                stream=httpx.ByteStream(stream=b"\n\n".join(lines)),
+                headers={"Content-Type": "text/event-stream"},
+            )
+        )
+        backend = GenericBackend(provider=_provider())
+        model = ModelConfig(name="qwen", provider="vllm", alias="qwen")
+
+        result: LLMChunk | None = None
+        async for chunk in backend.complete_streaming(
+            model=model, messages=[LLMMessage(role=Role.user, content="hi")]
+        ):
+            result = chunk if result is None else result + chunk
+
+    assert result is not None
+    return result
+
+
+def test_wire_api_selects_the_chat_completions_adapter() -> None:
+    provider = _provider()
+
+    assert provider.api_style == "chat"
+    assert BACKEND_ADAPTERS["chat"].endpoint == "/chat/completions"
+
+
+@pytest.mark.asyncio
+async def test_streams_reasoning_content_and_usage() -> None:
+    result = await _stream([
+        b'data:{"choices":[{"index":0,"delta":{"role":"assistant","reasoning":"Hmm"}}]}',
+        b'data: {"choices":[{"index":0,"delta":{"role":null,"content":"Hi"}}]}',
+        b": keep-alive",
+        b'data: {"choices":[],"usage":{"prompt_tokens":7,"completion_tokens":2}}',
+        b"data: [DONE]",
+    ])
+
+    assert result.message.reasoning_content == "Hmm"
+    assert result.message.content == "Hi"
+    assert result.usage is not None
+    assert (result.usage.prompt_tokens, result.usage.completion_tokens) == (7, 2)
+
+
+@pytest.mark.asyncio
+async def test_streams_tool_calls_without_indices() -> None:
+    result = await _stream([
+        b'data: {"choices":[{"index":0,"delta":{"role":"assistant","tool_calls":['
+        b'{"id":"a","type":"function","function":{"name":"grep",'
+        b'"arguments":{"pattern":"x"}}},'
+        b'{"id":"b","type":"function","function":{"name":"todo",'
+        b'"arguments":"{}"}}]}}]}',
+        b"data: [DONE]",
+    ])
+
+    tool_calls = result.message.tool_calls or []
+    assert [(tc.index, tc.function.name) for tc in tool_calls] == [
+        (0, "grep"),
+        (1, "todo"),
+    ]
+    assert tool_calls[0].function.arguments == '{"pattern": "x"}'
+
+
+def test_tool_images_follow_the_tool_results_in_a_user_message() -> None:
+    screenshot = ImageContent(data="iVBORw0KGgo=")
+    messages = [
+        LLMMessage(role=Role.user, content="Look", images=[screenshot]),
+        LLMMessage(role=Role.tool, tool_call_id="call_1", content="a"),
+        LLMMessage(
+            role=Role.tool, tool_call_id="call_2", content="b", images=[screenshot]
+        ),
+        LLMMessage(role=Role.assistant, content="Done"),
+    ]
+
+    request = OpenAIAdapter().prepare_request(
+        model_name="qwen",
+        messages=messages,
+        temperature=0.2,
+        tools=None,
+        max_tokens=None,
+        tool_choice=None,
+        enable_streaming=False,
+        provider=_provider(),
+    )
+    sent = json.loads(request.body)["messages"]
+
+    image_part = {
+        "type": "image_url",
+        "image_url": {"url": "data:image/png;base64,iVBORw0KGgo="},
+    }
+    assert [m["role"] for m in sent] == ["user", "tool", "tool", "user", "assistant"]
+    assert sent[0]["content"] == [{"type": "text", "text": "Look"}, image_part]
+    assert sent[2]["content"] == "b"
+    assert sent[3]["content"][1:] == [image_part]
+
+
+def test_reasoning_effort_is_sent_when_set() -> None:
+    request = OpenAIAdapter().prepare_request(
+        model_name="o4-mini",
+        messages=[LLMMessage(role=Role.user, content="hi")],
+        temperature=0.2,
+        tools=None,
+        max_tokens=None,
+        tool_choice=None,
+        enable_streaming=False,
+        provider=_provider(),
+        reasoning_effort="high",
+    )
+
+    assert json.loads(request.body)["reasoning_effort"] == "high"
@@ -0,0 +1,131 @@
+from __future__ import annotations
+
+import json
+from types import SimpleNamespace
+from typing import Any
+
+import httpx
+import pytest
+import respx
+
+from tests.conftest import build_test_rune_config
+from rune.core.config import EmbeddingsConfig, ModelConfig, ProviderConfig
+from rune.core.llm.backend.generic import GenericBackend
+from rune.core.llm.backend.ollama import OllamaBackend
+from rune.core.llm.embeddings import EmbeddingError, embed
+
+BASE_URL = "https://api.example.com/v1"
+PROVIDER = ProviderConfig(
+    name="openai", api_base=BASE_URL, api_key_env_var="OPENAI_API_KEY"
+)
+MODEL = ModelConfig(
+    name="text-embedding-3-small", provider="openai", alias="embed-small"
+)
+
+
+class RecordingBackend:
+    def __init__(self) -> None:
+        self.batches: list[list[str]] = []
+
+    async def embed(self, *, model: ModelConfig, texts: list[str]) -> list[list[float]]:
+        self.batches.append(texts)
+        return [[float(len(text))] for text in texts]
+
+
+@pytest.mark.asyncio
+async def test_generic_backend_posts_to_the_embeddings_endpoint(monkeypatch) -> None:
+    monkeypatch.setenv("OPENAI_API_KEY", "secret")
+
+    with respx.mock(base_url=BASE_URL) as mock_api:
+        route = mock_api.post("/embeddings").mock(
+            return_value=httpx.Response(
+                status_code=200,
+                json={
+                    "data": [
+                        {"index": 1, "embedding": [0.3, 0.4]},
+                        {"index": 0, "embedding": [0.1, 0.2]},
+                    ]
+                },
+            )
+        )
+        backend = GenericBackend(provider=PROVIDER)
+        vectors = await backend.embed(model=MODEL, texts=["a", "b"])
+
+    assert vectors == [[0.1, 0.2], [0.3, 0.4]]
+    request = route.calls.last.request
+    assert request.headers["Authorization"] == "Bearer secret"
+    assert json.loads(request.content) == {
+        "model": "text-embedding-3-small",
+        "input": ["a", "b"],
+    }
+
+
+@pytest.mark.asyncio
+async def test_generic_backend_rejects_apis_without_embeddings() -> None:
+    provider = ProviderConfig(
+        name="anthropic", api_base="https://api.anthropic.com", api_style="anthropic"
+    )
+
+    with pytest.raises(ValueError, match="does not serve embeddings"):
+        await GenericBackend(provider=provider).embed(model=MODEL, texts=["a"])
+
+
+@pytest.mark.asyncio
+async def test_ollama_backend_uses_the_embed_endpoint(monkeypatch) -> None:
+    calls: list[dict[str, Any]] = []
+
+    class FakeClient:
+        async def embed(self, **kwargs: Any) -> Any:
+            calls.append(kwargs)
+            return SimpleNamespace(embeddings=[[1.0, 0.0], [0.0, 1.0]])
+
+    backend = OllamaBackend(
+        provider=ProviderConfig(name="ollama", api_base="http://localhost:11434")
+    )
+    monkeypatch.setattr(backend, "_get_client", FakeClient)
+    model = ModelConfig(name="nomic-embed-text", provider="ollama", alias="nomic")
+
+    vectors = await backend.embed(model=model, texts=["x", "y"])
+
+    assert vectors == [[1.0, 0.0], [0.0, 1.0]]
+    assert calls == [{"model": "nomic-embed-text", "input": ["x", "y"]}]
+
+
+@pytest.mark.asyncio
+async def test_embed_batches_requests_and_keeps_order() -> None:
+    config = build_test_rune_config(
+        providers=[PROVIDER],
+        models=[MODEL],
+        active_model="embed-small",
+        embeddings=EmbeddingsConfig(model="embed-small", batch_size=2),
+    )
+    backend = RecordingBackend()
+
+    vectors = await embed(config, ["a", "bb", "ccc"], backend=backend)
+
+    assert vectors == [[1.0], [2.0], [3.0]]
+    assert backend.batches == [["a", "bb"], ["ccc"]]
+
+
+@pytest.mark.asyncio
+async def test_embed_accepts_provider_and_model_name() -> None:
+    config = build_test_rune_config(
+        providers=[PROVIDER],
+        models=[MODEL],
+        active_model="embed-small",
+        embeddings=EmbeddingsConfig(model="openai:text-embedding-3-large"),
+    )
+    backend = RecordingBackend()
+
+    await embed(config, ["a"], backend=backend)
+
+    assert backend.batches == [["a"]]
+    assert config.embeddings.get_model(config).name == "text-embedding-3-large"
+
+
+@pytest.mark.asyncio
+async def test_embed_requires_a_configured_model() -> None:
+    config = build_test_rune_config()
+
+    with pytest.raises(EmbeddingError, match="No embedding model configured"):
+        await embed(config, ["a"], backend=RecordingBackend())
@@ -0,0 +1,152 @@
+from __future__ import annotations
+
+import json
+
+import httpx
+import pytest
+import respx
+
+from rune.core.config import ModelConfig, ProviderConfig
+from rune.core.llm.backend.gemini import GeminiAdapter
+from rune.core.llm.backend.generic import GenericBackend
+from rune.core.types import (
+    AvailableFunction,
+    AvailableTool,
+    FunctionCall,
+    LLMChunk,
+    LLMMessage,
+    Role,
+    ToolCall,
+)
+
+BASE_URL = "https://generativelanguage.googleapis.com"
+PROVIDER = ProviderConfig(
+    name="gemini",
+    api_base=f"{BASE_URL}/v1beta",
+    api_key_env_var="GEMINI_API_KEY",
+    api_style="gemini",
+)
+MODEL = ModelConfig(name="gemini-2.5-pro", provider="gemini", alias="gemini")
+GREP_TOOL = AvailableTool(
+    function=AvailableFunction(
+        name="grep", description="Search", parameters={"type": "object"}
+    )
+)
+
+
+def test_request_maps_roles_function_calls_and_responses() -> None:
+    messages = [
+        LLMMessage(role=Role.system, content="Be brief."),
+        LLMMessage(role=Role.user, content="Find TODOs"),
+        LLMMessage(
+            role=Role.assistant,
+            content="",
+            tool_calls=[
+                ToolCall(
+                    id="call_1",
+                    index=0,
+                    function=FunctionCall(name="grep", arguments='{"pattern": "TODO"}'),
+                )
+            ],
+        ),
+        LLMMessage(role=Role.tool, tool_call_id="call_1", content="a.py:1: TODO"),
+    ]
+
+    request = GeminiAdapter().prepare_request(
+        model_name=MODEL.name,
+        messages=messages,
+        temperature=0.2,
+        tools=[GREP_TOOL],
+        max_tokens=256,
+        tool_choice=GREP_TOOL,
+        enable_streaming=True,
+        provider=PROVIDER,
+        api_key="secret",
+    )
+    payload = json.loads(request.body)
+
+    assert request.endpoint == "/models/gemini-2.5-pro:streamGenerateContent?alt=sse"
+    assert request.headers["x-goog-api-key"] == "secret"
+    assert payload["systemInstruction"] == {"parts": [{"text": "Be brief."}]}
+    assert payload["generationConfig"] == {"temperature": 0.2, "maxOutputTokens": 256}
+    assert payload["tools"][0]["functionDeclarations"][0]["parametersJsonSchema"] == {
+        "type": "object"
+    }
+    assert payload["toolConfig"]["functionCallingConfig"] == {
+        "mode": "ANY",
+        "allowedFunctionNames": ["grep"],
+    }
+    assert [c["role"] for c in payload["contents"]] == ["user", "model", "user"]
+    assert payload["contents"][1]["parts"] == [
+        {"functionCall": {"name": "grep", "args": {"pattern": "TODO"}}}
+    ]
+    assert payload["contents"][2]["parts"][0]["functionResponse"]["name"] == "grep"
+
+
+@pytest.mark.asyncio
+async def test_streaming_keeps_calls_from_separate_chunks_apart(monkeypatch) -> None:
+    monkeypatch.setenv("GEMINI_API_KEY", "secret")
+    chunks = [
+        {"candidates": [{"content": {"role": "model", "parts": [{"text": "On it."}]}}]},
+        {
+            "candidates": [
+                {
+                    "content": {
+                        "role": "model",
+                        "parts": [
+                            {"functionCall": {"name": "grep", "args": {"pattern": "a"}}}
+                        ],
+                    }
+                }
+            ],
+            "usageMetadata": {"promptTokenCount": 9, "candidatesTokenCount": 3},
+        },
+        {
+            "candidates": [
+                {
+                    "content": {
+                        "role": "model",
+                        "parts": [
+                            {"functionCall": {"name": "grep", "args": {"pattern": "b"}}}
+                        ],
+                    },
+                    "finishReason": "STOP",
+                }
+            ],
+            "usageMetadata": {"promptTokenCount": 9, "candidatesTokenCount": 6},
+        },
+    ]
+    stream = b"\n\n".join(f"data: {json.dumps(c)}".encode() for c in chunks)
+
+    with respx.mock(base_url=BASE_URL) as mock_api:
+        mock_api.post(
+            "/v1beta/models/gemini-2.5-pro:streamGenerateContent",
+            params={"alt": "sse"},
+        ).mock(
+            return_value=httpx.Response(
+                status_code=200,
+                stream=httpx.ByteStream(stream=stream),
+                headers={"Content-Type": "text/event-stream"},
+            )
+        )
+        backend = GenericBackend(provider=PROVIDER)
+
+        result: LLMChunk | None = None
+        async for chunk in backend.complete_streaming(
+            model=MODEL,
+            messages=[LLMMessage(role=Role.user, content="grep a and b")],
+            tools=[GREP_TOOL],
+        ):
+            result = chunk if result is None else result + chunk
+
+    assert result is not None
+    assert result.message.content == "On it."
+    tool_calls = result.message.tool_calls or []
+    assert [json.loads(tc.function.arguments or "") for tc in tool_calls] == [
+        {"pattern": "a"},
+        {"pattern": "b"},
+    ]
+    assert [tc.index for tc in tool_calls] == [0, 1]
+    assert all(tc.id for tc in tool_calls)
+    assert result.usage is not None
+    assert (result.usage.prompt_tokens, result.usage.completion_tokens) == (9, 6)
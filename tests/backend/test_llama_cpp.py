@@ -0,0 +1,152 @@
+from __future__ import annotations
+
+from pathlib import Path
+import sys
+import types
+from typing import Any
+
+import pytest
+
+from rune.core.config import LocalInferenceConfig, ModelConfig, ProviderConfig
+from rune.core.llm.backend import llama_cpp
+from rune.core.llm.backend.llama_cpp import LlamaCppBackend, LocalModelError
+from rune.core.types import LLMChunk, LLMMessage, Role
+
+
+class FakeLlama:
+    instances: list[FakeLlama] = []
+
+    def __init__(self, **kwargs: Any) -> None:
+        self.kwargs = kwargs
+        self.requests: list[dict[str, Any]] = []
+        self.n_tokens = 0
+        FakeLlama.instances.append(self)
+
+    def tokenize(self, text: bytes, add_bos: bool = True) -> list[int]:
+        return list(range(len(text.split())))
+
+    def create_chat_completion(self, **request: Any) -> Any:
+        self.requests.append(request)
+        if request.get("stream"):
+            self.n_tokens = 12
+            return iter([
+                {"choices": [{"index": 0, "delta": {"role": "assistant"}}]},
+                {"choices": [{"index": 0, "delta": {"content": "Hello "}}]},
+                {"choices": [{"index": 0, "delta": {"content": "there you"}}]},
+            ])
+        return {
+            "choices": [{"message": {"role": "assistant", "content": "Hi"}}],
+            "usage": {"prompt_tokens": 7, "completion_tokens": 1},
+        }
+
+
+@pytest.fixture
+def model_dir(tmp_path: Path, monkeypatch: pytest.MonkeyPatch) -> Path:
+    (tmp_path / "tiny.gguf").write_bytes(b"GGUF")
+    FakeLlama.instances.clear()
+    monkeypatch.setitem(
+        sys.modules, "llama_cpp", types.SimpleNamespace(Llama=FakeLlama)
+    )
+    monkeypatch.setattr(llama_cpp, "_MODELS", {})
+    return tmp_path
+
+
+def make_backend(model_dir: Path) -> LlamaCppBackend:
+    return LlamaCppBackend(
+        provider=ProviderConfig(
+            name="local",
+            api_base=str(model_dir),
+            backend="llama_cpp",
+            local_inference=LocalInferenceConfig(n_ctx=4096, n_gpu_layers=0),
+        )
+    )
+
+
+MODEL = ModelConfig(name="tiny.gguf", provider="local", alias="tiny")
+MESSAGES = [LLMMessage(role=Role.user, content="hi")]
+
+
+@pytest.mark.asyncio
+async def test_complete_loads_the_model_once(model_dir: Path) -> None:
+    backend = make_backend(model_dir)
+
+    for _ in range(2):
+        result = await backend.complete(
+            model=MODEL,
+            messages=MESSAGES,
+            temperature=0.1,
+            tools=None,
+            max_tokens=64,
+            tool_choice=None,
+            extra_headers=None,
+        )
+
+    assert result.message.content == "Hi"
+    assert result.usage is not None and result.usage.prompt_tokens == 7  # noqa: PLR2004
+    [llm] = FakeLlama.instances
+    assert llm.kwargs["model_path"] == str((model_dir / "tiny.gguf").resolve())
+    assert llm.kwargs["n_ctx"] == 4096  # noqa: PLR2004
+    assert llm.requests[0]["messages"] == [{"role": "user", "content": "hi"}]
+    assert llm.requests[0]["max_tokens"] == 64  # noqa: PLR2004
+    assert "model" not in llm.requests[0]
+
+
+@pytest.mark.asyncio
+async def test_streaming_estimates_usage(model_dir: Path) -> None:
+    backend = make_backend(model_dir)
+
+    result: LLMChunk | None = None
+    async for chunk in backend.complete_streaming(
+        model=MODEL,
+        messages=MESSAGES,
+        temperature=0.1,
+        tools=None,
+        max_tokens=None,
+        tool_choice=None,
+        extra_headers=None,
+    ):
+        result = chunk if result is None else result + chunk
+
+    assert result is not None
+    assert result.message.content == "Hello there you"
+    assert result.usage is not None
+    assert (result.usage.prompt_tokens, result.usage.completion_tokens) == (9, 3)
+
+
+@pytest.mark.asyncio
+async def test_missing_model_file_is_reported(model_dir: Path) -> None:
+    backend = make_backend(model_dir)
+
+    with pytest.raises(LocalModelError, match="GGUF model not found"):
+        await backend.complete(
+            model=MODEL.model_copy(update={"name": "missing.gguf"}),
+            messages=MESSAGES,
+            temperature=0.1,
+            tools=None,
+            max_tokens=None,
+            tool_choice=None,
+            extra_headers=None,
+        )
+
+
+@pytest.mark.asyncio
+async def test_output_schema_constrains_decoding(model_dir: Path) -> None:
+    backend = make_backend(model_dir)
+    schema = {"type": "object", "properties": {"ok": {"type": "boolean"}}}
+
+    await backend.complete(
+        model=MODEL,
+        messages=MESSAGES,
+        temperature=0.1,
+        tools=None,
+        max_tokens=None,
+        tool_choice=None,
+        extra_headers=None,
+        output_schema=schema,
+    )
+
+    [llm] = FakeLlama.instances
+    assert llm.requests[0]["response_format"] == {
+        "type": "json_object",
+        "schema": schema,
+    }
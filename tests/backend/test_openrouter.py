@@ -0,0 +1,68 @@
+from __future__ import annotations
+
+import json
+
+import pytest
+
+from rune.core.config import ModelConfig, OpenRouterConfig, ProviderConfig
+from rune.core.llm.backend.openrouter import OpenRouterAdapter, openrouter_model_name
+from rune.core.types import LLMMessage, Role
+
+PROVIDER = ProviderConfig(
+    name="openrouter",
+    api_base="https://openrouter.ai/api/v1",
+    api_key_env_var="OPENROUTER_API_KEY",
+    api_style="openrouter",
+    openrouter=OpenRouterConfig(
+        order=["anthropic", "amazon-bedrock"],
+        allow_fallbacks=False,
+        data_collection="deny",
+    ),
+)
+MODEL = ModelConfig(
+    name="openrouter/anthropic/claude-sonnet-4.5", provider="openrouter", alias="or"
+)
+
+
+def test_request_carries_attribution_and_routing_preferences() -> None:
+    request = OpenRouterAdapter().prepare_request(
+        model_name=MODEL.name,
+        messages=[LLMMessage(role=Role.user, content="Hi")],
+        temperature=0.2,
+        tools=None,
+        max_tokens=None,
+        tool_choice=None,
+        enable_streaming=True,
+        provider=PROVIDER,
+        api_key="secret",
+    )
+    payload = json.loads(request.body)
+
+    assert request.endpoint == "/chat/completions"
+    assert request.headers["Authorization"] == "Bearer secret"
+    assert request.headers["HTTP-Referer"] == "https://github.com/sagea-ai/rune"
+    assert request.headers["X-Title"] == "Rune"
+    assert payload["model"] == "anthropic/claude-sonnet-4.5"
+    assert payload["provider"] == {
+        "order": ["anthropic", "amazon-bedrock"],
+        "allow_fallbacks": False,
+        "data_collection": "deny",
+    }
+
+
+def test_default_routing_sends_no_preferences() -> None:
+    provider = PROVIDER.model_copy(update={"openrouter": OpenRouterConfig()})
+
+    assert OpenRouterAdapter().provider_payload(provider) == {}
+
+
+@pytest.mark.parametrize(
+    ("name", "expected"),
+    [
+        ("openrouter/auto", "openrouter/auto"),
+        ("openrouter/openai/gpt-4o", "openai/gpt-4o"),
+        ("qwen/qwen3-coder:free", "qwen/qwen3-coder:free"),
+    ],
+)
+def test_model_names(name: str, expected: str) -> None:
+    assert openrouter_model_name(name) == expected
@@ -0,0 +1,131 @@
+from __future__ import annotations
+
+from pathlib import Path
+
+import httpx
+from pydantic import ValidationError
+import pytest
+
+from rune.core.config import ModerationConfig, ProviderConfig
+from rune.core.llm.backend import transport
+from rune.core.llm.backend.generic import GenericBackend
+from rune.core.llm.backend.transport import (
+    TransportConfigError,
+    client_options,
+    provider_tls_options,
+    tls_options,
+)
+
+
+def _provider(**kwargs) -> ProviderConfig:
+    return ProviderConfig(name="corp", api_base="https://llm.corp.example/v1", **kwargs)
+
+
+def test_defaults_use_the_global_timeout() -> None:
+    options = client_options(_provider(), 720.0)
+
+    assert options["timeout"] == httpx.Timeout(720.0)
+    assert options["http2"] is False
+    assert "proxy" not in options
+    assert "verify" not in options
+
+
+def test_provider_settings_are_plumbed_into_the_client_options() -> None:
+    options = client_options(
+        _provider(
+            connect_timeout_ms=5000,
+            read_timeout_ms=300_000,
+            keepalive_timeout_ms=30_000,
+            http_proxy="http://proxy.corp.example:3128",
+        ),
+        720.0,
+    )
+
+    assert options["timeout"] == httpx.Timeout(720.0, connect=5.0, read=300.0)
+    assert options["limits"].keepalive_expiry == 30.0
+    assert options["proxy"] == "http://proxy.corp.example:3128"
+
+
+def test_zero_keepalive_disables_connection_reuse() -> None:
+    options = client_options(_provider(keepalive_timeout_ms=0), 720.0)
+
+    assert options["limits"].max_keepalive_connections == 0
+
+
+def test_http2_requires_h2(monkeypatch: pytest.MonkeyPatch) -> None:
+    monkeypatch.setattr(transport, "find_spec", lambda name: None)
+
+    with pytest.raises(TransportConfigError, match="h2"):
+        client_options(_provider(http2=True), 720.0)
+
+
+class _RecordingContext:
+    def __init__(self, cafile: Path | None) -> None:
+        self.cafile = cafile
+        self.cert_chain: tuple[Path, Path | None] | None = None
+
+    def load_cert_chain(self, certfile: Path, keyfile: Path | None) -> None:
+        self.cert_chain = (certfile, keyfile)
+
+
+def test_mtls_settings_build_an_ssl_context(
+    monkeypatch: pytest.MonkeyPatch, tmp_path: Path
+) -> None:
+    monkeypatch.setattr(transport.ssl, "create_default_context", _RecordingContext)
+    provider = _provider(
+        client_cert=tmp_path / "client.pem",
+        client_key=tmp_path / "client.key",
+        ca_bundle=tmp_path / "corp-ca.pem",
+    )
+
+    context = client_options(provider, 720.0)["verify"]
+
+    assert context.cafile == tmp_path / "corp-ca.pem"
+    assert context.cert_chain == (tmp_path / "client.pem", tmp_path / "client.key")
+
+
+def test_missing_client_cert_is_a_config_error(tmp_path: Path) -> None:
+    provider = _provider(client_cert=tmp_path / "missing.pem")
+
+    with pytest.raises(TransportConfigError, match="TLS"):
+        client_options(provider, 720.0)
+
+
+def test_oauth_logins_use_the_provider_certificate(
+    monkeypatch: pytest.MonkeyPatch, tmp_path: Path
+) -> None:
+    monkeypatch.setattr(transport.ssl, "create_default_context", _RecordingContext)
+    provider = _provider(client_cert=tmp_path / "client.pem")
+
+    options = provider_tls_options(provider)
+
+    assert list(options) == ["verify"]
+    assert options["verify"].cert_chain == (tmp_path / "client.pem", None)
+
+
+def test_moderation_endpoints_take_a_client_certificate_too(tmp_path: Path) -> None:
+    config = ModerationConfig(url="https://mod.example", client_cert=tmp_path / "x")
+
+    assert tls_options(ModerationConfig(), "The moderation hook") == {}
+    with pytest.raises(TransportConfigError, match="The moderation hook"):
+        tls_options(config, "The moderation hook")
+
+
+def test_client_key_needs_a_client_cert(tmp_path: Path) -> None:
+    with pytest.raises(ValidationError, match="client_cert"):
+        _provider(client_key=tmp_path / "client.key")
+
+
+def test_tls_paths_expand_the_home_directory() -> None:
+    provider = _provider(ca_bundle=Path("~/corp-ca.pem"))
+
+    assert provider.ca_bundle == Path.home() / "corp-ca.pem"
+
+
+@pytest.mark.asyncio
+async def test_generic_backend_client_uses_provider_timeouts() -> None:
+    backend = GenericBackend(provider=_provider(read_timeout_ms=1500), timeout=60.0)
+
+    async with backend:
+        client = backend._get_client()
+        assert client.timeout == httpx.Timeout(60.0, read=1.5)
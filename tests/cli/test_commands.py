@@ -0,0 +1,34 @@
+from __future__ import annotations
+
+from rune.cli.commands import CommandRegistry
+
+
+class TestFindCommand:
+    def test_matches_command_without_arguments(self) -> None:
+        registry = CommandRegistry()
+
+        command = registry.find_command("/STATUS ")
+
+        assert command is not None
+        assert command.handler == "_show_status"
+
+    def test_rejects_arguments_for_commands_that_take_none(self) -> None:
+        registry = CommandRegistry()
+
+        assert registry.find_command("/clear everything") is None
+
+    def test_passes_arguments_to_commands_that_take_them(self) -> None:
+        registry = CommandRegistry()
+
+        command = registry.find_command("/lang Brazilian Portuguese")
+
+        assert command is not None
+        assert command.handler == "_set_response_language"
+        assert registry.get_args("/lang Brazilian Portuguese") == (
+            "Brazilian Portuguese"
+        )
+
+    def test_excluded_commands_are_not_found(self) -> None:
+        registry = CommandRegistry(excluded_commands=["lang"])
+
+        assert registry.find_command("/lang French") is None
@@ -0,0 +1,43 @@
+from __future__ import annotations
+
+from pathlib import Path
+
+from rune.cli.textual_ui.widgets.editor_link import (
+    GREP_MATCH_RE,
+    build_editor_uri,
+    find_snippet_line,
+)
+
+
+def test_build_editor_uri_fills_template(tmp_path: Path) -> None:
+    file = tmp_path.resolve() / "src" / "main file.py"
+
+    uri = build_editor_uri("vscode://file/{path}:{line}:{column}", str(file), 12)
+
+    assert uri == f"vscode://file/{file.as_posix().replace(' ', '%20')}:12:1"
+
+
+def test_build_editor_uri_resolves_relative_paths(tmp_working_directory: Path) -> None:
+    expected = (tmp_working_directory.resolve() / "app.py").as_posix()
+
+    uri = build_editor_uri("idea://open?file={path}&line={line}", "app.py", 3)
+
+    assert uri == f"idea://open?file={expected}&line=3"
+
+
+def test_find_snippet_line(tmp_path: Path) -> None:
+    file = tmp_path / "module.py"
+    file.write_text("import os\n\n\ndef main():\n    return 1\n")
+
+    assert find_snippet_line(str(file), "def main():\n    return 1\n") == 4
+    assert find_snippet_line(str(file), "not there") is None
+    assert find_snippet_line(str(tmp_path / "missing.py"), "x") is None
+
+
+def test_grep_match_regex_matches_match_lines_only() -> None:
+    match = GREP_MATCH_RE.match("42:    run()")
+    context = GREP_MATCH_RE.match("43-    stop()")
+
+    assert match is not None
+    assert match["line"] == "42"
+    assert context is None
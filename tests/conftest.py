@@ -17,7 +17,7 @@ from rune.cli.plan_offer.ports.whoami_gateway import WhoAmIResponse
 from rune.cli.textual_ui.app import CORE_VERSION, RuneApp
 from rune.core.agent_loop import AgentLoop
 from rune.core.agents.models import BuiltinAgentName
-from rune.core.config import RuneConfig, SessionLoggingConfig
+from rune.core.config import AutoTitleConfig, RuneConfig, SessionLoggingConfig
 from rune.core.llm.types import BackendLike
 from rune.core.paths import global_paths
 from rune.core.paths.config_paths import unlock_config_paths
@@ -120,9 +120,26 @@ def build_test_rune_config(**kwargs) -> RuneConfig:
     resolved_enable_update_checks = (
         False if enable_update_checks is None else enable_update_checks
     )
+    # Titling runs a background request that would consume the fake backend's
+    # responses, so tests opt into it.
+    auto_title = kwargs.pop("auto_title", None)
+    resolved_auto_title = (
+        AutoTitleConfig(enabled=False) if auto_title is None else auto_title
+    )
+    # Agent loops of one test process would otherwise see each other as
+    # concurrent threads.
+    detect_concurrent_sessions = kwargs.pop("detect_concurrent_sessions", False)
+    # Other tests rewrite the config file, which would reload the model list
+    # and the config.
+    watch_models = kwargs.pop("watch_models", False)
+    watch_config = kwargs.pop("watch_config", False)
     return RuneConfig(
         session_logging=resolved_session_logging,
         enable_update_checks=resolved_enable_update_checks,
+        auto_title=resolved_auto_title,
+        detect_concurrent_sessions=detect_concurrent_sessions,
+        watch_models=watch_models,
+        watch_config=watch_config,
         **kwargs,
     )
 
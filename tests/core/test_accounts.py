@@ -0,0 +1,168 @@
+from __future__ import annotations
+
+import io
+from pathlib import Path
+import shlex
+import stat
+import sys
+
+import keyring
+import pytest
+
+from rune.cli.accounts import run_accounts_command, run_login_command
+from rune.core.auth.accounts import AccountError, AccountStore, CredentialsStoreMode
+from rune.core.config import RuneConfig
+
+
+@pytest.fixture
+def store(config_dir: Path) -> AccountStore:
+    return AccountStore(config_dir / "auth.json")
+
+
+def test_logging_in_activates_the_new_account(store: AccountStore) -> None:
+    store.add("personal", "openai", "sk-personal")
+    store.add("work", "openai", "sk-work")
+    store.add("anthropic", "anthropic", "sk-ant")
+
+    assert store.active_labels() == {"openai": "work", "anthropic": "anthropic"}
+    assert store.active_api_key("openai") == "sk-work"
+    assert stat.S_IMODE(store.path.stat().st_mode) == 0o600
+
+
+def test_switching_and_removing_accounts(store: AccountStore) -> None:
+    store.add("personal", "openai", "sk-personal")
+    store.add("work", "openai", "sk-work")
+
+    store.switch("personal")
+    assert store.active_api_key("openai") == "sk-personal"
+
+    store.remove("personal")
+    assert store.active_api_key("openai") == "sk-work"
+    store.remove("work")
+    assert store.active_api_key("openai") is None
+
+    with pytest.raises(AccountError):
+        store.switch("work")
+
+
+def test_labels_belong_to_one_provider(store: AccountStore) -> None:
+    store.add("work", "openai", "sk-work")
+
+    with pytest.raises(AccountError):
+        store.add("work", "anthropic", "sk-ant")
+    with pytest.raises(AccountError):
+        store.add("my work", "openai", "sk-work")
+
+
+def test_the_active_account_stands_in_for_the_environment(
+    monkeypatch: pytest.MonkeyPatch,
+) -> None:
+    monkeypatch.delenv("RUNE_API_KEY")
+    monkeypatch.setattr("sys.stdin", io.StringIO("sk-logged-in\n"))
+
+    run_login_command(["login", "--label", "work"])
+
+    config = RuneConfig.load()
+    provider = config.get_provider_for_model(config.get_active_model())
+    assert provider.get_api_key() == "sk-logged-in"
+
+
+def test_accounts_command_lists_and_switches(
+    store: AccountStore, capsys: pytest.CaptureFixture[str]
+) -> None:
+    store.add("personal", "rune", "sk-personal")
+    store.add("work", "rune", "sk-work")
+
+    run_accounts_command(["accounts", "switch", "personal"])
+    run_accounts_command(["accounts"])
+
+    assert capsys.readouterr().out.splitlines()[1:] == [
+        "* personal (rune)",
+        "  work (rune)",
+    ]
+
+
+@pytest.fixture
+def keychain(monkeypatch: pytest.MonkeyPatch) -> dict[tuple[str, str], str]:
+    entries: dict[tuple[str, str], str] = {}
+    monkeypatch.setattr(
+        keyring, "get_password", lambda service, name: entries.get((service, name))
+    )
+    monkeypatch.setattr(
+        keyring,
+        "set_password",
+        lambda service, name, value: entries.__setitem__((service, name), value),
+    )
+    monkeypatch.setattr(
+        keyring, "delete_password", lambda service, name: entries.pop((service, name))
+    )
+    return entries
+
+
+def test_keyring_accounts_keep_their_keys_out_of_the_file(
+    config_dir: Path, keychain: dict[tuple[str, str], str]
+) -> None:
+    store = AccountStore(config_dir / "auth.json", mode=CredentialsStoreMode.KEYRING)
+
+    store.add("work", "openai", "sk-work")
+
+    assert "sk-work" not in store.path.read_text()
+    assert AccountStore(store.path).active_api_key("openai") == "sk-work"
+    store.remove("work")
+    assert keychain == {}
+
+
+def test_keyring_keys_are_read_once_per_change_of_the_accounts(
+    config_dir: Path,
+    keychain: dict[tuple[str, str], str],
+    monkeypatch: pytest.MonkeyPatch,
+) -> None:
+    store = AccountStore(config_dir / "auth.json", mode=CredentialsStoreMode.KEYRING)
+    store.add("work", "openai", "sk-work")
+    store.add("personal", "openai", "sk-personal")
+    reads: list[str] = []
+    monkeypatch.setattr(
+        keyring,
+        "get_password",
+        lambda service, name: reads.append(name) or keychain.get((service, name)),
+    )
+
+    assert store.active_api_key("openai") == "sk-personal"
+    assert store.active_api_key("openai") == "sk-personal"
+    store.switch("work")
+
+    assert store.active_api_key("openai") == "sk-work"
+    assert reads == ["personal", "work"]
+
+
+def test_command_accounts_go_through_the_helper(config_dir: Path) -> None:
+    vault = config_dir / "vault"
+    vault.mkdir()
+    helper = config_dir / "helper.py"
+    helper.write_text(
+        "import pathlib, sys\n"
+        f"path = pathlib.Path({str(vault)!r}) / sys.argv[2]\n"
+        "match sys.argv[1]:\n"
+        "    case 'get': print(path.read_text())\n"
+        "    case 'store': path.write_text(sys.stdin.read())\n"
+        "    case 'erase': path.unlink()\n",
+        encoding="utf-8",
+    )
+    store = AccountStore(
+        config_dir / "auth.json",
+        mode=CredentialsStoreMode.COMMAND,
+        command=f"{shlex.quote(sys.executable)} {shlex.quote(str(helper))}",
+    )
+
+    store.add("work", "openai", "sk-work")
+
+    assert "sk-work" not in store.path.read_text()
+    assert "sk-work" in (vault / "work").read_text()
+    assert store.active_api_key("openai") == "sk-work"
+    store.remove("work")
+    assert not (vault / "work").exists()
+
+
+def test_command_mode_needs_a_command() -> None:
+    with pytest.raises(AccountError):
+        AccountStore(mode=CredentialsStoreMode.COMMAND)
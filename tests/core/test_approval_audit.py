@@ -0,0 +1,110 @@
+from __future__ import annotations
+
+import hashlib
+import logging
+import sys
+
+import pytest
+
+from tests.conftest import build_test_agent_loop, build_test_rune_config
+from tests.mock.utils import mock_llm_chunk
+from tests.stubs.fake_backend import FakeBackend
+from rune.core.agents.models import BuiltinAgentName
+from rune.core.approval_audit import (
+    INSTALL_HINT,
+    ApprovalAudit,
+    AuditEventKind,
+    AuditRecord,
+    command_hash,
+)
+from rune.core.config import OtelConfig
+from rune.core.tools.base import BaseToolConfig, ToolPermission
+from rune.core.tools.builtins.bash import BashArgs
+from rune.core.tools.builtins.todo import TodoArgs
+from rune.core.types import ApprovalResponse, FunctionCall, ToolCall
+
+
+def make_loop(permission: ToolPermission):
+    todo_call = ToolCall(
+        id="tc1",
+        index=0,
+        function=FunctionCall(name="todo", arguments='{"action": "read"}'),
+    )
+    config = build_test_rune_config(
+        system_prompt_id="tests",
+        include_project_context=False,
+        include_prompt_detail=False,
+        enabled_tools=["todo"],
+        tools={"todo": BaseToolConfig(permission=permission)},
+    )
+    agent_loop = build_test_agent_loop(
+        config=config,
+        agent_name=BuiltinAgentName.DEFAULT,
+        backend=FakeBackend([
+            [mock_llm_chunk(content="", tool_calls=[todo_call])],
+            [mock_llm_chunk(content="Done")],
+        ]),
+    )
+    records: list[AuditRecord] = []
+    agent_loop.audit = ApprovalAudit(lambda: agent_loop.config, sink=records.append)
+    return agent_loop, records
+
+
+@pytest.mark.asyncio
+async def test_policy_matches_are_logged_with_their_rule() -> None:
+    agent_loop, records = make_loop(ToolPermission.ALWAYS)
+
+    [_ async for _ in agent_loop.act("Check todos")]
+
+    assert [(r.kind, r.rule_id, r.decision) for r in records] == [
+        (AuditEventKind.POLICY_MATCHED, "tools.todo.permission", "allow"),
+        (AuditEventKind.APPROVAL_DECIDED, "tools.todo.permission", "allow"),
+    ]
+    assert records[0].session_id == agent_loop.session_id
+    assert records[0].command_hash == command_hash(TodoArgs(action="read"))
+
+
+@pytest.mark.asyncio
+async def test_user_approvals_log_the_request_and_the_decision() -> None:
+    agent_loop, records = make_loop(ToolPermission.ASK)
+    agent_loop.set_approval_callback(lambda *_: (ApprovalResponse.NO, None))
+
+    [_ async for _ in agent_loop.act("Check todos")]
+
+    assert [(r.kind, r.rule_id, r.decision) for r in records] == [
+        (AuditEventKind.APPROVAL_REQUESTED, None, None),
+        (AuditEventKind.APPROVAL_DECIDED, None, "deny"),
+    ]
+    assert records[1].attributes()["rune.tool_call_id"] == "tc1"
+
+
+def test_command_hash_covers_only_the_command() -> None:
+    expected = hashlib.sha256(b"rm -rf build").hexdigest()
+
+    assert command_hash(BashArgs(command="rm -rf build")) == expected
+    assert command_hash(BashArgs(command="rm -rf build", timeout=5)) == expected
+
+
+def test_export_without_opentelemetry_warns_once(
+    monkeypatch: pytest.MonkeyPatch, caplog: pytest.LogCaptureFixture
+) -> None:
+    monkeypatch.setitem(sys.modules, "opentelemetry.sdk._logs", None)
+    config = build_test_rune_config(
+        otel=OtelConfig(logs_endpoint="http://127.0.0.1:4318/v1/logs")
+    )
+    audit = ApprovalAudit(lambda: config)
+    record = AuditRecord(
+        kind=AuditEventKind.APPROVAL_REQUESTED,
+        session_id="s",
+        tool="bash",
+        tool_call_id="tc1",
+        command_hash="0" * 64,
+    )
+
+    with caplog.at_level(logging.WARNING):
+        audit.record(record)
+        audit.record(record)
+
+    assert [r.message for r in caplog.records if "rune-cli[otel]" in r.message] == [
+        INSTALL_HINT
+    ]
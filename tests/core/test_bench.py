@@ -0,0 +1,100 @@
+from __future__ import annotations
+
+from pathlib import Path
+
+import pytest
+
+from tests.conftest import build_test_rune_config
+from tests.mock.utils import mock_llm_chunk
+from tests.stubs.fake_backend import FakeBackend
+from rune.core.bench import (
+    BenchError,
+    Variant,
+    load_tasks,
+    report_to_markdown,
+    run_bench,
+)
+
+
+def write_tasks(directory: Path) -> Path:
+    directory.mkdir()
+    (directory / "answer.toml").write_text(
+        'prompt = "What is 6 x 7?"\nexpect = ["\\\\b42\\\\b"]\nmax_turns = 3\n'
+    )
+    (directory / "marker.toml").write_text(
+        'prompt = "Create marker.txt"\ncheck = "test -f marker.txt"\n'
+    )
+    (directory / "summary.md").write_text("Summarize the project.")
+    (directory / "notes.txt").write_text("not a task")
+    return directory
+
+
+def test_load_tasks_reads_md_and_toml_files(tmp_path: Path) -> None:
+    tasks = load_tasks(write_tasks(tmp_path / "tasks"))
+
+    assert [task.name for task in tasks] == ["answer", "marker", "summary"]
+    assert tasks[0].expect == [r"\b42\b"]
+    assert tasks[0].max_turns == 3
+    assert tasks[1].check == "test -f marker.txt"
+    assert tasks[2].prompt == "Summarize the project."
+
+
+def test_invalid_task_files_are_reported(tmp_path: Path) -> None:
+    (tmp_path / "broken.toml").write_text("expect = []\n")
+
+    with pytest.raises(BenchError, match="broken.toml"):
+        load_tasks(tmp_path)
+    with pytest.raises(BenchError, match="No .md or .toml"):
+        load_tasks(tmp_path / "missing")
+
+
+def test_variants_are_model_aliases_or_config_files(tmp_path: Path) -> None:
+    overrides = tmp_path / "low-effort.toml"
+    overrides.write_text('active_model = "small"\nauto_compact_threshold = 1000\n')
+
+    assert Variant.parse("large").overrides == {"active_model": "large"}
+    variant = Variant.parse(str(overrides))
+    assert variant.label == "low-effort"
+    assert variant.overrides["auto_compact_threshold"] == 1000
+
+
+@pytest.mark.asyncio
+async def test_bench_compares_variants_in_throwaway_copies(tmp_path: Path) -> None:
+    tasks = load_tasks(write_tasks(tmp_path / "tasks"))
+    source = tmp_path / "project"
+    source.mkdir()
+    (source / "README.md").write_text("project")
+    replies = {"a": "The answer is 42.", "b": "It is forty-two."}
+
+    report = await run_bench(
+        tasks[:2],
+        [Variant(label="a"), Variant(label="b")],
+        source=source,
+        repeat=2,
+        load_config=lambda variant: build_test_rune_config(),
+        backend_factory=lambda variant: FakeBackend(
+            mock_llm_chunk(content=replies[variant.label])
+        ),
+    )
+
+    outcomes = [(r.task, r.variant, r.success) for r in report.results]
+    assert outcomes == [
+        ("answer", "a", True),
+        ("answer", "b", False),
+        ("answer", "a", True),
+        ("answer", "b", False),
+        ("marker", "a", False),
+        ("marker", "b", False),
+        ("marker", "a", False),
+        ("marker", "b", False),
+    ]
+    assert report.results[1].error == r"reply did not match \b42\b"
+    assert report.results[4].error == "check exited with 1"
+    assert all(r.total_tokens > 0 for r in report.results)
+    assert not (source / "marker.txt").exists()
+
+    a, b = report.summaries()
+    assert (a.successes, a.runs, b.successes) == (2, 4, 0)
+    markdown = report_to_markdown(report)
+    assert "| a | 2/4 (50%) |" in markdown
+    assert "b vs a: tokens" in markdown
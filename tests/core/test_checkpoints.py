@@ -0,0 +1,110 @@
+from __future__ import annotations
+
+import json
+from pathlib import Path
+
+import pytest
+
+from tests.conftest import build_test_agent_loop, build_test_rune_config
+from tests.mock.utils import mock_llm_chunk
+from tests.stubs.fake_backend import FakeBackend
+from rune.core.agents.models import BuiltinAgentName
+from rune.core.checkpoints import CheckpointError, CheckpointManager
+from rune.core.types import FunctionCall, Role, ToolCall
+
+
+class TestCheckpointManager:
+    def test_restore_rewrites_edited_and_removes_created_files(
+        self, tmp_path: Path
+    ) -> None:
+        edited = tmp_path / "edited.txt"
+        edited.write_text("before")
+        created = tmp_path / "created.txt"
+        manager = CheckpointManager()
+        manager.create("start", message_count=1)
+
+        manager.record_edit(edited)
+        edited.write_text("after")
+        manager.record_edit(created)
+        created.write_text("new")
+
+        checkpoint = manager.restore("start")
+
+        assert checkpoint.message_count == 1
+        assert edited.read_text() == "before"
+        assert not created.exists()
+
+    def test_only_first_edit_after_checkpoint_is_snapshotted(
+        self, tmp_path: Path
+    ) -> None:
+        target = tmp_path / "a.txt"
+        target.write_text("v1")
+        manager = CheckpointManager()
+        manager.create("first", message_count=1)
+        manager.record_edit(target)
+        target.write_text("v2")
+        manager.create("second", message_count=3)
+        manager.record_edit(target)
+        target.write_text("v3")
+
+        manager.restore("second")
+        assert target.read_text() == "v2"
+
+        manager.restore("first")
+        assert target.read_text() == "v1"
+
+    def test_restore_drops_later_checkpoints(self) -> None:
+        manager = CheckpointManager()
+        for i, name in enumerate(["a", "b", "c"]):
+            manager.create(name, message_count=i)
+
+        manager.restore("b")
+
+        assert manager.names == ["a", "b"]
+
+    def test_unknown_checkpoint_raises(self) -> None:
+        with pytest.raises(CheckpointError):
+            CheckpointManager().restore("missing")
+
+
+@pytest.mark.asyncio
+async def test_agent_loop_restores_messages_and_files(
+    tmp_path: Path, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    monkeypatch.chdir(tmp_path)
+    write_call = ToolCall(
+        id="call_1",
+        index=0,
+        function=FunctionCall(
+            name="write_file",
+            arguments=json.dumps({"path": "notes.txt", "content": "hello"}),
+        ),
+    )
+    agent_loop = build_test_agent_loop(
+        config=build_test_rune_config(
+            auto_compact_threshold=0,
+            enabled_tools=["write_file"],
+            system_prompt_id="tests",
+            include_project_context=False,
+            include_prompt_detail=False,
+        ),
+        agent_name=BuiltinAgentName.AUTO_APPROVE,
+        backend=FakeBackend([
+            [mock_llm_chunk(content="noted")],
+            [mock_llm_chunk(content="", tool_calls=[write_call])],
+            [mock_llm_chunk(content="written")],
+        ]),
+    )
+
+    [_ async for _ in agent_loop.act("remember this")]
+    agent_loop.rewind.create_checkpoint("before-write")
+    message_count = len(agent_loop.messages)
+    [_ async for _ in agent_loop.act("write notes.txt")]
+    assert (tmp_path / "notes.txt").read_text() == "hello"
+
+    await agent_loop.rewind.restore_checkpoint("before-write")
+
+    assert not (tmp_path / "notes.txt").exists()
+    assert len(agent_loop.messages) == message_count
+    assert agent_loop.messages[-1].role == Role.assistant
+    assert agent_loop.messages[-1].content == "noted"
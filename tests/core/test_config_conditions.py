@@ -0,0 +1,63 @@
+from __future__ import annotations
+
+from pathlib import Path
+
+import pytest
+import tomli_w
+
+from tests.conftest import get_base_config
+from rune.core import config_conditions
+from rune.core.config import RuneConfig
+from rune.core.config_conditions import apply_when_blocks
+
+DATA = {
+    "api_timeout": 60.0,
+    "session_logging": {"enabled": True, "compress": False},
+    "when": [
+        {"os": "windows", "api_timeout": 300.0},
+        {
+            "hostname": ["ci-*", "build-*"],
+            "auto_approve": True,
+            "session_logging": {"enabled": False},
+        },
+        {"os": ["linux", "macos"], "hostname": "laptop", "api_timeout": 30.0},
+    ],
+}
+
+
+def test_matching_blocks_are_merged_in_order() -> None:
+    resolved = apply_when_blocks(DATA, os_name="linux", hostname="CI-7.example.com")
+
+    assert resolved == {
+        "api_timeout": 60.0,
+        "auto_approve": True,
+        "session_logging": {"enabled": False, "compress": False},
+    }
+
+
+def test_all_conditions_of_a_block_must_match() -> None:
+    laptop = apply_when_blocks(DATA, os_name="macos", hostname="laptop")
+    windows = apply_when_blocks(DATA, os_name="windows", hostname="laptop")
+
+    assert laptop["api_timeout"] == 30.0
+    assert windows["api_timeout"] == 300.0
+
+
+def test_unknown_os_names_are_rejected() -> None:
+    with pytest.raises(ValueError, match="darwin"):
+        apply_when_blocks({"when": [{"os": "darwin"}]}, os_name="macos", hostname="x")
+
+
+def test_config_loads_with_the_blocks_for_this_machine(
+    monkeypatch: pytest.MonkeyPatch, config_dir: Path
+) -> None:
+    monkeypatch.setattr(config_conditions.socket, "gethostname", lambda: "ci-3")
+    config = get_base_config()
+    config["when"] = [{"hostname": "ci-*", "api_timeout": 300.0}]
+    (config_dir / "config.toml").write_text(tomli_w.dumps(config), encoding="utf-8")
+
+    assert RuneConfig.load().api_timeout == 300.0
+
+    RuneConfig.save_updates({"vim_keybindings": True})
+
+    assert "[[when]]" in (config_dir / "config.toml").read_text()
@@ -0,0 +1,131 @@
+from __future__ import annotations
+
+from pathlib import Path
+import tomllib
+
+import pytest
+
+from rune.cli.config import run_config_command
+from rune.core import config as config_module
+from rune.core.config import RuneConfig
+from rune.core.config_edit import (
+    ConfigKeyError,
+    get_value,
+    parse_value,
+    set_value,
+    unset_value,
+)
+from rune.core.org_defaults import OrgDefaults
+
+CONFIG = """\
+# Picked for speed.
+active_model = "devstral-latest"
+
+[[providers]]
+name = "rune"
+api_base = "https://api.rune.ai/v1"  # production
+api_key_env_var = "RUNE_API_KEY"
+
+[[providers]]
+name = "local"
+api_base = "http://localhost:8080/v1"
+
+[session_logging]
+# Keep everything.
+enabled = true
+"""
+
+
+def test_parse_value_reads_toml_and_falls_back_to_strings() -> None:
+    assert parse_value("true") is True
+    assert parse_value("3") == 3
+    assert parse_value('["a", "b"]') == ["a", "b"]
+    assert parse_value("devstral-latest") == "devstral-latest"
+    assert parse_value("http://localhost:8080/v1") == "http://localhost:8080/v1"
+
+
+def test_set_value_edits_only_the_line_of_the_key() -> None:
+    edited = set_value(CONFIG, "session_logging.enabled", False)
+
+    assert edited == CONFIG.replace("enabled = true", "enabled = false")
+
+
+def test_set_value_addresses_array_entries_by_name() -> None:
+    edited = set_value(CONFIG, "providers.local.api_base", "http://gpu:8080/v1")
+
+    assert "# production" in edited
+    assert 'api_base = "http://gpu:8080/v1"' in edited
+    data = tomllib.loads(edited)
+    assert get_value(data, "providers.rune.api_base") == "https://api.rune.ai/v1"
+    assert get_value(data, "providers.local.api_base") == "http://gpu:8080/v1"
+
+
+def test_set_value_adds_missing_keys_and_tables() -> None:
+    edited = set_value(CONFIG, "session_logging.compress", True)
+    edited = set_value(edited, "tools.bash.timeout", 30)
+
+    assert edited.startswith("# Picked for speed.\n")
+    data = tomllib.loads(edited)
+    assert data["session_logging"] == {"enabled": True, "compress": True}
+    assert data["tools"] == {"bash": {"timeout": 30}}
+
+
+def test_unset_value_removes_the_line() -> None:
+    edited = unset_value(CONFIG, "providers.rune.api_key_env_var")
+
+    assert "RUNE_API_KEY" not in edited
+    assert "# Keep everything." in edited
+    with pytest.raises(ConfigKeyError):
+        unset_value(CONFIG, "session_logging.compress")
+
+
+def test_keys_through_scalars_or_unknown_entries_are_rejected() -> None:
+    with pytest.raises(ConfigKeyError):
+        set_value(CONFIG, "active_model.name", "x")
+    with pytest.raises(ConfigKeyError):
+        set_value(CONFIG, "providers.missing.api_base", "x")
+
+
+def test_set_and_unset_persist_to_the_config_file(config_dir: Path) -> None:
+    run_config_command(["config", "set", "session_logging.compress", "false"])
+    assert RuneConfig.load().session_logging.compress is False
+
+    run_config_command(["config", "unset", "session_logging.compress"])
+    assert "compress" not in (config_dir / "config.toml").read_text()
+
+
+def test_get_prints_the_effective_value(capsys: pytest.CaptureFixture[str]) -> None:
+    run_config_command(["config", "get", "providers.rune.api_base"])
+
+    assert capsys.readouterr().out == "https://api.rune.ai/v1\n"
+
+
+def test_invalid_values_are_rolled_back(config_dir: Path) -> None:
+    before = (config_dir / "config.toml").read_text()
+
+    with pytest.raises(SystemExit):
+        run_config_command(["config", "set", "session_logging.compress", "[1, 2]"])
+
+    assert (config_dir / "config.toml").read_text() == before
+
+
+def test_unknown_top_level_keys_are_rejected(config_dir: Path) -> None:
+    with pytest.raises(SystemExit):
+        run_config_command(["config", "set", "sesion_logging.enabled", "true"])
+
+    assert "sesion_logging" not in (config_dir / "config.toml").read_text()
+
+
+def test_locked_keys_are_refused(
+    monkeypatch: pytest.MonkeyPatch, config_dir: Path
+) -> None:
+    document = OrgDefaults(defaults={"api_timeout": 300.0}, locked=["api_timeout"])
+    monkeypatch.setenv("RUNE_ORG_DEFAULTS_URL", "https://config.example/rune.toml")
+    monkeypatch.setattr(
+        config_module, "load_org_defaults", lambda url, *, ttl: document
+    )
+
+    with pytest.raises(SystemExit):
+        run_config_command(["config", "set", "api_timeout", "5.0"])
+
+    assert "api_timeout" not in (config_dir / "config.toml").read_text()
@@ -0,0 +1,79 @@
+from __future__ import annotations
+
+from pathlib import Path
+import tomllib
+
+from rune.core.config import RuneConfig
+from rune.core.config_migrations import (
+    CONFIG_VERSION,
+    migrate_config_data,
+    migrate_config_file,
+)
+
+OLD_CONFIG = """\
+# Local models
+active_model = "qwen"
+
+[[providers]]
+name = "vllm"
+api_base = "http://localhost:8000/v1"
+wire_api = "chat"  # for vLLM
+
+[[models]]
+name = "qwen"
+provider = "vllm"
+"""
+
+
+def test_deprecated_keys_are_renamed() -> None:
+    data = tomllib.loads(OLD_CONFIG)
+
+    migrated, changes = migrate_config_data(data)
+
+    assert migrated["providers"][0]["api_style"] == "chat"
+    assert "wire_api" not in migrated["providers"][0]
+    assert migrated["config_version"] == CONFIG_VERSION
+    assert changes == ["providers.vllm.wire_api is now providers.vllm.api_style"]
+    assert "wire_api" in data["providers"][0]
+
+
+def test_current_configs_are_left_alone() -> None:
+    data = {"config_version": CONFIG_VERSION, "providers": [{"wire_api": "chat"}]}
+
+    assert migrate_config_data(data) == (data, [])
+
+
+def test_migrating_a_file_keeps_a_backup_and_happens_once(tmp_path: Path) -> None:
+    path = tmp_path / "config.toml"
+    path.write_text(OLD_CONFIG, encoding="utf-8")
+
+    migration = migrate_config_file(path)
+
+    assert migration is not None
+    assert migration.backup == tmp_path / "config.toml.v0.bak"
+    backup = migration.backup.read_text(encoding="utf-8")
+    assert backup.endswith(OLD_CONFIG)
+    assert "# providers.vllm.wire_api is now providers.vllm.api_style" in backup
+    assert tomllib.loads(path.read_text())["config_version"] == CONFIG_VERSION
+    assert migrate_config_file(path) is None
+
+
+def test_migrating_a_file_keeps_its_comments(tmp_path: Path) -> None:
+    path = tmp_path / "config.toml"
+    path.write_text(OLD_CONFIG, encoding="utf-8")
+
+    migrate_config_file(path)
+
+    text = path.read_text(encoding="utf-8")
+    assert text.startswith("# Local models\n")
+    assert 'api_style = "chat"' in text
+    assert "wire_api" not in text
+
+
+def test_files_without_deprecated_keys_are_not_rewritten(config_dir: Path) -> None:
+    before = (config_dir / "config.toml").read_text()
+
+    RuneConfig.load()
+
+    assert (config_dir / "config.toml").read_text() == before
+    assert not list(config_dir.glob("*.bak"))
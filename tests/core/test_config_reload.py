@@ -0,0 +1,119 @@
+from __future__ import annotations
+
+import pytest
+import tomli_w
+
+from tests.conftest import build_test_agent_loop, build_test_rune_config
+from tests.mock.utils import mock_llm_chunk
+from tests.stubs.fake_backend import FakeBackend
+from rune.core.config import ModelConfig, ProviderConfig
+from rune.core.config_reload import describe_config_reload, merge_config_changes
+from rune.core.paths.config_paths import CONFIG_FILE
+from rune.core.tools.base import BaseToolConfig, ToolPermission
+from rune.core.types import ConfigReloadedEvent
+
+PROVIDER = ProviderConfig(name="local", api_base="http://127.0.0.1:8080/v1")
+CODER = ModelConfig(name="coder-1", provider="local", alias="coder")
+NEXT = ModelConfig(name="coder-2", provider="local", alias="coder-next")
+
+
+def make_config(**kwargs):
+    kwargs.setdefault("active_model", "coder")
+    return build_test_rune_config(providers=[PROVIDER], models=[CODER, NEXT], **kwargs)
+
+
+def test_changed_live_keys_are_applied_and_others_reported() -> None:
+    current = make_config()
+    fresh = make_config(
+        active_model="coder-next", disabled_tools=["bash"], api_timeout=5.0
+    )
+
+    merged, event = merge_config_changes(
+        current,
+        fresh,
+        before={"active_model": "coder"},
+        after={
+            "active_model": "coder-next",
+            "disabled_tools": ["bash"],
+            "api_timeout": 5.0,
+        },
+    )
+
+    assert merged.active_model == "coder-next"
+    assert merged.disabled_tools == ["bash"]
+    assert merged.api_timeout == current.api_timeout
+    assert event == ConfigReloadedEvent(
+        applied=["active_model", "disabled_tools"],
+        needs_restart=["api_timeout"],
+        active_model="coder-next",
+    )
+    assert describe_config_reload(event) == (
+        "Config reloaded: applied active_model, disabled_tools. "
+        "Restart Rune to apply api_timeout"
+    )
+
+
+def test_session_only_tool_settings_are_kept() -> None:
+    current = make_config(
+        tools={"bash": BaseToolConfig(permission=ToolPermission.ALWAYS)}
+    )
+    fresh = make_config(tools={"grep": BaseToolConfig(permission=ToolPermission.NEVER)})
+
+    merged, _ = merge_config_changes(
+        current, fresh, before={}, after={"tools": {"grep": {"permission": "never"}}}
+    )
+
+    assert merged.tools["bash"].permission == ToolPermission.ALWAYS
+    assert merged.tools["grep"].permission == ToolPermission.NEVER
+
+
+def test_unknown_active_models_wait_for_a_restart() -> None:
+    current = build_test_rune_config(
+        providers=[PROVIDER], models=[CODER], active_model="coder"
+    )
+
+    merged, event = merge_config_changes(
+        current,
+        make_config(active_model="coder-next"),
+        before={"active_model": "coder"},
+        after={"active_model": "coder-next"},
+    )
+
+    assert merged is current
+    assert event is not None
+    assert event.needs_restart == ["active_model"]
+
+
+def test_unchanged_files_reload_nothing() -> None:
+    current = make_config()
+
+    merged, event = merge_config_changes(
+        current, make_config(), before={"a": 1}, after={"a": 1}
+    )
+
+    assert event is None
+    assert merged is current
+
+
+@pytest.mark.asyncio
+async def test_next_turn_picks_up_a_new_active_model() -> None:
+    agent_loop = build_test_agent_loop(
+        config=make_config(watch_config=True),
+        backend=FakeBackend(mock_llm_chunk(content="Hi")),
+    )
+    CONFIG_FILE.path.write_text(
+        tomli_w.dumps({
+            "active_model": "coder-next",
+            "providers": [PROVIDER.model_dump(mode="json", exclude_none=True)],
+            "models": [
+                m.model_dump(mode="json", exclude_none=True) for m in (CODER, NEXT)
+            ],
+        }),
+        encoding="utf-8",
+    )
+
+    events = [event async for event in agent_loop.act("Hello")]
+
+    assert isinstance(events[0], ConfigReloadedEvent)
+    assert events[0].applied == ["active_model"]
+    assert agent_loop.config.active_model == "coder-next"
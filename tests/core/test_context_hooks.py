@@ -0,0 +1,105 @@
+from __future__ import annotations
+
+import sys
+
+import pytest
+
+from tests.conftest import build_test_agent_loop, build_test_rune_config
+from tests.mock.utils import mock_llm_chunk
+from tests.stubs.fake_backend import FakeBackend
+from rune.core.config import ContextHookConfig, ContextHooksConfig, RuneConfig
+from rune.core.context_hooks import ContextHookMiddleware
+from rune.core.middleware import ConversationContext, MiddlewareAction
+from rune.core.types import AgentStats, LLMMessage, Role
+from rune.core.utils import RUNE_CONTEXT_TAG
+
+ECHO_PROMPT = (
+    "import json, sys; print('ticket for: ' + json.load(sys.stdin)['prompt'])"
+)
+
+
+def _hook(code: str, **kwargs: object) -> ContextHookConfig:
+    return ContextHookConfig(command=[sys.executable, "-c", code], **kwargs)
+
+
+def _context(config: RuneConfig, *messages: LLMMessage) -> ConversationContext:
+    return ConversationContext(
+        messages=list(messages), stats=AgentStats(), config=config
+    )
+
+
+@pytest.mark.asyncio
+async def test_output_is_injected_at_the_start_of_a_turn(
+    rune_config: RuneConfig,
+) -> None:
+    middleware = ContextHookMiddleware(lambda: _hook(ECHO_PROMPT), lambda: "s1")
+
+    result = await middleware.before_turn(
+        _context(rune_config, LLMMessage(role=Role.user, content="fix bug"))
+    )
+
+    assert result.action == MiddlewareAction.INJECT_MESSAGE
+    assert result.metadata["context_message"] == (
+        f"<{RUNE_CONTEXT_TAG}>ticket for: fix bug</{RUNE_CONTEXT_TAG}>"
+    )
+
+
+@pytest.mark.asyncio
+async def test_hook_does_not_run_after_tool_results(rune_config: RuneConfig) -> None:
+    middleware = ContextHookMiddleware(lambda: _hook(ECHO_PROMPT), lambda: "s1")
+
+    result = await middleware.before_turn(
+        _context(rune_config, LLMMessage(role=Role.tool, content="output"))
+    )
+
+    assert result.action == MiddlewareAction.CONTINUE
+
+
+@pytest.mark.asyncio
+async def test_long_output_is_cut(rune_config: RuneConfig) -> None:
+    hook = _hook("print('x' * 100)", max_bytes=10)
+    middleware = ContextHookMiddleware(lambda: hook, lambda: "s1")
+
+    result = await middleware.before_turn(
+        _context(rune_config, LLMMessage(role=Role.user, content="hi"))
+    )
+
+    message = result.metadata["context_message"]
+    assert "x" * 10 + "\n[output cut at 10 bytes]" in message
+    assert "x" * 11 not in message
+
+
+@pytest.mark.asyncio
+async def test_failing_hook_is_skipped(rune_config: RuneConfig) -> None:
+    middleware = ContextHookMiddleware(
+        lambda: _hook("import sys; sys.exit(3)"), lambda: "s1"
+    )
+
+    result = await middleware.before_turn(
+        _context(rune_config, LLMMessage(role=Role.user, content="hi"))
+    )
+
+    assert result.action == MiddlewareAction.CONTINUE
+
+
+@pytest.mark.asyncio
+async def test_agent_loop_sends_hook_output_after_the_user_message() -> None:
+    backend = FakeBackend([[mock_llm_chunk(content="ok")]])
+    agent_loop = build_test_agent_loop(
+        config=build_test_rune_config(
+            context_hooks=ContextHooksConfig(pre_turn=_hook("print('build: red')")),
+            system_prompt_id="tests",
+            include_project_context=False,
+            include_prompt_detail=False,
+        ),
+        backend=backend,
+    )
+
+    [_ async for _ in agent_loop.act("why is CI failing?")]
+
+    user_message, hook_message = backend.requests_messages[0][-2:]
+    assert user_message.content == "why is CI failing?"
+    assert hook_message.role == Role.user
+    assert hook_message.content == (
+        f"<{RUNE_CONTEXT_TAG}>build: red</{RUNE_CONTEXT_TAG}>"
+    )
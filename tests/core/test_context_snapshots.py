@@ -0,0 +1,79 @@
+from __future__ import annotations
+
+import pytest
+
+from tests.conftest import build_test_agent_loop, build_test_rune_config
+from tests.mock.utils import mock_llm_chunk
+from tests.stubs.fake_backend import FakeBackend
+from rune.core.agent_loop import AgentLoop
+from rune.core.context_snapshots import ContextSnapshotError, ContextSnapshotStore
+from rune.core.types import LLMMessage, Role
+
+
+def _agent_loop(replies: int) -> AgentLoop:
+    return build_test_agent_loop(
+        config=build_test_rune_config(
+            auto_compact_threshold=0,
+            system_prompt_id="tests",
+            include_project_context=False,
+            include_prompt_detail=False,
+        ),
+        backend=FakeBackend([
+            [mock_llm_chunk(content=f"reply {i}")] for i in range(replies)
+        ]),
+    )
+
+
+class TestContextSnapshotStore:
+    def test_snapshot_is_a_copy(self) -> None:
+        messages = [LLMMessage(role=Role.user, content="hi")]
+        store = ContextSnapshotStore()
+
+        snapshot = store.take(messages, context_tokens=3)
+        messages[0].content = "changed"
+
+        assert store.get(snapshot.id).messages[0].content == "hi"
+
+    def test_oldest_snapshots_are_dropped_past_the_limit(self) -> None:
+        store = ContextSnapshotStore(limit=2)
+        for _ in range(3):
+            store.take([], context_tokens=0)
+
+        assert [s.id for s in store.snapshots] == ["2", "3"]
+        with pytest.raises(ContextSnapshotError):
+            store.get("1")
+
+
+@pytest.mark.asyncio
+async def test_restore_context_rolls_the_conversation_back() -> None:
+    agent_loop = _agent_loop(replies=2)
+    [_ async for _ in agent_loop.act("first")]
+    snapshot = agent_loop.rewind.snapshot_context()
+    [_ async for _ in agent_loop.act("second")]
+
+    await agent_loop.rewind.restore_context(snapshot.id)
+
+    assert [m.content for m in agent_loop.messages[1:]] == ["first", "reply 0"]
+    assert agent_loop.messages[0].role == Role.system
+
+
+@pytest.mark.asyncio
+async def test_undo_turns_can_be_undone() -> None:
+    agent_loop = _agent_loop(replies=3)
+    for prompt in ("one", "two", "three"):
+        [_ async for _ in agent_loop.act(prompt)]
+    before = [m.content for m in agent_loop.messages]
+
+    snapshot = await agent_loop.rewind.undo_turns(2)
+
+    assert [m.content for m in agent_loop.messages[1:]] == ["one", "reply 0"]
+    await agent_loop.rewind.restore_context(snapshot.id)
+    assert [m.content for m in agent_loop.messages] == before
+
+
+@pytest.mark.asyncio
+async def test_undo_without_turns_raises() -> None:
+    agent_loop = _agent_loop(replies=0)
+
+    with pytest.raises(ContextSnapshotError):
+        await agent_loop.rewind.undo_turns()
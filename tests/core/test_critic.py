@@ -0,0 +1,99 @@
+from __future__ import annotations
+
+from pydantic import BaseModel
+import pytest
+
+from tests.conftest import build_test_agent_loop, build_test_rune_config
+from tests.mock.utils import mock_llm_chunk
+from tests.stubs.fake_backend import FakeBackend
+from rune.core.agent_loop import AgentLoop
+from rune.core.config import CriticConfig
+from rune.core.critic import CriticReview, CriticVerdict
+from rune.core.tools.base import BaseToolConfig, ToolPermission
+from rune.core.types import ApprovalResponse, FunctionCall, ToolCall
+
+
+class TestCriticReviewParse:
+    def test_parses_verdict_and_summary(self) -> None:
+        review = CriticReview.parse(
+            "VERDICT: REJECT\nThe edit removes the only caller of `load`.", "judge"
+        )
+
+        assert review.verdict == CriticVerdict.REJECT
+        assert review.summary == "The edit removes the only caller of `load`."
+        assert review.model == "judge"
+
+    def test_unparseable_verdict_is_flagged_as_concerns(self) -> None:
+        review = CriticReview.parse("Looks fine to me.", "judge")
+
+        assert review.verdict == CriticVerdict.CONCERNS
+        assert review.summary == "Looks fine to me."
+
+
+def _make_agent_loop(critic_enabled: bool) -> tuple[AgentLoop, FakeBackend]:
+    config = build_test_rune_config(
+        auto_compact_threshold=0,
+        enabled_tools=["todo"],
+        tools={"todo": BaseToolConfig(permission=ToolPermission.ASK)},
+        system_prompt_id="tests",
+        include_project_context=False,
+        include_prompt_detail=False,
+        critic=CriticConfig(enabled=critic_enabled, tools=["todo"]),
+    )
+    tool_call = ToolCall(
+        id="call_1",
+        index=0,
+        function=FunctionCall(name="todo", arguments='{"action": "read"}'),
+    )
+    agent_loop = build_test_agent_loop(
+        config=config,
+        backend=FakeBackend([
+            [mock_llm_chunk(content="", tool_calls=[tool_call])],
+            [mock_llm_chunk(content="done")],
+        ]),
+    )
+    critic_backend = FakeBackend(
+        mock_llm_chunk(content="VERDICT: APPROVE\nReading todos is harmless.")
+    )
+    agent_loop.critic.backend = critic_backend
+    return agent_loop, critic_backend
+
+
+@pytest.mark.asyncio
+async def test_review_is_attached_to_approval_request() -> None:
+    agent_loop, critic_backend = _make_agent_loop(critic_enabled=True)
+    seen: list[CriticReview | None] = []
+
+    def approval_callback(
+        tool_name: str, args: BaseModel, tool_call_id: str
+    ) -> tuple[ApprovalResponse, str | None]:
+        seen.append(agent_loop.critic.reviews.get(tool_call_id))
+        return (ApprovalResponse.YES, None)
+
+    agent_loop.set_approval_callback(approval_callback)
+    [_ async for _ in agent_loop.act("show my todos")]
+
+    assert len(seen) == 1
+    review = seen[0]
+    assert review is not None
+    assert review.verdict == CriticVerdict.APPROVE
+    assert "show my todos" in (critic_backend.requests_messages[0][1].content or "")
+    assert agent_loop.critic.reviews == {}
+
+
+@pytest.mark.asyncio
+async def test_no_review_when_critic_disabled() -> None:
+    agent_loop, critic_backend = _make_agent_loop(critic_enabled=False)
+    seen: list[CriticReview | None] = []
+
+    def approval_callback(
+        tool_name: str, args: BaseModel, tool_call_id: str
+    ) -> tuple[ApprovalResponse, str | None]:
+        seen.append(agent_loop.critic.reviews.get(tool_call_id))
+        return (ApprovalResponse.YES, None)
+
+    agent_loop.set_approval_callback(approval_callback)
+    [_ async for _ in agent_loop.act("show my todos")]
+
+    assert seen == [None]
+    assert critic_backend.requests_messages == []
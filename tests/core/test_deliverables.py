@@ -0,0 +1,78 @@
+from __future__ import annotations
+
+from pathlib import Path
+
+import pytest
+
+from tests.conftest import build_test_agent_loop, build_test_rune_config
+from tests.mock.utils import mock_llm_chunk
+from tests.stubs.fake_backend import FakeBackend
+from rune.core.deliverables import composer_text, detect_deliverable, save_deliverable
+from rune.core.types import DeliverableEvent, DeliverableKind
+
+COMMIT_REPLY = """Here you go:
+
+```
+Fix off-by-one in pagination
+
+The last page was skipped when the total was a multiple of the size.
+```
+"""
+
+
+def test_commit_message_in_a_single_block_is_a_deliverable() -> None:
+    deliverable = detect_deliverable("Write a commit message for this", COMMIT_REPLY)
+
+    assert deliverable is not None
+    assert deliverable.kind == DeliverableKind.COMMIT_MESSAGE
+    assert deliverable.content.startswith("Fix off-by-one in pagination\n\n")
+
+
+def test_command_loses_its_prompt() -> None:
+    deliverable = detect_deliverable(
+        "What's the command to list open ports?", "```bash\n$ lsof -i -P -n\n```"
+    )
+
+    assert deliverable == DeliverableEvent(
+        kind=DeliverableKind.COMMAND, content="lsof -i -P -n", language="bash"
+    )
+
+
+@pytest.mark.parametrize(
+    ("request_text", "reply"),
+    [
+        ("Explain this function", COMMIT_REPLY),
+        ("Give me a command to format it", "```python\nprint('hi')\n```"),
+        ("A command to list files?", "```sh\nls\n```\nor\n```sh\nfind .\n```"),
+        ("Write a commit message", "Fix the pagination bug"),
+    ],
+)
+def test_unclear_replies_are_not_deliverables(request_text: str, reply: str) -> None:
+    assert detect_deliverable(request_text, reply) is None
+
+
+def test_commit_message_is_loaded_as_a_commit(tmp_path: Path) -> None:
+    deliverable = DeliverableEvent(
+        kind=DeliverableKind.COMMIT_MESSAGE, content="Don't skip the last page"
+    )
+
+    assert composer_text(deliverable) == (
+        "!git commit -m 'Don'\"'\"'t skip the last page'"
+    )
+    path = save_deliverable(deliverable, tmp_path)
+    assert path.name == "commit_message.txt"
+    assert path.read_text() == "Don't skip the last page\n"
+
+
+@pytest.mark.asyncio
+async def test_agent_loop_annotates_the_final_reply() -> None:
+    agent_loop = build_test_agent_loop(
+        config=build_test_rune_config(),
+        backend=FakeBackend(mock_llm_chunk(content=COMMIT_REPLY)),
+    )
+
+    events = [event async for event in agent_loop.act("Suggest a commit message")]
+
+    deliverables = [e for e in events if isinstance(e, DeliverableEvent)]
+    assert len(deliverables) == 1
+    assert deliverables[0].message_id == agent_loop.messages[-1].message_id
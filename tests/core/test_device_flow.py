@@ -0,0 +1,85 @@
+from __future__ import annotations
+
+from collections.abc import Iterator
+
+import httpx
+import pytest
+
+from rune.cli.accounts import run_login_command
+from rune.core.auth.device_flow import DeviceCodeFlow, DeviceFlowError
+from rune.core.config import OAuthConfig
+
+OAUTH = OAuthConfig(
+    client_id="rune-cli",
+    device_authorization_url="https://auth.example/device",
+    token_url="https://auth.example/token",
+    scopes=["inference", "offline_access"],
+)
+AUTHORIZATION = {
+    "device_code": "dev-123",
+    "user_code": "WDJB-MJHT",
+    "verification_uri": "https://auth.example/activate",
+    "expires_in": 900,
+    "interval": 5,
+}
+
+
+def _client(token_answers: list[dict]) -> tuple[httpx.AsyncClient, list[dict]]:
+    requests: list[dict] = []
+    answers: Iterator[dict] = iter(token_answers)
+
+    def handler(request: httpx.Request) -> httpx.Response:
+        form = dict(httpx.QueryParams(request.content.decode()))
+        requests.append({"url": str(request.url), **form})
+        if request.url.path == "/device":
+            return httpx.Response(200, json=AUTHORIZATION)
+        answer = next(answers)
+        return httpx.Response(400 if "error" in answer else 200, json=answer)
+
+    return httpx.AsyncClient(transport=httpx.MockTransport(handler)), requests
+
+
+@pytest.mark.asyncio
+async def test_polls_until_the_code_is_entered() -> None:
+    client, requests = _client([
+        {"error": "authorization_pending"},
+        {"error": "slow_down"},
+        {"access_token": "at-1", "refresh_token": "rt-1", "expires_in": 3600},
+    ])
+    waits: list[float] = []
+
+    async def sleep(seconds: float) -> None:
+        waits.append(seconds)
+
+    flow = DeviceCodeFlow(OAUTH, client=client, sleep=sleep)
+    authorization = await flow.start()
+    token = await flow.wait_for_token(authorization)
+
+    assert authorization.user_code == "WDJB-MJHT"
+    assert requests[0]["scope"] == "inference offline_access"
+    assert requests[1]["device_code"] == "dev-123"
+    assert waits == [5, 5, 10]
+    assert (token.access_token, token.refresh_token) == ("at-1", "rt-1")
+    assert token.expires_at is not None
+
+
+@pytest.mark.asyncio
+async def test_a_denied_login_fails() -> None:
+    client, _ = _client([{"error": "access_denied"}])
+
+    async def sleep(seconds: float) -> None:
+        pass
+
+    flow = DeviceCodeFlow(OAUTH, client=client, sleep=sleep)
+
+    with pytest.raises(DeviceFlowError, match="denied"):
+        await flow.wait_for_token(await flow.start())
+
+
+def test_device_login_needs_the_providers_oauth_settings(
+    capsys: pytest.CaptureFixture[str],
+) -> None:
+    with pytest.raises(SystemExit):
+        run_login_command(["login", "--device"])
+
+    assert "no OAuth settings" in capsys.readouterr().out
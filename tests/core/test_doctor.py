@@ -0,0 +1,63 @@
+from __future__ import annotations
+
+from pathlib import Path
+
+import pytest
+
+from rune.cli.doctor import run_doctor_command
+from rune.core.auth.github import GitHubAuthProvider
+from rune.core.config import RuneConfig
+from rune.core.doctor import CheckStatus, DoctorCheck, run_doctor
+
+
+@pytest.fixture(autouse=True)
+def _no_github_token(monkeypatch: pytest.MonkeyPatch) -> None:
+    monkeypatch.setattr(GitHubAuthProvider, "get_token", lambda self: None)
+
+
+def _by_name(checks: list[DoctorCheck]) -> dict[str, DoctorCheck]:
+    return {check.name: check for check in checks}
+
+
+def test_offline_run_skips_providers_and_mcp_servers() -> None:
+    checks = _by_name(run_doctor(online=False))
+
+    assert checks["Rune home"].status == CheckStatus.OK
+    assert checks["Config"].status == CheckStatus.OK
+    assert checks["Providers and MCP servers"].status == CheckStatus.SKIP
+
+
+def test_a_config_that_does_not_load_fails_with_a_fix() -> None:
+    def load_config() -> RuneConfig:
+        raise ValueError("active_model 'missing' is not in models")
+
+    checks = _by_name(run_doctor(online=False, load_config=load_config))
+
+    assert checks["Config"].status == CheckStatus.FAIL
+    assert "missing" in checks["Config"].detail
+    assert checks["Config"].fix
+    skipped = checks["Providers and MCP servers"]
+    assert skipped.detail == "Skipped until the config loads"
+
+
+def test_secrets_readable_by_others_are_flagged(config_dir: Path) -> None:
+    secrets = config_dir / "secrets.toml"
+    secrets.write_text("", encoding="utf-8")
+    secrets.chmod(0o644)
+
+    warnings = [
+        check
+        for check in run_doctor(online=False)
+        if check.status == CheckStatus.WARN and check.name == "Rune home"
+    ]
+
+    assert [check.fix for check in warnings] == [f"chmod 600 {secrets}"]
+
+
+def test_a_corrupt_memory_database_fails_the_command(config_dir: Path) -> None:
+    (config_dir / "memory.sqlite3").write_bytes(b"not a database" * 100)
+
+    with pytest.raises(SystemExit) as exc_info:
+        run_doctor_command(["doctor", "--offline"])
+
+    assert exc_info.value.code == 1
@@ -0,0 +1,69 @@
+from __future__ import annotations
+
+from pathlib import Path
+
+import pytest
+
+from tests.conftest import build_test_rune_config
+from rune.core import environment, system_prompt
+from rune.core.agents import AgentManager
+from rune.core.environment import EnvironmentDescriptor, detect_container
+from rune.core.skills.manager import SkillManager
+from rune.core.system_prompt import get_universal_system_prompt
+from rune.core.tools.manager import ToolManager
+
+DESCRIPTOR = EnvironmentDescriptor(
+    os="Linux",
+    os_version="6.8.0",
+    arch="x86_64",
+    container="docker",
+    toolchains={"git": "2.43.0", "python": "3.12.3"},
+    missing=["rustc"],
+)
+
+
+def test_render_lists_toolchains_and_missing_ones() -> None:
+    rendered = DESCRIPTOR.render()
+
+    assert "- OS: Linux 6.8.0 (x86_64), running on a docker container" in rendered
+    assert "- Installed: git 2.43.0, python 3.12.3" in rendered
+    assert "- Not installed: rustc" in rendered
+
+
+def test_probe_reads_versions_from_stderr(tmp_path: Path) -> None:
+    script = tmp_path / "fakejava"
+    script.write_text('#!/bin/sh\necho \'openjdk version "21.0.2" 2024-01-16\' >&2\n')
+    script.chmod(0o755)
+
+    assert environment._probe_version((str(script), "-version")) == "21.0.2"
+    assert environment._probe_version(("no-such-toolchain", "--version")) is None
+
+
+def test_kubernetes_is_detected_from_the_environment(
+    monkeypatch: pytest.MonkeyPatch,
+) -> None:
+    monkeypatch.setenv("KUBERNETES_SERVICE_HOST", "10.0.0.1")
+
+    assert detect_container() == "kubernetes"
+
+
+@pytest.mark.parametrize("enabled", [True, False])
+def test_descriptor_is_injected_into_the_system_prompt(
+    monkeypatch: pytest.MonkeyPatch, enabled: bool
+) -> None:
+    monkeypatch.setattr(system_prompt, "collect_environment", lambda: DESCRIPTOR)
+    config = build_test_rune_config(
+        system_prompt_id="tests",
+        include_project_context=False,
+        include_prompt_detail=True,
+        include_environment=enabled,
+    )
+
+    prompt = get_universal_system_prompt(
+        ToolManager(lambda: config),
+        config,
+        SkillManager(lambda: config),
+        AgentManager(lambda: config),
+    )
+
+    assert ("- Installed: git 2.43.0, python 3.12.3" in prompt) is enabled
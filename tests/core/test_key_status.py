@@ -0,0 +1,117 @@
+from __future__ import annotations
+
+import json
+from pathlib import Path
+import time
+
+import pytest
+
+from rune.cli.accounts import run_auth_command
+from rune.core.auth import key_status as key_status_module
+from rune.core.auth.accounts import AccountStore
+from rune.core.auth.key_status import (
+    KeySource,
+    KeyState,
+    key_status,
+    record_key_result,
+)
+from rune.core.config import ProviderConfig
+
+PROVIDER = ProviderConfig(
+    name="rune", api_base="https://api.rune.ai/v1", api_key_env_var="RUNE_API_KEY"
+)
+DAY = 24 * 3600
+
+
+@pytest.fixture
+def store(config_dir: Path) -> AccountStore:
+    return AccountStore(config_dir / "auth.json")
+
+
+def test_environment_keys_are_reported(monkeypatch: pytest.MonkeyPatch) -> None:
+    assert key_status(PROVIDER).source == KeySource.ENV
+    assert key_status(PROVIDER).state == KeyState.OK
+
+    monkeypatch.delenv("RUNE_API_KEY")
+    status = key_status(PROVIDER)
+
+    assert status.state == KeyState.MISSING
+    assert "rune login" in status.message
+
+
+def test_logged_in_keys_are_timestamped(store: AccountStore) -> None:
+    store.add("work", "rune", "sk-work")
+
+    status = key_status(PROVIDER, store=store)
+
+    assert status.source == KeySource.ACCOUNT
+    assert status.account == "work"
+    assert status.state == KeyState.OK
+    assert status.created_at == pytest.approx(time.time(), abs=60)
+    assert status.validated_at is None
+
+
+def test_rejected_key_is_invalid_until_accepted_again(store: AccountStore) -> None:
+    store.add("work", "rune", "sk-work")
+
+    record_key_result(PROVIDER, valid=False, store=store)
+    assert key_status(PROVIDER, store=store).state == KeyState.INVALID
+
+    record_key_result(PROVIDER, valid=True, store=store)
+    status = key_status(PROVIDER, store=store)
+    assert status.state == KeyState.OK
+    assert status.validated_at is not None
+
+
+def test_validation_is_recorded_at_most_hourly(
+    store: AccountStore, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    store.add("work", "rune", "sk-work")
+    record_key_result(PROVIDER, valid=True, store=store)
+    first = key_status(PROVIDER, store=store).validated_at
+
+    monkeypatch.setattr(key_status_module.time, "time", lambda: first + 60)
+    record_key_result(PROVIDER, valid=True, store=store)
+
+    assert key_status(PROVIDER, store=store).validated_at == first
+
+
+def test_tokens_without_refresh_warn_before_they_expire(store: AccountStore) -> None:
+    store.add("sso", "rune", "at-1", expires_at=time.time() + 3600)
+    assert key_status(PROVIDER, store=store).state == KeyState.EXPIRING
+
+    store.add("sso", "rune", "at-1", expires_at=time.time() - 60)
+    assert key_status(PROVIDER, store=store).state == KeyState.EXPIRED
+
+    store.add("sso", "rune", "at-1", refresh_token="rt", expires_at=time.time() + 60)
+    assert key_status(PROVIDER, store=store).state == KeyState.OK
+
+
+def test_old_keys_are_due_for_rotation(
+    store: AccountStore, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    store.add("work", "rune", "sk-work")
+    now = time.time()
+    monkeypatch.setattr(key_status_module.time, "time", lambda: now + 100 * DAY)
+
+    assert key_status(PROVIDER, store=store).state == KeyState.OK
+    status = key_status(PROVIDER, rotation_days=90, store=store)
+    assert status.state == KeyState.STALE
+    assert "100 days" in status.message
+
+
+def test_auth_status_json(
+    store: AccountStore, capsys: pytest.CaptureFixture[str]
+) -> None:
+    store.add("work", "rune", "sk-work")
+    record_key_result(PROVIDER, valid=False, store=store)
+
+    with pytest.raises(SystemExit) as exc_info:
+        run_auth_command(["auth", "status", "--json"])
+
+    assert exc_info.value.code == 1
+    output = json.loads(capsys.readouterr().out)
+    assert output["active_provider"] == "rune"
+    (status,) = output["providers"]
+    assert status["account"] == "work"
+    assert status["state"] == "invalid"
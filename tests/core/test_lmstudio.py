@@ -0,0 +1,111 @@
+from __future__ import annotations
+
+from pathlib import Path
+
+import httpx
+import pytest
+
+from rune.core import lmstudio
+from rune.core.config import LMStudioConfig, ProviderConfig
+from rune.core.lmstudio import (
+    DownloadProgress,
+    LMStudioClient,
+    LMStudioError,
+    LMStudioModel,
+)
+
+MODEL = {
+    "id": "qwen2.5-coder-7b-instruct",
+    "object": "model",
+    "type": "llm",
+    "publisher": "lmstudio-community",
+    "arch": "qwen2",
+    "quantization": "Q4_K_M",
+    "state": "not-loaded",
+    "max_context_length": 32768,
+}
+
+
+def _provider(**options: object) -> ProviderConfig:
+    return ProviderConfig(
+        name="lmstudio",
+        api_base="http://localhost:1234/v1",
+        lmstudio=LMStudioConfig.model_validate(options),
+    )
+
+
+def _server(*listings: list[dict]) -> httpx.AsyncClient:
+    remaining = list(listings)
+
+    def handler(request: httpx.Request) -> httpx.Response:
+        assert request.url.path == "/api/v0/models"
+        return httpx.Response(200, json={"data": remaining.pop(0)})
+
+    return httpx.AsyncClient(transport=httpx.MockTransport(handler))
+
+
+@pytest.fixture
+def fake_lms(tmp_path: Path, monkeypatch: pytest.MonkeyPatch) -> Path:
+    calls = tmp_path / "calls.txt"
+    script = tmp_path / "lms"
+    script.write_text(
+        "#!/bin/sh\n"
+        f'echo "$@" >> {calls}\n'
+        'if [ "$1" = get ]; then printf "Downloading 12.5%%\\r60%%\\r100%%\\n"; fi\n'
+    )
+    script.chmod(0o755)
+    monkeypatch.setattr(lmstudio, "find_lms", lambda: str(script))
+    return calls
+
+
+@pytest.mark.asyncio
+async def test_lists_downloaded_models() -> None:
+    client = LMStudioClient(_provider(), client=_server([MODEL]))
+
+    models = await client.list_models()
+
+    assert models == [LMStudioModel.model_validate(MODEL)]
+    assert models[0].describe() == (
+        "`qwen2.5-coder-7b-instruct` (llm, Q4_K_M, 32768 ctx)"
+    )
+
+
+@pytest.mark.asyncio
+async def test_download_reports_progress(fake_lms: Path) -> None:
+    client = LMStudioClient(_provider(), client=_server())
+
+    progress = [p async for p in client.download("qwen2.5-coder-7b")]
+
+    assert [p.fraction for p in progress] == [None, 1.0, 1.0]
+    assert progress[-1].done
+    assert fake_lms.read_text().split() == ["get", "qwen2.5-coder-7b", "--yes"]
+
+
+@pytest.mark.asyncio
+async def test_ensure_ready_downloads_then_loads_with_the_context_length(
+    fake_lms: Path,
+) -> None:
+    provider = _provider(auto_download=True, context_length=16384, ttl_seconds=600)
+    client = LMStudioClient(provider, client=_server([], [MODEL]))
+
+    steps = [step async for step in client.ensure_ready(MODEL["id"])]
+
+    assert isinstance(steps[0], DownloadProgress)
+    assert isinstance(steps[-1], LMStudioModel) and steps[-1].loaded
+    assert fake_lms.read_text().splitlines()[-1].split() == [
+        "load",
+        MODEL["id"],
+        "--yes",
+        "--context-length",
+        "16384",
+        "--ttl",
+        "600",
+    ]
+
+
+@pytest.mark.asyncio
+async def test_missing_model_is_not_downloaded_unless_allowed() -> None:
+    client = LMStudioClient(_provider(), client=_server([]))
+
+    with pytest.raises(LMStudioError, match="not downloaded"):
+        [_ async for _ in client.ensure_ready(MODEL["id"])]
@@ -0,0 +1,128 @@
+from __future__ import annotations
+
+from pathlib import Path
+
+import pytest
+
+from tests.conftest import build_test_rune_config
+from tests.mock.utils import collect_result
+from rune.core.agents import AgentManager
+from rune.core.memory import MemoryStore, MemoryStoreError
+from rune.core.skills.manager import SkillManager
+from rune.core.system_prompt import get_universal_system_prompt
+from rune.core.tools.base import BaseToolState, InvokeContext, ToolError
+from rune.core.tools.builtins.memory import (
+    Memory,
+    MemoryArgs,
+    MemoryResult,
+    MemoryToolConfig,
+)
+from rune.core.tools.manager import ToolManager
+
+
+@pytest.fixture
+def store(tmp_path: Path) -> MemoryStore:
+    return MemoryStore(tmp_path / "memory.sqlite3", project=tmp_path / "project")
+
+
+class TestMemoryStore:
+    def test_memories_are_kept_per_project(
+        self, store: MemoryStore, tmp_path: Path
+    ) -> None:
+        store.save("Tests run with `uv run pytest`", tags=["Testing"])
+        other = MemoryStore(store.path, project=tmp_path / "other")
+
+        [memory] = store.recent()
+        assert memory.content == "Tests run with `uv run pytest`"
+        assert memory.tags == ["testing"]
+        assert other.recent() == []
+
+    def test_search_ranks_by_shared_words_and_marks_use(
+        self, store: MemoryStore
+    ) -> None:
+        store.save("The API server lives in services/api")
+        store.save("Run the API tests with make test-api")
+        store.save("Use tabs in Makefiles")
+
+        found = store.search("how do I run api tests")
+
+        assert [m.content for m in found] == [
+            "Run the API tests with make test-api",
+            "The API server lives in services/api",
+        ]
+        assert store.recent(limit=1)[0].id in {m.id for m in found}
+
+    def test_delete_only_touches_the_project(
+        self, store: MemoryStore, tmp_path: Path
+    ) -> None:
+        memory = store.save("A note")
+
+        with pytest.raises(MemoryStoreError):
+            MemoryStore(store.path, project=tmp_path / "other").delete(memory.id)
+        store.delete(memory.id)
+        assert store.recent() == []
+
+    def test_empty_memories_are_rejected(self, store: MemoryStore) -> None:
+        with pytest.raises(MemoryStoreError):
+            store.save("   ")
+
+    def test_missing_database_is_not_created_by_reads(self, tmp_path: Path) -> None:
+        store = MemoryStore(tmp_path / "memory.sqlite3", project=tmp_path)
+
+        assert store.recent() == []
+        assert not store.path.exists()
+
+
+@pytest.mark.asyncio
+async def test_memory_tool_keeps_notes_for_the_session_workspace(
+    tmp_path: Path,
+) -> None:
+    tool = Memory(config=MemoryToolConfig(), state=BaseToolState())
+    ctx = InvokeContext(tool_call_id="call_1", cwd=tmp_path)
+
+    await collect_result(
+        tool.run(MemoryArgs(action="save", content="Worktree note"), ctx)
+    )
+
+    assert [m.content for m in MemoryStore(project=tmp_path).recent()] == [
+        "Worktree note"
+    ]
+    assert MemoryStore(project=Path.cwd()).recent() == []
+
+
+@pytest.mark.asyncio
+async def test_memory_tool_saves_searches_and_forgets() -> None:
+    tool = Memory(config=MemoryToolConfig(), state=BaseToolState())
+
+    saved = await collect_result(
+        tool.run(MemoryArgs(action="save", content="Deploys go through CI only"))
+    )
+    found = await collect_result(
+        tool.run(MemoryArgs(action="search", content="deploys"))
+    )
+    assert isinstance(found, MemoryResult)
+    memory_id = saved.notes[0].id
+    assert [n.id for n in found.notes] == [memory_id]
+
+    await collect_result(tool.run(MemoryArgs(action="forget", id=memory_id)))
+    with pytest.raises(ToolError):
+        await collect_result(tool.run(MemoryArgs(action="forget", id=memory_id)))
+
+
+def test_recent_memories_are_added_to_the_system_prompt() -> None:
+    MemoryStore(project=Path.cwd()).save("Never edit generated files under gen/")
+    config = build_test_rune_config(
+        system_prompt_id="tests",
+        include_project_context=False,
+        include_prompt_detail=False,
+    )
+
+    prompt = get_universal_system_prompt(
+        ToolManager(lambda: config),
+        config,
+        SkillManager(lambda: config),
+        AgentManager(lambda: config),
+    )
+
+    assert "# Memories" in prompt
+    assert "Never edit generated files under gen/" in prompt
@@ -0,0 +1,113 @@
+from __future__ import annotations
+
+from pathlib import Path
+
+import pytest
+import tomli_w
+
+from tests.conftest import build_test_agent_loop, build_test_rune_config
+from tests.mock.utils import mock_llm_chunk
+from tests.stubs.fake_backend import FakeBackend
+from rune.core.config import ModelConfig, ProviderConfig
+from rune.core.model_catalog import (
+    ModelSourceWatcher,
+    describe_model_list_change,
+    merge_model_list,
+)
+from rune.core.paths.config_paths import CONFIG_FILE
+from rune.core.types import ModelListChangedEvent
+
+PROVIDER = ProviderConfig(name="local", api_base="http://127.0.0.1:8080/v1")
+OLD = ModelConfig(name="coder-1", provider="local", alias="coder")
+NEW = ModelConfig(name="coder-2", provider="local", alias="coder-next")
+
+
+def make_config(*models: ModelConfig, active_model: str = "coder"):
+    return build_test_rune_config(
+        providers=[PROVIDER], models=list(models), active_model=active_model
+    )
+
+
+def test_watcher_reports_each_change_once(tmp_path: Path) -> None:
+    models_file = tmp_path / "config.toml"
+    watcher = ModelSourceWatcher(lambda: [models_file])
+
+    assert watcher.changed() is False
+    models_file.write_text("models = []\n")
+    assert watcher.changed() is True
+    assert watcher.changed() is False
+
+
+def test_active_model_follows_upgrades_on_load() -> None:
+    old = OLD.model_copy(update={"upgrade_to": "coder-next"})
+
+    config = make_config(old, NEW)
+
+    assert config.active_model == "coder-next"
+
+
+def test_merge_reports_added_removed_and_upgraded_models() -> None:
+    current = make_config(OLD)
+    fresh = make_config(OLD.model_copy(update={"upgrade_to": "coder-next"}), NEW)
+
+    merged, event = merge_model_list(current, fresh)
+
+    assert event == ModelListChangedEvent(
+        added=["coder-next"],
+        changed=["coder"],
+        active_model="coder-next",
+        upgraded_from="coder",
+    )
+    assert merged.active_model == "coder-next"
+    assert describe_model_list_change(event) == (
+        "Model list reloaded: added coder-next; updated coder. "
+        "Switched from coder to coder-next"
+    )
+
+
+def test_merge_keeps_a_removed_active_model() -> None:
+    current = make_config(OLD, NEW)
+    fresh = make_config(NEW, active_model="coder-next")
+
+    merged, event = merge_model_list(current, fresh)
+
+    assert event is not None
+    assert event.removed == ["coder"]
+    assert merged.get_active_model() == OLD
+
+
+def test_merge_ignores_unchanged_lists() -> None:
+    current = make_config(OLD)
+
+    merged, event = merge_model_list(current, make_config(OLD))
+
+    assert event is None
+    assert merged is current
+
+
+@pytest.mark.asyncio
+async def test_next_turn_picks_up_a_model_rollout() -> None:
+    config = build_test_rune_config(
+        providers=[PROVIDER], models=[OLD], active_model="coder", watch_models=True
+    )
+    agent_loop = build_test_agent_loop(
+        config=config, backend=FakeBackend(mock_llm_chunk(content="Hi"))
+    )
+    CONFIG_FILE.path.write_text(
+        tomli_w.dumps({
+            "active_model": "coder",
+            "providers": [PROVIDER.model_dump(mode="json", exclude_none=True)],
+            "models": [
+                {"name": "coder-1", "provider": "local", "alias": "coder"},
+                {"name": "coder-2", "provider": "local", "alias": "coder-next"},
+            ],
+        }),
+        encoding="utf-8",
+    )
+
+    events = [event async for event in agent_loop.act("Hello")]
+
+    assert events[0] == ModelListChangedEvent(
+        added=["coder-next"], active_model="coder"
+    )
+    assert [model.alias for model in agent_loop.config.models] == ["coder", "coder-next"]
@@ -0,0 +1,159 @@
+from __future__ import annotations
+
+from http import HTTPStatus
+
+import httpx
+import pytest
+
+from tests.conftest import build_test_agent_loop, build_test_rune_config
+from tests.mock.utils import mock_llm_chunk
+from tests.stubs.fake_backend import FakeBackend
+from rune.core.config import ModelConfig, RuneConfig
+from rune.core.llm.exceptions import BackendError, PayloadSummary, fallback_reason
+from rune.core.types import AssistantEvent, ModelFallbackEvent, RateLimitError
+
+
+def backend_error(status: int | None) -> BackendError:
+    return BackendError(
+        provider="rune",
+        endpoint="https://api.rune.ai/v1/chat/completions",
+        status=status,
+        reason=None,
+        headers=None,
+        body_text=None,
+        parsed_error=None,
+        model="primary",
+        payload_summary=PayloadSummary(
+            model="primary",
+            message_count=1,
+            approx_chars=5,
+            temperature=0.2,
+            has_tools=False,
+            tool_choice=None,
+        ),
+    )
+
+
+class FailingModelsBackend(FakeBackend):
+    """Raises for the listed model aliases and answers for every other one."""
+
+    def __init__(self, failing: dict[str, Exception], **kwargs) -> None:
+        super().__init__(**kwargs)
+        self.failing = failing
+        self.models: list[str] = []
+
+    async def complete(self, *, model, **kwargs):
+        self.models.append(model.alias)
+        if error := self.failing.get(model.alias):
+            raise error
+        return await super().complete(model=model, **kwargs)
+
+    async def complete_streaming(self, *, model, **kwargs):
+        self.models.append(model.alias)
+        if error := self.failing.get(model.alias):
+            raise error
+        async for chunk in super().complete_streaming(model=model, **kwargs):
+            yield chunk
+
+
+def make_config(fallbacks: list[str]) -> RuneConfig:
+    return build_test_rune_config(
+        models=[
+            ModelConfig(name="rune-large", provider="rune", alias="primary"),
+            ModelConfig(name="rune-medium", provider="rune", alias="secondary"),
+        ],
+        active_model="primary",
+        model_fallbacks=fallbacks,
+        system_prompt_id="tests",
+        include_project_context=False,
+        include_prompt_detail=False,
+    )
+
+
+@pytest.mark.parametrize(
+    ("error", "reason"),
+    [
+        (backend_error(HTTPStatus.TOO_MANY_REQUESTS), "rate limited"),
+        (backend_error(HTTPStatus.PAYMENT_REQUIRED), "quota exceeded"),
+        (backend_error(HTTPStatus.SERVICE_UNAVAILABLE), "provider unavailable"),
+        (backend_error(None), "provider unreachable"),
+        (httpx.ConnectTimeout("slow"), "provider unreachable"),
+        (backend_error(HTTPStatus.BAD_REQUEST), None),
+        (ValueError("bad"), None),
+    ],
+)
+def test_fallback_reason(error: Exception, reason: str | None) -> None:
+    wrapped = RuntimeError("API error")
+    wrapped.__cause__ = error
+
+    assert fallback_reason(wrapped) == reason
+
+
+def test_resolve_model_accepts_provider_prefixed_names() -> None:
+    config = make_config([])
+
+    assert config.resolve_model("secondary").name == "rune-medium"
+    assert config.resolve_model("rune:rune-medium").alias == "secondary"
+    unlisted = config.resolve_model("rune:rune-small")
+    assert (unlisted.provider, unlisted.name) == ("rune", "rune-small")
+    with pytest.raises(ValueError):
+        config.resolve_model("nope:rune-small")
+
+
+@pytest.mark.asyncio
+@pytest.mark.parametrize("streaming", [False, True])
+async def test_turn_moves_down_the_chain(streaming: bool) -> None:
+    backend = FailingModelsBackend(
+        {
+            "primary": backend_error(HTTPStatus.TOO_MANY_REQUESTS),
+            "secondary": backend_error(HTTPStatus.PAYMENT_REQUIRED),
+        },
+        chunks=[[mock_llm_chunk(content="Hello from the fallback")]],
+    )
+    agent_loop = build_test_agent_loop(
+        config=make_config(["unknown", "secondary", "rune:rune-small"]),
+        backend=backend,
+        enable_streaming=streaming,
+    )
+
+    events = [ev async for ev in agent_loop.act("hi")]
+
+    fallbacks = [ev for ev in events if isinstance(ev, ModelFallbackEvent)]
+    assert [(ev.from_model, ev.to_model, ev.reason) for ev in fallbacks] == [
+        ("primary", "secondary", "rate limited"),
+        ("secondary", "rune:rune-small", "quota exceeded"),
+    ]
+    assert backend.models == ["primary", "secondary", "rune:rune-small"]
+    assert any(
+        isinstance(ev, AssistantEvent) and ev.content == "Hello from the fallback"
+        for ev in events
+    )
+
+
+@pytest.mark.asyncio
+async def test_errors_are_raised_once_the_chain_is_exhausted() -> None:
+    backend = FailingModelsBackend({
+        "primary": backend_error(HTTPStatus.TOO_MANY_REQUESTS),
+        "secondary": backend_error(HTTPStatus.TOO_MANY_REQUESTS),
+    })
+    agent_loop = build_test_agent_loop(
+        config=make_config(["secondary"]), backend=backend
+    )
+
+    with pytest.raises(RateLimitError):
+        [ev async for ev in agent_loop.act("hi")]
+
+    assert backend.models == ["primary", "secondary"]
+
+
+@pytest.mark.asyncio
+async def test_other_errors_do_not_fall_back() -> None:
+    backend = FailingModelsBackend({"primary": backend_error(HTTPStatus.BAD_REQUEST)})
+    agent_loop = build_test_agent_loop(
+        config=make_config(["secondary"]), backend=backend
+    )
+
+    with pytest.raises(RuntimeError):
+        [ev async for ev in agent_loop.act("hi")]
+
+    assert backend.models == ["primary"]
@@ -0,0 +1,146 @@
+from __future__ import annotations
+
+import json
+from pathlib import Path
+
+import httpx
+import pytest
+
+from rune.core import config as config_module
+from rune.core.config import NetworkConfig, RuneConfig
+from rune.core.models_registry import FetchResult, ModelsRegistry, load_models_registry
+
+URL = "https://models.corp.example/rune/models.json"
+REGISTRY = {
+    "providers": [
+        {"name": "corp", "api_base": "https://llm.corp.example/v1"},
+    ],
+    "models": [
+        {"name": "corp-coder-2", "provider": "corp", "alias": "coder-2"},
+        {
+            "name": "corp-coder-1",
+            "provider": "corp",
+            "alias": "coder-1",
+            "upgrade_to": "coder-2",
+        },
+        {"name": "missing-alias", "provider": "corp"},
+    ],
+}
+
+
+class FakeServer:
+    def __init__(self, *responses: FetchResult | Exception) -> None:
+        self.responses = list(responses)
+        self.etags: list[str | None] = []
+
+    def __call__(self, url: str, etag: str | None) -> FetchResult:
+        self.etags.append(etag)
+        response = self.responses.pop(0)
+        if isinstance(response, Exception):
+            raise response
+        return response
+
+
+def test_registry_is_fetched_and_cached(tmp_path: Path) -> None:
+    server = FakeServer(FetchResult(json.dumps(REGISTRY), '"v1"'))
+    cache = tmp_path / "models_registry.json"
+
+    first = load_models_registry(URL, cache_file=cache, fetch=server)
+    second = load_models_registry(URL, cache_file=cache, fetch=server)
+
+    assert first is not None and second is not None
+    assert first == second
+    assert server.etags == [None]
+
+
+def test_stale_cache_is_revalidated_with_its_etag(tmp_path: Path) -> None:
+    cache = tmp_path / "models_registry.json"
+    load_models_registry(
+        URL,
+        cache_file=cache,
+        fetch=FakeServer(FetchResult(json.dumps(REGISTRY), '"v1"')),
+    )
+
+    server = FakeServer(FetchResult(None, None))
+    result = load_models_registry(URL, ttl=0, cache_file=cache, fetch=server)
+
+    assert server.etags == ['"v1"']
+    assert result is not None and len(result.models) == 3
+    assert json.loads(cache.read_text())["etag"] == '"v1"'
+
+
+def test_cached_copy_is_used_when_the_fetch_fails(tmp_path: Path) -> None:
+    cache = tmp_path / "models_registry.json"
+    load_models_registry(
+        URL,
+        cache_file=cache,
+        fetch=FakeServer(FetchResult(json.dumps(REGISTRY), None)),
+    )
+
+    result = load_models_registry(
+        URL,
+        ttl=0,
+        cache_file=cache,
+        fetch=FakeServer(httpx.ConnectError("offline")),
+    )
+
+    assert result is not None and len(result.providers) == 1
+
+
+def test_plain_http_urls_are_refused(tmp_path: Path) -> None:
+    server = FakeServer()
+
+    result = load_models_registry(
+        "http://models.corp.example/models.json",
+        cache_file=tmp_path / "cache.json",
+        fetch=server,
+    )
+
+    assert result is None
+    assert server.etags == []
+
+
+def test_registry_models_sit_below_user_config(
+    monkeypatch: pytest.MonkeyPatch, config_dir: Path
+) -> None:
+    (config_dir / "config.toml").write_text(
+        f'models_registry_url = "{URL}"\n'
+        'active_model = "coder-1"\n'
+        "[[models]]\n"
+        'name = "local-coder-2"\n'
+        'provider = "corp"\n'
+        'alias = "coder-2"\n'
+    )
+    requested: list[str] = []
+
+    def fake_load(
+        url: str, *, ttl: float, network: NetworkConfig, refresh_in_background: bool
+    ) -> ModelsRegistry:
+        requested.append(url)
+        return ModelsRegistry.model_validate(REGISTRY)
+
+    monkeypatch.setattr(config_module, "load_models_registry", fake_load)
+
+    config = RuneConfig.load()
+
+    assert requested == [URL]
+    assert config.get_provider_for_model(config.get_active_model()).name == "corp"
+    assert config.active_model == "coder-2"
+    assert config.get_active_model().name == "local-coder-2"
+    assert "missing-alias" not in {m.name for m in config.models}
+
+
+def test_expired_copy_is_served_while_it_is_refreshed(tmp_path: Path) -> None:
+    cache = tmp_path / "models_registry.json"
+    load_models_registry(
+        URL,
+        cache_file=cache,
+        fetch=FakeServer(FetchResult(json.dumps(REGISTRY), '"v1"')),
+    )
+    server = FakeServer(FetchResult(None, '"v1"'))
+
+    result = load_models_registry(
+        URL, ttl=0, cache_file=cache, fetch=server, refresh_in_background=True
+    )
+
+    assert result is not None and len(result.models) == 3
@@ -0,0 +1,124 @@
+from __future__ import annotations
+
+import json
+import sys
+
+import pytest
+
+from tests.conftest import build_test_agent_loop, build_test_rune_config
+from tests.mock.utils import mock_llm_chunk
+from tests.stubs.fake_backend import FakeBackend
+from rune.core.agent_loop import AgentLoop
+from rune.core.agents.models import BuiltinAgentName
+from rune.core.config import ModerationConfig
+from rune.core.types import (
+    AssistantEvent,
+    FunctionCall,
+    LLMChunk,
+    ModerationAction,
+    ModerationEvent,
+    Role,
+    ToolCall,
+    ToolResultEvent,
+)
+
+
+def _hook(verdict: dict[str, str] | None = None, exit_code: int = 0) -> list[str]:
+    script = (
+        "import json, sys; json.load(sys.stdin); "
+        f"print({json.dumps(json.dumps(verdict or {}))}); sys.exit({exit_code})"
+    )
+    return [sys.executable, "-c", script]
+
+
+def _make_agent_loop(moderation: ModerationConfig, chunk: LLMChunk) -> AgentLoop:
+    config = build_test_rune_config(
+        auto_compact_threshold=0,
+        enabled_tools=["todo"],
+        system_prompt_id="tests",
+        include_project_context=False,
+        include_prompt_detail=False,
+        moderation=moderation,
+    )
+    return build_test_agent_loop(
+        config=config,
+        agent_name=BuiltinAgentName.AUTO_APPROVE,
+        backend=FakeBackend(chunk),
+    )
+
+
+@pytest.mark.asyncio
+async def test_allowed_response_is_passed_through() -> None:
+    agent_loop = _make_agent_loop(
+        ModerationConfig(command=_hook({"action": "allow"})),
+        mock_llm_chunk(content="Hello"),
+    )
+
+    events = [ev async for ev in agent_loop.act("hi")]
+
+    assert [ev.content for ev in events if isinstance(ev, AssistantEvent)] == ["Hello"]
+    assert not any(isinstance(ev, ModerationEvent) for ev in events)
+
+
+@pytest.mark.asyncio
+async def test_annotation_follows_the_response() -> None:
+    agent_loop = _make_agent_loop(
+        ModerationConfig(
+            command=_hook({"action": "annotate", "message": "GPL snippet"})
+        ),
+        mock_llm_chunk(content="Here is the code"),
+    )
+
+    events = [ev async for ev in agent_loop.act("hi")]
+
+    assert isinstance(events[-2], AssistantEvent)
+    assert isinstance(events[-1], ModerationEvent)
+    assert events[-1].action == ModerationAction.ANNOTATE
+    assert events[-1].message == "GPL snippet"
+
+
+@pytest.mark.asyncio
+async def test_blocked_response_is_not_shown_or_applied() -> None:
+    tool_call = ToolCall(
+        id="call_1",
+        index=0,
+        function=FunctionCall(name="todo", arguments='{"action": "read"}'),
+    )
+    agent_loop = _make_agent_loop(
+        ModerationConfig(command=_hook({"action": "block", "message": "restricted"})),
+        mock_llm_chunk(content="secret", tool_calls=[tool_call]),
+    )
+
+    events = [ev async for ev in agent_loop.act("hi")]
+
+    assert not any(isinstance(ev, AssistantEvent | ToolResultEvent) for ev in events)
+    assert isinstance(events[-1], ModerationEvent)
+    last = agent_loop.messages[-1]
+    assert last.role == Role.assistant
+    assert last.tool_calls is None
+    assert last.content == "[Response blocked by moderation hook: restricted]"
+
+
+@pytest.mark.asyncio
+async def test_failing_hook_fails_open_by_default() -> None:
+    agent_loop = _make_agent_loop(
+        ModerationConfig(command=_hook(exit_code=1)), mock_llm_chunk(content="Hello")
+    )
+
+    events = [ev async for ev in agent_loop.act("hi")]
+
+    assert any(isinstance(ev, AssistantEvent) for ev in events)
+
+
+@pytest.mark.asyncio
+async def test_failing_hook_blocks_when_fail_closed() -> None:
+    agent_loop = _make_agent_loop(
+        ModerationConfig(command=_hook(exit_code=1), fail_closed=True),
+        mock_llm_chunk(content="Hello"),
+    )
+
+    events = [ev async for ev in agent_loop.act("hi")]
+
+    assert not any(isinstance(ev, AssistantEvent) for ev in events)
+    assert isinstance(events[-1], ModerationEvent)
+    assert events[-1].action == ModerationAction.BLOCK
@@ -0,0 +1,229 @@
+from __future__ import annotations
+
+from pathlib import Path
+import re
+from typing import Any
+
+from cryptography.hazmat.primitives import serialization
+from cryptography.hazmat.primitives.asymmetric.ed25519 import Ed25519PrivateKey
+import httpx
+import pytest
+import tomli_w
+
+from tests.conftest import get_base_config
+from rune.core import config as config_module
+from rune.core.config import RuneConfig
+from rune.core.org_defaults import LockedConfigError, OrgDefaults, load_org_defaults
+from rune.core.trusted_folders import trusted_folders_manager
+
+URL = "https://config.corp.example/rune/defaults.toml"
+DEFAULTS = {
+    "api_timeout": 300.0,
+    "disabled_tools": ["fetch_url"],
+    "not_a_setting": True,
+}
+
+
+def _private_pem(key: Ed25519PrivateKey) -> bytes:
+    return key.private_bytes(
+        encoding=serialization.Encoding.PEM,
+        format=serialization.PrivateFormat.PKCS8,
+        encryption_algorithm=serialization.NoEncryption(),
+    )
+
+
+@pytest.fixture
+def key() -> Ed25519PrivateKey:
+    return Ed25519PrivateKey.generate()
+
+
+@pytest.fixture
+def document(key: Ed25519PrivateKey) -> str:
+    return OrgDefaults(name="acme", defaults=DEFAULTS).sign(_private_pem(key)).to_toml()
+
+
+class FakeServer:
+    def __init__(self, *responses: str | Exception) -> None:
+        self.responses = list(responses)
+        self.calls = 0
+
+    def __call__(self, url: str) -> str:
+        self.calls += 1
+        response = self.responses.pop(0)
+        if isinstance(response, Exception):
+            raise response
+        return response
+
+
+def test_signed_document_is_fetched_and_cached(
+    tmp_path: Path, key: Ed25519PrivateKey, document: str
+) -> None:
+    server = FakeServer(document)
+    cache = tmp_path / "org_defaults.json"
+
+    first = load_org_defaults(
+        URL, trusted_keys=[key.public_key()], cache_file=cache, fetch=server
+    )
+    second = load_org_defaults(
+        URL, trusted_keys=[key.public_key()], cache_file=cache, fetch=server
+    )
+
+    assert first is not None and second is not None
+    assert first.defaults == second.defaults == DEFAULTS
+    assert server.calls == 1
+
+
+def test_unsigned_or_untrusted_documents_are_rejected(
+    tmp_path: Path, document: str
+) -> None:
+    unsigned = OrgDefaults(defaults=DEFAULTS).to_toml()
+    other_key = Ed25519PrivateKey.generate().public_key()
+
+    for text in (unsigned, document):
+        assert (
+            load_org_defaults(
+                URL,
+                trusted_keys=[other_key],
+                cache_file=tmp_path / "cache.json",
+                fetch=FakeServer(text),
+            )
+            is None
+        )
+
+
+def test_last_verified_copy_is_used_when_the_fetch_fails(
+    tmp_path: Path, key: Ed25519PrivateKey, document: str
+) -> None:
+    cache = tmp_path / "org_defaults.json"
+    keys = [key.public_key()]
+    load_org_defaults(
+        URL, trusted_keys=keys, cache_file=cache, fetch=FakeServer(document)
+    )
+
+    server = FakeServer(httpx.ConnectError("offline"))
+    result = load_org_defaults(
+        URL, ttl=0, trusted_keys=keys, cache_file=cache, fetch=server
+    )
+
+    assert server.calls == 1
+    assert result is not None and result.name == "acme"
+
+
+def test_failed_fetches_are_not_retried_right_away(
+    tmp_path: Path, key: Ed25519PrivateKey, document: str
+) -> None:
+    cache = tmp_path / "org_defaults.json"
+    keys = [key.public_key()]
+    server = FakeServer(httpx.ConnectError("offline"), document)
+
+    first = load_org_defaults(URL, trusted_keys=keys, cache_file=cache, fetch=server)
+    second = load_org_defaults(URL, trusted_keys=keys, cache_file=cache, fetch=server)
+
+    assert first is None and second is None
+    assert server.calls == 1
+
+
+def test_tampered_cache_is_ignored(
+    tmp_path: Path, key: Ed25519PrivateKey, document: str
+) -> None:
+    cache = tmp_path / "org_defaults.json"
+    keys = [key.public_key()]
+    load_org_defaults(
+        URL, trusted_keys=keys, cache_file=cache, fetch=FakeServer(document)
+    )
+    cache.write_text(cache.read_text().replace("300.0", "1.0"))
+
+    server = FakeServer(httpx.ConnectError("offline"))
+    assert (
+        load_org_defaults(URL, trusted_keys=keys, cache_file=cache, fetch=server)
+        is None
+    )
+
+
+def test_plain_http_urls_are_refused(tmp_path: Path, key: Ed25519PrivateKey) -> None:
+    server = FakeServer()
+
+    result = load_org_defaults(
+        "http://config.corp.example/defaults.toml",
+        trusted_keys=[key.public_key()],
+        cache_file=tmp_path / "cache.json",
+        fetch=server,
+    )
+
+    assert result is None
+    assert server.calls == 0
+
+
+def test_org_defaults_sit_below_user_config(
+    monkeypatch: pytest.MonkeyPatch, config_dir: Path
+) -> None:
+    (config_dir / "config.toml").write_text(
+        f'org_defaults_url = "{URL}"\ndisabled_tools = ["bash"]\n'
+    )
+    requested: list[str] = []
+
+    def fake_load(url: str, *, ttl: float) -> OrgDefaults:
+        requested.append(url)
+        return OrgDefaults(defaults=DEFAULTS)
+
+    monkeypatch.setattr(config_module, "load_org_defaults", fake_load)
+
+    config = RuneConfig.load()
+
+    assert requested == [URL]
+    assert config.api_timeout == 300.0
+    assert config.disabled_tools == ["bash"]
+
+
+def _config_with(**settings: Any) -> str:
+    return tomli_w.dumps({**get_base_config(), "org_defaults_url": URL, **settings})
+
+
+@pytest.fixture
+def locked_defaults(monkeypatch: pytest.MonkeyPatch, config_dir: Path) -> None:
+    (config_dir / "config.toml").write_text(_config_with())
+    document = OrgDefaults(name="acme", defaults=DEFAULTS, locked=["api_timeout"])
+    monkeypatch.setattr(
+        config_module, "load_org_defaults", lambda url, *, ttl: document
+    )
+
+
+@pytest.mark.usefixtures("locked_defaults")
+def test_locked_keys_overrule_the_user_config(config_dir: Path) -> None:
+    (config_dir / "config.toml").write_text(_config_with(api_timeout=5.0))
+
+    assert RuneConfig.load().api_timeout == 300.0
+
+
+@pytest.mark.usefixtures("locked_defaults")
+def test_overrides_of_locked_keys_are_rejected(monkeypatch: pytest.MonkeyPatch) -> None:
+    with pytest.raises(LockedConfigError, match="command line"):
+        RuneConfig.load(api_timeout=5.0)
+
+    monkeypatch.setenv("RUNE_API_TIMEOUT", "5")
+    with pytest.raises(LockedConfigError, match="org defaults 'acme' at https://"):
+        RuneConfig.load()
+
+    monkeypatch.setenv("RUNE_API_TIMEOUT", "300.0")
+    assert RuneConfig.load().api_timeout == 300.0
+
+
+@pytest.mark.usefixtures("locked_defaults")
+def test_project_configs_cannot_change_locked_keys(
+    monkeypatch: pytest.MonkeyPatch, tmp_path: Path
+) -> None:
+    project_config = tmp_path / ".rune" / "config.toml"
+    project_config.parent.mkdir()
+    project_config.write_text(_config_with(api_timeout=5.0))
+    monkeypatch.chdir(tmp_path)
+    monkeypatch.setattr(trusted_folders_manager, "is_trusted", lambda _: True)
+
+    with pytest.raises(LockedConfigError, match=re.escape(str(project_config))):
+        RuneConfig.load()
+
+
+def test_documents_without_locks_sign_as_before(key: Ed25519PrivateKey) -> None:
+    signed = OrgDefaults(name="acme", defaults=DEFAULTS).sign(_private_pem(key))
+
+    assert b"locked" not in signed.payload()
+    assert OrgDefaults.parse(signed.to_toml()).verify([key.public_key()])
@@ -0,0 +1,97 @@
+from __future__ import annotations
+
+import json
+
+import pytest
+
+from tests.conftest import build_test_agent_loop, build_test_rune_config
+from tests.mock.utils import mock_llm_chunk
+from tests.stubs.fake_backend import FakeBackend
+from rune.core.config import ModelConfig, ProviderConfig, RuneConfig
+from rune.core.llm.backend.anthropic import AnthropicAdapter
+from rune.core.llm.backend.generic import OpenAIAdapter
+from rune.core.types import LLMMessage, OutputSchemaUnsupportedEvent, Role
+
+SCHEMA = {
+    "type": "object",
+    "properties": {"summary": {"type": "string"}},
+    "required": ["summary"],
+}
+
+
+def _payload(adapter: OpenAIAdapter | AnthropicAdapter, provider: ProviderConfig):
+    request = adapter.prepare_request(
+        model_name="model",
+        messages=[LLMMessage(role=Role.user, content="hi")],
+        temperature=0.2,
+        tools=None,
+        max_tokens=None,
+        tool_choice=None,
+        enable_streaming=False,
+        provider=provider,
+        output_schema=SCHEMA,
+    )
+    return json.loads(request.body)
+
+
+def make_config(api_style: str) -> RuneConfig:
+    return build_test_rune_config(
+        providers=[
+            ProviderConfig(
+                name="remote", api_base="https://example.invalid", api_style=api_style
+            )
+        ],
+        models=[ModelConfig(name="remote-model", provider="remote", alias="remote")],
+        active_model="remote",
+        system_prompt_id="tests",
+        include_project_context=False,
+        include_prompt_detail=False,
+    )
+
+
+def test_chat_completions_send_the_schema_as_response_format() -> None:
+    provider = ProviderConfig(name="lmstudio", api_base="http://localhost:1234/v1")
+
+    payload = _payload(OpenAIAdapter(), provider)
+
+    assert payload["response_format"] == {
+        "type": "json_schema",
+        "json_schema": {"name": "final_output", "schema": SCHEMA, "strict": False},
+    }
+
+
+def test_anthropic_requests_leave_the_schema_out() -> None:
+    provider = ProviderConfig(
+        name="anthropic", api_base="https://api.anthropic.com/v1", api_style="anthropic"
+    )
+
+    assert "response_format" not in _payload(AnthropicAdapter(), provider)
+
+
+@pytest.mark.asyncio
+async def test_schema_is_only_sent_for_the_final_reply_of_the_turn() -> None:
+    backend = FakeBackend([
+        [mock_llm_chunk(content="All done.")],
+        [mock_llm_chunk(content='{"summary": "done"}')],
+        [mock_llm_chunk(content="Hello again.")],
+    ])
+    agent_loop = build_test_agent_loop(config=make_config("openai"), backend=backend)
+
+    events = [ev async for ev in agent_loop.act("hi", output_schema=SCHEMA)]
+    [ev async for ev in agent_loop.act("again")]
+
+    assert not any(isinstance(ev, OutputSchemaUnsupportedEvent) for ev in events)
+    assert backend.requests_output_schemas == [None, SCHEMA, None]
+    replies = [msg.content for msg in agent_loop.messages if msg.role == Role.assistant]
+    assert replies == ['{"summary": "done"}', "Hello again."]
+
+
+@pytest.mark.asyncio
+async def test_unsupported_provider_is_reported() -> None:
+    backend = FakeBackend([mock_llm_chunk(content="done")])
+    agent_loop = build_test_agent_loop(config=make_config("anthropic"), backend=backend)
+
+    events = [ev async for ev in agent_loop.act("hi", output_schema=SCHEMA)]
+
+    unsupported = [ev for ev in events if isinstance(ev, OutputSchemaUnsupportedEvent)]
+    assert [(ev.model, ev.provider) for ev in unsupported] == [("remote", "remote")]
@@ -0,0 +1,120 @@
+from __future__ import annotations
+
+from pathlib import Path
+
+from cryptography.hazmat.primitives import serialization
+from cryptography.hazmat.primitives.asymmetric.ed25519 import Ed25519PrivateKey
+import pytest
+
+from rune.core.policy_bundle import (
+    BundleKind,
+    BundleSignatureError,
+    PolicyBundle,
+    load_trusted_keys,
+)
+from rune.core.tools.base import ToolPermission
+
+CONFIG_DATA = {
+    "enabled_tools": [],
+    "disabled_tools": ["mcp_*"],
+    "tools": {
+        "bash": {
+            "permission": "ask",
+            "allowlist": ["npm test", "npm run lint"],
+            "denylist": ["rm -rf"],
+        },
+        "read_file": {"permission": "always"},
+    },
+}
+
+
+def _private_pem(key: Ed25519PrivateKey) -> bytes:
+    return key.private_bytes(
+        encoding=serialization.Encoding.PEM,
+        format=serialization.PrivateFormat.PKCS8,
+        encryption_algorithm=serialization.NoEncryption(),
+    )
+
+
+def _write_public_key(key: Ed25519PrivateKey, directory: Path) -> None:
+    directory.mkdir(parents=True, exist_ok=True)
+    (directory / "team.pem").write_bytes(
+        key.public_key().public_bytes(
+            encoding=serialization.Encoding.PEM,
+            format=serialization.PublicFormat.SubjectPublicKeyInfo,
+        )
+    )
+
+
+def test_approvals_bundle_keeps_only_approval_rules() -> None:
+    bundle = PolicyBundle.from_config_data(BundleKind.APPROVALS, CONFIG_DATA)
+
+    assert bundle.tools["bash"].allowlist == ["npm test", "npm run lint"]
+    assert bundle.tools["bash"].denylist == []
+    assert bundle.tools["read_file"].permission == ToolPermission.ALWAYS
+    assert bundle.disabled_tools == []
+
+
+def test_policy_bundle_keeps_only_restrictions() -> None:
+    bundle = PolicyBundle.from_config_data(BundleKind.POLICY, CONFIG_DATA)
+
+    assert set(bundle.tools) == {"bash"}
+    assert bundle.tools["bash"].denylist == ["rm -rf"]
+    assert bundle.tools["bash"].permission is None
+    assert bundle.disabled_tools == ["mcp_*"]
+
+
+def test_bundle_round_trips_through_toml(tmp_path: Path) -> None:
+    bundle = PolicyBundle.from_config_data(
+        BundleKind.APPROVALS, CONFIG_DATA, name="node"
+    )
+    path = tmp_path / "bundle.toml"
+    path.write_text(bundle.to_toml())
+
+    assert PolicyBundle.load(path) == bundle
+
+
+def test_signed_bundle_verifies_against_trusted_key(tmp_path: Path) -> None:
+    key = Ed25519PrivateKey.generate()
+    _write_public_key(key, tmp_path / "keys")
+    bundle = PolicyBundle.from_config_data(BundleKind.POLICY, CONFIG_DATA).sign(
+        _private_pem(key)
+    )
+    path = tmp_path / "bundle.toml"
+    path.write_text(bundle.to_toml())
+
+    loaded = PolicyBundle.load(path)
+
+    assert loaded.verify(load_trusted_keys(tmp_path / "keys")) == loaded.signed_by
+
+
+def test_tampered_bundle_fails_verification(tmp_path: Path) -> None:
+    key = Ed25519PrivateKey.generate()
+    _write_public_key(key, tmp_path / "keys")
+    bundle = PolicyBundle.from_config_data(BundleKind.POLICY, CONFIG_DATA).sign(
+        _private_pem(key)
+    )
+    bundle.tools["bash"].denylist.clear()
+
+    with pytest.raises(BundleSignatureError):
+        bundle.verify(load_trusted_keys(tmp_path / "keys"))
+
+
+def test_unsigned_bundle_fails_verification() -> None:
+    bundle = PolicyBundle.from_config_data(BundleKind.POLICY, CONFIG_DATA)
+
+    with pytest.raises(BundleSignatureError):
+        bundle.verify([Ed25519PrivateKey.generate().public_key()])
+
+
+def test_config_updates_merge_rules() -> None:
+    bundle = PolicyBundle.from_config_data(BundleKind.APPROVALS, CONFIG_DATA)
+
+    updates = bundle.to_config_updates()
+
+    assert updates["tools"]["read_file"] == {
+        "permission": "always",
+        "allowlist": [],
+        "denylist": [],
+    }
+    assert "disabled_tools" not in updates
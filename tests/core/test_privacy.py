@@ -0,0 +1,63 @@
+from __future__ import annotations
+
+from pathlib import Path
+
+import pytest
+
+from tests.conftest import build_test_rune_config
+from rune.cli.privacy import run_privacy_command
+from rune.core.config import AutoTitleConfig, OtelConfig, RuneConfig
+from rune.core.privacy import (
+    PRIVACY_NOTICE_VERSION,
+    PrivacyError,
+    category_status,
+    needs_consent,
+    toggle_update,
+)
+
+
+def test_status_reflects_the_config() -> None:
+    config = build_test_rune_config(
+        enable_update_checks=True,
+        auto_title=AutoTitleConfig(enabled=False),
+        otel=OtelConfig(logs_endpoint="https://collector:4318/v1/logs"),
+    )
+
+    statuses = {status.name: status for status in category_status(config)}
+
+    assert statuses["update_checks"].enabled is True
+    assert statuses["session_titles"].enabled is False
+    assert statuses["approval_audit"].enabled is True
+    assert statuses["approval_audit"].destination == "https://collector:4318/v1/logs"
+    assert statuses["org_defaults"].enabled is False
+
+
+def test_toggles_write_nested_keys() -> None:
+    assert toggle_update("session_logs", False) == {
+        "session_logging": {"enabled": False}
+    }
+    assert toggle_update("update_checks", True) == {"enable_update_checks": True}
+
+
+@pytest.mark.parametrize("name", ["approval_audit", "analytics"])
+def test_unknown_or_endpoint_categories_cannot_be_toggled(name: str) -> None:
+    with pytest.raises(PrivacyError):
+        toggle_update(name, True)
+
+
+def test_notice_is_shown_until_its_version_is_accepted() -> None:
+    assert needs_consent(build_test_rune_config()) is True
+    assert (
+        needs_consent(
+            build_test_rune_config(privacy_notice_version=PRIVACY_NOTICE_VERSION)
+        )
+        is False
+    )
+
+
+def test_toggles_persist_to_the_config_file(config_dir: Path) -> None:
+    (config_dir / "config.toml").write_text("")
+
+    run_privacy_command(["privacy", "enable", "session_titles"])
+
+    assert RuneConfig.load().auto_title.enabled is True
@@ -0,0 +1,91 @@
+from __future__ import annotations
+
+from pathlib import Path
+
+import pytest
+
+from tests.conftest import build_test_agent_loop, build_test_rune_config
+from tests.mock.utils import mock_llm_chunk
+from tests.stubs.fake_backend import FakeBackend
+from rune.core.project_docs import (
+    discover_project_docs,
+    load_project_docs,
+    render_project_docs,
+)
+from rune.core.trusted_folders import trusted_folders_manager
+from rune.core.types import ProjectDocsLoadedEvent
+
+
+@pytest.fixture
+def repo(tmp_path: Path, monkeypatch: pytest.MonkeyPatch) -> Path:
+    (tmp_path / ".git").mkdir()
+    (tmp_path / "AGENTS.md").write_text("root rules", encoding="utf-8")
+    package = tmp_path / "pkg"
+    (package / ".rune").mkdir(parents=True)
+    (package / "AGENTS.md").write_text("package rules", encoding="utf-8")
+    (package / ".rune" / "instructions.md").write_text("local", encoding="utf-8")
+    monkeypatch.setattr(trusted_folders_manager, "is_trusted", lambda _: True)
+    return tmp_path
+
+
+def test_discovers_files_from_the_repository_root_down(repo: Path) -> None:
+    (repo.parent / "AGENTS.md").write_text("outside", encoding="utf-8")
+
+    paths = discover_project_docs(repo / "pkg")
+
+    assert paths == [
+        repo / "AGENTS.md",
+        repo / "pkg" / "AGENTS.md",
+        repo / "pkg" / ".rune" / "instructions.md",
+    ]
+
+
+def test_total_limit_cuts_the_farthest_files_first(repo: Path) -> None:
+    docs = load_project_docs(repo / "pkg", max_doc_bytes=100, max_total_bytes=20)
+
+    assert [(d.path.name, d.content, d.truncated) for d in docs] == [
+        ("AGENTS.md", "ro", True),
+        ("AGENTS.md", "package rules", False),
+        ("instructions.md", "local", False),
+    ]
+
+
+def test_untrusted_folders_load_nothing(
+    repo: Path, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    monkeypatch.setattr(trusted_folders_manager, "is_trusted", lambda _: None)
+
+    assert load_project_docs(repo / "pkg", 100, 100) == []
+
+
+def test_render_labels_files_by_their_repository_path(repo: Path) -> None:
+    docs = load_project_docs(repo / "pkg", 100, 100)
+
+    rendered = render_project_docs(docs, repo / "pkg")
+
+    assert rendered.index("## AGENTS.md\n\nroot rules") < rendered.index(
+        "## pkg/AGENTS.md\n\npackage rules"
+    )
+
+
+@pytest.mark.asyncio
+async def test_new_thread_reports_the_loaded_files(
+    repo: Path, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    monkeypatch.chdir(repo / "pkg")
+    backend = FakeBackend([[mock_llm_chunk(content="one")], [mock_llm_chunk()]])
+    agent = build_test_agent_loop(
+        config=build_test_rune_config(include_project_context=True), backend=backend
+    )
+
+    events = [event async for event in agent.act("Hello")]
+    later = [event async for event in agent.act("Again")]
+
+    loaded = [e for e in events if isinstance(e, ProjectDocsLoadedEvent)]
+    assert [Path(p).name for p in loaded[0].paths] == [
+        "AGENTS.md",
+        "AGENTS.md",
+        "instructions.md",
+    ]
+    assert "package rules" in (agent.messages[0].content or "")
+    assert not any(isinstance(e, ProjectDocsLoadedEvent) for e in later)
@@ -0,0 +1,107 @@
+from __future__ import annotations
+
+import pytest
+
+from tests.conftest import build_test_agent_loop, build_test_rune_config
+from tests.mock.utils import mock_llm_chunk
+from tests.stubs.fake_backend import FakeBackend
+from rune.core.config import ModelConfig, RouterConfig, RuneConfig
+from rune.core.router import ModelRouter
+from rune.core.types import AgentStats, LLMMessage, ModelRoutedEvent, Role
+from rune.core.utils import TOOL_ERROR_TAG
+
+
+def make_config(**router_kwargs) -> RuneConfig:
+    return build_test_rune_config(
+        models=[
+            ModelConfig(
+                name="rune-cli-cli-latest", provider="rune", alias="devstral-latest"
+            ),
+            ModelConfig(name="rune-mini", provider="rune", alias="mini"),
+        ],
+        router=RouterConfig(enabled=True, light_model="mini", **router_kwargs),
+        system_prompt_id="tests",
+        include_project_context=False,
+        include_prompt_detail=False,
+    )
+
+
+def route(config: RuneConfig, messages: list[LLMMessage], **kwargs) -> str | None:
+    routed = ModelRouter(lambda: config).route(messages, AgentStats(), **kwargs)
+    return routed[1].model if routed else None
+
+
+def test_disabled_router_does_not_route() -> None:
+    config = build_test_rune_config(router=RouterConfig(enabled=False))
+
+    assert route(config, [LLMMessage(role=Role.user, content="hi")]) is None
+
+
+def test_short_question_goes_to_light_model() -> None:
+    messages = [LLMMessage(role=Role.user, content="what does HEAD~1 mean?")]
+
+    assert route(make_config(), messages) == "mini"
+
+
+def test_code_escalates_to_heavy_model() -> None:
+    messages = [LLMMessage(role=Role.user, content="fix this\n```\nx = 1\n```")]
+
+    assert route(make_config(), messages) == "devstral-latest"
+
+
+def test_long_prompt_escalates_to_heavy_model() -> None:
+    messages = [LLMMessage(role=Role.user, content="a" * 50)]
+
+    assert route(make_config(max_light_prompt_chars=10), messages) == (
+        "devstral-latest"
+    )
+
+
+def test_unknown_router_model_falls_back_to_active_model() -> None:
+    messages = [LLMMessage(role=Role.user, content="what does HEAD~1 mean?")]
+
+    config = make_config()
+    config.router.light_model = "missing"
+
+    assert route(config, messages) == "devstral-latest"
+
+
+def test_failed_tool_calls_escalate() -> None:
+    messages = [
+        LLMMessage(role=Role.user, content="rename it"),
+        LLMMessage(
+            role=Role.tool,
+            tool_call_id="call_1",
+            content=f"<{TOOL_ERROR_TAG}>bash failed</{TOOL_ERROR_TAG}>",
+        ),
+    ]
+
+    assert route(make_config(), messages) == "devstral-latest"
+
+
+def test_low_budget_forces_light_model() -> None:
+    config = make_config()
+    stats = AgentStats(session_prompt_tokens=1_000_000, input_price_per_million=0.9)
+    messages = [LLMMessage(role=Role.user, content="```\ncode\n```")]
+
+    routed = ModelRouter(lambda: config).route(messages, stats, max_price=1.0)
+
+    assert routed is not None
+    assert routed[1].model == "mini"
+
+
+@pytest.mark.asyncio
+async def test_agent_loop_emits_routing_event_once_per_model() -> None:
+    agent_loop = build_test_agent_loop(
+        config=make_config(),
+        backend=FakeBackend([
+            [mock_llm_chunk(content="first")],
+            [mock_llm_chunk(content="second")],
+        ]),
+    )
+
+    events = [ev async for ev in agent_loop.act("hello")]
+    events += [ev async for ev in agent_loop.act("hello again")]
+
+    routed = [ev for ev in events if isinstance(ev, ModelRoutedEvent)]
+    assert [ev.model for ev in routed] == ["mini"]
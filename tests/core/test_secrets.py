@@ -0,0 +1,128 @@
+from __future__ import annotations
+
+import base64
+import os
+from pathlib import Path
+
+import keyring
+from pydantic import ValidationError
+import pytest
+
+from tests.conftest import build_test_rune_config
+from rune.core.config import ModelConfig, ProviderConfig, RuneConfig
+from rune.core.secrets import (
+    REDACTED,
+    SECRETS_KEY_ENV,
+    SecretsError,
+    SecretsStore,
+    resolve_secret_env,
+    scrub_secrets_in,
+    write_keychain,
+)
+from rune.core.tools.exec_env import ExecEnvPolicy
+
+OPENAI_API = "https://api.openai.com/v1"
+
+
+def _key() -> str:
+    return base64.b64encode(os.urandom(32)).decode()
+
+
+@pytest.fixture
+def store(tmp_path: Path, monkeypatch: pytest.MonkeyPatch) -> SecretsStore:
+    monkeypatch.setenv(SECRETS_KEY_ENV, _key())
+    return SecretsStore(tmp_path / "secrets.toml")
+
+
+def test_secrets_are_stored_encrypted(store: SecretsStore) -> None:
+    store.set("GITHUB_TOKEN", "ghp_supersecretvalue")
+
+    assert store.get("GITHUB_TOKEN") == "ghp_supersecretvalue"
+    assert store.names() == ["GITHUB_TOKEN"]
+    assert "ghp_supersecretvalue" not in store.path.read_text()
+
+
+def test_another_key_cannot_decrypt(
+    store: SecretsStore, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    store.set("DB_PASSWORD", "hunter22")
+    monkeypatch.setenv(SECRETS_KEY_ENV, _key())
+
+    with pytest.raises(SecretsError, match="wrong key"):
+        store.get("DB_PASSWORD")
+
+
+def test_references_are_resolved_and_scrubbed(store: SecretsStore) -> None:
+    store.set("API_TOKEN", "tok-0123456789")
+
+    env = resolve_secret_env(
+        {"AUTH": "Bearer ${secret:API_TOKEN}", "MODE": "ci"}, store
+    )
+
+    assert env == {"AUTH": "Bearer tok-0123456789", "MODE": "ci"}
+    assert scrub_secrets_in({"content": ["echo tok-0123456789"]}) == {
+        "content": [f"echo {REDACTED}"]
+    }
+
+
+def test_unknown_reference_fails(store: SecretsStore) -> None:
+    with pytest.raises(SecretsError, match="Unknown secret 'MISSING'"):
+        resolve_secret_env({"TOKEN": "${secret:MISSING}"}, store)
+
+
+def test_tool_environment_overrides_resolve_secrets(
+    store: SecretsStore, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    store.set("NPM_TOKEN", "npm_abcdefgh")
+    monkeypatch.setattr(
+        "rune.core.secrets.SecretsStore", lambda: SecretsStore(store.path)
+    )
+
+    env = ExecEnvPolicy(overrides={"NPM_TOKEN": "${secret:NPM_TOKEN}"}).apply({})
+
+    assert env == {"NPM_TOKEN": "npm_abcdefgh"}
+
+
+@pytest.fixture
+def keychain(monkeypatch: pytest.MonkeyPatch) -> dict[tuple[str, str], str]:
+    entries: dict[tuple[str, str], str] = {}
+    monkeypatch.setattr(
+        keyring, "get_password", lambda service, name: entries.get((service, name))
+    )
+    monkeypatch.setattr(
+        keyring,
+        "set_password",
+        lambda service, name, value: entries.__setitem__((service, name), value),
+    )
+    return entries
+
+
+def _keychain_config(**provider: str) -> RuneConfig:
+    return build_test_rune_config(
+        providers=[ProviderConfig(name="openai", api_base=OPENAI_API, **provider)],
+        models=[ModelConfig(name="gpt", provider="openai", alias="gpt")],
+        active_model="gpt",
+    )
+
+
+@pytest.mark.usefixtures("keychain")
+def test_provider_keys_are_read_from_the_keychain() -> None:
+    write_keychain("openai", "sk-from-the-keychain")
+
+    config = _keychain_config(api_key="keychain:openai")
+
+    provider = config.get_provider_for_model(config.get_active_model())
+    assert provider.get_api_key() == "sk-from-the-keychain"
+    assert "sk-from-the-keychain" not in config.model_dump_json()
+    assert scrub_secrets_in("key sk-from-the-keychain") == f"key {REDACTED}"
+
+
+@pytest.mark.usefixtures("keychain")
+def test_missing_keychain_entries_fail_at_load() -> None:
+    with pytest.raises(ValidationError, match="rune secrets set openai --keychain"):
+        _keychain_config(api_key="keychain:openai")
+
+
+def test_plain_api_keys_are_refused() -> None:
+    with pytest.raises(ValidationError, match="keychain:NAME"):
+        ProviderConfig(name="openai", api_base=OPENAI_API, api_key="sk-plain")
@@ -0,0 +1,136 @@
+from __future__ import annotations
+
+import asyncio
+
+import pytest
+
+from tests.conftest import build_test_agent_loop, build_test_rune_config
+from tests.mock.utils import mock_llm_chunk
+from tests.stubs.fake_backend import FakeBackend
+from rune.core.config import ModelConfig, ProviderConfig, RuneConfig
+from rune.core.llm.exceptions import fallback_reason
+from rune.core.llm.stream_watchdog import StreamStalledError, watch_idle
+from rune.core.types import (
+    AssistantEvent,
+    LLMChunk,
+    LLMMessage,
+    Role,
+    StreamStalledEvent,
+)
+
+IDLE_TIMEOUT = 0.05
+
+
+class StallingBackend(FakeBackend):
+    """Streams the given attempts in turn; attempts marked as stalling go silent
+    after their chunks instead of finishing.
+    """
+
+    def __init__(self, attempts: list[tuple[list[LLMChunk], bool]]) -> None:
+        super().__init__()
+        self.attempts = list(attempts)
+        self.requests: list[list[LLMMessage]] = []
+
+    async def complete_streaming(self, *, messages, **kwargs):
+        self.requests.append(list(messages))
+        chunks, stalls = self.attempts.pop(0)
+        for chunk in chunks:
+            yield chunk
+        if stalls:
+            await asyncio.sleep(60)
+
+
+def text_chunk(content: str, *, final: bool = False) -> LLMChunk:
+    if final:
+        return mock_llm_chunk(content=content)
+    return LLMChunk(message=LLMMessage(role=Role.assistant, content=content))
+
+
+def make_config(*, api_style: str = "openai", retries: int = 2) -> RuneConfig:
+    return build_test_rune_config(
+        providers=[
+            ProviderConfig(
+                name="remote", api_base="https://example.invalid", api_style=api_style
+            )
+        ],
+        models=[ModelConfig(name="remote-model", provider="remote", alias="remote")],
+        active_model="remote",
+        stream_idle_timeout=IDLE_TIMEOUT,
+        stream_stall_retries=retries,
+        system_prompt_id="tests",
+        include_project_context=False,
+        include_prompt_detail=False,
+    )
+
+
+@pytest.mark.asyncio
+async def test_watch_idle_raises_when_no_item_arrives() -> None:
+    async def stream():
+        yield 1
+        await asyncio.sleep(60)
+        yield 2
+
+    received: list[int] = []
+    with pytest.raises(StreamStalledError):
+        async for item in watch_idle(stream(), IDLE_TIMEOUT):
+            received.append(item)
+
+    assert received == [1]
+    assert fallback_reason(StreamStalledError(IDLE_TIMEOUT)) == "stream stalled"
+
+
+@pytest.mark.asyncio
+async def test_stalled_stream_is_restarted_when_it_cannot_be_resumed() -> None:
+    backend = StallingBackend([
+        ([text_chunk("Partial ans")], True),
+        ([text_chunk("Full answer", final=True)], False),
+    ])
+    agent_loop = build_test_agent_loop(
+        config=make_config(), backend=backend, enable_streaming=True
+    )
+
+    events = [ev async for ev in agent_loop.act("hi")]
+
+    stalls = [ev for ev in events if isinstance(ev, StreamStalledEvent)]
+    assert [(ev.attempt, ev.resumed, ev.discarded_output) for ev in stalls] == [
+        (1, False, True)
+    ]
+    assert len(backend.requests) == 2
+    assert backend.requests[1] == backend.requests[0]
+    assert agent_loop.messages[-1].content == "Full answer"
+    assert isinstance(events[-1], AssistantEvent)
+
+
+@pytest.mark.asyncio
+async def test_stalled_stream_is_resumed_from_the_partial_reply() -> None:
+    backend = StallingBackend([
+        ([text_chunk("The answer ")], True),
+        ([text_chunk(" is 42.", final=True)], False),
+    ])
+    agent_loop = build_test_agent_loop(
+        config=make_config(api_style="anthropic"),
+        backend=backend,
+        enable_streaming=True,
+    )
+
+    events = [ev async for ev in agent_loop.act("hi")]
+
+    stalls = [ev for ev in events if isinstance(ev, StreamStalledEvent)]
+    assert [(ev.resumed, ev.discarded_output) for ev in stalls] == [(True, False)]
+    prefill = backend.requests[1][-1]
+    assert (prefill.role, prefill.content) == (Role.assistant, "The answer")
+    assert agent_loop.messages[-1].content == "The answer is 42."
+
+
+@pytest.mark.asyncio
+async def test_turn_fails_once_reconnections_are_exhausted() -> None:
+    backend = StallingBackend([([], True), ([], True)])
+    agent_loop = build_test_agent_loop(
+        config=make_config(retries=1), backend=backend, enable_streaming=True
+    )
+
+    with pytest.raises(RuntimeError) as exc_info:
+        [ev async for ev in agent_loop.act("hi")]
+
+    assert isinstance(exc_info.value.__cause__, StreamStalledError)
+    assert len(backend.requests) == 2
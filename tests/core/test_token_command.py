@@ -0,0 +1,143 @@
+from __future__ import annotations
+
+from collections.abc import Iterator
+import os
+from pathlib import Path
+import shlex
+import sys
+
+import httpx
+import pytest
+
+from rune.core.auth import token_command
+from rune.core.auth.token_command import CommandTokenAuth, TokenCommandError
+from rune.core.config import ProviderConfig
+from rune.core.llm.backend.transport import client_options
+
+
+@pytest.fixture(autouse=True)
+def empty_cache() -> Iterator[None]:
+    token_command._cache.clear()
+    yield
+    token_command._cache.clear()
+
+
+@pytest.fixture
+def command(tmp_path: Path) -> str:
+    """Prints tok-1, tok-2, ... on successive runs."""
+    counter = tmp_path / "runs"
+    helper = tmp_path / "sso.py"
+    helper.write_text(
+        "import pathlib\n"
+        f"path = pathlib.Path({str(counter)!r})\n"
+        "runs = int(path.read_text()) + 1 if path.exists() else 1\n"
+        "path.write_text(str(runs))\n"
+        "print(f'tok-{runs}')\n",
+        encoding="utf-8",
+    )
+    return f"{shlex.quote(sys.executable)} {shlex.quote(str(helper))}"
+
+
+def _server(valid: set[str], seen: list[str]) -> httpx.MockTransport:
+    def handler(request: httpx.Request) -> httpx.Response:
+        seen.append(request.headers["Authorization"])
+        token = request.headers["Authorization"].removeprefix("Bearer ")
+        return httpx.Response(200 if token in valid else 401)
+
+    return httpx.MockTransport(handler)
+
+
+@pytest.mark.asyncio
+async def test_token_is_cached_between_requests(command: str) -> None:
+    seen: list[str] = []
+    auth = CommandTokenAuth(command, ttl=300)
+    async with httpx.AsyncClient(
+        auth=auth, transport=_server({"tok-1"}, seen)
+    ) as client:
+        await client.get("https://llm.example/v1/models")
+        await client.get("https://llm.example/v1/models")
+
+    assert seen == ["Bearer tok-1", "Bearer tok-1"]
+
+
+@pytest.mark.asyncio
+async def test_401_fetches_a_new_token_and_retries_once(command: str) -> None:
+    seen: list[str] = []
+    auth = CommandTokenAuth(command, ttl=300)
+    async with httpx.AsyncClient(
+        auth=auth, transport=_server({"tok-2"}, seen)
+    ) as client:
+        response = await client.get("https://llm.example/v1/models")
+
+    assert response.status_code == 200
+    assert seen == ["Bearer tok-1", "Bearer tok-2"]
+
+
+@pytest.mark.asyncio
+async def test_expired_token_is_fetched_again(command: str) -> None:
+    seen: list[str] = []
+    auth = CommandTokenAuth(command, ttl=300)
+    async with httpx.AsyncClient(
+        auth=auth, transport=_server({"tok-1", "tok-2"}, seen)
+    ) as client:
+        await client.get("https://llm.example/v1/models")
+        auth.ttl = 0.0
+        await client.get("https://llm.example/v1/models")
+
+    assert seen == ["Bearer tok-1", "Bearer tok-2"]
+
+
+def test_sync_clients_use_the_command_too(command: str) -> None:
+    seen: list[str] = []
+    auth = CommandTokenAuth(command, ttl=300)
+    with httpx.Client(auth=auth, transport=_server({"tok-1"}, seen)) as client:
+        response = client.get("https://llm.example/v1/models")
+
+    assert response.status_code == 200
+    assert seen == ["Bearer tok-1"]
+
+
+def test_failing_command_raises(tmp_path: Path) -> None:
+    helper = tmp_path / "broken.py"
+    helper.write_text("import sys\nsys.exit('not logged in')\n", encoding="utf-8")
+
+    with pytest.raises(TokenCommandError, match="not logged in"):
+        token_command.run_token_command(
+            f"{shlex.quote(sys.executable)} {shlex.quote(str(helper))}"
+        )
+
+
+@pytest.mark.asyncio
+async def test_commands_that_hang_are_killed(
+    tmp_path: Path, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    pid_file = tmp_path / "pid"
+    helper = tmp_path / "hang.py"
+    helper.write_text(
+        "import os, pathlib, time\n"
+        f"pathlib.Path({str(pid_file)!r}).write_text(str(os.getpid()))\n"
+        "time.sleep(60)\n",
+        encoding="utf-8",
+    )
+    monkeypatch.setattr(token_command, "TOKEN_COMMAND_TIMEOUT_SECONDS", 1.0)
+
+    with pytest.raises(TokenCommandError, match="timed out"):
+        await token_command.run_token_command_async(
+            f"{shlex.quote(sys.executable)} {shlex.quote(str(helper))}"
+        )
+
+    with pytest.raises(ProcessLookupError):
+        os.kill(int(pid_file.read_text()), 0)
+
+
+def test_client_options_authenticate_with_the_command(command: str) -> None:
+    provider = ProviderConfig(
+        name="corp",
+        api_base="https://llm.example/v1",
+        bearer_token_command=command,
+    )
+
+    auth = client_options(provider, timeout=30.0)["auth"]
+
+    assert isinstance(auth, CommandTokenAuth)
+    assert auth.command == command
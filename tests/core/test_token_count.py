@@ -0,0 +1,70 @@
+from __future__ import annotations
+
+import asyncio
+
+import pytest
+
+from tests.conftest import build_test_agent_loop, build_test_rune_config
+from tests.mock.utils import mock_llm_chunk
+from tests.stubs.fake_backend import FakeBackend
+from rune.core.config import ModelConfig, ProviderConfig
+from rune.core.types import AssistantEvent, LLMChunk, LLMMessage, Role, TokenCountEvent
+
+INTERVAL = 0.01
+CONTEXT_WINDOW = 1000
+
+
+class SlowBackend(FakeBackend):
+    async def complete_streaming(self, **kwargs):
+        for content in ["abcd" * 5, "efgh" * 5]:
+            await asyncio.sleep(INTERVAL * 2)
+            yield LLMChunk(message=LLMMessage(role=Role.assistant, content=content))
+        yield mock_llm_chunk(content="", prompt_tokens=100, completion_tokens=10)
+
+
+def make_loop(interval: float = INTERVAL):
+    config = build_test_rune_config(
+        providers=[ProviderConfig(name="local", api_base="http://127.0.0.1:1")],
+        models=[
+            ModelConfig(
+                name="local-model",
+                provider="local",
+                alias="local",
+                context_window=CONTEXT_WINDOW,
+            )
+        ],
+        active_model="local",
+        token_count_interval=interval,
+        system_prompt_id="tests",
+        include_project_context=False,
+        include_prompt_detail=False,
+    )
+    return build_test_agent_loop(
+        config=config, backend=SlowBackend(), enable_streaming=True
+    )
+
+
+@pytest.mark.asyncio
+async def test_token_counts_are_estimated_while_the_reply_streams() -> None:
+    agent_loop = make_loop()
+
+    events = [ev async for ev in agent_loop.act("hi")]
+
+    counts = [ev for ev in events if isinstance(ev, TokenCountEvent)]
+    assert [ev.completion_tokens for ev in counts] == [5, 10]
+    assert all(ev.estimated for ev in counts)
+    assert counts[0].prompt_tokens > 0
+    assert counts[-1].context_utilization == pytest.approx(
+        counts[-1].context_tokens / CONTEXT_WINDOW
+    )
+    assert isinstance(events[-1], AssistantEvent)
+    assert agent_loop.stats.context_tokens == 110
+
+
+@pytest.mark.asyncio
+async def test_token_counts_can_be_turned_off() -> None:
+    agent_loop = make_loop(interval=0)
+
+    events = [ev async for ev in agent_loop.act("hi")]
+
+    assert not any(isinstance(ev, TokenCountEvent) for ev in events)
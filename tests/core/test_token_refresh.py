@@ -0,0 +1,84 @@
+from __future__ import annotations
+
+from pathlib import Path
+import time
+
+import httpx
+import pytest
+
+from rune.core.auth import token_refresh
+from rune.core.auth.accounts import AccountStore
+from rune.core.auth.device_flow import OAuthToken
+from rune.core.auth.token_refresh import refresh_active_account, refresh_oauth_token
+from rune.core.config import OAuthConfig, ProviderConfig
+
+OAUTH = OAuthConfig(
+    client_id="rune-cli",
+    device_authorization_url="https://sso.example/device",
+    token_url="https://sso.example/token",
+)
+PROVIDER = ProviderConfig(name="corp", api_base="https://llm.example/v1", oauth=OAUTH)
+
+
+@pytest.fixture
+def store(config_dir: Path) -> AccountStore:
+    return AccountStore(config_dir / "auth.json")
+
+
+@pytest.fixture
+def refreshes(monkeypatch: pytest.MonkeyPatch) -> list[str]:
+    calls: list[str] = []
+
+    async def refresh(
+        oauth: OAuthConfig, refresh_token: str, *, client: httpx.AsyncClient
+    ) -> OAuthToken:
+        calls.append(refresh_token)
+        return OAuthToken(access_token="at-2", expires_at=time.time() + 3600)
+
+    monkeypatch.setattr(token_refresh, "refresh_oauth_token", refresh)
+    return calls
+
+
+@pytest.mark.asyncio
+async def test_expiring_tokens_are_refreshed(
+    store: AccountStore, refreshes: list[str]
+) -> None:
+    store.add("work", "corp", "at-1", refresh_token="rt-1", expires_at=time.time())
+
+    assert await refresh_active_account(PROVIDER, store) == "at-2"
+
+    account = store.active_account("corp")
+    assert refreshes == ["rt-1"]
+    assert account is not None
+    assert (account.api_key, account.refresh_token) == ("at-2", "rt-1")
+    assert not account.expires_within(60)
+
+
+@pytest.mark.asyncio
+async def test_each_provider_refreshes_its_own_account(
+    store: AccountStore, refreshes: list[str]
+) -> None:
+    later = time.time() + 3600
+    store.add("work", "corp", "at-1", refresh_token="rt-1", expires_at=later)
+    store.add("other", "other", "at-o", refresh_token="rt-o", expires_at=0)
+
+    assert await refresh_active_account(PROVIDER, store) == "at-1"
+
+    assert refreshes == []
+    assert store.active_api_key("corp") == "at-1"
+
+
+@pytest.mark.asyncio
+async def test_refresh_requests_use_the_refresh_token_grant() -> None:
+    forms: list[str] = []
+
+    def handler(request: httpx.Request) -> httpx.Response:
+        forms.append(request.content.decode())
+        return httpx.Response(200, json={"access_token": "at-2", "expires_in": 60})
+
+    async with httpx.AsyncClient(transport=httpx.MockTransport(handler)) as client:
+        token = await refresh_oauth_token(OAUTH, "rt-1", client=client)
+
+    assert token.access_token == "at-2"
+    assert "grant_type=refresh_token" in forms[0]
+    assert "refresh_token=rt-1" in forms[0]
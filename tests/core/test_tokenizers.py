@@ -0,0 +1,81 @@
+from __future__ import annotations
+
+import pytest
+
+from tests.conftest import build_test_agent_loop, build_test_rune_config
+from tests.stubs.fake_backend import FakeBackend
+from rune.core.config import ModelConfig, ProviderConfig
+from rune.core.tokenizers import (
+    ByteHeuristicTokenizer,
+    count_message_tokens,
+    load_tokenizer,
+)
+from rune.core.types import FunctionCall, LLMMessage, Role, ToolCall
+
+
+class WordTokenizer:
+    name = "words"
+
+    def count(self, text: str) -> int:
+        return len(text.split())
+
+
+def test_byte_heuristic_counts_utf8_bytes() -> None:
+    tokenizer = ByteHeuristicTokenizer()
+
+    assert tokenizer.count("abcd" * 5) == 5
+    # Three bytes per character: CJK text is not underestimated.
+    assert tokenizer.count("日本語の") == 3
+
+
+@pytest.mark.parametrize("spec", [None, "bytes", "sentencepiece:llama", "hf:"])
+def test_unset_or_unknown_tokenizers_fall_back_to_bytes(spec: str | None) -> None:
+    assert isinstance(load_tokenizer(spec), ByteHeuristicTokenizer)
+
+
+def test_messages_count_content_reasoning_and_tool_arguments() -> None:
+    messages = [
+        LLMMessage(role=Role.user, content="list the files"),
+        LLMMessage(
+            role=Role.assistant,
+            reasoning_content="use ls",
+            tool_calls=[
+                ToolCall(
+                    id="1", function=FunctionCall(name="bash", arguments='{"a": 1}')
+                )
+            ],
+        ),
+    ]
+
+    assert count_message_tokens(WordTokenizer(), messages) == 3 + 2 + 2
+
+
+@pytest.mark.asyncio
+async def test_agent_loop_estimates_with_the_model_tokenizer_once_loaded(
+    monkeypatch: pytest.MonkeyPatch,
+) -> None:
+    config = build_test_rune_config(
+        providers=[ProviderConfig(name="local", api_base="http://127.0.0.1:1")],
+        models=[
+            ModelConfig(
+                name="qwen", provider="local", alias="qwen", tokenizer="hf:Qwen/Qwen3"
+            )
+        ],
+        active_model="qwen",
+    )
+    requested: list[str | None] = []
+
+    def fake_load(spec: str | None) -> WordTokenizer:
+        requested.append(spec)
+        return WordTokenizer()
+
+    monkeypatch.setattr("rune.core.agent_loop.load_tokenizer", fake_load)
+    agent_loop = build_test_agent_loop(config=config, backend=FakeBackend())
+
+    messages = [LLMMessage(role=Role.user, content="one two three")]
+
+    assert agent_loop._estimate_tokens(messages) == 4
+    await agent_loop._load_tokenizer()
+    await agent_loop._load_tokenizer()
+    assert agent_loop._estimate_tokens(messages) == 3
+    assert requested == ["hf:Qwen/Qwen3"]
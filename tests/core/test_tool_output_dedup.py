@@ -0,0 +1,64 @@
+from __future__ import annotations
+
+import json
+
+from rune.core.compaction import MIN_SUPERSEDED_LENGTH, supersede_tool_outputs
+from rune.core.types import FunctionCall, LLMMessage, Role, ToolCall
+
+LONG_OUTPUT = "x" * MIN_SUPERSEDED_LENGTH
+
+
+def _call(call_id: str, name: str, **args: object) -> list[LLMMessage]:
+    return [
+        LLMMessage(
+            role=Role.assistant,
+            tool_calls=[
+                ToolCall(
+                    id=call_id,
+                    function=FunctionCall(name=name, arguments=json.dumps(args)),
+                )
+            ],
+        ),
+        LLMMessage(
+            role=Role.tool, tool_call_id=call_id, name=name, content=LONG_OUTPUT
+        ),
+    ]
+
+
+def test_earlier_outputs_of_the_same_call_are_superseded() -> None:
+    history = [
+        LLMMessage(role=Role.user, content="refactor"),
+        *_call("a", "read_file", path="main.py"),
+        *_call("b", "read_file", path="other.py"),
+        *_call("c", "read_file", path="main.py"),
+    ]
+
+    assert supersede_tool_outputs(history, "c") == 1
+
+    outputs = {m.tool_call_id: m.content for m in history if m.role == Role.tool}
+    assert "tool call c" in outputs["a"]
+    assert outputs["b"] == LONG_OUTPUT
+    assert outputs["c"] == LONG_OUTPUT
+
+
+def test_argument_order_does_not_matter() -> None:
+    history = [
+        *_call("a", "bash", command="ls", timeout=5),
+        *_call("b", "bash", timeout=5, command="ls"),
+    ]
+
+    assert supersede_tool_outputs(history, "b") == 1
+
+
+def test_different_arguments_and_short_outputs_are_kept() -> None:
+    history = [
+        *_call("a", "bash", command="ls"),
+        *_call("b", "bash", command="ls -la"),
+        *_call("c", "bash", command="pwd"),
+        *_call("d", "bash", command="pwd"),
+    ]
+    history[5].content = "/repo"
+
+    assert supersede_tool_outputs(history, "b") == 0
+    assert supersede_tool_outputs(history, "d") == 0
+    assert history[5].content == "/repo"
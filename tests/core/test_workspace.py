@@ -0,0 +1,88 @@
+from __future__ import annotations
+
+from pathlib import Path
+
+from pydantic import BaseModel
+import pytest
+
+from rune.core.workspace import Workspace
+
+
+class _Args(BaseModel):
+    path: str
+    pattern: str = ""
+
+
+@pytest.fixture
+def workspace(tmp_path: Path, monkeypatch: pytest.MonkeyPatch) -> Workspace:
+    for name in ("app", "lib"):
+        (tmp_path / name).mkdir()
+    monkeypatch.chdir(tmp_path / "app")
+    workspace = Workspace(Path.cwd())
+    workspace.add(tmp_path / "lib")
+    return workspace
+
+
+def test_roots_are_named_after_their_directories(workspace: Workspace) -> None:
+    assert [root.name for root in workspace.roots] == ["app", "lib"]
+    assert workspace.active.name == "app"
+    assert workspace.is_multi_root
+
+
+def test_adding_a_root_twice_returns_the_existing_one(
+    workspace: Workspace, tmp_path: Path
+) -> None:
+    assert workspace.add(tmp_path / "lib", "other").name == "lib"
+    assert len(workspace.roots) == 2  # noqa: PLR2004
+
+
+@pytest.mark.parametrize("name", ["x", "1lib", "has space"])
+def test_invalid_root_names_are_rejected(
+    workspace: Workspace, tmp_path: Path, name: str
+) -> None:
+    (tmp_path / "extra").mkdir()
+    with pytest.raises(ValueError, match="Invalid root name"):
+        workspace.add(tmp_path / "extra", name)
+
+
+@pytest.mark.parametrize(
+    ("raw", "expected"),
+    [
+        ("lib:src/util.py", "lib/src/util.py"),
+        ("lib:/README.md", "lib/README.md"),
+        ("src/main.py", None),
+        ("unknown:src/main.py", None),
+        ("C:\\Users\\me", None),
+    ],
+)
+def test_resolve(
+    workspace: Workspace, tmp_path: Path, raw: str, expected: str | None
+) -> None:
+    resolved = str(tmp_path.resolve() / expected) if expected else raw
+    assert workspace.resolve(raw) == resolved
+
+
+def test_expand_args_only_touches_path_fields(
+    workspace: Workspace, tmp_path: Path
+) -> None:
+    args = _Args(path="lib:a.py", pattern="lib:b")
+
+    expanded = workspace.expand_args(args)
+
+    assert expanded.path == str(tmp_path.resolve() / "lib" / "a.py")
+    assert expanded.pattern == "lib:b"
+    assert args.path == "lib:a.py"
+
+
+def test_use_switches_the_active_root_but_not_the_process_directory(
+    workspace: Workspace, tmp_path: Path
+) -> None:
+    workspace.use("lib")
+
+    assert Path.cwd() == (tmp_path / "app").resolve()
+    assert workspace.active.name == "lib"
+    expanded = workspace.expand_args(_Args(path="src/util.py"))
+    assert expanded.path == str(tmp_path.resolve() / "lib" / "src" / "util.py")
+    assert not workspace.remove("lib")
+    assert workspace.remove("app")
+    assert not workspace.is_multi_root
@@ -0,0 +1,113 @@
+from __future__ import annotations
+
+import json
+from pathlib import Path
+import subprocess
+import time
+
+import pytest
+
+from tests.conftest import build_test_agent_loop, build_test_rune_config
+from tests.mock.utils import mock_llm_chunk
+from tests.stubs.fake_backend import FakeBackend
+from rune.core import workspace_lease
+from rune.core.types import ConcurrentSessionEvent
+from rune.core.workspace_lease import WorkspaceLease, WorktreeError, create_worktree
+
+
+def test_threads_in_the_same_workspace_are_reported_to_each_other_once(
+    tmp_path: Path,
+) -> None:
+    first = WorkspaceLease(tmp_path, "first", leases_dir=tmp_path / "leases")
+    second = WorkspaceLease(tmp_path, "second", leases_dir=tmp_path / "leases")
+    elsewhere = WorkspaceLease(
+        tmp_path / "leases", "elsewhere", leases_dir=tmp_path / "leases"
+    )
+
+    assert first.refresh("session-1") == []
+    assert elsewhere.refresh("session-3") == []
+    assert [peer.session_id for peer in second.refresh("session-2")] == ["session-1"]
+    assert [peer.session_id for peer in first.refresh("session-1")] == ["session-2"]
+    assert first.refresh("session-1") == []
+
+    second.release()
+    assert not second.path.exists()
+    assert first.peers() == []
+
+
+def test_leases_of_dead_processes_are_removed(
+    tmp_path: Path, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    first = WorkspaceLease(tmp_path, "first", leases_dir=tmp_path)
+    second = WorkspaceLease(tmp_path, "second", leases_dir=tmp_path)
+    first.refresh("session-1")
+
+    monkeypatch.setattr(workspace_lease, "_pid_running", lambda pid: False)
+
+    assert second.refresh("session-2") == []
+    assert not first.path.exists()
+
+
+def test_leases_from_other_hosts_expire_without_heartbeat(tmp_path: Path) -> None:
+    lease = WorkspaceLease(tmp_path, "local", leases_dir=tmp_path)
+    lease.refresh("session-1")
+    remote = {
+        "lease_id": "remote",
+        "session_id": "session-2",
+        "pid": 1,
+        "hostname": "build-box.invalid",
+        "started_at": "2026-01-01T00:00:00+00:00",
+    }
+    (lease.path.parent / "remote.json").write_text(
+        json.dumps({**remote, "heartbeat_at": time.time()})
+    )
+    assert [peer.hostname for peer in lease.peers()] == ["build-box.invalid"]
+
+    stale = time.time() - workspace_lease.REMOTE_LEASE_TTL_SECONDS - 1
+    (lease.path.parent / "remote.json").write_text(
+        json.dumps({**remote, "heartbeat_at": stale})
+    )
+    assert lease.peers() == []
+
+
+def test_create_worktree_checks_out_a_new_branch(tmp_path: Path) -> None:
+    repo = tmp_path / "repo"
+    (repo / "pkg").mkdir(parents=True)
+    (repo / "pkg" / "a.txt").write_text("a")
+    for args in (
+        ["init", "-q"],
+        ["add", "."],
+        ["-c", "user.name=t", "-c", "user.email=t@t", "commit", "-qm", "init"],
+    ):
+        subprocess.run(["git", *args], cwd=repo, check=True)
+
+    path = create_worktree(repo / "pkg", "rune/abc12345")
+
+    assert path == tmp_path.resolve() / "repo-rune-abc12345" / "pkg"
+    assert (path / "a.txt").read_text() == "a"
+    with pytest.raises(WorktreeError):
+        create_worktree(repo, "rune/abc12345")
+
+
+@pytest.mark.asyncio
+async def test_agent_loops_in_the_same_directory_warn_each_other() -> None:
+    def make_loop():
+        return build_test_agent_loop(
+            config=build_test_rune_config(detect_concurrent_sessions=True),
+            backend=FakeBackend([[mock_llm_chunk(content="ok")]] * 2),
+        )
+
+    first, second = make_loop(), make_loop()
+
+    first_events = [ev async for ev in first.act("hi")]
+    second_events = [ev async for ev in second.act("hi")]
+    first_again = [ev async for ev in first.act("still there?")]
+
+    def warnings(events: list) -> list[ConcurrentSessionEvent]:
+        return [ev for ev in events if isinstance(ev, ConcurrentSessionEvent)]
+
+    assert warnings(first_events) == []
+    [warning] = warnings(second_events)
+    assert [peer.session_id for peer in warning.peers] == [first.session_id]
+    [warning] = warnings(first_again)
+    assert [peer.session_id for peer in warning.peers] == [second.session_id]
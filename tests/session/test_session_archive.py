@@ -0,0 +1,77 @@
+from __future__ import annotations
+
+import json
+from pathlib import Path
+
+import pytest
+
+from rune.core.config import SessionLoggingConfig
+from rune.core.session.session_archive import (
+    SessionArchiveError,
+    archive_session,
+    archived_config,
+    refresh_index,
+    unarchive_session,
+)
+from rune.core.session.session_index import SessionIndex
+from rune.core.session.session_loader import SessionLoader
+
+
+@pytest.fixture
+def config(tmp_path: Path) -> SessionLoggingConfig:
+    return SessionLoggingConfig(save_dir=str(tmp_path), session_prefix="test")
+
+
+@pytest.fixture
+def session_dir(config: SessionLoggingConfig) -> Path:
+    session_dir = Path(config.save_dir) / "test_20250101_120000_abcd1234"
+    session_dir.mkdir()
+    (session_dir / "messages.jsonl").write_text(
+        json.dumps({"role": "user", "content": "rotate the billing keys"}) + "\n"
+    )
+    (session_dir / "meta.json").write_text(
+        json.dumps({"session_id": "abcd1234-0000", "title": "Billing keys"})
+    )
+    return session_dir
+
+
+def test_archived_sessions_leave_the_list_and_search(
+    config: SessionLoggingConfig, session_dir: Path
+) -> None:
+    index = SessionIndex(config)
+    assert len(index.search("billing")) == 1
+
+    archived = archive_session(session_dir, config)
+    refresh_index(config)
+
+    assert not session_dir.exists()
+    assert SessionLoader.list_recent_sessions(config) == []
+    assert index.search("billing") == []
+    assert SessionLoader.find_session_by_id("abcd1234", archived_config(config)) == (
+        archived
+    )
+
+
+def test_unarchived_sessions_come_back(
+    config: SessionLoggingConfig, session_dir: Path
+) -> None:
+    archived = archive_session(session_dir, config)
+
+    restored = unarchive_session(archived, config)
+    refresh_index(config)
+
+    assert restored == session_dir
+    assert [path for path, _ in SessionLoader.list_recent_sessions(config)] == [
+        session_dir
+    ]
+    assert len(SessionIndex(config).search("billing")) == 1
+
+
+def test_unarchiving_does_not_overwrite_a_session(
+    config: SessionLoggingConfig, session_dir: Path
+) -> None:
+    archived = archive_session(session_dir, config)
+    session_dir.mkdir()
+
+    with pytest.raises(SessionArchiveError):
+        unarchive_session(archived, config)
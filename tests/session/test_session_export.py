@@ -0,0 +1,91 @@
+from __future__ import annotations
+
+import json
+from pathlib import Path
+
+import pytest
+
+from rune.core.session.session_export import ExportFormat, export_session
+from rune.core.types import FunctionCall, LLMMessage, Role, ToolCall
+
+EDIT = "<<<<<<< SEARCH\nold = 1\n=======\nnew = 2\n>>>>>>> REPLACE"
+
+
+@pytest.fixture
+def session_dir(tmp_path: Path) -> Path:
+    messages = [
+        LLMMessage(role=Role.user, content="Bump the value <now>"),
+        LLMMessage(
+            role=Role.assistant,
+            content="Checking first.",
+            reasoning_content="The value lives in config.py.",
+            tool_calls=[
+                ToolCall(
+                    id="call_1",
+                    function=FunctionCall(
+                        name="bash", arguments='{"command": "cat config.py"}'
+                    ),
+                ),
+                ToolCall(
+                    id="call_2",
+                    function=FunctionCall(
+                        name="search_replace",
+                        arguments=json.dumps(
+                            {"file_path": "config.py", "content": EDIT}
+                        ),
+                    ),
+                ),
+            ],
+        ),
+        LLMMessage(
+            role=Role.tool,
+            name="bash",
+            tool_call_id="call_1",
+            content="token = 'sk-abcdefghijklmnopqrstuvwx'",
+        ),
+        LLMMessage(role=Role.assistant, content="Done."),
+    ]
+    (tmp_path / "messages.jsonl").write_text(
+        "".join(m.model_dump_json(exclude_none=True) + "\n" for m in messages)
+    )
+    (tmp_path / "meta.json").write_text(
+        json.dumps({
+            "session_id": "abc12345-0000",
+            "title": "Bump value",
+            "start_time": "2025-01-01T12:00:00+00:00",
+        })
+    )
+    return tmp_path
+
+
+def test_markdown_transcript(session_dir: Path) -> None:
+    text = export_session(session_dir, ExportFormat.MARKDOWN)
+
+    assert text.startswith("# Bump value\n\n_Session abc12345 · 2025-01-01 12:00_")
+    assert "## You\n\nBump the value <now>" in text
+    assert "<summary>Reasoning</summary>" in text
+    assert "**Command**\n\n```bash\ncat config.py\n```" in text
+    assert "**Edit config.py**\n\n```diff\n@@ -1 +1 @@\n-old = 1\n+new = 2\n```" in text
+    assert "sk-abcdefghijklmnopqrstuvwx" not in text
+    assert text.index("Checking first.") < text.index("Done.")
+
+
+def test_html_transcript_escapes_content(session_dir: Path) -> None:
+    text = export_session(session_dir, ExportFormat.HTML, redact=False)
+
+    assert "<title>Bump value</title>" in text
+    assert "Bump the value &lt;now&gt;" in text
+    assert '<span class="add">+new = 2</span>' in text
+    assert "sk-abcdefghijklmnopqrstuvwx" in text
+
+
+def test_json_export_keeps_the_messages(session_dir: Path) -> None:
+    dump = json.loads(export_session(session_dir, ExportFormat.JSON))
+
+    assert dump["metadata"]["title"] == "Bump value"
+    assert [m["role"] for m in dump["messages"]] == [
+        "user",
+        "assistant",
+        "tool",
+        "assistant",
+    ]
@@ -0,0 +1,87 @@
+from __future__ import annotations
+
+import json
+from pathlib import Path
+
+import pytest
+
+from tests.conftest import build_test_agent_loop, build_test_rune_config
+from tests.mock.utils import mock_llm_chunk
+from tests.stubs.fake_backend import FakeBackend
+from rune.core.config import SessionLoggingConfig
+from rune.core.session.session_fork import (
+    SessionForkError,
+    fork_session,
+    history_until_turn,
+)
+from rune.core.session.session_loader import SessionLoader
+from rune.core.types import LLMMessage, Role
+
+MESSAGES = [
+    LLMMessage(role=Role.system, content="System prompt"),
+    LLMMessage(role=Role.user, content="first"),
+    LLMMessage(role=Role.assistant, content="one"),
+    LLMMessage(role=Role.user, content="second"),
+    LLMMessage(role=Role.assistant, content="two"),
+]
+
+
+@pytest.fixture
+def config(tmp_path: Path) -> SessionLoggingConfig:
+    return SessionLoggingConfig(save_dir=str(tmp_path), session_prefix="test")
+
+
+def test_history_until_turn_keeps_whole_turns() -> None:
+    assert [m.content for m in history_until_turn(MESSAGES, 1)] == ["first", "one"]
+    assert len(history_until_turn(MESSAGES)) == len(MESSAGES) - 1
+    with pytest.raises(SessionForkError):
+        history_until_turn(MESSAGES, 3)
+
+
+def test_fork_session_logs_a_new_resumable_session(
+    config: SessionLoggingConfig,
+) -> None:
+    source = Path(config.save_dir) / "test_20250101_120000_abcd1234"
+    source.mkdir()
+    (source / "messages.jsonl").write_text(
+        "".join(json.dumps(m.model_dump(exclude_none=True)) + "\n" for m in MESSAGES)
+    )
+    (source / "meta.json").write_text(
+        json.dumps({"session_id": "abcd1234-0000", "title": "Numbers"})
+    )
+
+    session_id, session_dir = fork_session(source, config, at_turn=1)
+
+    messages, metadata = SessionLoader.load_session(session_dir)
+    assert [m.content for m in messages] == ["first", "one"]
+    assert metadata["session_id"] == session_id
+    assert metadata["title"] == "Numbers (fork)"
+    assert (metadata["forked_from"], metadata["forked_at_turn"]) == ("abcd1234-0000", 1)
+    assert SessionLoader.find_session_by_id(session_id, config) == session_dir
+
+
+@pytest.mark.asyncio
+async def test_fork_at_turn_continues_in_a_new_session(
+    config: SessionLoggingConfig,
+) -> None:
+    agent_loop = build_test_agent_loop(
+        config=build_test_rune_config(session_logging=config),
+        backend=FakeBackend([
+            [mock_llm_chunk(content="one")],
+            [mock_llm_chunk(content="two")],
+        ]),
+    )
+    [_ async for _ in agent_loop.act("first")]
+    [_ async for _ in agent_loop.act("second")]
+    original = agent_loop.session_id
+
+    forked = await agent_loop.rewind.fork_at_turn(1)
+
+    assert forked != original
+    assert [m.content for m in agent_loop.messages[1:]] == ["first", "one"]
+    metadata = agent_loop.session_logger.session_metadata
+    assert metadata is not None
+    assert (metadata.forked_from, metadata.forked_at_turn) == (original, 1)
+    original_dir = SessionLoader.find_session_by_id(original, config)
+    assert original_dir is not None
+    assert len(SessionLoader.load_session(original_dir)[0]) == 4
@@ -0,0 +1,117 @@
+from __future__ import annotations
+
+import json
+from pathlib import Path
+
+import pytest
+
+from rune.core.config import SessionLoggingConfig
+from rune.core.session.session_export import ExportFormat, export_session
+from rune.core.session.session_import import SessionImportError, import_session
+from rune.core.session.session_loader import SessionLoader
+from rune.core.types import FunctionCall, LLMMessage, Role, ToolCall
+
+
+@pytest.fixture
+def config(tmp_path: Path) -> SessionLoggingConfig:
+    return SessionLoggingConfig(save_dir=str(tmp_path / "sessions"), session_prefix="t")
+
+
+@pytest.fixture
+def remote_session(tmp_path: Path) -> Path:
+    session_dir = tmp_path / "remote"
+    session_dir.mkdir()
+    messages = [
+        LLMMessage(role=Role.system, content="System prompt"),
+        LLMMessage(
+            role=Role.user,
+            content="Read /home/ana/proj/app.py, not /home/ana/projects/app.py",
+        ),
+        LLMMessage(
+            role=Role.assistant,
+            tool_calls=[
+                ToolCall(
+                    id="call_1",
+                    function=FunctionCall(
+                        name="read_file", arguments='{"path": "/home/ana/proj/app.py"}'
+                    ),
+                )
+            ],
+        ),
+        LLMMessage(role=Role.tool, tool_call_id="call_1", content="print('hi')"),
+    ]
+    (session_dir / "messages.jsonl").write_text(
+        "".join(m.model_dump_json(exclude_none=True) + "\n" for m in messages)
+    )
+    (session_dir / "meta.json").write_text(
+        json.dumps({
+            "session_id": "remote01-0000",
+            "title": "Read the app",
+            "environment": {"working_directory": "/home/ana/proj"},
+        })
+    )
+    return session_dir
+
+
+def test_imported_sessions_are_resumable_with_paths_moved(
+    remote_session: Path, config: SessionLoggingConfig, tmp_path: Path
+) -> None:
+    export = tmp_path / "export.json"
+    export.write_text(export_session(remote_session, ExportFormat.JSON, redact=False))
+
+    session_id, session_dir = import_session(export, config, tmp_path / "proj")
+
+    assert SessionLoader.find_session_by_id(session_id, config) == session_dir
+    messages, metadata = SessionLoader.load_session(session_dir)
+    assert messages[0].content == (
+        f"Read {tmp_path / 'proj'}/app.py, not /home/ana/projects/app.py"
+    )
+    assert messages[1].tool_calls is not None
+    arguments = json.loads(messages[1].tool_calls[0].function.arguments or "")
+    assert arguments == {"path": f"{tmp_path / 'proj'}/app.py"}
+    assert metadata["title"] == "Read the app"
+    assert metadata["imported_from"] == "remote01-0000"
+    assert metadata["total_messages"] == 3
+    assert session_id != "remote01-0000"
+
+
+def test_session_directories_import_as_they_are(
+    remote_session: Path, config: SessionLoggingConfig
+) -> None:
+    _, session_dir = import_session(remote_session, config, Path("/home/ana/proj"))
+
+    messages, _ = SessionLoader.load_session(session_dir)
+    assert messages[0].content == (
+        "Read /home/ana/proj/app.py, not /home/ana/projects/app.py"
+    )
+
+
+def test_redacted_exports_are_refused(
+    remote_session: Path, config: SessionLoggingConfig, tmp_path: Path
+) -> None:
+    export = tmp_path / "export.json"
+    export.write_text(export_session(remote_session, ExportFormat.JSON))
+
+    with pytest.raises(SessionImportError, match="--no-redact"):
+        import_session(export, config)
+    assert not list(Path(config.save_dir).glob("t_*"))
+
+
+def test_invalid_sessions_are_rejected(
+    tmp_path: Path, config: SessionLoggingConfig
+) -> None:
+    orphan = tmp_path / "orphan.jsonl"
+    orphan.write_text(
+        json.dumps({"role": "user", "content": "hi"})
+        + "\n"
+        + json.dumps({"role": "tool", "tool_call_id": "missing", "content": "x"})
+        + "\n"
+    )
+    garbage = tmp_path / "garbage.jsonl"
+    garbage.write_text("not json\n")
+
+    with pytest.raises(SessionImportError, match="answers no tool call"):
+        import_session(orphan, config)
+    with pytest.raises(SessionImportError, match="Could not read"):
+        import_session(garbage, config)
+    assert not list(Path(config.save_dir).glob("t_*"))
@@ -0,0 +1,135 @@
+from __future__ import annotations
+
+from datetime import UTC, datetime, timedelta
+import json
+import os
+from pathlib import Path
+import shutil
+
+import pytest
+
+from rune.core.config import SessionLoggingConfig
+from rune.core.session.session_index import SessionIndex
+from rune.core.types import FunctionCall, LLMMessage, Role, ToolCall
+
+
+@pytest.fixture
+def config(tmp_path: Path) -> SessionLoggingConfig:
+    return SessionLoggingConfig(save_dir=str(tmp_path), session_prefix="test")
+
+
+def write_session(
+    config: SessionLoggingConfig, session_id: str, messages: list[LLMMessage]
+) -> Path:
+    session_dir = Path(config.save_dir) / f"test_20250101_120000_{session_id}"
+    session_dir.mkdir(exist_ok=True)
+    (session_dir / "messages.jsonl").write_text(
+        "".join(m.model_dump_json(exclude_none=True) + "\n" for m in messages)
+    )
+    (session_dir / "meta.json").write_text(
+        json.dumps({"session_id": session_id, "title": f"Session {session_id}"})
+    )
+    return session_dir
+
+
+def test_search_finds_prompts_replies_and_commands(
+    config: SessionLoggingConfig,
+) -> None:
+    write_session(
+        config,
+        "aaaa1111",
+        [
+            LLMMessage(role=Role.user, content="Why is the billing job slow?"),
+            LLMMessage(
+                role=Role.assistant,
+                content="",
+                tool_calls=[
+                    ToolCall(
+                        function=FunctionCall(
+                            name="bash", arguments='{"command": "pytest -k invoices"}'
+                        )
+                    )
+                ],
+            ),
+            LLMMessage(role=Role.assistant, content="The invoices query is slow."),
+        ],
+    )
+    write_session(
+        config, "bbbb2222", [LLMMessage(role=Role.user, content="Rename the CLI")]
+    )
+    index = SessionIndex(config)
+
+    [billing] = index.search("billing")
+    [command] = index.search("invoices")
+
+    assert (billing.session_id, billing.kind) == ("aaaa1111", "user")
+    assert billing.title == "Session aaaa1111"
+    assert "[billing]" in billing.snippet
+    assert (command.session_id, command.kind) == ("aaaa1111", "command")
+    assert index.search("deploy") == []
+
+
+def test_refresh_picks_up_changed_and_deleted_sessions(
+    config: SessionLoggingConfig,
+) -> None:
+    session_dir = write_session(
+        config, "aaaa1111", [LLMMessage(role=Role.user, content="first question")]
+    )
+    index = SessionIndex(config)
+    assert index.refresh() == 1
+    assert index.refresh() == 0
+
+    write_session(
+        config, "aaaa1111", [LLMMessage(role=Role.user, content="second question")]
+    )
+    messages = session_dir / "messages.jsonl"
+    os.utime(messages, (messages.stat().st_atime, messages.stat().st_mtime + 1))
+
+    assert [hit.session_id for hit in index.search("second")] == ["aaaa1111"]
+    assert index.search("first") == []
+
+    messages.unlink()
+    (session_dir / "meta.json").unlink()
+    session_dir.rmdir()
+    assert index.search("second") == []
+
+
+def test_query_syntax_is_taken_literally(config: SessionLoggingConfig) -> None:
+    write_session(
+        config, "aaaa1111", [LLMMessage(role=Role.user, content='fix "NOT" handling')]
+    )
+
+    assert len(SessionIndex(config).search('NOT "handling" (')) == 1
+
+
+def test_maintain_drops_sessions_whose_logs_are_gone(
+    config: SessionLoggingConfig,
+) -> None:
+    write_session(config, "aaaa1111", [LLMMessage(role=Role.user, content="billing")])
+    gone = write_session(
+        config, "bbbb2222", [LLMMessage(role=Role.user, content="billing")]
+    )
+    index = SessionIndex(config)
+    index.refresh()
+    shutil.rmtree(gone)
+
+    report = index.maintain()
+
+    assert (report.pruned_sessions, report.indexed_sessions) == (1, 1)
+    assert report.size_after > 0
+    assert list(index.indexed_sessions()) == [
+        Path(config.save_dir) / "test_20250101_120000_aaaa1111"
+    ]
+
+
+def test_maintenance_is_due_weekly(config: SessionLoggingConfig) -> None:
+    index = SessionIndex(config)
+    assert not index.maintenance_due()
+    index.refresh()
+    assert index.maintenance_due()
+
+    index.maintain()
+
+    now = datetime.now(UTC)
+    assert not index.maintenance_due(now)
+    assert index.maintenance_due(now + timedelta(days=8))
@@ -8,7 +8,7 @@ import time
 import pytest
 
 from rune.core.config import SessionLoggingConfig
-from rune.core.session.session_loader import SessionLoader
+from rune.core.session.session_loader import SessionFilter, SessionLoader
 from rune.core.types import LLMMessage, Role, ToolCall
 
 
@@ -284,6 +284,125 @@ class TestSessionLoaderFindLatestSession:
         assert result == valid_session
 
 
+class TestSessionLoaderListRecentSessions:
+    def test_list_recent_sessions_newest_first_with_metadata(
+        self, session_config: SessionLoggingConfig, create_test_session
+    ) -> None:
+        session_dir = Path(session_config.save_dir)
+        create_test_session(session_dir, "aaaa1111-session")
+        time.sleep(0.01)
+        newest = create_test_session(session_dir, "bbbb2222-session")
+
+        result = SessionLoader.list_recent_sessions(session_config)
+
+        assert result[0][0] == newest
+        assert [meta["session_id"] for _, meta in result] == [
+            "bbbb2222-session",
+            "aaaa1111-session",
+        ]
+
+    def test_list_recent_sessions_respects_limit_and_skips_invalid(
+        self, session_config: SessionLoggingConfig, create_test_session
+    ) -> None:
+        session_dir = Path(session_config.save_dir)
+        for idx in range(3):
+            create_test_session(session_dir, f"{idx}000000-session")
+            time.sleep(0.01)
+        invalid = session_dir / "test_20230101_120000_invalid0"
+        invalid.mkdir()
+        (invalid / "messages.jsonl").write_text("")
+
+        result = SessionLoader.list_recent_sessions(session_config, limit=2)
+
+        assert [meta["session_id"] for _, meta in result] == [
+            "2000000-session",
+            "1000000-session",
+        ]
+
+    def test_list_recent_sessions_nonexistent_save_dir(self) -> None:
+        config = SessionLoggingConfig(
+            save_dir="/nonexistent/path", session_prefix="test", enabled=True
+        )
+
+        assert SessionLoader.list_recent_sessions(config) == []
+
+
+class TestSessionFilter:
+    @staticmethod
+    def metadata(**fields) -> dict:
+        return {
+            "session_id": "abc",
+            "tags": ["billing", "infra"],
+            "environment": {"working_directory": "/src/app/api"},
+            "config": {"active_model": "devstral"},
+            **fields,
+        }
+
+    def test_matches_tags_cwd_and_model(self) -> None:
+        assert SessionFilter(
+            tags=["#Billing"], cwd=Path("/src/app"), model="DEVSTRAL"
+        ).matches(self.metadata())
+        assert not SessionFilter(tags=["docs"]).matches(self.metadata())
+        assert not SessionFilter(cwd=Path("/src/other")).matches(self.metadata())
+        assert not SessionFilter(model="local").matches(self.metadata(config={}))
+
+    def test_from_query_splits_off_filter_terms(self) -> None:
+        session_filter, rest = SessionFilter.from_query(
+            "tag:billing fix model:devstral upload tag:Infra"
+        )
+
+        assert session_filter.tags == ["billing", "infra"]
+        assert session_filter.model == "devstral"
+        assert rest == "fix upload"
+        assert SessionFilter.from_query("fix upload")[0].is_empty
+
+    def test_list_recent_sessions_applies_the_filter_before_the_limit(
+        self, session_config: SessionLoggingConfig, create_test_session
+    ) -> None:
+        session_dir = Path(session_config.save_dir)
+        create_test_session(
+            session_dir,
+            "aaaa1111-session",
+            metadata={"session_id": "aaaa1111-session", "tags": ["billing"]},
+        )
+        time.sleep(0.01)
+        create_test_session(session_dir, "bbbb2222-session")
+
+        result = SessionLoader.list_recent_sessions(
+            session_config, limit=1, session_filter=SessionFilter(tags=["billing"])
+        )
+
+        assert [meta["session_id"] for _, meta in result] == ["aaaa1111-session"]
+
+    def test_list_recent_sessions_only_validates_matching_sessions(
+        self,
+        session_config: SessionLoggingConfig,
+        create_test_session,
+        monkeypatch: pytest.MonkeyPatch,
+    ) -> None:
+        session_dir = Path(session_config.save_dir)
+        create_test_session(
+            session_dir,
+            "aaaa1111-session",
+            metadata={"session_id": "aaaa1111-session", "tags": ["billing"]},
+        )
+        create_test_session(session_dir, "bbbb2222-session")
+        validated: list[str] = []
+        is_valid = SessionLoader._is_valid_session
+        monkeypatch.setattr(
+            SessionLoader,
+            "_is_valid_session",
+            staticmethod(lambda path: validated.append(path.name) or is_valid(path)),
+        )
+
+        SessionLoader.list_recent_sessions(
+            session_config, session_filter=SessionFilter(tags=["billing"])
+        )
+
+        assert len(validated) == 1
+        assert validated[0].endswith("aaaa1111")
+
+
 class TestSessionLoaderFindSessionById:
     def test_find_session_by_id_exact_match(
         self, session_config: SessionLoggingConfig, create_test_session
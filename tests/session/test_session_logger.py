@@ -11,6 +11,7 @@ import pytest
 from tests.conftest import build_test_rune_config
 from rune.core.agents.models import AgentProfile, AgentSafety
 from rune.core.config import SessionLoggingConfig, RuneConfig
+from rune.core.session.session_loader import SessionLoader
 from rune.core.session.session_logger import SessionLogger
 from rune.core.tools.manager import ToolManager
 from rune.core.types import AgentStats, LLMMessage, Role, SessionMetadata
@@ -503,6 +504,53 @@ class TestSessionLoggerSaveInteraction:
             assert len(f.readlines()) == 2
 
 
+class TestSessionLoggerCompression:
+    @pytest.mark.asyncio
+    async def test_compressed_sessions_append_and_load(
+        self,
+        temp_session_dir: Path,
+        mock_rune_config: RuneConfig,
+        mock_tool_manager: ToolManager,
+        mock_agent_profile: AgentProfile,
+    ) -> None:
+        config = SessionLoggingConfig(
+            save_dir=str(temp_session_dir), session_prefix="test", compress=True
+        )
+        logger = SessionLogger(config, "test-session-123")
+        messages = [
+            LLMMessage(role=Role.system, content="System prompt"),
+            LLMMessage(role=Role.user, content="Hello"),
+            LLMMessage(role=Role.assistant, content="Hi there!"),
+        ]
+        for count in (2, 3):
+            await logger.save_interaction(
+                messages=messages[:count],
+                stats=AgentStats(),
+                base_config=mock_rune_config,
+                tool_manager=mock_tool_manager,
+                agent_profile=mock_agent_profile,
+            )
+
+        assert logger.session_dir is not None
+        assert logger.messages_filepath.name == "messages.jsonl.zst"
+        assert not (logger.session_dir / "messages.jsonl").exists()
+        assert SessionLoader.find_latest_session(config) == logger.session_dir
+        loaded, metadata = SessionLoader.load_session(logger.session_dir)
+        assert [m.content for m in loaded] == ["Hello", "Hi there!"]
+        assert metadata["total_messages"] == 2
+
+    def test_plain_sessions_stay_plain(
+        self, temp_session_dir: Path, session_config: SessionLoggingConfig
+    ) -> None:
+        logger = SessionLogger(session_config, "test-session-123")
+        assert logger.session_dir is not None
+        logger.session_dir.mkdir()
+        (logger.session_dir / "messages.jsonl").touch()
+        logger.session_config = session_config.model_copy(update={"compress": True})
+
+        assert logger.messages_filepath.name == "messages.jsonl"
+
+
 class TestSessionLoggerResetSession:
     def test_reset_session(self, session_config: SessionLoggingConfig) -> None:
         """Test that reset_session correctly resets session information."""
@@ -0,0 +1,81 @@
+from __future__ import annotations
+
+from datetime import UTC, datetime, timedelta
+import os
+from pathlib import Path
+
+import pytest
+
+from rune.core.config import SessionLoggingConfig, SessionRetentionConfig
+from rune.core.session.session_index import SessionIndex
+from rune.core.session.session_retention import prune_sessions
+
+NOW = datetime(2025, 6, 1, tzinfo=UTC)
+
+
+@pytest.fixture
+def config(tmp_path: Path) -> SessionLoggingConfig:
+    return SessionLoggingConfig(save_dir=str(tmp_path), session_prefix="t")
+
+
+def make_session(
+    config: SessionLoggingConfig, name: str, days_ago: float, size: int = 10
+) -> Path:
+    session_dir = Path(config.save_dir) / f"t_{name}"
+    session_dir.mkdir()
+    messages = session_dir / "messages.jsonl"
+    messages.write_text('{"role": "user", "content": "' + "x" * size + '"}\n')
+    (session_dir / "meta.json").write_text('{"session_id": "' + name + '"}')
+    mtime = (NOW - timedelta(days=days_ago)).timestamp()
+    os.utime(messages, (mtime, mtime))
+    return session_dir
+
+
+def test_sessions_past_max_age_are_deleted(config: SessionLoggingConfig) -> None:
+    old = make_session(config, "old", days_ago=40)
+    recent = make_session(config, "recent", days_ago=5)
+
+    pruned = prune_sessions(config, SessionRetentionConfig(max_age_days=30), NOW)
+
+    assert pruned == [old]
+    assert not old.exists()
+    assert recent.exists()
+
+
+def test_oldest_sessions_go_until_the_rest_fit(config: SessionLoggingConfig) -> None:
+    oldest = make_session(config, "a", days_ago=9, size=400_000)
+    older = make_session(config, "b", days_ago=8, size=400_000)
+    newer = make_session(config, "c", days_ago=7, size=400_000)
+
+    pruned = prune_sessions(config, SessionRetentionConfig(max_total_mb=1), NOW)
+
+    assert pruned == [oldest]
+    assert older.exists() and newer.exists()
+
+
+def test_sessions_in_use_are_kept(config: SessionLoggingConfig) -> None:
+    active = make_session(config, "active", days_ago=0.1, size=2_000_000)
+
+    pruned = prune_sessions(config, SessionRetentionConfig(max_total_mb=1), NOW)
+
+    assert pruned == []
+    assert active.exists()
+
+
+def test_archiving_keeps_the_session_and_updates_the_index(
+    config: SessionLoggingConfig,
+) -> None:
+    old = make_session(config, "old", days_ago=40)
+    index = SessionIndex(config)
+    assert len(index.search("xxxxxxxxxx")) == 1
+
+    prune_sessions(config, SessionRetentionConfig(max_age_days=30, archive=True), NOW)
+
+    assert (Path(config.save_dir) / "archived" / old.name / "meta.json").exists()
+    assert index.search("xxxxxxxxxx") == []
+
+
+def test_no_policy_prunes_nothing(config: SessionLoggingConfig) -> None:
+    make_session(config, "old", days_ago=4000)
+
+    assert prune_sessions(config, SessionRetentionConfig(), NOW) == []
@@ -0,0 +1,144 @@
+from __future__ import annotations
+
+import json
+from pathlib import Path
+
+import pytest
+
+from tests.conftest import build_test_agent_loop, build_test_rune_config
+from tests.mock.utils import mock_llm_chunk
+from tests.stubs.fake_backend import FakeBackend
+from rune.core.config import AutoTitleConfig, SessionLoggingConfig
+from rune.core.prompts import UtilityPrompt
+from rune.core.session.session_loader import SessionFilter, SessionLoader
+from rune.core.session.session_logger import METADATA_FILENAME
+from rune.core.session.session_title import SessionTitler, clean_title
+from rune.core.types import LLMMessage, Role
+
+
+@pytest.fixture
+def session_config(tmp_path: Path) -> SessionLoggingConfig:
+    return SessionLoggingConfig(
+        save_dir=str(tmp_path / "sessions"), session_prefix="test", enabled=True
+    )
+
+
+def read_metadata(session_dir: Path | None) -> dict:
+    assert session_dir is not None
+    return json.loads((session_dir / METADATA_FILENAME).read_text())
+
+
+@pytest.mark.parametrize(
+    ("raw", "expected"),
+    [
+        ('"Fix flaky upload test."', "Fix flaky upload test"),
+        ("Title: Add dark mode\nBecause the user asked.", "Add dark mode"),
+        ("   ", ""),
+        ("x" * 80, "x" * 60 + "…"),
+    ],
+)
+def test_clean_title(raw: str, expected: str) -> None:
+    assert clean_title(raw) == expected
+
+
+@pytest.mark.asyncio
+async def test_titler_sends_the_opening_exchange() -> None:
+    backend = FakeBackend(mock_llm_chunk(content="Rename config loader"))
+    titler = SessionTitler(build_test_rune_config, backend)
+
+    title = await titler.generate([
+        LLMMessage(role=Role.user, content="rename load_cfg to load_config"),
+        LLMMessage(role=Role.assistant, content="Done."),
+        LLMMessage(role=Role.user, content="thanks"),
+    ])
+
+    assert title == "Rename config loader"
+    system, request = backend.requests_messages[0]
+    assert system.content == UtilityPrompt.SESSION_TITLE.read()
+    assert request.content == (
+        "## User\nrename load_cfg to load_config\n\n## Assistant\nDone."
+    )
+
+
+@pytest.mark.asyncio
+async def test_first_turn_titles_the_session_until_it_is_renamed(
+    session_config: SessionLoggingConfig,
+) -> None:
+    backend = FakeBackend([
+        [mock_llm_chunk(content="Hi! How can I help?")],
+        [mock_llm_chunk(content="Greeting the assistant.")],
+    ])
+    agent_loop = build_test_agent_loop(
+        config=build_test_rune_config(
+            session_logging=session_config,
+            auto_title=AutoTitleConfig(enabled=True),
+        ),
+        backend=backend,
+    )
+    agent_loop.titler.backend = backend
+
+    [_ async for _ in agent_loop.act("hello")]
+    assert agent_loop._title_task is not None
+    await agent_loop._title_task
+
+    session_dir = agent_loop.session_logger.session_dir
+    metadata = read_metadata(session_dir)
+    assert metadata["auto_title"] == "Greeting the assistant"
+    assert metadata["title"] == "Greeting the assistant"
+
+    await agent_loop.session_logger.set_name("Billing refactor")
+
+    metadata = read_metadata(session_dir)
+    assert (metadata["name"], metadata["title"]) == (
+        "Billing refactor",
+        "Billing refactor",
+    )
+    assert SessionLoader.find_session_by_name("billing REFACTOR", session_config) == (
+        session_dir
+    )
+
+
+@pytest.mark.asyncio
+async def test_named_sessions_are_not_titled(
+    session_config: SessionLoggingConfig,
+) -> None:
+    backend = FakeBackend([[mock_llm_chunk(content="Hi!")]])
+    agent_loop = build_test_agent_loop(
+        config=build_test_rune_config(
+            session_logging=session_config,
+            auto_title=AutoTitleConfig(enabled=True),
+        ),
+        backend=backend,
+    )
+    agent_loop.titler.backend = backend
+
+    await agent_loop.session_logger.set_name("Scratch")
+    [_ async for _ in agent_loop.act("hello")]
+
+    assert agent_loop._title_task is None
+    assert read_metadata(agent_loop.session_logger.session_dir)["title"] == "Scratch"
+
+
+@pytest.mark.asyncio
+async def test_tags_are_saved_and_filter_the_session_list(
+    session_config: SessionLoggingConfig,
+) -> None:
+    backend = FakeBackend([[mock_llm_chunk(content="Hi!")]])
+    agent_loop = build_test_agent_loop(
+        config=build_test_rune_config(session_logging=session_config),
+        backend=backend,
+    )
+    [_ async for _ in agent_loop.act("hello")]
+
+    tags = await agent_loop.session_logger.set_tags(["#Billing", "infra", "billing"])
+
+    session_dir = agent_loop.session_logger.session_dir
+    assert tags == ["billing", "infra"]
+    assert read_metadata(session_dir)["tags"] == ["billing", "infra"]
+    listed = SessionLoader.list_recent_sessions(
+        session_config, session_filter=SessionFilter(tags=["billing"])
+    )
+    assert [path for path, _ in listed] == [session_dir]
+    assert not SessionLoader.list_recent_sessions(
+        session_config, session_filter=SessionFilter(tags=["billing", "docs"])
+    )
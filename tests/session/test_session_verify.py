@@ -0,0 +1,102 @@
+from __future__ import annotations
+
+import json
+from pathlib import Path
+
+import pytest
+
+from rune.core.config import SessionLoggingConfig
+from rune.core.session.session_index import SessionIndex
+from rune.core.session.session_verify import (
+    ProblemKind,
+    repair_sessions,
+    verify_sessions,
+)
+
+MESSAGE = json.dumps({"role": "user", "content": "rotate the billing keys"})
+
+
+@pytest.fixture
+def config(tmp_path: Path) -> SessionLoggingConfig:
+    return SessionLoggingConfig(save_dir=str(tmp_path), session_prefix="test")
+
+
+def make_session(
+    config: SessionLoggingConfig,
+    session_id: str = "abcd1234-0000",
+    messages: str = MESSAGE + "\n",
+) -> Path:
+    session_dir = Path(config.save_dir) / f"test_20250101_120000_{session_id[:8]}"
+    session_dir.mkdir()
+    (session_dir / "messages.jsonl").write_text(messages)
+    (session_dir / "meta.json").write_text(json.dumps({"session_id": session_id}))
+    return session_dir
+
+
+def test_intact_sessions_have_no_problems(config: SessionLoggingConfig) -> None:
+    make_session(config)
+    SessionIndex(config).refresh()
+
+    assert verify_sessions(config) == []
+
+
+def test_a_truncated_last_line_is_cut_with_a_backup(
+    config: SessionLoggingConfig,
+) -> None:
+    session_dir = make_session(config, messages=MESSAGE + '\n{"role": "assis')
+
+    problems = verify_sessions(config)
+
+    assert [p.kind for p in problems] == [ProblemKind.TRUNCATED_LINE]
+    assert repair_sessions(config, problems) == problems
+    assert (session_dir / "messages.jsonl").read_text() == MESSAGE + "\n"
+    assert (session_dir / "messages.jsonl.bak").exists()
+    assert verify_sessions(config) == []
+
+
+def test_corrupt_lines_and_metadata_are_reported_not_repaired(
+    config: SessionLoggingConfig,
+) -> None:
+    make_session(config, messages="garbage\n" + MESSAGE + "\n")
+    other = make_session(config, session_id="ffff0000-1111")
+    (other / "meta.json").write_text("{}")
+
+    problems = verify_sessions(config)
+
+    assert {p.kind for p in problems} == {
+        ProblemKind.CORRUPT_LINE,
+        ProblemKind.INVALID_METADATA,
+    }
+    assert repair_sessions(config, problems) == []
+
+
+def test_a_directory_not_matching_its_id_is_reported(
+    config: SessionLoggingConfig,
+) -> None:
+    session_dir = make_session(config)
+    (session_dir / "meta.json").write_text(json.dumps({"session_id": "99999999"}))
+
+    assert [p.kind for p in verify_sessions(config)] == [ProblemKind.ID_MISMATCH]
+
+
+def test_the_index_is_rebuilt_when_it_is_out_of_date(
+    config: SessionLoggingConfig,
+) -> None:
+    session_dir = make_session(config)
+    index = SessionIndex(config)
+    index.refresh()
+    (session_dir / "meta.json").write_text(json.dumps({"session_id": "abcd1234-9"}))
+    gone = make_session(config, session_id="ffff0000-1111")
+    index.refresh()
+    for path in gone.iterdir():
+        path.unlink()
+    gone.rmdir()
+
+    problems = verify_sessions(config)
+
+    assert {p.kind for p in problems} == {
+        ProblemKind.INDEX_MISMATCH,
+        ProblemKind.INDEX_STALE,
+    }
+    repair_sessions(config, problems)
+    assert index.indexed_sessions() == {session_dir: "abcd1234-9"}
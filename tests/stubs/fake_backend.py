@@ -1,7 +1,7 @@
 from __future__ import annotations
 
 from collections.abc import AsyncGenerator, Callable, Iterable
-from typing import cast
+from typing import Any, cast
 
 from tests.mock.utils import mock_llm_chunk
 from rune.core.types import LLMChunk, LLMMessage, Role
@@ -35,6 +35,7 @@ class FakeBackend:
         """
         self._requests_messages: list[list[LLMMessage]] = []
         self._requests_extra_headers: list[dict[str, str] | None] = []
+        self._requests_output_schemas: list[dict[str, Any] | None] = []
         self._count_tokens_calls: list[list[LLMMessage]] = []
         self._token_counter = token_counter or self._default_token_counter
         self._exception_to_raise = exception_to_raise
@@ -65,6 +66,10 @@ class FakeBackend:
     def requests_extra_headers(self) -> list[dict[str, str] | None]:
         return self._requests_extra_headers
 
+    @property
+    def requests_output_schemas(self) -> list[dict[str, Any] | None]:
+        return self._requests_output_schemas
+
     @staticmethod
     def _default_token_counter(messages: list[LLMMessage]) -> int:
         return 1
@@ -85,12 +90,14 @@ class FakeBackend:
         tool_choice,
         extra_headers,
         max_tokens,
+        output_schema=None,
     ) -> LLMChunk:
         if self._exception_to_raise:
             raise self._exception_to_raise
 
         self._requests_messages.append(messages)
         self._requests_extra_headers.append(extra_headers)
+        self._requests_output_schemas.append(output_schema)
 
         if self._streams:
             stream = self._streams.pop(0)
@@ -111,12 +118,14 @@ class FakeBackend:
         tool_choice,
         extra_headers,
         max_tokens,
+        output_schema=None,
     ) -> AsyncGenerator[LLMChunk]:
         if self._exception_to_raise:
             raise self._exception_to_raise
 
         self._requests_messages.append(messages)
         self._requests_extra_headers.append(extra_headers)
+        self._requests_output_schemas.append(output_schema)
 
         if self._streams:
             stream = list(self._streams.pop(0))
@@ -38,6 +38,7 @@ class FakeClient(Client):
 
     def __init__(self) -> None:
         self._session_updates = []
+        self._ext_notifications: list[tuple[str, dict[str, Any]]] = []
 
     async def session_update(
         self,
@@ -119,7 +120,7 @@ class FakeClient(Client):
         raise NotImplementedError()
 
     async def ext_notification(self, method: str, params: dict[str, Any]) -> None:
-        raise NotImplementedError()
+        self._ext_notifications.append((method, params))
 
     async def close(self) -> None:
         raise NotImplementedError()
@@ -1,10 +1,15 @@
 from __future__ import annotations
 
+import asyncio
+
 import pytest
 
 from tests.conftest import build_test_agent_loop, build_test_rune_config
 from tests.mock.utils import mock_llm_chunk
 from tests.stubs.fake_backend import FakeBackend
+from rune.core.agent_loop import AgentLoop
+from rune.core.config import CompactionConfig, CompactionStrategy
+from rune.core.prompts import UtilityPrompt
 from rune.core.types import (
     AssistantEvent,
     CompactEndEvent,
@@ -52,3 +57,96 @@ async def test_auto_compact_triggers_and_batches_observer() -> None:
     assert roles == [Role.system, Role.user, Role.assistant]
     assert observed[1][1] is not None and "<summary>" in observed[1][1]
     assert observed[2][1] == "<final>"
+
+
+def _add_turns(agent: AgentLoop, count: int) -> None:
+    for i in range(count):
+        agent.messages.append(LLMMessage(role=Role.user, content=f"question {i}"))
+        agent.messages.append(LLMMessage(role=Role.assistant, content=f"answer {i}"))
+
+
+@pytest.mark.asyncio
+async def test_truncate_oldest_drops_turns_without_a_model_call() -> None:
+    backend = FakeBackend()
+    cfg = build_test_rune_config(
+        auto_compact_threshold=10,
+        compaction=CompactionConfig(strategy=CompactionStrategy.TRUNCATE_OLDEST),
+    )
+    agent = build_test_agent_loop(config=cfg, backend=backend)
+    _add_turns(agent, 3)
+
+    summary = await agent.compact()
+
+    assert backend.requests_messages == []
+    assert [m.content for m in agent.messages[1:]] == ["question 2", "answer 2"]
+    assert summary == "Dropped the 4 oldest messages of the conversation."
+
+
+@pytest.mark.asyncio
+async def test_hybrid_summarizes_older_turns_and_keeps_recent_ones() -> None:
+    backend = FakeBackend([[mock_llm_chunk(content="<summary>")]])
+    cfg = build_test_rune_config(
+        compaction=CompactionConfig(
+            strategy=CompactionStrategy.HYBRID, keep_recent_turns=1
+        )
+    )
+    agent = build_test_agent_loop(config=cfg, backend=backend)
+    _add_turns(agent, 3)
+
+    await agent.compact()
+
+    summarized = [m.content for m in backend.requests_messages[0][1:-1]]
+    assert summarized == ["question 0", "answer 0", "question 1", "answer 1"]
+    assert [m.content for m in agent.messages[1:]] == [
+        "<summary>",
+        "question 2",
+        "answer 2",
+    ]
+
+
+class SummaryBackend(FakeBackend):
+    """Writes summaries off the main queue, so turns can go on meanwhile."""
+
+    def __init__(self, *args, **kwargs) -> None:
+        super().__init__(*args, **kwargs)
+        self.summarized = asyncio.Event()
+
+    async def complete(self, *, messages, **kwargs):
+        if messages[-1].content != UtilityPrompt.COMPACT.read():
+            return await super().complete(messages=messages, **kwargs)
+        self.summarized.set()
+        return mock_llm_chunk(content="<summary>")
+
+
+@pytest.mark.asyncio
+async def test_background_compaction_is_swapped_in_at_the_next_turn() -> None:
+    backend = SummaryBackend([
+        [mock_llm_chunk(content="answer 2")],
+        [mock_llm_chunk(content="answer 3")],
+    ])
+    cfg = build_test_rune_config(
+        auto_compact_threshold=100, compaction=CompactionConfig(background=True)
+    )
+    agent = build_test_agent_loop(config=cfg, backend=backend)
+    _add_turns(agent, 2)
+    agent.stats.context_tokens = 110
+
+    events = [ev async for ev in agent.act("question 2")]
+
+    start = next(ev for ev in events if isinstance(ev, CompactStartEvent))
+    assert start.background
+    assert not any(isinstance(ev, CompactEndEvent) for ev in events)
+    assert agent.messages[1].content == "question 0"
+
+    await backend.summarized.wait()
+    await asyncio.sleep(0)
+    events = [ev async for ev in agent.act("question 3")]
+
+    end = next(ev for ev in events if isinstance(ev, CompactEndEvent))
+    assert end.tool_call_id == start.tool_call_id
+    assert [m.content for m in agent.messages[1:]] == [
+        "<summary>",
+        "answer 2",
+        "question 3",
+        "answer 3",
+    ]
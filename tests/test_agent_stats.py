@@ -11,6 +11,7 @@ from rune.core.agents.models import BuiltinAgentName
 from rune.core.config import (
     Backend,
     ModelConfig,
+    ModelPricing,
     ProviderConfig,
     SessionLoggingConfig,
     RuneConfig,
@@ -22,7 +23,9 @@ from rune.core.types import (
     CompactEndEvent,
     CompactStartEvent,
     FunctionCall,
+    LLMChunk,
     LLMMessage,
+    LLMUsage,
     Role,
     ToolCall,
     UserMessageEvent,
@@ -156,6 +159,56 @@ class TestAgentStatsHelpers:
         # Cost = 1M * $2/M + 0.5M * $4/M = $2 + $2 = $4
         assert stats.session_cost == 4.0
 
+    def test_recorded_usage_keeps_its_cost_when_pricing_changes(self) -> None:
+        stats = AgentStats(input_price_per_million=1.0, output_price_per_million=2.0)
+        stats.record_usage(
+            LLMUsage(prompt_tokens=1_000_000, completion_tokens=500_000), cost=3.0
+        )
+        stats.session_prompt_tokens += 1_000_000
+
+        stats.update_pricing(2.0, 4.0)
+
+        # $3 recorded, plus 1M tokens counted without a price at $2/M
+        assert stats.session_cost == 5.0
+        assert stats.last_turn_cost == 3.0
+
+
+class TestPricingTable:
+    @pytest.mark.asyncio
+    async def test_pricing_table_overrides_model_prices(self) -> None:
+        config = make_config()
+        config.pricing = {
+            "rune-cli-cli-latest": ModelPricing(
+                input=1.0, output=10.0, cached_input=0.1
+            )
+        }
+        usage = LLMUsage(
+            prompt_tokens=1_000_000, completion_tokens=100_000, cached_tokens=800_000
+        )
+        backend = FakeBackend(
+            LLMChunk(message=LLMMessage(role=Role.assistant, content="Hi"), usage=usage)
+        )
+        agent = build_test_agent_loop(config=config, backend=backend)
+
+        async for _ in agent.act("Hello"):
+            pass
+
+        # 200k uncached at $1/M + 800k cached at $0.1/M + 100k output at $10/M
+        assert agent.stats.last_turn_cost == pytest.approx(0.2 + 0.08 + 1.0)
+        assert agent.stats.session_cost == pytest.approx(1.28)
+        assert agent.stats.session_cached_tokens == 800_000
+        assert agent.stats.input_price_per_million == 1.0
+
+    def test_models_without_an_entry_use_their_own_prices(self) -> None:
+        config = make_config()
+        config.pricing = {"devstral-small": ModelPricing(input=9.0)}
+
+        pricing = config.get_model_pricing(config.get_active_model())
+
+        assert (pricing.input, pricing.output) == (0.4, 2.0)
+        small = config.resolve_model("devstral-small")
+        assert config.get_model_pricing(small).input == 9.0
+
 
 class TestReloadPreservesStats:
     @pytest.mark.asyncio
@@ -609,7 +662,7 @@ class TestClearHistoryFullReset:
 
 class TestStatsEdgeCases:
     @pytest.mark.asyncio
-    async def test_session_cost_approximation_on_model_change(
+    async def test_session_cost_keeps_the_price_of_past_requests_on_model_change(
         self, monkeypatch
     ) -> None:
         monkeypatch.setenv("LECHAT_API_KEY", "mock-key")
@@ -628,7 +681,9 @@ class TestStatsEdgeCases:
 
         cost_after = agent.stats.session_cost
 
-        assert cost_after > cost_before
+        assert cost_before > 0
+        assert cost_after == cost_before
+        assert agent.stats.input_price_per_million == 2.5
 
     @pytest.mark.asyncio
     async def test_multiple_reloads_accumulate_correctly(self) -> None:
@@ -699,3 +754,62 @@ class TestStatsEdgeCases:
         await agent.reload_with_initial_messages(base_config=new_config)
 
         assert agent.config.active_model == "devstral-small"
+
+
+class TestTurnTiming:
+    @pytest.mark.asyncio
+    async def test_turn_records_wall_and_model_time(self) -> None:
+        backend = FakeBackend([
+            [mock_llm_chunk(content="R1")],
+            [mock_llm_chunk(content="R2")],
+        ])
+        agent = build_test_agent_loop(config=make_config(), backend=backend)
+
+        async for _ in agent.act("Hello"):
+            pass
+        first_turn = agent.stats.last_turn_wall_time
+
+        assert first_turn > 0
+        assert 0 < agent.stats.last_turn_model_time <= first_turn
+        assert agent.stats.last_turn_tool_time == 0
+
+        async for _ in agent.act("Again"):
+            pass
+
+        assert agent.stats.session_wall_time == pytest.approx(
+            first_turn + agent.stats.last_turn_wall_time
+        )
+
+    @pytest.mark.asyncio
+    async def test_tool_time_is_tracked_separately(self) -> None:
+        backend = FakeBackend([
+            mock_llm_chunk(
+                content="Calling tool",
+                tool_calls=[
+                    ToolCall(
+                        id="tc1",
+                        index=0,
+                        function=FunctionCall(
+                            name="todo", arguments='{"action": "read"}'
+                        ),
+                    )
+                ],
+            ),
+            mock_llm_chunk(content="Done"),
+        ])
+        agent = build_test_agent_loop(
+            config=make_config(enabled_tools=["todo"]),
+            agent_name=BuiltinAgentName.AUTO_APPROVE,
+            backend=backend,
+        )
+
+        async for _ in agent.act("Check todos"):
+            pass
+
+        stats = agent.stats
+        assert stats.last_turn_tool_time > 0
+        assert stats.session_tool_time == stats.last_turn_tool_time
+        assert (
+            stats.last_turn_model_time + stats.last_turn_tool_time
+            <= stats.last_turn_wall_time
+        )
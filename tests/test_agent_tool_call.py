@@ -2,6 +2,7 @@ from __future__ import annotations
 
 import asyncio
 import json
+from pathlib import Path
 
 from pydantic import BaseModel
 import pytest
@@ -12,7 +13,7 @@ from tests.stubs.fake_backend import FakeBackend
 from tests.stubs.fake_tool import FakeTool
 from rune.core.agent_loop import AgentLoop
 from rune.core.agents.models import BuiltinAgentName
-from rune.core.config import RuneConfig
+from rune.core.config import RuneConfig, ToolCacheConfig
 from rune.core.tools.base import BaseToolConfig, ToolPermission
 from rune.core.tools.builtins.todo import TodoItem
 from rune.core.types import (
@@ -494,3 +495,389 @@ async def test_ensure_assistant_after_tool_appends_understood() -> None:
     idx = next(i for i, m in enumerate(agent_loop.messages) if m.role == Role.tool)
     assert agent_loop.messages[idx + 1].role == Role.assistant
     assert agent_loop.messages[idx + 1].content == "Understood."
+
+
+@pytest.mark.asyncio
+async def test_read_only_mode_blocks_state_changing_tools() -> None:
+    tool_call = ToolCall(
+        id="call_ro", index=0, function=FunctionCall(name="stub_tool", arguments="{}")
+    )
+    config = build_test_rune_config(
+        auto_compact_threshold=0, enabled_tools=["stub_tool"]
+    )
+    agent_loop = build_test_agent_loop(
+        config=config,
+        agent_name=BuiltinAgentName.AUTO_APPROVE,
+        backend=FakeBackend([
+            [mock_llm_chunk(content="Let me use the tool.", tool_calls=[tool_call])],
+            [mock_llm_chunk(content="Okay.")],
+        ]),
+    )
+    agent_loop.tool_manager._available["stub_tool"] = FakeTool
+    agent_loop.read_only = True
+
+    events = await act_and_collect_events(agent_loop, "Execute tool")
+
+    tool_result = next(e for e in events if isinstance(e, ToolResultEvent))
+    assert tool_result.skipped
+    assert tool_result.skip_reason == (
+        "Tool 'stub_tool' is not allowed in read-only mode."
+    )
+    assert "Read-only mode is active" in (agent_loop.messages[1].content or "")
+
+
+@pytest.mark.asyncio
+async def test_read_only_mode_allows_read_only_tools() -> None:
+    agent_loop = make_agent_loop(
+        backend=FakeBackend([
+            [mock_llm_chunk(content="", tool_calls=[make_todo_tool_call("call_1")])],
+            [mock_llm_chunk(content="Done.")],
+        ])
+    )
+    agent_loop.read_only = True
+
+    events = await act_and_collect_events(agent_loop, "What's on my todo list?")
+
+    tool_result = next(e for e in events if isinstance(e, ToolResultEvent))
+    assert not tool_result.skipped
+    assert tool_result.result is not None
+
+
+def _write_file_call(call_id: str, path: Path) -> ToolCall:
+    return ToolCall(
+        id=call_id,
+        index=0,
+        function=FunctionCall(
+            name="write_file",
+            arguments=json.dumps({"path": str(path), "content": "data"}),
+        ),
+    )
+
+
+@pytest.mark.asyncio
+async def test_write_outside_workspace_asks_and_grants_directory(
+    tmp_path: Path, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    workspace = tmp_path / "workspace"
+    outside = tmp_path / "outside"
+    workspace.mkdir()
+    outside.mkdir()
+    monkeypatch.chdir(workspace)
+    asked: list[str] = []
+
+    def approval_callback(
+        tool_name: str, args: BaseModel, tool_call_id: str
+    ) -> tuple[ApprovalResponse, str | None]:
+        asked.append(tool_call_id)
+        assert agent_loop.write_grant_requests[tool_call_id] == outside.resolve()
+        return (ApprovalResponse.YES, None)
+
+    first = _write_file_call("c1", outside / "a")
+    second = _write_file_call("c2", outside / "b")
+    agent_loop = build_test_agent_loop(
+        config=build_test_rune_config(
+            auto_compact_threshold=0, enabled_tools=["write_file"]
+        ),
+        agent_name=BuiltinAgentName.AUTO_APPROVE,
+        backend=FakeBackend([
+            [mock_llm_chunk(content="", tool_calls=[first])],
+            [mock_llm_chunk(content="", tool_calls=[second])],
+            [mock_llm_chunk(content="Done.")],
+        ]),
+    )
+    agent_loop.set_approval_callback(approval_callback)
+
+    await act_and_collect_events(agent_loop, "Write both files")
+
+    assert asked == ["c1"]
+    assert agent_loop.write_roots.roots == [outside.resolve()]
+    assert (outside / "a").read_text() == "data"
+    assert (outside / "b").read_text() == "data"
+
+
+@pytest.mark.asyncio
+async def test_rejected_write_outside_workspace_is_not_granted(
+    tmp_path: Path, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    workspace = tmp_path / "workspace"
+    workspace.mkdir()
+    monkeypatch.chdir(workspace)
+    write_call = _write_file_call("c1", tmp_path / "a")
+    agent_loop = build_test_agent_loop(
+        config=build_test_rune_config(
+            auto_compact_threshold=0, enabled_tools=["write_file"]
+        ),
+        agent_name=BuiltinAgentName.AUTO_APPROVE,
+        backend=FakeBackend([
+            [mock_llm_chunk(content="", tool_calls=[write_call])],
+            [mock_llm_chunk(content="Okay.")],
+        ]),
+    )
+    agent_loop.set_approval_callback(lambda *_: (ApprovalResponse.NO, "Not there"))
+
+    events = await act_and_collect_events(agent_loop, "Write it")
+
+    tool_result = next(e for e in events if isinstance(e, ToolResultEvent))
+    assert tool_result.skipped
+    assert agent_loop.write_roots.roots == []
+    assert not (tmp_path / "a").exists()
+
+
+@pytest.mark.asyncio
+async def test_search_replace_outside_workspace_needs_a_grant(
+    tmp_path: Path, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    workspace = tmp_path / "workspace"
+    workspace.mkdir()
+    monkeypatch.chdir(workspace)
+    target = tmp_path / "a.txt"
+    target.write_text("old\n")
+    patch_call = ToolCall(
+        id="c1",
+        index=0,
+        function=FunctionCall(
+            name="search_replace",
+            arguments=json.dumps({
+                "file_path": str(target),
+                "content": "<<<<<<< SEARCH\nold\n=======\nnew\n>>>>>>> REPLACE",
+            }),
+        ),
+    )
+    agent_loop = build_test_agent_loop(
+        config=build_test_rune_config(
+            auto_compact_threshold=0, enabled_tools=["search_replace"]
+        ),
+        agent_name=BuiltinAgentName.AUTO_APPROVE,
+        backend=FakeBackend([
+            [mock_llm_chunk(content="", tool_calls=[patch_call])],
+            [mock_llm_chunk(content="Okay.")],
+        ]),
+    )
+    agent_loop.set_approval_callback(lambda *_: (ApprovalResponse.NO, "Not there"))
+
+    events = await act_and_collect_events(agent_loop, "Patch it")
+
+    tool_result = next(e for e in events if isinstance(e, ToolResultEvent))
+    assert tool_result.skipped
+    assert target.read_text() == "old\n"
+
+
+@pytest.mark.asyncio
+async def test_root_prefixed_paths_resolve_into_added_workspace_roots(
+    tmp_path: Path, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    app = tmp_path / "app"
+    lib = tmp_path / "lib"
+    app.mkdir()
+    lib.mkdir()
+    monkeypatch.chdir(app)
+    agent_loop = build_test_agent_loop(
+        config=build_test_rune_config(
+            auto_compact_threshold=0, enabled_tools=["write_file"]
+        ),
+        agent_name=BuiltinAgentName.AUTO_APPROVE,
+        backend=FakeBackend([
+            [
+                mock_llm_chunk(
+                    content="",
+                    tool_calls=[_write_file_call("c1", Path("lib:notes.txt"))],
+                )
+            ],
+            [mock_llm_chunk(content="Done.")],
+        ]),
+    )
+    agent_loop.workspace.add(lib)
+
+    await act_and_collect_events(agent_loop, "Write notes in lib")
+
+    assert (lib / "notes.txt").read_text() == "data"
+    assert not (app / "lib:notes.txt").exists()
+
+
+@pytest.mark.asyncio
+async def test_dry_run_assesses_shell_commands_instead_of_running_them(
+    tmp_path: Path, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    monkeypatch.chdir(tmp_path)
+    target = tmp_path / "build"
+    target.mkdir()
+    tool_call = ToolCall(
+        id="call_dry",
+        index=0,
+        function=FunctionCall(
+            name="bash", arguments=json.dumps({"command": "rm -rf build"})
+        ),
+    )
+    config = build_test_rune_config(auto_compact_threshold=0, enabled_tools=["bash"])
+    agent_loop = build_test_agent_loop(
+        config=config,
+        agent_name=BuiltinAgentName.AUTO_APPROVE,
+        backend=FakeBackend([
+            [mock_llm_chunk(content="Cleaning up.", tool_calls=[tool_call])],
+            [mock_llm_chunk(content="Okay.")],
+        ]),
+    )
+    agent_loop.dry_run = True
+
+    events = await act_and_collect_events(agent_loop, "Remove the build dir")
+
+    tool_result = next(e for e in events if isinstance(e, ToolResultEvent))
+    assert tool_result.skipped
+    assert "NOT executed" in (tool_result.skip_reason or "")
+    assert "destructive" in (tool_result.skip_reason or "")
+    assert target.exists()
+    assert "Dry-run mode is active" in (agent_loop.messages[1].content or "")
+
+
+def make_policy_agent_loop(
+    backend: FakeBackend,
+    approval_callback: SyncApprovalCallback | None = None,
+    **policy: object,
+) -> AgentLoop:
+    config = build_test_rune_config(
+        auto_compact_threshold=0,
+        enabled_tools=["todo"],
+        tools={
+            "todo": BaseToolConfig(permission=ToolPermission.ALWAYS),
+            "policy": BaseToolConfig.model_validate(policy),
+        },
+        system_prompt_id="tests",
+        include_project_context=False,
+        include_prompt_detail=False,
+    )
+    agent_loop = build_test_agent_loop(
+        config=config, agent_name=BuiltinAgentName.AUTO_APPROVE, backend=backend
+    )
+    if approval_callback:
+        agent_loop.set_approval_callback(approval_callback)
+    return agent_loop
+
+
+@pytest.mark.asyncio
+async def test_policy_requires_approval_even_under_auto_approve() -> None:
+    asked: list[str] = []
+
+    def approval_callback(
+        tool_name: str, _args: BaseModel, _tool_call_id: str
+    ) -> tuple[ApprovalResponse, str | None]:
+        asked.append(tool_name)
+        return (ApprovalResponse.NO, "Not now")
+
+    agent_loop = make_policy_agent_loop(
+        FakeBackend([
+            [mock_llm_chunk(content="", tool_calls=[make_todo_tool_call("call_p1")])],
+            [mock_llm_chunk(content="Okay.")],
+        ]),
+        approval_callback,
+        require_approval=["todo"],
+    )
+
+    events = await act_and_collect_events(agent_loop, "What's my todo list?")
+
+    tool_result = next(e for e in events if isinstance(e, ToolResultEvent))
+    assert asked == ["todo"]
+    assert tool_result.skipped
+
+
+@pytest.mark.asyncio
+async def test_policy_limits_the_output_the_model_sees() -> None:
+    agent_loop = make_policy_agent_loop(
+        FakeBackend([
+            [mock_llm_chunk(content="", tool_calls=[make_todo_tool_call("call_p2")])],
+            [mock_llm_chunk(content="Okay.")],
+        ]),
+        max_output_chars={"todo": 10},
+    )
+
+    await act_and_collect_events(agent_loop, "What's my todo list?")
+
+    (tool_msg,) = [m for m in agent_loop.messages if m.role == Role.tool]
+    assert "cut by [tools.policy]" in (tool_msg.content or "")
+    assert len(tool_msg.content or "") < 80
+
+
+def _read_file_call(call_id: str, path: Path) -> ToolCall:
+    return ToolCall(
+        id=call_id,
+        index=0,
+        function=FunctionCall(
+            name="read_file", arguments=json.dumps({"path": str(path)})
+        ),
+    )
+
+
+@pytest.mark.asyncio
+async def test_tool_cache_reuses_reads_until_a_tool_changes_the_workspace(
+    tmp_path: Path, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    monkeypatch.chdir(tmp_path)
+    notes = tmp_path / "notes.txt"
+    notes.write_text("hello\n")
+    agent_loop = build_test_agent_loop(
+        config=build_test_rune_config(
+            auto_compact_threshold=0,
+            enabled_tools=["read_file", "write_file"],
+            tool_cache=ToolCacheConfig(enabled=True),
+        ),
+        agent_name=BuiltinAgentName.AUTO_APPROVE,
+        backend=FakeBackend([
+            [mock_llm_chunk(content="", tool_calls=[_read_file_call("r1", notes)])],
+            [mock_llm_chunk(content="", tool_calls=[_read_file_call("r2", notes)])],
+            [
+                mock_llm_chunk(
+                    content="", tool_calls=[_write_file_call("w1", tmp_path / "x")]
+                )
+            ],
+            [mock_llm_chunk(content="", tool_calls=[_read_file_call("r3", notes)])],
+            [mock_llm_chunk(content="Done.")],
+        ]),
+    )
+
+    events = await act_and_collect_events(agent_loop, "Read the notes")
+
+    results = [e for e in events if isinstance(e, ToolResultEvent)]
+    assert [e.error for e in results] == [None] * 4
+    assert results[1].result is results[0].result
+    assert results[3].result is not results[0].result
+    assert agent_loop.stats.tool_calls_cached == 1
+
+
+def _grep_call(call_id: str, pattern: str) -> ToolCall:
+    return ToolCall(
+        id=call_id,
+        index=0,
+        function=FunctionCall(name="grep", arguments=json.dumps({"pattern": pattern})),
+    )
+
+
+@pytest.mark.asyncio
+async def test_tool_cache_does_not_reuse_searches_of_a_directory(
+    tmp_path: Path, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    monkeypatch.chdir(tmp_path)
+    notes = tmp_path / "notes.txt"
+    notes.write_text("TODO: one\n")
+    agent_loop = build_test_agent_loop(
+        config=build_test_rune_config(
+            auto_compact_threshold=0,
+            enabled_tools=["grep"],
+            tool_cache=ToolCacheConfig(enabled=True),
+        ),
+        agent_name=BuiltinAgentName.AUTO_APPROVE,
+        backend=FakeBackend([
+            [mock_llm_chunk(content="", tool_calls=[_grep_call("g1", "TODO")])],
+            [mock_llm_chunk(content="", tool_calls=[_grep_call("g2", "TODO")])],
+            [mock_llm_chunk(content="Done.")],
+        ]),
+    )
+
+    results: list[ToolResultEvent] = []
+    async for event in agent_loop.act("Find the TODOs"):
+        if isinstance(event, ToolResultEvent):
+            results.append(event)
+            # Edited outside the agent, between the two searches.
+            notes.write_text("TODO: one\nTODO: two\n")
+
+    assert "two" not in str(results[0].result)
+    assert "TODO: two" in str(results[1].result)
+    assert agent_loop.stats.tool_calls_cached == 0
@@ -0,0 +1,73 @@
+from __future__ import annotations
+
+import pytest
+
+from tests.conftest import build_test_agent_loop, build_test_rune_config
+from tests.mock.utils import mock_llm_chunk
+from tests.stubs.fake_backend import FakeBackend
+from rune.core.types import ContextBudgetWarningEvent, LLMMessage, Role
+
+
+def test_context_usage_splits_tokens_by_category() -> None:
+    agent = build_test_agent_loop(
+        config=build_test_rune_config(auto_compact_threshold=1_000)
+    )
+    agent.messages.append(LLMMessage(role=Role.user, content="a" * 40))
+    agent.messages.append(
+        LLMMessage(role=Role.tool, content="b" * 400, tool_call_id="call_1")
+    )
+
+    usage = agent.context_usage()
+
+    assert usage.instructions > 0
+    assert usage.history == 10
+    assert usage.tool_outputs == 100
+    assert usage.total == usage.instructions + 110
+    assert usage.context_window == 1_000
+
+
+@pytest.mark.asyncio
+async def test_warning_is_emitted_once_past_the_threshold() -> None:
+    backend = FakeBackend([
+        [mock_llm_chunk(content="first", prompt_tokens=700, completion_tokens=50)],
+        [mock_llm_chunk(content="second", prompt_tokens=800, completion_tokens=50)],
+        [mock_llm_chunk(content="third", prompt_tokens=900, completion_tokens=50)],
+    ])
+    cfg = build_test_rune_config(
+        auto_compact_threshold=1_000, context_warning_threshold=0.75
+    )
+    agent = build_test_agent_loop(config=cfg, backend=backend)
+
+    first = [ev async for ev in agent.act("Hello")]
+    second = [ev async for ev in agent.act("Again")]
+    third = [ev async for ev in agent.act("And again")]
+
+    warnings = [ev for ev in second if isinstance(ev, ContextBudgetWarningEvent)]
+    assert not any(isinstance(ev, ContextBudgetWarningEvent) for ev in first)
+    assert len(warnings) == 1
+    assert warnings[0].context_tokens == 750
+    assert warnings[0].context_window == 1_000
+    assert not any(isinstance(ev, ContextBudgetWarningEvent) for ev in third)
+
+
+@pytest.mark.asyncio
+@pytest.mark.parametrize("context_warnings", [False, True])
+async def test_model_is_only_warned_with_context_warnings(
+    context_warnings: bool,
+) -> None:
+    backend = FakeBackend([
+        [mock_llm_chunk(content="first", prompt_tokens=700, completion_tokens=50)],
+        [mock_llm_chunk(content="second", prompt_tokens=800, completion_tokens=50)],
+    ])
+    cfg = build_test_rune_config(
+        auto_compact_threshold=1_000,
+        context_warning_threshold=0.75,
+        context_warnings=context_warnings,
+    )
+    agent = build_test_agent_loop(config=cfg, backend=backend)
+
+    [ev async for ev in agent.act("Hello")]
+    [ev async for ev in agent.act("Again")]
+
+    warned = any("of your total context" in (m.content or "") for m in agent.messages)
+    assert warned == context_warnings
@@ -1,5 +1,6 @@
 from __future__ import annotations
 
+from pathlib import Path
 import sys
 
 import pytest
@@ -7,7 +8,11 @@ import pytest
 from tests.conftest import build_test_rune_config
 from rune.core.agents import AgentManager
 from rune.core.skills.manager import SkillManager
-from rune.core.system_prompt import get_universal_system_prompt
+from rune.core.system_prompt import (
+    ProjectContextProvider,
+    get_system_message,
+    get_universal_system_prompt,
+)
 from rune.core.tools.manager import ToolManager
 
 
@@ -42,3 +47,62 @@ def test_get_universal_system_prompt_includes_windows_prompt_on_windows(
     assert "Use: backslashes (\\\\) for paths" in prompt
     assert "Check command availability with: `where command` (Windows)" in prompt
     assert "Script shebang: Not applicable on Windows" in prompt
+
+
+def test_get_universal_system_prompt_includes_response_language() -> None:
+    config = build_test_rune_config(
+        system_prompt_id="tests",
+        include_project_context=False,
+        include_prompt_detail=False,
+        response_language="Japanese",
+    )
+    tool_manager = ToolManager(lambda: config)
+    skill_manager = SkillManager(lambda: config)
+    agent_manager = AgentManager(lambda: config)
+
+    prompt = get_universal_system_prompt(
+        tool_manager, config, skill_manager, agent_manager
+    )
+
+    assert "Always respond to the user in Japanese" in prompt
+
+
+def test_system_message_marks_instructions_and_context_as_cacheable(
+    tmp_path: Path,
+) -> None:
+    (tmp_path / "main.py").write_text("print('hi')")
+    config = build_test_rune_config(
+        system_prompt_id="tests",
+        include_project_context=True,
+        include_prompt_detail=False,
+        include_model_info=False,
+        include_commit_signature=False,
+    )
+
+    message = get_system_message(
+        ToolManager(lambda: config),
+        config,
+        SkillManager(lambda: config),
+        AgentManager(lambda: config),
+        tmp_path,
+    )
+
+    content = message.content or ""
+    instructions_end, context_end = message.cache_breaks
+    assert content[:instructions_end] == config.system_prompt
+    assert "main.py" in content[instructions_end:]
+    assert context_end == len(content)
+    assert "cache_breaks" not in message.model_dump()
+
+
+def test_project_tree_respects_runeignore(tmp_path: Path) -> None:
+    (tmp_path / "data").mkdir()
+    (tmp_path / "data" / "dump.sql").write_text("")
+    (tmp_path / "app.py").write_text("")
+    (tmp_path / ".runeignore").write_text("# large dumps\ndata/\n")
+    config = build_test_rune_config().project_context
+
+    provider = ProjectContextProvider(config=config, root_path=tmp_path)
+
+    assert provider._is_ignored(tmp_path / "data")
+    assert not provider._is_ignored(tmp_path / "app.py")
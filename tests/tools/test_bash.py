@@ -1,10 +1,18 @@
 from __future__ import annotations
 
+import asyncio
+
 import pytest
 
 from tests.mock.utils import collect_result
-from rune.core.tools.base import BaseToolState, ToolError, ToolPermission
-from rune.core.tools.builtins.bash import Bash, BashArgs, BashToolConfig
+from rune.core.tools.base import (
+    BaseToolState,
+    InvokeContext,
+    ToolError,
+    ToolPermission,
+)
+from rune.core.tools.builtins.bash import Bash, BashArgs, BashResult, BashToolConfig
+from rune.core.types import ToolOutputEvent
 
 
 @pytest.fixture
@@ -45,6 +53,17 @@ async def test_uses_effective_workdir(tmp_path, monkeypatch):
     assert result.stdout.strip() == str(tmp_path)
 
 
+@pytest.mark.asyncio
+async def test_runs_in_the_working_directory_of_the_thread(bash, tmp_path):
+    worktree = tmp_path / "worktree"
+    worktree.mkdir()
+    ctx = InvokeContext(tool_call_id="call-1", cwd=worktree)
+
+    result = await collect_result(bash.run(BashArgs(command="pwd"), ctx))
+
+    assert result.stdout.strip() == str(worktree)
+
+
 @pytest.mark.asyncio
 async def test_handles_timeout(bash):
     with pytest.raises(ToolError) as err:
@@ -76,6 +95,41 @@ async def test_decodes_non_utf8_bytes(bash):
     assert result.stderr == ""
 
 
+@pytest.mark.asyncio
+async def test_streams_output_while_the_command_runs(bash):
+    command = "echo one; sleep 0.3; echo two >&2; sleep 0.3; echo three"
+    events = []
+    async for item in bash.run(
+        BashArgs(command=command), InvokeContext(tool_call_id="call_1")
+    ):
+        events.append(item)
+
+    *output, result = events
+    assert all(isinstance(e, ToolOutputEvent) for e in output)
+    assert [(e.stream, e.message) for e in output] == [
+        ("stdout", "one\n"),
+        ("stderr", "two\n"),
+        ("stdout", "three\n"),
+    ]
+    assert {e.tool_call_id for e in output} == {"call_1"}
+    assert isinstance(result, BashResult)
+    assert result.stdout == "one\nthree\n"
+
+
+@pytest.mark.asyncio
+async def test_stopping_returns_the_output_so_far(bash):
+    stop = asyncio.Event()
+    ctx = InvokeContext(tool_call_id="call_1", stop_requested=stop)
+    asyncio.get_running_loop().call_later(0.3, stop.set)
+
+    result = await collect_result(
+        bash.run(BashArgs(command="echo started; sleep 10; echo done"), ctx)
+    )
+
+    assert result.stopped
+    assert result.stdout == "started\n"
+
+
 def test_check_allowlist_denylist():
     config = BashToolConfig(allowlist=["echo", "pwd"], denylist=["rm"])
     bash_tool = Bash(config=config, state=BaseToolState())
@@ -0,0 +1,218 @@
+from __future__ import annotations
+
+from typing import Any
+
+import pytest
+
+from tests.mock.utils import collect_result
+from rune.core.tools.base import ToolError, ToolPermission
+from rune.core.tools.builtins import browser as browser_module
+from rune.core.tools.builtins.browser import (
+    Browser,
+    BrowserAction,
+    BrowserArgs,
+    BrowserState,
+    BrowserToolConfig,
+)
+
+PAGE = (
+    "<html><head><title>Cart</title></head><body><nav>Home</nav>"
+    "<main><h1>Cart</h1><button id='checkout'>Check out</button></main></body></html>"
+)
+
+
+class FakeChromium:
+    """Answers the scripts the tool runs as a page holding PAGE would."""
+
+    def __init__(self) -> None:
+        self.url = "about:blank"
+        # Where a click leads, if anywhere.
+        self.link: str | None = None
+        self.clicks: list[tuple[float, float]] = []
+        self.typed: list[str] = []
+        self.console: list[str] = []
+        self.alive = True
+
+    async def navigate(self, url: str) -> None:
+        self.url = url
+        self.console.append("error: Failed to load resource: 404")
+
+    async def evaluate(self, expression: str) -> Any:
+        if expression == browser_module._PAGE:
+            return {"url": self.url, "title": "Cart"}
+        if expression == "location.href":
+            return self.url
+        if "#missing" in expression:
+            return None
+        if "outerHTML" in expression:
+            return PAGE
+        if "scrollIntoView" in expression:
+            return {"x": 40.5, "y": 12.0, "visible": True}
+        return True
+
+    async def screenshot(self) -> str:
+        return "iVBORw0KGgo="
+
+    async def click(self, x: float, y: float) -> None:
+        self.clicks.append((x, y))
+        if self.link is not None:
+            self.url = self.link
+
+    async def insert_text(self, text: str) -> None:
+        self.typed.append(text)
+
+    def take_console(self) -> list[str]:
+        messages, self.console = self.console, []
+        return messages
+
+    async def close(self) -> None:
+        self.alive = False
+
+
+@pytest.fixture
+def chromium(monkeypatch: pytest.MonkeyPatch) -> FakeChromium:
+    monkeypatch.setattr(browser_module, "SETTLE_SECONDS", 0)
+    return FakeChromium()
+
+
+def _tool(chromium: FakeChromium, **config: object) -> Browser:
+    return Browser(
+        config=BrowserToolConfig(**config),
+        state=BrowserState.model_construct(browser=chromium),
+    )
+
+
+@pytest.mark.asyncio
+async def test_navigate_reports_the_page_and_console(chromium: FakeChromium) -> None:
+    result = await collect_result(
+        _tool(chromium, allow_private_addresses=True).run(
+            BrowserArgs(action=BrowserAction.NAVIGATE, url="http://localhost:3000/")
+        )
+    )
+
+    assert result.url == "http://localhost:3000/"
+    assert result.title == "Cart"
+    assert result.console == ["error: Failed to load resource: 404"]
+
+
+@pytest.mark.asyncio
+async def test_screenshots_are_returned_as_images(chromium: FakeChromium) -> None:
+    result = await collect_result(
+        _tool(chromium).run(BrowserArgs(action=BrowserAction.SCREENSHOT))
+    )
+
+    (image,) = result.images
+    assert image.media_type == "image/png"
+    assert image.data == "iVBORw0KGgo="
+    assert "images" not in result.model_dump()
+
+
+@pytest.mark.asyncio
+async def test_read_returns_the_main_content_as_markdown(
+    chromium: FakeChromium,
+) -> None:
+    tool = _tool(chromium)
+
+    text = await collect_result(tool.run(BrowserArgs(action=BrowserAction.READ)))
+    html = await collect_result(
+        tool.run(BrowserArgs(action=BrowserAction.READ, html=True))
+    )
+
+    assert text.content == "# Cart\n\nCheck out"
+    assert html.content == PAGE
+
+
+@pytest.mark.asyncio
+async def test_click_and_type_act_on_the_matching_element(
+    chromium: FakeChromium,
+) -> None:
+    tool = _tool(chromium)
+
+    await collect_result(
+        tool.run(BrowserArgs(action=BrowserAction.CLICK, selector="#checkout"))
+    )
+    await collect_result(
+        tool.run(
+            BrowserArgs(action=BrowserAction.TYPE, selector="#email", text="a@b.c")
+        )
+    )
+
+    assert chromium.clicks == [(40.5, 12.0)]
+    assert chromium.typed == ["a@b.c"]
+
+
+@pytest.mark.asyncio
+async def test_missing_elements_are_reported(chromium: FakeChromium) -> None:
+    tool = _tool(chromium)
+
+    with pytest.raises(ToolError, match="No element matches #missing"):
+        await collect_result(
+            tool.run(BrowserArgs(action=BrowserAction.CLICK, selector="#missing"))
+        )
+    with pytest.raises(ToolError, match="click needs a selector"):
+        await collect_result(tool.run(BrowserArgs(action=BrowserAction.CLICK)))
+
+
+@pytest.mark.asyncio
+@pytest.mark.parametrize("url", ["javascript:alert(1)", "file:///etc/passwd"])
+async def test_refuses_other_url_schemes(chromium: FakeChromium, url: str) -> None:
+    with pytest.raises(ToolError, match="Only http and https"):
+        await collect_result(
+            _tool(chromium).run(BrowserArgs(action=BrowserAction.NAVIGATE, url=url))
+        )
+
+
+@pytest.mark.asyncio
+async def test_refuses_private_addresses_unless_allowed(chromium: FakeChromium) -> None:
+    with pytest.raises(ToolError, match="non-public address"):
+        await collect_result(
+            _tool(chromium).run(
+                BrowserArgs(action=BrowserAction.NAVIGATE, url="http://127.0.0.1:3000/")
+            )
+        )
+    assert chromium.url == "about:blank"
+
+
+@pytest.mark.asyncio
+async def test_clicks_leading_to_unapproved_hosts_are_refused(
+    chromium: FakeChromium,
+) -> None:
+    tool = _tool(chromium, allowlist=["localhost"], allow_private_addresses=True)
+    await collect_result(
+        tool.run(
+            BrowserArgs(action=BrowserAction.NAVIGATE, url="http://localhost:3000/")
+        )
+    )
+    chromium.link = "https://tracker.example/pixel"
+
+    with pytest.raises(ToolError, match="tracker.example, which was not approved"):
+        await collect_result(
+            tool.run(BrowserArgs(action=BrowserAction.CLICK, selector="#checkout"))
+        )
+    assert chromium.url == "about:blank"
+
+
+def test_allowlist_applies_to_navigation(chromium: FakeChromium) -> None:
+    tool = _tool(chromium, allowlist=["localhost"])
+
+    assert (
+        tool.check_allowlist_denylist(
+            BrowserArgs(action=BrowserAction.NAVIGATE, url="http://localhost:5173/")
+        )
+        == ToolPermission.ALWAYS
+    )
+    assert (
+        tool.check_allowlist_denylist(BrowserArgs(action=BrowserAction.SCREENSHOT))
+        is None
+    )
+
+
+def test_host_patterns_do_not_match_urls_without_a_host(chromium: FakeChromium) -> None:
+    tool = _tool(chromium, allowlist=["*"])
+
+    assert (
+        tool.check_allowlist_denylist(
+            BrowserArgs(action=BrowserAction.NAVIGATE, url="file:///etc/passwd")
+        )
+        is None
+    )
@@ -0,0 +1,72 @@
+from __future__ import annotations
+
+import pytest
+
+from tests.mock.utils import collect_result
+from rune.core.tools.base import BaseToolState
+from rune.core.tools.builtins.bash import Bash, BashArgs, BashToolConfig
+from rune.core.tools.exec_env import EnvInherit, ExecEnvPolicy
+
+ENVIRON = {
+    "PATH": "/usr/bin",
+    "HOME": "/home/me",
+    "LC_CTYPE": "UTF-8",
+    "EDITOR": "vim",
+    "GITHUB_TOKEN": "ghp_x",
+    "AWS_SECRET_ACCESS_KEY": "aws",
+    "AWS_ACCESS_KEY_ID": "AKIA",
+    "OPENAI_API_KEY": "sk",
+    "db_password": "hunter2",
+}
+
+
+def test_default_policy_drops_credentials() -> None:
+    env = ExecEnvPolicy().apply(ENVIRON)
+
+    assert env == {
+        "PATH": "/usr/bin",
+        "HOME": "/home/me",
+        "LC_CTYPE": "UTF-8",
+        "EDITOR": "vim",
+    }
+
+
+def test_allow_overrides_exclusions() -> None:
+    env = ExecEnvPolicy(allow=["GITHUB_TOKEN"]).apply(ENVIRON)
+
+    assert env["GITHUB_TOKEN"] == "ghp_x"
+    assert "OPENAI_API_KEY" not in env
+
+
+def test_core_inherits_only_the_basics() -> None:
+    env = ExecEnvPolicy(inherit=EnvInherit.CORE, exclude=[]).apply(ENVIRON)
+
+    assert set(env) == {"PATH", "HOME", "LC_CTYPE"}
+
+
+def test_none_acts_as_an_allowlist_and_overrides_apply() -> None:
+    policy = ExecEnvPolicy(
+        inherit=EnvInherit.NONE, allow=["PATH"], overrides={"CI_MODE": "1"}
+    )
+
+    assert policy.apply(ENVIRON) == {"PATH": "/usr/bin", "CI_MODE": "1"}
+
+
+def test_empty_exclude_passes_everything_through() -> None:
+    assert ExecEnvPolicy(exclude=[]).apply(ENVIRON) == ENVIRON
+
+
+@pytest.mark.asyncio
+async def test_bash_commands_do_not_see_excluded_variables(tmp_path, monkeypatch):
+    monkeypatch.chdir(tmp_path)
+    monkeypatch.setenv("RUNE_TEST_TOKEN", "secret")
+    monkeypatch.setenv("RUNE_TEST_VISIBLE", "shown")
+    bash = Bash(config=BashToolConfig(), state=BaseToolState())
+
+    result = await collect_result(
+        bash.run(
+            BashArgs(command='echo "${RUNE_TEST_TOKEN:-unset} $RUNE_TEST_VISIBLE"')
+        )
+    )
+
+    assert result.stdout == "unset shown\n"
@@ -0,0 +1,234 @@
+from __future__ import annotations
+
+from functools import partial
+import socket
+
+import httpx
+import pytest
+
+from tests.mock.utils import collect_result
+from rune.core.tools.base import InvokeContext, ToolError, ToolPermission
+from rune.core.tools.builtins import fetch_url as fetch_url_module
+from rune.core.tools.builtins.fetch_url import (
+    FetchUrl,
+    FetchUrlArgs,
+    FetchUrlState,
+    FetchUrlToolConfig,
+)
+from rune.core.tools.html_markdown import html_to_markdown
+
+PAGE = """<html><head><title>Install</title><script>track()</script></head>
+<body>
+<nav><a href="/">Home</a> <a href="/blog">Blog</a></nav>
+<main>
+<h1>Installing</h1>
+<p>Run <code>pip install rune</code>, then see <a href="/usage">usage</a>.</p>
+<ul><li>Python 3.12</li><li>git</li></ul>
+</main>
+<footer>Copyright</footer>
+</body></html>"""
+
+ADDRESSES = {"docs.example.com": "93.184.216.34", "intranet.example.com": "10.0.0.8"}
+
+
+def _handler(request: httpx.Request) -> httpx.Response:
+    match request.url.path:
+        case "/install":
+            return httpx.Response(200, html=PAGE)
+        case "/moved":
+            return httpx.Response(302, headers={"location": "/install"})
+        case "/leave":
+            return httpx.Response(
+                302, headers={"location": "http://intranet.example.com/"}
+            )
+        case "/notes.txt":
+            return httpx.Response(200, text="x" * 250)
+        case "/logo.png":
+            return httpx.Response(
+                200, content=b"\x89PNG", headers={"content-type": "image/png"}
+            )
+    return httpx.Response(404)
+
+
+@pytest.fixture(autouse=True)
+def fake_network(monkeypatch: pytest.MonkeyPatch) -> None:
+    async def getaddrinfo(host: str, port: int, **kwargs: object) -> list:
+        if host not in ADDRESSES:
+            raise socket.gaierror(f"unknown host {host}")
+        return [(socket.AF_INET, socket.SOCK_STREAM, 6, "", (ADDRESSES[host], port))]
+
+    monkeypatch.setattr(fetch_url_module.anyio, "getaddrinfo", getaddrinfo)
+    monkeypatch.setattr(
+        fetch_url_module.httpx,
+        "AsyncClient",
+        partial(httpx.AsyncClient, transport=httpx.MockTransport(_handler)),
+    )
+
+
+def _tool(**config: object) -> FetchUrl:
+    return FetchUrl(config=FetchUrlToolConfig(**config), state=FetchUrlState())
+
+
+@pytest.mark.asyncio
+async def test_returns_the_main_content_as_markdown() -> None:
+    result = await collect_result(
+        _tool().run(FetchUrlArgs(url="https://docs.example.com/install"))
+    )
+
+    assert result.title == "Install"
+    assert result.content == (
+        "# Installing\n\n"
+        "Run `pip install rune`, then see "
+        "[usage](https://docs.example.com/usage).\n\n"
+        "- Python 3.12\n- git"
+    )
+    assert result.next_start_index is None
+
+
+@pytest.mark.asyncio
+async def test_follows_redirects() -> None:
+    result = await collect_result(
+        _tool().run(FetchUrlArgs(url="https://docs.example.com/moved"))
+    )
+
+    assert result.url == "https://docs.example.com/install"
+    assert result.title == "Install"
+
+
+@pytest.mark.asyncio
+async def test_long_pages_are_returned_in_parts() -> None:
+    tool = _tool(max_content_chars=100)
+    url = "https://docs.example.com/notes.txt"
+
+    first = await collect_result(tool.run(FetchUrlArgs(url=url)))
+    last = await collect_result(tool.run(FetchUrlArgs(url=url, start_index=200)))
+
+    assert len(first.content) == 100
+    assert first.next_start_index == 100
+    assert first.total_chars == 250
+    assert len(last.content) == 50
+    assert last.next_start_index is None
+
+
+@pytest.mark.asyncio
+async def test_parts_fit_in_the_tool_policy_output_limit() -> None:
+    url = "https://docs.example.com/notes.txt"
+    ctx = InvokeContext(tool_call_id="call", output_limit=len(url) + 200 + 60)
+
+    result = await collect_result(_tool().run(FetchUrlArgs(url=url), ctx))
+
+    assert len(result.content) == 60
+    assert result.next_start_index == 60
+
+
+@pytest.mark.asyncio
+async def test_requests_go_to_the_checked_address(
+    monkeypatch: pytest.MonkeyPatch,
+) -> None:
+    seen: list[httpx.Request] = []
+
+    def handler(request: httpx.Request) -> httpx.Response:
+        seen.append(request)
+        return _handler(request)
+
+    monkeypatch.setattr(
+        fetch_url_module.httpx,
+        "AsyncClient",
+        partial(httpx.AsyncClient, transport=httpx.MockTransport(handler)),
+    )
+
+    await collect_result(
+        _tool().run(FetchUrlArgs(url="https://docs.example.com/install"))
+    )
+
+    assert seen[0].url.host == "93.184.216.34"
+    assert seen[0].headers["host"] == "docs.example.com"
+    assert seen[0].extensions["sni_hostname"] == "docs.example.com"
+
+
+@pytest.mark.asyncio
+async def test_large_downloads_are_cut_short() -> None:
+    result = await collect_result(
+        _tool(max_download_bytes=10).run(
+            FetchUrlArgs(url="https://docs.example.com/notes.txt")
+        )
+    )
+
+    assert result.content == "x" * 10
+    assert result.download_truncated
+
+
+@pytest.mark.asyncio
+async def test_refuses_domains_outside_the_allowed_ones() -> None:
+    tool = _tool(allowed_domains=["example.org"])
+
+    with pytest.raises(ToolError, match="not in allowed_domains"):
+        await collect_result(tool.run(FetchUrlArgs(url="https://docs.example.com/")))
+
+
+@pytest.mark.asyncio
+async def test_denied_domains_include_subdomains() -> None:
+    tool = _tool(denied_domains=["example.com"])
+
+    with pytest.raises(ToolError, match="in denied_domains"):
+        await collect_result(tool.run(FetchUrlArgs(url="https://docs.example.com/")))
+
+
+@pytest.mark.asyncio
+async def test_refuses_private_addresses() -> None:
+    with pytest.raises(ToolError, match="non-public address 10.0.0.8"):
+        await collect_result(
+            _tool().run(FetchUrlArgs(url="http://intranet.example.com/"))
+        )
+
+
+@pytest.mark.asyncio
+async def test_redirects_are_checked_too() -> None:
+    with pytest.raises(ToolError, match="non-public address"):
+        await collect_result(
+            _tool().run(FetchUrlArgs(url="https://docs.example.com/leave"))
+        )
+
+
+@pytest.mark.asyncio
+async def test_refuses_binary_content_and_other_schemes() -> None:
+    with pytest.raises(ToolError, match="Unsupported content type image/png"):
+        await collect_result(
+            _tool().run(FetchUrlArgs(url="https://docs.example.com/logo.png"))
+        )
+    with pytest.raises(ToolError, match="Only http and https"):
+        await collect_result(_tool().run(FetchUrlArgs(url="file:///etc/passwd")))
+
+
+@pytest.mark.asyncio
+async def test_http_errors_are_reported() -> None:
+    with pytest.raises(ToolError, match="HTTP 404"):
+        await collect_result(
+            _tool().run(FetchUrlArgs(url="https://docs.example.com/missing"))
+        )
+
+
+def test_allowlist_and_denylist_match_host_names() -> None:
+    tool = _tool(allowlist=["*.example.com"], denylist=["evil.example.com"])
+
+    assert (
+        tool.check_allowlist_denylist(FetchUrlArgs(url="https://docs.example.com/a"))
+        == ToolPermission.ALWAYS
+    )
+    assert (
+        tool.check_allowlist_denylist(FetchUrlArgs(url="https://evil.example.com/"))
+        == ToolPermission.NEVER
+    )
+    assert tool.check_allowlist_denylist(FetchUrlArgs(url="https://a.org/")) is None
+
+
+def test_html_without_main_keeps_the_body_but_drops_chrome() -> None:
+    page = html_to_markdown(
+        "<body><header>Site</header><div class='sidebar'>Links</div>"
+        "<h2>Notes</h2><ol><li>one<ul><li>nested</li></ul></li><li>two</li></ol>"
+        "<pre>a  =  1\n</pre></body>"
+    )
+
+    assert page.markdown == (
+        "## Notes\n\n1. one\n  - nested\n2. two\n\n```\na  =  1\n```"
+    )
@@ -0,0 +1,147 @@
+from __future__ import annotations
+
+from pathlib import Path
+
+import pytest
+
+from tests.mock.utils import collect_result
+from rune.core.tools.builtins.grep import Grep, GrepArgs, GrepState, GrepToolConfig
+from rune.core.tools.builtins.read_file import (
+    ReadFile,
+    ReadFileArgs,
+    ReadFileState,
+    ReadFileToolConfig,
+)
+from rune.core.tools.builtins.search_replace import (
+    SearchReplace,
+    SearchReplaceArgs,
+    SearchReplaceConfig,
+    SearchReplaceState,
+)
+from rune.core.tools.file_guards import (
+    FileGuardError,
+    RefusalReason,
+    detect_encoding,
+    guard_text_file,
+    inspect_file,
+    is_probably_text,
+)
+
+PNG_HEADER = b"\x89PNG\r\n\x1a\n\x00\x00\x00\rIHDR" + bytes(range(256))
+
+
+@pytest.fixture
+def binary_file(tmp_path: Path) -> Path:
+    path = tmp_path / "blob.dat"
+    path.write_bytes(PNG_HEADER)
+    return path
+
+
+@pytest.fixture
+def text_file(tmp_path: Path) -> Path:
+    path = tmp_path / "notes.txt"
+    path.write_text("hello\nworld\n", encoding="utf-8")
+    return path
+
+
+def test_detects_text_and_binary_content() -> None:
+    assert is_probably_text("a.txt", "héllo wörld\r\n\tindent\n".encode())
+    assert is_probably_text("empty.txt", b"")
+    assert not is_probably_text("a.txt", b"abc\x00def")
+    assert not is_probably_text("photo.png", b"looks like text")
+    assert is_probably_text("icon.svg", b'<svg xmlns="http://www.w3.org/2000/svg"/>')
+
+
+def test_detects_encodings() -> None:
+    assert detect_encoding("héllo".encode()) == "utf-8"
+    # A sample may stop in the middle of a character.
+    assert detect_encoding("héllo".encode()[:2]) == "utf-8"
+    assert detect_encoding("héllo".encode("cp1252")) == "cp1252"
+    assert detect_encoding("héllo".encode("utf-16")) == "utf-16"
+    assert detect_encoding(b"\x81\x8d") == "latin-1"
+    assert is_probably_text("a.txt", "héllo".encode("utf-16"))
+
+
+def test_inspect_reports_size_and_mime(binary_file: Path, text_file: Path) -> None:
+    binary = inspect_file(binary_file)
+    assert binary.is_binary
+    assert binary.size == len(PNG_HEADER)
+    assert binary.mime == "application/octet-stream"
+
+    text = inspect_file(text_file)
+    assert not text.is_binary
+    assert text.mime == "text/plain"
+
+
+def test_refusal_carries_metadata(binary_file: Path) -> None:
+    with pytest.raises(FileGuardError) as exc_info:
+        guard_text_file(binary_file, action="read")
+
+    error = exc_info.value
+    assert error.reason == RefusalReason.BINARY
+    assert error.metadata == {
+        "path": str(binary_file),
+        "reason": "binary",
+        "size": len(PNG_HEADER),
+        "mime": "application/octet-stream",
+    }
+    assert f"size: {len(PNG_HEADER)} bytes" in str(error)
+    assert "mime: application/octet-stream" in str(error)
+
+
+def test_refuses_files_over_the_size_limit(text_file: Path) -> None:
+    with pytest.raises(FileGuardError) as exc_info:
+        guard_text_file(text_file, action="read", max_bytes=4)
+
+    assert exc_info.value.reason == RefusalReason.TOO_LARGE
+    assert guard_text_file(text_file, action="read", max_bytes=None).size == 12
+
+
+@pytest.mark.asyncio
+async def test_read_file_reports_binary_instead_of_reading_it(
+    tmp_path, monkeypatch, binary_file
+):
+    monkeypatch.chdir(tmp_path)
+    tool = ReadFile(config=ReadFileToolConfig(), state=ReadFileState())
+
+    result = await collect_result(tool.run(ReadFileArgs(path=str(binary_file))))
+
+    assert result.binary
+    assert result.content == ""
+    assert result.mime == "application/octet-stream"
+    assert result.size_bytes == len(PNG_HEADER)
+    assert result.encoding is None
+    assert "blob.dat is a binary file" in result.hint
+
+
+@pytest.mark.asyncio
+async def test_read_file_refuses_huge_files(tmp_path, monkeypatch, text_file):
+    monkeypatch.chdir(tmp_path)
+    tool = ReadFile(config=ReadFileToolConfig(max_file_bytes=4), state=ReadFileState())
+
+    with pytest.raises(FileGuardError, match="file is too large"):
+        await collect_result(tool.run(ReadFileArgs(path=str(text_file))))
+
+
+@pytest.mark.asyncio
+async def test_grep_refuses_a_binary_file_target(tmp_path, monkeypatch, binary_file):
+    monkeypatch.chdir(tmp_path)
+    tool = Grep(config=GrepToolConfig(), state=GrepState())
+
+    with pytest.raises(FileGuardError, match="Refused to search binary file"):
+        await collect_result(tool.run(GrepArgs(pattern="PNG", path=str(binary_file))))
+
+
+@pytest.mark.asyncio
+async def test_search_replace_leaves_binary_untouched(
+    tmp_path, monkeypatch, binary_file
+):
+    monkeypatch.chdir(tmp_path)
+    tool = SearchReplace(config=SearchReplaceConfig(), state=SearchReplaceState())
+    content = "<<<<<<< SEARCH\nPNG\n=======\nJPG\n>>>>>>> REPLACE"
+
+    with pytest.raises(FileGuardError, match="Refused to edit binary file"):
+        await collect_result(
+            tool.run(SearchReplaceArgs(file_path=str(binary_file), content=content))
+        )
+    assert binary_file.read_bytes() == PNG_HEADER
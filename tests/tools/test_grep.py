@@ -1,6 +1,7 @@
 from __future__ import annotations
 
 import shutil
+import subprocess
 
 import pytest
 
@@ -152,6 +153,60 @@ async def test_truncates_to_max_output_bytes(grep, tmp_path, monkeypatch):
     assert result.was_truncated
 
 
+@pytest.mark.asyncio
+async def test_groups_matches_by_file(grep, tmp_path):
+    (tmp_path / "b.py").write_text("x\nfoo()\n")
+    (tmp_path / "a.py").write_text("foo = 1\nbar\n")
+
+    result = await collect_result(grep.run(GrepArgs(pattern="foo")))
+
+    assert result.matches == "./a.py\n1:foo = 1\n\n./b.py\n2:foo()"
+    assert (result.match_count, result.file_count) == (2, 2)
+
+
+@pytest.mark.asyncio
+async def test_literal_mode_matches_regex_characters_as_text(grep, tmp_path):
+    (tmp_path / "test.py").write_text("items[0].name\nitemsX0Yname\n")
+
+    regex = await collect_result(grep.run(GrepArgs(pattern="items[0].name")))
+    literal = await collect_result(
+        grep.run(GrepArgs(pattern="items[0].name", literal=True))
+    )
+
+    assert regex.match_count == 0
+    assert literal.match_count == 1
+    assert "1:items[0].name" in literal.matches
+
+
+@pytest.mark.asyncio
+async def test_shows_context_lines(grep, tmp_path):
+    (tmp_path / "test.py").write_text("a\nb\nmatch\nc\nd\ne\nf\nmatch\n")
+
+    result = await collect_result(grep.run(GrepArgs(pattern="match", context_lines=1)))
+
+    assert result.matches == "./test.py\n2-b\n3:match\n4-c\n--\n7-f\n8:match"
+    assert result.match_count == 2
+
+
+@pytest.mark.asyncio
+async def test_max_matches_counts_across_files(grep, tmp_path):
+    for name in ("a.py", "b.py", "c.py"):
+        (tmp_path / name).write_text("match\nmatch\n")
+
+    result = await collect_result(grep.run(GrepArgs(pattern="match", max_matches=3)))
+
+    assert result.match_count == 3
+    assert result.file_count == 2
+    assert "c.py" not in result.matches
+    assert result.was_truncated
+
+
+@pytest.mark.asyncio
+async def test_rejects_negative_context_lines(grep):
+    with pytest.raises(ToolError, match="context_lines cannot be negative"):
+        await collect_result(grep.run(GrepArgs(pattern="x", context_lines=-1)))
+
+
 @pytest.mark.asyncio
 async def test_respects_default_ignore_patterns(grep, tmp_path):
     (tmp_path / "included.py").write_text("match\n")
@@ -317,6 +372,25 @@ class TestGnuGrepBackend:
         assert result.match_count == 50
         assert result.was_truncated
 
+    @pytest.mark.asyncio
+    @pytest.mark.skipif(not shutil.which("git"), reason="git not available")
+    async def test_respects_gitignore(self, grep_gnu_only, tmp_path):
+        subprocess.run(["git", "init", "-q"], cwd=tmp_path, check=True)
+        (tmp_path / ".gitignore").write_text("generated/\n")
+        generated = tmp_path / "generated"
+        generated.mkdir()
+        (generated / "out.py").write_text("match\n")
+        (tmp_path / "included.py").write_text("match\n")
+
+        result = await collect_result(grep_gnu_only.run(GrepArgs(pattern="match")))
+        unfiltered = await collect_result(
+            grep_gnu_only.run(GrepArgs(pattern="match", use_default_ignore=False))
+        )
+
+        assert "included.py" in result.matches
+        assert "generated" not in result.matches
+        assert "generated/out.py" in unfiltered.matches
+
 
 @pytest.mark.skipif(not shutil.which("rg"), reason="ripgrep not available")
 class TestRipgrepBackend:
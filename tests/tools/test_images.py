@@ -0,0 +1,50 @@
+from __future__ import annotations
+
+import base64
+import io
+import sys
+
+import pytest
+
+from rune.core.tools.images import fit_image
+from rune.core.types import ImageContent
+
+
+def _png(width: int, height: int) -> ImageContent:
+    image = pytest.importorskip("PIL.Image")
+    buffer = io.BytesIO()
+    image.new("RGB", (width, height), "white").save(buffer, format="PNG")
+    return ImageContent(data=base64.b64encode(buffer.getvalue()).decode())
+
+
+def _size(image: ImageContent) -> tuple[int, int]:
+    from PIL import Image
+
+    with Image.open(io.BytesIO(base64.b64decode(image.data))) as picture:
+        return picture.size
+
+
+def test_images_within_limits_are_sent_as_they_are():
+    image = _png(200, 100)
+
+    assert fit_image(image, max_side=1568, max_bytes=1_000_000) is image
+
+
+def test_large_images_are_scaled_down_to_the_longest_side():
+    fitted = fit_image(_png(4000, 1000), max_side=1000, max_bytes=1_000_000)
+
+    assert fitted is not None
+    assert fitted.media_type == "image/png"
+    assert _size(fitted) == (1000, 250)
+
+
+def test_images_that_cannot_fit_are_dropped():
+    assert fit_image(_png(400, 400), max_side=1000, max_bytes=10) is None
+
+
+def test_without_pillow_only_the_size_is_checked(monkeypatch):
+    monkeypatch.setitem(sys.modules, "PIL", None)
+    image = ImageContent(data=base64.b64encode(b"x" * 100).decode())
+
+    assert fit_image(image, max_side=1, max_bytes=100) is image
+    assert fit_image(image, max_side=1, max_bytes=99) is None
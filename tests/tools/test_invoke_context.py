@@ -1,12 +1,25 @@
 from __future__ import annotations
 
 from collections.abc import AsyncGenerator
+from pathlib import Path
 
 from pydantic import BaseModel
 import pytest
 
 from tests.mock.utils import collect_result
 from rune.core.tools.base import BaseTool, BaseToolConfig, BaseToolState, InvokeContext
+from rune.core.tools.builtins.read_file import (
+    ReadFile,
+    ReadFileArgs,
+    ReadFileState,
+    ReadFileToolConfig,
+)
+from rune.core.tools.builtins.write_file import (
+    WriteFile,
+    WriteFileArgs,
+    WriteFileConfig,
+    WriteFileState,
+)
 from rune.core.types import ApprovalCallback, ApprovalResponse, ToolStreamEvent
 
 
@@ -104,3 +117,24 @@ class TestToolInvokeWithContext:
         result = await collect_result(simple_tool.run(SimpleArgs(value="direct")))
 
         assert result.had_context is False
+
+
+@pytest.mark.asyncio
+async def test_file_tools_work_in_the_session_directory(
+    tmp_path: Path, monkeypatch: pytest.MonkeyPatch
+) -> None:
+    session_dir = tmp_path / "session"
+    session_dir.mkdir()
+    monkeypatch.chdir(tmp_path)
+    ctx = InvokeContext(tool_call_id="call_1", cwd=session_dir)
+    writer = WriteFile(config=WriteFileConfig(), state=WriteFileState())
+    reader = ReadFile(config=ReadFileToolConfig(), state=ReadFileState())
+
+    written = await collect_result(
+        writer.run(WriteFileArgs(path="notes.txt", content="hello\n"), ctx)
+    )
+    read = await collect_result(reader.run(ReadFileArgs(path="notes.txt"), ctx))
+
+    assert written.path == str((session_dir / "notes.txt").resolve())
+    assert not (tmp_path / "notes.txt").exists()
+    assert read.content == "hello\n"
@@ -71,6 +71,28 @@ def test_falls_back_to_base_config_for_unknown_tool(tool_manager):
     assert config.permission == ToolPermission.ASK
 
 
+def test_reads_the_config_table_the_tool_names():
+    rune_config = build_test_rune_config(
+        system_prompt_id="tests",
+        include_project_context=False,
+        tools={
+            "sql": BaseToolConfig.model_validate({
+                "max_rows": 50,
+                "analytics": {"url": "sqlite:///analytics.db"},
+            }),
+            "sql_query": BaseToolConfig(permission=ToolPermission.ALWAYS),
+        },
+    )
+    manager = ToolManager(lambda: rune_config)
+
+    config = manager.get_tool_config("sql_query")
+
+    connections = config.connections  # type: ignore[attr-defined]
+    assert connections["analytics"].url == "sqlite:///analytics.db"
+    assert config.max_rows == 50  # type: ignore[attr-defined]
+    assert config.permission == ToolPermission.ALWAYS
+
+
 class TestToolManagerFiltering:
     def test_enabled_tools_filters_to_only_enabled(self):
         rune_config = build_test_rune_config(
@@ -412,3 +434,16 @@ class DummyTool(BaseTool[DummyArgs, DummyResult, BaseToolConfig, BaseToolState])
         final_class = available.get("dummy_tool")
         assert final_class is not None
         assert final_class.description == "Dummy tool v2"
+
+
+def test_opt_in_tools_are_offered_once_enabled(tool_manager):
+    assert "browser" not in tool_manager.available_tools
+
+    rune_config = build_test_rune_config(
+        system_prompt_id="tests",
+        include_project_context=False,
+        tools={"browser": BaseToolConfig.model_validate({"enabled": True})},
+    )
+    manager = ToolManager(lambda: rune_config)
+
+    assert "browser" in manager.available_tools
@@ -0,0 +1,77 @@
+from __future__ import annotations
+
+import base64
+
+import pytest
+
+from rune.core.tools.base import BaseToolState, ToolPermission
+from rune.core.tools.builtins.bash import Bash, BashArgs, BashToolConfig
+from rune.core.tools.dry_run import CommandCategory, assess_command
+from rune.core.tools.powershell import extract_powershell_commands, unwrap_powershell
+from rune.core.tools.shell_profile import ShellProfile
+
+
+@pytest.mark.parametrize(
+    ("script", "expected"),
+    [
+        (
+            "Get-ChildItem; Remove-Item -Recurse build | Out-Null",
+            ["Get-ChildItem", "Remove-Item -Recurse build", "Out-Null"],
+        ),
+        ("git status && git diff", ["git status", "git diff"]),
+        ("echo $(Remove-Item x)", ["echo $(Remove-Item x)", "Remove-Item x"]),
+        ('echo "at $(Get-Date)"', ['echo "at $(Get-Date)"', "Get-Date"]),
+        (
+            "if (Test-Path a) { Remove-Item a } else { New-Item a }",
+            ["Test-Path a", "Remove-Item a", "New-Item a"],
+        ),
+        ("$text = Get-Content a.txt  # then; rm b", ["Get-Content a.txt"]),
+        ("@'\n--- a/x\n+++ b/x\n'@ | git apply", ["git apply"]),
+        ("Write-Output 'a; b'", ["Write-Output 'a; b'"]),
+    ],
+)
+def test_extract_powershell_commands(script: str, expected: list[str]) -> None:
+    assert extract_powershell_commands(script) == expected
+
+
+def test_nested_powershell_invocations_are_unwrapped() -> None:
+    encoded = base64.b64encode("Remove-Item y".encode("utf-16-le")).decode()
+
+    assert extract_powershell_commands(
+        'powershell -NoProfile -Command "Get-Date; del x"'
+    ) == ["Get-Date", "del x"]
+    assert extract_powershell_commands(f"pwsh -enc {encoded}") == ["Remove-Item y"]
+    assert unwrap_powershell("pwsh -File script.ps1") is None
+
+
+def test_allowlist_uses_the_powershell_parser_for_powershell_shells() -> None:
+    config = BashToolConfig(
+        allowlist=["Get-ChildItem", "git status"],
+        denylist=["Remove-Item"],
+        shell=ShellProfile(program="pwsh"),
+    )
+    bash = Bash(config=config, state=BaseToolState())
+
+    def check(command: str) -> ToolPermission | None:
+        return bash.check_allowlist_denylist(BashArgs(command=command))
+
+    assert check("get-childitem -Recurse; git status") is ToolPermission.ALWAYS
+    assert check("Get-ChildItem | ForEach-Object { Remove-Item $_ }") is (
+        ToolPermission.NEVER
+    )
+    assert check("Get-ChildItem; Set-Content a.txt hi") is None
+
+
+def test_dry_run_classifies_powershell_cmdlets() -> None:
+    assessment = assess_command(
+        "Get-Content a.txt | Set-Content b.txt; Remove-Item c.txt", powershell=True
+    )
+
+    assert assessment.categories == [
+        CommandCategory.READ,
+        CommandCategory.WRITE,
+        CommandCategory.DESTRUCTIVE,
+    ]
+    assert assess_command(
+        "Get-ChildItem > $null", powershell=True
+    ).categories == [CommandCategory.READ]
@@ -0,0 +1,212 @@
+from __future__ import annotations
+
+import asyncio
+import sys
+
+import pytest
+
+from tests.conftest import build_test_rune_config
+from tests.mock.utils import collect_result
+from rune.core.tools.base import InvokeContext, ToolError
+from rune.core.tools.builtins.python import (
+    Python,
+    PythonArgs,
+    PythonState,
+    PythonToolConfig,
+)
+from rune.core.tools.manager import ToolManager
+
+
+@pytest.fixture
+def python(tmp_path, monkeypatch):
+    monkeypatch.chdir(tmp_path)
+    config = PythonToolConfig(interpreter=sys.executable)
+    return Python(config=config, state=PythonState())
+
+
+async def _run(python, code="", **args):
+    return await collect_result(python.run(PythonArgs(code=code, **args)))
+
+
+@pytest.mark.asyncio
+async def test_variables_persist_between_calls(python):
+    try:
+        first = await _run(python, "import math\nx = 41\nprint('loaded')")
+        second = await _run(python, "x + 1")
+    finally:
+        await python._stop_kernel()
+
+    assert first.stdout == "loaded\n"
+    assert first.fresh_kernel
+    assert second.result == "42"
+    assert not second.fresh_kernel
+
+
+@pytest.mark.asyncio
+async def test_the_kernel_starts_in_the_session_directory(python, tmp_path):
+    session_dir = tmp_path / "project"
+    session_dir.mkdir()
+    ctx = InvokeContext(tool_call_id="call-1", cwd=session_dir)
+    try:
+        result = await collect_result(
+            python.run(PythonArgs(code="import os\nos.getcwd()"), ctx)
+        )
+    finally:
+        await python._stop_kernel()
+
+    assert result.result == repr(str(session_dir))
+
+
+@pytest.mark.asyncio
+async def test_the_kernel_follows_the_session_to_another_directory(python, tmp_path):
+    worktree = tmp_path / "worktree"
+    worktree.mkdir()
+    code = PythonArgs(code="import os\nos.getcwd()")
+    try:
+        await collect_result(python.run(code, InvokeContext(tool_call_id="c1")))
+        moved = await collect_result(
+            python.run(code, InvokeContext(tool_call_id="c2", cwd=worktree))
+        )
+    finally:
+        await python._stop_kernel()
+
+    assert moved.result == repr(str(worktree))
+    assert not moved.fresh_kernel
+
+
+@pytest.mark.asyncio
+async def test_stopping_interrupts_the_run_and_keeps_the_kernel(python):
+    stop = asyncio.Event()
+    ctx = InvokeContext(tool_call_id="call-1", stop_requested=stop)
+    asyncio.get_running_loop().call_later(0.5, stop.set)
+    try:
+        result = await collect_result(
+            python.run(
+                PythonArgs(code="import time\nv = 1\nwhile True: time.sleep(0.05)"),
+                ctx,
+            )
+        )
+        after = await _run(python, "v")
+    finally:
+        await python._stop_kernel()
+
+    assert result.stopped
+    assert not result.interrupted
+    assert "KeyboardInterrupt" in result.error
+    assert after.result == "1"
+
+
+@pytest.mark.asyncio
+async def test_exceptions_are_reported_and_keep_the_kernel(python):
+    try:
+        failed = await _run(python, "y = 1\n1 / 0")
+        after = await _run(python, "y")
+    finally:
+        await python._stop_kernel()
+
+    assert "ZeroDivisionError: division by zero" in failed.error
+    assert after.result == "1"
+
+
+@pytest.mark.asyncio
+async def test_reset_clears_the_namespace(python):
+    try:
+        await _run(python, "z = 1")
+        reset = await _run(python, "'z' in globals()", reset=True)
+    finally:
+        await python._stop_kernel()
+
+    assert reset.fresh_kernel
+    assert reset.result == "False"
+
+
+@pytest.mark.asyncio
+async def test_long_runs_are_interrupted(python):
+    try:
+        result = await _run(
+            python, "import time\nw = 1\nwhile True: time.sleep(0.05)", timeout=1
+        )
+        after = await _run(python, "w")
+    finally:
+        await python._stop_kernel()
+
+    assert result.interrupted
+    assert "KeyboardInterrupt" in result.error
+    assert after.result == "1"
+
+
+@pytest.mark.asyncio
+async def test_kernel_crashes_are_reported(python):
+    try:
+        with pytest.raises(ToolError, match="exited with code 3"):
+            await _run(python, "import os\nos._exit(3)")
+        result = await _run(python, "1")
+    finally:
+        await python._stop_kernel()
+
+    assert result.fresh_kernel
+
+
+@pytest.mark.asyncio
+async def test_kernel_env_leaves_out_credentials(python, monkeypatch):
+    monkeypatch.setenv("DEPLOY_TOKEN", "secret")
+    monkeypatch.setenv("RUNE_TEST_VALUE", "kept")
+    try:
+        result = await _run(
+            python,
+            "import os\n"
+            "(os.environ.get('DEPLOY_TOKEN'), os.environ['RUNE_TEST_VALUE'])",
+        )
+    finally:
+        await python._stop_kernel()
+
+    assert result.result == "(None, 'kept')"
+
+
+@pytest.mark.asyncio
+async def test_needs_code_unless_resetting(python):
+    with pytest.raises(ToolError, match="Nothing to run"):
+        await _run(python)
+
+    assert (await _run(python, reset=True)).fresh_kernel
+
+
+@pytest.mark.asyncio
+async def test_kernel_is_listed_until_killed(python):
+    assert python.list_background_processes() == []
+    try:
+        await _run(python, "x = 1")
+        (process,) = python.list_background_processes()
+        assert process.id == "python"
+        assert process.pid == python.state.kernel.pid
+
+        assert not await python.kill_background_process("browser")
+        assert await python.kill_background_process("python")
+    finally:
+        await python._stop_kernel()
+
+    assert python.list_background_processes() == []
+    assert (await _run(python, "1")).fresh_kernel
+    await python._stop_kernel()
+
+
+@pytest.mark.asyncio
+@pytest.mark.parametrize(
+    ("method", "args"), [("reset_all", ()), ("invalidate_tool", ("python",))]
+)
+async def test_dropping_the_tool_stops_its_kernel(tmp_path, monkeypatch, method, args):
+    monkeypatch.chdir(tmp_path)
+    config = build_test_rune_config(
+        system_prompt_id="tests", include_project_context=False
+    )
+    manager = ToolManager(lambda: config)
+    python = manager.get("python")
+    try:
+        await _run(python, "1")
+        kernel = python.state.kernel
+        await getattr(manager, method)(*args)
+    finally:
+        await python._stop_kernel()
+
+    assert kernel is not None and not kernel.alive
+    assert manager.list_background_processes() == []
@@ -0,0 +1,233 @@
+from __future__ import annotations
+
+import base64
+import os
+from pathlib import Path
+
+import pytest
+
+from tests.mock.utils import collect_result
+from rune.core.tools.base import ToolError
+from rune.core.tools.builtins.read_file import (
+    ReadFile,
+    ReadFileArgs,
+    ReadFileState,
+    ReadFileToolConfig,
+)
+
+
+@pytest.fixture
+def read_file(tmp_path, monkeypatch):
+    monkeypatch.chdir(tmp_path)
+    return ReadFile(config=ReadFileToolConfig(), state=ReadFileState())
+
+
+@pytest.fixture
+def numbered_file(tmp_path: Path) -> Path:
+    path = tmp_path / "numbers.txt"
+    path.write_text("".join(f"line {i}\n" for i in range(1, 11)), encoding="utf-8")
+    return path
+
+
+@pytest.mark.asyncio
+async def test_reads_whole_file_without_cursor(read_file, numbered_file):
+    result = await collect_result(read_file.run(ReadFileArgs(path=str(numbered_file))))
+
+    assert result.lines_read == 10
+    assert (result.start_line, result.end_line, result.total_lines) == (1, 10, 10)
+    assert result.next_cursor is None
+    assert result.hint is None
+
+
+@pytest.mark.asyncio
+async def test_reads_explicit_line_range(read_file, numbered_file):
+    result = await collect_result(
+        read_file.run(ReadFileArgs(path=str(numbered_file), start_line=3, end_line=5))
+    )
+
+    assert result.content == "line 3\nline 4\nline 5\n"
+    assert (result.start_line, result.end_line) == (3, 5)
+    assert result.next_cursor is not None
+
+
+@pytest.mark.asyncio
+async def test_cursor_pages_through_the_file(read_file, numbered_file):
+    first = await collect_result(
+        read_file.run(ReadFileArgs(path=str(numbered_file), limit=4))
+    )
+    assert first.next_cursor is not None
+    assert first.hint == (
+        f'Showing lines 1-4. Call read_file again with cursor="'
+        f'{first.next_cursor}" to continue.'
+    )
+
+    second = await collect_result(
+        read_file.run(
+            ReadFileArgs(path=str(numbered_file), cursor=first.next_cursor, limit=4)
+        )
+    )
+    assert second.content.splitlines() == ["line 5", "line 6", "line 7", "line 8"]
+
+    last = await collect_result(
+        read_file.run(
+            ReadFileArgs(path=str(numbered_file), cursor=second.next_cursor, limit=4)
+        )
+    )
+    assert last.content.splitlines() == ["line 9", "line 10"]
+    assert last.total_lines == 10
+    assert last.next_cursor is None
+
+
+@pytest.mark.asyncio
+async def test_byte_limit_returns_a_cursor(tmp_path, monkeypatch, numbered_file):
+    monkeypatch.chdir(tmp_path)
+    tool = ReadFile(config=ReadFileToolConfig(max_read_bytes=20), state=ReadFileState())
+
+    result = await collect_result(tool.run(ReadFileArgs(path=str(numbered_file))))
+
+    assert result.was_truncated
+    assert result.lines_read == 2
+    assert result.next_cursor is not None
+
+
+@pytest.mark.asyncio
+async def test_cursor_is_rejected_after_the_file_changes(read_file, numbered_file):
+    first = await collect_result(
+        read_file.run(ReadFileArgs(path=str(numbered_file), limit=2))
+    )
+    numbered_file.write_text("changed\n" * 20, encoding="utf-8")
+    os.utime(numbered_file, ns=(0, 1_000_000_000))
+
+    with pytest.raises(ToolError, match="file changed"):
+        await collect_result(
+            read_file.run(
+                ReadFileArgs(path=str(numbered_file), cursor=first.next_cursor)
+            )
+        )
+
+
+@pytest.mark.asyncio
+async def test_invalid_cursor_is_rejected(read_file, numbered_file):
+    with pytest.raises(ToolError, match="Invalid cursor"):
+        await collect_result(
+            read_file.run(ReadFileArgs(path=str(numbered_file), cursor="nope"))
+        )
+
+
+@pytest.mark.asyncio
+async def test_end_line_before_start_is_rejected(read_file, numbered_file):
+    with pytest.raises(ToolError):
+        await collect_result(
+            read_file.run(
+                ReadFileArgs(path=str(numbered_file), start_line=5, end_line=4)
+            )
+        )
+
+
+@pytest.mark.asyncio
+async def test_reads_by_byte_offset(read_file, numbered_file):
+    first = await collect_result(
+        read_file.run(ReadFileArgs(path=str(numbered_file), byte_limit=10))
+    )
+    second = await collect_result(
+        read_file.run(
+            ReadFileArgs(path=str(numbered_file), byte_offset=first.next_byte_offset)
+        )
+    )
+
+    assert first.content == "line 1\nlin"
+    assert (first.byte_offset, first.next_byte_offset) == (0, 10)
+    assert first.hint == (
+        "Showing bytes 0-10 of 71. Call read_file again with byte_offset=10 to "
+        "continue."
+    )
+    assert second.content.startswith("e 2\nline 3\n")
+    assert second.next_byte_offset is None
+    with pytest.raises(ToolError, match="can't be combined with line positions"):
+        await collect_result(
+            read_file.run(ReadFileArgs(path=str(numbered_file), byte_offset=3, limit=2))
+        )
+
+
+@pytest.mark.asyncio
+async def test_large_files_can_be_read_by_byte_range(tmp_path, monkeypatch):
+    monkeypatch.chdir(tmp_path)
+    path = tmp_path / "big.log"
+    path.write_text("x" * 100 + "tail\n", encoding="utf-8")
+    read_file = ReadFile(
+        config=ReadFileToolConfig(max_file_bytes=50), state=ReadFileState()
+    )
+
+    with pytest.raises(ToolError, match="file is too large"):
+        await collect_result(read_file.run(ReadFileArgs(path=str(path))))
+    result = await collect_result(
+        read_file.run(ReadFileArgs(path=str(path), byte_offset=100))
+    )
+
+    assert result.content == "tail\n"
+
+
+@pytest.mark.asyncio
+async def test_byte_offset_inside_a_character_moves_to_the_next(read_file, tmp_path):
+    path = tmp_path / "accents.txt"
+    path.write_text("aé€b\n", encoding="utf-8")
+
+    result = await collect_result(
+        read_file.run(ReadFileArgs(path=str(path), byte_offset=2))
+    )
+
+    assert result.content == "€b\n"
+    assert result.byte_offset == 3
+
+
+@pytest.mark.asyncio
+async def test_decodes_files_in_other_encodings(read_file, tmp_path):
+    path = tmp_path / "legacy.txt"
+    path.write_bytes("café crème\n".encode("cp1252"))
+
+    result = await collect_result(read_file.run(ReadFileArgs(path=str(path))))
+    forced = await collect_result(
+        read_file.run(ReadFileArgs(path=str(path), encoding="latin1"))
+    )
+
+    assert result.content == "café crème\n"
+    assert result.encoding == "cp1252"
+    assert forced.encoding == "iso8859-1"
+    with pytest.raises(ToolError, match="Unknown encoding"):
+        await collect_result(
+            read_file.run(ReadFileArgs(path=str(path), encoding="klingon"))
+        )
+
+
+@pytest.mark.asyncio
+async def test_reports_size_and_modification_time(read_file, numbered_file):
+    os.utime(numbered_file, (0, 1_700_000_000))
+
+    result = await collect_result(read_file.run(ReadFileArgs(path=str(numbered_file))))
+
+    assert result.size_bytes == 71
+    assert result.modified_at == "2023-11-14T22:13:20+00:00"
+    assert result.encoding == "utf-8"
+    assert result.mime == "text/plain"
+
+
+@pytest.mark.asyncio
+async def test_attaches_image_files(read_file, tmp_path):
+    png = b"\x89PNG\r\n\x1a\n" + b"\x00" * 32
+    (tmp_path / "plot.png").write_bytes(png)
+    (tmp_path / "big.png").write_bytes(png)
+    small_limit = ReadFile(
+        config=ReadFileToolConfig(max_image_file_bytes=8), state=ReadFileState()
+    )
+
+    result = await collect_result(read_file.run(ReadFileArgs(path="plot.png")))
+    too_big = await collect_result(small_limit.run(ReadFileArgs(path="big.png")))
+
+    (image,) = result.images
+    assert image.media_type == "image/png"
+    assert base64.b64decode(image.data) == png
+    assert result.binary and result.content == ""
+    assert "attached" in result.hint
+    assert "images" not in result.model_dump()
+    assert too_big.images == []
+    assert "binary file" in too_big.hint
@@ -0,0 +1,62 @@
+from __future__ import annotations
+
+import shutil
+import sys
+
+import pytest
+
+from tests.mock.utils import collect_result
+from rune.core.tools.base import BaseToolState
+from rune.core.tools.builtins.bash import Bash, BashArgs, BashToolConfig
+from rune.core.tools.shell_profile import ShellProfile, select_shell_profile
+
+
+def test_default_profile_keeps_the_shell_behaviour() -> None:
+    assert ShellProfile().is_default
+    assert not ShellProfile(load_rc=False).is_default
+
+
+@pytest.mark.parametrize(
+    ("profile", "expected"),
+    [
+        (ShellProfile(program="zsh"), ["zsh", "-c", "ls"]),
+        (
+            ShellProfile(program="bash", load_rc=False),
+            ["bash", "--noprofile", "--norc", "-c", "ls"],
+        ),
+        (
+            ShellProfile(program="pwsh", load_rc=False),
+            ["pwsh", "-NoProfile", "-NonInteractive", "-Command", "ls"],
+        ),
+        (ShellProfile(program="/opt/bin/nu", args=["-c"]), ["/opt/bin/nu", "-c", "ls"]),
+        (
+            ShellProfile(wrapper=["direnv", "exec", "."]),
+            ["direnv", "exec", ".", "/bin/sh", "-c", "ls"],
+        ),
+    ],
+)
+def test_argv(profile: ShellProfile, expected: list[str]) -> None:
+    assert profile.argv("ls", "/bin/sh") == expected
+
+
+def test_platform_profile_wins(monkeypatch: pytest.MonkeyPatch) -> None:
+    monkeypatch.setattr(sys, "platform", "darwin")
+    default = ShellProfile(program="bash")
+    mac = ShellProfile(program="zsh")
+
+    assert select_shell_profile(default, {"darwin": mac}) is mac
+    assert select_shell_profile(default, {"win32": mac}) is default
+
+
+@pytest.mark.asyncio
+@pytest.mark.skipif(not shutil.which("bash"), reason="bash not available")
+async def test_bash_runs_commands_with_the_configured_shell(tmp_path, monkeypatch):
+    monkeypatch.chdir(tmp_path)
+    config = BashToolConfig(shell=ShellProfile(program="bash", load_rc=False))
+    bash = Bash(config=config, state=BaseToolState())
+
+    result = await collect_result(
+        bash.run(BashArgs(command='echo "$BASH_VERSION" | cut -c1'))
+    )
+
+    assert result.stdout.strip().isdigit()
@@ -0,0 +1,208 @@
+from __future__ import annotations
+
+import sqlite3
+import sys
+from types import SimpleNamespace
+
+import pytest
+
+from tests.mock.utils import collect_result
+from rune.core.tools.base import BaseToolState, InvokeContext, ToolError
+from rune.core.tools.databases import PostgresDatabase
+from rune.core.tools.builtins.sql_query import (
+    SqlQuery,
+    SqlQueryArgs,
+    SqlQueryToolConfig,
+)
+
+
+@pytest.fixture
+def database(tmp_path, monkeypatch):
+    monkeypatch.chdir(tmp_path)
+    conn = sqlite3.connect(tmp_path / "shop.db")
+    conn.execute(
+        "CREATE TABLE orders "
+        "(id INTEGER PRIMARY KEY, customer TEXT NOT NULL, total REAL, receipt BLOB)"
+    )
+    conn.executemany(
+        "INSERT INTO orders (customer, total, receipt) VALUES (?, ?, ?)",
+        [("ada", 12.5, b"\x00\x01"), ("bob", 3.0, None), ("cy", 40.0, None)],
+    )
+    conn.commit()
+    conn.close()
+    return tmp_path / "shop.db"
+
+
+def _tool(**config):
+    config = {"shop": {"url": "sqlite:///shop.db"}, **config}
+    return SqlQuery(
+        config=SqlQueryToolConfig.model_validate(config), state=BaseToolState()
+    )
+
+
+@pytest.mark.asyncio
+async def test_returns_rows_up_to_the_limit(database):
+    result = await collect_result(
+        _tool(max_rows=2).run(
+            SqlQueryArgs(query="SELECT customer, total, receipt FROM orders")
+        )
+    )
+
+    assert result.connection == "shop"
+    assert result.columns == ["customer", "total", "receipt"]
+    assert result.rows == [["ada", 12.5, "<2 bytes>"], ["bob", 3.0, None]]
+    assert result.truncated
+    assert result.row_count == 2
+
+
+@pytest.mark.asyncio
+async def test_describes_the_schema(database):
+    result = await collect_result(_tool().run(SqlQueryArgs(table="orders")))
+
+    (table,) = result.tables
+    assert table.name == "orders"
+    assert [(c.name, c.type, c.nullable) for c in table.columns] == [
+        ("id", "INTEGER", True),
+        ("customer", "TEXT", False),
+        ("total", "REAL", True),
+        ("receipt", "BLOB", True),
+    ]
+    assert table.columns[0].primary_key
+    with pytest.raises(ToolError, match="No table named missing"):
+        await collect_result(_tool().run(SqlQueryArgs(table="missing")))
+
+
+@pytest.mark.asyncio
+async def test_read_only_connections_refuse_writes(database):
+    for query in ("DELETE FROM orders", "ATTACH 'other.db' AS other"):
+        with pytest.raises(ToolError):
+            await collect_result(_tool().run(SqlQueryArgs(query=query)))
+
+    assert not (database.parent / "other.db").exists()
+    result = await collect_result(
+        _tool().run(SqlQueryArgs(query="SELECT COUNT(*) FROM orders"))
+    )
+    assert result.rows == [[3]]
+
+
+@pytest.mark.asyncio
+async def test_writable_connections_commit(database):
+    tool = _tool(shop={"url": "sqlite:///shop.db", "read_only": False})
+
+    deleted = await collect_result(
+        tool.run(SqlQueryArgs(query="DELETE FROM orders WHERE total < 10"))
+    )
+    left = await collect_result(
+        tool.run(SqlQueryArgs(query="SELECT COUNT(*) FROM orders"))
+    )
+
+    assert deleted.row_count == 1
+    assert left.rows == [[2]]
+
+
+@pytest.mark.asyncio
+@pytest.mark.parametrize("read_only", [True, False])
+async def test_queries_run_one_statement_at_a_time(database, read_only):
+    tool = _tool(shop={"url": "sqlite:///shop.db", "read_only": read_only})
+
+    with pytest.raises(ToolError):
+        await collect_result(
+            tool.run(SqlQueryArgs(query="SELECT 1; DELETE FROM orders"))
+        )
+
+    result = await collect_result(
+        tool.run(SqlQueryArgs(query="SELECT COUNT(*) FROM orders"))
+    )
+    assert result.rows == [[3]]
+
+
+class _FakeCursor:
+    description = None
+    rowcount = 0
+
+    def __init__(self, executed: list[tuple[str, bool]]) -> None:
+        self._executed = executed
+
+    def __enter__(self) -> _FakeCursor:
+        return self
+
+    def __exit__(self, *_: object) -> None:
+        pass
+
+    def execute(self, sql: str, *, prepare: bool = False) -> None:
+        self._executed.append((sql, prepare))
+
+
+class _FakeConnection:
+    def __init__(self) -> None:
+        self.read_only = False
+        self.executed: list[tuple[str, bool]] = []
+
+    def cursor(self) -> _FakeCursor:
+        return _FakeCursor(self.executed)
+
+    def commit(self) -> None:
+        pass
+
+    def rollback(self) -> None:
+        pass
+
+
+def test_postgres_queries_are_prepared_in_read_only_sessions(monkeypatch):
+    conn = _FakeConnection()
+    connect_options: dict[str, object] = {}
+
+    def connect(url: str, **options: object) -> _FakeConnection:
+        connect_options.update(options)
+        return conn
+
+    psycopg = SimpleNamespace(Error=Exception, connect=connect)
+    monkeypatch.setitem(sys.modules, "psycopg", psycopg)
+
+    database = PostgresDatabase("postgres://localhost/shop", read_only=True, timeout=5)
+    database.query("COMMIT; DELETE FROM orders", max_rows=10)
+
+    assert conn.read_only
+    assert conn.executed == [("COMMIT; DELETE FROM orders", True)]
+    assert connect_options["options"] == (
+        "-c statement_timeout=5000 -c default_transaction_read_only=on"
+    )
+
+
+@pytest.mark.asyncio
+async def test_connection_must_be_named_when_there_are_several(database):
+    tool = _tool(archive={"url": "sqlite:///archive.db", "description": "Old orders"})
+
+    with pytest.raises(ToolError, match=r"Pick a connection: shop, archive \(Old"):
+        await collect_result(tool.run(SqlQueryArgs(query="SELECT 1")))
+    with pytest.raises(ToolError, match="Unknown connection 'nope'"):
+        await collect_result(
+            tool.run(SqlQueryArgs(connection="nope", query="SELECT 1"))
+        )
+
+
+@pytest.mark.asyncio
+async def test_reports_missing_configuration_and_files(tmp_path, monkeypatch):
+    monkeypatch.chdir(tmp_path)
+    empty = SqlQuery(config=SqlQueryToolConfig(), state=BaseToolState())
+
+    with pytest.raises(ToolError, match=r"add one as \[tools.sql.<name>\]"):
+        await collect_result(empty.run(SqlQueryArgs(query="SELECT 1")))
+    with pytest.raises(ToolError, match="No SQLite database at"):
+        await collect_result(_tool().run(SqlQueryArgs(query="SELECT 1")))
+
+
+@pytest.mark.asyncio
+async def test_resolves_relative_paths_against_the_session_directory(
+    database, tmp_path, monkeypatch
+):
+    elsewhere = tmp_path / "elsewhere"
+    elsewhere.mkdir()
+    monkeypatch.chdir(elsewhere)
+    ctx = InvokeContext(tool_call_id="call-1", cwd=tmp_path)
+
+    result = await collect_result(
+        _tool().run(SqlQueryArgs(query="SELECT COUNT(*) FROM orders"), ctx)
+    )
+
+    assert result.rows == [[3]]
@@ -0,0 +1,76 @@
+from __future__ import annotations
+
+import os
+
+from pydantic import BaseModel, ValidationError
+import pytest
+
+from rune.core.config import ToolCacheConfig
+from rune.core.tools.cache import ToolCallCache, cache_key
+
+
+class _Result(BaseModel):
+    content: str
+
+
+def _cache(**config) -> ToolCallCache:
+    return ToolCallCache(lambda: ToolCacheConfig(enabled=True, **config))
+
+
+def test_results_expire_after_max_turns():
+    cache = _cache(max_turns=2)
+    cache.put("grep", _Result(content="a"))
+
+    cache.next_turn()
+    assert cache.get("grep") == _Result(content="a")
+    cache.next_turn()
+    assert cache.get("grep") is None
+
+
+def test_tools_that_are_not_read_only_clear_it():
+    cache = _cache()
+    cache.put("grep", _Result(content="a"))
+
+    cache.forget_after("read_file")
+    assert cache.get("grep") == _Result(content="a")
+    cache.forget_after("write_file")
+    assert cache.get("grep") is None
+
+
+def test_only_configured_tools_get_a_key(tmp_path):
+    (tmp_path / "notes.txt").write_text("one\n")
+    args = {"path": "notes.txt"}
+
+    assert _cache(tools=["grep"]).key("read_file", args, tmp_path) is None
+    assert ToolCallCache(ToolCacheConfig).key("read_file", args, tmp_path) is None
+    assert _cache().key("read_file", args, tmp_path) == cache_key(
+        "read_file", args, tmp_path
+    )
+
+
+def test_key_changes_with_the_arguments_and_the_file(tmp_path):
+    path = tmp_path / "notes.txt"
+    path.write_text("one\n")
+    args = {"path": "notes.txt", "limit": None}
+
+    key = cache_key("read_file", args, tmp_path)
+
+    assert key == cache_key("read_file", {"limit": None, "path": "notes.txt"}, tmp_path)
+    assert key != cache_key("read_file", {"path": "notes.txt", "limit": 10}, tmp_path)
+    assert key != cache_key("grep", args, tmp_path)
+    assert key != cache_key("read_file", args, tmp_path / "elsewhere")
+    os.utime(path, ns=(0, 0))
+    assert key != cache_key("read_file", args, tmp_path)
+
+
+def test_calls_without_a_file_to_check_are_not_cached(tmp_path):
+    (tmp_path / "src").mkdir()
+
+    assert cache_key("grep", {"pattern": "todo", "path": "src"}, tmp_path) is None
+    assert cache_key("read_file", {"path": "missing.txt"}, tmp_path) is None
+    assert cache_key("todo", {"action": "read"}, tmp_path) is None
+
+
+def test_only_read_only_tools_can_be_cached():
+    with pytest.raises(ValidationError, match="bash"):
+        ToolCacheConfig(tools=["grep", "bash"])
@@ -0,0 +1,87 @@
+from __future__ import annotations
+
+import asyncio
+
+from pydantic import ValidationError
+import pytest
+
+from tests.conftest import build_test_rune_config
+from rune.core.tools.base import BaseToolConfig
+from rune.core.tools.manager import ToolManager
+from rune.core.tools.policy import (
+    ToolPolicyConfig,
+    ToolRuntimeExceededError,
+    limit_runtime,
+    truncate_output,
+)
+
+
+def _config(**policy):
+    return build_test_rune_config(
+        system_prompt_id="tests",
+        include_project_context=False,
+        tools={"policy": BaseToolConfig.model_validate(policy)},
+    )
+
+
+def test_policy_is_read_from_the_tools_table():
+    policy = _config(
+        disabled=["mcp_github_*"], max_runtime={"bash": 60, "mcp_*": 10}
+    ).tool_policy
+
+    assert policy.is_disabled("mcp_github_create_issue")
+    assert not policy.is_disabled("bash")
+    assert policy.runtime_limit("bash") == 60
+    assert policy.runtime_limit("mcp_github_create_issue") == 10
+    assert policy.runtime_limit("grep") is None
+
+
+def test_exact_names_win_over_patterns():
+    policy = ToolPolicyConfig(max_output_chars={"*": 1000, "read_file": 50_000})
+
+    assert policy.output_limit("read_file") == 50_000
+    assert policy.output_limit("grep") == 1000
+
+
+def test_unknown_policy_settings_are_rejected():
+    with pytest.raises(ValidationError):
+        _config(disable=["bash"])
+
+
+def test_disabled_tools_are_not_offered():
+    config = _config(disabled=["bash", "re:^write_.*"])
+    manager = ToolManager(lambda: config)
+
+    tools = manager.available_tools
+
+    assert "bash" not in tools
+    assert "write_file" not in tools
+    assert "grep" in tools
+
+
+@pytest.mark.asyncio
+async def test_runtime_limit_stops_the_tool():
+    cleaned_up = asyncio.Event()
+
+    async def slow_tool():
+        try:
+            yield "started"
+            await asyncio.sleep(10)
+            yield "done"
+        finally:
+            cleaned_up.set()
+
+    seen = []
+    with pytest.raises(ToolRuntimeExceededError, match="bash was stopped after"):
+        async for item in limit_runtime(slow_tool(), "bash", 0.05):
+            seen.append(item)
+
+    assert seen == ["started"]
+    assert cleaned_up.is_set()
+
+
+def test_output_is_cut_at_the_limit():
+    assert truncate_output("abcdef", None) == "abcdef"
+    assert truncate_output("abcdef", 4) == (
+        "abcd\n... [2 more characters cut by [tools.policy]]"
+    )
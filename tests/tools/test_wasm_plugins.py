@@ -0,0 +1,180 @@
+from __future__ import annotations
+
+import json
+from pathlib import Path
+
+import pytest
+
+from tests.conftest import build_test_rune_config
+from tests.mock.utils import collect_result
+from rune.core.config import PluginGrants, PluginsConfig
+from rune.core.tools.base import BaseToolConfig, ToolError
+from rune.core.tools.builtins.read_file import ReadFile
+from rune.core.tools.manager import ToolManager
+from rune.core.tools.wasm import (
+    PluginError,
+    WasmPlugin,
+    _modules,
+    create_plugin_tool_class,
+    load_plugins,
+)
+
+wasmtime = pytest.importorskip("wasmtime")
+
+TOOLS = json.dumps([
+    {"name": "echo", "description": "Echo the arguments"},
+    {"name": "loop_forever", "description": "Never returns"},
+    {"name": "fail", "description": "Always fails"},
+])
+FAILURE = '{"error":"nope"}'
+
+
+# Echoes `{"output": <args>}`, spins for `loop_forever` and fails for `fail`,
+# told apart by the length of the tool name.
+def _plugin_wat(tools: str) -> str:
+    return f"""
+(module
+  (import "rune" "log" (func $log (param i32 i32)))
+  (memory (export "memory") 1)
+  (global $next (mut i32) (i32.const 4096))
+  (data (i32.const 0) {json.dumps(tools)})
+  (data (i32.const 2048) "{{\\"output\\":")
+  (data (i32.const 3072) {json.dumps(FAILURE)})
+  (func (export "rune_alloc") (param $size i32) (result i32)
+    (local $ptr i32)
+    (local.set $ptr (global.get $next))
+    (global.set $next (i32.add (global.get $next) (local.get $size)))
+    (local.get $ptr))
+  (func (export "rune_tools") (result i64)
+    (i64.const {len(tools)}))
+  (func (export "rune_call")
+    (param $name i32) (param $name_len i32) (param $args i32) (param $args_len i32)
+    (result i64)
+    (local $out i32)
+    (call $log (local.get $name) (local.get $name_len))
+    (if (i32.eq (local.get $name_len) (i32.const 12))
+      (then (loop $spin (br $spin))))
+    (if (i32.eq (local.get $name_len) (i32.const 4))
+      (then
+        (if (i32.eq (i32.load8_u (local.get $name)) (i32.const 102))
+          (then
+            (return (i64.or
+              (i64.shl (i64.const 3072) (i64.const 32))
+              (i64.const {len(FAILURE)})))))))
+    (local.set $out (global.get $next))
+    (memory.copy (local.get $out) (i32.const 2048) (i32.const 10))
+    (memory.copy
+      (i32.add (local.get $out) (i32.const 10)) (local.get $args) (local.get $args_len))
+    (i32.store8
+      (i32.add (local.get $out) (i32.add (local.get $args_len) (i32.const 10)))
+      (i32.const 125))
+    (i64.or
+      (i64.shl (i64.extend_i32_u (local.get $out)) (i64.const 32))
+      (i64.extend_i32_u (i32.add (local.get $args_len) (i32.const 11))))))
+"""
+
+
+PLUGIN_WAT = _plugin_wat(TOOLS)
+
+
+@pytest.fixture
+def plugins_dir(config_dir: Path) -> Path:
+    directory = config_dir / "plugins"
+    directory.mkdir()
+    (directory / "demo.wasm").write_bytes(wasmtime.wat2wasm(PLUGIN_WAT))
+    return directory
+
+
+def _plugin(plugins_dir: Path, **limits: int) -> WasmPlugin:
+    return WasmPlugin(plugins_dir / "demo.wasm", grants=PluginGrants(), **limits)
+
+
+def test_plugins_are_compiled_once(plugins_dir: Path) -> None:
+    assert _plugin(plugins_dir)._module is _plugin(plugins_dir)._module
+
+
+def test_edited_plugins_replace_their_compiled_module(plugins_dir: Path) -> None:
+    path = plugins_dir / "demo.wasm"
+    before = _plugin(plugins_dir)
+    path.write_bytes(wasmtime.wat2wasm(_plugin_wat(TOOLS[:-1] + " ]")))
+
+    after = _plugin(plugins_dir)
+
+    assert after._module is not before._module
+    assert _modules[path.resolve()][1] is after._module
+
+
+def test_plugin_lists_its_tools(plugins_dir: Path) -> None:
+    tools = _plugin(plugins_dir).tools()
+
+    assert [t.name for t in tools] == ["echo", "loop_forever", "fail"]
+    assert tools[0].parameters == {"type": "object", "properties": {}}
+
+
+def test_calls_run_in_the_plugin(plugins_dir: Path) -> None:
+    plugin = _plugin(plugins_dir)
+
+    assert plugin.call("echo", {"text": "hi"}) == {"text": "hi"}
+    with pytest.raises(PluginError, match="nope"):
+        plugin.call("fail", {})
+
+
+def test_runaway_plugins_are_stopped(plugins_dir: Path) -> None:
+    plugin = _plugin(plugins_dir, max_fuel=100_000)
+
+    with pytest.raises(PluginError, match="ran out of fuel"):
+        plugin.call("loop_forever", {})
+    assert plugin.call("echo", {}) == {}
+
+
+def test_invalid_modules_are_reported(plugins_dir: Path) -> None:
+    (plugins_dir / "broken.wasm").write_bytes(b"not wasm")
+
+    loaded = {p.name: p for p in load_plugins(plugins_dir, PluginsConfig())}
+
+    assert loaded["broken"].plugin is None
+    assert "not a valid WebAssembly module" in (loaded["broken"].error or "")
+    assert len(loaded["demo"].tools) == 3
+    assert load_plugins(plugins_dir, PluginsConfig(enabled=False)) == []
+
+
+def test_plugin_names_must_be_valid_tool_names(plugins_dir: Path) -> None:
+    (plugins_dir / "demo.wasm").rename(plugins_dir / "my plugin.wasm")
+
+    (loaded,) = load_plugins(plugins_dir, PluginsConfig())
+
+    assert loaded.plugin is None
+    assert "may only use letters, digits, _ and -" in (loaded.error or "")
+
+
+@pytest.mark.asyncio
+async def test_plugin_tools_are_offered_with_the_plugin_prefix(
+    plugins_dir: Path,
+) -> None:
+    config = build_test_rune_config(
+        system_prompt_id="tests", include_project_context=False
+    )
+    manager = ToolManager(lambda: config)
+
+    assert {"demo_echo", "demo_fail"} <= set(manager.available_tools)
+
+    plugin = _plugin(plugins_dir)
+    (spec,) = [s for s in plugin.tools() if s.name == "fail"]
+    tool_cls = create_plugin_tool_class(plugin, spec)
+    tool = tool_cls.from_config(BaseToolConfig())
+    args_cls, _ = tool_cls._get_tool_args_results()
+    with pytest.raises(ToolError, match="Plugin call failed: nope"):
+        await collect_result(tool.run(args_cls()))
+
+
+def test_plugin_tools_do_not_replace_existing_tools(plugins_dir: Path) -> None:
+    tools = json.dumps([{"name": "file"}])
+    (plugins_dir / "read.wasm").write_bytes(wasmtime.wat2wasm(_plugin_wat(tools)))
+    config = build_test_rune_config(
+        system_prompt_id="tests", include_project_context=False
+    )
+
+    manager = ToolManager(lambda: config)
+
+    assert manager.available_tools["read_file"] is ReadFile
+    assert "demo_echo" in manager.available_tools